@@ -0,0 +1,390 @@
+//! Assembles AVM1 actions.
+//!
+//! Every action is written with the name of its `swf::avm1::types::Action` variant, followed by
+//! its operands. Branches refer to labels, and nested code (such as function bodies) is given
+//! as a block.
+
+use crate::code::CodeBuilder;
+use crate::error::{AsmError, AsmResult};
+use crate::parser::{Argument, Line, Statement, Value as AsmValue};
+use std::collections::HashMap;
+use std::num::NonZeroU8;
+use std::sync::LazyLock;
+use swf::avm1::types::*;
+use swf::avm1::write::Writer;
+use swf::SwfStr;
+
+macro_rules! unit_actions {
+    ($($name:ident),* $(,)?) => {
+        HashMap::from([$((stringify!($name), Action::$name)),*])
+    };
+}
+
+/// Actions without any operands.
+static UNIT_ACTIONS: LazyLock<HashMap<&'static str, Action<'static>>> = LazyLock::new(|| {
+    unit_actions!(
+        Add,
+        Add2,
+        And,
+        AsciiToChar,
+        BitAnd,
+        BitLShift,
+        BitOr,
+        BitRShift,
+        BitURShift,
+        BitXor,
+        Call,
+        CallFunction,
+        CallMethod,
+        CastOp,
+        CharToAscii,
+        CloneSprite,
+        Decrement,
+        DefineLocal,
+        DefineLocal2,
+        Delete,
+        Delete2,
+        Divide,
+        End,
+        EndDrag,
+        Enumerate,
+        Enumerate2,
+        Equals,
+        Equals2,
+        Extends,
+        GetMember,
+        GetProperty,
+        GetTime,
+        GetVariable,
+        Greater,
+        ImplementsOp,
+        Increment,
+        InitArray,
+        InitObject,
+        InstanceOf,
+        Less,
+        Less2,
+        MBAsciiToChar,
+        MBCharToAscii,
+        MBStringExtract,
+        MBStringLength,
+        Modulo,
+        Multiply,
+        NewMethod,
+        NewObject,
+        NextFrame,
+        Not,
+        Or,
+        Play,
+        Pop,
+        PreviousFrame,
+        PushDuplicate,
+        RandomNumber,
+        RemoveSprite,
+        Return,
+        SetMember,
+        SetProperty,
+        SetTarget2,
+        SetVariable,
+        StackSwap,
+        StartDrag,
+        Stop,
+        StopSounds,
+        StrictEquals,
+        StringAdd,
+        StringEquals,
+        StringExtract,
+        StringGreater,
+        StringLength,
+        StringLess,
+        Subtract,
+        TargetPath,
+        Throw,
+        ToInteger,
+        ToNumber,
+        ToString,
+        ToggleQuality,
+        Trace,
+        TypeOf,
+    )
+});
+
+/// Assembles a block of actions, such as the contents of a `DoAction` tag.
+pub fn assemble_actions(lines: &[Line], version: u8) -> AsmResult<Vec<u8>> {
+    let mut code = CodeBuilder::default();
+    for line in lines {
+        match line {
+            Line::Label(name, location) => code.label(name, *location)?,
+            Line::Statement(statement) => assemble_action(&mut code, statement, version)?,
+        }
+    }
+    Ok(code.finish()?.0)
+}
+
+fn write_action(action: &Action, version: u8) -> AsmResult<Vec<u8>> {
+    let mut bytes = vec![];
+    Writer::new(&mut bytes, version).write_action(action)?;
+    Ok(bytes)
+}
+
+fn swf_str(argument: &Argument) -> AsmResult<&SwfStr> {
+    Ok(SwfStr::from_utf8_str(argument.string()?))
+}
+
+fn assemble_action(code: &mut CodeBuilder, statement: &Statement, version: u8) -> AsmResult<()> {
+    let location = statement.location;
+    let name = statement.name.as_str();
+
+    if name == "If" || name == "Jump" {
+        statement.no_block()?;
+        let mut args = statement.args(&[])?;
+        let label = args.next()?.identifier()?.to_owned();
+        args.finish()?;
+        let is_if = name == "If";
+        return code.push_branch(vec![(label.clone(), location)], move |start, targets| {
+            // Branches are relative to the end of the action, which is always 5 bytes long.
+            let offset = i16::try_from(targets[0] as isize - (start as isize + 5))
+                .map_err(|_| AsmError::BranchTooFar(location, label.clone()))?;
+            let action = if is_if {
+                Action::If(If { offset })
+            } else {
+                Action::Jump(Jump { offset })
+            };
+            write_action(&action, version)
+        });
+    }
+
+    let named: &[&str] = match name {
+        "DefineFunction2" => &["registers"],
+        "GotoFrame2" => &["play", "scene_offset"],
+        "WaitForFrame" | "WaitForFrame2" => &["skip"],
+        _ => &[],
+    };
+    let mut args = statement.args(named)?;
+    let has_block = matches!(name, "DefineFunction" | "DefineFunction2" | "Try" | "With");
+    if !has_block {
+        statement.no_block()?;
+    }
+
+    let bytes = match name {
+        "ConstantPool" => {
+            let strings = args.rest().map(swf_str).collect::<AsmResult<_>>()?;
+            write_action(&Action::ConstantPool(ConstantPool { strings }), version)?
+        }
+        "DefineFunction" => {
+            let name = swf_str(args.next()?)?;
+            let params = args.rest().map(swf_str).collect::<AsmResult<_>>()?;
+            let actions = assemble_actions(statement.block()?, version)?;
+            let action = Action::DefineFunction(DefineFunction {
+                name,
+                params,
+                actions: &actions,
+            });
+            write_action(&action, version)?
+        }
+        "DefineFunction2" => {
+            let name = swf_str(args.next()?)?;
+            let mut params = vec![];
+            let mut flags = FunctionFlags::empty();
+            for argument in args.rest() {
+                match &argument.value {
+                    AsmValue::String(name) => params.push(FunctionParam {
+                        name: SwfStr::from_utf8_str(name),
+                        register_index: None,
+                    }),
+                    // A parameter that is stored in a register: `register("name", 1)`.
+                    AsmValue::Call(call, values) if call == "register" && values.len() == 2 => {
+                        params.push(FunctionParam {
+                            name: SwfStr::from_utf8_str(values[0].string(argument.location)?),
+                            register_index: NonZeroU8::new(values[1].int(argument.location)?),
+                        })
+                    }
+                    AsmValue::Identifier(flag) => {
+                        flags |= FunctionFlags::from_name(flag).ok_or_else(|| {
+                            AsmError::UnknownArgument(argument.location, flag.clone())
+                        })?
+                    }
+                    _ => {
+                        return Err(AsmError::WrongArgument(
+                            argument.location,
+                            "a parameter or a flag",
+                        ))
+                    }
+                }
+            }
+            let register_count = match args.named("registers") {
+                Some(registers) => registers.int()?,
+                None => params
+                    .iter()
+                    .filter_map(|p| p.register_index)
+                    .map(|r| r.get().saturating_add(1))
+                    .max()
+                    .unwrap_or(0),
+            };
+            let actions = assemble_actions(statement.block()?, version)?;
+            let action = Action::DefineFunction2(DefineFunction2 {
+                name,
+                register_count,
+                params,
+                flags,
+                actions: &actions,
+            });
+            write_action(&action, version)?
+        }
+        "GetUrl" => {
+            let url = swf_str(args.next()?)?;
+            let target = swf_str(args.next()?)?;
+            write_action(&Action::GetUrl(GetUrl { url, target }), version)?
+        }
+        "GetUrl2" => {
+            let mut method = SendVarsMethod::None;
+            let mut constructor: fn(SendVarsMethod) -> GetUrl2 = GetUrl2::for_get_url;
+            for argument in args.rest() {
+                match argument.identifier()? {
+                    "None" => method = SendVarsMethod::None,
+                    "Get" => method = SendVarsMethod::Get,
+                    "Post" => method = SendVarsMethod::Post,
+                    "LOAD_TARGET" => constructor = GetUrl2::for_load_movie,
+                    "LOAD_VARIABLES" => constructor = GetUrl2::for_load_vars,
+                    other => {
+                        return Err(AsmError::UnknownArgument(
+                            argument.location,
+                            other.to_owned(),
+                        ))
+                    }
+                }
+            }
+            write_action(&Action::GetUrl2(constructor(method)), version)?
+        }
+        "GotoFrame" => {
+            let frame = args.next()?.int()?;
+            write_action(&Action::GotoFrame(GotoFrame { frame }), version)?
+        }
+        "GotoFrame2" => {
+            let set_playing = args.named("play").map_or(Ok(false), |a| a.bool())?;
+            let scene_offset = args.named("scene_offset").map_or(Ok(0), |a| a.int())?;
+            let action = Action::GotoFrame2(GotoFrame2 {
+                set_playing,
+                scene_offset,
+            });
+            write_action(&action, version)?
+        }
+        "GotoLabel" => {
+            let label = swf_str(args.next()?)?;
+            write_action(&Action::GotoLabel(GotoLabel { label }), version)?
+        }
+        "Push" => {
+            let values = args.rest().map(push_value).collect::<AsmResult<_>>()?;
+            write_action(&Action::Push(Push { values }), version)?
+        }
+        "SetTarget" => {
+            let target = swf_str(args.next()?)?;
+            write_action(&Action::SetTarget(SetTarget { target }), version)?
+        }
+        "StoreRegister" => {
+            let register = register(args.next()?)?;
+            write_action(&Action::StoreRegister(StoreRegister { register }), version)?
+        }
+        "Try" => assemble_try(statement, version)?,
+        "WaitForFrame" => {
+            let frame = args.next()?.int()?;
+            let num_actions_to_skip = args.named("skip").map_or(Ok(0), |a| a.int())?;
+            let action = Action::WaitForFrame(WaitForFrame {
+                frame,
+                num_actions_to_skip,
+            });
+            write_action(&action, version)?
+        }
+        "WaitForFrame2" => {
+            let num_actions_to_skip = args.named("skip").map_or(Ok(0), |a| a.int())?;
+            let action = Action::WaitForFrame2(WaitForFrame2 {
+                num_actions_to_skip,
+            });
+            write_action(&action, version)?
+        }
+        "With" => {
+            let actions = assemble_actions(statement.block()?, version)?;
+            write_action(&Action::With(With { actions: &actions }), version)?
+        }
+        _ => match UNIT_ACTIONS.get(name) {
+            Some(action) => write_action(action, version)?,
+            None => return Err(AsmError::UnknownInstruction(location, name.to_owned())),
+        },
+    };
+    args.finish()?;
+    code.push(bytes);
+    Ok(())
+}
+
+/// Assembles a `Try` action, whose block contains `body`, `catch` and `finally` blocks.
+fn assemble_try(statement: &Statement, version: u8) -> AsmResult<Vec<u8>> {
+    let mut try_body = None;
+    let mut catch_body = None;
+    let mut finally_body = None;
+    for line in statement.block()? {
+        let part = match line {
+            Line::Statement(part) => part,
+            Line::Label(_, location) => return Err(AsmError::UnexpectedLabel(*location)),
+        };
+        let mut args = part.args(&[])?;
+        let actions = assemble_actions(part.block()?, version)?;
+        match part.name.as_str() {
+            "body" => try_body = Some(actions),
+            "catch" => {
+                let argument = args.next()?;
+                let var = match &argument.value {
+                    AsmValue::String(_) => CatchVar::Var(swf_str(argument)?),
+                    _ => CatchVar::Register(register(argument)?),
+                };
+                catch_body = Some((var, actions));
+            }
+            "finally" => finally_body = Some(actions),
+            other => {
+                return Err(AsmError::UnknownStatement(part.location, other.to_owned()));
+            }
+        }
+        args.finish()?;
+    }
+    let action = Action::Try(Try {
+        try_body: try_body.as_deref().unwrap_or_default(),
+        catch_body: catch_body
+            .as_ref()
+            .map(|(var, actions)| (var.clone(), actions.as_slice())),
+        finally_body: finally_body.as_deref(),
+    });
+    write_action(&action, version)
+}
+
+/// A register, written either as a plain number or as `register(n)`.
+fn register(argument: &Argument) -> AsmResult<u8> {
+    match &argument.value {
+        AsmValue::Call(call, values) if call == "register" && values.len() == 1 => {
+            values[0].int(argument.location)
+        }
+        _ => argument.int(),
+    }
+}
+
+fn push_value(argument: &Argument) -> AsmResult<Value<'_>> {
+    let location = argument.location;
+    Ok(match &argument.value {
+        AsmValue::Integer(_) | AsmValue::Hex { .. } => Value::Int(argument.int()?),
+        AsmValue::Number(value) => Value::Double(*value),
+        AsmValue::String(value) => Value::Str(SwfStr::from_utf8_str(value)),
+        AsmValue::Identifier(value) => match value.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "null" => Value::Null,
+            "undefined" => Value::Undefined,
+            _ => return Err(AsmError::WrongArgument(location, "a value")),
+        },
+        AsmValue::Call(call, values) if values.len() == 1 => match call.as_str() {
+            "register" => Value::Register(values[0].int(location)?),
+            "pool" => Value::ConstantPool(values[0].int(location)?),
+            "float" => Value::Float(values[0].float(location)? as f32),
+            "double" => Value::Double(values[0].float(location)?),
+            _ => return Err(AsmError::WrongArgument(location, "a value")),
+        },
+        AsmValue::Call(..) => return Err(AsmError::WrongArgument(location, "a value")),
+    })
+}
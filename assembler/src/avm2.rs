@@ -0,0 +1,1097 @@
+//! Assembles AVM2 bytecode into an ABC file.
+//!
+//! An ABC block is made of `method`, `class` and `script` statements. Methods and classes are
+//! given a reference name, which other statements use to refer to them regardless of the order
+//! in which they are written. Multinames and namespaces are written as strings in the same
+//! format that the disassembler prints them in, such as `"flash.display::Sprite"`.
+
+use crate::code::CodeBuilder;
+use crate::error::{AsmError, AsmResult, Location};
+use crate::parser::{Args, Argument, Line, Statement, Value};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use swf::avm2::types::*;
+use swf::avm2::write::Writer;
+
+macro_rules! unit_ops {
+    ($($name:ident),* $(,)?) => {
+        HashMap::from([$((stringify!($name), Op::$name)),*])
+    };
+}
+
+/// Ops without any operands.
+static UNIT_OPS: LazyLock<HashMap<&'static str, Op>> = LazyLock::new(|| {
+    unit_ops!(
+        Add,
+        AddI,
+        AsTypeLate,
+        BitAnd,
+        BitNot,
+        BitOr,
+        BitXor,
+        Bkpt,
+        CheckFilter,
+        CoerceA,
+        CoerceB,
+        CoerceD,
+        CoerceI,
+        CoerceO,
+        CoerceS,
+        CoerceU,
+        ConvertB,
+        ConvertD,
+        ConvertI,
+        ConvertO,
+        ConvertS,
+        ConvertU,
+        Decrement,
+        DecrementI,
+        Divide,
+        Dup,
+        DxnsLate,
+        Equals,
+        EscXAttr,
+        EscXElem,
+        GetGlobalScope,
+        GreaterEquals,
+        GreaterThan,
+        HasNext,
+        In,
+        Increment,
+        IncrementI,
+        InstanceOf,
+        IsTypeLate,
+        Label,
+        LessEquals,
+        LessThan,
+        Lf32,
+        Lf64,
+        Li16,
+        Li32,
+        Li8,
+        LShift,
+        Modulo,
+        Multiply,
+        MultiplyI,
+        Negate,
+        NegateI,
+        NewActivation,
+        NextName,
+        NextValue,
+        Nop,
+        Not,
+        Pop,
+        PopScope,
+        PushFalse,
+        PushNaN,
+        PushNull,
+        PushScope,
+        PushTrue,
+        PushUndefined,
+        PushWith,
+        ReturnValue,
+        ReturnVoid,
+        RShift,
+        Sf32,
+        Sf64,
+        Si16,
+        Si32,
+        Si8,
+        StrictEquals,
+        Subtract,
+        SubtractI,
+        Swap,
+        Sxi1,
+        Sxi16,
+        Sxi8,
+        Throw,
+        TypeOf,
+        Timestamp,
+        URShift,
+    )
+});
+
+/// Builds the op called `$name` out of those listed, whose only operand is `$value`.
+///
+/// `$value` is only evaluated if `$name` is one of the listed ops.
+macro_rules! op_with_operand {
+    ($name:expr, $value:expr, $($op:ident { $field:ident }),* $(,)?) => {
+        match $name {
+            $(stringify!($op) => Some(Op::$op { $field: $value }),)*
+            _ => None,
+        }
+    };
+}
+
+/// Builds a branch op, whose offset is relative to the end of the op.
+fn branch_op(name: &str, offset: i32) -> Option<Op> {
+    op_with_operand!(
+        name,
+        offset,
+        IfEq { offset },
+        IfFalse { offset },
+        IfGe { offset },
+        IfGt { offset },
+        IfLe { offset },
+        IfLt { offset },
+        IfNe { offset },
+        IfNge { offset },
+        IfNgt { offset },
+        IfNle { offset },
+        IfNlt { offset },
+        IfStrictEq { offset },
+        IfStrictNe { offset },
+        IfTrue { offset },
+        Jump { offset },
+    )
+}
+
+fn write_op(op: &Op) -> AsmResult<Vec<u8>> {
+    let mut bytes = vec![];
+    Writer::new(&mut bytes).write_op(op)?;
+    Ok(bytes)
+}
+
+/// Checks that a branch offset fits in the 24 bits that it's encoded in.
+fn i24(offset: i64, location: Location, label: &str) -> AsmResult<i32> {
+    if (-(1 << 23)..(1 << 23)).contains(&offset) {
+        Ok(offset as i32)
+    } else {
+        Err(AsmError::BranchTooFar(location, label.to_owned()))
+    }
+}
+
+/// Builds a namespace of a given kind out of its URI.
+type NamespaceKind = fn(Index<String>) -> Namespace;
+
+/// What's needed to compute the default limits of a method body.
+#[derive(Default)]
+struct CodeStats {
+    num_ops: u32,
+    num_locals: u32,
+    num_scopes: u32,
+}
+
+impl CodeStats {
+    fn record(&mut self, op: &Op) {
+        let local = match *op {
+            Op::GetLocal { index }
+            | Op::SetLocal { index }
+            | Op::Kill { index }
+            | Op::IncLocal { index }
+            | Op::IncLocalI { index }
+            | Op::DecLocal { index }
+            | Op::DecLocalI { index } => index,
+            Op::HasNext2 {
+                object_register,
+                index_register,
+            } => object_register.max(index_register),
+            Op::PushScope | Op::PushWith => {
+                self.num_scopes += 1;
+                return;
+            }
+            _ => return,
+        };
+        self.num_locals = self.num_locals.max(local + 1);
+    }
+}
+
+/// Assembles the contents of an ABC block.
+pub fn assemble_abc(lines: &[Line]) -> AsmResult<AbcFile> {
+    let mut assembler = AbcAssembler::default();
+
+    // Methods and classes may be referred to before they are defined.
+    for line in lines {
+        let Line::Statement(statement) = line else {
+            continue;
+        };
+        let references = match statement.name.as_str() {
+            "method" => &mut assembler.method_refs,
+            "class" => &mut assembler.class_refs,
+            _ => continue,
+        };
+        let reference = statement
+            .arguments
+            .first()
+            .ok_or(AsmError::TooFewArguments(statement.location))?
+            .identifier()?
+            .to_owned();
+        let index = references.len() as u32;
+        if references.insert(reference.clone(), index).is_some() {
+            return Err(AsmError::DuplicateDefinition(statement.location, reference));
+        }
+    }
+
+    for line in lines {
+        let statement = match line {
+            Line::Statement(statement) => statement,
+            Line::Label(_, location) => return Err(AsmError::UnexpectedLabel(*location)),
+        };
+        match statement.name.as_str() {
+            "version" => {
+                statement.no_block()?;
+                let mut args = statement.args(&[])?;
+                assembler.abc.major_version = args.next()?.int()?;
+                assembler.abc.minor_version = args.next()?.int()?;
+                args.finish()?;
+            }
+            "method" => assembler.method(statement)?,
+            "class" => assembler.class(statement)?,
+            "script" => assembler.script(statement)?,
+            other => {
+                return Err(AsmError::UnknownStatement(
+                    statement.location,
+                    other.to_owned(),
+                ))
+            }
+        }
+    }
+
+    Ok(assembler.abc)
+}
+
+/// Assembles the contents of an ABC block, and writes it out as bytes.
+pub fn write_abc(lines: &[Line]) -> AsmResult<Vec<u8>> {
+    let abc = assemble_abc(lines)?;
+    let mut bytes = vec![];
+    Writer::new(&mut bytes).write(abc)?;
+    Ok(bytes)
+}
+
+struct AbcAssembler {
+    abc: AbcFile,
+    method_refs: HashMap<String, u32>,
+    class_refs: HashMap<String, u32>,
+}
+
+impl Default for AbcAssembler {
+    fn default() -> Self {
+        Self {
+            abc: AbcFile {
+                major_version: 46,
+                minor_version: 16,
+                constant_pool: ConstantPool {
+                    ints: vec![],
+                    uints: vec![],
+                    doubles: vec![],
+                    strings: vec![],
+                    namespaces: vec![],
+                    namespace_sets: vec![],
+                    multinames: vec![],
+                },
+                methods: vec![],
+                metadata: vec![],
+                instances: vec![],
+                classes: vec![],
+                scripts: vec![],
+                method_bodies: vec![],
+            },
+            method_refs: HashMap::new(),
+            class_refs: HashMap::new(),
+        }
+    }
+}
+
+/// Adds `value` to a constant pool unless it's already there, returning its index.
+fn intern<T>(entries: &mut Vec<T>, value: T, eq: impl Fn(&T, &T) -> bool) -> u32 {
+    let index = match entries.iter().position(|entry| eq(entry, &value)) {
+        Some(index) => index,
+        None => {
+            entries.push(value);
+            entries.len() - 1
+        }
+    };
+    // Index 0 is reserved in every constant pool.
+    index as u32 + 1
+}
+
+/// Splits `text` at every `, ` that isn't nested within `<...>` or `[...]`.
+fn split_list(text: &str) -> Vec<&str> {
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '[' => depth += 1,
+            '>' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(text[start..].trim());
+    items
+}
+
+impl AbcAssembler {
+    fn string(&mut self, value: &str) -> Index<String> {
+        let strings = &mut self.abc.constant_pool.strings;
+        Index::new(intern(strings, value.as_bytes().to_vec(), |a, b| a == b))
+    }
+
+    /// A name within a multiname, where `*` stands for any name.
+    fn name(&mut self, value: &str) -> Index<String> {
+        match value {
+            "*" => Index::new(0),
+            _ => self.string(value),
+        }
+    }
+
+    fn int(&mut self, value: i32) -> Index<i32> {
+        Index::new(intern(&mut self.abc.constant_pool.ints, value, |a, b| {
+            a == b
+        }))
+    }
+
+    fn uint(&mut self, value: u32) -> Index<u32> {
+        Index::new(intern(&mut self.abc.constant_pool.uints, value, |a, b| {
+            a == b
+        }))
+    }
+
+    fn double(&mut self, value: f64) -> Index<f64> {
+        // Compare bits, so that 0 and -0 are kept apart.
+        let doubles = &mut self.abc.constant_pool.doubles;
+        Index::new(intern(doubles, value, |a, b| a.to_bits() == b.to_bits()))
+    }
+
+    /// Parses a namespace such as `flash.display`, `private` or `protected Foo`.
+    fn namespace(&mut self, text: &str, location: Location) -> AsmResult<Index<Namespace>> {
+        if text == "*" {
+            return Ok(Index::new(0));
+        }
+        let kinds: [(&str, NamespaceKind); 6] = [
+            ("static protected", Namespace::StaticProtected),
+            ("protected", Namespace::Protected),
+            ("private", Namespace::Private),
+            ("internal", Namespace::PackageInternal),
+            ("explicit", Namespace::Explicit),
+            ("namespace", Namespace::Namespace),
+        ];
+        let (constructor, uri): (NamespaceKind, _) = kinds
+            .into_iter()
+            .find_map(|(kind, constructor)| {
+                let uri = text.strip_prefix(kind)?;
+                if uri.is_empty() {
+                    Some((constructor, ""))
+                } else {
+                    Some((constructor, uri.strip_prefix(' ')?))
+                }
+            })
+            .unwrap_or((Namespace::Package, text));
+        if uri.contains(['[', ']']) {
+            return Err(AsmError::InvalidMultiname(location, text.to_owned()));
+        }
+        let namespace = constructor(self.string(uri));
+        let namespaces = &mut self.abc.constant_pool.namespaces;
+        Ok(Index::new(intern(namespaces, namespace, |a, b| a == b)))
+    }
+
+    /// Parses a multiname, such as `flash.display::Sprite`, `[a, b]::name`,
+    /// `<runtime>::name`, `@attribute` or `Vector.<int>`.
+    fn multiname(&mut self, text: &str, location: Location) -> AsmResult<Index<Multiname>> {
+        let invalid = || AsmError::InvalidMultiname(location, text.to_owned());
+        if text == "*" {
+            return Ok(Index::new(0));
+        }
+        let (is_attribute, rest) = match text.strip_prefix('@') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+
+        let multiname = if let Some((base, parameters)) = rest
+            .strip_suffix('>')
+            .and_then(|rest| rest.split_once(".<"))
+        {
+            if is_attribute {
+                return Err(invalid());
+            }
+            let base_type = self.multiname(base, location)?;
+            let parameters = split_list(parameters)
+                .into_iter()
+                .map(|parameter| self.multiname(parameter, location))
+                .collect::<AsmResult<_>>()?;
+            Multiname::TypeName {
+                base_type,
+                parameters,
+            }
+        } else if let Some(rest) = rest.strip_prefix('[') {
+            let (set, name) = rest.split_once("]::").ok_or_else(invalid)?;
+            let namespaces = if set.is_empty() {
+                vec![]
+            } else {
+                split_list(set)
+                    .into_iter()
+                    .map(|namespace| self.namespace(namespace, location))
+                    .collect::<AsmResult<_>>()?
+            };
+            let sets = &mut self.abc.constant_pool.namespace_sets;
+            let namespace_set = Index::new(intern(sets, namespaces, |a, b| a == b));
+            match (name, is_attribute) {
+                ("<runtime>", false) => Multiname::MultinameL { namespace_set },
+                ("<runtime>", true) => Multiname::MultinameLA { namespace_set },
+                (name, false) => Multiname::Multiname {
+                    namespace_set,
+                    name: self.name(name),
+                },
+                (name, true) => Multiname::MultinameA {
+                    namespace_set,
+                    name: self.name(name),
+                },
+            }
+        } else if let Some(name) = rest.strip_prefix("<runtime>::") {
+            match (name, is_attribute) {
+                ("<runtime>", false) => Multiname::RTQNameL,
+                ("<runtime>", true) => Multiname::RTQNameLA,
+                (name, false) => Multiname::RTQName {
+                    name: self.name(name),
+                },
+                (name, true) => Multiname::RTQNameA {
+                    name: self.name(name),
+                },
+            }
+        } else {
+            let (namespace, name) = rest.rsplit_once("::").unwrap_or(("", rest));
+            if name.contains(['<', '>', '[', ']']) {
+                return Err(invalid());
+            }
+            let namespace = self.namespace(namespace, location)?;
+            let name = self.name(name);
+            if is_attribute {
+                Multiname::QNameA { namespace, name }
+            } else {
+                Multiname::QName { namespace, name }
+            }
+        };
+
+        let multinames = &mut self.abc.constant_pool.multinames;
+        Ok(Index::new(intern(multinames, multiname, |a, b| a == b)))
+    }
+
+    fn multiname_arg(&mut self, argument: &Argument) -> AsmResult<Index<Multiname>> {
+        self.multiname(argument.string()?, argument.location)
+    }
+
+    fn method_ref(&self, argument: &Argument) -> AsmResult<Index<Method>> {
+        let reference = argument.identifier()?;
+        match self.method_refs.get(reference) {
+            Some(index) => Ok(Index::new(*index)),
+            None => Err(AsmError::UnknownReference(
+                argument.location,
+                reference.to_owned(),
+            )),
+        }
+    }
+
+    fn class_ref(&self, argument: &Argument) -> AsmResult<Index<Class>> {
+        let reference = argument.identifier()?;
+        match self.class_refs.get(reference) {
+            Some(index) => Ok(Index::new(*index)),
+            None => Err(AsmError::UnknownReference(
+                argument.location,
+                reference.to_owned(),
+            )),
+        }
+    }
+
+    fn default_value(&mut self, argument: &Argument) -> AsmResult<DefaultValue> {
+        let location = argument.location;
+        Ok(match &argument.value {
+            Value::Integer(_) | Value::Hex { .. } => DefaultValue::Int(self.int(argument.int()?)),
+            Value::Number(value) => DefaultValue::Double(self.double(*value)),
+            Value::String(value) => DefaultValue::String(self.string(value)),
+            Value::Identifier(value) => match value.as_str() {
+                "true" => DefaultValue::True,
+                "false" => DefaultValue::False,
+                "null" => DefaultValue::Null,
+                "undefined" => DefaultValue::Undefined,
+                _ => return Err(AsmError::WrongArgument(location, "a value")),
+            },
+            Value::Call(call, values) if values.len() == 1 => match call.as_str() {
+                "int" => DefaultValue::Int(self.int(values[0].int(location)?)),
+                "uint" => DefaultValue::Uint(self.uint(values[0].int(location)?)),
+                "double" => DefaultValue::Double(self.double(values[0].float(location)?)),
+                "namespace" => {
+                    let namespace = self.namespace(values[0].string(location)?, location)?;
+                    DefaultValue::Namespace(namespace)
+                }
+                _ => return Err(AsmError::WrongArgument(location, "a value")),
+            },
+            Value::Call(..) => return Err(AsmError::WrongArgument(location, "a value")),
+        })
+    }
+
+    /// `method ref, name="", returns="*", FLAGS... { param ...; catch ...; code { ... }; traits }`
+    fn method(&mut self, statement: &Statement) -> AsmResult<()> {
+        let mut args = statement.args(&[
+            "name",
+            "returns",
+            "max_stack",
+            "locals",
+            "init_scope",
+            "max_scope",
+        ])?;
+        let index = self.method_ref(args.next()?)?;
+        let name = match args.named("name") {
+            Some(name) => self.string(name.string()?),
+            None => Index::new(0),
+        };
+        let return_type = match args.named("returns") {
+            Some(returns) => self.multiname_arg(returns)?,
+            None => Index::new(0),
+        };
+        let mut flags = MethodFlags::empty();
+        for argument in args.rest() {
+            let flag = argument.identifier()?;
+            flags |= MethodFlags::from_name(flag)
+                .ok_or_else(|| AsmError::UnknownArgument(argument.location, flag.to_owned()))?;
+        }
+
+        let mut params = vec![];
+        let mut traits = vec![];
+        let mut catches = vec![];
+        let mut code = None;
+        for line in statement.block.as_deref().unwrap_or_default() {
+            let statement = match line {
+                Line::Statement(statement) => statement,
+                Line::Label(_, location) => return Err(AsmError::UnexpectedLabel(*location)),
+            };
+            match statement.name.as_str() {
+                "param" => {
+                    statement.no_block()?;
+                    let mut param_args = statement.args(&["name", "default"])?;
+                    let kind = self.multiname_arg(param_args.next()?)?;
+                    let name = match param_args.named("name") {
+                        Some(name) => Some(self.string(name.string()?)),
+                        None => None,
+                    };
+                    let default_value = match param_args.named("default") {
+                        Some(value) => Some(self.default_value(value)?),
+                        None => None,
+                    };
+                    param_args.finish()?;
+                    params.push(MethodParam {
+                        name,
+                        kind,
+                        default_value,
+                    });
+                }
+                "catch" => catches.push(statement),
+                "code" => {
+                    statement.args(&[])?.finish()?;
+                    if code.is_some() {
+                        return Err(AsmError::DuplicateDefinition(
+                            statement.location,
+                            "code".to_owned(),
+                        ));
+                    }
+                    code = Some(statement.block()?);
+                }
+                _ => traits.push(self.trait_(statement)?),
+            }
+        }
+
+        if params.iter().any(|param| param.default_value.is_some()) {
+            flags |= MethodFlags::HAS_OPTIONAL;
+        }
+        if params.iter().any(|param| param.name.is_some()) {
+            flags |= MethodFlags::HAS_PARAM_NAMES;
+            // Either every parameter is named, or none of them are.
+            for param in &mut params {
+                param.name.get_or_insert(Index::new(0));
+            }
+        }
+
+        let body = match code {
+            Some(code) => {
+                let body = self.method_body(index, code, &catches, params.len(), traits, &args)?;
+                self.abc.method_bodies.push(body);
+                Some(Index::new(self.abc.method_bodies.len() as u32 - 1))
+            }
+            None if catches.is_empty() && traits.is_empty() => None,
+            None => return Err(AsmError::MissingBlock(statement.location)),
+        };
+        args.finish()?;
+
+        self.abc.methods.push(Method {
+            name,
+            params,
+            return_type,
+            flags,
+            body,
+        });
+        Ok(())
+    }
+
+    fn method_body(
+        &mut self,
+        method: Index<Method>,
+        lines: &[Line],
+        catches: &[&Statement],
+        num_params: usize,
+        traits: Vec<Trait>,
+        args: &Args<'_>,
+    ) -> AsmResult<MethodBody> {
+        let mut code = CodeBuilder::default();
+        let mut stats = CodeStats::default();
+        for line in lines {
+            match line {
+                Line::Label(name, location) => code.label(name, *location)?,
+                Line::Statement(statement) => self.op(&mut code, &mut stats, statement)?,
+            }
+        }
+        let (code, labels) = code.finish()?;
+
+        let label_offset = |argument: &Argument| -> AsmResult<u32> {
+            let label = argument.identifier()?;
+            labels
+                .get(label)
+                .map(|offset| *offset as u32)
+                .ok_or_else(|| AsmError::UnknownLabel(argument.location, label.to_owned()))
+        };
+        let mut exceptions = vec![];
+        for catch in catches {
+            catch.no_block()?;
+            let mut catch_args = catch.args(&["type", "var"])?;
+            let from_offset = label_offset(catch_args.next()?)?;
+            let to_offset = label_offset(catch_args.next()?)?;
+            let target_offset = label_offset(catch_args.next()?)?;
+            let type_name = match catch_args.named("type") {
+                Some(type_name) => self.multiname_arg(type_name)?,
+                None => Index::new(0),
+            };
+            let variable_name = match catch_args.named("var") {
+                Some(var) => self.multiname_arg(var)?,
+                None => Index::new(0),
+            };
+            catch_args.finish()?;
+            exceptions.push(Exception {
+                from_offset,
+                to_offset,
+                target_offset,
+                variable_name,
+                type_name,
+            });
+        }
+
+        // No op pushes more than one value, so the number of ops is a safe upper bound.
+        let max_stack = match args.named("max_stack") {
+            Some(max_stack) => max_stack.int()?,
+            None => stats.num_ops.max(1),
+        };
+        let num_locals = match args.named("locals") {
+            Some(locals) => locals.int()?,
+            None => stats.num_locals.max(num_params as u32 + 1),
+        };
+        let init_scope_depth = match args.named("init_scope") {
+            Some(init_scope) => init_scope.int()?,
+            None => 0,
+        };
+        let max_scope_depth = match args.named("max_scope") {
+            Some(max_scope) => max_scope.int()?,
+            None => init_scope_depth + stats.num_scopes,
+        };
+
+        Ok(MethodBody {
+            method,
+            max_stack,
+            num_locals,
+            init_scope_depth,
+            max_scope_depth,
+            code,
+            exceptions,
+            traits,
+        })
+    }
+
+    fn op(
+        &mut self,
+        code: &mut CodeBuilder,
+        stats: &mut CodeStats,
+        statement: &Statement,
+    ) -> AsmResult<()> {
+        statement.no_block()?;
+        let location = statement.location;
+        let name = statement.name.as_str();
+        let mut args = statement.args(&[])?;
+        stats.num_ops += 1;
+
+        if branch_op(name, 0).is_some() {
+            let label = args.next()?.identifier()?.to_owned();
+            args.finish()?;
+            let name = name.to_owned();
+            return code.push_branch(vec![(label.clone(), location)], move |start, targets| {
+                // Branches are 4 bytes long, and are relative to the end of the op.
+                let offset = targets[0] as i64 - (start as i64 + 4);
+                let op = branch_op(&name, i24(offset, location, &label)?)
+                    .expect("Op should be a branch");
+                write_op(&op)
+            });
+        }
+
+        if name == "LookupSwitch" {
+            let labels = args
+                .rest()
+                .map(|argument| Ok((argument.identifier()?.to_owned(), argument.location)))
+                .collect::<AsmResult<Vec<_>>>()?;
+            if labels.len() < 2 {
+                return Err(AsmError::TooFewArguments(location));
+            }
+            return code.push_branch(labels.clone(), move |start, targets| {
+                // Unlike other branches, these are relative to the start of the op.
+                let offsets = targets
+                    .iter()
+                    .zip(&labels)
+                    .map(|(target, (label, _))| i24(*target as i64 - start as i64, location, label))
+                    .collect::<AsmResult<Vec<_>>>()?;
+                write_op(&Op::LookupSwitch(Box::new(LookupSwitch {
+                    default_offset: offsets[0],
+                    case_offsets: offsets[1..].into(),
+                })))
+            });
+        }
+
+        let op = if let Some(op) = UNIT_OPS.get(name) {
+            op.clone()
+        } else if let Some(op) = op_with_operand!(
+            name,
+            self.multiname_arg(args.next()?)?,
+            AsType { type_name },
+            Coerce { index },
+            DeleteProperty { index },
+            FindDef { index },
+            FindProperty { index },
+            FindPropStrict { index },
+            GetDescendants { index },
+            GetLex { index },
+            GetProperty { index },
+            GetSuper { index },
+            InitProperty { index },
+            IsType { index },
+            SetProperty { index },
+            SetSuper { index },
+        ) {
+            op
+        } else if let Some(op) = op_with_operand!(
+            name,
+            args.next()?.int()?,
+            ApplyType { num_types },
+            BkptLine { line_num },
+            Call { num_args },
+            Construct { num_args },
+            ConstructSuper { num_args },
+            DebugLine { line_num },
+            DecLocal { index },
+            DecLocalI { index },
+            GetGlobalSlot { index },
+            GetLocal { index },
+            GetOuterScope { index },
+            GetSlot { index },
+            IncLocal { index },
+            IncLocalI { index },
+            Kill { index },
+            NewArray { num_args },
+            NewObject { num_args },
+            SetGlobalSlot { index },
+            SetLocal { index },
+            SetSlot { index },
+        ) {
+            op
+        } else if let Some(op) = op_with_operand!(
+            name,
+            self.string(args.next()?.string()?),
+            DebugFile { file_name },
+            Dxns { index },
+            PushString { value },
+        ) {
+            op
+        } else {
+            match name {
+                "CallProperty" | "CallPropLex" | "CallPropVoid" | "CallSuper" | "CallSuperVoid"
+                | "ConstructProp" => {
+                    let index = self.multiname_arg(args.next()?)?;
+                    let num_args = args.next()?.int()?;
+                    match name {
+                        "CallProperty" => Op::CallProperty { index, num_args },
+                        "CallPropLex" => Op::CallPropLex { index, num_args },
+                        "CallPropVoid" => Op::CallPropVoid { index, num_args },
+                        "CallSuper" => Op::CallSuper { index, num_args },
+                        "CallSuperVoid" => Op::CallSuperVoid { index, num_args },
+                        _ => Op::ConstructProp { index, num_args },
+                    }
+                }
+                "CallMethod" => Op::CallMethod {
+                    index: args.next()?.int()?,
+                    num_args: args.next()?.int()?,
+                },
+                "CallStatic" => Op::CallStatic {
+                    index: self.method_ref(args.next()?)?,
+                    num_args: args.next()?.int()?,
+                },
+                "Debug" => {
+                    let register_name = self.string(args.next()?.string()?);
+                    let register = args.next()?.int()?;
+                    Op::Debug {
+                        is_local_register: true,
+                        register_name,
+                        register,
+                    }
+                }
+                "GetScopeObject" => Op::GetScopeObject {
+                    index: args.next()?.int()?,
+                },
+                "HasNext2" => Op::HasNext2 {
+                    object_register: args.next()?.int()?,
+                    index_register: args.next()?.int()?,
+                },
+                "NewCatch" => Op::NewCatch {
+                    index: Index::new(args.next()?.int()?),
+                },
+                "NewClass" => Op::NewClass {
+                    index: self.class_ref(args.next()?)?,
+                },
+                "NewFunction" => Op::NewFunction {
+                    index: self.method_ref(args.next()?)?,
+                },
+                "PushByte" => {
+                    // Accept both signed and unsigned bytes, as the value is sign-extended.
+                    let value: i64 = args.next()?.int()?;
+                    let value = i8::try_from(value)
+                        .map(|value| value as u8)
+                        .or_else(|_| u8::try_from(value))
+                        .map_err(|_| AsmError::OutOfRange(location, value.to_string()))?;
+                    Op::PushByte { value }
+                }
+                "PushDouble" => Op::PushDouble {
+                    value: self.double(args.next()?.float()?),
+                },
+                "PushInt" => Op::PushInt {
+                    value: self.int(args.next()?.int()?),
+                },
+                "PushNamespace" => {
+                    let argument = args.next()?;
+                    Op::PushNamespace {
+                        value: self.namespace(argument.string()?, argument.location)?,
+                    }
+                }
+                "PushShort" => Op::PushShort {
+                    value: args.next()?.int()?,
+                },
+                "PushUint" => Op::PushUint {
+                    value: self.uint(args.next()?.int()?),
+                },
+                _ => return Err(AsmError::UnknownInstruction(location, name.to_owned())),
+            }
+        };
+        args.finish()?;
+
+        stats.record(&op);
+        code.push(write_op(&op)?);
+        Ok(())
+    }
+
+    /// `class ref, "name", extends="Object", init=ref, class_init=ref, protected="ns", FLAGS...`
+    ///
+    /// The block may contain `implements "name"` statements and instance traits, along with a
+    /// `static { ... }` block of class traits.
+    fn class(&mut self, statement: &Statement) -> AsmResult<()> {
+        let mut args = statement.args(&["extends", "init", "class_init", "protected"])?;
+        args.next()?;
+        let name = self.multiname_arg(args.next()?)?;
+        let super_name = match args.named("extends") {
+            Some(extends) => self.multiname_arg(extends)?,
+            None => Index::new(0),
+        };
+        let init_method = self.method_ref(
+            args.named("init")
+                .ok_or(AsmError::MissingArgument(statement.location, "init"))?,
+        )?;
+        let class_init = self.method_ref(
+            args.named("class_init")
+                .ok_or(AsmError::MissingArgument(statement.location, "class_init"))?,
+        )?;
+        let protected_namespace = match args.named("protected") {
+            Some(protected) => Some(self.namespace(protected.string()?, protected.location)?),
+            None => None,
+        };
+        let (mut is_sealed, mut is_final, mut is_interface) = (false, false, false);
+        for argument in args.rest() {
+            match argument.identifier()? {
+                "sealed" => is_sealed = true,
+                "final" => is_final = true,
+                "interface" => is_interface = true,
+                other => {
+                    return Err(AsmError::UnknownArgument(
+                        argument.location,
+                        other.to_owned(),
+                    ))
+                }
+            }
+        }
+        args.finish()?;
+
+        let mut interfaces = vec![];
+        let mut instance_traits = vec![];
+        let mut class_traits = vec![];
+        for line in statement.block.as_deref().unwrap_or_default() {
+            let statement = match line {
+                Line::Statement(statement) => statement,
+                Line::Label(_, location) => return Err(AsmError::UnexpectedLabel(*location)),
+            };
+            match statement.name.as_str() {
+                "implements" => {
+                    statement.no_block()?;
+                    for argument in statement.args(&[])?.rest() {
+                        interfaces.push(self.multiname_arg(argument)?);
+                    }
+                }
+                "static" => {
+                    statement.args(&[])?.finish()?;
+                    class_traits.extend(self.traits(statement.block()?)?);
+                }
+                _ => instance_traits.push(self.trait_(statement)?),
+            }
+        }
+
+        self.abc.instances.push(Instance {
+            name,
+            super_name,
+            is_sealed,
+            is_final,
+            is_interface,
+            protected_namespace,
+            interfaces,
+            init_method,
+            traits: instance_traits,
+        });
+        self.abc.classes.push(Class {
+            init_method: class_init,
+            traits: class_traits,
+        });
+        Ok(())
+    }
+
+    /// `script init=ref { traits }`
+    fn script(&mut self, statement: &Statement) -> AsmResult<()> {
+        let args = statement.args(&["init"])?;
+        let init_method = self.method_ref(
+            args.named("init")
+                .ok_or(AsmError::MissingArgument(statement.location, "init"))?,
+        )?;
+        args.finish()?;
+        let traits = match &statement.block {
+            Some(lines) => self.traits(lines)?,
+            None => vec![],
+        };
+        self.abc.scripts.push(Script {
+            init_method,
+            traits,
+        });
+        Ok(())
+    }
+
+    fn traits(&mut self, lines: &[Line]) -> AsmResult<Vec<Trait>> {
+        lines
+            .iter()
+            .map(|line| match line {
+                Line::Statement(statement) => self.trait_(statement),
+                Line::Label(_, location) => Err(AsmError::UnexpectedLabel(*location)),
+            })
+            .collect()
+    }
+
+    /// A trait, followed by the optional `final` and `override` flags:
+    ///
+    /// - `slot "name", "type", id=0, value=...` (or `const`)
+    /// - `method "name", ref, disp=0` (or `getter` and `setter`)
+    /// - `class "name", ref, slot=0`
+    /// - `function "name", ref, slot=0`
+    fn trait_(&mut self, statement: &Statement) -> AsmResult<Trait> {
+        statement.no_block()?;
+        let location = statement.location;
+        let mut args = statement.args(&["id", "value", "disp", "slot"])?;
+        let name = self.multiname_arg(args.next()?)?;
+        let slot_id = match args.named("id").or(args.named("slot")) {
+            Some(id) => id.int()?,
+            None => 0,
+        };
+        let disp_id = match args.named("disp") {
+            Some(disp) => disp.int()?,
+            None => 0,
+        };
+        let mut rest = args.rest().peekable();
+        let mut reference = || rest.next().ok_or(AsmError::TooFewArguments(location));
+
+        let kind = match statement.name.as_str() {
+            "slot" | "const" => {
+                // The type is optional, and is the only string that may follow the name.
+                let type_name = match rest.next_if(|a| matches!(a.value, Value::String(_))) {
+                    Some(type_name) => self.multiname_arg(type_name)?,
+                    None => Index::new(0),
+                };
+                let value = match args.named("value") {
+                    Some(value) => Some(self.default_value(value)?),
+                    None => None,
+                };
+                if statement.name == "slot" {
+                    TraitKind::Slot {
+                        slot_id,
+                        type_name,
+                        value,
+                    }
+                } else {
+                    TraitKind::Const {
+                        slot_id,
+                        type_name,
+                        value,
+                    }
+                }
+            }
+            "method" => TraitKind::Method {
+                disp_id,
+                method: self.method_ref(reference()?)?,
+            },
+            "getter" => TraitKind::Getter {
+                disp_id,
+                method: self.method_ref(reference()?)?,
+            },
+            "setter" => TraitKind::Setter {
+                disp_id,
+                method: self.method_ref(reference()?)?,
+            },
+            "class" => TraitKind::Class {
+                slot_id,
+                class: self.class_ref(reference()?)?,
+            },
+            "function" => TraitKind::Function {
+                slot_id,
+                function: self.method_ref(reference()?)?,
+            },
+            other => return Err(AsmError::UnknownStatement(location, other.to_owned())),
+        };
+
+        let (mut is_final, mut is_override) = (false, false);
+        for argument in rest {
+            match argument.identifier()? {
+                "final" => is_final = true,
+                "override" => is_override = true,
+                other => {
+                    return Err(AsmError::UnknownArgument(
+                        argument.location,
+                        other.to_owned(),
+                    ))
+                }
+            }
+        }
+
+        Ok(Trait {
+            name,
+            kind,
+            metadata: vec![],
+            is_final,
+            is_override,
+        })
+    }
+}
@@ -0,0 +1,16 @@
+//! CLI Options
+
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[clap(version, about)]
+pub struct Opt {
+    /// The assembly source to read
+    #[clap(name = "source")]
+    pub input_path: PathBuf,
+
+    /// The SWF file to write, defaulting to the source with a `.swf` extension
+    #[clap(short, long)]
+    pub output_path: Option<PathBuf>,
+}
@@ -0,0 +1,93 @@
+//! Lays out bytecode whose branches may refer to labels that are only defined later on.
+
+use crate::error::{AsmError, AsmResult, Location};
+use std::collections::HashMap;
+
+/// Encodes a branch, given its own offset and the offsets of each of its targets.
+///
+/// The length of the encoded branch must not depend on the offsets.
+type EncodeBranch<'a> = Box<dyn Fn(usize, &[usize]) -> AsmResult<Vec<u8>> + 'a>;
+
+struct Item<'a> {
+    bytes: Vec<u8>,
+    branch: Option<(Vec<(String, Location)>, EncodeBranch<'a>)>,
+}
+
+#[derive(Default)]
+pub struct CodeBuilder<'a> {
+    items: Vec<Item<'a>>,
+    /// The index of the item following each label.
+    labels: HashMap<String, usize>,
+}
+
+impl<'a> CodeBuilder<'a> {
+    pub fn push(&mut self, bytes: Vec<u8>) {
+        self.items.push(Item {
+            bytes,
+            branch: None,
+        });
+    }
+
+    pub fn push_branch(
+        &mut self,
+        targets: Vec<(String, Location)>,
+        encode: impl Fn(usize, &[usize]) -> AsmResult<Vec<u8>> + 'a,
+    ) -> AsmResult<()> {
+        let bytes = encode(0, &vec![0; targets.len()])?;
+        self.items.push(Item {
+            bytes,
+            branch: Some((targets, Box::new(encode))),
+        });
+        Ok(())
+    }
+
+    pub fn label(&mut self, name: &str, location: Location) -> AsmResult<()> {
+        if self
+            .labels
+            .insert(name.to_owned(), self.items.len())
+            .is_some()
+        {
+            return Err(AsmError::DuplicateLabel(location, name.to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Resolves every branch, returning the final code and the offset of each label.
+    pub fn finish(self) -> AsmResult<(Vec<u8>, HashMap<String, usize>)> {
+        let mut starts = Vec::with_capacity(self.items.len() + 1);
+        let mut offset = 0;
+        for item in &self.items {
+            starts.push(offset);
+            offset += item.bytes.len();
+        }
+        starts.push(offset);
+
+        let labels: HashMap<_, _> = self
+            .labels
+            .into_iter()
+            .map(|(name, index)| (name, starts[index]))
+            .collect();
+
+        let mut code = Vec::with_capacity(offset);
+        for (item, start) in self.items.into_iter().zip(starts) {
+            match item.branch {
+                Some((targets, encode)) => {
+                    let targets = targets
+                        .into_iter()
+                        .map(|(name, location)| {
+                            labels
+                                .get(&name)
+                                .copied()
+                                .ok_or(AsmError::UnknownLabel(location, name))
+                        })
+                        .collect::<AsmResult<Vec<_>>>()?;
+                    let bytes = encode(start, &targets)?;
+                    debug_assert_eq!(bytes.len(), item.bytes.len());
+                    code.extend(bytes);
+                }
+                None => code.extend(item.bytes),
+            }
+        }
+        Ok((code, labels))
+    }
+}
@@ -0,0 +1,87 @@
+use crate::parser::Rule;
+use std::fmt::Display;
+use thiserror::Error;
+
+/// A line and column within the assembly source.
+#[derive(Clone, Copy, Debug)]
+pub struct Location(pub usize, pub usize);
+
+impl Location {
+    pub(crate) fn from(line_col: (usize, usize)) -> Self {
+        Self(line_col.0, line_col.1)
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.0, self.1)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AsmError {
+    #[error("Parsing failed: {0}")]
+    ParsingError(Box<pest::error::Error<Rule>>),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Error writing SWF: {0}")]
+    SwfError(#[from] swf::error::Error),
+
+    #[error("{0}: Unknown statement: {1}")]
+    UnknownStatement(Location, String),
+
+    #[error("{0}: Unknown instruction: {1}")]
+    UnknownInstruction(Location, String),
+
+    #[error("{0}: Too few arguments")]
+    TooFewArguments(Location),
+
+    #[error("{0}: Too many arguments")]
+    TooManyArguments(Location),
+
+    #[error("{0}: Missing argument: {1}")]
+    MissingArgument(Location, &'static str),
+
+    #[error("{0}: Unknown argument: {1}")]
+    UnknownArgument(Location, String),
+
+    #[error("{0}: Unexpected argument, expected {1}")]
+    WrongArgument(Location, &'static str),
+
+    #[error("{0}: Value out of range: {1}")]
+    OutOfRange(Location, String),
+
+    #[error("{0}: Error parsing string: {1}")]
+    ErrorParsingString(Location, Box<serde_json::Error>),
+
+    #[error("{0}: Expected a block")]
+    MissingBlock(Location),
+
+    #[error("{0}: Unexpected block")]
+    UnexpectedBlock(Location),
+
+    #[error("{0}: Labels are only allowed in code")]
+    UnexpectedLabel(Location),
+
+    #[error("{0}: Duplicate label: {1}")]
+    DuplicateLabel(Location, String),
+
+    #[error("{0}: Unknown label: {1}")]
+    UnknownLabel(Location, String),
+
+    #[error("{0}: Branch to {1} is too far")]
+    BranchTooFar(Location, String),
+
+    #[error("{0}: Duplicate definition: {1}")]
+    DuplicateDefinition(Location, String),
+
+    #[error("{0}: Unknown reference: {1}")]
+    UnknownReference(Location, String),
+
+    #[error("{0}: Invalid multiname: {1}")]
+    InvalidMultiname(Location, String),
+}
+
+pub type AsmResult<T> = Result<T, AsmError>;
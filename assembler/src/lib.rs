@@ -0,0 +1,55 @@
+//! Assembles SWF files out of a textual description of their tags, shapes, AVM1 actions and
+//! AVM2 bytecode. This is mostly meant for writing test fixtures by hand.
+//!
+//! A source file is made of statements, one per line. Each statement is a keyword followed by
+//! comma-separated arguments, some of which may be named (`x=10`), and optionally a block of
+//! nested statements in braces. Code blocks may also contain labels (`loop:`), which branches
+//! refer to. Comments start with `//` or `;`.
+//!
+//! ```text
+//! version 10
+//! size 100, 100
+//! shape 1 {
+//!   fill 0xFF0000
+//!   rect 0, 0, 50, 50
+//! }
+//! place 1, 1, x=25, y=25
+//! do_action {
+//!   Push "Hello"
+//!   Trace
+//! }
+//! show
+//! ```
+
+mod avm1;
+mod avm2;
+mod code;
+mod error;
+mod movie;
+mod parser;
+
+pub use error::{AsmError, AsmResult, Location};
+pub use swf::avm2::types::AbcFile;
+
+/// Assembles a whole SWF, and writes it to `output`.
+pub fn assemble_swf(input: &str, output: impl std::io::Write) -> AsmResult<()> {
+    movie::assemble_movie(&parser::parse(input)?, output)
+}
+
+/// Assembles a block of AVM1 actions, as found in a `DoAction` tag.
+pub fn assemble_avm1(input: &str, swf_version: u8) -> AsmResult<Vec<u8>> {
+    avm1::assemble_actions(&parser::parse(input)?, swf_version)
+}
+
+/// Assembles an ABC file, without writing it out.
+pub fn assemble_abc(input: &str) -> AsmResult<AbcFile> {
+    avm2::assemble_abc(&parser::parse(input)?)
+}
+
+/// Assembles an ABC file, as found in a `DoAbc` tag.
+pub fn assemble_abc_bytes(input: &str) -> AsmResult<Vec<u8>> {
+    avm2::write_abc(&parser::parse(input)?)
+}
+
+#[cfg(test)]
+mod tests;
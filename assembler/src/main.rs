@@ -0,0 +1,25 @@
+//! Assembles a SWF file out of its textual description.
+
+use crate::cli_options::Opt;
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::fs::File;
+use std::io::BufWriter;
+
+mod cli_options;
+
+fn main() -> Result<()> {
+    let opt = Opt::parse();
+
+    let input = std::fs::read_to_string(&opt.input_path)
+        .with_context(|| format!("Couldn't read {}", opt.input_path.display()))?;
+    let output_path = opt
+        .output_path
+        .unwrap_or_else(|| opt.input_path.with_extension("swf"));
+    let output = File::create(&output_path)
+        .with_context(|| format!("Couldn't create {}", output_path.display()))?;
+
+    ruffle_assembler::assemble_swf(&input, BufWriter::new(output))
+        .with_context(|| format!("Couldn't assemble {}", opt.input_path.display()))?;
+    Ok(())
+}
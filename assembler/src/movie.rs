@@ -0,0 +1,584 @@
+//! Assembles a whole SWF: its header, and the tags on its timeline.
+
+use crate::avm1::assemble_actions;
+use crate::avm2::write_abc;
+use crate::error::{AsmError, AsmResult};
+use crate::parser::{Args, Line, Statement};
+use std::io::Write;
+use swf::{
+    CharacterId, ClipAction, ClipEventFlag, Color, Compression, Depth, DoAbc2, DoAbc2Flag,
+    ExportedAsset, FileAttributes, FillStyle, Fixed16, Fixed8, FrameLabel, Header, LineStyle,
+    Matrix, PlaceObject, PlaceObjectAction, Point, PointDelta, Rectangle, RemoveObject, Shape,
+    ShapeFlag, ShapeRecord, ShapeStyles, Sprite, StyleChangeData, SwfStr, SymbolClassLink, Tag,
+    Twips,
+};
+
+/// A tag whose contents are owned, so that tags may borrow from it when the SWF is written.
+enum Item {
+    Shape(Box<Shape>),
+    Sprite {
+        id: CharacterId,
+        num_frames: u16,
+        items: Vec<Item>,
+    },
+    Place(Box<Place>),
+    Remove(Depth),
+    ShowFrame,
+    FrameLabel(String),
+    DoAction(Vec<u8>),
+    DoInitAction {
+        id: CharacterId,
+        action_data: Vec<u8>,
+    },
+    DoAbc {
+        name: Option<String>,
+        is_lazy: bool,
+        data: Vec<u8>,
+    },
+    Export(CharacterId, String),
+    SymbolClass(CharacterId, String),
+    SetBackgroundColor(Color),
+    FileAttributes(FileAttributes),
+}
+
+struct Place {
+    action: PlaceObjectAction,
+    depth: Depth,
+    matrix: Option<Matrix>,
+    ratio: Option<u16>,
+    name: Option<String>,
+    clip_depth: Option<Depth>,
+    clip_actions: Vec<(ClipEventFlag, Vec<u8>)>,
+}
+
+impl Item {
+    fn to_tag(&self) -> Tag<'_> {
+        match self {
+            Item::Shape(shape) => Tag::DefineShape(shape.clone()),
+            Item::Sprite {
+                id,
+                num_frames,
+                items,
+            } => Tag::DefineSprite(Sprite {
+                id: *id,
+                num_frames: *num_frames,
+                tags: items.iter().map(Item::to_tag).collect(),
+            }),
+            Item::Place(place) => Tag::PlaceObject(Box::new(PlaceObject {
+                version: 2,
+                action: place.action,
+                depth: place.depth,
+                matrix: place.matrix,
+                color_transform: None,
+                ratio: place.ratio,
+                name: place.name.as_deref().map(SwfStr::from_utf8_str),
+                clip_depth: place.clip_depth,
+                class_name: None,
+                filters: None,
+                background_color: None,
+                blend_mode: None,
+                clip_actions: (!place.clip_actions.is_empty()).then(|| {
+                    place
+                        .clip_actions
+                        .iter()
+                        .map(|(events, action_data)| ClipAction {
+                            events: *events,
+                            key_code: None,
+                            action_data,
+                        })
+                        .collect()
+                }),
+                has_image: false,
+                is_bitmap_cached: None,
+                is_visible: None,
+                amf_data: None,
+            })),
+            Item::Remove(depth) => Tag::RemoveObject(RemoveObject {
+                depth: *depth,
+                character_id: None,
+            }),
+            Item::ShowFrame => Tag::ShowFrame,
+            Item::FrameLabel(label) => Tag::FrameLabel(FrameLabel {
+                label: SwfStr::from_utf8_str(label),
+                is_anchor: false,
+            }),
+            Item::DoAction(action_data) => Tag::DoAction(action_data),
+            Item::DoInitAction { id, action_data } => Tag::DoInitAction {
+                id: *id,
+                action_data,
+            },
+            Item::DoAbc {
+                name: None,
+                is_lazy: false,
+                data,
+            } => Tag::DoAbc(data),
+            Item::DoAbc {
+                name,
+                is_lazy,
+                data,
+            } => Tag::DoAbc2(DoAbc2 {
+                flags: if *is_lazy {
+                    DoAbc2Flag::LAZY_INITIALIZE
+                } else {
+                    DoAbc2Flag::empty()
+                },
+                name: SwfStr::from_utf8_str(name.as_deref().unwrap_or_default()),
+                data,
+            }),
+            Item::Export(id, name) => Tag::ExportAssets(vec![ExportedAsset {
+                id: *id,
+                name: SwfStr::from_utf8_str(name),
+            }]),
+            Item::SymbolClass(id, class_name) => Tag::SymbolClass(vec![SymbolClassLink {
+                id: *id,
+                class_name: SwfStr::from_utf8_str(class_name),
+            }]),
+            Item::SetBackgroundColor(color) => Tag::SetBackgroundColor(*color),
+            Item::FileAttributes(attributes) => Tag::FileAttributes(*attributes),
+        }
+    }
+}
+
+/// Assembles a SWF and writes it to `output`.
+pub fn assemble_movie(lines: &[Line], output: impl Write) -> AsmResult<()> {
+    let mut header = Header {
+        compression: Compression::Zlib,
+        version: 10,
+        stage_size: Rectangle {
+            x_min: Twips::ZERO,
+            x_max: Twips::from_pixels(550.0),
+            y_min: Twips::ZERO,
+            y_max: Twips::from_pixels(400.0),
+        },
+        frame_rate: Fixed8::from_f32(24.0),
+        num_frames: 0,
+    };
+
+    // The header may be given anywhere, but the SWF version affects how actions are written.
+    let mut timeline = vec![];
+    for line in lines {
+        let statement = match line {
+            Line::Statement(statement)
+                if matches!(
+                    statement.name.as_str(),
+                    "version" | "size" | "frame_rate" | "compression"
+                ) =>
+            {
+                statement
+            }
+            _ => {
+                timeline.push(line);
+                continue;
+            }
+        };
+        let mut args = statement.args(&[])?;
+        match statement.name.as_str() {
+            "version" => header.version = args.next()?.int()?,
+            "size" => {
+                header.stage_size.x_max = Twips::from_pixels(args.next()?.float()?);
+                header.stage_size.y_max = Twips::from_pixels(args.next()?.float()?);
+            }
+            "frame_rate" => header.frame_rate = Fixed8::from_f64(args.next()?.float()?),
+            "compression" => {
+                let argument = args.next()?;
+                header.compression = match argument.identifier()? {
+                    "none" => Compression::None,
+                    "zlib" => Compression::Zlib,
+                    "lzma" => Compression::Lzma,
+                    other => {
+                        return Err(AsmError::UnknownArgument(
+                            argument.location,
+                            other.to_owned(),
+                        ))
+                    }
+                };
+            }
+            _ => unreachable!(),
+        }
+        statement.no_block()?;
+        args.finish()?;
+    }
+
+    let mut assembler = TimelineAssembler {
+        version: header.version,
+        is_sprite: false,
+        items: vec![],
+        num_frames: 0,
+    };
+    for line in timeline {
+        assembler.line(line)?;
+    }
+    header.num_frames = assembler.num_frames;
+
+    let tags: Vec<_> = assembler.items.iter().map(Item::to_tag).collect();
+    swf::write_swf(&header, &tags, output)?;
+    Ok(())
+}
+
+struct TimelineAssembler {
+    version: u8,
+    is_sprite: bool,
+    items: Vec<Item>,
+    num_frames: u16,
+}
+
+impl TimelineAssembler {
+    fn line(&mut self, line: &Line) -> AsmResult<()> {
+        let statement = match line {
+            Line::Statement(statement) => statement,
+            Line::Label(_, location) => return Err(AsmError::UnexpectedLabel(*location)),
+        };
+        let location = statement.location;
+        let name = statement.name.as_str();
+
+        // Only control tags are allowed within sprites.
+        let is_definition = matches!(
+            name,
+            "shape"
+                | "sprite"
+                | "init_action"
+                | "export"
+                | "symbol"
+                | "abc"
+                | "file_attributes"
+                | "background"
+        );
+        if self.is_sprite && is_definition {
+            return Err(AsmError::UnknownStatement(location, name.to_owned()));
+        }
+
+        let named: &[&str] = match name {
+            "place" | "move" => &[
+                "x",
+                "y",
+                "scale_x",
+                "scale_y",
+                "rotation",
+                "name",
+                "ratio",
+                "clip_depth",
+            ],
+            _ => &[],
+        };
+        let mut args = statement.args(named)?;
+        if !matches!(
+            name,
+            "shape" | "sprite" | "place" | "do_action" | "init_action" | "abc"
+        ) {
+            statement.no_block()?;
+        }
+
+        let item = match name {
+            "file_attributes" => {
+                let mut attributes = FileAttributes::empty();
+                for argument in args.rest() {
+                    let flag = argument.identifier()?;
+                    attributes |= FileAttributes::from_name(flag).ok_or_else(|| {
+                        AsmError::UnknownArgument(argument.location, flag.to_owned())
+                    })?;
+                }
+                Item::FileAttributes(attributes)
+            }
+            "background" => Item::SetBackgroundColor(args.next()?.color()?),
+            "shape" => {
+                let id = args.next()?.int()?;
+                Item::Shape(Box::new(assemble_shape(id, statement.block()?)?))
+            }
+            "sprite" => {
+                let id = args.next()?.int()?;
+                let mut sprite = TimelineAssembler {
+                    version: self.version,
+                    is_sprite: true,
+                    items: vec![],
+                    num_frames: 0,
+                };
+                for line in statement.block()? {
+                    sprite.line(line)?;
+                }
+                Item::Sprite {
+                    id,
+                    num_frames: sprite.num_frames,
+                    items: sprite.items,
+                }
+            }
+            "place" | "move" => Item::Place(Box::new(self.place(statement, &mut args)?)),
+            "remove" => Item::Remove(args.next()?.int()?),
+            "show" => {
+                self.num_frames += 1;
+                Item::ShowFrame
+            }
+            "label" => Item::FrameLabel(args.next()?.string()?.to_owned()),
+            "do_action" => Item::DoAction(assemble_actions(statement.block()?, self.version)?),
+            "init_action" => Item::DoInitAction {
+                id: args.next()?.int()?,
+                action_data: assemble_actions(statement.block()?, self.version)?,
+            },
+            "export" => Item::Export(args.next()?.int()?, args.next()?.string()?.to_owned()),
+            "symbol" => Item::SymbolClass(args.next()?.int()?, args.next()?.string()?.to_owned()),
+            "abc" => {
+                // `abc { ... }` is written as a DoAbc tag, and `abc "name", lazy { ... }` as DoAbc2.
+                let name = match args.next_opt() {
+                    Some(name) => Some(name.string()?.to_owned()),
+                    None => None,
+                };
+                let is_lazy = match args.next_opt() {
+                    Some(argument) if argument.identifier()? == "lazy" => true,
+                    Some(argument) => {
+                        return Err(AsmError::WrongArgument(argument.location, "lazy"));
+                    }
+                    None => false,
+                };
+                Item::DoAbc {
+                    name: name.or_else(|| is_lazy.then(String::new)),
+                    is_lazy,
+                    data: write_abc(statement.block()?)?,
+                }
+            }
+            _ => return Err(AsmError::UnknownStatement(location, name.to_owned())),
+        };
+        args.finish()?;
+        self.items.push(item);
+        Ok(())
+    }
+
+    /// `place id, depth, x=, y=, scale_x=, scale_y=, rotation=, name=, ratio=, clip_depth=`
+    /// places a character, and `move depth, ...` modifies the one that's already there.
+    ///
+    /// The block of a `place` statement holds clip actions, such as `on ENTER_FRAME { ... }`.
+    fn place(&self, statement: &Statement, args: &mut Args<'_>) -> AsmResult<Place> {
+        let action = match statement.name.as_str() {
+            "place" => PlaceObjectAction::Place(args.next()?.int()?),
+            _ => PlaceObjectAction::Modify,
+        };
+        let depth = args.next()?.int()?;
+
+        let float = |name| args.named(name).map(|a| a.float()).transpose();
+        let (x, y) = (float("x")?, float("y")?);
+        let (scale_x, scale_y) = (float("scale_x")?, float("scale_y")?);
+        let rotation = float("rotation")?;
+        let matrix = (x.is_some()
+            || y.is_some()
+            || scale_x.is_some()
+            || scale_y.is_some()
+            || rotation.is_some())
+        .then(|| {
+            let (sin, cos) = rotation.unwrap_or(0.0).to_radians().sin_cos();
+            let (scale_x, scale_y) = (scale_x.unwrap_or(1.0), scale_y.unwrap_or(1.0));
+            Matrix {
+                a: Fixed16::from_f64(scale_x * cos),
+                b: Fixed16::from_f64(scale_x * sin),
+                c: Fixed16::from_f64(-scale_y * sin),
+                d: Fixed16::from_f64(scale_y * cos),
+                tx: Twips::from_pixels(x.unwrap_or(0.0)),
+                ty: Twips::from_pixels(y.unwrap_or(0.0)),
+            }
+        });
+
+        let mut clip_actions = vec![];
+        for line in statement.block.as_deref().unwrap_or_default() {
+            let event = match line {
+                Line::Statement(event) if event.name == "on" => event,
+                Line::Statement(other) => {
+                    return Err(AsmError::UnknownStatement(
+                        other.location,
+                        other.name.clone(),
+                    ))
+                }
+                Line::Label(_, location) => return Err(AsmError::UnexpectedLabel(*location)),
+            };
+            let mut events = ClipEventFlag::empty();
+            for argument in event.args(&[])?.rest() {
+                let flag = argument.identifier()?;
+                events |= ClipEventFlag::from_name(flag)
+                    .ok_or_else(|| AsmError::UnknownArgument(argument.location, flag.to_owned()))?;
+            }
+            if events.is_empty() {
+                return Err(AsmError::TooFewArguments(event.location));
+            }
+            clip_actions.push((events, assemble_actions(event.block()?, self.version)?));
+        }
+
+        Ok(Place {
+            action,
+            depth,
+            matrix,
+            ratio: args.named("ratio").map(|a| a.int()).transpose()?,
+            name: args
+                .named("name")
+                .map(|a| a.string().map(str::to_owned))
+                .transpose()?,
+            clip_depth: args.named("clip_depth").map(|a| a.int()).transpose()?,
+            clip_actions,
+        })
+    }
+}
+
+/// Builds the records of a shape, keeping track of the pen and of the bounds.
+struct ShapeBuilder {
+    styles: ShapeStyles,
+    records: Vec<ShapeRecord>,
+    style_change: Option<StyleChangeData>,
+    position: Point<Twips>,
+    bounds: Option<Rectangle<Twips>>,
+    max_line_width: Twips,
+}
+
+impl ShapeBuilder {
+    fn extend_bounds(&mut self, point: Point<Twips>) {
+        let bounds = self.bounds.get_or_insert(Rectangle {
+            x_min: point.x,
+            x_max: point.x,
+            y_min: point.y,
+            y_max: point.y,
+        });
+        bounds.x_min = bounds.x_min.min(point.x);
+        bounds.x_max = bounds.x_max.max(point.x);
+        bounds.y_min = bounds.y_min.min(point.y);
+        bounds.y_max = bounds.y_max.max(point.y);
+    }
+
+    fn style_change(&mut self) -> &mut StyleChangeData {
+        self.style_change.get_or_insert(StyleChangeData {
+            move_to: None,
+            fill_style_0: None,
+            fill_style_1: None,
+            line_style: None,
+            new_styles: None,
+        })
+    }
+
+    fn flush_style_change(&mut self) {
+        if let Some(style_change) = self.style_change.take() {
+            self.records
+                .push(ShapeRecord::StyleChange(Box::new(style_change)));
+        }
+    }
+
+    fn move_to(&mut self, point: Point<Twips>) {
+        self.style_change().move_to = Some(point);
+        self.flush_style_change();
+        self.position = point;
+        self.extend_bounds(point);
+    }
+
+    fn line_to(&mut self, point: Point<Twips>) {
+        self.flush_style_change();
+        self.records.push(ShapeRecord::StraightEdge {
+            delta: point - self.position,
+        });
+        self.position = point;
+        self.extend_bounds(point);
+    }
+
+    fn curve_to(&mut self, control: Point<Twips>, anchor: Point<Twips>) {
+        self.flush_style_change();
+        self.records.push(ShapeRecord::CurvedEdge {
+            control_delta: control - self.position,
+            anchor_delta: anchor - control,
+        });
+        self.position = anchor;
+        self.extend_bounds(control);
+        self.extend_bounds(anchor);
+    }
+}
+
+fn point(args: &mut Args<'_>) -> AsmResult<Point<Twips>> {
+    let x = args.next()?.float()?;
+    let y = args.next()?.float()?;
+    Ok(Point::from_pixels(x, y))
+}
+
+/// `shape id { fill 0xRRGGBB; line width, 0xRRGGBB; move x, y; line_to x, y;
+/// curve_to cx, cy, x, y; rect x, y, width, height }`
+fn assemble_shape(id: CharacterId, lines: &[Line]) -> AsmResult<Shape> {
+    let mut builder = ShapeBuilder {
+        styles: ShapeStyles {
+            fill_styles: vec![],
+            line_styles: vec![],
+        },
+        records: vec![],
+        style_change: None,
+        position: Point::ZERO,
+        bounds: None,
+        max_line_width: Twips::ZERO,
+    };
+
+    for line in lines {
+        let statement = match line {
+            Line::Statement(statement) => statement,
+            Line::Label(_, location) => return Err(AsmError::UnexpectedLabel(*location)),
+        };
+        statement.no_block()?;
+        let mut args = statement.args(&[])?;
+        match statement.name.as_str() {
+            "fill" => {
+                let fill = match args.next_opt() {
+                    Some(color) => {
+                        let styles = &mut builder.styles.fill_styles;
+                        styles.push(FillStyle::Color(color.color()?));
+                        Some(styles.len() as u32)
+                    }
+                    None => None,
+                };
+                builder.style_change().fill_style_1 = Some(fill.unwrap_or(0));
+            }
+            "line" => {
+                let line = match args.next_opt() {
+                    Some(width) => {
+                        let width = Twips::from_pixels(width.float()?);
+                        let color = args.next()?.color()?;
+                        builder.max_line_width = builder.max_line_width.max(width);
+                        let styles = &mut builder.styles.line_styles;
+                        styles.push(LineStyle::new().with_width(width).with_color(color));
+                        Some(styles.len() as u32)
+                    }
+                    None => None,
+                };
+                builder.style_change().line_style = Some(line.unwrap_or(0));
+            }
+            "move" => builder.move_to(point(&mut args)?),
+            "line_to" => builder.line_to(point(&mut args)?),
+            "curve_to" => {
+                let control = point(&mut args)?;
+                let anchor = point(&mut args)?;
+                builder.curve_to(control, anchor);
+            }
+            "rect" => {
+                let origin = point(&mut args)?;
+                let size = PointDelta::from_pixels(args.next()?.float()?, args.next()?.float()?);
+                builder.move_to(origin);
+                builder.line_to(Point::new(origin.x + size.dx, origin.y));
+                builder.line_to(origin + size);
+                builder.line_to(Point::new(origin.x, origin.y + size.dy));
+                builder.line_to(origin);
+            }
+            other => {
+                return Err(AsmError::UnknownStatement(
+                    statement.location,
+                    other.to_owned(),
+                ))
+            }
+        }
+        args.finish()?;
+    }
+    builder.flush_style_change();
+
+    let edge_bounds = builder.bounds.unwrap_or_default();
+    let half_width = builder.max_line_width / 2;
+    let shape_bounds = Rectangle {
+        x_min: edge_bounds.x_min - half_width,
+        x_max: edge_bounds.x_max + half_width,
+        y_min: edge_bounds.y_min - half_width,
+        y_max: edge_bounds.y_max + half_width,
+    };
+    Ok(Shape {
+        // DefineShape3 is the first version that supports transparent colors.
+        version: 3,
+        id,
+        shape_bounds,
+        edge_bounds,
+        flags: ShapeFlag::empty(),
+        styles: builder.styles,
+        shape: builder.records,
+    })
+}
@@ -0,0 +1,301 @@
+//! Parses assembly source into a tree of statements, leaving their meaning to the assemblers.
+
+use crate::error::{AsmError, AsmResult, Location};
+use pest::iterators::{Pair, Pairs};
+use pest::Parser;
+use pest_derive::Parser;
+use std::collections::VecDeque;
+use swf::Color;
+
+#[derive(Parser)]
+#[grammar = "grammar.pest"]
+struct SwfAsmParser;
+
+#[derive(Debug)]
+pub enum Line {
+    Label(String, Location),
+    Statement(Statement),
+}
+
+/// A keyword followed by its arguments, and optionally a block of nested lines.
+#[derive(Debug)]
+pub struct Statement {
+    pub name: String,
+    pub location: Location,
+    pub arguments: Vec<Argument>,
+    pub block: Option<Vec<Line>>,
+}
+
+/// A value, which may be given a name with `name=value`.
+#[derive(Debug)]
+pub struct Argument {
+    pub name: Option<String>,
+    pub value: Value,
+    pub location: Location,
+}
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Integer(i64),
+    Hex { value: u64, digits: usize },
+    Number(f64),
+    String(String),
+    Identifier(String),
+    Call(String, Vec<Value>),
+}
+
+pub fn parse(input: &str) -> AsmResult<Vec<Line>> {
+    let pairs =
+        SwfAsmParser::parse(Rule::file, input).map_err(|e| AsmError::ParsingError(Box::new(e)))?;
+    parse_lines(pairs)
+}
+
+fn parse_lines(pairs: Pairs<'_, Rule>) -> AsmResult<Vec<Line>> {
+    let mut lines = vec![];
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::label => {
+                let location = Location::from(pair.line_col());
+                let name = pair.into_inner().as_str().to_owned();
+                lines.push(Line::Label(name, location));
+            }
+            Rule::statement => lines.push(Line::Statement(parse_statement(pair)?)),
+            Rule::EOI => break,
+            _ => unreachable!(),
+        }
+    }
+    Ok(lines)
+}
+
+fn parse_statement(pair: Pair<'_, Rule>) -> AsmResult<Statement> {
+    let location = Location::from(pair.line_col());
+    let mut pairs = pair.into_inner();
+    let name = pairs
+        .next()
+        .expect("Statement should start with an identifier")
+        .as_str()
+        .to_owned();
+
+    let mut arguments = vec![];
+    let mut block = None;
+    for pair in pairs {
+        match pair.as_rule() {
+            Rule::argument => arguments.push(parse_argument(pair)?),
+            Rule::block => block = Some(parse_lines(pair.into_inner())?),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(Statement {
+        name,
+        location,
+        arguments,
+        block,
+    })
+}
+
+fn parse_argument(pair: Pair<'_, Rule>) -> AsmResult<Argument> {
+    let location = Location::from(pair.line_col());
+    let inner = pair.into_inner().next().expect("Argument can't be empty");
+    match inner.as_rule() {
+        Rule::named => {
+            let mut pairs = inner.into_inner();
+            let name = pairs.next().expect("Name should exist").as_str().to_owned();
+            let value = parse_value(pairs.next().expect("Value should exist"))?;
+            Ok(Argument {
+                name: Some(name),
+                value,
+                location,
+            })
+        }
+        Rule::value => Ok(Argument {
+            name: None,
+            value: parse_value(inner)?,
+            location,
+        }),
+        _ => unreachable!(),
+    }
+}
+
+fn parse_value(pair: Pair<'_, Rule>) -> AsmResult<Value> {
+    let location = Location::from(pair.line_col());
+    let inner = pair.into_inner().next().expect("Value can't be empty");
+    let text = inner.as_str();
+    let out_of_range = || AsmError::OutOfRange(location, text.to_owned());
+    Ok(match inner.as_rule() {
+        Rule::hex => Value::Hex {
+            value: u64::from_str_radix(&text[2..], 16).map_err(|_| out_of_range())?,
+            digits: text.len() - 2,
+        },
+        Rule::number if text.contains(['.', 'e', 'E']) => {
+            Value::Number(text.parse().map_err(|_| out_of_range())?)
+        }
+        Rule::number => Value::Integer(text.parse().map_err(|_| out_of_range())?),
+        Rule::string => {
+            let inner = inner.into_inner().as_str();
+            // Strings are escaped in the same way as JSON strings.
+            let string = serde_json::from_str(&format!("\"{inner}\""))
+                .map_err(|e| AsmError::ErrorParsingString(location, Box::new(e)))?;
+            Value::String(string)
+        }
+        Rule::call => {
+            let mut pairs = inner.into_inner();
+            let name = pairs.next().expect("Call should have a name").as_str();
+            let values = pairs.map(parse_value).collect::<AsmResult<_>>()?;
+            Value::Call(name.to_owned(), values)
+        }
+        Rule::identifier => Value::Identifier(text.to_owned()),
+        _ => unreachable!(),
+    })
+}
+
+impl Statement {
+    /// Returns the arguments of this statement, failing if any of them is named
+    /// something other than one of `allowed_names`.
+    pub fn args(&self, allowed_names: &[&str]) -> AsmResult<Args<'_>> {
+        let mut positional = VecDeque::new();
+        let mut named = vec![];
+        for argument in &self.arguments {
+            match &argument.name {
+                Some(name) if allowed_names.contains(&name.as_str()) => named.push(argument),
+                Some(name) => {
+                    return Err(AsmError::UnknownArgument(argument.location, name.clone()));
+                }
+                None => positional.push_back(argument),
+            }
+        }
+        Ok(Args {
+            location: self.location,
+            positional,
+            named,
+        })
+    }
+
+    pub fn block(&self) -> AsmResult<&[Line]> {
+        self.block
+            .as_deref()
+            .ok_or(AsmError::MissingBlock(self.location))
+    }
+
+    pub fn no_block(&self) -> AsmResult<()> {
+        match self.block {
+            Some(_) => Err(AsmError::UnexpectedBlock(self.location)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct Args<'a> {
+    location: Location,
+    positional: VecDeque<&'a Argument>,
+    named: Vec<&'a Argument>,
+}
+
+impl<'a> Args<'a> {
+    pub fn next(&mut self) -> AsmResult<&'a Argument> {
+        self.positional
+            .pop_front()
+            .ok_or(AsmError::TooFewArguments(self.location))
+    }
+
+    pub fn next_opt(&mut self) -> Option<&'a Argument> {
+        self.positional.pop_front()
+    }
+
+    /// Takes all of the remaining positional arguments.
+    pub fn rest(&mut self) -> impl Iterator<Item = &'a Argument> {
+        std::mem::take(&mut self.positional).into_iter()
+    }
+
+    pub fn named(&self, name: &str) -> Option<&'a Argument> {
+        self.named
+            .iter()
+            .rev()
+            .find(|argument| argument.name.as_deref() == Some(name))
+            .copied()
+    }
+
+    pub fn finish(self) -> AsmResult<()> {
+        match self.positional.front() {
+            Some(argument) => Err(AsmError::TooManyArguments(argument.location)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Argument {
+    pub fn int<T: TryFrom<i64>>(&self) -> AsmResult<T> {
+        self.value.int(self.location)
+    }
+
+    pub fn float(&self) -> AsmResult<f64> {
+        self.value.float(self.location)
+    }
+
+    pub fn string(&self) -> AsmResult<&str> {
+        self.value.string(self.location)
+    }
+
+    pub fn identifier(&self) -> AsmResult<&str> {
+        self.value.identifier(self.location)
+    }
+
+    pub fn bool(&self) -> AsmResult<bool> {
+        self.value.bool(self.location)
+    }
+
+    pub fn color(&self) -> AsmResult<Color> {
+        self.value.color(self.location)
+    }
+}
+
+impl Value {
+    pub fn int<T: TryFrom<i64>>(&self, location: Location) -> AsmResult<T> {
+        let value = match *self {
+            Value::Integer(value) => value,
+            Value::Hex { value, .. } => i64::try_from(value)
+                .map_err(|_| AsmError::OutOfRange(location, value.to_string()))?,
+            _ => return Err(AsmError::WrongArgument(location, "an integer")),
+        };
+        T::try_from(value).map_err(|_| AsmError::OutOfRange(location, value.to_string()))
+    }
+
+    pub fn float(&self, location: Location) -> AsmResult<f64> {
+        match *self {
+            Value::Integer(value) => Ok(value as f64),
+            Value::Number(value) => Ok(value),
+            _ => Err(AsmError::WrongArgument(location, "a number")),
+        }
+    }
+
+    pub fn string(&self, location: Location) -> AsmResult<&str> {
+        match self {
+            Value::String(value) => Ok(value),
+            _ => Err(AsmError::WrongArgument(location, "a string")),
+        }
+    }
+
+    pub fn identifier(&self, location: Location) -> AsmResult<&str> {
+        match self {
+            Value::Identifier(value) => Ok(value),
+            _ => Err(AsmError::WrongArgument(location, "an identifier")),
+        }
+    }
+
+    pub fn bool(&self, location: Location) -> AsmResult<bool> {
+        match self.identifier(location) {
+            Ok("true") => Ok(true),
+            Ok("false") => Ok(false),
+            _ => Err(AsmError::WrongArgument(location, "true or false")),
+        }
+    }
+
+    /// Colors are written in hex, either as `0xRRGGBB` or `0xAARRGGBB`.
+    pub fn color(&self, location: Location) -> AsmResult<Color> {
+        match *self {
+            Value::Hex { value, digits: 6 } => Ok(Color::from_rgb(value as u32, 255)),
+            Value::Hex { value, digits: 8 } => Ok(Color::from_rgba(value as u32)),
+            _ => Err(AsmError::WrongArgument(location, "a color")),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{assemble_abc, assemble_avm1, assemble_swf, AsmError};
+use swf::avm1::read::Reader as Avm1Reader;
+use swf::avm1::types::{Action, Jump, Push, Value as Avm1Value};
+use swf::avm2::read::Reader as Avm2Reader;
+use swf::avm2::types::{Multiname, Namespace, Op, TraitKind};
+use swf::extensions::ReadSwfExt;
+use swf::{ShapeRecord, Tag, Twips};
+
+fn read_actions(data: &[u8]) -> Vec<Action<'_>> {
+    let mut reader = Avm1Reader::new(data, 10);
+    let mut actions = vec![];
+    while !reader.get_ref().is_empty() {
+        actions.push(reader.read_action().unwrap());
+    }
+    actions
+}
+
+fn read_ops(code: &[u8]) -> Vec<Op> {
+    let mut reader = Avm2Reader::new(code);
+    let mut ops = vec![];
+    while !reader.as_slice().is_empty() {
+        ops.push(reader.read_op().unwrap());
+    }
+    ops
+}
+
+#[test]
+fn avm1_branches_to_labels() {
+    let data = assemble_avm1(
+        r#"
+        start:
+          Push "a", 1, 2.5, true, register(3)
+          Pop
+          Jump start
+        "#,
+        10,
+    )
+    .unwrap();
+    let actions = read_actions(&data);
+    assert_eq!(actions.len(), 3);
+    let Action::Push(Push { values }) = &actions[0] else {
+        panic!("Expected Push, got {:?}", actions[0]);
+    };
+    assert_eq!(values.len(), 5);
+    assert!(matches!(values[0], Avm1Value::Str(s) if s == "a"));
+    assert!(matches!(values[1], Avm1Value::Int(1)));
+    assert!(matches!(values[4], Avm1Value::Register(3)));
+    // The jump goes back over itself, the Pop and the Push.
+    assert_eq!(
+        actions[2],
+        Action::Jump(Jump {
+            offset: -(data.len() as i16)
+        })
+    );
+}
+
+#[test]
+fn avm1_reports_unknown_labels() {
+    let error = assemble_avm1("If nowhere\n", 10).unwrap_err();
+    assert!(matches!(error, AsmError::UnknownLabel(_, label) if label == "nowhere"));
+}
+
+#[test]
+fn abc_resolves_references_and_multinames() {
+    let abc = assemble_abc(
+        r#"
+        script init=main {
+          class "Test", test
+          slot "flash.display::count", "int", id=1, value=5
+        }
+        method main {
+          code {
+            GetLocal 0
+            PushScope
+            GetScopeObject 0
+            FindPropStrict "flash.display::Sprite"
+            GetProperty "flash.display::Sprite"
+            PushScope
+            NewClass test
+            PopScope
+            InitProperty "Test"
+            ReturnVoid
+          }
+        }
+        class test, "Test", extends="flash.display::Sprite", init=ctor, class_init=cinit, sealed
+        method ctor {
+          code {
+            GetLocal 0
+            ConstructSuper 0
+            ReturnVoid
+          }
+        }
+        method cinit {
+          code {
+            ReturnVoid
+          }
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(abc.methods.len(), 3);
+    assert_eq!(abc.instances.len(), 1);
+    assert!(abc.instances[0].is_sealed);
+    assert_eq!(abc.instances[0].init_method.0, 1);
+    assert_eq!(abc.classes[0].init_method.0, 2);
+
+    let pool = &abc.constant_pool;
+    let sprite = &pool.multinames[abc.instances[0].super_name.0 as usize - 1];
+    let Multiname::QName { namespace, name } = sprite else {
+        panic!("Expected a QName, got {sprite:?}");
+    };
+    assert_eq!(pool.strings[name.0 as usize - 1], b"Sprite");
+    assert!(matches!(
+        pool.namespaces[namespace.0 as usize - 1],
+        Namespace::Package(uri) if pool.strings[uri.0 as usize - 1] == b"flash.display"
+    ));
+    // Both uses of the same multiname share a single constant pool entry.
+    assert_eq!(pool.multinames.iter().filter(|m| *m == sprite).count(), 1);
+
+    let traits = &abc.scripts[0].traits;
+    assert!(matches!(traits[0].kind, TraitKind::Class { class, .. } if class.0 == 0));
+    assert!(matches!(traits[1].kind, TraitKind::Slot { slot_id: 1, .. }));
+
+    let body = &abc.method_bodies[0];
+    assert_eq!(body.max_scope_depth, 2);
+    assert_eq!(body.num_locals, 1);
+    let ops = read_ops(&body.code);
+    assert_eq!(ops.len(), 10);
+    assert!(matches!(ops[6], Op::NewClass { index } if index.0 == 0));
+}
+
+#[test]
+fn abc_branches_and_exceptions_use_label_offsets() {
+    let abc = assemble_abc(
+        r#"
+        method main {
+          catch start, end, handler, type="Error"
+          code {
+          start:
+            PushTrue
+            IfFalse end
+            Jump start
+          end:
+            ReturnVoid
+          handler:
+            Pop
+            ReturnVoid
+          }
+        }
+        "#,
+    )
+    .unwrap();
+    let body = &abc.method_bodies[0];
+    let ops = read_ops(&body.code);
+    // PushTrue is 1 byte, and each branch is 4 bytes long.
+    assert_eq!(ops[1], Op::IfFalse { offset: 4 });
+    assert_eq!(ops[2], Op::Jump { offset: -9 });
+    let exception = &body.exceptions[0];
+    assert_eq!(exception.from_offset, 0);
+    assert_eq!(exception.to_offset, 9);
+    assert_eq!(exception.target_offset, 10);
+}
+
+#[test]
+fn abc_reports_unknown_references() {
+    let error = assemble_abc("script init=missing\n").unwrap_err();
+    assert!(matches!(error, AsmError::UnknownReference(_, name) if name == "missing"));
+}
+
+#[test]
+fn swf_round_trips_through_the_parser() {
+    let mut data = vec![];
+    assemble_swf(
+        r#"
+        version 8
+        size 200, 100
+        frame_rate 30
+        compression none
+        background 0x336699
+        shape 1 {
+          fill 0xFF0000
+          line 2, 0x000000
+          rect 10, 10, 50, 20
+        }
+        sprite 2 {
+          place 1, 1
+          show
+          show
+        }
+        place 2, 1, x=20, y=30, name="clip" {
+          on ENTER_FRAME {
+            Stop
+          }
+        }
+        do_action {
+          Push "hi"
+          Trace
+        }
+        label "first"
+        show
+        remove 1
+        show
+        "#,
+        &mut data,
+    )
+    .unwrap();
+
+    let swf_buf = swf::decompress_swf(&data[..]).unwrap();
+    let swf = swf::parse_swf(&swf_buf).unwrap();
+    assert_eq!(swf.header.version(), 8);
+    assert_eq!(swf.header.num_frames(), 2);
+    assert_eq!(swf.header.stage_size().width().to_pixels(), 200.0);
+
+    let Tag::DefineShape(shape) = &swf.tags[1] else {
+        panic!("Expected DefineShape, got {:?}", swf.tags[1]);
+    };
+    // The bounds include half of the line width.
+    assert_eq!(shape.shape_bounds.x_min, Twips::from_pixels(9.0));
+    assert_eq!(shape.shape_bounds.x_max, Twips::from_pixels(61.0));
+    let edges = shape
+        .shape
+        .iter()
+        .filter(|record| matches!(record, ShapeRecord::StraightEdge { .. }))
+        .count();
+    assert_eq!(edges, 4);
+
+    let Tag::DefineSprite(sprite) = &swf.tags[2] else {
+        panic!("Expected DefineSprite, got {:?}", swf.tags[2]);
+    };
+    assert_eq!(sprite.num_frames, 2);
+
+    let Tag::PlaceObject(place) = &swf.tags[3] else {
+        panic!("Expected PlaceObject, got {:?}", swf.tags[3]);
+    };
+    assert_eq!(place.name.map(|name| name.as_bytes()), Some(&b"clip"[..]));
+    assert_eq!(place.matrix.unwrap().ty, Twips::from_pixels(30.0));
+    let clip_actions = place.clip_actions.as_ref().unwrap();
+    assert_eq!(
+        read_actions(clip_actions[0].action_data),
+        vec![Action::Stop]
+    );
+}
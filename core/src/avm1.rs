@@ -7,10 +7,12 @@ mod function;
 #[macro_use]
 mod property_decl;
 
+mod action_cache;
 mod activation;
 mod callable_value;
 mod clamp;
 mod debug;
+pub mod trace_recorder;
 mod error;
 mod flv;
 mod fscommand;
@@ -0,0 +1,287 @@
+//! Pre-decoded AVM1 code.
+//!
+//! Reading an action from the SWF data means parsing its header and operands
+//! every time it runs, which adds up in loops and frequently called functions.
+//! Each block of code is therefore decoded once, the first time it runs, into
+//! a list of actions with their operands ready to use.
+//!
+//! The decoded actions are only a shortcut for reading the SWF data. They are
+//! decoded one after the other from the start of the block, and any position
+//! that isn't the start of one of them, such as a jump into the middle of an
+//! action or outside of the block, is run by reading the SWF data as before.
+
+use crate::avm1::{Activation, Value};
+use crate::string::AvmString;
+use crate::tag_utils::{SwfMovie, SwfSlice};
+use fnv::FnvHashMap;
+use gc_arena::{Collect, Gc};
+use std::sync::{Arc, Weak};
+use swf::avm1::read::Reader;
+use swf::avm1::types::{Action, ConstantPool, Value as SwfValue};
+use swf::extensions::ReadSwfExt;
+
+/// Every opcode of an action without operands, which is all opcodes below
+/// `0x80`. These actions are read from here rather than from the SWF data, so
+/// that they can be kept without borrowing from the movie.
+static OPCODES: [u8; 0x80] = {
+    let mut opcodes = [0; 0x80];
+    let mut i = 0;
+    while i < opcodes.len() {
+        opcodes[i] = i as u8;
+        i += 1;
+    }
+    opcodes
+};
+
+/// Decodes the strings of an `ActionConstantPool`.
+pub fn decode_constant_pool<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    action: &ConstantPool,
+) -> Gc<'gc, Vec<Value<'gc>>> {
+    let encoding = activation.encoding();
+    let constants = action
+        .strings
+        .iter()
+        .map(|s| activation.strings().intern_wstr(s.decode(encoding)).into())
+        .collect();
+    Gc::new(activation.gc(), constants)
+}
+
+/// A value pushed by `ActionPush`.
+#[derive(Clone, Collect, Copy, Debug)]
+#[collect(no_drop)]
+pub enum PushValue<'gc> {
+    Value(Value<'gc>),
+
+    /// Registers are read when the action runs.
+    Register(u8),
+
+    /// The constant pool can be redefined at any time, so the index is only
+    /// looked up when the action runs.
+    ConstantPool(u16),
+}
+
+impl<'gc> PushValue<'gc> {
+    pub fn new(activation: &Activation<'_, 'gc>, value: &SwfValue) -> Self {
+        match *value {
+            SwfValue::Undefined => Self::Value(Value::Undefined),
+            SwfValue::Null => Self::Value(Value::Null),
+            SwfValue::Bool(v) => Self::Value(v.into()),
+            SwfValue::Int(v) => Self::Value(v.into()),
+            SwfValue::Float(v) => Self::Value(v.into()),
+            SwfValue::Double(v) => Self::Value(v.into()),
+            SwfValue::Str(v) => {
+                Self::Value(AvmString::new(activation.gc(), v.decode(activation.encoding())).into())
+            }
+            SwfValue::Register(v) => Self::Register(v),
+            SwfValue::ConstantPool(i) => Self::ConstantPool(i),
+        }
+    }
+}
+
+/// A position in the movie to run an action from.
+#[derive(Clone, Collect, Copy, Debug)]
+#[collect(require_static)]
+pub struct Target {
+    /// The position in the movie data.
+    pub position: usize,
+
+    /// The index of the decoded action starting at `position`, if there is one.
+    pub index: Option<usize>,
+}
+
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub enum DecodedOp<'gc> {
+    /// An action without operands.
+    Simple(#[collect(require_static)] Action<'static>),
+
+    Push(Box<[PushValue<'gc>]>),
+
+    /// The constant pool is decoded ahead of time, but is still made the
+    /// current one every time the action runs.
+    ConstantPool(Gc<'gc, Vec<Value<'gc>>>),
+
+    StoreRegister(u8),
+
+    Jump(Target),
+
+    If(Target),
+
+    /// Any other action, which is read from the SWF data again whenever it
+    /// runs. These either refer to the SWF data, such as the body of
+    /// `ActionDefineFunction`, or aren't run often enough to be worth it.
+    Read,
+}
+
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub struct DecodedAction<'gc> {
+    /// The position of the action in the movie data.
+    pub offset: usize,
+
+    /// The position of the action following this one.
+    pub next: usize,
+
+    pub op: DecodedOp<'gc>,
+}
+
+/// The actions of a block of code, decoded in order from its start.
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub struct DecodedCode<'gc> {
+    actions: Box<[DecodedAction<'gc>]>,
+}
+
+impl<'gc> DecodedCode<'gc> {
+    pub fn decode(activation: &mut Activation<'_, 'gc>, code: &SwfSlice) -> Gc<'gc, Self> {
+        let data = code.movie.data();
+        let mut reader = Reader::new(&data[code.start..], activation.swf_version());
+        let mut actions = Vec::new();
+
+        // Stop at the first action that can't be read; running it reports the error.
+        while reader.pos(data) < code.end {
+            let offset = reader.pos(data);
+            let Ok(action) = reader.read_action() else {
+                break;
+            };
+            let next = reader.pos(data);
+
+            // Jumps are clamped to the movie, like `Reader::seek` does.
+            let jump_target = |jump_offset: i16| Target {
+                position: ((next as isize + jump_offset as isize) as usize).min(data.len()),
+                index: None,
+            };
+            let op = match action {
+                Action::ConstantPool(action) => {
+                    DecodedOp::ConstantPool(decode_constant_pool(activation, &action))
+                }
+                Action::Push(action) => DecodedOp::Push(
+                    action
+                        .values
+                        .iter()
+                        .map(|value| PushValue::new(activation, value))
+                        .collect(),
+                ),
+                Action::StoreRegister(action) => DecodedOp::StoreRegister(action.register),
+                Action::Jump(action) => DecodedOp::Jump(jump_target(action.offset)),
+                Action::If(action) => DecodedOp::If(jump_target(action.offset)),
+                _ if data[offset] < 0x80 => {
+                    let opcode = usize::from(data[offset]);
+                    match Reader::new(&OPCODES[opcode..], activation.swf_version()).read_action() {
+                        Ok(action) => DecodedOp::Simple(action),
+                        Err(_) => DecodedOp::Read,
+                    }
+                }
+                _ => DecodedOp::Read,
+            };
+            actions.push(DecodedAction { offset, next, op });
+        }
+
+        let offsets: Vec<usize> = actions.iter().map(|action| action.offset).collect();
+        for action in &mut actions {
+            if let DecodedOp::Jump(target) | DecodedOp::If(target) = &mut action.op {
+                target.index = offsets.binary_search(&target.position).ok();
+            }
+        }
+
+        Gc::new(
+            activation.gc(),
+            Self {
+                actions: actions.into_boxed_slice(),
+            },
+        )
+    }
+
+    pub fn action(&self, index: usize) -> &DecodedAction<'gc> {
+        &self.actions[index]
+    }
+
+    /// Finds the decoded action starting at `position` in the movie data.
+    fn index_of(&self, position: usize) -> Option<usize> {
+        self.actions
+            .binary_search_by_key(&position, |action| action.offset)
+            .ok()
+    }
+
+    pub fn target(&self, position: usize) -> Target {
+        Target {
+            position,
+            index: self.index_of(position),
+        }
+    }
+
+    /// The action following the one at `index`.
+    pub fn next(&self, index: usize) -> Target {
+        Target {
+            position: self.actions[index].next,
+            index: Some(index + 1).filter(|&next| next < self.actions.len()),
+        }
+    }
+}
+
+/// Identifies a block of code by the movie it belongs to and its bounds.
+#[derive(Clone, Collect, Copy, Debug, PartialEq, Eq, Hash)]
+#[collect(require_static)]
+struct CodeKey {
+    /// The address of the movie. This can't be reused by another movie for
+    /// as long as the cache holds a `Weak` to it.
+    movie: usize,
+    start: usize,
+    end: usize,
+    swf_version: u8,
+}
+
+impl CodeKey {
+    fn new(code: &SwfSlice, swf_version: u8) -> Self {
+        Self {
+            movie: Arc::as_ptr(&code.movie) as usize,
+            start: code.start,
+            end: code.end,
+            swf_version,
+        }
+    }
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct CacheEntry<'gc> {
+    #[collect(require_static)]
+    movie: Weak<SwfMovie>,
+
+    code: Gc<'gc, DecodedCode<'gc>>,
+}
+
+/// The decoded form of every block of code that has run.
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct ActionCache<'gc> {
+    entries: FnvHashMap<CodeKey, CacheEntry<'gc>>,
+
+    /// The number of entries at which those of unloaded movies are removed.
+    prune_at: usize,
+}
+
+impl<'gc> ActionCache<'gc> {
+    pub fn get(&self, code: &SwfSlice, swf_version: u8) -> Option<Gc<'gc, DecodedCode<'gc>>> {
+        self.entries
+            .get(&CodeKey::new(code, swf_version))
+            .map(|entry| entry.code)
+    }
+
+    pub fn insert(&mut self, code: &SwfSlice, swf_version: u8, decoded: Gc<'gc, DecodedCode<'gc>>) {
+        if self.entries.len() >= self.prune_at {
+            self.entries
+                .retain(|_, entry| entry.movie.strong_count() > 0);
+            self.prune_at = (self.entries.len() * 2).max(64);
+        }
+
+        self.entries.insert(
+            CodeKey::new(code, swf_version),
+            CacheEntry {
+                movie: Arc::downgrade(&code.movie),
+                code: decoded,
+            },
+        );
+    }
+}
@@ -1,9 +1,11 @@
+use crate::avm1::action_cache::{decode_constant_pool, DecodedCode, DecodedOp, PushValue, Target};
 use crate::avm1::callable_value::CallableValue;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Avm1Function, ExecutionReason, FunctionObject};
 use crate::avm1::property::Attribute;
 use crate::avm1::runtime::skip_actions;
 use crate::avm1::scope::{Scope, ScopeClass};
+use crate::avm1::trace_recorder::ActionRecord;
 use crate::avm1::{fscommand, globals, scope, ArrayBuilder, Object, Value};
 use crate::backend::navigator::{NavigationMethod, Request};
 use crate::context::UpdateContext;
@@ -27,6 +29,7 @@ use std::fmt;
 use std::rc::Rc;
 use swf::avm1::read::Reader;
 use swf::avm1::types::*;
+use swf::extensions::ReadSwfExt;
 use url::form_urlencoded;
 use web_time::Instant;
 
@@ -359,6 +362,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
             clip_obj.into(),
             None,
         );
+        child_activation.enter_timeline_code();
         child_activation.run_actions(code)
     }
 
@@ -399,8 +403,42 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         function(&mut activation)
     }
 
+    /// Let the trace recorder know that timeline code is about to run on the base clip.
+    pub fn enter_timeline_code(&mut self) {
+        if !self.context.avm1.trace_recorder().breakpoints().is_empty() {
+            if let Some(clip) = self.base_clip.as_movie_clip() {
+                let frame = clip.current_frame();
+                self.context
+                    .avm1
+                    .trace_recorder_mut()
+                    .enter_timeline_code(&clip.path(), frame);
+            }
+        }
+    }
+
     pub fn run_actions(&mut self, code: SwfSlice) -> Result<ReturnType<'gc>, Error<'gc>> {
-        let mut read = Reader::new(&code.movie.data()[code.start..], self.swf_version());
+        let decoded = self.decoded_code(&code);
+
+        // Run the decoded actions for as long as the code stays on them.
+        let mut target = decoded.target(code.start);
+        while let Some(index) = target.index {
+            self.check_timeout()?;
+            if self.context.avm1.trace_recorder().is_active() {
+                self.trace_hook(&code, target.position);
+            }
+
+            match self.do_decoded_action(&code, &decoded, index)? {
+                (FrameControl::Return(return_type), _) => return Ok(return_type),
+                (FrameControl::Continue, next) => target = next,
+            }
+
+            if target.position >= code.end {
+                //Executing beyond the end of a function constitutes an implicit return.
+                return Ok(ReturnType::Implicit);
+            }
+        }
+
+        let mut read = Reader::new(&code.movie.data()[target.position..], self.swf_version());
 
         loop {
             let result = self.do_action(&code, &mut read);
@@ -412,12 +450,22 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         }
     }
 
-    /// Run a single action from a given action reader.
-    fn do_action<'b>(
-        &mut self,
-        data: &'b SwfSlice,
-        reader: &mut Reader<'b>,
-    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+    /// Returns the decoded form of `code`, decoding it if it hasn't run before.
+    fn decoded_code(&mut self, code: &SwfSlice) -> Gc<'gc, DecodedCode<'gc>> {
+        let swf_version = self.swf_version();
+        if let Some(decoded) = self.context.avm1.action_cache().get(code, swf_version) {
+            return decoded;
+        }
+
+        let decoded = DecodedCode::decode(self, code);
+        self.context
+            .avm1
+            .action_cache_mut()
+            .insert(code, swf_version, decoded);
+        decoded
+    }
+
+    fn check_timeout(&mut self) -> Result<(), Error<'gc>> {
         *self.context.actions_since_timeout_check += 1;
         if *self.context.actions_since_timeout_check >= 2000 {
             *self.context.actions_since_timeout_check = 0;
@@ -425,11 +473,89 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 return Err(Error::ExecutionTimeout);
             }
         }
+        Ok(())
+    }
+
+    /// Run the decoded action at `index`, returning where to continue.
+    fn do_decoded_action(
+        &mut self,
+        code: &SwfSlice,
+        decoded: &DecodedCode<'gc>,
+        index: usize,
+    ) -> Result<(FrameControl<'gc>, Target), Error<'gc>> {
+        let action = decoded.action(index);
+        let data = code.movie.data();
+        if !matches!(action.op, DecodedOp::Read) {
+            avm_debug!(
+                self.context.avm1,
+                "({}) Action: {:?}",
+                self.id.depth(),
+                action.op
+            );
+        }
+
+        let (control, position) = match &action.op {
+            DecodedOp::Simple(simple) => {
+                let mut reader = Reader::new(&data[action.next..], self.swf_version());
+                let control = self.run_action(simple.clone(), code, &mut reader)?;
+                (control, reader.pos(data))
+            }
+            DecodedOp::Push(values) => {
+                for &value in values.iter() {
+                    self.push_value(value);
+                }
+                (FrameControl::Continue, action.next)
+            }
+            DecodedOp::ConstantPool(constants) => {
+                self.set_constant_pools(*constants);
+                (FrameControl::Continue, action.next)
+            }
+            &DecodedOp::StoreRegister(register) => (
+                self.action_store_register(StoreRegister { register })?,
+                action.next,
+            ),
+            &DecodedOp::Jump(target) => return Ok((FrameControl::Continue, target)),
+            &DecodedOp::If(target) => {
+                let val = self.context.avm1.pop();
+                if val.as_bool(self.swf_version()) {
+                    return Ok((FrameControl::Continue, target));
+                }
+                (FrameControl::Continue, action.next)
+            }
+            DecodedOp::Read => {
+                let mut reader = Reader::new(&data[action.offset..], self.swf_version());
+                let read = reader.read_action()?;
+                avm_debug!(self.context.avm1, "({}) Action: {read:?}", self.id.depth());
+                let control = self.run_action(read, code, &mut reader)?;
+                (control, reader.pos(data))
+            }
+        };
+
+        let next = if position == action.next {
+            decoded.next(index)
+        } else {
+            // The action moved the reader, like `ActionWaitForFrame` skipping actions.
+            decoded.target(position)
+        };
+        Ok((control, next))
+    }
+
+    /// Run a single action from a given action reader.
+    fn do_action<'b>(
+        &mut self,
+        data: &'b SwfSlice,
+        reader: &mut Reader<'b>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        self.check_timeout()?;
 
         if reader.get_ref().as_ptr() as usize >= data.as_ref().as_ptr_range().end as usize {
             //Executing beyond the end of a function constitutes an implicit return.
             Ok(FrameControl::Return(ReturnType::Implicit))
         } else {
+            if self.context.avm1.trace_recorder().is_active() {
+                self.trace_hook(data, reader.pos(data.movie.data()));
+            }
+
             let action = reader.read_action()?;
             avm_debug!(
                 self.context.avm1,
@@ -437,112 +563,151 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 self.id.depth(),
             );
 
-            match action {
-                Action::Add => self.action_add(),
-                Action::Add2 => self.action_add_2(),
-                Action::And => self.action_and(),
-                Action::AsciiToChar => self.action_ascii_to_char(),
-                Action::BitAnd => self.action_bit_and(),
-                Action::BitLShift => self.action_bit_lshift(),
-                Action::BitOr => self.action_bit_or(),
-                Action::BitRShift => self.action_bit_rshift(),
-                Action::BitURShift => self.action_bit_urshift(),
-                Action::BitXor => self.action_bit_xor(),
-                Action::Call => self.action_call(),
-                Action::CallFunction => self.action_call_function(),
-                Action::CallMethod => self.action_call_method(),
-                Action::CastOp => self.action_cast_op(),
-                Action::CharToAscii => self.action_char_to_ascii(),
-                Action::CloneSprite => self.action_clone_sprite(),
-                Action::ConstantPool(action) => self.action_constant_pool(action),
-                Action::Decrement => self.action_decrement(),
-                Action::DefineFunction(action) => self.action_define_function(action.into(), data),
-                Action::DefineFunction2(action) => self.action_define_function(action, data),
-                Action::DefineLocal => self.action_define_local(),
-                Action::DefineLocal2 => self.action_define_local_2(),
-                Action::Delete => self.action_delete(),
-                Action::Delete2 => self.action_delete_2(),
-                Action::Divide => self.action_divide(),
-                Action::End => self.action_end(),
-                Action::EndDrag => self.action_end_drag(),
-                Action::Enumerate => self.action_enumerate(),
-                Action::Enumerate2 => self.action_enumerate_2(),
-                Action::Equals => self.action_equals(),
-                Action::Equals2 => self.action_equals_2(),
-                Action::Extends => self.action_extends(),
-                Action::GetMember => self.action_get_member(),
-                Action::GetProperty => self.action_get_property(),
-                Action::GetTime => self.action_get_time(),
-                Action::GetVariable => self.action_get_variable(),
-                Action::GetUrl(action) => self.action_get_url(action),
-                Action::GetUrl2(action) => self.action_get_url_2(action),
-                Action::GotoFrame(action) => self.action_goto_frame(action),
-                Action::GotoFrame2(action) => self.action_goto_frame_2(action),
-                Action::Greater => self.action_greater(),
-                Action::GotoLabel(action) => self.action_goto_label(action),
-                Action::If(action) => self.action_if(action, reader, data),
-                Action::Increment => self.action_increment(),
-                Action::InitArray => self.action_init_array(),
-                Action::InitObject => self.action_init_object(),
-                Action::ImplementsOp => self.action_implements_op(),
-                Action::InstanceOf => self.action_instance_of(),
-                Action::Jump(action) => self.action_jump(action, reader, data),
-                Action::Less => self.action_less(),
-                Action::Less2 => self.action_less_2(),
-                Action::MBAsciiToChar => self.action_mb_ascii_to_char(),
-                Action::MBCharToAscii => self.action_mb_char_to_ascii(),
-                Action::MBStringLength => self.action_mb_string_length(),
-                Action::MBStringExtract => self.action_mb_string_extract(),
-                Action::Modulo => self.action_modulo(),
-                Action::Multiply => self.action_multiply(),
-                Action::NextFrame => self.action_next_frame(),
-                Action::NewMethod => self.action_new_method(),
-                Action::NewObject => self.action_new_object(),
-                Action::Not => self.action_not(),
-                Action::Or => self.action_or(),
-                Action::Play => self.action_play(),
-                Action::Pop => self.action_pop(),
-                Action::PreviousFrame => self.action_prev_frame(),
-                Action::Push(action) => self.action_push(action),
-                Action::PushDuplicate => self.action_push_duplicate(),
-                Action::RandomNumber => self.action_random_number(),
-                Action::RemoveSprite => self.action_remove_sprite(),
-                Action::Return => self.action_return(),
-                Action::SetMember => self.action_set_member(),
-                Action::SetProperty => self.action_set_property(),
-                Action::SetTarget(action) => self.action_set_target(action),
-                Action::SetTarget2 => self.action_set_target_2(),
-                Action::SetVariable => self.action_set_variable(),
-                Action::StackSwap => self.action_stack_swap(),
-                Action::StartDrag => self.action_start_drag(),
-                Action::Stop => self.action_stop(),
-                Action::StopSounds => self.action_stop_sounds(),
-                Action::StoreRegister(action) => self.action_store_register(action),
-                Action::StrictEquals => self.action_strict_equals(),
-                Action::StringAdd => self.action_string_add(),
-                Action::StringEquals => self.action_string_equals(),
-                Action::StringExtract => self.action_string_extract(),
-                Action::StringGreater => self.action_string_greater(),
-                Action::StringLength => self.action_string_length(),
-                Action::StringLess => self.action_string_less(),
-                Action::Subtract => self.action_subtract(),
-                Action::TargetPath => self.action_target_path(),
-                Action::Throw => self.action_throw(),
-                Action::ToggleQuality => self.action_toggle_quality(),
-                Action::ToInteger => self.action_to_integer(),
-                Action::ToNumber => self.action_to_number(),
-                Action::ToString => self.action_to_string(),
-                Action::Trace => self.action_trace(),
-                Action::Try(action) => self.action_try(&action, data),
-                Action::TypeOf => self.action_type_of(),
-                Action::WaitForFrame(action) => self.action_wait_for_frame(action, reader),
-                Action::WaitForFrame2(action) => self.action_wait_for_frame_2(action, reader),
-                Action::With(action) => self.action_with(action, data),
-                Action::Unknown(action) => self.action_unknown(action),
-            }
+            self.run_action(action, data, reader)
         }
     }
 
+    fn run_action<'b>(
+        &mut self,
+        action: Action<'b>,
+        data: &'b SwfSlice,
+        reader: &mut Reader<'b>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        match action {
+            Action::Add => self.action_add(),
+            Action::Add2 => self.action_add_2(),
+            Action::And => self.action_and(),
+            Action::AsciiToChar => self.action_ascii_to_char(),
+            Action::BitAnd => self.action_bit_and(),
+            Action::BitLShift => self.action_bit_lshift(),
+            Action::BitOr => self.action_bit_or(),
+            Action::BitRShift => self.action_bit_rshift(),
+            Action::BitURShift => self.action_bit_urshift(),
+            Action::BitXor => self.action_bit_xor(),
+            Action::Call => self.action_call(),
+            Action::CallFunction => self.action_call_function(),
+            Action::CallMethod => self.action_call_method(),
+            Action::CastOp => self.action_cast_op(),
+            Action::CharToAscii => self.action_char_to_ascii(),
+            Action::CloneSprite => self.action_clone_sprite(),
+            Action::ConstantPool(action) => self.action_constant_pool(action),
+            Action::Decrement => self.action_decrement(),
+            Action::DefineFunction(action) => self.action_define_function(action.into(), data),
+            Action::DefineFunction2(action) => self.action_define_function(action, data),
+            Action::DefineLocal => self.action_define_local(),
+            Action::DefineLocal2 => self.action_define_local_2(),
+            Action::Delete => self.action_delete(),
+            Action::Delete2 => self.action_delete_2(),
+            Action::Divide => self.action_divide(),
+            Action::End => self.action_end(),
+            Action::EndDrag => self.action_end_drag(),
+            Action::Enumerate => self.action_enumerate(),
+            Action::Enumerate2 => self.action_enumerate_2(),
+            Action::Equals => self.action_equals(),
+            Action::Equals2 => self.action_equals_2(),
+            Action::Extends => self.action_extends(),
+            Action::GetMember => self.action_get_member(),
+            Action::GetProperty => self.action_get_property(),
+            Action::GetTime => self.action_get_time(),
+            Action::GetVariable => self.action_get_variable(),
+            Action::GetUrl(action) => self.action_get_url(action),
+            Action::GetUrl2(action) => self.action_get_url_2(action),
+            Action::GotoFrame(action) => self.action_goto_frame(action),
+            Action::GotoFrame2(action) => self.action_goto_frame_2(action),
+            Action::Greater => self.action_greater(),
+            Action::GotoLabel(action) => self.action_goto_label(action),
+            Action::If(action) => self.action_if(action, reader, data),
+            Action::Increment => self.action_increment(),
+            Action::InitArray => self.action_init_array(),
+            Action::InitObject => self.action_init_object(),
+            Action::ImplementsOp => self.action_implements_op(),
+            Action::InstanceOf => self.action_instance_of(),
+            Action::Jump(action) => self.action_jump(action, reader, data),
+            Action::Less => self.action_less(),
+            Action::Less2 => self.action_less_2(),
+            Action::MBAsciiToChar => self.action_mb_ascii_to_char(),
+            Action::MBCharToAscii => self.action_mb_char_to_ascii(),
+            Action::MBStringLength => self.action_mb_string_length(),
+            Action::MBStringExtract => self.action_mb_string_extract(),
+            Action::Modulo => self.action_modulo(),
+            Action::Multiply => self.action_multiply(),
+            Action::NextFrame => self.action_next_frame(),
+            Action::NewMethod => self.action_new_method(),
+            Action::NewObject => self.action_new_object(),
+            Action::Not => self.action_not(),
+            Action::Or => self.action_or(),
+            Action::Play => self.action_play(),
+            Action::Pop => self.action_pop(),
+            Action::PreviousFrame => self.action_prev_frame(),
+            Action::Push(action) => self.action_push(action),
+            Action::PushDuplicate => self.action_push_duplicate(),
+            Action::RandomNumber => self.action_random_number(),
+            Action::RemoveSprite => self.action_remove_sprite(),
+            Action::Return => self.action_return(),
+            Action::SetMember => self.action_set_member(),
+            Action::SetProperty => self.action_set_property(),
+            Action::SetTarget(action) => self.action_set_target(action),
+            Action::SetTarget2 => self.action_set_target_2(),
+            Action::SetVariable => self.action_set_variable(),
+            Action::StackSwap => self.action_stack_swap(),
+            Action::StartDrag => self.action_start_drag(),
+            Action::Stop => self.action_stop(),
+            Action::StopSounds => self.action_stop_sounds(),
+            Action::StoreRegister(action) => self.action_store_register(action),
+            Action::StrictEquals => self.action_strict_equals(),
+            Action::StringAdd => self.action_string_add(),
+            Action::StringEquals => self.action_string_equals(),
+            Action::StringExtract => self.action_string_extract(),
+            Action::StringGreater => self.action_string_greater(),
+            Action::StringLength => self.action_string_length(),
+            Action::StringLess => self.action_string_less(),
+            Action::Subtract => self.action_subtract(),
+            Action::TargetPath => self.action_target_path(),
+            Action::Throw => self.action_throw(),
+            Action::ToggleQuality => self.action_toggle_quality(),
+            Action::ToInteger => self.action_to_integer(),
+            Action::ToNumber => self.action_to_number(),
+            Action::ToString => self.action_to_string(),
+            Action::Trace => self.action_trace(),
+            Action::Try(action) => self.action_try(&action, data),
+            Action::TypeOf => self.action_type_of(),
+            Action::WaitForFrame(action) => self.action_wait_for_frame(action, reader),
+            Action::WaitForFrame2(action) => self.action_wait_for_frame_2(action, reader),
+            Action::With(action) => self.action_with(action, data),
+            Action::Unknown(action) => self.action_unknown(action),
+        }
+    }
+
+    /// Let the trace recorder start a trace at the action at `offset` in the
+    /// movie, and record the state of the AVM while it is tracing.
+    #[cold]
+    fn trace_hook(&mut self, data: &SwfSlice, offset: usize) {
+        let recorder = self.context.avm1.trace_recorder_mut();
+        let reason = recorder.check_action(data.movie.url(), offset);
+        if reason.is_none() && !recorder.is_recording() {
+            return;
+        }
+
+        let (registers, local_registers) = match &self.local_registers {
+            Some(registers) => (registers.iter().map(Cell::get).collect(), true),
+            None => ((0..4).map(|id| self.current_register(id)).collect(), false),
+        };
+        let record = ActionRecord {
+            code: data.clone(),
+            offset,
+            depth: self.id.depth(),
+            activation: self.id.to_string(),
+            reason,
+            clip: self.base_clip,
+            this: self.this,
+            scope: self.scope,
+            registers,
+            local_registers,
+            stack: self.context.avm1.stack().to_vec(),
+        };
+        self.context.avm1.trace_recorder_mut().record(record);
+    }
+
     fn stack_push(&mut self, mut value: Value<'gc>) {
         if let Value::Object(obj) = value {
             // Note that there currently exists a subtle issue with this logic:
@@ -840,18 +1005,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         &mut self,
         action: ConstantPool,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        let encoding = self.encoding();
-        let constants = action
-            .strings
-            .iter()
-            .map(|s| self.strings().intern_wstr(s.decode(encoding)).into())
-            .collect();
-
-        self.context
-            .avm1
-            .set_constant_pool(Gc::new(self.gc(), constants));
-        self.set_constant_pool(self.context.avm1.constant_pool());
-
+        let constants = decode_constant_pool(self, &action);
+        self.set_constant_pools(constants);
         Ok(FrameControl::Continue)
     }
 
@@ -1788,36 +1943,34 @@ impl<'a, 'gc> Activation<'a, 'gc> {
     }
 
     fn action_push(&mut self, action: Push) -> Result<FrameControl<'gc>, Error<'gc>> {
-        for value in action.values {
-            use swf::avm1::types::Value as SwfValue;
-            let value = match value {
-                SwfValue::Undefined => Value::Undefined,
-                SwfValue::Null => Value::Null,
-                SwfValue::Bool(v) => v.into(),
-                SwfValue::Int(v) => v.into(),
-                SwfValue::Float(v) => v.into(),
-                SwfValue::Double(v) => v.into(),
-                SwfValue::Str(v) => AvmString::new(self.gc(), v.decode(self.encoding())).into(),
-                SwfValue::Register(v) => self.current_register(v),
-                SwfValue::ConstantPool(i) => {
-                    if let Some(value) = self.constant_pool().get(i as usize) {
-                        *value
-                    } else {
-                        avm_warn!(
-                            self,
-                            "ActionPush: Constant pool index {} out of range (len = {})",
-                            i,
-                            self.constant_pool().len()
-                        );
-                        Value::Undefined
-                    }
-                }
-            };
-            self.stack_push(value); // Needs to handle MovieClipReferences, in case pushing a register
+        for value in &action.values {
+            let value = PushValue::new(self, value);
+            self.push_value(value);
         }
         Ok(FrameControl::Continue)
     }
 
+    fn push_value(&mut self, value: PushValue<'gc>) {
+        let value = match value {
+            PushValue::Value(value) => value,
+            PushValue::Register(v) => self.current_register(v),
+            PushValue::ConstantPool(i) => {
+                if let Some(value) = self.constant_pool().get(i as usize) {
+                    *value
+                } else {
+                    avm_warn!(
+                        self,
+                        "ActionPush: Constant pool index {} out of range (len = {})",
+                        i,
+                        self.constant_pool().len()
+                    );
+                    Value::Undefined
+                }
+            }
+        };
+        self.stack_push(value); // Needs to handle MovieClipReferences, in case pushing a register
+    }
+
     fn action_push_duplicate(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
         let val = self.context.avm1.pop();
         self.context.avm1.push(val);
@@ -3064,6 +3217,13 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         self.constant_pool = constant_pool;
     }
 
+    /// Makes `constant_pool` the current one, both for this activation and
+    /// for the code that runs after it.
+    fn set_constant_pools(&mut self, constant_pool: Gc<'gc, Vec<Value<'gc>>>) {
+        self.context.avm1.set_constant_pool(constant_pool);
+        self.set_constant_pool(constant_pool);
+    }
+
     /// Checks that the clip executing a script still exists.
     /// If the clip executing a script is removed during execution, return from this activation.
     /// Should be called after any action that could potentially destroy a clip (gotos, etc.)
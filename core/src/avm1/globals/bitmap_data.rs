@@ -16,6 +16,7 @@ use crate::swf::BlendMode;
 use crate::{avm1_stub, avm_error};
 use ruffle_macros::istr;
 use ruffle_render::transform::Transform;
+use swf::Rectangle;
 
 const PROTO_DECLS: &[Declaration] = declare_properties! {
     "height" => property(height);
@@ -686,12 +687,53 @@ fn apply_filter<'gc>(
 fn generate_filter_rect<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let NativeObject::BitmapData(bitmap_data) = this.native() {
         if !bitmap_data.disposed() {
-            avm1_stub!(activation, "BitmapData", "generateFilterRect");
-            return Ok(Value::Undefined);
+            let source_rect = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let x = source_rect
+                .get(istr!("x"), activation)?
+                .coerce_to_f64(activation)? as i32;
+            let y = source_rect
+                .get(istr!("y"), activation)?
+                .coerce_to_f64(activation)? as i32;
+            let width = source_rect
+                .get(istr!("width"), activation)?
+                .coerce_to_f64(activation)? as i32;
+            let height = source_rect
+                .get(istr!("height"), activation)?
+                .coerce_to_f64(activation)? as i32;
+
+            let filter_object = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_object(activation);
+            let Some(filter) = bitmap_filter::avm1_to_filter(filter_object, activation.context)
+            else {
+                return Ok(Value::Undefined);
+            };
+
+            let dest_rect = filter.calculate_dest_pixel_rect(Rectangle {
+                x_min: x,
+                x_max: x.saturating_add(width),
+                y_min: y,
+                y_max: y.saturating_add(height),
+            });
+            let proto = activation.context.avm1.prototypes().rectangle_constructor;
+            let rect = proto.construct(
+                activation,
+                &[
+                    dest_rect.x_min.into(),
+                    dest_rect.y_min.into(),
+                    dest_rect.width().into(),
+                    dest_rect.height().into(),
+                ],
+            )?;
+            return Ok(rect);
         }
     }
 
@@ -1,9 +1,11 @@
+use crate::avm1::action_cache::ActionCache;
 use crate::avm1::function::ExecutionReason;
 use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::globals::{as_broadcaster, create_globals};
 use crate::avm1::object::stage_object;
 use crate::avm1::property_map::PropertyMap;
 use crate::avm1::scope::Scope;
+use crate::avm1::trace_recorder::TraceRecorder;
 use crate::avm1::{scope, Activation, ActivationIdentifier, Error, Object, Value};
 use crate::context::UpdateContext;
 use crate::display_object::{DisplayObject, MovieClip, TDisplayObject, TDisplayObjectContainer};
@@ -84,6 +86,12 @@ pub struct Avm1<'gc> {
     /// More examples of this are in the movieclip_invalid_get_bounds_X tests.
     use_new_invalid_bounds_value: bool,
 
+    /// Breakpoints and the recorded trace of the trace recorder.
+    trace_recorder: TraceRecorder<'gc>,
+
+    /// The decoded form of the code that has run.
+    action_cache: ActionCache<'gc>,
+
     #[cfg(feature = "avm_debug")]
     pub debug_output: bool,
 }
@@ -117,6 +125,8 @@ impl<'gc> Avm1<'gc> {
             #[cfg(feature = "avm_debug")]
             debug_output: false,
             use_new_invalid_bounds_value: false,
+            trace_recorder: TraceRecorder::default(),
+            action_cache: ActionCache::default(),
         }
     }
 
@@ -163,6 +173,7 @@ impl<'gc> Avm1<'gc> {
             clip_obj.into(),
             None,
         );
+        child_activation.enter_timeline_code();
         if let Err(e) = child_activation.run_actions(code) {
             root_error_handler(&mut child_activation, e);
         }
@@ -332,6 +343,11 @@ impl<'gc> Avm1<'gc> {
         self.stack.len()
     }
 
+    /// The operand stack, from bottom to top.
+    pub fn stack(&self) -> &[Value<'gc>] {
+        &self.stack
+    }
+
     pub fn clear_stack(&mut self) {
         self.stack.clear()
     }
@@ -401,6 +417,22 @@ impl<'gc> Avm1<'gc> {
         self.player_version
     }
 
+    pub fn trace_recorder(&self) -> &TraceRecorder<'gc> {
+        &self.trace_recorder
+    }
+
+    pub fn trace_recorder_mut(&mut self) -> &mut TraceRecorder<'gc> {
+        &mut self.trace_recorder
+    }
+
+    pub fn action_cache(&self) -> &ActionCache<'gc> {
+        &self.action_cache
+    }
+
+    pub fn action_cache_mut(&mut self) -> &mut ActionCache<'gc> {
+        &mut self.action_cache
+    }
+
     pub fn get_register(&self, id: usize) -> Option<&Value<'gc>> {
         self.registers.get(id)
     }
@@ -0,0 +1,262 @@
+//! A trace recorder for AVM1 code, which offers breakpoints and stepping
+//! after the fact.
+//!
+//! This is not a debugger that suspends scripts. AVM1 runs on the same thread
+//! as the debug UI, so a script can't be stopped halfway through: the only way
+//! back to the event loop is to let it finish. Instead, hitting a breakpoint
+//! starts a trace: the state before every action that runs for the rest of
+//! the tick is recorded, and the player holds still before the next frame.
+//! "Stepping" then walks through that trace, while the scripts have long
+//! finished running.
+//!
+//! Registers and the stack are copied into each record, but objects are not,
+//! so their properties always show their current values, and nothing done
+//! while looking at a trace can change what the scripts did.
+
+use crate::avm1::scope::Scope;
+use crate::avm1::Value;
+use crate::display_object::DisplayObject;
+use crate::string::WStr;
+use crate::tag_utils::SwfSlice;
+use gc_arena::{Collect, Gc};
+
+/// The maximum amount of actions recorded after breaking, so that a paused
+/// player running a long loop doesn't exhaust memory.
+const MAX_RECORDED_ACTIONS: usize = 10000;
+
+/// A location that starts a trace when it is reached.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Breakpoint {
+    /// Starts tracing at the action at `offset` in the data of the SWF at `url`.
+    Action { url: String, offset: usize },
+
+    /// Starts tracing at the first action of any timeline code run for the clip
+    /// at `path` (such as `_level0.menu`) while it is on `frame`.
+    Frame { path: String, frame: u16 },
+}
+
+impl Breakpoint {
+    fn matches_action(&self, action_url: &str, action_offset: usize) -> bool {
+        match self {
+            Breakpoint::Action { url, offset } => url == action_url && *offset == action_offset,
+            Breakpoint::Frame { .. } => false,
+        }
+    }
+
+    fn matches_frame(&self, clip_path: &str, clip_frame: u16) -> bool {
+        match self {
+            Breakpoint::Frame { path, frame } => path == clip_path && *frame == clip_frame,
+            Breakpoint::Action { .. } => false,
+        }
+    }
+}
+
+/// Why a trace started at an action.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BreakReason {
+    Breakpoint,
+    Frame,
+    Pause,
+    Step,
+}
+
+/// The state of the AVM right before an action was run.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct ActionRecord<'gc> {
+    /// The block of code being run, which is the whole `DoAction` for
+    /// timeline code and the body for functions.
+    #[collect(require_static)]
+    pub code: SwfSlice,
+
+    /// The offset of the action in the data of the SWF.
+    pub offset: usize,
+
+    /// How deep the activation running the action is nested.
+    pub depth: u16,
+
+    /// The name of the activation running the action.
+    #[collect(require_static)]
+    pub activation: String,
+
+    /// Why the trace started at this action, if it did.
+    #[collect(require_static)]
+    pub reason: Option<BreakReason>,
+
+    pub clip: DisplayObject<'gc>,
+    pub this: Value<'gc>,
+    pub scope: Gc<'gc, Scope<'gc>>,
+
+    /// The registers, indexed by register number.
+    pub registers: Vec<Value<'gc>>,
+
+    /// Whether `registers` belong to the function, instead of being the global ones.
+    pub local_registers: bool,
+
+    /// The operand stack, from bottom to top.
+    pub stack: Vec<Value<'gc>>,
+}
+
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct TraceRecorder<'gc> {
+    #[collect(require_static)]
+    breakpoints: Vec<Breakpoint>,
+
+    /// Set when the next action to run should start a trace regardless of breakpoints.
+    #[collect(require_static)]
+    break_next: Option<BreakReason>,
+
+    /// Every action run since the trace started; empty when not paused.
+    recording: Vec<ActionRecord<'gc>>,
+
+    /// The index of the record currently being looked at.
+    position: usize,
+}
+
+impl<'gc> TraceRecorder<'gc> {
+    /// Whether the recorder needs to look at every action being run.
+    #[inline(always)]
+    pub fn is_active(&self) -> bool {
+        !self.breakpoints.is_empty() || self.break_next.is_some() || !self.recording.is_empty()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        !self.recording.is_empty()
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) {
+        self.breakpoints.retain(|b| b != breakpoint);
+    }
+
+    pub fn toggle_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if self.breakpoints.contains(&breakpoint) {
+            self.remove_breakpoint(&breakpoint);
+        } else {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    /// Start a trace at the next action that runs.
+    pub fn pause(&mut self) {
+        if !self.is_paused() {
+            self.break_next = Some(BreakReason::Pause);
+        }
+    }
+
+    /// The action currently being looked at while paused.
+    pub fn current(&self) -> Option<&ActionRecord<'gc>> {
+        self.recording.get(self.position)
+    }
+
+    /// The position of the current action in the recording, and the length of the recording.
+    pub fn recording_position(&self) -> (usize, usize) {
+        (self.position, self.recording.len())
+    }
+
+    /// Move to the next action.
+    pub fn step_in(&mut self) {
+        self.step_to(|_| true);
+    }
+
+    /// Move to the next action that isn't inside a function called by the current one.
+    pub fn step_over(&mut self) {
+        if let Some(depth) = self.current().map(|record| record.depth) {
+            self.step_to(|record| record.depth <= depth);
+        }
+    }
+
+    /// Move to the next action after the current function returned.
+    pub fn step_out(&mut self) {
+        if let Some(depth) = self.current().map(|record| record.depth) {
+            self.step_to(|record| record.depth < depth);
+        }
+    }
+
+    /// Move to the next action that hit a breakpoint, or let the player run
+    /// again if there is none.
+    pub fn resume(&mut self) {
+        let next = self.find_next(|record| {
+            matches!(
+                record.reason,
+                Some(BreakReason::Breakpoint | BreakReason::Frame)
+            ) || self
+                .breakpoints
+                .iter()
+                .any(|breakpoint| breakpoint.matches_action(record.code.movie.url(), record.offset))
+        });
+        match next {
+            Some(next) => self.position = next,
+            None => self.finish(),
+        }
+    }
+
+    fn step_to(&mut self, predicate: impl Fn(&ActionRecord<'gc>) -> bool) {
+        match self.find_next(predicate) {
+            Some(next) => self.position = next,
+            None => {
+                // The step continues past what has run so far, so start a
+                // new trace as soon as the player runs the next action.
+                self.finish();
+                self.break_next = Some(BreakReason::Step);
+            }
+        }
+    }
+
+    /// Find the first record after the current one matching `predicate`.
+    fn find_next(&self, predicate: impl Fn(&ActionRecord<'gc>) -> bool) -> Option<usize> {
+        (self.position + 1..self.recording.len()).find(|&index| predicate(&self.recording[index]))
+    }
+
+    /// Throw away the recording, letting the player run again.
+    fn finish(&mut self) {
+        self.recording.clear();
+        self.position = 0;
+    }
+
+    /// Called when timeline code starts running for `path` on `frame`.
+    pub(crate) fn enter_timeline_code(&mut self, path: &WStr, frame: u16) {
+        if self.break_next.is_some() {
+            return;
+        }
+        let path = path.to_utf8_lossy();
+        if self
+            .breakpoints
+            .iter()
+            .any(|breakpoint| breakpoint.matches_frame(&path, frame))
+        {
+            self.break_next = Some(BreakReason::Frame);
+        }
+    }
+
+    /// Whether actions still need to be recorded.
+    pub(crate) fn is_recording(&self) -> bool {
+        self.is_paused() && self.recording.len() < MAX_RECORDED_ACTIONS
+    }
+
+    /// Decide whether to start a trace at the action at `offset` in the SWF at `url`.
+    pub(crate) fn check_action(&mut self, url: &str, offset: usize) -> Option<BreakReason> {
+        self.break_next.take().or_else(|| {
+            self.breakpoints
+                .iter()
+                .any(|breakpoint| breakpoint.matches_action(url, offset))
+                .then_some(BreakReason::Breakpoint)
+        })
+    }
+
+    pub(crate) fn record(&mut self, record: ActionRecord<'gc>) {
+        if self.recording.len() < MAX_RECORDED_ACTIONS {
+            self.recording.push(record);
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::avm2::bytearray::ObjectEncoding;
 use crate::avm2::class::{AllocatorFn, CustomConstructorFn};
+use crate::avm2::debugger::{Debugger, DebuggerConnection};
 use crate::avm2::e4x::XmlSettings;
 use crate::avm2::error::{
     make_error_1014, make_error_1107, type_error, verify_error, Error1014Type,
@@ -25,7 +26,7 @@ use crate::PlayerRuntime;
 
 use fnv::FnvHashMap;
 use gc_arena::lock::GcRefLock;
-use gc_arena::{Collect, Gc, Mutation};
+use gc_arena::{Collect, Finalization, Gc, Mutation};
 use std::sync::Arc;
 use swf::avm2::read::Reader;
 use swf::DoAbc2Flag;
@@ -46,6 +47,7 @@ mod array;
 pub mod bytearray;
 mod call_stack;
 mod class;
+pub mod debugger;
 mod domain;
 mod dynamic_map;
 mod e4x;
@@ -64,6 +66,7 @@ mod op;
 mod optimizer;
 mod parameters;
 pub mod property;
+mod property_cache;
 mod property_map;
 mod qname;
 mod regexp;
@@ -100,7 +103,8 @@ pub use crate::avm2::qname::QName;
 pub use crate::avm2::value::Value;
 
 use self::api_version::ApiVersion;
-use self::object::WeakObject;
+use self::globals::flash::display::shader_job::PendingShaderJob;
+use self::object::{DictionaryObject, DictionaryObjectWeak, WeakObject};
 use self::scope::Scope;
 
 const BROADCAST_WHITELIST: [&[u8]; 4] =
@@ -171,6 +175,13 @@ pub struct Avm2<'gc> {
     alias_to_class_map: FnvHashMap<AvmString<'gc>, ClassObject<'gc>>,
     class_to_alias_map: FnvHashMap<Class<'gc>, AvmString<'gc>>,
 
+    /// Every `Dictionary` with weak keys that hasn't been collected yet,
+    /// whose entries need to be removed when their keys are collected.
+    weak_dictionaries: Vec<DictionaryObjectWeak<'gc>>,
+
+    /// `ShaderJob`s which were started asynchronously and haven't completed yet.
+    pub pending_shader_jobs: Vec<PendingShaderJob<'gc>>,
+
     #[collect(require_static)]
     pub xml_settings: XmlSettings,
 
@@ -188,6 +199,9 @@ pub struct Avm2<'gc> {
     pub debug_output: bool,
 
     pub optimizer_enabled: bool,
+
+    /// The debugger attached to this AVM, if any.
+    debugger: Option<Box<Debugger<'gc>>>,
 }
 
 impl<'gc> Avm2<'gc> {
@@ -228,6 +242,10 @@ impl<'gc> Avm2<'gc> {
             alias_to_class_map: Default::default(),
             class_to_alias_map: Default::default(),
 
+            weak_dictionaries: Vec::new(),
+
+            pending_shader_jobs: Vec::new(),
+
             xml_settings: XmlSettings::new_default(),
             default_bytearray_encoding: ObjectEncoding::Amf3,
 
@@ -238,6 +256,8 @@ impl<'gc> Avm2<'gc> {
             debug_output: false,
 
             optimizer_enabled: true,
+
+            debugger: None,
         }
     }
 
@@ -373,6 +393,11 @@ impl<'gc> Avm2<'gc> {
                     event_name,
                     err,
                 );
+                let message = uncaught_error_message(&mut activation, &err);
+                activation
+                    .context
+                    .fdb
+                    .exception(activation.context.sockets, &message);
                 // TODO: push the error onto `loaderInfo.uncaughtErrorEvents`
                 false
             }
@@ -411,6 +436,30 @@ impl<'gc> Avm2<'gc> {
         bucket.push(object.downgrade());
     }
 
+    /// Register a `Dictionary` with weak keys, so that the entries of keys
+    /// which are collected get removed from it.
+    pub fn register_weak_dictionary(&mut self, dictionary: DictionaryObject<'gc>) {
+        self.weak_dictionaries
+            .push(DictionaryObjectWeak(Gc::downgrade(dictionary.0)));
+    }
+
+    /// Remove the entries of collected keys from every `Dictionary` with weak
+    /// keys.
+    ///
+    /// This must run once marking has finished and before the arena is swept,
+    /// so that the keys which are about to be collected are known.
+    pub fn remove_dead_dictionary_keys(&mut self, fc: &Finalization<'gc>) {
+        self.weak_dictionaries.retain(|dictionary| {
+            if dictionary.0.is_dead(fc) {
+                return false;
+            }
+            if let Some(dictionary) = dictionary.0.upgrade(fc) {
+                DictionaryObject(dictionary).remove_dead_keys(fc);
+            }
+            true
+        });
+    }
+
     /// Dispatch an event on all objects in the current execution list.
     ///
     /// `on_type` specifies a class or interface constructor whose instances,
@@ -459,6 +508,11 @@ impl<'gc> Avm2<'gc> {
                             event_name,
                             err,
                         );
+                        let message = uncaught_error_message(&mut activation, &err);
+                        activation
+                            .context
+                            .fdb
+                            .exception(activation.context.sockets, &message);
                         // TODO: push the error onto `loaderInfo.uncaughtErrorEvents`
                     }
                 }
@@ -723,4 +777,36 @@ impl<'gc> Avm2<'gc> {
     pub fn set_optimizer_enabled(&mut self, value: bool) {
         self.optimizer_enabled = value;
     }
+
+    /// Attach a debugger to this AVM.
+    ///
+    /// Only methods that haven't run yet can be debugged, so this should
+    /// happen before any of the movie's code runs.
+    pub fn attach_debugger(&mut self, connection: Box<dyn DebuggerConnection>) {
+        self.debugger = Some(Box::new(Debugger::new(connection)));
+    }
+
+    pub fn debugger_attached(&self) -> bool {
+        self.debugger.is_some()
+    }
+
+    /// Whether the attached debugger holds the player still.
+    pub fn debugger_paused(&mut self) -> bool {
+        self.debugger
+            .as_mut()
+            .is_some_and(|debugger| debugger.is_paused())
+    }
+}
+
+/// Describe an error that no script caught the way Flash Player does for its
+/// debugger, which is the result of calling `toString()` on the thrown value.
+fn uncaught_error_message<'gc>(activation: &mut Activation<'_, 'gc>, err: &Error<'gc>) -> String {
+    match err.as_avm_error() {
+        Some(value) => match value.coerce_to_string(activation) {
+            Ok(message) => message.to_utf8_lossy().into_owned(),
+            Err(_) => format!("{err:?}"),
+        },
+        // Internal errors have no ActionScript representation.
+        None => format!("{err:?}"),
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::avm2::array::ArrayStorage;
 use crate::avm2::class::Class;
+use crate::avm2::debugger::Debugger;
 use crate::avm2::domain::Domain;
 use crate::avm2::e4x::{escape_attribute_value, escape_element_value};
 use crate::avm2::error::{
@@ -15,7 +16,8 @@ use crate::avm2::object::{
     XmlListObject,
 };
 use crate::avm2::object::{Object, TObject};
-use crate::avm2::op::{LookupSwitch, Op};
+use crate::avm2::op::{LookupSwitch, MemoryAccess, Op};
+use crate::avm2::property_cache::PropertyCache;
 use crate::avm2::scope::{search_scope_stack, Scope, ScopeChain};
 use crate::avm2::script::Script;
 use crate::avm2::stack::StackFrame;
@@ -558,6 +560,37 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         self.stack.pop_args(arg_count)
     }
 
+    /// Pops a value that the optimizer has proven to be a Number or an int.
+    ///
+    /// Should the optimizer be wrong, this falls back to coercing the value.
+    fn pop_number(&mut self) -> Result<f64, Error<'gc>> {
+        let value = self.pop_stack();
+        debug_assert!(
+            value.try_as_f64().is_some(),
+            "Expected Number or Integer, got {value:?}"
+        );
+
+        match value.try_as_f64() {
+            Some(value) => Ok(value),
+            None => value.coerce_to_number(self),
+        }
+    }
+
+    /// Like `pop_number`, but for `i32`.
+    fn pop_int(&mut self) -> Result<i32, Error<'gc>> {
+        let value = self.pop_stack();
+        debug_assert!(
+            value.try_as_f64().is_some(),
+            "Expected Number or Integer, got {value:?}"
+        );
+
+        match value {
+            Value::Integer(value) => Ok(value),
+            Value::Number(_) => Ok(value.as_i32()),
+            _ => value.coerce_to_i32(self),
+        }
+    }
+
     /// Pushes a scope onto the scope stack.
     #[inline]
     pub fn push_scope(&mut self, scope: Scope<'gc>) {
@@ -647,6 +680,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::Dup => self.op_dup(),
                 Op::GetLocal { index } => self.op_get_local(*index),
                 Op::SetLocal { index } => self.op_set_local(*index),
+                Op::SetLocalInt { index } => self.op_set_local_int(*index),
+                Op::SetLocalNumber { index } => self.op_set_local_number(*index),
                 Op::Kill { index } => self.op_kill(*index),
                 Op::Call { num_args } => self.op_call(*num_args),
                 Op::CallMethod {
@@ -663,6 +698,9 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                     multiname,
                     num_args,
                 } => self.op_call_property(*multiname, *num_args),
+                Op::CallPropertyCached { cache, num_args } => {
+                    self.op_call_property_cached(*cache, *num_args)
+                }
                 Op::CallPropLex {
                     multiname,
                     num_args,
@@ -671,15 +709,20 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                     multiname,
                     num_args,
                 } => self.op_call_prop_void(*multiname, *num_args),
+                Op::CallPropVoidCached { cache, num_args } => {
+                    self.op_call_prop_void_cached(*cache, *num_args)
+                }
                 Op::CallStatic { method, num_args } => self.op_call_static(*method, *num_args),
                 Op::CallSuper {
                     multiname,
                     num_args,
                 } => self.op_call_super(*multiname, *num_args),
                 Op::GetPropertyStatic { multiname } => self.op_get_property_static(*multiname),
+                Op::GetPropertyCached { cache } => self.op_get_property_cached(*cache),
                 Op::GetPropertyFast { multiname } => self.op_get_property_fast(*multiname),
                 Op::GetPropertySlow { multiname } => self.op_get_property_slow(*multiname),
                 Op::SetPropertyStatic { multiname } => self.op_set_property_static(*multiname),
+                Op::SetPropertyCached { cache } => self.op_set_property_cached(*cache),
                 Op::SetPropertyFast { multiname } => self.op_set_property_fast(*multiname),
                 Op::SetPropertySlow { multiname } => self.op_set_property_slow(*multiname),
                 Op::InitProperty { multiname } => self.op_init_property(*multiname),
@@ -729,6 +772,8 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::ConvertS => self.op_convert_s(),
                 Op::Add => self.op_add(),
                 Op::AddI => self.op_add_i(),
+                Op::AddInt => self.op_add_int(),
+                Op::AddNumber => self.op_add_number(),
                 Op::BitAnd => self.op_bitand(),
                 Op::BitNot => self.op_bitnot(),
                 Op::BitOr => self.op_bitor(),
@@ -738,6 +783,7 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::Decrement => self.op_decrement(),
                 Op::DecrementI => self.op_decrement_i(),
                 Op::Divide => self.op_divide(),
+                Op::DivideNumber => self.op_divide_number(),
                 Op::IncLocal { index } => self.op_inclocal(*index),
                 Op::IncLocalI { index } => self.op_inclocal_i(*index),
                 Op::Increment => self.op_increment(),
@@ -745,20 +791,27 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::LShift => self.op_lshift(),
                 Op::Modulo => self.op_modulo(),
                 Op::Multiply => self.op_multiply(),
+                Op::MultiplyNumber => self.op_multiply_number(),
                 Op::MultiplyI => self.op_multiply_i(),
                 Op::Negate => self.op_negate(),
                 Op::NegateI => self.op_negate_i(),
                 Op::RShift => self.op_rshift(),
                 Op::Subtract => self.op_subtract(),
                 Op::SubtractI => self.op_subtract_i(),
+                Op::SubtractInt => self.op_subtract_int(),
+                Op::SubtractNumber => self.op_subtract_number(),
                 Op::Swap => self.op_swap(),
                 Op::URShift => self.op_urshift(),
                 Op::StrictEquals => self.op_strict_equals(),
                 Op::Equals => self.op_equals(),
                 Op::GreaterEquals => self.op_greater_equals(),
+                Op::GreaterEqualsNumber => self.op_greater_equals_number(),
                 Op::GreaterThan => self.op_greater_than(),
+                Op::GreaterThanNumber => self.op_greater_than_number(),
                 Op::LessEquals => self.op_less_equals(),
+                Op::LessEqualsNumber => self.op_less_equals_number(),
                 Op::LessThan => self.op_less_than(),
+                Op::LessThanNumber => self.op_less_than_number(),
                 Op::Nop => Ok(()),
                 Op::Not => self.op_not(),
                 Op::HasNext => self.op_has_next(),
@@ -791,16 +844,18 @@ impl<'a, 'gc> Activation<'a, 'gc> {
                 Op::Coerce { class } => self.op_coerce(*class),
                 Op::CoerceSwapPop { class } => self.op_coerce_swap_pop(*class),
                 Op::CheckFilter => self.op_check_filter(),
-                Op::Si8 => self.op_si8(),
-                Op::Si16 => self.op_si16(),
-                Op::Si32 => self.op_si32(),
-                Op::Sf32 => self.op_sf32(),
-                Op::Sf64 => self.op_sf64(),
-                Op::Li8 => self.op_li8(),
-                Op::Li16 => self.op_li16(),
-                Op::Li32 => self.op_li32(),
-                Op::Lf32 => self.op_lf32(),
-                Op::Lf64 => self.op_lf64(),
+                Op::Si8 => self.op_store_memory(MemoryAccess::I8, 0),
+                Op::Si16 => self.op_store_memory(MemoryAccess::I16, 0),
+                Op::Si32 => self.op_store_memory(MemoryAccess::I32, 0),
+                Op::Sf32 => self.op_store_memory(MemoryAccess::F32, 0),
+                Op::Sf64 => self.op_store_memory(MemoryAccess::F64, 0),
+                Op::StoreMemory { kind, offset } => self.op_store_memory(*kind, *offset),
+                Op::Li8 => self.op_load_memory(MemoryAccess::I8, 0),
+                Op::Li16 => self.op_load_memory(MemoryAccess::I16, 0),
+                Op::Li32 => self.op_load_memory(MemoryAccess::I32, 0),
+                Op::Lf32 => self.op_load_memory(MemoryAccess::F32, 0),
+                Op::Lf64 => self.op_load_memory(MemoryAccess::F64, 0),
+                Op::LoadMemory { kind, offset } => self.op_load_memory(*kind, *offset),
                 Op::Sxi1 => self.op_sxi1(),
                 Op::Sxi8 => self.op_sxi8(),
                 Op::Sxi16 => self.op_sxi16(),
@@ -999,6 +1054,26 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    /// Coerces a value to int and stores it in a local register, replacing a
+    /// `coerce_i` followed by a `setlocal`.
+    fn op_set_local_int(&mut self, register_index: u32) -> Result<(), Error<'gc>> {
+        let value = self.pop_stack().coerce_to_i32(self)?;
+
+        self.set_local_register(register_index, value);
+
+        Ok(())
+    }
+
+    /// Coerces a value to Number and stores it in a local register, replacing
+    /// a `coerce_d` followed by a `setlocal`.
+    fn op_set_local_number(&mut self, register_index: u32) -> Result<(), Error<'gc>> {
+        let value = self.pop_stack().coerce_to_number(self)?;
+
+        self.set_local_register(register_index, value);
+
+        Ok(())
+    }
+
     fn op_kill(&mut self, register_index: u32) -> Result<(), Error<'gc>> {
         self.set_local_register(register_index, Value::Undefined);
 
@@ -1081,6 +1156,24 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_call_property_cached(
+        &mut self,
+        cache: Gc<'gc, PropertyCache<'gc>>,
+        arg_count: u32,
+    ) -> Result<(), Error<'gc>> {
+        let args = self.stack.get_args(arg_count as usize);
+        let multiname = cache.multiname();
+        let receiver = self.pop_stack().null_check(self, Some(&multiname))?;
+
+        let vtable = receiver.vtable(self);
+        let property = PropertyCache::get_trait(cache, self.gc(), vtable);
+        let value = receiver.call_resolved_property(&multiname, property, args, self)?;
+
+        self.push_stack(value);
+
+        Ok(())
+    }
+
     fn op_call_prop_lex(
         &mut self,
         multiname: Gc<'gc, Multiname<'gc>>,
@@ -1111,6 +1204,22 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_call_prop_void_cached(
+        &mut self,
+        cache: Gc<'gc, PropertyCache<'gc>>,
+        arg_count: u32,
+    ) -> Result<(), Error<'gc>> {
+        let args = self.stack.get_args(arg_count as usize);
+        let multiname = cache.multiname();
+        let receiver = self.pop_stack().null_check(self, Some(&multiname))?;
+
+        let vtable = receiver.vtable(self);
+        let property = PropertyCache::get_trait(cache, self.gc(), vtable);
+        receiver.call_resolved_property(&multiname, property, args, self)?;
+
+        Ok(())
+    }
+
     fn op_call_static(&mut self, method: Method<'gc>, arg_count: u32) -> Result<(), Error<'gc>> {
         let args = self.stack.get_args(arg_count as usize);
         let receiver = self.pop_stack();
@@ -1194,6 +1303,22 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_get_property_cached(
+        &mut self,
+        cache: Gc<'gc, PropertyCache<'gc>>,
+    ) -> Result<(), Error<'gc>> {
+        // static names that couldn't be resolved ahead of time
+        let multiname = cache.multiname();
+        let object = self.pop_stack().null_check(self, Some(&multiname))?;
+
+        let vtable = object.vtable(self);
+        let property = PropertyCache::get_trait(cache, self.gc(), vtable);
+        let value = object.get_resolved_property(&multiname, vtable, property, self)?;
+        self.push_stack(value);
+
+        Ok(())
+    }
+
     fn op_get_property_fast(
         &mut self,
         multiname: Gc<'gc, Multiname<'gc>>,
@@ -1267,6 +1392,24 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_set_property_cached(
+        &mut self,
+        cache: Gc<'gc, PropertyCache<'gc>>,
+    ) -> Result<(), Error<'gc>> {
+        // static names that couldn't be resolved ahead of time
+
+        let value = self.pop_stack();
+
+        let multiname = cache.multiname();
+        let object = self.pop_stack().null_check(self, Some(&multiname))?;
+
+        let vtable = object.vtable(self);
+        let property = PropertyCache::get_trait(cache, self.gc(), vtable);
+        object.set_resolved_property(&multiname, property, value, self)?;
+
+        Ok(())
+    }
+
     fn op_set_property_fast(
         &mut self,
         multiname: Gc<'gc, Multiname<'gc>>,
@@ -2031,6 +2174,32 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    /// Adds two values that the optimizer has proven to be ints.
+    fn op_add_int(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_int()?;
+        let value1 = self.pop_int()?;
+
+        // The sum of two ints is a Number, which may lie outside the int range
+        let sum: Value<'gc> = match value1.checked_add(value2) {
+            Some(sum) => sum.into(),
+            None => (value1 as f64 + value2 as f64).into(),
+        };
+
+        self.push_stack(sum);
+
+        Ok(())
+    }
+
+    /// Adds two values that the optimizer has proven to be numeric.
+    fn op_add_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 + value2);
+
+        Ok(())
+    }
+
     fn op_bitand(&mut self) -> Result<(), Error<'gc>> {
         let value2 = self.pop_stack().coerce_to_i32(self)?;
         let value1 = self.pop_stack().coerce_to_i32(self)?;
@@ -2107,6 +2276,15 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_divide_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 / value2);
+
+        Ok(())
+    }
+
     fn op_inclocal(&mut self, index: u32) -> Result<(), Error<'gc>> {
         let value = self.local_register(index).coerce_to_number(self)?;
 
@@ -2178,6 +2356,15 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_multiply_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 * value2);
+
+        Ok(())
+    }
+
     fn op_multiply_i(&mut self) -> Result<(), Error<'gc>> {
         let value2 = self.pop_stack().coerce_to_i32(self)?;
         let value1 = self.pop_stack().coerce_to_i32(self)?;
@@ -2241,6 +2428,29 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_subtract_int(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_int()?;
+        let value1 = self.pop_int()?;
+
+        let difference: Value<'gc> = match value1.checked_sub(value2) {
+            Some(difference) => difference.into(),
+            None => (value1 as f64 - value2 as f64).into(),
+        };
+
+        self.push_stack(difference);
+
+        Ok(())
+    }
+
+    fn op_subtract_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 - value2);
+
+        Ok(())
+    }
+
     fn op_swap(&mut self) -> Result<(), Error<'gc>> {
         let value2 = self.pop_stack();
         let value1 = self.pop_stack();
@@ -2296,6 +2506,15 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_greater_equals_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 >= value2);
+
+        Ok(())
+    }
+
     fn op_greater_than(&mut self) -> Result<(), Error<'gc>> {
         let value2 = self.pop_stack();
         let value1 = self.pop_stack();
@@ -2307,6 +2526,15 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_greater_than_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 > value2);
+
+        Ok(())
+    }
+
     fn op_less_equals(&mut self) -> Result<(), Error<'gc>> {
         let value2 = self.pop_stack();
         let value1 = self.pop_stack();
@@ -2318,6 +2546,15 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    fn op_less_equals_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 <= value2);
+
+        Ok(())
+    }
+
     fn op_less_than(&mut self) -> Result<(), Error<'gc>> {
         let value2 = self.pop_stack();
         let value1 = self.pop_stack();
@@ -2329,6 +2566,17 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
+    /// Compares two values that the optimizer has proven to be numeric. Like
+    /// `abstract_lt`, any comparison involving NaN is false.
+    fn op_less_than_number(&mut self) -> Result<(), Error<'gc>> {
+        let value2 = self.pop_number()?;
+        let value1 = self.pop_number()?;
+
+        self.push_stack(value1 < value2);
+
+        Ok(())
+    }
+
     fn op_not(&mut self) -> Result<(), Error<'gc>> {
         let value = self.pop_stack().coerce_to_boolean();
 
@@ -2685,168 +2933,101 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         self.outer.domain().domain_memory()
     }
 
-    /// Implements `Op::Si8`
-    fn op_si8(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_i32(self)?;
-        let val = self.pop_stack().coerce_to_i32(self)? as i8;
-        let mut dm = self.domain_memory().storage_mut();
-
-        let Ok(address) = usize::try_from(address) else {
-            return Err(make_error_1506(self));
+    /// Pops the address of a domain memory access and adds `offset` to it.
+    ///
+    /// Addresses are unsigned, so this wraps around the same way as a
+    /// separate `add_i` would have.
+    #[inline]
+    fn pop_memory_address(&mut self, offset: i32) -> Result<usize, Error<'gc>> {
+        let address = match self.pop_stack() {
+            // Alchemy code computes its addresses with int arithmetic.
+            Value::Integer(address) => address,
+            address => address.coerce_to_i32(self)?,
         };
-
-        if address >= dm.len() {
-            return Err(make_error_1506(self));
-        }
-
-        dm.set_nongrowing(address, val as u8);
-
-        Ok(())
+        Ok(address.wrapping_add(offset) as u32 as usize)
     }
 
-    /// Implements `Op::Si16`
-    fn op_si16(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_i32(self)?;
-        let val = self.pop_stack().coerce_to_i32(self)? as i16;
-        let mut dm = self.domain_memory().storage_mut();
+    /// Implements `Op::Si8`, `Op::Si16`, `Op::Si32`, `Op::Sf32`, `Op::Sf64`
+    /// and `Op::StoreMemory`.
+    fn op_store_memory(&mut self, kind: MemoryAccess, offset: i32) -> Result<(), Error<'gc>> {
+        let address = self.pop_memory_address(offset)?;
+        let value = self.pop_stack();
 
-        let Ok(address) = usize::try_from(address) else {
-            return Err(make_error_1506(self));
-        };
-        if address > dm.len() - 2 {
-            return Err(make_error_1506(self));
+        // The integer stores write the low bytes of the little-endian value.
+        // Coercing the value may run code, so this is done before borrowing the memory.
+        let mut buf = [0; 8];
+        match kind {
+            MemoryAccess::I8 | MemoryAccess::I16 | MemoryAccess::I32 => {
+                let value = match value {
+                    Value::Integer(value) => value,
+                    value => value.coerce_to_i32(self)?,
+                };
+                buf[..4].copy_from_slice(&value.to_le_bytes());
+            }
+            MemoryAccess::F32 | MemoryAccess::F64 => {
+                let value = match value.try_as_f64() {
+                    Some(value) => value,
+                    None => value.coerce_to_number(self)?,
+                };
+                if kind == MemoryAccess::F32 {
+                    buf[..4].copy_from_slice(&(value as f32).to_le_bytes());
+                } else {
+                    buf = value.to_le_bytes();
+                }
+            }
         }
-        dm.write_at_nongrowing(&val.to_le_bytes(), address)
-            .map_err(|e| e.to_avm(self))?;
-
-        Ok(())
-    }
 
-    /// Implements `Op::Si32`
-    fn op_si32(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_i32(self)?;
-        let val = self.pop_stack().coerce_to_i32(self)?;
+        // The memory is borrowed for a single access: any op in between two
+        // accesses may run code which resizes or replaces the domain memory.
         let mut dm = self.domain_memory().storage_mut();
+        let target = dm
+            .bytes_mut()
+            .get_mut(address..)
+            .and_then(|bytes| bytes.get_mut(..kind.size()));
 
-        let Ok(address) = usize::try_from(address) else {
+        let Some(target) = target else {
+            drop(dm);
             return Err(make_error_1506(self));
         };
-        if address > dm.len() - 4 {
-            return Err(make_error_1506(self));
-        }
-        dm.write_at_nongrowing(&val.to_le_bytes(), address)
-            .map_err(|e| e.to_avm(self))?;
+        target.copy_from_slice(&buf[..kind.size()]);
 
         Ok(())
     }
 
-    /// Implements `Op::Sf32`
-    fn op_sf32(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_i32(self)?;
-        let val = self.pop_stack().coerce_to_number(self)? as f32;
-        let mut dm = self.domain_memory().storage_mut();
-
-        let Ok(address) = usize::try_from(address) else {
-            return Err(make_error_1506(self));
-        };
-        if address > dm.len() - 4 {
-            return Err(make_error_1506(self));
+    /// Implements `Op::Li8`, `Op::Li16`, `Op::Li32`, `Op::Lf32`, `Op::Lf64`
+    /// and `Op::LoadMemory`.
+    fn op_load_memory(&mut self, kind: MemoryAccess, offset: i32) -> Result<(), Error<'gc>> {
+        fn read<const N: usize>(bytes: &[u8], address: usize) -> Option<[u8; N]> {
+            bytes.get(address..)?.first_chunk().copied()
         }
-        dm.write_at_nongrowing(&val.to_le_bytes(), address)
-            .map_err(|e| e.to_avm(self))?;
-
-        Ok(())
-    }
-
-    /// Implements `Op::Sf64`
-    fn op_sf64(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_i32(self)?;
-        let val = self.pop_stack().coerce_to_number(self)?;
-        let mut dm = self.domain_memory().storage_mut();
 
-        let Ok(address) = usize::try_from(address) else {
-            return Err(make_error_1506(self));
+        let address = self.pop_memory_address(offset)?;
+
+        let value = {
+            let dm = self.domain_memory().storage();
+            let bytes = dm.bytes();
+            match kind {
+                MemoryAccess::I8 => bytes
+                    .get(address)
+                    .map(|byte| Value::Integer((*byte).into())),
+                MemoryAccess::I16 => read(bytes, address)
+                    .map(|bytes| Value::Integer(u16::from_le_bytes(bytes).into())),
+                MemoryAccess::I32 => {
+                    read(bytes, address).map(|bytes| Value::Integer(i32::from_le_bytes(bytes)))
+                }
+                MemoryAccess::F32 => read(bytes, address)
+                    .map(|bytes| Value::Number(f32::from_le_bytes(bytes).into())),
+                MemoryAccess::F64 => {
+                    read(bytes, address).map(|bytes| Value::Number(f64::from_le_bytes(bytes)))
+                }
+            }
         };
-        if address > dm.len() - 8 {
-            return Err(make_error_1506(self));
-        }
-        dm.write_at_nongrowing(&val.to_le_bytes(), address)
-            .map_err(|e| e.to_avm(self))?;
-
-        Ok(())
-    }
-
-    /// Implements `Op::Li8`
-    fn op_li8(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_u32(self)? as usize;
-        let dm = self.domain_memory().storage();
-
-        let val = dm.get(address);
-
-        if let Some(val) = val {
-            self.push_stack(val);
-        } else {
-            return Err(make_error_1506(self));
-        }
-
-        Ok(())
-    }
-
-    /// Implements `Op::Li16`
-    fn op_li16(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_u32(self)? as usize;
-        let dm = self.domain_memory().storage();
-
-        if address > dm.len() - 2 {
-            return Err(make_error_1506(self));
-        }
-
-        let val = dm.read_at(2, address).map_err(|e| e.to_avm(self))?;
-        self.push_stack(u16::from_le_bytes(val.try_into().unwrap()));
 
-        Ok(())
-    }
-
-    /// Implements `Op::Li32`
-    fn op_li32(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_u32(self)? as usize;
-        let dm = self.domain_memory().storage();
-
-        if address > dm.len() - 4 {
-            return Err(make_error_1506(self));
-        }
-
-        let val = dm.read_at(4, address).map_err(|e| e.to_avm(self))?;
-        self.push_stack(i32::from_le_bytes(val.try_into().unwrap()));
-        Ok(())
-    }
-
-    /// Implements `Op::Lf32`
-    fn op_lf32(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_u32(self)? as usize;
-        let dm = self.domain_memory().storage();
-
-        if address > dm.len() - 4 {
+        let Some(value) = value else {
             return Err(make_error_1506(self));
-        }
-
-        let val = dm.read_at(4, address).map_err(|e| e.to_avm(self))?;
-        self.push_stack(f32::from_le_bytes(val.try_into().unwrap()));
-
-        Ok(())
-    }
-
-    /// Implements `Op::Lf64`
-    fn op_lf64(&mut self) -> Result<(), Error<'gc>> {
-        let address = self.pop_stack().coerce_to_u32(self)? as usize;
-        let dm = self.domain_memory().storage();
-
-        if address > dm.len() - 8 {
-            return Err(make_error_1506(self));
-        }
+        };
+        self.push_stack(value);
 
-        let val = dm.read_at(8, address).map_err(|e| e.to_avm(self))?;
-        self.push_stack(f64::from_le_bytes(val.try_into().unwrap()));
         Ok(())
     }
 
@@ -2918,21 +3099,19 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         Ok(())
     }
 
-    #[cfg(feature = "avm_debug")]
     fn op_debug_file(&mut self, file_name: AvmAtom<'gc>) -> Result<(), Error<'gc>> {
         avm_debug!(self.avm2(), "File: {file_name}");
 
-        Ok(())
-    }
+        Debugger::debug_file(self, file_name.into());
 
-    #[cfg(not(feature = "avm_debug"))]
-    fn op_debug_file(&mut self, _file_name: AvmAtom<'gc>) -> Result<(), Error<'gc>> {
         Ok(())
     }
 
     fn op_debug_line(&mut self, line_num: u32) -> Result<(), Error<'gc>> {
         avm_debug!(self.avm2(), "Line: {line_num}");
 
+        Debugger::debug_line(self, line_num);
+
         Ok(())
     }
 
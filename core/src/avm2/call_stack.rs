@@ -11,6 +11,37 @@ pub struct CallNode<'gc> {
     class: Option<Class<'gc>>,
 }
 
+impl<'gc> CallNode<'gc> {
+    pub fn method(&self) -> Method<'gc> {
+        self.method
+    }
+
+    pub fn display(&self, output: &mut WString) {
+        let is_global_init = self.class.is_some_and(|c| {
+            // If the class is a script `global` class and its instance
+            // initializer is this method, then this is a script initializer
+            c.is_script_traits() && c.instance_init() == Some(self.method)
+        });
+
+        // Special-case the printed message for script initializers
+        if is_global_init {
+            let tunit = self.method.translation_unit();
+            let name = if let Some(name) = tunit.name() {
+                name.to_utf8_lossy().to_string()
+            } else {
+                "<No name>".to_string()
+            };
+
+            // NOTE: We intentionally diverge from Flash Player's output
+            // here - everything with the [] brackets is extra information
+            // added by Ruffle
+            output.push_utf8(&format!("global$init() [TU={name}]"));
+        } else {
+            display_function(output, self.method, self.class);
+        }
+    }
+}
+
 #[derive(Collect, Clone)]
 #[collect(no_drop)]
 pub struct CallStack<'gc> {
@@ -33,32 +64,15 @@ impl<'gc> CallStack<'gc> {
     pub fn display(&self, output: &mut WString) {
         for call in self.stack.iter().rev() {
             output.push_utf8("\n\tat ");
-
-            let is_global_init = call.class.is_some_and(|c| {
-                // If the class is a script `global` class and its instance
-                // initializer is this method, then this is a script initializer
-                c.is_script_traits() && c.instance_init() == Some(call.method)
-            });
-
-            // Special-case the printed message for script initializers
-            if is_global_init {
-                let tunit = call.method.translation_unit();
-                let name = if let Some(name) = tunit.name() {
-                    name.to_utf8_lossy().to_string()
-                } else {
-                    "<No name>".to_string()
-                };
-
-                // NOTE: We intentionally diverge from Flash Player's output
-                // here - everything with the [] brackets is extra information
-                // added by Ruffle
-                output.push_utf8(&format!("global$init() [TU={name}]"));
-            } else {
-                display_function(output, call.method, call.class);
-            }
+            call.display(output);
         }
     }
 
+    /// The calls currently on the stack, starting with the outermost one.
+    pub fn nodes(&self) -> &[CallNode<'gc>] {
+        &self.stack
+    }
+
     pub fn is_empty(&self) -> bool {
         self.stack.is_empty()
     }
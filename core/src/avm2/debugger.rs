@@ -0,0 +1,778 @@
+//! Interactive debugger for AVM2 code
+//!
+//! The debugger relies on the `debugfile` and `debugline` instructions that
+//! compilers emit into debug builds of a movie to map bytecode back to source
+//! lines. These instructions are normally stripped by the optimizer, so a
+//! debugger has to be attached before any of the movie's code has run.
+//!
+//! The debugger itself doesn't know about any particular protocol; it talks to
+//! a client through a [`DebuggerConnection`]. It never waits for the client,
+//! as that would block the player's thread; the [`recorder`] module provides a
+//! connection that lets a client look around at its own pace instead.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::dynamic_map::DynamicKey;
+use crate::avm2::method::Method;
+use crate::avm2::object::TObject;
+use crate::avm2::op::Op;
+use crate::avm2::property::Property;
+use crate::avm2::{Avm2, Object, Value};
+use crate::string::{AvmString, WString};
+use gc_arena::Collect;
+
+pub mod recorder;
+
+/// A request sent by a debugger client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebuggerRequest {
+    /// Replace all line breakpoints in the given source file.
+    SetLineBreakpoints { source: String, lines: Vec<u32> },
+
+    /// Replace all function breakpoints.
+    SetFunctionBreakpoints { names: Vec<String> },
+
+    /// Resume execution.
+    Continue,
+
+    /// Stop at the next line that is executed.
+    Pause,
+
+    /// Resume until the next line in the current method or its callers.
+    Next,
+
+    /// Resume until the next line, entering any method that gets called.
+    StepIn,
+
+    /// Resume until the next line after the current method returns.
+    StepOut,
+
+    /// Request the current call stack, answered with [`DebuggerMessage::StackTrace`].
+    StackTrace,
+
+    /// Request the variable scopes of a stack frame, answered with
+    /// [`DebuggerMessage::Scopes`].
+    Scopes { frame_id: usize },
+
+    /// Request the contents of a variable reference, answered with
+    /// [`DebuggerMessage::Variables`].
+    Variables { reference: u32 },
+
+    /// The client went away. All breakpoints are removed and execution resumes.
+    Disconnect,
+}
+
+/// A message sent by the debugger to its client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebuggerMessage {
+    /// Execution has been paused.
+    Stopped {
+        reason: StopReason,
+    },
+
+    StackTrace(Vec<DebugStackFrame>),
+
+    Scopes(Vec<DebugScope>),
+
+    Variables(Vec<DebugVariable>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint,
+    FunctionBreakpoint,
+    Step,
+    Pause,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugStackFrame {
+    /// The id of this frame, to be used in [`DebuggerRequest::Scopes`].
+    pub id: usize,
+
+    /// The name of the method executing in this frame.
+    pub name: String,
+
+    /// The source file as given by the last `debugfile` instruction, if any.
+    pub file: Option<String>,
+
+    /// The line as given by the last `debugline` instruction, or 0.
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugScope {
+    pub name: String,
+
+    /// The reference to be used in [`DebuggerRequest::Variables`].
+    pub reference: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugVariable {
+    pub name: String,
+    pub value: String,
+    pub type_name: String,
+
+    /// The reference to be used in [`DebuggerRequest::Variables`] to list the
+    /// properties of this value, or 0 if it has none.
+    pub reference: u32,
+}
+
+/// The connection between the debugger and its client.
+///
+/// The debugger never waits for requests. After sending
+/// [`DebuggerMessage::Stopped`], it handles the requests that are available
+/// right away, and resumes on its own once there are none left.
+pub trait DebuggerConnection {
+    /// Returns the next pending request, if there is one.
+    fn try_recv(&mut self) -> Option<DebuggerRequest>;
+
+    fn send(&mut self, message: DebuggerMessage);
+
+    /// Whether the player should hold still, without running any code.
+    ///
+    /// The player asks between frames, once the code of a frame has finished.
+    fn is_paused(&mut self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepMode {
+    None,
+    In,
+    Over { depth: usize },
+    Out { depth: usize },
+}
+
+struct LineBreakpoints {
+    source: String,
+    lines: Vec<u32>,
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+struct DebugFrame<'gc> {
+    method: Method<'gc>,
+
+    /// The absolute stack index of the first local register, for bytecode methods.
+    locals_start: Option<usize>,
+
+    /// The length of the scope stack when this frame was entered.
+    scope_start: usize,
+
+    file: Option<AvmString<'gc>>,
+
+    line: u32,
+
+    /// The index of the line breakpoints matching `file`, if any.
+    breakpoints: Option<usize>,
+}
+
+#[derive(Collect, Clone, Copy)]
+#[collect(no_drop)]
+enum VariableContainer<'gc> {
+    Locals { frame: usize },
+    Registers { frame: usize },
+    Scope { frame: usize },
+    Object(Object<'gc>),
+}
+
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Debugger<'gc> {
+    #[collect(require_static)]
+    connection: Box<dyn DebuggerConnection>,
+
+    /// Debugging information for every frame on the call stack, in the same
+    /// order as the `CallStack`.
+    frames: Vec<DebugFrame<'gc>>,
+
+    #[collect(require_static)]
+    line_breakpoints: Vec<LineBreakpoints>,
+
+    #[collect(require_static)]
+    function_breakpoints: Vec<String>,
+
+    #[collect(require_static)]
+    step: StepMode,
+
+    pause_requested: bool,
+
+    /// Variable references handed out to the client while paused.
+    references: Vec<VariableContainer<'gc>>,
+}
+
+impl<'gc> Debugger<'gc> {
+    pub fn new(connection: Box<dyn DebuggerConnection>) -> Self {
+        Self {
+            connection,
+            frames: Vec::new(),
+            line_breakpoints: Vec::new(),
+            function_breakpoints: Vec::new(),
+            step: StepMode::None,
+            pause_requested: false,
+            references: Vec::new(),
+        }
+    }
+
+    /// Whether the client wants the player to hold still.
+    pub fn is_paused(&mut self) -> bool {
+        self.connection.is_paused()
+    }
+
+    /// Called after a method has been pushed onto the call stack.
+    ///
+    /// `locals_start` is the absolute stack index of the method's local
+    /// registers, or `None` for native methods.
+    pub fn enter_frame(
+        activation: &mut Activation<'_, 'gc>,
+        method: Method<'gc>,
+        locals_start: Option<usize>,
+    ) {
+        let Some(mut debugger) = activation.avm2().debugger.take() else {
+            return;
+        };
+        let scope_start = activation.avm2().scope_stack.len();
+
+        debugger.frames.push(DebugFrame {
+            method,
+            locals_start,
+            scope_start,
+            file: None,
+            line: 0,
+            breakpoints: None,
+        });
+
+        if !debugger.function_breakpoints.is_empty() {
+            let mut name = WString::new();
+            if let Some(node) = activation.avm2().call_stack().borrow().nodes().last() {
+                node.display(&mut name);
+            }
+            let name = name.to_utf8_lossy();
+            if debugger
+                .function_breakpoints
+                .iter()
+                .any(|breakpoint| function_matches(&name, breakpoint))
+            {
+                debugger.stop(activation, StopReason::FunctionBreakpoint);
+            }
+        }
+
+        activation.avm2().debugger = Some(debugger);
+    }
+
+    /// Called after a method has been popped off the call stack.
+    pub fn exit_frame(avm2: &mut Avm2<'gc>) {
+        if let Some(debugger) = &mut avm2.debugger {
+            debugger.frames.pop();
+        }
+    }
+
+    /// Called for every `debugfile` instruction.
+    pub fn debug_file(activation: &mut Activation<'_, 'gc>, file: AvmString<'gc>) {
+        let Some(debugger) = &mut activation.avm2().debugger else {
+            return;
+        };
+
+        let breakpoints = debugger.find_line_breakpoints(file);
+        if let Some(frame) = debugger.frames.last_mut() {
+            frame.file = Some(file);
+            frame.breakpoints = breakpoints;
+        }
+    }
+
+    /// Called for every `debugline` instruction. This is where the debugger
+    /// decides whether to stop.
+    pub fn debug_line(activation: &mut Activation<'_, 'gc>, line: u32) {
+        let Some(mut debugger) = activation.avm2().debugger.take() else {
+            return;
+        };
+
+        if let Some(frame) = debugger.frames.last_mut() {
+            frame.line = line;
+        }
+
+        while let Some(request) = debugger.connection.try_recv() {
+            debugger.handle_request(activation, request);
+        }
+
+        if let Some(reason) = debugger.stop_reason() {
+            debugger.stop(activation, reason);
+        }
+
+        activation.avm2().debugger = Some(debugger);
+    }
+
+    fn stop_reason(&self) -> Option<StopReason> {
+        let frame = self.frames.last()?;
+        let depth = self.frames.len();
+
+        if let Some(breakpoints) = frame.breakpoints {
+            if self.line_breakpoints[breakpoints]
+                .lines
+                .contains(&frame.line)
+            {
+                return Some(StopReason::Breakpoint);
+            }
+        }
+
+        if self.pause_requested {
+            return Some(StopReason::Pause);
+        }
+
+        match self.step {
+            StepMode::None => None,
+            StepMode::In => Some(StopReason::Step),
+            StepMode::Over { depth: from } if depth <= from => Some(StopReason::Step),
+            StepMode::Out { depth: from } if depth < from => Some(StopReason::Step),
+            _ => None,
+        }
+    }
+
+    /// Tell the client that execution stopped, and answer the requests it
+    /// has ready until it resumes or runs out of them.
+    fn stop(&mut self, activation: &mut Activation<'_, 'gc>, reason: StopReason) {
+        self.step = StepMode::None;
+        self.pause_requested = false;
+        self.connection.send(DebuggerMessage::Stopped { reason });
+
+        while let Some(request) = self.connection.try_recv() {
+            if self.handle_request(activation, request) {
+                break;
+            }
+        }
+
+        self.references.clear();
+    }
+
+    /// Remove all breakpoints and let the movie run freely.
+    fn reset(&mut self) {
+        self.line_breakpoints.clear();
+        self.function_breakpoints.clear();
+        for frame in &mut self.frames {
+            frame.breakpoints = None;
+        }
+        self.step = StepMode::None;
+        self.pause_requested = false;
+    }
+
+    /// Handle a request from the client, returning whether execution should resume.
+    fn handle_request(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        request: DebuggerRequest,
+    ) -> bool {
+        match request {
+            DebuggerRequest::SetLineBreakpoints { source, lines } => {
+                self.line_breakpoints
+                    .retain(|breakpoints| breakpoints.source != source);
+                if !lines.is_empty() {
+                    self.line_breakpoints
+                        .push(LineBreakpoints { source, lines });
+                }
+                for i in 0..self.frames.len() {
+                    self.frames[i].breakpoints = self.frames[i]
+                        .file
+                        .and_then(|file| self.find_line_breakpoints(file));
+                }
+                false
+            }
+            DebuggerRequest::SetFunctionBreakpoints { names } => {
+                self.function_breakpoints = names;
+                false
+            }
+            DebuggerRequest::Continue => true,
+            DebuggerRequest::Pause => {
+                self.pause_requested = true;
+                false
+            }
+            DebuggerRequest::Next => {
+                self.step = StepMode::Over {
+                    depth: self.frames.len(),
+                };
+                true
+            }
+            DebuggerRequest::StepIn => {
+                self.step = StepMode::In;
+                true
+            }
+            DebuggerRequest::StepOut => {
+                self.step = StepMode::Out {
+                    depth: self.frames.len(),
+                };
+                true
+            }
+            DebuggerRequest::StackTrace => {
+                let stack_trace = self.stack_trace(activation);
+                self.connection
+                    .send(DebuggerMessage::StackTrace(stack_trace));
+                false
+            }
+            DebuggerRequest::Scopes { frame_id } => {
+                let scopes = self.scopes(frame_id);
+                self.connection.send(DebuggerMessage::Scopes(scopes));
+                false
+            }
+            DebuggerRequest::Variables { reference } => {
+                let variables = self.variables(activation, reference);
+                self.connection.send(DebuggerMessage::Variables(variables));
+                false
+            }
+            DebuggerRequest::Disconnect => {
+                self.reset();
+                true
+            }
+        }
+    }
+
+    fn find_line_breakpoints(&self, file: AvmString<'gc>) -> Option<usize> {
+        if self.line_breakpoints.is_empty() {
+            return None;
+        }
+
+        let file = file.to_utf8_lossy();
+        self.line_breakpoints
+            .iter()
+            .position(|breakpoints| source_matches(&file, &breakpoints.source))
+    }
+
+    fn stack_trace(&self, activation: &mut Activation<'_, 'gc>) -> Vec<DebugStackFrame> {
+        let call_stack = activation.avm2().call_stack();
+        let call_stack = call_stack.borrow();
+        let nodes = call_stack.nodes();
+
+        // The debugger may have been attached while code was already running,
+        // so line the frames up from the innermost one.
+        let skipped = nodes.len().saturating_sub(self.frames.len());
+
+        self.frames
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(id, frame)| {
+                let mut name = WString::new();
+                match nodes.get(skipped + id) {
+                    Some(node) => node.display(&mut name),
+                    None => name.push_utf8(&frame.method.method_name()),
+                }
+                DebugStackFrame {
+                    id,
+                    name: name.to_utf8_lossy().into_owned(),
+                    file: frame.file.map(|file| file.to_utf8_lossy().into_owned()),
+                    line: frame.line,
+                }
+            })
+            .collect()
+    }
+
+    fn scopes(&mut self, frame_id: usize) -> Vec<DebugScope> {
+        let Some(frame) = self.frames.get(frame_id) else {
+            return Vec::new();
+        };
+        if frame.locals_start.is_none() {
+            return Vec::new();
+        }
+
+        [
+            ("Locals", VariableContainer::Locals { frame: frame_id }),
+            (
+                "Registers",
+                VariableContainer::Registers { frame: frame_id },
+            ),
+            ("Scope", VariableContainer::Scope { frame: frame_id }),
+        ]
+        .into_iter()
+        .map(|(name, container)| DebugScope {
+            name: name.to_string(),
+            reference: self.add_reference(container),
+        })
+        .collect()
+    }
+
+    fn add_reference(&mut self, container: VariableContainer<'gc>) -> u32 {
+        self.references.push(container);
+        self.references.len() as u32
+    }
+
+    fn variables(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        reference: u32,
+    ) -> Vec<DebugVariable> {
+        let Some(container) = (reference as usize)
+            .checked_sub(1)
+            .and_then(|index| self.references.get(index))
+            .copied()
+        else {
+            return Vec::new();
+        };
+
+        let values: Vec<(String, Value<'gc>)> = match container {
+            VariableContainer::Locals { frame } => self
+                .local_names(frame)
+                .into_iter()
+                .map(|(register, name)| (name, self.register(activation, frame, register)))
+                .collect(),
+            VariableContainer::Registers { frame } => {
+                let num_locals = self.frames[frame]
+                    .method
+                    .body()
+                    .map_or(0, |body| body.num_locals);
+                (0..num_locals)
+                    .map(|register| {
+                        (
+                            format!("_{register}"),
+                            self.register(activation, frame, register),
+                        )
+                    })
+                    .collect()
+            }
+            VariableContainer::Scope { frame } => {
+                let scope_stack = &activation.avm2().scope_stack;
+                let start = self.frames[frame].scope_start.min(scope_stack.len());
+                let end = self
+                    .frames
+                    .get(frame + 1)
+                    .map_or(scope_stack.len(), |next| next.scope_start)
+                    .clamp(start, scope_stack.len());
+                scope_stack[start..end]
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(i, scope)| (format!("[{i}]"), scope.values()))
+                    .collect()
+            }
+            VariableContainer::Object(object) => object_properties(object),
+        };
+
+        values
+            .into_iter()
+            .map(|(name, value)| self.describe(activation, name, value))
+            .collect()
+    }
+
+    fn register(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        frame: usize,
+        register: u32,
+    ) -> Value<'gc> {
+        match self.frames[frame].locals_start {
+            Some(start) => activation.avm2().stack.value_at(start + register as usize),
+            None => Value::Undefined,
+        }
+    }
+
+    /// The names of local registers, as given by the `debug` instructions in
+    /// the method.
+    fn local_names(&self, frame: usize) -> Vec<(u32, String)> {
+        let method = self.frames[frame].method;
+        let mut names: Vec<(u32, String)> = method
+            .get_verified_info()
+            .parsed_code
+            .iter()
+            .filter_map(|op| match op {
+                // Register 0 is always `this`, so these are offset by one.
+                Op::Debug {
+                    is_local_register: true,
+                    register_name,
+                    register,
+                } => Some((
+                    *register as u32 + 1,
+                    AvmString::from(*register_name).to_utf8_lossy().into_owned(),
+                )),
+                _ => None,
+            })
+            .collect();
+        names.sort_by_key(|(register, _)| *register);
+        names.dedup_by_key(|(register, _)| *register);
+        names.insert(0, (0, "this".to_string()));
+        names
+    }
+
+    /// Describe a value without running any ActionScript code.
+    fn describe(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        name: String,
+        value: Value<'gc>,
+    ) -> DebugVariable {
+        let (value, type_name, reference) = match value {
+            Value::Undefined => ("undefined".to_string(), "void", 0),
+            Value::Null => ("null".to_string(), "null", 0),
+            Value::Bool(_) | Value::Number(_) | Value::Integer(_) => {
+                let type_name = match value {
+                    Value::Bool(_) => "Boolean",
+                    Value::Integer(_) => "int",
+                    _ => "Number",
+                };
+                let string = value
+                    .coerce_to_string(activation)
+                    .map(|string| string.to_utf8_lossy().into_owned())
+                    .unwrap_or_default();
+                (string, type_name, 0)
+            }
+            Value::String(string) => (format!("{:?}", string.to_utf8_lossy()), "String", 0),
+            Value::Object(object) => {
+                let class_name = object.instance_class().name().local_name();
+                let reference = self.add_reference(VariableContainer::Object(object));
+                return DebugVariable {
+                    name,
+                    value: format!("[object {class_name}]"),
+                    type_name: class_name.to_utf8_lossy().into_owned(),
+                    reference,
+                };
+            }
+        };
+
+        DebugVariable {
+            name,
+            value,
+            type_name: type_name.to_string(),
+            reference,
+        }
+    }
+}
+
+/// The properties of an object that can be read without running any
+/// ActionScript code: slots, array elements and dynamic properties.
+fn object_properties<'gc>(object: Object<'gc>) -> Vec<(String, Value<'gc>)> {
+    let mut properties: Vec<(String, Value<'gc>)> = object
+        .vtable()
+        .resolved_traits()
+        .iter()
+        .filter_map(|(name, _, property)| match property {
+            Property::Slot { slot_id } | Property::ConstSlot { slot_id } => {
+                Some((name.to_utf8_lossy().into_owned(), object.get_slot(*slot_id)))
+            }
+            _ => None,
+        })
+        .collect();
+    properties.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    if let Some(array) = object.as_array_storage() {
+        properties.extend(
+            array
+                .iter()
+                .enumerate()
+                .filter_map(|(i, value)| Some((format!("[{i}]"), value?))),
+        );
+    }
+
+    let mut dynamic: Vec<(String, Value<'gc>)> = object
+        .base()
+        .values()
+        .as_hashmap()
+        .iter()
+        .map(|(key, property)| {
+            let name = match key {
+                DynamicKey::String(name) => name.to_utf8_lossy().into_owned(),
+                DynamicKey::Uint(index) => index.to_string(),
+                DynamicKey::Object(_) | DynamicKey::WeakObject(_) => "[object]".to_string(),
+            };
+            (name, property.value)
+        })
+        .collect();
+    dynamic.sort_by(|(a, _), (b, _)| a.cmp(b));
+    properties.extend(dynamic);
+
+    properties
+}
+
+/// Whether a function breakpoint refers to a method with the given display
+/// name, such as `com.example::Main/update()`.
+fn function_matches(display_name: &str, breakpoint: &str) -> bool {
+    let name = display_name.strip_suffix("()").unwrap_or(display_name);
+    name == breakpoint
+        || [
+            format!("/{breakpoint}"),
+            format!(":{breakpoint}"),
+            format!(".{breakpoint}"),
+        ]
+        .iter()
+        .any(|suffix| name.ends_with(suffix.as_str()))
+}
+
+/// Turn the file name of a `debugfile` instruction into a path, if it
+/// refers to a file on disk.
+///
+/// Compilers encode these as `source root;package path;file name`, for
+/// example `C:\project\src;com\example;Main.as`.
+pub fn source_path(debug_file: &str) -> Option<String> {
+    if !debug_file.contains(';') {
+        return None;
+    }
+
+    let separator = if debug_file.contains('\\') { "\\" } else { "/" };
+    Some(
+        debug_file
+            .split(';')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(separator),
+    )
+}
+
+/// Whether the file name of a `debugfile` instruction refers to the given path.
+///
+/// The movie may have been compiled on another machine, so only the path
+/// relative to the source root has to match.
+fn source_matches(debug_file: &str, path: &str) -> bool {
+    let normalize = |path: &str| path.replace('\\', "/");
+    let path = normalize(path);
+
+    let relative = match debug_file.split_once(';') {
+        Some((_, relative)) => normalize(
+            &relative
+                .split(';')
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("/"),
+        ),
+        None => return normalize(debug_file) == path,
+    };
+
+    path == relative || path.ends_with(&format!("/{relative}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_file_paths() {
+        let file = r"I:\Code\project\src;com\example;Main.as";
+        assert_eq!(
+            source_path(file).as_deref(),
+            Some(r"I:\Code\project\src\com\example\Main.as")
+        );
+        assert!(source_matches(
+            file,
+            "/home/user/project/src/com/example/Main.as"
+        ));
+        assert!(source_matches(file, r"D:\other\com\example\Main.as"));
+        assert!(!source_matches(file, "/home/user/project/src/Main.as"));
+
+        let file = "test_fla.MainTimeline::frame1";
+        assert_eq!(source_path(file), None);
+        assert!(source_matches(file, "test_fla.MainTimeline::frame1"));
+    }
+
+    #[test]
+    fn function_names() {
+        assert!(function_matches("com.example::Main/update()", "update"));
+        assert!(function_matches(
+            "com.example::Main/update()",
+            "Main/update"
+        ));
+        assert!(function_matches(
+            "Function/Test.as$38:testfunc()",
+            "testfunc"
+        ));
+        assert!(!function_matches(
+            "Function/Test.as$38:testfunc2()",
+            "testfunc"
+        ));
+    }
+}
@@ -0,0 +1,472 @@
+//! A [`DebuggerConnection`] for clients that can't answer the debugger while
+//! the movie runs.
+//!
+//! The debugger can't suspend the AVM2 code it stops in, so a client has to
+//! answer it before the player carries on. The recorder answers on the
+//! client's behalf instead: once the debugger stops, it takes a snapshot of
+//! every line that runs for the rest of the frame, and holds the player still
+//! while the client steps through those snapshots at its own pace.
+//!
+//! The recorder can be shared with other threads, so the client may live on a
+//! thread of its own.
+
+use crate::avm2::debugger::{
+    DebugStackFrame, DebugVariable, DebuggerConnection, DebuggerMessage, DebuggerRequest,
+    StopReason,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The maximum amount of lines recorded after stopping.
+const MAX_SNAPSHOTS: usize = 10000;
+
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub reason: StopReason,
+
+    /// The call stack, innermost frame first.
+    pub frames: Vec<DebugStackFrame>,
+
+    /// The local variables of the innermost frame.
+    pub locals: Vec<DebugVariable>,
+}
+
+type Listener = Box<dyn Fn(StopReason) + Send>;
+
+#[derive(Default)]
+struct State {
+    /// Requests to hand to the debugger the next time it checks for them.
+    requests: VecDeque<DebuggerRequest>,
+
+    /// Answers to the debugger while it is stopped.
+    replies: VecDeque<DebuggerRequest>,
+
+    /// Whether the debugger is stopped.
+    stopped: bool,
+
+    /// The snapshot being taken while the debugger is stopped.
+    pending: Option<Snapshot>,
+
+    /// Every line run since stopping; empty when not paused.
+    recording: Vec<Snapshot>,
+
+    /// Whether the player has stopped running code for the current recording.
+    settled: bool,
+
+    /// The index of the snapshot currently being looked at.
+    position: usize,
+
+    /// Whether the client has set up its breakpoints.
+    configured: bool,
+
+    /// Told about every recording once it's settled.
+    listener: Option<Listener>,
+}
+
+impl State {
+    fn finish_snapshot(&mut self) {
+        if let Some(snapshot) = self.pending.take() {
+            self.recording.push(snapshot);
+        }
+        self.replies
+            .push_back(if self.recording.len() < MAX_SNAPSHOTS {
+                DebuggerRequest::StepIn
+            } else {
+                DebuggerRequest::Continue
+            });
+    }
+
+    /// Let the player run again.
+    fn clear(&mut self) {
+        self.recording.clear();
+        self.position = 0;
+        self.settled = false;
+    }
+}
+
+/// A [`DebuggerConnection`] shared between the AVM2 debugger and its client.
+#[derive(Clone)]
+pub struct Recorder(Arc<Mutex<State>>);
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// Create a recorder for a client that sets its breakpoints as it goes.
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(State {
+            configured: true,
+            ..Default::default()
+        })))
+    }
+
+    /// Create a recorder that holds the player still until the client calls
+    /// [`Recorder::configuration_done`], so that no code runs before the
+    /// breakpoints are in place.
+    pub fn waiting_for_configuration() -> Self {
+        Self(Arc::new(Mutex::new(State::default())))
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.0.lock().expect("Lock should not be poisoned")
+    }
+
+    /// Call `listener` whenever the debugger has stopped and the player is
+    /// held still. This happens on the player's thread, while the recorder is
+    /// locked.
+    pub fn set_listener(&self, listener: impl Fn(StopReason) + Send + 'static) {
+        self.state().listener = Some(Box::new(listener));
+    }
+
+    pub fn configuration_done(&self) {
+        self.state().configured = true;
+    }
+
+    /// Whether the player is held still for the client.
+    pub fn is_paused(&self) -> bool {
+        let state = self.state();
+        !state.configured || state.settled
+    }
+
+    /// The line currently being looked at while paused.
+    pub fn current(&self) -> Option<Snapshot> {
+        let state = self.state();
+        if !state.settled {
+            return None;
+        }
+        state.recording.get(state.position).cloned()
+    }
+
+    pub fn set_line_breakpoints(&self, source: String, lines: Vec<u32>) {
+        self.state()
+            .requests
+            .push_back(DebuggerRequest::SetLineBreakpoints { source, lines });
+    }
+
+    pub fn set_function_breakpoints(&self, names: Vec<String>) {
+        self.state()
+            .requests
+            .push_back(DebuggerRequest::SetFunctionBreakpoints { names });
+    }
+
+    /// Stop at the next line that runs.
+    pub fn pause(&self) {
+        self.state().requests.push_back(DebuggerRequest::Pause);
+    }
+
+    /// Move to the next line.
+    ///
+    /// Returns why the player is paused afterwards, or `None` if it runs again.
+    pub fn step_in(&self) -> Option<StopReason> {
+        self.step_to(|_, _| true)
+    }
+
+    /// Move to the next line in the current method or its callers.
+    pub fn step_over(&self) -> Option<StopReason> {
+        self.step_to(|snapshot, depth| snapshot.frames.len() <= depth)
+    }
+
+    /// Move to the next line after the current method returns.
+    pub fn step_out(&self) -> Option<StopReason> {
+        self.step_to(|snapshot, depth| snapshot.frames.len() < depth)
+    }
+
+    /// Move to the next line that hit a breakpoint, or let the player run
+    /// again if there is none.
+    pub fn resume(&self) -> Option<StopReason> {
+        let mut state = self.state();
+        if !state.settled {
+            return None;
+        }
+        let next = (state.position + 1..state.recording.len())
+            .find(|&index| is_breakpoint(&state.recording[index]));
+        match next {
+            Some(next) => {
+                state.position = next;
+                Some(state.recording[next].reason)
+            }
+            None => {
+                state.clear();
+                None
+            }
+        }
+    }
+
+    /// Forget about the client, letting the movie run freely.
+    pub fn disconnect(&self) {
+        let mut state = self.state();
+        state.clear();
+        state.configured = true;
+        state.requests.push_back(DebuggerRequest::Disconnect);
+    }
+
+    fn step_to(&self, predicate: impl Fn(&Snapshot, usize) -> bool) -> Option<StopReason> {
+        let mut state = self.state();
+        if !state.settled {
+            return None;
+        }
+        let depth = state.recording.get(state.position)?.frames.len();
+        let next = (state.position + 1..state.recording.len()).find(|&index| {
+            let snapshot = &state.recording[index];
+            predicate(snapshot, depth) || is_breakpoint(snapshot)
+        });
+        match next {
+            Some(next) => {
+                state.position = next;
+                Some(match state.recording[next].reason {
+                    StopReason::Pause => StopReason::Step,
+                    reason => reason,
+                })
+            }
+            None => {
+                // The step continues past what has run so far, so stop
+                // again at the next line the player runs.
+                state.clear();
+                state.requests.push_back(DebuggerRequest::Pause);
+                None
+            }
+        }
+    }
+}
+
+fn is_breakpoint(snapshot: &Snapshot) -> bool {
+    matches!(
+        snapshot.reason,
+        StopReason::Breakpoint | StopReason::FunctionBreakpoint
+    )
+}
+
+impl DebuggerConnection for Recorder {
+    fn try_recv(&mut self) -> Option<DebuggerRequest> {
+        let mut state = self.state();
+        if !state.stopped {
+            return state.requests.pop_front();
+        }
+
+        let reply = state.replies.pop_front();
+        if !matches!(
+            reply,
+            Some(
+                DebuggerRequest::StackTrace
+                    | DebuggerRequest::Scopes { .. }
+                    | DebuggerRequest::Variables { .. }
+            )
+        ) {
+            // The debugger resumes with this, or on its own without a reply.
+            state.stopped = false;
+        }
+        reply
+    }
+
+    fn send(&mut self, message: DebuggerMessage) {
+        let mut state = self.state();
+        match message {
+            DebuggerMessage::Stopped { reason } => {
+                state.stopped = true;
+                let record = if state.recording.is_empty() {
+                    // Single steps are only requested while recording; one
+                    // arriving now is left over from a recording that the
+                    // client has already resumed from.
+                    reason != StopReason::Step
+                } else {
+                    state.recording.len() < MAX_SNAPSHOTS
+                };
+                if record {
+                    state.pending = Some(Snapshot {
+                        reason,
+                        frames: Vec::new(),
+                        locals: Vec::new(),
+                    });
+                    state.replies.push_back(DebuggerRequest::StackTrace);
+                }
+            }
+            DebuggerMessage::StackTrace(frames) => {
+                let top = frames.first().map(|frame| frame.id);
+                if let Some(pending) = &mut state.pending {
+                    pending.frames = frames;
+                }
+                match top {
+                    Some(frame_id) => state
+                        .replies
+                        .push_back(DebuggerRequest::Scopes { frame_id }),
+                    None => state.finish_snapshot(),
+                }
+            }
+            DebuggerMessage::Scopes(scopes) => {
+                match scopes.iter().find(|scope| scope.name == "Locals") {
+                    Some(locals) => state.replies.push_back(DebuggerRequest::Variables {
+                        reference: locals.reference,
+                    }),
+                    None => state.finish_snapshot(),
+                }
+            }
+            DebuggerMessage::Variables(variables) => {
+                if let Some(pending) = &mut state.pending {
+                    pending.locals = variables;
+                }
+                state.finish_snapshot();
+            }
+        }
+    }
+
+    fn is_paused(&mut self) -> bool {
+        let mut state = self.state();
+        if !state.settled && !state.recording.is_empty() {
+            // The player only asks between frames, so nothing more will be
+            // recorded until the client lets it run again.
+            state.settled = true;
+            let reason = state.recording[0].reason;
+            if let Some(listener) = &state.listener {
+                listener(reason);
+            }
+        }
+        !state.configured || state.settled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::debugger::DebugScope;
+
+    fn frame(id: usize, line: u32) -> DebugStackFrame {
+        DebugStackFrame {
+            id,
+            name: format!("method{id}"),
+            file: Some("Main.as".to_string()),
+            line,
+        }
+    }
+
+    /// Plays the part of the AVM2 debugger stopping at a line, returning how
+    /// it resumes.
+    fn stop(
+        recorder: &mut Recorder,
+        reason: StopReason,
+        frames: Vec<DebugStackFrame>,
+    ) -> Option<DebuggerRequest> {
+        recorder.send(DebuggerMessage::Stopped { reason });
+        loop {
+            match recorder.try_recv() {
+                Some(DebuggerRequest::StackTrace) => {
+                    recorder.send(DebuggerMessage::StackTrace(frames.clone()))
+                }
+                Some(DebuggerRequest::Scopes { .. }) => {
+                    recorder.send(DebuggerMessage::Scopes(vec![DebugScope {
+                        name: "Locals".to_string(),
+                        reference: 1,
+                    }]))
+                }
+                Some(DebuggerRequest::Variables { reference }) => {
+                    assert_eq!(reference, 1);
+                    recorder.send(DebuggerMessage::Variables(vec![]));
+                }
+                resume => return resume,
+            }
+        }
+    }
+
+    #[test]
+    fn record_and_step() {
+        let mut recorder = Recorder::new();
+        assert!(!DebuggerConnection::is_paused(&mut recorder));
+
+        assert_eq!(
+            stop(&mut recorder, StopReason::Breakpoint, vec![frame(0, 3)]),
+            Some(DebuggerRequest::StepIn)
+        );
+        assert_eq!(
+            stop(
+                &mut recorder,
+                StopReason::Step,
+                vec![frame(1, 10), frame(0, 3)]
+            ),
+            Some(DebuggerRequest::StepIn)
+        );
+        assert_eq!(
+            stop(&mut recorder, StopReason::Step, vec![frame(0, 4)]),
+            Some(DebuggerRequest::StepIn)
+        );
+
+        // Nothing can be looked at until the frame is over.
+        assert!(recorder.current().is_none());
+        assert_eq!(recorder.step_over(), None);
+        assert!(DebuggerConnection::is_paused(&mut recorder));
+        assert_eq!(recorder.current().unwrap().frames[0].line, 3);
+
+        assert_eq!(recorder.step_over(), Some(StopReason::Step));
+        assert_eq!(recorder.current().unwrap().frames[0].line, 4);
+
+        // Stepping past the end asks the debugger to stop again.
+        assert_eq!(recorder.step_over(), None);
+        assert!(!DebuggerConnection::is_paused(&mut recorder));
+        assert_eq!(recorder.try_recv(), Some(DebuggerRequest::Pause));
+        assert_eq!(recorder.try_recv(), None);
+    }
+
+    #[test]
+    fn steps_stop_at_breakpoints() {
+        let mut recorder = Recorder::new();
+        stop(&mut recorder, StopReason::Breakpoint, vec![frame(0, 3)]);
+        stop(
+            &mut recorder,
+            StopReason::FunctionBreakpoint,
+            vec![frame(1, 0), frame(0, 3)],
+        );
+        stop(&mut recorder, StopReason::Step, vec![frame(0, 4)]);
+        assert!(DebuggerConnection::is_paused(&mut recorder));
+
+        assert_eq!(recorder.step_over(), Some(StopReason::FunctionBreakpoint));
+        assert_eq!(recorder.current().unwrap().frames.len(), 2);
+        assert_eq!(recorder.resume(), None);
+        assert!(!DebuggerConnection::is_paused(&mut recorder));
+    }
+
+    #[test]
+    fn leftover_step_is_ignored() {
+        let mut recorder = Recorder::new();
+        assert_eq!(
+            stop(&mut recorder, StopReason::Step, vec![frame(0, 3)]),
+            None
+        );
+        assert!(!DebuggerConnection::is_paused(&mut recorder));
+    }
+
+    #[test]
+    fn hold_until_configured() {
+        let mut recorder = Recorder::waiting_for_configuration();
+        recorder.set_line_breakpoints("Main.as".to_string(), vec![3]);
+        assert!(DebuggerConnection::is_paused(&mut recorder));
+
+        recorder.configuration_done();
+        assert!(!DebuggerConnection::is_paused(&mut recorder));
+        assert_eq!(
+            recorder.try_recv(),
+            Some(DebuggerRequest::SetLineBreakpoints {
+                source: "Main.as".to_string(),
+                lines: vec![3],
+            })
+        );
+    }
+
+    #[test]
+    fn listener_hears_about_settled_recordings() {
+        let mut recorder = Recorder::new();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        recorder.set_listener(move |reason| {
+            let _ = sender.send(reason);
+        });
+
+        stop(&mut recorder, StopReason::Pause, vec![frame(0, 3)]);
+        assert!(receiver.try_recv().is_err());
+        assert!(DebuggerConnection::is_paused(&mut recorder));
+        assert_eq!(receiver.try_recv(), Ok(StopReason::Pause));
+
+        // Asking again doesn't repeat it.
+        assert!(DebuggerConnection::is_paused(&mut recorder));
+        assert!(receiver.try_recv().is_err());
+    }
+}
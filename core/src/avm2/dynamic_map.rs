@@ -3,7 +3,7 @@ use gc_arena::Collect;
 use hashbrown::raw::RawTable;
 use std::{cell::Cell, hash::Hash};
 
-use super::{string::AvmString, Object};
+use super::{object::WeakObject, string::AvmString, Object};
 
 #[derive(Debug, Collect, Copy, Clone)]
 #[collect(no_drop)]
@@ -22,6 +22,28 @@ pub enum DynamicKey<'gc> {
     // can be `number`
     Uint(u32),
     Object(Object<'gc>),
+    // Keys of a `Dictionary` created with `weakKeys` set, which don't keep
+    // the object alive. Entries are removed once the key is collected.
+    WeakObject(WeakObjectKey<'gc>),
+}
+
+/// A weak reference to an object, compared by identity.
+#[derive(Copy, Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct WeakObjectKey<'gc>(pub WeakObject<'gc>);
+
+impl PartialEq for WeakObjectKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0.as_ptr(), other.0.as_ptr())
+    }
+}
+
+impl Eq for WeakObjectKey<'_> {}
+
+impl Hash for WeakObjectKey<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state);
+    }
 }
 
 /// A HashMap designed for dynamic properties on an object.
@@ -106,6 +128,10 @@ impl<K: Eq + PartialEq + Hash, V> DynamicMap<K, V> {
         self.values.remove(key)
     }
 
+    pub fn retain(&mut self, f: impl FnMut(&K, &mut DynamicProperty<V>) -> bool) {
+        self.values.retain(f)
+    }
+
     pub fn next(&self, index: usize) -> Option<usize> {
         // Start iteration from the beginning
         if index == 0 {
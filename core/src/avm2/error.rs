@@ -220,6 +220,20 @@ pub fn make_error_1014<'gc>(
     }
 }
 
+#[inline(never)]
+#[cold]
+pub fn make_error_1019<'gc>(activation: &mut Activation<'_, 'gc>, index: usize) -> Error<'gc> {
+    let err = verify_error(
+        activation,
+        &format!("Error #1019: Getscopeobject {index} is out of bounds."),
+        1019,
+    );
+    match err {
+        Ok(err) => Error::avm_error(err),
+        Err(err) => err,
+    }
+}
+
 #[inline(never)]
 #[cold]
 pub fn make_error_1021<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
@@ -363,7 +377,7 @@ pub fn make_error_1054<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc>
 #[inline(never)]
 #[cold]
 pub fn make_error_1058<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
-    let err = verify_error(activation, "#1058: Illegal operand type.", 1058);
+    let err = verify_error(activation, "Error #1058: Illegal operand type.", 1058);
     match err {
         Ok(err) => Error::avm_error(err),
         Err(err) => err,
@@ -389,6 +403,31 @@ pub fn make_error_1065<'gc>(
     }
 }
 
+#[inline(never)]
+#[cold]
+pub fn make_error_1068<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    first: Option<Class<'gc>>,
+    second: Option<Class<'gc>>,
+) -> Error<'gc> {
+    let mc = activation.gc();
+    let type_name = |class: Option<Class<'gc>>| match class {
+        Some(class) => class.name().to_qualified_name_err_message(mc),
+        None => AvmString::new_utf8(mc, "*"),
+    };
+    let message = format!(
+        "Error #1068: {} and {} cannot be reconciled.",
+        type_name(first),
+        type_name(second),
+    );
+
+    let err = verify_error(activation, &message, 1068);
+    match err {
+        Ok(err) => Error::avm_error(err),
+        Err(err) => err,
+    }
+}
+
 #[inline(never)]
 #[cold]
 pub fn make_error_1080<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
@@ -801,6 +840,20 @@ pub fn make_error_2162<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc>
     }
 }
 
+#[inline(never)]
+#[cold]
+pub fn make_error_3694<'gc>(activation: &mut Activation<'_, 'gc>) -> Error<'gc> {
+    let err = error(
+        activation,
+        "Error #3694: The object was disposed by an earlier call of dispose() on it.",
+        3694,
+    );
+    match err {
+        Ok(err) => Error::avm_error(err),
+        Err(err) => err,
+    }
+}
+
 #[inline(never)]
 #[cold]
 pub fn range_error<'gc>(
@@ -1,5 +1,6 @@
 use crate::avm2::activation::Activation;
 use crate::avm2::class::Class;
+use crate::avm2::debugger::Debugger;
 use crate::avm2::error::{make_mismatch_error, Error};
 use crate::avm2::method::{Method, MethodKind, ParamConfig};
 use crate::avm2::object::{ClassObject, FunctionObject};
@@ -253,12 +254,14 @@ pub fn exec<'gc>(
             };
 
             activation.context.avm2.push_call(mc, method, bound_class);
+            Debugger::enter_frame(&mut activation, method, None);
 
             native_method(&mut activation, receiver, &arguments)
         }
         MethodKind::Bytecode { .. } => {
             // We must initialize the stack frame here so the lifetime works out
             let stack = activation.context.avm2.stack;
+            let locals_start = stack.stack_pointer();
             let stack_frame = stack.get_stack_frame(method);
 
             // This used to be a one step called Activation::from_method,
@@ -297,6 +300,7 @@ pub fn exec<'gc>(
             };
 
             activation.context.avm2.push_call(mc, method, bound_class);
+            Debugger::enter_frame(&mut activation, method, Some(locals_start));
 
             let result = activation.run_actions(method);
 
@@ -306,6 +310,7 @@ pub fn exec<'gc>(
         }
     };
     activation.context.avm2.pop_call(mc);
+    Debugger::exit_frame(activation.context.avm2);
     ret
 }
 
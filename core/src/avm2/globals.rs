@@ -158,6 +158,7 @@ pub struct SystemClasses<'gc> {
     pub shader: ClassObject<'gc>,
     pub shaderinput: ClassObject<'gc>,
     pub shaderparameter: ClassObject<'gc>,
+    pub shaderevent: ClassObject<'gc>,
     pub netstatusevent: ClassObject<'gc>,
     pub shaderfilter: ClassObject<'gc>,
     pub statusevent: ClassObject<'gc>,
@@ -211,6 +212,9 @@ pub struct SystemClassDefs<'gc> {
     pub graphicssolidfill: Class<'gc>,
     pub graphicsshaderfill: Class<'gc>,
     pub graphicsstroke: Class<'gc>,
+    pub jpegencoderoptions: Class<'gc>,
+    pub jpegxrencoderoptions: Class<'gc>,
+    pub pngencoderoptions: Class<'gc>,
     pub cubetexture: Class<'gc>,
     pub rectangletexture: Class<'gc>,
     pub display_object: Class<'gc>,
@@ -327,6 +331,7 @@ impl<'gc> SystemClasses<'gc> {
             shader: object,
             shaderinput: object,
             shaderparameter: object,
+            shaderevent: object,
             netstatusevent: object,
             shaderfilter: object,
             statusevent: object,
@@ -383,6 +388,9 @@ impl<'gc> SystemClassDefs<'gc> {
             graphicssolidfill: object,
             graphicsshaderfill: object,
             graphicsstroke: object,
+            jpegencoderoptions: object,
+            jpegxrencoderoptions: object,
+            pngencoderoptions: object,
             cubetexture: object,
             rectangletexture: object,
             display_object: object,
@@ -712,6 +720,7 @@ pub fn init_native_system_classes(activation: &mut Activation<'_, '_>) {
             ("flash.events", "AsyncErrorEvent", asyncerrorevent),
             ("flash.events", "ContextMenuEvent", contextmenuevent),
             ("flash.events", "FocusEvent", focusevent),
+            ("flash.events", "ShaderEvent", shaderevent),
             ("flash.geom", "Matrix", matrix),
             ("flash.geom", "Matrix3D", matrix3d),
             ("flash.geom", "PerspectiveProjection", perspectiveprojection),
@@ -782,6 +791,13 @@ pub fn init_native_system_classes(activation: &mut Activation<'_, '_>) {
             ),
             ("flash.display", "GraphicsSolidFill", graphicssolidfill),
             ("flash.display", "GraphicsStroke", graphicsstroke),
+            ("flash.display", "JPEGEncoderOptions", jpegencoderoptions),
+            (
+                "flash.display",
+                "JPEGXREncoderOptions",
+                jpegxrencoderoptions
+            ),
+            ("flash.display", "PNGEncoderOptions", pngencoderoptions),
             ("flash.display", "Sprite", sprite),
             ("flash.display3D.textures", "CubeTexture", cubetexture),
             (
@@ -96,13 +96,8 @@ package flash.display {
         public native function merge(
             sourceBitmapData:BitmapData, sourceRect:Rectangle, destPoint:Point, redMultiplier:uint, greenMultiplier:uint, blueMultiplier:uint, alphaMultiplier:uint
         ):void;
-        public function generateFilterRect(sourceRect:Rectangle, filter:BitmapFilter):Rectangle {
-            // Flash always reports that a ShaderFilter affects the entire BitmapData, ignoring sourceRect.
-            if (filter is ShaderFilter) {
-                return this.rect.clone();
-            }
-            stub_method("flash.display.BitmapData", "generateFilterRect");
-            return sourceRect.clone();
-        }
+        [API("680")]
+        public native function encode(rect:Rectangle, compressor:Object, byteArray:ByteArray = null):ByteArray;
+        public native function generateFilterRect(sourceRect:Rectangle, filter:BitmapFilter):Rectangle;
     }
 }
@@ -1,7 +1,6 @@
 package flash.display
 {
     import flash.geom.Matrix;
-    import __ruffle__.stub_method;
 
     [Ruffle(Abstract)]
     public final class Graphics
@@ -17,9 +16,7 @@ package flash.display
         public native function beginGradientFill(
             type:String, colors:Array, alphas:Array, ratios:Array, matrix:Matrix = null, spreadMethod:String = "pad", interpolationMethod:String = "rgb", focalPointRatio:Number = 0
         ): void;
-        public function beginShaderFill(shader:Shader, matrix:Matrix = null):void {
-            stub_method("flash.display.Graphics", "beginShaderFill");
-        }
+        public native function beginShaderFill(shader:Shader, matrix:Matrix = null):void;
         [Ruffle(FastCall)]
         public native function clear(): void;
         public native function curveTo(controlX:Number, controlY:Number, anchorX:Number, anchorY:Number): void;
@@ -36,7 +33,6 @@ package flash.display
         public native function lineTo(x:Number, y:Number): void;
         [Ruffle(FastCall)]
         public native function moveTo(x:Number, y:Number): void;
-        //public native function beginShaderFill(shader:Shader, matrix:Matrix = null):void;
         public native function lineGradientStyle(
             type:String, colors:Array, alphas:Array, ratios:Array, matrix:Matrix = null, spreadMethod:String = "pad", interpolationMethod:String = "rgb", focalPointRatio:Number = 0
         ):void;
@@ -2,8 +2,10 @@ package flash.display {
     import flash.geom.Matrix;
 
     public final class GraphicsShaderFill implements IGraphicsFill, IGraphicsData {
+        [Ruffle(NativeAccessible)]
         public var shader:Shader;
 
+        [Ruffle(NativeAccessible)]
         public var matrix:Matrix;
 
         public function GraphicsShaderFill(shader:Shader = null, matrix:Matrix = null) {
@@ -1,6 +1,4 @@
 package flash.display {
-    import __ruffle__.stub_method;
-    import __ruffle__.stub_getter;
     import __ruffle__.stub_constructor;
     import flash.events.EventDispatcher;
 
@@ -17,6 +15,9 @@ package flash.display {
         [Ruffle(NativeAccessible)]
         private var _height:int;
 
+        [Ruffle(NativeAccessible)]
+        private var _progress:Number = 0;
+
         public function ShaderJob(shader:Shader = null, target:Object = null, width:int = 0, height:int = 0) {
             this._shader = shader;
             this._target = target;
@@ -25,9 +26,7 @@ package flash.display {
             stub_constructor("flash.display.ShaderJob");
         }
 
-        public function cancel():void {
-            stub_method("flash.display.ShaderJob", "cancel")
-        }
+        public native function cancel():void;
 
         public native function start(waitForCompletion:Boolean = false):void;
 
@@ -48,8 +47,7 @@ package flash.display {
         }
 
         public function get progress():Number {
-            stub_getter("flash.display.ShaderJob", "progress")
-            return 0;
+            return this._progress;
         }
 
         public function get shader():Shader {
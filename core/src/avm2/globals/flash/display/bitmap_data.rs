@@ -7,6 +7,8 @@ use crate::avm2::error::{
 };
 use crate::avm2::filters::FilterAvm2Ext;
 use crate::avm2::globals::slots::{
+    flash_display_jpeg_encoder_options as jpeg_encoder_options_slots,
+    flash_display_png_encoder_options as png_encoder_options_slots,
     flash_geom_point as point_slots, flash_geom_rectangle as rectangle_slots,
 };
 pub use crate::avm2::object::bitmap_data_allocator;
@@ -18,6 +20,7 @@ use crate::avm2::Error;
 use crate::avm2_stub_method;
 use crate::bitmap::bitmap_data::{BitmapData, ChannelOptions, ThresholdOperation};
 use crate::bitmap::bitmap_data::{BitmapDataDrawError, IBitmapDrawable};
+use crate::bitmap::encode::{encode_rgba, BitmapEncoding};
 use crate::bitmap::{is_size_valid, operations};
 use crate::character::{Character, CompressedBitmap};
 use crate::ecma_conversions::round_to_even;
@@ -1144,6 +1147,51 @@ pub fn get_rect<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implement `BitmapData.generateFilterRect`
+pub fn generate_filter_rect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(bitmap_data) = this.as_bitmap_data() {
+        bitmap_data.check_valid(activation)?;
+        let source_rect = args.get_object(activation, 0, "sourceRect")?;
+        let filter = args.get_object(activation, 1, "filter")?;
+        let filter = Filter::from_avm2_object(activation, filter)?;
+
+        let dest_rect = if matches!(filter, Filter::ShaderFilter(_)) {
+            // Flash always reports that a ShaderFilter affects the entire BitmapData, ignoring sourceRect.
+            Rectangle {
+                x_min: 0,
+                x_max: bitmap_data.width() as i32,
+                y_min: 0,
+                y_max: bitmap_data.height() as i32,
+            }
+        } else {
+            let (x, y, width, height) = get_rectangle_x_y_width_height(activation, source_rect)?;
+            filter.calculate_dest_pixel_rect(Rectangle {
+                x_min: x,
+                x_max: x.saturating_add(width),
+                y_min: y,
+                y_max: y.saturating_add(height),
+            })
+        };
+
+        return activation.avm2().classes().rectangle.construct(
+            activation,
+            &[
+                dest_rect.x_min.into(),
+                dest_rect.y_min.into(),
+                dest_rect.width().into(),
+                dest_rect.height().into(),
+            ],
+        );
+    }
+    Ok(Value::Undefined)
+}
+
 /// Implement `BitmapData.applyFilter`
 pub fn apply_filter<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -1638,3 +1686,84 @@ pub fn merge<'gc>(
 
     Ok(Value::Undefined)
 }
+
+/// Implements `BitmapData.encode`.
+pub fn encode<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let Some(bitmap_data) = this.as_bitmap_data() else {
+        return Ok(Value::Null);
+    };
+    bitmap_data.check_valid(activation)?;
+    let rectangle = args.get_object(activation, 0, "rect")?;
+    let compressor = args.get_object(activation, 1, "compressor")?;
+
+    let class = compressor.instance_class();
+    let class_defs = activation.avm2().class_defs();
+    let encoding = if class == class_defs.pngencoderoptions {
+        BitmapEncoding::Png {
+            fast_compression: compressor
+                .get_slot(png_encoder_options_slots::FAST_COMPRESSION)
+                .coerce_to_boolean(),
+        }
+    } else if class == class_defs.jpegencoderoptions {
+        let quality = compressor
+            .get_slot(jpeg_encoder_options_slots::QUALITY)
+            .coerce_to_u32(activation)?;
+        BitmapEncoding::Jpeg {
+            quality: quality.clamp(1, 100) as u8,
+        }
+    } else if class == class_defs.jpegxrencoderoptions {
+        // There is no JPEG XR encoder available.
+        avm2_stub_method!(
+            activation,
+            "flash.display.BitmapData",
+            "encode",
+            "with JPEGXREncoderOptions"
+        );
+        return Ok(Value::Null);
+    } else {
+        return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+    };
+
+    let (x, y, width, height) = get_rectangle_x_y_width_height(activation, rectangle)?;
+    let (width, height, pixels) = operations::get_rgba_pixels(
+        activation.context.renderer,
+        bitmap_data,
+        x,
+        y,
+        width,
+        height,
+    );
+    if width == 0 || height == 0 {
+        return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+    }
+
+    let encoded = match encode_rgba(encoding, width, height, bitmap_data.transparency(), &pixels) {
+        Ok(encoded) => encoded,
+        Err(e) => {
+            tracing::warn!("Failed to encode BitmapData: {e}");
+            return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+        }
+    };
+
+    // The image is written at the current position of the given ByteArray, if there is one.
+    let bytearray = match args.try_get_object(2) {
+        Some(bytearray) => bytearray,
+        None => {
+            ByteArrayObject::from_storage(activation, ByteArrayStorage::new(activation.context))
+                .into()
+        }
+    };
+    bytearray
+        .as_bytearray_mut()
+        .unwrap()
+        .write_bytes(&encoded)
+        .map_err(|e| e.to_avm(activation))?;
+
+    Ok(bytearray.into())
+}
@@ -5,10 +5,12 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::error::{make_error_2004, make_error_2007, make_error_2008, Error2004Type};
+use crate::avm2::globals::flash::display::shader_job::get_shader_args;
 use crate::avm2::globals::flash::geom::transform::object_to_matrix;
 use crate::avm2::globals::slots::flash_display_graphics_bitmap_fill as graphics_bitmap_fill_slots;
 use crate::avm2::globals::slots::flash_display_graphics_gradient_fill as graphics_gradient_fill_slots;
 use crate::avm2::globals::slots::flash_display_graphics_path as graphics_path_slots;
+use crate::avm2::globals::slots::flash_display_graphics_shader_fill as graphics_shader_fill_slots;
 use crate::avm2::globals::slots::flash_display_graphics_solid_fill as graphics_solid_fill_slots;
 use crate::avm2::globals::slots::flash_display_graphics_stroke as graphics_stroke_slots;
 use crate::avm2::globals::slots::flash_display_graphics_triangle_path as graphics_triangle_path_slots;
@@ -21,6 +23,7 @@ use crate::avm2_stub_method;
 use crate::display_object::TDisplayObject;
 use crate::drawing::Drawing;
 use crate::string::{AvmString, WStr};
+use ruffle_render::pixel_bender_support::ShaderFill;
 use ruffle_render::shape_utils::{DrawCommand, FillRule, GradientType};
 use std::f64::consts::FRAC_1_SQRT_2;
 use swf::{
@@ -102,6 +105,50 @@ pub fn begin_bitmap_fill<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implements `Graphics.beginShaderFill`.
+pub fn begin_shader_fill<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(this) = this.as_display_object() {
+        let shader = args.get_object(activation, 0, "shader")?;
+        let matrix = if let Some(matrix) = args.try_get_object(1) {
+            Matrix::from(object_to_matrix(matrix, activation)?)
+        } else {
+            // Users can explicitly pass in `null` to mean identity matrix
+            Matrix::IDENTITY
+        };
+
+        let fill = create_shader_fill(activation, shader, matrix)?;
+        if let Some(mut draw) = this.as_drawing() {
+            draw.set_shader_fill(fill);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn create_shader_fill<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    shader: Object<'gc>,
+    matrix: Matrix,
+) -> Result<ShaderFill, Error<'gc>> {
+    let (shader, arguments) = get_shader_args(shader, activation)?;
+    let scale_matrix = Matrix::scale(
+        (Twips::TWIPS_PER_PIXEL as i16).into(),
+        (Twips::TWIPS_PER_PIXEL as i16).into(),
+    );
+
+    Ok(ShaderFill {
+        shader,
+        arguments,
+        matrix: matrix * scale_matrix,
+    })
+}
+
 /// Implements `Graphics.beginGradientFill`.
 pub fn begin_gradient_fill<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -1515,8 +1562,8 @@ fn handle_igraphics_data<'gc>(
         let style = handle_solid_fill(activation, obj)?;
         drawing.set_fill_style(Some(style));
     } else if class == activation.avm2().class_defs().graphicsshaderfill {
-        tracing::warn!("Graphics shader fill unimplemented {:?}", class);
-        drawing.set_fill_style(None);
+        let fill = handle_shader_fill(activation, obj)?;
+        drawing.set_shader_fill(fill);
     } else if class == activation.avm2().class_defs().graphicsstroke {
         let thickness = obj
             .get_slot(graphics_stroke_slots::THICKNESS)
@@ -1755,6 +1802,28 @@ fn handle_gradient_fill<'gc>(
     Ok(fill)
 }
 
+fn handle_shader_fill<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    obj: &Object<'gc>,
+) -> Result<ShaderFill, Error<'gc>> {
+    let shader = obj
+        .get_slot(graphics_shader_fill_slots::SHADER)
+        .as_object()
+        .ok_or_else(|| make_error_2007(activation, "shader"))?;
+
+    let matrix = obj
+        .get_slot(graphics_shader_fill_slots::MATRIX)
+        .as_object()
+        .and_then(|matrix| {
+            let matrix = Matrix::from(object_to_matrix(matrix, activation).ok()?);
+
+            Some(matrix)
+        })
+        .unwrap_or(Matrix::IDENTITY);
+
+    create_shader_fill(activation, shader, matrix)
+}
+
 fn handle_bitmap_fill<'gc>(
     activation: &mut Activation<'_, 'gc>,
     drawing: &mut Drawing,
@@ -1,23 +1,28 @@
 use crate::avm2::bytearray::Endian;
-use crate::avm2::error::make_error_2162;
+use crate::avm2::error::{make_error_2004, make_error_2007, make_error_2162, Error2004Type};
 use crate::avm2::globals::slots::{
     flash_display_shader as shader_slots, flash_display_shader_input as shader_input_slots,
     flash_display_shader_job as shader_job_slots,
     flash_display_shader_parameter as shader_parameter_slots,
 };
 use crate::avm2::parameters::ParametersExt;
-use crate::avm2::{Activation, Error, Object, TObject as _, Value};
+use crate::avm2::{Activation, Avm2, Error, EventObject, Object, TObject as _, Value};
+use crate::avm2_stub_method;
+use crate::bitmap::bitmap_data::Color;
+use crate::context::UpdateContext;
 use crate::pixel_bender::PixelBenderTypeExt;
 use crate::string::AvmString;
-
-use crate::avm2_stub_method;
+use gc_arena::Collect;
+use ruffle_macros::istr;
 
 use ruffle_render::backend::{PixelBenderOutput, PixelBenderTarget};
 use ruffle_render::bitmap::PixelRegion;
+use ruffle_render::error::Error as RenderError;
 use ruffle_render::pixel_bender::{
     PixelBenderParam, PixelBenderParamQualifier, PixelBenderShaderHandle, PixelBenderType,
     OUT_COORD_NAME,
 };
+use ruffle_render::pixel_bender_interpreter::{self, ShaderMode};
 use ruffle_render::pixel_bender_support::{ImageInputTexture, PixelBenderShaderArgument};
 
 pub fn get_shader_args<'gc>(
@@ -30,20 +35,25 @@ pub fn get_shader_args<'gc>(
     ),
     Error<'gc>,
 > {
-    // FIXME - determine what errors Flash Player throws here
-    // instead of using `expect`
     let shader_data = shader_obj
         .get_slot(shader_slots::_DATA)
         .as_object()
-        .expect("Missing ShaderData object")
-        .as_shader_data()
-        .expect("ShaderData object is not a ShaderData instance");
-
-    let shader_handle = shader_data.pixel_bender_shader();
-    let shader_handle = shader_handle
-        .as_ref()
-        .expect("ShaderData object has no shader");
+        .and_then(|data| data.as_shader_data());
+    let Some((shader_data, shader_handle)) =
+        shader_data.and_then(|data| Some((data, data.pixel_bender_shader()?)))
+    else {
+        // This happens for a `Shader` constructed without bytecode. The exact error
+        // Flash Player throws here hasn't been confirmed, so report it as a stub.
+        avm2_stub_method!(
+            activation,
+            "flash.display.Shader",
+            "data",
+            "using a Shader without bytecode"
+        );
+        return Err(make_error_2004(activation, Error2004Type::ArgumentError));
+    };
     let shader = shader_handle.0.parsed_shader();
+    let is_cpu_shader = pixel_bender_interpreter::is_cpu_shader(&shader_handle);
 
     let args = shader
         .params
@@ -130,9 +140,23 @@ pub fn get_shader_args<'gc>(
 
                     let texture = if let Some(input) = input.as_object() {
                         let input_texture = if let Some(bitmap) = input.as_bitmap_data() {
-                            ImageInputTexture::Bitmap(
-                                bitmap.bitmap_handle(activation.gc(), activation.context.renderer),
-                            )
+                            if is_cpu_shader {
+                                // Shaders run on the CPU can't read from a `BitmapHandle`.
+                                let bitmap = bitmap.sync(activation.context.renderer);
+                                let bitmap = bitmap.borrow();
+                                pixel_bender_interpreter::image_input_from_rgba(
+                                    bitmap.width(),
+                                    bitmap.height(),
+                                    bitmap.pixels_rgba(),
+                                )
+                            } else {
+                                ImageInputTexture::Bitmap(
+                                    bitmap.bitmap_handle(
+                                        activation.gc(),
+                                        activation.context.renderer,
+                                    ),
+                                )
+                            }
                         } else if let Some(byte_array) = input.as_bytearray() {
                             let expected_len = (width * height * input_channels) as usize
                                 * std::mem::size_of::<f32>();
@@ -175,7 +199,64 @@ pub fn get_shader_args<'gc>(
             }
         })
         .collect::<Result<Vec<PixelBenderShaderArgument<'_>>, Error<'gc>>>()?;
-    Ok((shader_handle.clone(), args))
+    Ok((shader_handle, args))
+}
+
+/// The number of pixels that an asynchronous `ShaderJob` run on the CPU processes per frame.
+const CPU_PIXELS_PER_FRAME: u32 = 64 * 1024;
+
+/// A `ShaderJob` started with `waitForCompletion` set to `false`,
+/// whose `complete` event hasn't been dispatched yet.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct PendingShaderJob<'gc> {
+    job: Object<'gc>,
+    target: Object<'gc>,
+    /// The work left to do before the output can be written to the target.
+    /// It is discarded if the job is cancelled, leaving the target untouched.
+    #[collect(require_static)]
+    work: PendingWork,
+}
+
+enum PendingWork {
+    /// The render backend has run the shader, and its output still needs to be written
+    /// to the target.
+    Output(PixelBenderOutput),
+    /// The shader is run on the CPU a few rows at a time.
+    Rows(CpuShaderRun),
+}
+
+/// A shader run by `pixel_bender_interpreter` over several frames, so that the job's
+/// `progress` increases as rows are completed.
+struct CpuShaderRun {
+    shader: PixelBenderShaderHandle,
+    arguments: Vec<PixelBenderShaderArgument<'static>>,
+    width: u32,
+    height: u32,
+    next_row: u32,
+    output: Vec<u8>,
+}
+
+impl CpuShaderRun {
+    /// Runs the shader for the next rows, returning `true` once every row is done.
+    fn run_next_rows(&mut self) -> Result<bool, RenderError> {
+        let rows = (CPU_PIXELS_PER_FRAME / self.width.max(1)).max(1);
+        let end = self.next_row.saturating_add(rows).min(self.height);
+        let output = pixel_bender_interpreter::run_shader_rows(
+            self.shader.0.parsed_shader(),
+            &self.arguments,
+            self.width,
+            self.next_row..end,
+            ShaderMode::ShaderJob,
+        )?;
+        self.output.extend(output);
+        self.next_row = end;
+        Ok(self.next_row >= self.height)
+    }
+
+    fn progress(&self) -> f64 {
+        f64::from(self.next_row) / f64::from(self.height.max(1))
+    }
 }
 
 /// Implements `ShaderJob.start`.
@@ -187,47 +268,39 @@ pub fn start<'gc>(
     let this = this.as_object().unwrap();
 
     let wait_for_completion = args.get_bool(0);
-    if !wait_for_completion {
+
+    // Restarting a job discards the output of the previous run.
+    cancel_pending(activation, this);
+
+    let Some(shader) = this.get_slot(shader_job_slots::_SHADER).as_object() else {
+        return Err(make_error_2007(activation, "shader"));
+    };
+
+    let (shader_handle, arguments) = get_shader_args(shader, activation)?;
+
+    let Some(target) = this.get_slot(shader_job_slots::_TARGET).as_object() else {
+        return Err(make_error_2007(activation, "target"));
+    };
+    if target.as_bitmap_data().is_none()
+        && target.as_bytearray().is_none()
+        && target.as_vector_storage().is_none()
+    {
+        // The exact error Flash Player throws for other targets hasn't been confirmed.
         avm2_stub_method!(
             activation,
             "flash.display.ShaderJob",
             "start",
-            "with waitForCompletion=false"
+            "with a target which isn't a BitmapData, ByteArray or Vector.<Number>"
         );
+        return Err(make_error_2004(activation, Error2004Type::ArgumentError));
     }
-    let shader = this
-        .get_slot(shader_job_slots::_SHADER)
-        .as_object()
-        .expect("Missing Shader object");
-
-    let (shader_handle, arguments) = get_shader_args(shader, activation)?;
-
-    let target = this
-        .get_slot(shader_job_slots::_TARGET)
-        .as_object()
-        .expect("ShaderJob.target is not an object");
 
-    let output_width = this.get_slot(shader_job_slots::_WIDTH).as_u32();
-
-    let output_height = this.get_slot(shader_job_slots::_HEIGHT).as_u32();
-
-    let pixel_bender_target = if let Some(bitmap) = target.as_bitmap_data() {
-        let target_bitmap = bitmap.sync(activation.context.renderer);
-        // Perform both a GPU->CPU and CPU->GPU sync before writing to it.
-        // FIXME - are both necessary?
-        let mut target_bitmap_data = target_bitmap.borrow_mut(activation.gc());
-        target_bitmap_data.update_dirty_texture(activation.context.renderer);
-
-        PixelBenderTarget::Bitmap(
-            target_bitmap_data
-                .bitmap_handle(activation.context.renderer)
-                .expect("Missing handle"),
-        )
-    } else {
-        PixelBenderTarget::Bytes {
-            width: output_width,
-            height: output_height,
-        }
+    let (width, height) = match target.as_bitmap_data() {
+        Some(bitmap) => (bitmap.width(), bitmap.height()),
+        None => (
+            this.get_slot(shader_job_slots::_WIDTH).as_u32(),
+            this.get_slot(shader_job_slots::_HEIGHT).as_u32(),
+        ),
     };
 
     match shader_handle.0.parsed_shader().output_channels() {
@@ -240,14 +313,65 @@ pub fn start<'gc>(
         }
     }
 
-    let output = activation
-        .context
-        .renderer
-        .run_pixelbender_shader(shader_handle, &arguments, &pixel_bender_target)
-        .expect("Failed to run shader");
+    this.set_slot(shader_job_slots::_PROGRESS, 0.0.into(), activation)?;
 
-    match output {
+    let is_cpu_shader = pixel_bender_interpreter::is_cpu_shader(&shader_handle);
+    if !wait_for_completion && is_cpu_shader {
+        activation
+            .avm2()
+            .pending_shader_jobs
+            .push(PendingShaderJob {
+                job: this,
+                target,
+                work: PendingWork::Rows(CpuShaderRun {
+                    shader: shader_handle,
+                    arguments,
+                    width,
+                    height,
+                    next_row: 0,
+                    output: Vec::new(),
+                }),
+            });
+        return Ok(Value::Undefined);
+    }
+
+    let pixel_bender_target = match target.as_bitmap_data() {
+        // Shaders run on the CPU can't write to a `BitmapHandle`, and asynchronous jobs
+        // must leave the target untouched until they complete (they can still be
+        // cancelled), so their output is staged and copied into the target afterwards.
+        Some(bitmap) if wait_for_completion && !is_cpu_shader => {
+            let target_bitmap = bitmap.sync(activation.context.renderer);
+            // Perform both a GPU->CPU and CPU->GPU sync before writing to it.
+            // FIXME - are both necessary?
+            let mut target_bitmap_data = target_bitmap.borrow_mut(activation.gc());
+            target_bitmap_data.update_dirty_texture(activation.context.renderer);
+
+            PixelBenderTarget::Bitmap(
+                target_bitmap_data
+                    .bitmap_handle(activation.context.renderer)
+                    .expect("Missing handle"),
+            )
+        }
+        _ => PixelBenderTarget::Bytes { width, height },
+    };
+
+    let output = match activation.context.renderer.run_pixelbender_shader(
+        shader_handle,
+        &arguments,
+        &pixel_bender_target,
+    ) {
+        Ok(output) => output,
+        Err(e) => {
+            tracing::warn!("Failed to run shader: {e}");
+            return Err(make_error_2004(activation, Error2004Type::Error));
+        }
+    };
+
+    let output = match output {
         PixelBenderOutput::Bitmap(sync_handle) => {
+            // The shader is already writing to the texture of the target, so the
+            // CPU-side pixels need to be marked as outdated right away. This only
+            // happens for synchronous jobs, which can't be cancelled.
             let target_bitmap = target
                 .as_bitmap_data()
                 .unwrap()
@@ -260,21 +384,169 @@ pub fn start<'gc>(
                 sync_handle,
                 PixelRegion::for_whole_size(width, height),
             );
+            None
         }
-        PixelBenderOutput::Bytes(pixels) => {
-            if let Some(mut bytearray) = target.as_bytearray_mut() {
-                bytearray.write_at(&pixels, 0).unwrap();
-            } else if let Some(mut vector) = target.as_vector_storage_mut(activation.gc()) {
-                let new_storage: Vec<_> = bytemuck::cast_slice::<u8, f32>(&pixels)
-                    .iter()
-                    .map(|p| Value::from(*p as f64))
-                    .collect();
-                vector.replace_storage(new_storage);
-            } else {
-                panic!("Unexpected target object {target:?}");
+        output => Some(output),
+    };
+
+    match output {
+        Some(output) if !wait_for_completion => {
+            activation
+                .avm2()
+                .pending_shader_jobs
+                .push(PendingShaderJob {
+                    job: this,
+                    target,
+                    work: PendingWork::Output(output),
+                });
+        }
+        output => {
+            if let Some(output) = output {
+                if let Err(e) = write_output(activation, target, output) {
+                    tracing::warn!("Failed to read back shader output: {e}");
+                    return Err(make_error_2004(activation, Error2004Type::Error));
+                }
             }
+            this.set_slot(shader_job_slots::_PROGRESS, 1.0.into(), activation)?;
         }
     }
 
     Ok(Value::Undefined)
 }
+
+/// Implements `ShaderJob.cancel`.
+pub fn cancel<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+    cancel_pending(activation, this);
+    Ok(Value::Undefined)
+}
+
+fn cancel_pending<'gc>(activation: &mut Activation<'_, 'gc>, job: Object<'gc>) {
+    activation
+        .avm2()
+        .pending_shader_jobs
+        .retain(|pending| !Object::ptr_eq(pending.job, job));
+}
+
+/// Writes the output of a shader to a `ByteArray` or `Vector.<Number>` target (or a
+/// `BitmapData` target for shaders run on the CPU and asynchronous jobs), waiting for
+/// the render backend to finish running the shader if needed.
+///
+/// The target must have been checked by `start`.
+fn write_output<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    target: Object<'gc>,
+    output: PixelBenderOutput,
+) -> Result<(), RenderError> {
+    let pixels = match output {
+        PixelBenderOutput::Bytes(pixels) => pixels,
+        PixelBenderOutput::PendingBytes(pending) => pending.resolve(activation.context.renderer)?,
+        PixelBenderOutput::Bitmap(_) => unreachable!("Bitmap outputs are handled in start"),
+    };
+
+    if let Some(bitmap) = target.as_bitmap_data() {
+        let target_bitmap = bitmap.sync(activation.context.renderer);
+        let mut write = target_bitmap.borrow_mut(activation.gc());
+        let region = PixelRegion::for_whole_size(write.width(), write.height());
+        let pixel_count = region.width() as usize * region.height() as usize;
+        let channels = pixels.len() / std::mem::size_of::<f32>() / pixel_count.max(1);
+        let rgba = pixel_bender_interpreter::output_to_rgba(&pixels, channels);
+        for (pixel, rgba) in write.raw_pixels_mut().iter_mut().zip(rgba.chunks_exact(4)) {
+            *pixel = Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3]);
+        }
+        write.set_cpu_dirty(activation.gc(), region);
+    } else if let Some(mut bytearray) = target.as_bytearray_mut() {
+        bytearray.write_at(&pixels, 0).unwrap();
+    } else if let Some(mut vector) = target.as_vector_storage_mut(activation.gc()) {
+        let new_storage: Vec<_> = bytemuck::cast_slice::<u8, f32>(&pixels)
+            .iter()
+            .map(|p| Value::from(*p as f64))
+            .collect();
+        vector.replace_storage(new_storage);
+    }
+    Ok(())
+}
+
+/// Advances all pending `ShaderJob`s, updating their `progress`. Completed jobs write
+/// their output to their targets and dispatch `ShaderEvent.COMPLETE`.
+///
+/// This is called once per frame, so jobs started with `waitForCompletion` set to
+/// `false` complete on the frame after they were started at the earliest. Jobs that
+/// are run on the CPU take longer for large targets, see `CPU_PIXELS_PER_FRAME`.
+pub fn run_pending_jobs<'gc>(context: &mut UpdateContext<'gc>) {
+    if context.avm2.pending_shader_jobs.is_empty() {
+        return;
+    }
+
+    let mut activation = Activation::from_nothing(context);
+
+    // Write all of the outputs before running any event handlers, since
+    // the handlers might start or cancel other jobs.
+    let jobs = std::mem::take(&mut activation.avm2().pending_shader_jobs);
+    let mut completed = Vec::with_capacity(jobs.len());
+    for PendingShaderJob { job, target, work } in jobs {
+        let output = match work {
+            PendingWork::Output(output) => output,
+            PendingWork::Rows(mut run) => match run.run_next_rows() {
+                Ok(true) => PixelBenderOutput::Bytes(run.output),
+                Ok(false) => {
+                    job.set_slot_no_coerce(
+                        shader_job_slots::_PROGRESS,
+                        run.progress().into(),
+                        activation.gc(),
+                    );
+                    activation
+                        .avm2()
+                        .pending_shader_jobs
+                        .push(PendingShaderJob {
+                            job,
+                            target,
+                            work: PendingWork::Rows(run),
+                        });
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to run shader: {e}");
+                    continue;
+                }
+            },
+        };
+        if let Err(e) = write_output(&mut activation, target, output) {
+            tracing::warn!("Failed to read back shader output: {e}");
+            continue;
+        }
+        job.set_slot_no_coerce(shader_job_slots::_PROGRESS, 1.0.into(), activation.gc());
+        completed.push((job, target));
+    }
+
+    for (job, target) in completed {
+        let (bitmap, byte_array, vector) = if target.as_bitmap_data().is_some() {
+            (target.into(), Value::Null, Value::Null)
+        } else if target.as_bytearray().is_some() {
+            (Value::Null, target.into(), Value::Null)
+        } else {
+            (Value::Null, Value::Null, target.into())
+        };
+
+        let shader_event_cls = activation.avm2().classes().shaderevent;
+        let event = EventObject::from_class_and_args(
+            &mut activation,
+            shader_event_cls,
+            &[
+                istr!("complete").into(),
+                // bubbles
+                false.into(),
+                // cancelable
+                false.into(),
+                bitmap,
+                byte_array,
+                vector,
+            ],
+        );
+        Avm2::dispatch_event(activation.context, event, job);
+    }
+}
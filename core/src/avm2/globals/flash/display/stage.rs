@@ -1,17 +1,17 @@
 //! `flash.display.Stage` builtin/prototype
 
 use crate::avm2::activation::Activation;
-use crate::avm2::error::make_error_2008;
+use crate::avm2::error::{illegal_operation_error, make_error_2008};
 use crate::avm2::object::VectorObject;
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::value::Value;
 use crate::avm2::vector::VectorStorage;
 use crate::avm2::Error;
-use crate::avm2_stub_getter;
 use crate::display_object::{
     StageDisplayState, TDisplayObject, TDisplayObjectContainer, TInteractiveObject,
 };
 use crate::string::{AvmString, WString};
+use crate::{avm2_stub_getter, avm2_stub_method};
 use swf::Color;
 
 /// Implement `align`'s getter
@@ -172,6 +172,43 @@ pub fn set_display_state<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Implement `mouseLock`'s getter
+pub fn get_mouse_lock<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(activation.context.stage.mouse_lock().into())
+}
+
+/// Implement `mouseLock`'s setter
+pub fn set_mouse_lock<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let locked = args.get_bool(0);
+    if !activation
+        .context
+        .stage
+        .set_mouse_lock(activation.context, locked)
+    {
+        // The error id Flash Player uses here hasn't been confirmed yet.
+        avm2_stub_method!(
+            activation,
+            "flash.display.Stage",
+            "mouseLock",
+            "error id when locking outside of full screen"
+        );
+        return Err(Error::avm_error(illegal_operation_error(
+            activation,
+            "Mouse lock is only available in full screen mode.",
+            0,
+        )?));
+    }
+    Ok(Value::Undefined)
+}
+
 /// Implement `focus`'s getter
 pub fn get_focus<'gc>(
     activation: &mut Activation<'_, 'gc>,
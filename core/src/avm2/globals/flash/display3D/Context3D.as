@@ -1,4 +1,5 @@
 package flash.display3D {
+    import flash.display.Stage3D;
     import flash.events.EventDispatcher;
     import flash.geom.Matrix3D;
     import flash.geom.Rectangle;
@@ -6,8 +7,6 @@ package flash.display3D {
     import flash.display3D.textures.TextureBase;
     import flash.display3D.textures.RectangleTexture;
     import flash.display3D.textures.Texture;
-    import __ruffle__.stub_method;
-    import __ruffle__.stub_getter;
 
     [API("674")]
     [Ruffle(Abstract)]
@@ -29,10 +28,7 @@ package flash.display3D {
         public native function createCubeTexture(size:int, format:String, optimizeForRenderToTexture:Boolean, streamingLevels:int = 0):CubeTexture;
         public native function createRectangleTexture(width:int, height:int, format:String, optimizeForRenderToTexture:Boolean):RectangleTexture;
 
-        public function get driverInfo():String {
-            stub_getter("flash.display3D.Context3D", "driverInfo");
-            return "Dummy Ruffle driver";
-        }
+        public native function get driverInfo():String;
 
         private var _enableErrorChecking:Boolean = false;
         public function get enableErrorChecking():Boolean {
@@ -58,35 +54,40 @@ package flash.display3D {
         public native function get profile():String;
 
         [API("700")]
-        public function get maxBackBufferWidth():int {
-            stub_getter("flash.display3D.Context3D", "maxBackBufferWidth");
-            return 2048;
-        }
+        public native function get maxBackBufferWidth():int;
+        [API("700")]
+        public native function set maxBackBufferWidth(width:int):void;
 
         [API("700")]
-        public function get maxBackBufferHeight():int {
-            stub_getter("flash.display3D.Context3D", "maxBackBufferHeight");
-            return 2048;
-        }
+        public native function get maxBackBufferHeight():int;
+        [API("700")]
+        public native function set maxBackBufferHeight(height:int):void;
 
-        public function setStencilReferenceValue(referenceValue:uint, readMask:uint = 255, writeMask:uint = 255):void {
-            stub_method("flash.display3D.Context3D", "setStencilReferenceValue");
-        }
+        public native function setStencilReferenceValue(referenceValue:uint, readMask:uint = 255, writeMask:uint = 255):void;
 
         public native function setSamplerStateAt(sampler:int, wrap:String, filter:String, mipfilter:String):void;
 
         public native function setRenderToTexture(texture:TextureBase, enableDepthAndStencil:Boolean = false, antiAlias:int = 0, surfaceSelector:int = 0, colorOutputIndex:int = 0):void;
 
-        public function setStencilActions(
+        public native function setStencilActions(
             triangleFace:String = "frontAndBack",
             compareMode:String = "always",
             actionOnBothPass:String = "keep",
             actionOnDepthFail:String = "keep",
             actionOnDepthPassStencilFail:String = "keep"
-        ):void {
-            stub_method("flash.display3D.Context3D", "setStencilActions");
+        ):void;
+
+        public function dispose(recreate:Boolean = true):void {
+            var stage3D:Stage3D = this.disposeInternal();
+            if (recreate && stage3D) {
+                // Flash Player treats this like a lost device, and `context3DCreate` is dispatched
+                // on the Stage3D again. `requestContext3D` creates the new context from a timer,
+                // so this happens after the current code has finished, not during `dispose`.
+                stage3D.requestContext3D("auto", this.profile);
+            }
         }
 
-        public native function dispose(recreate:Boolean = true):void;
+        // Returns the Stage3D that owned this context, or null if it was already disposed.
+        private native function disposeInternal():Stage3D;
     }
 }
@@ -1,5 +1,4 @@
 package flash.display3D {
-    import __ruffle__.stub_method;
     import flash.utils.ByteArray;
 
     [API("674")]
@@ -8,8 +7,6 @@ package flash.display3D {
         public native function uploadFromByteArray(data:ByteArray, byteArrayOffset:int, startOffset:int, count:int):void;
         public native function uploadFromVector(data:Vector.<uint>, startOffset:int, count:int):void;
 
-        public function dispose():void {
-            stub_method("flash.display3D.IndexBuffer3D", "dispose");
-        }
+        public native function dispose():void;
     }
 }
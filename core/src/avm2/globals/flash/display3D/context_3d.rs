@@ -5,14 +5,17 @@ use crate::avm2::globals::slots::flash_geom_rectangle as rectangle_slots;
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::Activation;
 use crate::avm2::Error;
+use crate::avm2::Object;
 use crate::avm2::TObject as _;
 use crate::avm2::Value;
 use crate::avm2_stub_method;
+use crate::avm2_stub_setter;
+use crate::string::AvmString;
 use ruffle_macros::istr;
 use ruffle_render::backend::Context3DWrapMode;
 use ruffle_render::backend::{
-    BufferUsage, Context3DBlendFactor, Context3DCompareMode, Context3DTextureFormat,
-    Context3DTriangleFace, Context3DVertexBufferFormat, ProgramType,
+    BufferUsage, Context3DBlendFactor, Context3DCompareMode, Context3DStencilAction,
+    Context3DTextureFormat, Context3DTriangleFace, Context3DVertexBufferFormat, ProgramType,
 };
 use ruffle_render::backend::{Context3DProfile, Context3DTextureFilter};
 use swf::{Rectangle, Twips};
@@ -25,6 +28,8 @@ pub fn create_index_buffer<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // FIXME - get bufferUsage and pass it through
         let num_indices = args.get_u32(0);
 
@@ -49,6 +54,8 @@ pub fn create_vertex_buffer<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // FIXME - get bufferUsage and pass it through
         let num_vertices = args.get_u32(0);
         let data_32_per_vertex = args.get_u32(1);
@@ -85,6 +92,8 @@ pub fn configure_back_buffer<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         let width = args.get_u32(0);
         let height = args.get_u32(1);
         let anti_alias = args.get_u32(2);
@@ -139,6 +148,16 @@ pub fn configure_back_buffer<'gc>(
                 "wantsBestResolutionOnBrowserZoom"
             );
         }
+        // We don't know yet what Flash Player does with a back buffer larger than
+        // `maxBackBufferWidth` and `maxBackBufferHeight`, so it is created as requested.
+        if width > context.max_back_buffer_width() || height > context.max_back_buffer_height() {
+            avm2_stub_method!(
+                activation,
+                "flash.display3D.Context3D",
+                "configureBackBuffer",
+                "size above maxBackBufferWidth or maxBackBufferHeight"
+            );
+        }
 
         context.configure_back_buffer(
             width,
@@ -160,6 +179,8 @@ pub fn set_vertex_buffer_at<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         let index = args.get_u32(0);
         let buffer = args.try_get_object(1);
 
@@ -185,7 +206,9 @@ pub fn set_vertex_buffer_at<'gc>(
                 )?));
             };
 
-            Some((buffer.as_vertex_buffer().unwrap(), format))
+            let buffer = buffer.as_vertex_buffer().unwrap();
+            buffer.check_disposed(activation)?;
+            Some((buffer, format))
         } else {
             None
         };
@@ -205,20 +228,27 @@ pub fn create_program<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         return Ok(context.create_program(activation));
     }
     Ok(Value::Undefined)
 }
 
 pub fn set_program<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         let program = args.try_get_object(0).map(|p| p.as_program_3d().unwrap());
+        if let Some(program) = program {
+            program.check_disposed(activation)?;
+        }
         context.set_program(program);
     }
     Ok(Value::Undefined)
@@ -232,10 +262,13 @@ pub fn draw_triangles<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         let index_buffer = args
             .get_object(activation, 0, "indexBuffer")?
             .as_index_buffer()
             .unwrap();
+        index_buffer.check_disposed(activation)?;
 
         let first_index = args.get_u32(1);
         let num_triangles = args.get_u32(2) as i32;
@@ -246,13 +279,15 @@ pub fn draw_triangles<'gc>(
 }
 
 pub fn present<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         context.present();
     }
     Ok(Value::Undefined)
@@ -266,7 +301,7 @@ pub fn get_profile<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
-        let profile = match context.with_context_3d(|context| context.profile()) {
+        let profile = match context.profile() {
             Context3DProfile::Baseline => istr!("baseline"),
             Context3DProfile::BaselineConstrained => istr!("baselineConstrained"),
             Context3DProfile::BaselineExtended => istr!("baselineExtended"),
@@ -288,20 +323,14 @@ pub fn set_culling<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         let culling = args.get_string(activation, 0);
 
-        let culling = if &*culling == b"none" {
-            Context3DTriangleFace::None
-        } else if &*culling == b"back" {
-            Context3DTriangleFace::Back
-        } else if &*culling == b"front" {
-            Context3DTriangleFace::Front
-        } else if &*culling == b"frontAndBack" {
-            Context3DTriangleFace::FrontAndBack
-        } else {
+        let culling = Context3DTriangleFace::from_wstr(&culling).unwrap_or_else(|| {
             tracing::error!("Unknown culling {:?}", culling);
             Context3DTriangleFace::None
-        };
+        });
 
         context.set_culling(culling);
     }
@@ -316,6 +345,8 @@ pub fn set_program_constants_from_matrix<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         let program_type = args.get_string(activation, 0);
 
         let is_vertex = if &*program_type == b"vertex" {
@@ -373,6 +404,8 @@ pub fn set_program_constants_from_vector<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         let program_type = args.get_string(activation, 0);
 
         let program_type = if &*program_type == b"vertex" {
@@ -410,13 +443,15 @@ pub fn set_program_constants_from_vector<'gc>(
 }
 
 pub fn clear<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let red = args.get_f64(0);
         let green = args.get_f64(1);
@@ -439,6 +474,8 @@ pub fn create_texture<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let width = args.get_i32(0) as u32;
         let height = args.get_i32(1) as u32;
@@ -471,6 +508,8 @@ pub fn create_rectangle_texture<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let width = args.get_i32(0) as u32;
         let height = args.get_i32(1) as u32;
@@ -502,6 +541,8 @@ pub fn create_cube_texture<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let size = args.get_i32(0) as u32;
         let format = args.get_string_non_null(activation, 1, "textureFormat")?;
@@ -529,6 +570,8 @@ pub fn set_texture_at<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let sampler = args.get_i32(0) as u32;
         let mut cube = false;
@@ -542,7 +585,9 @@ pub fn set_texture_at<'gc>(
                     .inner_class_definition(),
             );
 
-            Some(texture_object.as_texture().unwrap().handle())
+            let texture = texture_object.as_texture().unwrap();
+            texture.check_disposed(activation)?;
+            Some(texture.handle())
         } else {
             None
         };
@@ -553,13 +598,15 @@ pub fn set_texture_at<'gc>(
 }
 
 pub fn set_color_mask<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let red = args.get_bool(0);
         let green = args.get_bool(1);
@@ -579,6 +626,8 @@ pub fn set_depth_test<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let depth_mask = args.get_bool(0);
         let pass_compare_mode = args.get_string_non_null(activation, 1, "passCompareMode")?;
@@ -598,6 +647,8 @@ pub fn set_blend_factors<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let source_factor = args.get_string_non_null(activation, 0, "sourceFactor")?;
         let destination_factor = args.get_string_non_null(activation, 1, "destinationFactor")?;
@@ -621,10 +672,12 @@ pub fn set_render_to_texture<'gc>(
     let this = this.as_object().unwrap();
 
     let context = this.as_context_3d().unwrap();
+    context.check_disposed(activation)?;
     let texture = args
         .get_object(activation, 0, "texture")?
         .as_texture()
         .unwrap();
+    texture.check_disposed(activation)?;
     let enable_depth_and_stencil = args.get_bool(1);
     let anti_alias = args.get_u32(2);
     let surface_selector = args.get_u32(3);
@@ -667,32 +720,25 @@ pub fn set_render_to_texture<'gc>(
         );
     }
 
-    if color_output_index != 0 {
-        avm2_stub_method!(
-            activation,
-            "flash.display3D.Context3D",
-            "setRenderToTexture",
-            "colorOutputIndex != 0"
-        );
-    }
-
     context.set_render_to_texture(
         texture.handle(),
         enable_depth_and_stencil,
         anti_alias,
         surface_selector,
+        color_output_index,
     );
     Ok(Value::Undefined)
 }
 
 pub fn set_render_to_back_buffer<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this = this.as_object().unwrap();
 
     let context = this.as_context_3d().unwrap();
+    context.check_disposed(activation)?;
     context.set_render_to_back_buffer();
     Ok(Value::Undefined)
 }
@@ -705,6 +751,8 @@ pub fn set_sampler_state_at<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
         // This is a native method, so all of the arguments have been checked and coerced for us
         let sampler = args.get_i32(0) as u32;
         let wrap = args.get_string_non_null(activation, 1, "wrap")?;
@@ -754,6 +802,7 @@ pub fn set_scissor_rectangle<'gc>(
     let this = this.as_object().unwrap();
 
     let context3d = this.as_context_3d().unwrap();
+    context3d.check_disposed(activation)?;
     let rectangle = args.try_get_object(0);
     let rectangle = if let Some(rectangle) = rectangle {
         let x = rectangle
@@ -782,17 +831,165 @@ pub fn set_scissor_rectangle<'gc>(
     Ok(Value::Undefined)
 }
 
-pub fn dispose<'gc>(
+pub fn set_stencil_actions<'gc>(
     activation: &mut Activation<'_, 'gc>,
     this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
+        // This is a native method, so all of the arguments have been checked and coerced for us
+        let face = args.get_string_non_null(activation, 0, "triangleFace")?;
+        let compare_mode = args.get_string_non_null(activation, 1, "compareMode")?;
+        let action_on_both_pass = args.get_string_non_null(activation, 2, "actionOnBothPass")?;
+        let action_on_depth_fail = args.get_string_non_null(activation, 3, "actionOnDepthFail")?;
+        let action_on_depth_pass_stencil_fail =
+            args.get_string_non_null(activation, 4, "actionOnDepthPassStencilFail")?;
+
+        let face = Context3DTriangleFace::from_wstr(&face)
+            .ok_or_else(|| make_error_2008(activation, "triangleFace"))?;
+        let compare_mode = Context3DCompareMode::from_wstr(&compare_mode)
+            .ok_or_else(|| make_error_2008(activation, "compareMode"))?;
+        let action_on_both_pass = Context3DStencilAction::from_wstr(&action_on_both_pass)
+            .ok_or_else(|| make_error_2008(activation, "actionOnBothPass"))?;
+        let action_on_depth_fail = Context3DStencilAction::from_wstr(&action_on_depth_fail)
+            .ok_or_else(|| make_error_2008(activation, "actionOnDepthFail"))?;
+        let action_on_depth_pass_stencil_fail =
+            Context3DStencilAction::from_wstr(&action_on_depth_pass_stencil_fail)
+                .ok_or_else(|| make_error_2008(activation, "actionOnDepthPassStencilFail"))?;
+
+        context.set_stencil_actions(
+            face,
+            compare_mode,
+            action_on_both_pass,
+            action_on_depth_fail,
+            action_on_depth_pass_stencil_fail,
+        );
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_stencil_reference_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(context) = this.as_context_3d() {
+        context.check_disposed(activation)?;
+
+        // The stencil buffer only has 8 bits, so Flash ignores anything above that
+        let reference_value = args.get_u32(0) & 0xFF;
+        let read_mask = args.get_u32(1) & 0xFF;
+        let write_mask = args.get_u32(2) & 0xFF;
+
+        context.set_stencil_reference_value(reference_value, read_mask, write_mask);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_driver_info<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(context) = this.as_context_3d() {
+        return Ok(AvmString::new_utf8(activation.gc(), context.driver_info()).into());
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_max_back_buffer_width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(context) = this.as_context_3d() {
+        return Ok(context.max_back_buffer_width().into());
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_max_back_buffer_width<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(context) = this.as_context_3d() {
+        avm2_stub_setter!(
+            activation,
+            "flash.display3D.Context3D",
+            "maxBackBufferWidth"
+        );
+        context.set_max_back_buffer_width(args.get_i32(0).max(0) as u32);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_max_back_buffer_height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this = this.as_object().unwrap();
 
-    avm2_stub_method!(activation, "flash.display3D.Context3D", "dispose");
-    this.as_context_3d()
-        .unwrap()
-        .stage3d()
-        .set_context3d(None, activation.gc());
+    if let Some(context) = this.as_context_3d() {
+        return Ok(context.max_back_buffer_height().into());
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_max_back_buffer_height<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(context) = this.as_context_3d() {
+        avm2_stub_setter!(
+            activation,
+            "flash.display3D.Context3D",
+            "maxBackBufferHeight"
+        );
+        context.set_max_back_buffer_height(args.get_i32(0).max(0) as u32);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn dispose_internal<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(context) = this.as_context_3d() {
+        if context.is_disposed() {
+            return Ok(Value::Null);
+        }
+
+        context.dispose();
+
+        let stage3d = context.stage3d();
+        // The Stage3D may already have been given a newer context
+        if stage3d
+            .context3d()
+            .is_some_and(|current| Object::ptr_eq(current, this))
+        {
+            stage3d.set_context3d(None, activation.gc());
+        }
+        return Ok(Object::from(stage3d).into());
+    }
     Ok(Value::Undefined)
 }
@@ -11,6 +11,8 @@ pub fn upload<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(this) = this.as_program_3d() {
+        this.check_disposed(activation)?;
+
         let vertex_agal = args.get_object(activation, 0, "source_vertex")?;
         let vertex_agal = vertex_agal
             .as_bytearray()
@@ -28,3 +30,16 @@ pub fn upload<'gc>(
     }
     Ok(Value::Undefined)
 }
+
+pub fn dispose<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(this) = this.as_program_3d() {
+        this.dispose();
+    }
+    Ok(Value::Undefined)
+}
@@ -1,11 +1,8 @@
 package flash.display3D.textures {
     import flash.events.EventDispatcher;
-    import __ruffle__.stub_method;
 
     [API("674")]
     public class TextureBase extends EventDispatcher {
-        public function dispose():void {
-            stub_method("flash.display3D.textures.TextureBase", "dispose");
-        }
+        public native function dispose():void;
     }
 }
@@ -23,6 +23,8 @@ pub fn upload_from_byte_array<'gc>(
         "uploadFromByteArray"
     );
     let texture = this.as_texture().unwrap();
+    texture.check_disposed(activation)?;
+
     let data = args.get_object(activation, 0, "data")?;
     let byte_array_offset = args.get_u32(1);
     let side = args.get_u32(2);
@@ -54,6 +56,8 @@ pub fn upload_compressed_texture_from_byte_array<'gc>(
     );
 
     let texture = this.as_texture().unwrap();
+    texture.check_disposed(activation)?;
+
     let data = args.get_object(activation, 0, "data")?;
     let byte_array_offset = args.get_u32(1) as usize;
     let async_ = args.get_bool(2);
@@ -88,6 +92,8 @@ pub fn upload_from_bitmap_data<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(texture) = this.as_texture() {
+        texture.check_disposed(activation)?;
+
         let source_obj = args.get_object(activation, 0, "source")?;
 
         if let Some(source) = source_obj.as_bitmap_data() {
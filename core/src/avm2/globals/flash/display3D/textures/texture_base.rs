@@ -0,0 +1,16 @@
+use crate::avm2::Activation;
+use crate::avm2::Error;
+use crate::avm2::Value;
+
+pub fn dispose<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(texture) = this.as_texture() {
+        texture.dispose();
+    }
+    Ok(Value::Undefined)
+}
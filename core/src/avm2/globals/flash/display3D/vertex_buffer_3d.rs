@@ -11,6 +11,8 @@ pub fn upload_from_byte_array<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(vertex_buffer) = this.as_vertex_buffer() {
+        vertex_buffer.check_disposed(activation)?;
+
         let byte_array = args.get_object(activation, 0, "data")?;
 
         let byte_array = byte_array
@@ -46,6 +48,8 @@ pub fn upload_from_vector<'gc>(
     let this = this.as_object().unwrap();
 
     if let Some(vertex_buffer) = this.as_vertex_buffer() {
+        vertex_buffer.check_disposed(activation)?;
+
         let vector = args.get_object(activation, 0, "data")?;
 
         let vector = vector
@@ -72,3 +76,16 @@ pub fn upload_from_vector<'gc>(
     }
     Ok(Value::Undefined)
 }
+
+pub fn dispose<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    if let Some(vertex_buffer) = this.as_vertex_buffer() {
+        vertex_buffer.dispose();
+    }
+    Ok(Value::Undefined)
+}
@@ -43,7 +43,10 @@ package flash.events {
         private var _altKey:Boolean;
         private var _ctrlKey:Boolean;
         private var _shiftKey:Boolean;
+        [Ruffle(NativeAccessible)]
         private var _movementX:Number;
+
+        [Ruffle(NativeAccessible)]
         private var _movementY:Number;
 
         public function MouseEvent(
@@ -69,8 +72,9 @@ package flash.events {
             this.buttonDown = buttonDown;
             this.delta = delta;
 
-            this.movementX = 0.0; // unimplemented
-            this.movementY = 0.0; // unimplemented
+            // These are set by Ruffle for `mouseMove` events while the mouse is locked.
+            this.movementX = 0.0;
+            this.movementY = 0.0;
         }
 
         override public function clone():Event {
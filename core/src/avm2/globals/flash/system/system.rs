@@ -1,9 +1,112 @@
 //! `flash.system.System` native methods
 
 use crate::avm2::activation::Activation;
+use crate::avm2::e4x::E4XNodeKind;
 use crate::avm2::parameters::ParametersExt;
 use crate::avm2::value::Value;
 use crate::avm2::Error;
+use crate::memory;
+
+/// Implements `flash.system.System.gc` method
+pub fn gc<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // The collection runs once the current script has finished, as the arena
+    // can't be collected while it's being mutated.
+    *activation.context.gc_requested = true;
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.System.pauseForGCIfCollectionImminent` method
+pub fn pause_for_gc_if_collection_imminent<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let imminence = args.get_f64(0);
+    let imminence = if imminence.is_nan() {
+        0.75
+    } else if imminence < 0.0 {
+        0.25
+    } else {
+        imminence.min(1.0)
+    };
+
+    let context = &activation.context;
+    let memory = memory::total_memory(context.gc().metrics(), &*context.audio);
+    if memory::is_collection_imminent(memory, context.memory_after_gc, imminence) {
+        *activation.context.gc_requested = true;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.System.disposeXML` method
+pub fn dispose_xml<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let Some(xml) = args.try_get_object(0).and_then(|o| o.as_xml_object()) else {
+        return Ok(Value::Undefined);
+    };
+
+    // Remove the parent and child connections between every node of the tree,
+    // so that none of them keep the others alive. The tree is taken out of its
+    // parent's children too, if it has one.
+    let mc = activation.gc();
+    let node = xml.node();
+    if let Some(parent) = node.parent() {
+        parent.remove_child(mc, node);
+    }
+    node.set_parent(None, mc);
+
+    let mut nodes = vec![node];
+    while let Some(node) = nodes.pop() {
+        if let E4XNodeKind::Element { children, .. } = &*node.kind() {
+            nodes.extend(children.iter().copied());
+        }
+        node.remove_all_children(mc);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.system.System.freeMemory` getter
+pub fn get_free_memory<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Memory is given back as soon as it's collected, so none of the memory
+    // allocated by the player is left unused.
+    Ok(0.into())
+}
+
+/// Implements `flash.system.System.privateMemory` getter
+pub fn get_private_memory<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // We don't know about the memory used by the rest of the process.
+    get_total_memory_number(activation, this, args)
+}
+
+/// Implements `flash.system.System.totalMemoryNumber` getter
+pub fn get_total_memory_number<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let context = &activation.context;
+    let memory = memory::total_memory(context.gc().metrics(), &*context.audio);
+
+    Ok((memory as f64).into())
+}
 
 /// Implements `flash.system.System.setClipboard` method
 pub fn set_clipboard<'gc>(
@@ -1 +1,21 @@
 pub use crate::avm2::object::dictionary_allocator;
+use crate::avm2::{Activation, Error, Value};
+
+/// Implements `Dictionary.makeKeysWeak`, which is called by the constructor
+/// when `weakKeys` is set.
+pub fn make_keys_weak<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    this: Value<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this.as_object().unwrap();
+
+    let dictionary = this
+        .as_dictionary_object()
+        .expect("Must be Dictionary object");
+
+    dictionary.set_weak_keys();
+    activation.avm2().register_weak_dictionary(dictionary);
+
+    Ok(Value::Undefined)
+}
@@ -21,7 +21,7 @@ use crate::display_object::DisplayObject;
 use crate::html::TextFormat;
 use crate::streams::NetStream;
 use crate::string::AvmString;
-use gc_arena::{Collect, Gc, GcWeak, Mutation};
+use gc_arena::{Collect, Finalization, Gc, GcWeak, Mutation};
 use ruffle_macros::enum_trait_object;
 use std::cell::{Ref, RefMut};
 use std::fmt::Debug;
@@ -529,11 +529,13 @@ pub trait TObject<'gc>: 'gc + Collect<'gc> + Debug + Into<Object<'gc>> + Clone +
     fn get_enumerant_name(
         self,
         index: u32,
-        _activation: &mut Activation<'_, 'gc>,
+        activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
         let base = self.base();
 
-        Ok(base.get_enumerant_name(index).unwrap_or(Value::Null))
+        Ok(base
+            .get_enumerant_name(index, activation.gc())
+            .unwrap_or(Value::Null))
     }
 
     /// Retrieve a given enumerable value by index.
@@ -928,6 +930,14 @@ macro_rules! define_weak_enum {
                     $( Self::$variant(o) => $strong_enum::$variant($variant(o.0.upgrade(mc)?)).into(), )*
                 }
             }
+
+            /// Whether the object is about to be collected, for use while
+            /// the arena is being finalized.
+            $vis fn is_dead(self, fc: &Finalization<'gc>) -> bool {
+                match self {
+                    $( Self::$variant(o) => o.0.is_dead(fc), )*
+                }
+            }
         }
 
         impl<'gc> $strong_enum<'gc> {
@@ -1,6 +1,7 @@
 //! Object representation for Context3D objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::error::make_error_3694;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{Object, TObject};
 use crate::avm2::value::Value;
@@ -12,8 +13,8 @@ use crate::utils::HasPrefixField;
 use gc_arena::{Collect, Gc, GcWeak};
 use ruffle_render::backend::{
     BufferUsage, Context3D, Context3DBlendFactor, Context3DCommand, Context3DCompareMode,
-    Context3DTextureFormat, Context3DTriangleFace, Context3DVertexBufferFormat, ProgramType,
-    Texture,
+    Context3DProfile, Context3DStencilAction, Context3DTextureFormat, Context3DTriangleFace,
+    Context3DVertexBufferFormat, IndexBuffer, ProgramType, ShaderModule, Texture, VertexBuffer,
 };
 use ruffle_render::commands::CommandHandler;
 use std::cell::Cell;
@@ -39,6 +40,8 @@ impl<'gc> Context3DObject<'gc> {
         stage3d: Stage3DObject<'gc>,
     ) -> Object<'gc> {
         let class = activation.avm2().classes().context3d;
+        let profile = context.profile();
+        let max_back_buffer_size = context.max_back_buffer_size();
 
         Context3DObject(Gc::new(
             activation.gc(),
@@ -46,6 +49,10 @@ impl<'gc> Context3DObject<'gc> {
                 base: ScriptObjectData::new(class),
                 render_context: Cell::new(Some(context)),
                 stage3d,
+                profile,
+                disposed: Cell::new(false),
+                max_back_buffer_width: Cell::new(max_back_buffer_size),
+                max_back_buffer_height: Cell::new(max_back_buffer_size),
             },
         ))
         .into()
@@ -55,6 +62,55 @@ impl<'gc> Context3DObject<'gc> {
         self.0.stage3d
     }
 
+    pub fn profile(self) -> Context3DProfile {
+        self.0.profile
+    }
+
+    pub fn is_disposed(self) -> bool {
+        self.0.disposed.get()
+    }
+
+    /// Throws the error that Flash Player raises when a disposed Context3D is used.
+    pub fn check_disposed(self, activation: &mut Activation<'_, 'gc>) -> Result<(), Error<'gc>> {
+        if self.is_disposed() {
+            return Err(make_error_3694(activation));
+        }
+        Ok(())
+    }
+
+    /// Releases the underlying render context. Any further use of this
+    /// object (or of the resources created from it) throws an error.
+    pub fn dispose(self) {
+        self.0.disposed.set(true);
+        drop(self.0.render_context.take());
+    }
+
+    pub fn driver_info(self) -> String {
+        if self.is_disposed() {
+            // This is what Flash Player reports, and content relies on it
+            // to detect a lost context.
+            "Disposed".to_string()
+        } else {
+            self.with_context_3d(|ctx| ctx.driver_info())
+        }
+    }
+
+    pub fn max_back_buffer_width(self) -> u32 {
+        self.0.max_back_buffer_width.get()
+    }
+
+    pub fn set_max_back_buffer_width(self, width: u32) {
+        self.0.max_back_buffer_width.set(width);
+    }
+
+    pub fn max_back_buffer_height(self) -> u32 {
+        self.0.max_back_buffer_height.get()
+    }
+
+    pub fn set_max_back_buffer_height(self, height: u32) {
+        self.0.max_back_buffer_height.set(height);
+    }
+
     pub fn with_context_3d<R>(self, f: impl FnOnce(&mut dyn Context3D) -> R) -> R {
         // Temporarily take ownership of the Context3D instance.
         let cell = &self.0.render_context;
@@ -278,6 +334,7 @@ impl<'gc> Context3DObject<'gc> {
         enable_depth_and_stencil: bool,
         anti_alias: u32,
         surface_selector: u32,
+        color_output_index: u32,
     ) {
         self.with_context_3d(|ctx| {
             ctx.process_command(Context3DCommand::SetRenderToTexture {
@@ -285,6 +342,7 @@ impl<'gc> Context3DObject<'gc> {
                 enable_depth_and_stencil,
                 anti_alias,
                 surface_selector,
+                color_output_index,
             })
         });
     }
@@ -457,6 +515,75 @@ impl<'gc> Context3DObject<'gc> {
             ctx.process_command(Context3DCommand::SetScissorRectangle { rect })
         });
     }
+
+    pub(crate) fn set_stencil_actions(
+        self,
+        face: Context3DTriangleFace,
+        compare_mode: Context3DCompareMode,
+        action_on_both_pass: Context3DStencilAction,
+        action_on_depth_fail: Context3DStencilAction,
+        action_on_depth_pass_stencil_fail: Context3DStencilAction,
+    ) {
+        self.with_context_3d(|ctx| {
+            ctx.process_command(Context3DCommand::SetStencilActions {
+                face,
+                compare_mode,
+                action_on_both_pass,
+                action_on_depth_fail,
+                action_on_depth_pass_stencil_fail,
+            })
+        });
+    }
+
+    pub(crate) fn set_stencil_reference_value(
+        self,
+        reference_value: u32,
+        read_mask: u32,
+        write_mask: u32,
+    ) {
+        self.with_context_3d(|ctx| {
+            ctx.process_command(Context3DCommand::SetStencilReferenceValue {
+                reference_value,
+                read_mask,
+                write_mask,
+            })
+        });
+    }
+
+    // The resources below may outlive a disposed context, in which case
+    // there's nothing left to release on the render backend's side.
+
+    pub(crate) fn dispose_index_buffer(self, buffer: Box<dyn IndexBuffer>) {
+        if !self.is_disposed() {
+            self.with_context_3d(|ctx| {
+                ctx.process_command(Context3DCommand::DisposeIndexBuffer { buffer })
+            });
+        }
+    }
+
+    pub(crate) fn dispose_vertex_buffer(self, buffer: Rc<dyn VertexBuffer>) {
+        if !self.is_disposed() {
+            self.with_context_3d(|ctx| {
+                ctx.process_command(Context3DCommand::DisposeVertexBuffer { buffer })
+            });
+        }
+    }
+
+    pub(crate) fn dispose_texture(self, texture: Rc<dyn Texture>) {
+        if !self.is_disposed() {
+            self.with_context_3d(|ctx| {
+                ctx.process_command(Context3DCommand::DisposeTexture { texture })
+            });
+        }
+    }
+
+    pub(crate) fn dispose_shaders(self, module: Rc<dyn ShaderModule>) {
+        if !self.is_disposed() {
+            self.with_context_3d(|ctx| {
+                ctx.process_command(Context3DCommand::DisposeShaders { module })
+            });
+        }
+    }
 }
 
 #[derive(Collect, HasPrefixField)]
@@ -470,6 +597,15 @@ pub struct Context3DData<'gc> {
     render_context: Cell<Option<Box<dyn Context3D>>>,
 
     stage3d: Stage3DObject<'gc>,
+
+    #[collect(require_static)]
+    profile: Context3DProfile,
+
+    /// Set by `Context3D.dispose`, after which `render_context` is gone.
+    disposed: Cell<bool>,
+
+    max_back_buffer_width: Cell<u32>,
+    max_back_buffer_height: Cell<u32>,
 }
 
 impl<'gc> TObject<'gc> for Context3DObject<'gc> {
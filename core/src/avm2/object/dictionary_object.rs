@@ -1,7 +1,7 @@
 //! Object representation for `flash.utils.Dictionary`
 
 use crate::avm2::activation::Activation;
-use crate::avm2::dynamic_map::DynamicKey;
+use crate::avm2::dynamic_map::{DynamicKey, WeakObjectKey};
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{ClassObject, Object, TObject};
 use crate::avm2::value::Value;
@@ -9,7 +9,8 @@ use crate::avm2::Error;
 use crate::string::AvmString;
 use crate::utils::HasPrefixField;
 use core::fmt;
-use gc_arena::{Collect, Gc, GcWeak, Mutation};
+use gc_arena::{Collect, Finalization, Gc, GcWeak, Mutation};
+use std::cell::Cell;
 
 /// A class instance allocator that allocates Dictionary objects.
 pub fn dictionary_allocator<'gc>(
@@ -18,7 +19,14 @@ pub fn dictionary_allocator<'gc>(
 ) -> Result<Object<'gc>, Error<'gc>> {
     let base = ScriptObjectData::new(class);
 
-    Ok(DictionaryObject(Gc::new(activation.gc(), DictionaryObjectData { base })).into())
+    Ok(DictionaryObject(Gc::new(
+        activation.gc(),
+        DictionaryObjectData {
+            base,
+            weak_keys: Cell::new(false),
+        },
+    ))
+    .into())
 }
 
 /// An object that allows associations between objects and values.
@@ -48,15 +56,57 @@ impl fmt::Debug for DictionaryObject<'_> {
 pub struct DictionaryObjectData<'gc> {
     /// Base script object
     base: ScriptObjectData<'gc>,
+
+    /// Whether the dictionary was created with `weakKeys` set, in which case
+    /// its object keys don't keep the objects alive.
+    weak_keys: Cell<bool>,
 }
 
 impl<'gc> DictionaryObject<'gc> {
+    pub fn has_weak_keys(self) -> bool {
+        self.0.weak_keys.get()
+    }
+
+    /// Make the object keys of this dictionary weak.
+    ///
+    /// This is only done by the constructor, before any keys are added. The
+    /// dictionary must also be registered with `Avm2::register_weak_dictionary`
+    /// for the entries of collected keys to be removed.
+    pub fn set_weak_keys(self) {
+        self.0.weak_keys.set(true);
+    }
+
+    fn object_key(self, name: Object<'gc>) -> DynamicKey<'gc> {
+        if self.has_weak_keys() {
+            DynamicKey::WeakObject(WeakObjectKey(name.downgrade()))
+        } else {
+            DynamicKey::Object(name)
+        }
+    }
+
+    /// Remove every entry whose key is about to be collected.
+    pub fn remove_dead_keys(self, fc: &Finalization<'gc>) {
+        let has_dead_keys = self
+            .base()
+            .values()
+            .as_hashmap()
+            .keys()
+            .any(|key| matches!(key, DynamicKey::WeakObject(key) if key.0.is_dead(fc)));
+
+        if has_dead_keys {
+            self.base().values_mut(fc).retain(|key, _| match key {
+                DynamicKey::WeakObject(key) => !key.0.is_dead(fc),
+                _ => true,
+            });
+        }
+    }
+
     /// Retrieve a value in the dictionary's object space.
     pub fn get_property_by_object(self, name: Object<'gc>) -> Value<'gc> {
         self.base()
             .values()
             .as_hashmap()
-            .get(&DynamicKey::Object(name))
+            .get(&self.object_key(name))
             .cloned()
             .map(|v| v.value)
             .unwrap_or(Value::Undefined)
@@ -66,19 +116,19 @@ impl<'gc> DictionaryObject<'gc> {
     pub fn set_property_by_object(self, name: Object<'gc>, value: Value<'gc>, mc: &Mutation<'gc>) {
         self.base()
             .values_mut(mc)
-            .insert(DynamicKey::Object(name), value);
+            .insert(self.object_key(name), value);
     }
 
     /// Delete a value from the dictionary's object space.
     pub fn delete_property_by_object(self, name: Object<'gc>, mc: &Mutation<'gc>) {
-        self.base().values_mut(mc).remove(&DynamicKey::Object(name));
+        self.base().values_mut(mc).remove(&self.object_key(name));
     }
 
     pub fn has_property_by_object(self, name: Object<'gc>) -> bool {
         self.base()
             .values()
             .as_hashmap()
-            .get(&DynamicKey::Object(name))
+            .get(&self.object_key(name))
             .is_some()
     }
 }
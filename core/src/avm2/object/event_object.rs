@@ -2,6 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::events::Event;
+use crate::avm2::globals::slots::flash_events_mouse_event as mouse_event_slots;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{ClassObject, Object, ScriptObject, TObject};
 use crate::avm2::value::Value;
@@ -122,7 +123,7 @@ impl<'gc> EventObject<'gc> {
         let local = target.local_mouse_position(activation.context);
 
         let mouse_event_cls = activation.avm2().classes().mouseevent;
-        Self::from_class_and_args(
+        let event = Self::from_class_and_args(
             activation,
             mouse_event_cls,
             &[
@@ -154,7 +155,23 @@ impl<'gc> EventObject<'gc> {
                 // delta
                 delta.into(),
             ],
-        )
+        );
+
+        let (movement_x, movement_y) = activation.context.input.mouse_movement();
+        if movement_x != 0.0 || movement_y != 0.0 {
+            event.set_slot_no_coerce(
+                mouse_event_slots::_MOVEMENT_X,
+                movement_x.into(),
+                activation.gc(),
+            );
+            event.set_slot_no_coerce(
+                mouse_event_slots::_MOVEMENT_Y,
+                movement_y.into(),
+                activation.gc(),
+            );
+        }
+
+        event
     }
 
     pub fn mouse_event_down(
@@ -1,8 +1,10 @@
 //! Object representation for IndexBuffer3D objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::error::make_error_3694;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{Object, TObject};
+use crate::avm2::Error;
 use crate::utils::HasPrefixField;
 use gc_arena::{Collect, Gc, GcWeak};
 use ruffle_render::backend::IndexBuffer;
@@ -31,7 +33,7 @@ impl<'gc> IndexBuffer3DObject<'gc> {
             IndexBuffer3DObjectData {
                 base: ScriptObjectData::new(class),
                 context3d,
-                handle: RefCell::new(handle),
+                handle: RefCell::new(Some(handle)),
                 count: Cell::new(0),
             },
         ))
@@ -47,12 +49,31 @@ impl<'gc> IndexBuffer3DObject<'gc> {
     }
 
     pub fn handle(&self) -> RefMut<'_, dyn IndexBuffer> {
-        RefMut::map(self.0.handle.borrow_mut(), |h| h.as_mut())
+        RefMut::map(self.0.handle.borrow_mut(), |h| {
+            h.as_deref_mut().expect("IndexBuffer3D was disposed")
+        })
     }
 
     pub fn context3d(self) -> Context3DObject<'gc> {
         self.0.context3d
     }
+
+    pub fn is_disposed(self) -> bool {
+        self.0.handle.borrow().is_none() || self.0.context3d.is_disposed()
+    }
+
+    pub fn check_disposed(self, activation: &mut Activation<'_, 'gc>) -> Result<(), Error<'gc>> {
+        if self.is_disposed() {
+            return Err(make_error_3694(activation));
+        }
+        Ok(())
+    }
+
+    pub fn dispose(self) {
+        if let Some(handle) = self.0.handle.take() {
+            self.0.context3d.dispose_index_buffer(handle);
+        }
+    }
 }
 
 #[derive(Collect, HasPrefixField)]
@@ -62,7 +83,8 @@ pub struct IndexBuffer3DObjectData<'gc> {
     /// Base script object
     base: ScriptObjectData<'gc>,
 
-    handle: RefCell<Box<dyn IndexBuffer>>,
+    /// This is `None` once the buffer has been disposed.
+    handle: RefCell<Option<Box<dyn IndexBuffer>>>,
 
     count: Cell<usize>,
 
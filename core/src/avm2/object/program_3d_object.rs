@@ -1,12 +1,14 @@
 //! Object representation for VertexBuffer3D objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::error::make_error_3694;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{Object, TObject};
+use crate::avm2::Error;
 use crate::utils::HasPrefixField;
 use gc_arena::{Collect, Gc, GcWeak};
 use ruffle_render::backend::ShaderModule;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use super::Context3DObject;
@@ -33,6 +35,7 @@ impl<'gc> Program3DObject<'gc> {
                 base,
                 context3d,
                 shader_module_handle: RefCell::new(None),
+                disposed: Cell::new(false),
             },
         ))
         .into()
@@ -45,6 +48,24 @@ impl<'gc> Program3DObject<'gc> {
     pub fn context3d(self) -> Context3DObject<'gc> {
         self.0.context3d
     }
+
+    pub fn is_disposed(self) -> bool {
+        self.0.disposed.get() || self.0.context3d.is_disposed()
+    }
+
+    pub fn check_disposed(self, activation: &mut Activation<'_, 'gc>) -> Result<(), Error<'gc>> {
+        if self.is_disposed() {
+            return Err(make_error_3694(activation));
+        }
+        Ok(())
+    }
+
+    pub fn dispose(self) {
+        self.0.disposed.set(true);
+        if let Some(module) = self.0.shader_module_handle.take() {
+            self.0.context3d.dispose_shaders(module);
+        }
+    }
 }
 
 #[derive(Collect, HasPrefixField)]
@@ -57,6 +78,8 @@ pub struct Program3DObjectData<'gc> {
     context3d: Context3DObject<'gc>,
 
     shader_module_handle: RefCell<Option<Rc<dyn ShaderModule>>>,
+
+    disposed: Cell<bool>,
 }
 
 impl<'gc> TObject<'gc> for Program3DObject<'gc> {
@@ -1,11 +1,14 @@
 //! Object representation for Texture3D objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::error::make_error_3694;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{Object, TObject};
+use crate::avm2::Error;
 use crate::utils::HasPrefixField;
 use gc_arena::{Collect, Gc, GcWeak};
 use ruffle_render::backend::{Context3DTextureFormat, Texture};
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use super::{ClassObject, Context3DObject};
@@ -32,7 +35,7 @@ impl<'gc> TextureObject<'gc> {
                 base: ScriptObjectData::new(class),
                 context3d,
                 original_format,
-                handle,
+                handle: RefCell::new(Some(handle)),
             },
         ))
         .into()
@@ -43,12 +46,33 @@ impl<'gc> TextureObject<'gc> {
     }
 
     pub fn handle(self) -> Rc<dyn Texture> {
-        self.0.handle.clone()
+        self.0
+            .handle
+            .borrow()
+            .clone()
+            .expect("Texture was disposed")
     }
 
     pub fn context3d(self) -> Context3DObject<'gc> {
         self.0.context3d
     }
+
+    pub fn is_disposed(self) -> bool {
+        self.0.handle.borrow().is_none() || self.0.context3d.is_disposed()
+    }
+
+    pub fn check_disposed(self, activation: &mut Activation<'_, 'gc>) -> Result<(), Error<'gc>> {
+        if self.is_disposed() {
+            return Err(make_error_3694(activation));
+        }
+        Ok(())
+    }
+
+    pub fn dispose(self) {
+        if let Some(handle) = self.0.handle.take() {
+            self.0.context3d.dispose_texture(handle);
+        }
+    }
 }
 
 #[derive(Collect, HasPrefixField)]
@@ -63,8 +87,9 @@ pub struct TextureObjectData<'gc> {
     #[collect(require_static)]
     original_format: Context3DTextureFormat,
 
+    /// This is `None` once the texture has been disposed.
     #[collect(require_static)]
-    handle: Rc<dyn Texture>,
+    handle: RefCell<Option<Rc<dyn Texture>>>,
 }
 
 impl<'gc> TObject<'gc> for TextureObject<'gc> {
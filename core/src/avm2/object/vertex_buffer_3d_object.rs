@@ -1,11 +1,14 @@
 //! Object representation for VertexBuffer3D objects
 
 use crate::avm2::activation::Activation;
+use crate::avm2::error::make_error_3694;
 use crate::avm2::object::script_object::ScriptObjectData;
 use crate::avm2::object::{Object, TObject};
+use crate::avm2::Error;
 use crate::utils::HasPrefixField;
 use gc_arena::{Collect, Gc, GcWeak};
 use ruffle_render::backend::VertexBuffer;
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use super::Context3DObject;
@@ -32,7 +35,7 @@ impl<'gc> VertexBuffer3DObject<'gc> {
             VertexBuffer3DObjectData {
                 base: ScriptObjectData::new(class),
                 context3d,
-                handle,
+                handle: RefCell::new(Some(handle)),
                 data32_per_vertex,
             },
         ))
@@ -40,13 +43,34 @@ impl<'gc> VertexBuffer3DObject<'gc> {
     }
 
     pub fn handle(self) -> Rc<dyn VertexBuffer> {
-        self.0.handle.clone()
+        self.0
+            .handle
+            .borrow()
+            .clone()
+            .expect("VertexBuffer3D was disposed")
     }
 
     pub fn context3d(self) -> Context3DObject<'gc> {
         self.0.context3d
     }
 
+    pub fn is_disposed(self) -> bool {
+        self.0.handle.borrow().is_none() || self.0.context3d.is_disposed()
+    }
+
+    pub fn check_disposed(self, activation: &mut Activation<'_, 'gc>) -> Result<(), Error<'gc>> {
+        if self.is_disposed() {
+            return Err(make_error_3694(activation));
+        }
+        Ok(())
+    }
+
+    pub fn dispose(self) {
+        if let Some(handle) = self.0.handle.take() {
+            self.0.context3d.dispose_vertex_buffer(handle);
+        }
+    }
+
     pub fn data32_per_vertex(self) -> u8 {
         self.0.data32_per_vertex
     }
@@ -61,8 +85,9 @@ pub struct VertexBuffer3DObjectData<'gc> {
 
     context3d: Context3DObject<'gc>,
 
+    /// This is `None` once the buffer has been disposed.
     #[collect(require_static)]
-    handle: Rc<dyn VertexBuffer>,
+    handle: RefCell<Option<Rc<dyn VertexBuffer>>>,
 
     /// The 'data32PerVertex' value that this object was created with.
     /// This is the number of 32-bit values associated with each vertex,
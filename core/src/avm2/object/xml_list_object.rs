@@ -1052,7 +1052,7 @@ impl<'gc> TObject<'gc> for XmlListObject<'gc> {
     fn get_enumerant_name(
         self,
         index: u32,
-        _activation: &mut Activation<'_, 'gc>,
+        activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
         let children_len = self.0.children.borrow().len() as u32;
         if children_len >= index {
@@ -1063,7 +1063,7 @@ impl<'gc> TObject<'gc> for XmlListObject<'gc> {
         } else {
             Ok(self
                 .base()
-                .get_enumerant_name(index - children_len)
+                .get_enumerant_name(index - children_len, activation.gc())
                 .unwrap_or(Value::Null))
         }
     }
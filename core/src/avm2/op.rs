@@ -2,6 +2,7 @@ use crate::avm2::class::Class;
 use crate::avm2::method::{Method, NativeMethodImpl};
 use crate::avm2::multiname::Multiname;
 use crate::avm2::namespace::Namespace;
+use crate::avm2::property_cache::PropertyCache;
 use crate::avm2::script::Script;
 use crate::string::AvmAtom;
 
@@ -13,6 +14,8 @@ use std::cell::Cell;
 pub enum Op<'gc> {
     Add,
     AddI,
+    AddInt,
+    AddNumber,
     ApplyType {
         num_types: u32,
     },
@@ -47,6 +50,11 @@ pub enum Op<'gc> {
 
         num_args: u32,
     },
+    CallPropertyCached {
+        cache: Gc<'gc, PropertyCache<'gc>>,
+
+        num_args: u32,
+    },
     CallPropLex {
         multiname: Gc<'gc, Multiname<'gc>>,
 
@@ -57,6 +65,11 @@ pub enum Op<'gc> {
 
         num_args: u32,
     },
+    CallPropVoidCached {
+        cache: Gc<'gc, PropertyCache<'gc>>,
+
+        num_args: u32,
+    },
     CallStatic {
         method: Method<'gc>,
 
@@ -123,6 +136,7 @@ pub enum Op<'gc> {
         multiname: Gc<'gc, Multiname<'gc>>,
     },
     Divide,
+    DivideNumber,
     Dup,
     Dxns {
         string: AvmAtom<'gc>,
@@ -157,9 +171,14 @@ pub enum Op<'gc> {
     //    emits GetPropertyFast.
     //  - If neither condition is met (i.e. the multiname has a lazy namespace),
     //    the verifier emits GetPropertySlow.
+    // The optimizer then turns a GetPropertyStatic it can't resolve into a
+    // GetPropertyCached.
     GetPropertyStatic {
         multiname: Gc<'gc, Multiname<'gc>>,
     },
+    GetPropertyCached {
+        cache: Gc<'gc, PropertyCache<'gc>>,
+    },
     GetPropertyFast {
         multiname: Gc<'gc, Multiname<'gc>>,
     },
@@ -180,7 +199,9 @@ pub enum Op<'gc> {
         multiname: Gc<'gc, Multiname<'gc>>,
     },
     GreaterEquals,
+    GreaterEqualsNumber,
     GreaterThan,
+    GreaterThanNumber,
     HasNext,
     HasNext2 {
         object_register: u32,
@@ -216,16 +237,26 @@ pub enum Op<'gc> {
         index: u32,
     },
     LessEquals,
+    LessEqualsNumber,
     LessThan,
+    LessThanNumber,
     Lf32,
     Lf64,
     Li16,
     Li32,
     Li8,
+    /// A domain memory load from a constant offset from the popped address,
+    /// fused from a `pushint`, `add_i` and load by the optimizer.
+    LoadMemory {
+        #[collect(require_static)]
+        kind: MemoryAccess,
+        offset: i32,
+    },
     LookupSwitch(Gc<'gc, LookupSwitch>),
     LShift,
     Modulo,
     Multiply,
+    MultiplyNumber,
     MultiplyI,
     Negate,
     NegateI,
@@ -291,11 +322,20 @@ pub enum Op<'gc> {
     SetLocal {
         index: u32,
     },
+    SetLocalInt {
+        index: u32,
+    },
+    SetLocalNumber {
+        index: u32,
+    },
 
     // See the comments on the GetProperty op
     SetPropertyStatic {
         multiname: Gc<'gc, Multiname<'gc>>,
     },
+    SetPropertyCached {
+        cache: Gc<'gc, PropertyCache<'gc>>,
+    },
     SetPropertyFast {
         multiname: Gc<'gc, Multiname<'gc>>,
     },
@@ -318,9 +358,17 @@ pub enum Op<'gc> {
     Si16,
     Si32,
     Si8,
+    /// The store counterpart of `LoadMemory`.
+    StoreMemory {
+        #[collect(require_static)]
+        kind: MemoryAccess,
+        offset: i32,
+    },
     StrictEquals,
     Subtract,
     SubtractI,
+    SubtractInt,
+    SubtractNumber,
     Swap,
     Sxi1,
     Sxi16,
@@ -331,6 +379,28 @@ pub enum Op<'gc> {
     URShift,
 }
 
+/// The kind of value that a domain memory access reads or writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryAccess {
+    I8,
+    I16,
+    I32,
+    F32,
+    F64,
+}
+
+impl MemoryAccess {
+    /// The number of bytes that are accessed.
+    pub fn size(self) -> usize {
+        match self {
+            MemoryAccess::I8 => 1,
+            MemoryAccess::I16 => 2,
+            MemoryAccess::I32 | MemoryAccess::F32 => 4,
+            MemoryAccess::F64 => 8,
+        }
+    }
+}
+
 impl Op<'_> {
     pub fn can_throw_error(&self) -> bool {
         !matches!(
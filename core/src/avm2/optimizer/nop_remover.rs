@@ -1,7 +1,13 @@
 use crate::avm2::op::Op;
 use crate::avm2::verify::Exception;
 
-pub fn remove_nops<'gc>(code: &mut Vec<Op<'gc>>, exceptions: &mut [Exception<'gc>]) {
+/// Remove nops from the code. When `keep_debug_ops` is set, debugging
+/// instructions are kept even if they'd otherwise be treated as nops.
+pub fn remove_nops<'gc>(
+    code: &mut Vec<Op<'gc>>,
+    exceptions: &mut [Exception<'gc>],
+    keep_debug_ops: bool,
+) {
     let mut offset_vec = vec![0; code.len()];
     let mut current_offset = 0;
 
@@ -9,7 +15,12 @@ pub fn remove_nops<'gc>(code: &mut Vec<Op<'gc>>, exceptions: &mut [Exception<'gc
     let mut i = 0;
     while i < code.len() {
         offset_vec[i] = i - current_offset;
-        if code[i].is_nop() {
+        let is_nop = if keep_debug_ops {
+            matches!(code[i], Op::Nop)
+        } else {
+            code[i].is_nop()
+        };
+        if is_nop {
             current_offset += 1;
         } else {
             // Shift the ops over the nops
@@ -1,5 +1,6 @@
 use crate::avm2::error::{
-    make_error_1026, make_error_1035, make_error_1051, make_error_1058, verify_error,
+    make_error_1019, make_error_1026, make_error_1035, make_error_1051, make_error_1058,
+    make_error_1068, verify_error,
 };
 use crate::avm2::method::{Method, MethodKind, ResolvedParamConfig};
 use crate::avm2::multiname::Multiname;
@@ -8,6 +9,7 @@ use crate::avm2::optimizer::blocks::assemble_blocks;
 use crate::avm2::optimizer::nop_remover::remove_nops;
 use crate::avm2::optimizer::peephole;
 use crate::avm2::property::Property;
+use crate::avm2::property_cache::PropertyCache;
 use crate::avm2::verify::Exception;
 use crate::avm2::vtable::VTable;
 use crate::avm2::{Activation, Class, Error};
@@ -98,6 +100,18 @@ impl<'gc> OptValue<'gc> {
         }
     }
 
+    // Returns true if the value is guaranteed to be an int, uint, or Number.
+    pub fn is_numeric(self) -> bool {
+        self.class
+            .is_some_and(|c| c.is_builtin_int() || c.is_builtin_uint() || c.is_builtin_number())
+    }
+
+    // Returns true if the value is guaranteed to be an int, whether it is
+    // stored as a Value::Integer or as a Value::Number.
+    pub fn is_int(self) -> bool {
+        self.contains_valid_integer || self.class.is_some_and(|c| c.is_builtin_int())
+    }
+
     pub fn merged_with(self, other: OptValue<'gc>) -> OptValue<'gc> {
         let mut created_value = OptValue::any();
 
@@ -503,12 +517,13 @@ impl<'gc> AbstractState<'gc> {
             let our_scope = self.scope_stack.at(i);
             let other_scope = other.scope_stack.at(i);
 
+            // A `pushwith` on one edge and a `pushscope` on the other
             if our_scope.1 != other_scope.1 {
-                return Err(Error::avm_error(verify_error(
+                return Err(make_error_1068(
                     activation,
-                    "Error #1068: Scope values cannot be reconciled.",
-                    1068,
-                )?));
+                    our_scope.0.class,
+                    other_scope.0.class,
+                ));
             }
 
             let merged = our_scope.0.merged_with(other_scope.0);
@@ -600,8 +615,13 @@ pub fn optimize<'gc>(
     }
 
     let empty_stack = Stack::new(method_body.max_stack as usize);
-    let empty_scope_stack =
-        ScopeStack::new((method_body.max_scope_depth - method_body.init_scope_depth) as usize);
+    // A method with a `max_scope_depth` below its `init_scope_depth` can't
+    // push any scopes.
+    let empty_scope_stack = ScopeStack::new(
+        method_body
+            .max_scope_depth
+            .saturating_sub(method_body.init_scope_depth) as usize,
+    );
 
     let entry_state = AbstractState {
         locals: initial_local_types,
@@ -614,6 +634,12 @@ pub fn optimize<'gc>(
 
     abstract_states[0] = Some(entry_state);
 
+    // This first pass is the verifier's dataflow analysis, and runs even when the
+    // optimizer is disabled: it tracks the stack, scope stack and local types of
+    // every reachable instruction, raising the stack/scope over- and underflow
+    // errors and checking that each merge point is reached with a matching
+    // stack depth, scope depth and scope kinds. The only operand type it checks
+    // is the `lookupswitch` index.
     // Block #0 is the entry block
     let mut worklist = vec![0];
     while let Some(block_idx) = worklist.pop() {
@@ -659,7 +685,9 @@ pub fn optimize<'gc>(
         peephole::postprocess_peephole(code_slice, jump_targets, !method_exceptions.is_empty());
     }
 
-    remove_nops(code, method_exceptions);
+    // The debugger needs the debugging instructions to map code back to source lines
+    let keep_debug_ops = activation.avm2().debugger_attached();
+    remove_nops(code, method_exceptions, keep_debug_ops);
 
     Ok(())
 }
@@ -790,8 +818,15 @@ fn abstract_interpret_ops<'gc>(
                 let stack_value = stack.pop(activation)?;
                 if stack_value.class == Some(types.int) || stack_value.contains_valid_integer {
                     optimize_op_to!(Op::Nop);
+
+                    // The value is left untouched, so whatever we knew about it still holds
+                    let mut new_value = OptValue::of_type(types.int);
+                    new_value.contains_valid_integer = stack_value.contains_valid_integer;
+                    new_value.contains_valid_unsigned = stack_value.contains_valid_unsigned;
+                    stack.push(activation, new_value)?;
+                } else {
+                    stack.push_class(activation, types.int)?;
                 }
-                stack.push_class(activation, types.int)?;
             }
             Op::CoerceO => {
                 stack.pop(activation)?;
@@ -820,16 +855,25 @@ fn abstract_interpret_ops<'gc>(
                 }
                 stack.push_class(activation, types.uint)?;
             }
-            Op::Equals
-            | Op::StrictEquals
-            | Op::LessEquals
-            | Op::LessThan
-            | Op::GreaterThan
-            | Op::GreaterEquals => {
+            Op::Equals | Op::StrictEquals => {
                 stack.pop(activation)?;
                 stack.pop(activation)?;
                 stack.push_class(activation, types.boolean)?;
             }
+            Op::LessEquals | Op::LessThan | Op::GreaterThan | Op::GreaterEquals => {
+                let value2 = stack.pop(activation)?;
+                let value1 = stack.pop(activation)?;
+                if value1.is_numeric() && value2.is_numeric() {
+                    // Numbers can be compared directly, without going through `abstract_lt`
+                    optimize_op_to!(match op.get() {
+                        Op::LessEquals => Op::LessEqualsNumber,
+                        Op::LessThan => Op::LessThanNumber,
+                        Op::GreaterThan => Op::GreaterThanNumber,
+                        _ => Op::GreaterEqualsNumber,
+                    });
+                }
+                stack.push_class(activation, types.boolean)?;
+            }
             Op::Not => {
                 stack.pop(activation)?;
                 stack.push_class(activation, types.boolean)?;
@@ -926,13 +970,12 @@ fn abstract_interpret_ops<'gc>(
             Op::Add => {
                 let value2 = stack.pop(activation)?;
                 let value1 = stack.pop(activation)?;
-                if (value1.class == Some(types.int)
-                    || value1.class == Some(types.uint)
-                    || value1.class == Some(types.number))
-                    && (value2.class == Some(types.int)
-                        || value2.class == Some(types.uint)
-                        || value2.class == Some(types.number))
-                {
+                if value1.is_numeric() && value2.is_numeric() {
+                    if value1.is_int() && value2.is_int() {
+                        optimize_op_to!(Op::AddInt);
+                    } else {
+                        optimize_op_to!(Op::AddNumber);
+                    }
                     stack.push_class(activation, types.number)?;
                 } else if (value1.class == Some(types.string) && value1.not_null())
                     || (value2.class == Some(types.string) && value2.not_null())
@@ -943,18 +986,35 @@ fn abstract_interpret_ops<'gc>(
                 }
             }
             Op::Subtract => {
-                stack.pop(activation)?;
-                stack.pop(activation)?;
+                let value2 = stack.pop(activation)?;
+                let value1 = stack.pop(activation)?;
+                if value1.is_numeric() && value2.is_numeric() {
+                    if value1.is_int() && value2.is_int() {
+                        optimize_op_to!(Op::SubtractInt);
+                    } else {
+                        optimize_op_to!(Op::SubtractNumber);
+                    }
+                }
                 stack.push_class(activation, types.number)?;
             }
             Op::Multiply => {
-                stack.pop(activation)?;
-                stack.pop(activation)?;
+                let value2 = stack.pop(activation)?;
+                let value1 = stack.pop(activation)?;
+                // `op_multiply` keeps the product of two ints as an int, so leave those alone
+                if value1.is_numeric()
+                    && value2.is_numeric()
+                    && !(value1.is_int() && value2.is_int())
+                {
+                    optimize_op_to!(Op::MultiplyNumber);
+                }
                 stack.push_class(activation, types.number)?;
             }
             Op::Divide => {
-                stack.pop(activation)?;
-                stack.pop(activation)?;
+                let value2 = stack.pop(activation)?;
+                let value1 = stack.pop(activation)?;
+                if value1.is_numeric() && value2.is_numeric() {
+                    optimize_op_to!(Op::DivideNumber);
+                }
                 stack.push_class(activation, types.number)?;
             }
             Op::Modulo => {
@@ -1151,11 +1211,7 @@ fn abstract_interpret_ops<'gc>(
             }
             Op::GetScopeObject { index } => {
                 if index >= scope_stack.len() {
-                    return Err(Error::avm_error(verify_error(
-                        activation,
-                        "Error #1019: Getscopeobject  is out of bounds.",
-                        1019,
-                    )?));
+                    return Err(make_error_1019(activation, index));
                 }
 
                 stack.push(activation, scope_stack.at(index).0)?;
@@ -1405,6 +1461,11 @@ fn abstract_interpret_ops<'gc>(
                         stack.push_any(activation)?;
                     }
                 } else {
+                    // Cache the lookup, since it couldn't be resolved ahead of time
+                    optimize_op_to!(Op::GetPropertyCached {
+                        cache: PropertyCache::new(activation.gc(), multiname),
+                    });
+
                     stack.push_any(activation)?;
                 }
             }
@@ -1570,7 +1631,13 @@ fn abstract_interpret_ops<'gc>(
                         _ => {}
                     }
                 }
-                // `stack_pop_multiname` handled lazy
+
+                if matches!(op.get(), Op::SetPropertyStatic { .. }) {
+                    // Cache the lookup, since it couldn't be resolved ahead of time
+                    optimize_op_to!(Op::SetPropertyCached {
+                        cache: PropertyCache::new(activation.gc(), multiname),
+                    });
+                }
             }
             Op::SetPropertyFast { multiname } | Op::SetPropertySlow { multiname } => {
                 // Verifier only emits these ops when the multiname is lazy
@@ -1731,6 +1798,14 @@ fn abstract_interpret_ops<'gc>(
                             stack.push_any(activation)?;
                         }
                     } else {
+                        if let Op::CallProperty { num_args, .. } = op.get() {
+                            // Cache the lookup, since it couldn't be resolved ahead of time
+                            optimize_op_to!(Op::CallPropertyCached {
+                                cache: PropertyCache::new(activation.gc(), multiname),
+                                num_args,
+                            });
+                        }
+
                         stack.push_any(activation)?;
                     }
                 } else {
@@ -1763,6 +1838,12 @@ fn abstract_interpret_ops<'gc>(
 
                     if let Some((new_op, _)) = opt_result {
                         optimize_op_to!(new_op);
+                    } else {
+                        // Cache the lookup, since it couldn't be resolved ahead of time
+                        optimize_op_to!(Op::CallPropVoidCached {
+                            cache: PropertyCache::new(activation.gc(), multiname),
+                            num_args,
+                        });
                     }
                 }
             }
@@ -2009,15 +2090,33 @@ fn abstract_interpret_ops<'gc>(
                 return Ok(());
             }
 
-            Op::CallMethod { .. }
+            Op::AddInt
+            | Op::AddNumber
+            | Op::CallMethod { .. }
             | Op::CallNative { .. }
+            | Op::CallPropertyCached { .. }
+            | Op::CallPropVoidCached { .. }
             | Op::CoerceSwapPop { .. }
             | Op::CoerceDSwapPop
             | Op::CoerceISwapPop
             | Op::CoerceUSwapPop
             | Op::ConstructSlot { .. }
+            | Op::DivideNumber
+            | Op::GetPropertyCached { .. }
             | Op::GetScriptGlobals { .. }
-            | Op::SetSlotNoCoerce { .. } => unreachable!("Custom ops should not be encountered"),
+            | Op::GreaterEqualsNumber
+            | Op::GreaterThanNumber
+            | Op::LessEqualsNumber
+            | Op::LessThanNumber
+            | Op::LoadMemory { .. }
+            | Op::MultiplyNumber
+            | Op::SetLocalInt { .. }
+            | Op::SetLocalNumber { .. }
+            | Op::SetPropertyCached { .. }
+            | Op::SetSlotNoCoerce { .. }
+            | Op::StoreMemory { .. }
+            | Op::SubtractInt
+            | Op::SubtractNumber => unreachable!("Custom ops should not be encountered"),
         }
     }
 
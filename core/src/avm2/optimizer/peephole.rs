@@ -1,4 +1,4 @@
-use crate::avm2::op::Op;
+use crate::avm2::op::{MemoryAccess, Op};
 
 use std::cell::Cell;
 use std::collections::HashSet;
@@ -36,6 +36,8 @@ pub fn postprocess_peephole(
     for op in ops {
         match op.get() {
             Op::SetLocal { index }
+            | Op::SetLocalInt { index }
+            | Op::SetLocalNumber { index }
             | Op::Kill { index }
             | Op::DecLocal { index }
             | Op::DecLocalI { index }
@@ -65,11 +67,13 @@ pub fn postprocess_peephole(
 
     // Now actually run the peephole optimizer.
     let mut last_op = None;
+    let mut second_last_op = None;
 
     for (i, current_op) in ops.iter().enumerate() {
         if jump_targets.contains(&i) {
             // If this op was a jump target, we don't know what the last op was
             last_op = None;
+            second_last_op = None;
         }
 
         match (last_op, last_op.map(Cell::get), current_op.get()) {
@@ -86,12 +90,44 @@ pub fn postprocess_peephole(
                 // Remove CoerceB before IfTrue, IfFalse, and Not
                 last_op.set(Op::Nop);
             }
+            (Some(last_op), Some(Op::CoerceD), Op::SetLocal { index }) if !has_exceptions => {
+                // Merge CoerceD+SetLocal. This moves the coercion to the
+                // position of the SetLocal, which is only safe to do when no
+                // exception handler could be covering one but not the other.
+                last_op.set(Op::Nop);
+                current_op.set(Op::SetLocalNumber { index });
+            }
+            (Some(last_op), Some(Op::CoerceI), Op::SetLocal { index }) if !has_exceptions => {
+                // Merge CoerceI+SetLocal
+                last_op.set(Op::Nop);
+                current_op.set(Op::SetLocalInt { index });
+            }
             (_, _, Op::GetScopeObject { index: 0 }) => {
                 // Replace `getscopeobject 0` with `getlocal 0` if possible
                 if simple_scope_op_positions.is_some() && !sets_local_0 {
                     current_op.set(Op::GetLocal { index: 0 })
                 }
             }
+            (Some(last_op), Some(Op::AddI | Op::AddInt), op) if !has_exceptions => {
+                // Fold a constant offset added to the address of a domain
+                // memory access into the access itself, as in the
+                // `base + 4` addressing that Alchemy code is full of. An
+                // `AddInt` that overflows produces a Number, which the access
+                // wraps around to the same address as `AddI` would have.
+                let offset = second_last_op.and_then(|op| match op.get() {
+                    Op::PushShort { value } => Some((op, i32::from(value))),
+                    Op::PushInt { value } => Some((op, value)),
+                    _ => None,
+                });
+
+                if let Some((push_op, offset)) = offset {
+                    if let Some(memory_op) = memory_op_with_offset(op, offset) {
+                        push_op.set(Op::Nop);
+                        last_op.set(Op::Nop);
+                        current_op.set(memory_op);
+                    }
+                }
+            }
             _ => {}
         }
 
@@ -100,6 +136,7 @@ pub fn postprocess_peephole(
         // `getlocal0`-`nop`-`pop`, as when the `pop` op is being processed,
         // `last_op` will still be set to the `getlocal0`.
         if !current_op.get().is_nop() {
+            second_last_op = last_op;
             last_op = Some(current_op);
         }
     }
@@ -133,6 +170,31 @@ pub fn postprocess_peephole(
     }
 }
 
+/// Returns the fused form of the domain memory access `op`, which accesses
+/// `offset` bytes past the address on the stack, or `None` if `op` isn't a
+/// domain memory access.
+fn memory_op_with_offset<'gc>(op: Op<'gc>, offset: i32) -> Option<Op<'gc>> {
+    let (kind, is_load) = match op {
+        Op::Li8 => (MemoryAccess::I8, true),
+        Op::Li16 => (MemoryAccess::I16, true),
+        Op::Li32 => (MemoryAccess::I32, true),
+        Op::Lf32 => (MemoryAccess::F32, true),
+        Op::Lf64 => (MemoryAccess::F64, true),
+        Op::Si8 => (MemoryAccess::I8, false),
+        Op::Si16 => (MemoryAccess::I16, false),
+        Op::Si32 => (MemoryAccess::I32, false),
+        Op::Sf32 => (MemoryAccess::F32, false),
+        Op::Sf64 => (MemoryAccess::F64, false),
+        _ => return None,
+    };
+
+    Some(if is_load {
+        Op::LoadMemory { kind, offset }
+    } else {
+        Op::StoreMemory { kind, offset }
+    })
+}
+
 /// Checks if the method fits the following pattern:
 ///
 /// ```text
@@ -0,0 +1,96 @@
+//! Inline caches for property accesses
+
+use crate::avm2::property::Property;
+use crate::avm2::vtable::VTable;
+use crate::avm2::Multiname;
+use gc_arena::barrier::unlock;
+use gc_arena::lock::Lock;
+use gc_arena::{Collect, Gc, Mutation};
+
+/// How many different vtables a cache remembers before it gives up.
+const CACHE_SIZE: usize = 4;
+
+#[derive(Clone, Collect, Copy)]
+#[collect(no_drop)]
+struct CacheEntry<'gc> {
+    vtable: VTable<'gc>,
+    property: Option<Property>,
+}
+
+/// A polymorphic inline cache for a property access with a static name that
+/// the optimizer couldn't resolve, such as a `getproperty` on a receiver of
+/// type `*` or `Object`.
+///
+/// This caches the result of looking up the name in the vtable of the
+/// receiver, for the last few vtables that the access was made on. Once more
+/// vtables than that have been seen, the access is considered megamorphic,
+/// and every lookup goes through the vtable again.
+///
+/// Vtables never change once they are built, so entries never need to be
+/// invalidated. Dynamic properties and `Proxy` objects are only consulted when
+/// no trait was found, which is also what gets cached for them: a cached
+/// `None` still results in a full lookup of the dynamic property by the
+/// caller, so that adding or removing dynamic properties, or a `Proxy`
+/// answering differently, is always observed.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct PropertyCache<'gc> {
+    multiname: Gc<'gc, Multiname<'gc>>,
+
+    entries: Lock<[Option<CacheEntry<'gc>>; CACHE_SIZE]>,
+}
+
+impl<'gc> PropertyCache<'gc> {
+    pub fn new(mc: &Mutation<'gc>, multiname: Gc<'gc, Multiname<'gc>>) -> Gc<'gc, Self> {
+        // Only static names can be cached, as the lookup depends on the name
+        assert!(!multiname.has_lazy_component());
+
+        Gc::new(
+            mc,
+            Self {
+                multiname,
+                entries: Lock::new([None; CACHE_SIZE]),
+            },
+        )
+    }
+
+    pub fn multiname(&self) -> Gc<'gc, Multiname<'gc>> {
+        self.multiname
+    }
+
+    /// Looks up the name of this cache in `vtable`, remembering the result
+    /// if there is still room for it.
+    pub fn get_trait(
+        this: Gc<'gc, Self>,
+        mc: &Mutation<'gc>,
+        vtable: VTable<'gc>,
+    ) -> Option<Property> {
+        let mut entries = this.entries.get();
+
+        let cached = entries
+            .iter()
+            .flatten()
+            .find(|entry| entry.vtable == vtable);
+        if let Some(entry) = cached {
+            return entry.property;
+        }
+
+        let property = vtable.get_trait(&this.multiname);
+
+        // Once every entry is taken, this access is megamorphic
+        if let Some(free) = entries.iter_mut().find(|entry| entry.is_none()) {
+            *free = Some(CacheEntry { vtable, property });
+            unlock!(Gc::write(mc, this), PropertyCache, entries).set(entries);
+        }
+
+        property
+    }
+}
+
+impl std::fmt::Debug for PropertyCache<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PropertyCache")
+            .field("multiname", &self.multiname)
+            .finish()
+    }
+}
@@ -300,6 +300,7 @@ impl Definition {
                 DynamicKey::Object(object) => {
                     Value::Object(*object).coerce_to_string(activation).unwrap()
                 }
+                DynamicKey::WeakObject(_) => unreachable!("Prototypes never have weak keys"),
             };
             if &name != b"constructor" {
                 Self::add_prototype_value(
@@ -77,6 +77,24 @@ impl<'gc> Stack<'gc> {
             .stack_pointer
             .set(self.0.stack_pointer.get() - stack_frame.data.len());
     }
+
+    /// The index at which the next stack frame will start.
+    pub fn stack_pointer(self) -> usize {
+        self.0.stack_pointer.get()
+    }
+
+    /// Read a value from a stack frame that is still alive, by its absolute
+    /// index in the stack.
+    ///
+    /// This is used by the debugger to inspect the locals of frames further
+    /// up the call stack, which it can't hold a `StackFrame` for.
+    pub fn value_at(self, index: usize) -> Value<'gc> {
+        if index < self.0.stack_pointer.get() {
+            self.0.stack[index].get()
+        } else {
+            Value::Undefined
+        }
+    }
 }
 
 unsafe impl<'gc> Collect<'gc> for StackData<'gc> {
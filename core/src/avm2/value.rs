@@ -897,8 +897,24 @@ impl<'gc> Value<'gc> {
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
         let vtable = self.vtable(activation);
+        let property = vtable.get_trait(multiname);
 
-        match vtable.get_trait(multiname) {
+        self.get_resolved_property(multiname, vtable, property, activation)
+    }
+
+    /// Retrieve a property, given the result of looking up `multiname` in
+    /// `vtable`, which must be the vtable of this value.
+    ///
+    /// This is used by the interpreter to skip the lookup when it has already
+    /// cached it; see `PropertyCache`.
+    pub fn get_resolved_property(
+        &self,
+        multiname: &Multiname<'gc>,
+        vtable: VTable<'gc>,
+        property: Option<Property>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 // Only objects can have slots
                 let object = self.as_object().unwrap();
@@ -1008,9 +1024,21 @@ impl<'gc> Value<'gc> {
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<(), Error<'gc>> {
-        let vtable = self.vtable(activation);
+        let property = self.vtable(activation).get_trait(multiname);
 
-        match vtable.get_trait(multiname) {
+        self.set_resolved_property(multiname, property, value, activation)
+    }
+
+    /// Set a property, given the result of looking up `multiname` in the
+    /// vtable of this value.
+    pub fn set_resolved_property(
+        &self,
+        multiname: &Multiname<'gc>,
+        property: Option<Property>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<(), Error<'gc>> {
+        match property {
             Some(Property::Slot { slot_id }) => {
                 // Only objects can have slots
                 let object = self.as_object().unwrap();
@@ -1150,9 +1178,21 @@ impl<'gc> Value<'gc> {
         arguments: FunctionArgs<'_, 'gc>,
         activation: &mut Activation<'_, 'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        let vtable = self.vtable(activation);
+        let property = self.vtable(activation).get_trait(multiname);
 
-        match vtable.get_trait(multiname) {
+        self.call_resolved_property(multiname, property, arguments, activation)
+    }
+
+    /// Call a named property, given the result of looking up `multiname` in
+    /// the vtable of this value.
+    pub fn call_resolved_property(
+        &self,
+        multiname: &Multiname<'gc>,
+        property: Option<Property>,
+        arguments: FunctionArgs<'_, 'gc>,
+        activation: &mut Activation<'_, 'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match property {
             Some(Property::Slot { slot_id }) | Some(Property::ConstSlot { slot_id }) => {
                 // Only objects can have slots
                 let object = self.as_object().unwrap();
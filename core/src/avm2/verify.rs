@@ -1,7 +1,7 @@
 use crate::avm2::class::Class;
 use crate::avm2::error::{
-    make_error_1014, make_error_1021, make_error_1025, make_error_1032, make_error_1054,
-    make_error_1107, verify_error, Error1014Type,
+    make_error_1014, make_error_1019, make_error_1021, make_error_1025, make_error_1032,
+    make_error_1054, make_error_1107, verify_error, Error1014Type,
 };
 use crate::avm2::method::Method;
 use crate::avm2::multiname::Multiname;
@@ -399,29 +399,32 @@ pub fn verify_method<'gc>(
 
         let mut offs = 0;
         while from_offset.is_none() {
+            if (exception.from_offset + offs) as usize >= body.code.len() {
+                return Err(make_error_1054(activation));
+            }
+
             from_offset = byte_offset_to_idx
                 .get(&((exception.from_offset + offs) as usize))
                 .copied();
 
             offs += 1;
-            if (exception.from_offset + offs) as usize >= body.code.len() {
-                return Err(make_error_1054(activation));
-            }
         }
 
-        // Now for the `to_offset`.
+        // Now for the `to_offset`. This may be on the last op of the method,
+        // if the handler is that op.
         let mut to_offset = None;
 
         let mut offs = 0;
         while to_offset.is_none() {
+            if (exception.to_offset + offs) as usize >= body.code.len() {
+                return Err(make_error_1054(activation));
+            }
+
             to_offset = byte_offset_to_idx
                 .get(&((exception.to_offset + offs) as usize))
                 .copied();
 
             offs += 1;
-            if (exception.to_offset + offs) as usize >= body.code.len() {
-                return Err(make_error_1054(activation));
-            }
         }
 
         let new_from_offset = from_offset.unwrap();
@@ -518,6 +521,11 @@ pub fn verify_method<'gc>(
         }
     }
 
+    // The per-instruction stack and scope stack checks are done by the
+    // optimizer's abstract interpretation, which always runs. It tracks operand
+    // types too, but only checks them for `lookupswitch`: other code that Flash
+    // Player rejects for an operand's type (#1058) is accepted here, and fails
+    // when it runs instead.
     crate::avm2::optimizer::optimize(
         activation,
         method,
@@ -797,11 +805,17 @@ fn translate_op<'gc>(
 
         AbcOp::GetOuterScope { index } => {
             if activation.outer().get(index as usize).is_none() {
-                return Err(Error::avm_error(verify_error(
-                    activation,
-                    "Error #1019: Getscopeobject  is out of bounds.",
-                    1019,
-                )?));
+                return Err(make_error_1019(activation, index as usize));
+            }
+        }
+
+        AbcOp::NewCatch { index } => {
+            let body = method
+                .body()
+                .expect("Cannot verify non-native method without body!");
+
+            if index.0 as usize >= body.exceptions.len() {
+                return Err(make_error_1107(activation));
             }
         }
 
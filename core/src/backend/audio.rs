@@ -152,6 +152,12 @@ pub trait AudioBackend: Any {
     /// This is specifically measured in compressed bytes.
     fn get_sound_size(&self, sound: SoundHandle) -> Option<u32>;
 
+    /// Get the total size of the data stored within every registered sound.
+    ///
+    /// This is counted in the memory used by the movie, as reported by
+    /// `System.totalMemory`.
+    fn sound_memory(&self) -> usize;
+
     /// Get the sound format that a given sound was added with.
     fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat>;
 
@@ -302,6 +308,9 @@ impl AudioBackend for NullAudioBackend {
             None
         }
     }
+    fn sound_memory(&self) -> usize {
+        self.sounds.values().map(|sound| sound.size as usize).sum()
+    }
 
     fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat> {
         self.sounds.get(sound).map(|s| &s.format)
@@ -682,6 +682,10 @@ impl AudioMixer {
         self.sounds.get(sound).map(|s| s.data.len() as u32)
     }
 
+    pub fn sound_memory(&self) -> usize {
+        self.sounds.values().map(|s| s.data.len()).sum()
+    }
+
     pub fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat> {
         self.sounds.get(sound).map(|s| &s.format)
     }
@@ -1130,6 +1134,11 @@ macro_rules! impl_audio_mixer_backend {
             self.$mixer.get_sound_size(sound)
         }
 
+        #[inline]
+        fn sound_memory(&self) -> usize {
+            self.$mixer.sound_memory()
+        }
+
         #[inline]
         fn get_sound_format(&self, sound: SoundHandle) -> Option<&swf::SoundFormat> {
             self.$mixer.get_sound_format(sound)
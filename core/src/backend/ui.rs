@@ -82,6 +82,12 @@ pub trait UiBackend: Any {
 
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError>;
 
+    /// Locks the mouse to the player, hiding the cursor, or releases it.
+    ///
+    /// While the mouse is locked, its movement should be reported with
+    /// `PlayerEvent::MouseMoveRelative`. This is only called in fullscreen.
+    fn set_mouse_lock(&mut self, locked: bool);
+
     /// Displays a message about an error during root movie download.
     /// In particular, on web this can be a CORS error, which we can sidestep
     /// by providing a direct .swf link instead.
@@ -184,6 +190,8 @@ impl UiBackend for NullUiBackend {
         Ok(())
     }
 
+    fn set_mouse_lock(&mut self, _locked: bool) {}
+
     fn display_root_movie_download_failed_message(&self, _invalid_swf: bool, _fetch_error: String) {
     }
 
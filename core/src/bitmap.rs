@@ -1,4 +1,5 @@
 pub mod bitmap_data;
+pub mod encode;
 pub mod operations;
 pub mod turbulence;
 
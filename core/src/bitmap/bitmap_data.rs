@@ -1,6 +1,7 @@
 use crate::avm2::object::BitmapDataObject;
 use crate::context::RenderContext;
 use crate::display_object::{DisplayObject, DisplayObjectWeak, TDisplayObject};
+use crate::memory::ExternalMemory;
 use bitflags::bitflags;
 use gc_arena::lock::GcRefLock;
 use gc_arena::{Collect, Gc, Mutation};
@@ -232,7 +233,8 @@ impl<'gc> BitmapData<'gc> {
         transparency: bool,
         fill_color: u32,
     ) -> Self {
-        let data = BitmapRawData::new(width, height, transparency, fill_color);
+        let mut data = BitmapRawData::new(width, height, transparency, fill_color);
+        data.track_memory(mc);
         let data = BitmapRawDataWrapper::new(Gc::new(mc, data.into()));
 
         Self(data)
@@ -245,7 +247,8 @@ impl<'gc> BitmapData<'gc> {
         transparency: bool,
         pixels: Vec<Color>,
     ) -> Self {
-        let data = BitmapRawData::new_with_pixels(width, height, transparency, pixels);
+        let mut data = BitmapRawData::new_with_pixels(width, height, transparency, pixels);
+        data.track_memory(mc);
         let data = BitmapRawDataWrapper::new(Gc::new(mc, data.into()));
 
         Self(data)
@@ -260,7 +263,8 @@ impl<'gc> BitmapData<'gc> {
         mc: &Mutation<'gc>,
         renderer: &mut dyn RenderBackend,
     ) -> BitmapData<'gc> {
-        let data = self.0.clone_data(renderer);
+        let mut data = self.0.clone_data(renderer);
+        data.track_memory(mc);
         let data = BitmapRawDataWrapper::new(Gc::new(mc, data.into()));
         Self(data)
     }
@@ -369,6 +373,11 @@ pub struct BitmapRawData<'gc> {
     #[collect(require_static)]
     pixels: Vec<Color>,
 
+    /// The memory used by `pixels`, which is counted in the metrics of the
+    /// GC arena once this is given to a `BitmapData`.
+    #[collect(require_static)]
+    memory: ExternalMemory,
+
     width: u32,
     height: u32,
     transparency: bool,
@@ -420,6 +429,7 @@ mod wrapper {
     use crate::avm2::object::BitmapDataObject;
     use crate::context::RenderContext;
     use crate::display_object::DisplayObjectWeak;
+    use crate::memory::ExternalMemory;
     use gc_arena::barrier::Write;
     use gc_arena::lock::GcRefLock;
     use gc_arena::{Collect, Gc, Mutation};
@@ -476,6 +486,7 @@ mod wrapper {
                 mc,
                 BitmapRawData {
                     pixels: Vec::new(),
+                    memory: ExternalMemory::default(),
                     width: 0,
                     height: 0,
                     transparency: false,
@@ -499,6 +510,7 @@ mod wrapper {
             let data = self.sync(renderer).borrow();
             BitmapRawData {
                 pixels: data.pixels.clone(),
+                memory: ExternalMemory::default(),
                 width: data.width,
                 height: data.height,
                 transparency: data.transparency,
@@ -741,6 +753,7 @@ impl<'gc> BitmapRawData<'gc> {
                 Color::bgra_u32(fill_color).to_premultiplied_alpha(transparency);
                 width as usize * height as usize
             ],
+            memory: ExternalMemory::default(),
             width,
             height,
             transparency,
@@ -762,6 +775,7 @@ impl<'gc> BitmapRawData<'gc> {
     ) -> Self {
         Self {
             pixels,
+            memory: ExternalMemory::default(),
             width,
             height,
             transparency,
@@ -779,10 +793,16 @@ impl<'gc> BitmapRawData<'gc> {
         self.disposed
     }
 
+    /// Count the pixels of this bitmap in the memory used by the movie.
+    fn track_memory(&mut self, mc: &Mutation<'_>) {
+        self.memory = ExternalMemory::new(mc, self.pixels.len() * std::mem::size_of::<Color>());
+    }
+
     pub fn dispose(&mut self) {
         self.width = 0;
         self.height = 0;
         self.pixels = Vec::new(); // free the CPU pixel buffer
+        self.memory = ExternalMemory::default();
         self.bitmap_handle = None;
         // There's no longer a handle to update
         self.dirty_state = DirtyState::Clean;
@@ -0,0 +1,168 @@
+//! Encoding of bitmap pixels into image files, as used by `BitmapData.encode`.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{ExtendedColorType, ImageEncoder, ImageError};
+
+/// The image format to encode to, along with its settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitmapEncoding {
+    /// Corresponds to `flash.display.PNGEncoderOptions`.
+    Png { fast_compression: bool },
+
+    /// Corresponds to `flash.display.JPEGEncoderOptions`.
+    ///
+    /// `quality` ranges from 1 to 100, with higher values giving larger but better looking images.
+    Jpeg { quality: u8 },
+}
+
+/// Encodes un-multiplied RGBA pixels, given row by row, into an image file.
+///
+/// When `transparent` is false, the alpha channel is left out of formats that support one.
+pub fn encode_rgba(
+    encoding: BitmapEncoding,
+    width: u32,
+    height: u32,
+    transparent: bool,
+    rgba: &[u8],
+) -> Result<Vec<u8>, ImageError> {
+    debug_assert_eq!(rgba.len(), width as usize * height as usize * 4);
+
+    let mut output = vec![];
+    match encoding {
+        BitmapEncoding::Png { fast_compression } => {
+            let encoder = if fast_compression {
+                PngEncoder::new_with_quality(&mut output, CompressionType::Fast, FilterType::Sub)
+            } else {
+                PngEncoder::new_with_quality(
+                    &mut output,
+                    CompressionType::Best,
+                    FilterType::Adaptive,
+                )
+            };
+            if transparent {
+                encoder.write_image(rgba, width, height, ExtendedColorType::Rgba8)?;
+            } else {
+                encoder.write_image(&to_rgb(rgba), width, height, ExtendedColorType::Rgb8)?;
+            }
+        }
+        BitmapEncoding::Jpeg { quality } => {
+            // JPEG has no alpha channel, so it is always dropped.
+            JpegEncoder::new_with_quality(&mut output, quality).write_image(
+                &to_rgb(rgba),
+                width,
+                height,
+                ExtendedColorType::Rgb8,
+            )?;
+        }
+    }
+    Ok(output)
+}
+
+fn to_rgb(rgba: &[u8]) -> Vec<u8> {
+    rgba.chunks_exact(4)
+        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+
+    /// A gradient with some noise, so that compression settings make a difference.
+    fn test_image(width: u32, height: u32) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        let mut seed = 0x12345678u32;
+        for y in 0..height {
+            for x in 0..width {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                let noise = (seed >> 24) as u8 / 8;
+                rgba.extend([
+                    (x * 255 / width) as u8 ^ noise,
+                    (y * 255 / height) as u8,
+                    ((x + y) * 4) as u8,
+                    (255 - x) as u8,
+                ]);
+            }
+        }
+        rgba
+    }
+
+    #[test]
+    fn png_round_trips_losslessly() {
+        let rgba = test_image(64, 48);
+        for fast_compression in [false, true] {
+            let encoding = BitmapEncoding::Png { fast_compression };
+            let png = encode_rgba(encoding, 64, 48, true, &rgba).unwrap();
+            assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+            let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (64, 48));
+            assert_eq!(decoded.to_rgba8().into_raw(), rgba);
+        }
+    }
+
+    #[test]
+    fn png_fast_compression_is_not_smaller() {
+        let rgba = test_image(128, 128);
+        let fast = encode_rgba(
+            BitmapEncoding::Png {
+                fast_compression: true,
+            },
+            128,
+            128,
+            true,
+            &rgba,
+        );
+        let best = encode_rgba(
+            BitmapEncoding::Png {
+                fast_compression: false,
+            },
+            128,
+            128,
+            true,
+            &rgba,
+        );
+        assert!(fast.unwrap().len() >= best.unwrap().len());
+    }
+
+    #[test]
+    fn opaque_png_has_no_alpha_channel() {
+        let rgba = test_image(16, 16);
+        let encoding = BitmapEncoding::Png {
+            fast_compression: false,
+        };
+        let png = encode_rgba(encoding, 16, 16, false, &rgba).unwrap();
+        let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+        assert!(!decoded.color().has_alpha());
+        assert_eq!(decoded.to_rgb8().into_raw(), to_rgb(&rgba));
+    }
+
+    #[test]
+    fn jpeg_decodes_and_honours_quality() {
+        let rgba = test_image(64, 64);
+        let low = encode_rgba(BitmapEncoding::Jpeg { quality: 10 }, 64, 64, true, &rgba).unwrap();
+        let high = encode_rgba(BitmapEncoding::Jpeg { quality: 100 }, 64, 64, true, &rgba).unwrap();
+        assert!(low.len() < high.len());
+
+        for jpeg in [low, high] {
+            assert!(jpeg.starts_with(&[0xFF, 0xD8, 0xFF]));
+            let decoded = image::load_from_memory_with_format(&jpeg, ImageFormat::Jpeg).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (64, 64));
+        }
+
+        // At the highest quality, the decoded colors should be close to the original ones.
+        let high = encode_rgba(BitmapEncoding::Jpeg { quality: 100 }, 64, 64, true, &rgba).unwrap();
+        let decoded = image::load_from_memory_with_format(&high, ImageFormat::Jpeg)
+            .unwrap()
+            .to_rgb8()
+            .into_raw();
+        let error: u64 = decoded
+            .iter()
+            .zip(to_rgb(&rgba))
+            .map(|(a, b)| a.abs_diff(b) as u64)
+            .sum();
+        assert!(error / (decoded.len() as u64) < 8);
+    }
+}
@@ -13,8 +13,10 @@ use gc_arena::Mutation;
 use ruffle_render::backend::RenderBackend;
 use ruffle_render::bitmap::{PixelRegion, PixelSnapping};
 use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
-use ruffle_render::filters::Filter;
+use ruffle_render::filters::{Filter, ShaderFilter};
 use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender_interpreter::{self, ShaderMode};
+use ruffle_render::pixel_bender_support::PixelBenderShaderArgument;
 use ruffle_render::quality::StageQuality;
 use ruffle_render::transform::Transform;
 use std::cell::{Ref, RefMut};
@@ -1220,6 +1222,22 @@ pub fn apply_filter<'gc>(
         return;
     }
 
+    let filter = match filter {
+        Filter::ShaderFilter(filter) if pixel_bender_interpreter::is_cpu_shader(&filter.shader) => {
+            apply_shader_filter_on_cpu(
+                context,
+                target,
+                source,
+                source_point,
+                source_size,
+                dest_point,
+                filter,
+            );
+            return;
+        }
+        filter => filter,
+    };
+
     if !context.renderer.is_filter_supported(&filter) {
         let mut source_region = PixelRegion::for_whole_size(source.width(), source.height());
         let mut dest_region = PixelRegion::for_whole_size(target.width(), target.height());
@@ -1269,6 +1287,89 @@ pub fn apply_filter<'gc>(
     }
 }
 
+/// Applies a `ShaderFilter` whose shader is run by `pixel_bender_interpreter`,
+/// which needs the pixels of the source rather than a `BitmapHandle`.
+fn apply_shader_filter_on_cpu<'gc>(
+    context: &mut UpdateContext<'gc>,
+    target: BitmapData<'gc>,
+    source: BitmapData<'gc>,
+    source_point: (u32, u32),
+    source_size: (u32, u32),
+    dest_point: (u32, u32),
+    mut filter: ShaderFilter<'static>,
+) {
+    let (width, height) = source_size;
+
+    let mut source_rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    {
+        let source_read = source.read_area(
+            PixelRegion::for_whole_size(source.width(), source.height()),
+            context.renderer,
+        );
+        for y in source_point.1..source_point.1 + height {
+            for x in source_point.0..source_point.0 + width {
+                let color = if source_read.is_point_in_bounds(x as i32, y as i32) {
+                    source_read.get_pixel32_raw(x, y)
+                } else {
+                    Color::rgba(0, 0, 0, 0)
+                };
+                source_rgba.extend([color.red(), color.green(), color.blue(), color.alpha()]);
+            }
+        }
+    }
+
+    // Like the GPU implementation, the source is only used as the first image input.
+    for argument in &mut filter.shader_args {
+        if let PixelBenderShaderArgument::ImageInput { texture, .. } = argument {
+            *texture = Some(pixel_bender_interpreter::image_input_from_rgba(
+                width,
+                height,
+                &source_rgba,
+            ));
+            break;
+        }
+    }
+
+    let shader = filter.shader.0.parsed_shader();
+    let output = match pixel_bender_interpreter::run_shader(
+        shader,
+        &filter.shader_args,
+        width,
+        height,
+        ShaderMode::Filter,
+    ) {
+        Ok(output) => output,
+        Err(e) => {
+            tracing::warn!("BitmapData.apply_filter: Failed to run shader: {e}");
+            return;
+        }
+    };
+    let output =
+        pixel_bender_interpreter::output_to_rgba(&output, shader.output_channels().unwrap_or(4));
+
+    let target = target.sync(context.renderer);
+    let mut write = target.borrow_mut(context.gc());
+    let mut dest_region = PixelRegion::for_region(dest_point.0, dest_point.1, width, height);
+    dest_region.clamp(write.width(), write.height());
+    if dest_region.width() == 0 || dest_region.height() == 0 {
+        return;
+    }
+
+    for y in dest_region.y_min..dest_region.y_max {
+        for x in dest_region.x_min..dest_region.x_max {
+            let index = ((y - dest_point.1) * width + (x - dest_point.0)) as usize * 4;
+            let [r, g, b, a] = [
+                output[index],
+                output[index + 1],
+                output[index + 2],
+                output[index + 3],
+            ];
+            write.set_pixel32_raw(x, y, Color::rgba(r, g, b, a));
+        }
+    }
+    write.set_cpu_dirty(context.gc(), dest_region);
+}
+
 fn copy_on_cpu<'gc>(
     context: &Mutation<'gc>,
     renderer: &mut dyn RenderBackend,
@@ -1705,6 +1806,32 @@ pub fn get_pixels_as_byte_array<'gc>(
     Ok(())
 }
 
+/// Reads the un-multiplied RGBA pixels of a region of `target`, clamped to its bounds.
+///
+/// Returns the size of the clamped region, along with its pixels in row order.
+pub fn get_rgba_pixels(
+    renderer: &mut dyn RenderBackend,
+    target: BitmapData,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+) -> (u32, u32, Vec<u8>) {
+    let mut region = PixelRegion::for_region_i32(x, y, width, height);
+    region.clamp(target.width(), target.height());
+
+    let read = target.read_area(region, renderer);
+    let mut pixels = Vec::with_capacity(region.width() as usize * region.height() as usize * 4);
+    for y in region.y_min..region.y_max {
+        for x in region.x_min..region.x_max {
+            let color = read.get_pixel32_raw(x, y).to_un_multiplied_alpha();
+            pixels.extend([color.red(), color.green(), color.blue(), color.alpha()]);
+        }
+    }
+
+    (region.width(), region.height(), pixels)
+}
+
 #[expect(clippy::too_many_arguments)]
 pub fn set_pixels_from_byte_array<'gc>(
     mc: &Mutation<'gc>,
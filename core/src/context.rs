@@ -19,6 +19,7 @@ use crate::context_menu::ContextMenuState;
 use crate::display_object::{EditText, MovieClip, SoundTransform, Stage};
 use crate::events::PlayerNotification;
 use crate::external::ExternalInterface;
+use crate::fdb::Fdb;
 use crate::focus_tracker::FocusTracker;
 use crate::frame_lifecycle::FramePhase;
 use crate::input::InputManager;
@@ -89,6 +90,12 @@ pub struct UpdateContext<'gc> {
     /// Requests that the player re-renders after this execution (e.g. due to `updateAfterEvent`).
     pub needs_render: &'gc mut bool,
 
+    /// Requests a full garbage collection after this execution (e.g. due to `System.gc()`).
+    pub gc_requested: &'gc mut bool,
+
+    /// The memory used by the movie after the last garbage collection.
+    pub memory_after_gc: usize,
+
     /// The root SWF file.
     pub root_swf: &'gc mut Arc<SwfMovie>,
 
@@ -216,6 +223,9 @@ pub struct UpdateContext<'gc> {
 
     pub sockets: &'gc mut Sockets<'gc>,
 
+    /// The session with a debugger, if the movie is being debugged.
+    pub fdb: &'gc mut Fdb<'gc>,
+
     /// List of active NetConnection instances.
     pub net_connections: &'gc mut NetConnections<'gc>,
 
@@ -440,6 +450,8 @@ impl<'gc> UpdateContext<'gc> {
         drop(activation);
 
         self.audio.set_frame_rate(*self.frame_rate);
+
+        Fdb::movie_loaded(self);
     }
 
     pub fn replace_root_movie(&mut self, movie: SwfMovie) {
@@ -471,7 +483,9 @@ impl<'gc> UpdateContext<'gc> {
     }
 
     pub fn avm_trace(&self, message: &str) {
-        self.log.avm_trace(&message.replace('\r', "\n"));
+        let message = message.replace('\r', "\n");
+        self.fdb.trace(self.sockets, &message);
+        self.log.avm_trace(&message);
     }
 
     pub fn avm_warning(&self, message: &str) {
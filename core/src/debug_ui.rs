@@ -7,7 +7,7 @@ mod handle;
 mod movie;
 
 use crate::context::{RenderContext, UpdateContext};
-use crate::debug_ui::avm1::Avm1ObjectWindow;
+use crate::debug_ui::avm1::{Avm1ObjectWindow, Avm1TraceRecorderWindow};
 use crate::debug_ui::avm2::Avm2ObjectWindow;
 use crate::debug_ui::display_object::{DisplayObjectSearchWindow, DisplayObjectWindow};
 use crate::debug_ui::domain::DomainListWindow;
@@ -37,6 +37,7 @@ pub struct DebugUi {
     movie_list: Option<MovieListWindow>,
     domain_list: Option<DomainListWindow>,
     display_object_search: Option<DisplayObjectSearchWindow>,
+    avm1_trace_recorder: Option<Avm1TraceRecorderWindow>,
 }
 
 #[derive(Debug)]
@@ -53,6 +54,7 @@ pub enum Message {
     SaveFile(ItemToSave),
     SearchForDisplayObject,
     TrackRootMovieClip,
+    ShowAvm1TraceRecorder,
 }
 
 impl DebugUi {
@@ -100,6 +102,16 @@ impl DebugUi {
             }
         }
 
+        // Bring up the trace recorder whenever AVM1 code breaks, even if it was closed.
+        if context.avm1.trace_recorder().is_paused() && self.avm1_trace_recorder.is_none() {
+            self.avm1_trace_recorder = Some(Default::default());
+        }
+        if let Some(mut window) = self.avm1_trace_recorder.take() {
+            if window.show(egui_ctx, context, &mut messages) {
+                self.avm1_trace_recorder = Some(window);
+            }
+        }
+
         for message in messages {
             match message {
                 Message::TrackDisplayObject(object) => {
@@ -136,6 +148,10 @@ impl DebugUi {
                 Message::SearchForDisplayObject => {
                     self.display_object_search = Some(Default::default());
                 }
+                Message::ShowAvm1TraceRecorder => {
+                    self.avm1_trace_recorder
+                        .get_or_insert_with(Default::default);
+                }
                 Message::TrackRootMovieClip => {
                     // Convenience action to quickly access the root movie clip
                     if let Some(obj @ DisplayObject::MovieClip(_)) = context.stage.root_clip() {
@@ -151,6 +167,10 @@ impl DebugUi {
 
     pub fn should_suspend_player(&self) -> bool {
         self.display_object_search.is_some()
+            || self
+                .avm1_trace_recorder
+                .as_ref()
+                .is_some_and(|window| window.is_paused())
     }
 
     pub fn items_to_save(&mut self) -> Vec<ItemToSave> {
@@ -202,6 +222,15 @@ impl DebugUi {
             }
         }
 
+        if let Some(window) = &self.avm1_trace_recorder {
+            if let Some(object) = window.hovered_debug_rect() {
+                let object = object.fetch(dynamic_root_set);
+                let bounds = world_matrix * object.debug_rect_bounds();
+
+                draw_debug_rect(context, Color::RED, bounds, 5.0);
+            }
+        }
+
         for (_object, window) in self.avm1_objects.iter() {
             if let Some(object) = window.hovered_debug_rect() {
                 let object = object.fetch(dynamic_root_set);
@@ -1,13 +1,17 @@
 use crate::avm1::globals::style_sheet::StyleSheetObject;
+use crate::avm1::trace_recorder::{BreakReason, Breakpoint};
 use crate::avm1::{Activation, ActivationIdentifier, Error, NativeObject, Object, Value};
 use crate::context::UpdateContext;
 use crate::debug_ui::display_object::open_display_object_button;
 use crate::debug_ui::handle::{AVM1ObjectHandle, DisplayObjectHandle};
 use crate::debug_ui::Message;
 use crate::string::AvmString;
-use egui::{Grid, Id, TextBuffer, TextEdit, Ui, Window};
+use crate::tag_utils::SwfSlice;
+use egui::{Align, Grid, Id, RichText, TextBuffer, TextEdit, Ui, Window};
 use gc_arena::Mutation;
 use ruffle_wstr::{WStr, WString};
+use swf::avm1::read::Reader;
+use swf::avm1::types::Action;
 
 use super::common::show_style_sheet;
 
@@ -269,6 +273,442 @@ impl Avm1ObjectWindow {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Hash, Default, Copy, Clone)]
+enum TraceRecorderPanel {
+    #[default]
+    Code,
+    Variables,
+    Stack,
+    Breakpoints,
+}
+
+/// Shows where AVM1 code is paused, and lets the user step through it.
+#[derive(Debug, Default)]
+pub struct Avm1TraceRecorderWindow {
+    hovered_debug_rect: Option<DisplayObjectHandle>,
+    open_panel: TraceRecorderPanel,
+    frame_breakpoint_path: String,
+    frame_breakpoint_frame: u16,
+    /// The action scrolled to last, so that we only scroll when it changes.
+    shown_offset: Option<usize>,
+    paused: bool,
+}
+
+impl Avm1TraceRecorderWindow {
+    pub fn hovered_debug_rect(&self) -> Option<DisplayObjectHandle> {
+        self.hovered_debug_rect.clone()
+    }
+
+    /// Whether the player should stay suspended for the user to look at paused code.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn show<'gc>(
+        &mut self,
+        egui_ctx: &egui::Context,
+        context: &mut UpdateContext<'gc>,
+        messages: &mut Vec<Message>,
+    ) -> bool {
+        let mut keep_open = true;
+        let base_clip = context.stage.into();
+        let mut activation =
+            Activation::from_nothing(context, ActivationIdentifier::root("Debug"), base_clip);
+        Window::new("AVM1 Trace Recorder")
+            .open(&mut keep_open)
+            .scroll([true, true])
+            .show(egui_ctx, |ui| {
+                self.show_controls(ui, &mut activation);
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.open_panel, TraceRecorderPanel::Code, "Code");
+                    ui.selectable_value(
+                        &mut self.open_panel,
+                        TraceRecorderPanel::Variables,
+                        "Variables",
+                    );
+                    ui.selectable_value(&mut self.open_panel, TraceRecorderPanel::Stack, "Stack");
+                    ui.selectable_value(
+                        &mut self.open_panel,
+                        TraceRecorderPanel::Breakpoints,
+                        "Breakpoints",
+                    );
+                });
+                ui.separator();
+
+                match self.open_panel {
+                    TraceRecorderPanel::Code => self.show_code(ui, &mut activation),
+                    TraceRecorderPanel::Variables => {
+                        self.show_variables(ui, &mut activation, messages)
+                    }
+                    TraceRecorderPanel::Stack => self.show_stack(ui, &mut activation, messages),
+                    TraceRecorderPanel::Breakpoints => self.show_breakpoints(ui, &mut activation),
+                }
+            });
+
+        let recorder = activation.context.avm1.trace_recorder_mut();
+        if !keep_open {
+            // Don't leave the player suspended without a way to resume it.
+            while recorder.is_paused() {
+                recorder.resume();
+            }
+        }
+        self.paused = recorder.is_paused();
+        keep_open
+    }
+
+    fn show_controls(&mut self, ui: &mut Ui, activation: &mut Activation<'_, '_>) {
+        let recorder = activation.context.avm1.trace_recorder_mut();
+        ui.horizontal(|ui| {
+            if recorder.is_paused() {
+                if ui.button("Continue").clicked() {
+                    recorder.resume();
+                }
+                if ui.button("Step Over").clicked() {
+                    recorder.step_over();
+                }
+                if ui.button("Step In").clicked() {
+                    recorder.step_in();
+                }
+                if ui.button("Step Out").clicked() {
+                    recorder.step_out();
+                }
+            } else if ui.button("Pause").clicked() {
+                recorder.pause();
+            }
+        });
+
+        match recorder.current() {
+            Some(record) => {
+                let (position, length) = recorder.recording_position();
+                let reason = match record.reason {
+                    Some(BreakReason::Breakpoint) => "Hit a breakpoint in ",
+                    Some(BreakReason::Frame) => "Hit a frame breakpoint in ",
+                    Some(BreakReason::Pause) => "Paused in ",
+                    Some(BreakReason::Step) | None => "Stepped to ",
+                };
+                ui.label(format!("{reason}{}", record.activation));
+                ui.weak(format!(
+                    "Action {} of {length} run since breaking",
+                    position + 1
+                ));
+            }
+            None => {
+                ui.label("Running");
+            }
+        }
+    }
+
+    fn show_code(&mut self, ui: &mut Ui, activation: &mut Activation<'_, '_>) {
+        let Some((code, offset)) = activation
+            .context
+            .avm1
+            .trace_recorder()
+            .current()
+            .map(|record| (record.code.clone(), record.offset))
+        else {
+            ui.weak("Not paused");
+            return;
+        };
+        let scroll = self.shown_offset != Some(offset);
+        self.shown_offset = Some(offset);
+
+        ui.label(format!("{} at {offset:#x}", code.movie.url()));
+        let recorder = activation.context.avm1.trace_recorder_mut();
+        Grid::new(ui.id().with("disassembly"))
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (action_offset, action) in disassemble(&code) {
+                    let breakpoint = Breakpoint::Action {
+                        url: code.movie.url().to_string(),
+                        offset: action_offset,
+                    };
+                    let has_breakpoint = recorder.breakpoints().contains(&breakpoint);
+                    if ui
+                        .selectable_label(has_breakpoint, "●")
+                        .on_hover_text("Toggle breakpoint")
+                        .clicked()
+                    {
+                        recorder.toggle_breakpoint(breakpoint);
+                    }
+                    ui.monospace(format!("{action_offset:#06x}"));
+                    let text = RichText::new(action).monospace();
+                    if action_offset == offset {
+                        let response = ui.label(
+                            text.strong()
+                                .background_color(ui.style().visuals.selection.bg_fill),
+                        );
+                        if scroll {
+                            response.scroll_to_me(Some(Align::Center));
+                        }
+                    } else {
+                        ui.label(text);
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_variables<'gc>(
+        &mut self,
+        ui: &mut Ui,
+        activation: &mut Activation<'_, 'gc>,
+        messages: &mut Vec<Message>,
+    ) {
+        let Some((clip, this, scope, registers, local_registers)) = activation
+            .context
+            .avm1
+            .trace_recorder()
+            .current()
+            .map(|record| {
+                (
+                    record.clip,
+                    record.this,
+                    record.scope,
+                    record.registers.clone(),
+                    record.local_registers,
+                )
+            })
+        else {
+            ui.weak("Not paused");
+            return;
+        };
+
+        Grid::new(ui.id().with("location"))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Clip");
+                open_display_object_button(
+                    ui,
+                    activation.context,
+                    messages,
+                    clip,
+                    &mut self.hovered_debug_rect,
+                );
+                ui.end_row();
+
+                ui.label("this");
+                self.show_value(ui, activation, this, messages);
+                ui.end_row();
+            });
+
+        ui.heading("Scope Chain");
+        Grid::new(ui.id().with("scope"))
+            .num_columns(2)
+            .show(ui, |ui| {
+                let mut scope = Some(scope);
+                while let Some(current) = scope {
+                    ui.label(format!("{:?}", current.class()));
+                    self.show_value(ui, activation, current.locals_cell().into(), messages);
+                    ui.end_row();
+                    scope = current.parent();
+                }
+            });
+
+        ui.heading(if local_registers {
+            "Local Registers"
+        } else {
+            "Global Registers"
+        });
+        Grid::new(ui.id().with("registers"))
+            .num_columns(2)
+            .show(ui, |ui| {
+                for (index, value) in registers.into_iter().enumerate() {
+                    ui.label(format!("r{index}"));
+                    self.show_value(ui, activation, value, messages);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_stack<'gc>(
+        &mut self,
+        ui: &mut Ui,
+        activation: &mut Activation<'_, 'gc>,
+        messages: &mut Vec<Message>,
+    ) {
+        let Some(stack) = activation
+            .context
+            .avm1
+            .trace_recorder()
+            .current()
+            .map(|record| record.stack.clone())
+        else {
+            ui.weak("Not paused");
+            return;
+        };
+        if stack.is_empty() {
+            ui.weak("Empty");
+        }
+
+        Grid::new(ui.id().with("stack"))
+            .num_columns(2)
+            .show(ui, |ui| {
+                // Show the top of the stack first.
+                for (index, value) in stack.into_iter().enumerate().rev() {
+                    ui.label(index.to_string());
+                    self.show_value(ui, activation, value, messages);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_breakpoints(&mut self, ui: &mut Ui, activation: &mut Activation<'_, '_>) {
+        let recorder = activation.context.avm1.trace_recorder_mut();
+        let mut removed = None;
+        Grid::new(ui.id().with("breakpoints"))
+            .num_columns(2)
+            .show(ui, |ui| {
+                for breakpoint in recorder.breakpoints() {
+                    match breakpoint {
+                        Breakpoint::Action { url, offset } => {
+                            ui.label(format!("{url} at {offset:#x}"))
+                        }
+                        Breakpoint::Frame { path, frame } => {
+                            ui.label(format!("{path} on frame {frame}"))
+                        }
+                    };
+                    if ui.button(CANCEL_ICON).on_hover_text("Remove").clicked() {
+                        removed = Some(breakpoint.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(breakpoint) = removed {
+            recorder.remove_breakpoint(&breakpoint);
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut self.frame_breakpoint_path)
+                    .hint_text("_level0")
+                    .desired_width(160.0),
+            );
+            ui.label("on frame");
+            ui.add(egui::DragValue::new(&mut self.frame_breakpoint_frame).range(1..=u16::MAX));
+            let path = self.frame_breakpoint_path.trim();
+            if ui
+                .add_enabled(!path.is_empty(), egui::Button::new("Add"))
+                .clicked()
+            {
+                recorder.add_breakpoint(Breakpoint::Frame {
+                    path: path.to_string(),
+                    frame: self.frame_breakpoint_frame.max(1),
+                });
+            }
+        });
+    }
+
+    fn show_value<'gc>(
+        &mut self,
+        ui: &mut Ui,
+        activation: &mut Activation<'_, 'gc>,
+        value: Value<'gc>,
+        messages: &mut Vec<Message>,
+    ) {
+        match value {
+            Value::Undefined => {
+                ui.weak("undefined");
+            }
+            Value::Null => {
+                ui.weak("null");
+            }
+            Value::Bool(value) => {
+                ui.label(value.to_string());
+            }
+            Value::Number(value) => {
+                ui.label(value.to_string());
+            }
+            Value::String(value) => {
+                ui.label(format!("{:?}", value.to_utf8_lossy()));
+            }
+            Value::Object(value) => {
+                if ui.button(object_name(value)).clicked() {
+                    messages.push(Message::TrackAVM1Object(AVM1ObjectHandle::new(
+                        activation.context,
+                        value,
+                    )));
+                }
+            }
+            Value::MovieClip(value) => {
+                if let Some((_, _, object)) = value.resolve_reference(activation) {
+                    open_display_object_button(
+                        ui,
+                        activation.context,
+                        messages,
+                        object,
+                        &mut self.hovered_debug_rect,
+                    );
+                } else {
+                    ui.colored_label(
+                        ui.style().visuals.error_fg_color,
+                        format!("Unknown movieclip {}", value.path()),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Lists the actions of a block of code along with their offsets in the SWF.
+///
+/// The bodies of functions defined in the block aren't part of the list.
+fn disassemble(code: &SwfSlice) -> Vec<(usize, String)> {
+    let data = code.data();
+    let mut reader = Reader::new(data, code.version());
+    let mut actions = vec![];
+    while !reader.get_ref().is_empty() {
+        let offset = code.start + (reader.get_ref().as_ptr() as usize - data.as_ptr() as usize);
+        match reader.read_action() {
+            Ok(action) => {
+                let is_end = matches!(action, Action::End);
+                actions.push((offset, action_text(&action, code.movie.encoding())));
+                if is_end {
+                    break;
+                }
+            }
+            Err(e) => {
+                actions.push((offset, format!("Error: {e}")));
+                break;
+            }
+        }
+    }
+    actions
+}
+
+fn action_text(action: &Action, encoding: &'static swf::Encoding) -> String {
+    match action {
+        Action::DefineFunction(function) => format!(
+            "DefineFunction {:?}({}) ({} bytes)",
+            function.name.to_str_lossy(encoding),
+            function
+                .params
+                .iter()
+                .map(|param| param.to_str_lossy(encoding))
+                .collect::<Vec<_>>()
+                .join(", "),
+            function.actions.len()
+        ),
+        Action::DefineFunction2(function) => format!(
+            "DefineFunction2 {:?}({}) ({} bytes)",
+            function.name.to_str_lossy(encoding),
+            function
+                .params
+                .iter()
+                .map(|param| param.name.to_str_lossy(encoding))
+                .collect::<Vec<_>>()
+                .join(", "),
+            function.actions.len()
+        ),
+        Action::With(with) => format!("With ({} bytes)", with.actions.len()),
+        Action::Try(_) => "Try".to_string(),
+        action => format!("{action:?}"),
+    }
+}
+
 /// Dropdown menu indicating the type of the value, as well as letting the
 /// user set a new type.
 fn show_value_type_combo_box<'gc>(
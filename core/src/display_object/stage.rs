@@ -130,6 +130,9 @@ pub struct StageData<'gc> {
     /// Whether to allow the stage's displayState to be changed.
     allow_fullscreen: Cell<bool>,
 
+    /// Whether the mouse is locked (`Stage.mouseLock`). This is only possible in fullscreen.
+    mouse_lock: Cell<bool>,
+
     /// Whether or not a RENDER event should be dispatched on the next render
     invalidated: Cell<bool>,
 
@@ -177,6 +180,7 @@ impl<'gc> Stage<'gc> {
                 align: Default::default(),
                 forced_align: Cell::new(false),
                 allow_fullscreen: Cell::new(true),
+                mouse_lock: Cell::new(false),
                 use_bitmap_downsampling: Cell::new(false),
                 view_bounds: Default::default(),
                 window_mode: Default::default(),
@@ -400,10 +404,35 @@ impl<'gc> Stage<'gc> {
 
         if result.is_ok() {
             self.0.display_state.set(display_state);
+            if !self.is_fullscreen() {
+                // Leaving fullscreen always releases the mouse.
+                self.set_mouse_lock(context, false);
+            }
             self.fire_fullscreen_event(context);
         }
     }
 
+    /// Gets whether the mouse is locked.
+    pub fn mouse_lock(self) -> bool {
+        self.0.mouse_lock.get()
+    }
+
+    /// Locks or unlocks the mouse.
+    ///
+    /// The mouse can only be locked in fullscreen, so this returns `false`
+    /// if a lock is requested outside of it.
+    pub fn set_mouse_lock(self, context: &mut UpdateContext<'gc>, locked: bool) -> bool {
+        if locked && !self.is_fullscreen() {
+            return false;
+        }
+
+        if locked != self.mouse_lock() {
+            context.ui.set_mouse_lock(locked);
+            self.0.mouse_lock.set(locked);
+        }
+        true
+    }
+
     /// Get the stage alignment.
     pub fn align(self) -> StageAlign {
         self.0.align.get()
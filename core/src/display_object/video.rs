@@ -4,6 +4,7 @@ use crate::avm1::{NativeObject as Avm1NativeObject, Object as Avm1Object, Value
 use crate::avm2::StageObject as Avm2StageObject;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{Avm1TextFieldBinding, DisplayObjectBase, RenderOptions};
+use crate::memory::ExternalMemory;
 use crate::prelude::*;
 use crate::streams::NetStream;
 use crate::tag_utils::{SwfMovie, SwfSlice};
@@ -74,6 +75,10 @@ pub struct VideoData<'gc> {
     /// NOTE: This is only used for SWF-source video streams.
     decoded_frame: RefCell<Option<(u32, BitmapInfo)>>,
 
+    /// The memory used by `decoded_frame`, counted in the metrics of the GC arena.
+    #[collect(require_static)]
+    decoded_frame_memory: RefCell<ExternalMemory>,
+
     /// The self bounds for this movie.
     size: Cell<(i32, i32)>,
 }
@@ -152,6 +157,7 @@ impl<'gc> Video<'gc> {
                 movie,
                 size: Cell::new(size),
                 decoded_frame: RefCell::new(None),
+                decoded_frame_memory: RefCell::new(ExternalMemory::default()),
             },
         ))
     }
@@ -175,6 +181,7 @@ impl<'gc> Video<'gc> {
                 movie,
                 size: Cell::new((width, height)),
                 decoded_frame: RefCell::new(None),
+                decoded_frame_memory: RefCell::new(ExternalMemory::default()),
             },
         ))
     }
@@ -335,6 +342,8 @@ impl<'gc> Video<'gc> {
 
         match res {
             Ok(bitmap) => {
+                *self.0.decoded_frame_memory.borrow_mut() =
+                    ExternalMemory::for_bitmap(context.gc(), &bitmap);
                 self.0.decoded_frame.replace(Some((frame_id, bitmap)));
                 self.invalidate_cached_bitmap();
                 *context.needs_render = true;
@@ -2,6 +2,7 @@ use crate::context::RenderContext;
 use ruffle_render::backend::{RenderBackend, ShapeHandle};
 use ruffle_render::bitmap::{BitmapHandle, BitmapInfo, BitmapSize, BitmapSource};
 use ruffle_render::commands::CommandHandler;
+use ruffle_render::pixel_bender_support::ShaderFill;
 use ruffle_render::shape_utils::{
     cubic_curve_bounds, quadratic_curve_bounds, DistilledShape, DrawCommand, DrawPath, FillRule,
 };
@@ -96,6 +97,22 @@ impl Drawing {
                         this.draw_command(command);
                     }
 
+                    this.set_fill_style(None);
+                }
+                DrawPath::ShaderFill {
+                    fill,
+                    commands,
+                    winding_rule,
+                } => {
+                    this.start_fill(
+                        Some(DrawingFillStyle::Shader(fill.clone())),
+                        Some(winding_rule),
+                    );
+
+                    for command in commands {
+                        this.draw_command(command);
+                    }
+
                     this.set_fill_style(None);
                 }
             }
@@ -117,11 +134,23 @@ impl Drawing {
     /// Set fill rule and keep the same fill style.
     pub fn set_fill_rule(&mut self, rule: Option<FillRule>) {
         let style = self.current_fill.as_ref().map(|fill| fill.style.clone());
-        self.new_fill(style, rule);
+        self.start_fill(style, rule);
     }
 
     /// Set fill style and rule.
     pub fn new_fill(&mut self, style: Option<FillStyle>, rule: Option<FillRule>) {
+        self.start_fill(style.map(DrawingFillStyle::Style), rule);
+    }
+
+    /// Fill with a Pixel Bender shader, and reset fill rule to default.
+    pub fn set_shader_fill(&mut self, fill: ShaderFill) {
+        self.start_fill(
+            Some(DrawingFillStyle::Shader(fill)),
+            Some(self.default_winding_rule),
+        );
+    }
+
+    fn start_fill(&mut self, style: Option<DrawingFillStyle>, rule: Option<FillRule>) {
         self.close_path();
         if let Some(existing) = self.current_fill.take() {
             self.paths.push(DrawingPath::Fill(existing));
@@ -251,11 +280,7 @@ impl Drawing {
             for path in &self.paths {
                 match path {
                     DrawingPath::Fill(fill) => {
-                        paths.push(DrawPath::Fill {
-                            style: &fill.style,
-                            commands: fill.commands.to_owned(),
-                            winding_rule: fill.rule,
-                        });
+                        paths.push(fill.to_draw_path());
                     }
                     DrawingPath::Line(line) => {
                         paths.push(DrawPath::Stroke {
@@ -268,11 +293,7 @@ impl Drawing {
             }
 
             if let Some(fill) = &self.current_fill {
-                paths.push(DrawPath::Fill {
-                    style: &fill.style,
-                    commands: fill.commands.to_owned(),
-                    winding_rule: fill.rule,
-                })
+                paths.push(fill.to_draw_path())
             }
 
             for line in &self.pending_lines {
@@ -429,13 +450,36 @@ impl BitmapSource for Drawing {
     }
 }
 
+#[derive(Debug, Clone)]
+enum DrawingFillStyle {
+    Style(FillStyle),
+    Shader(ShaderFill),
+}
+
 #[derive(Debug, Clone)]
 struct DrawingFill {
-    style: FillStyle,
+    style: DrawingFillStyle,
     rule: FillRule,
     commands: Vec<DrawCommand>,
 }
 
+impl DrawingFill {
+    fn to_draw_path(&self) -> DrawPath<'_> {
+        match &self.style {
+            DrawingFillStyle::Style(style) => DrawPath::Fill {
+                style,
+                commands: self.commands.to_owned(),
+                winding_rule: self.rule,
+            },
+            DrawingFillStyle::Shader(fill) => DrawPath::ShaderFill {
+                fill,
+                commands: self.commands.to_owned(),
+                winding_rule: self.rule,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct DrawingLine {
     style: LineStyle,
@@ -17,6 +17,13 @@ pub enum PlayerEvent {
         x: f64,
         y: f64,
     },
+    /// Mouse was moved while it's locked (see `UiBackend::set_mouse_lock`).
+    ///
+    /// The deltas are in screen pixels, and the cursor position doesn't change.
+    MouseMoveRelative {
+        dx: f64,
+        dy: f64,
+    },
     MouseUp {
         x: f64,
         y: f64,
@@ -0,0 +1,935 @@
+//! The player side of the debugger protocol spoken by `fdb`, and by the IDEs
+//! built on top of it.
+//!
+//! Movies compiled for debugging carry an `EnableDebugger2` tag. When a
+//! debugger address is configured, loading such a movie connects to the
+//! debugger, and the movie is held until the debugger has set its breakpoints
+//! and tells it to continue. If the tag sets a password, the player has to be
+//! given the same password, or the movie isn't debugged at all; like Flash
+//! Player, the password is asked of the person running the movie, not of the
+//! debugger.
+//!
+//! AVM2 bytecode names its own source files and lines. AVM1 code is mapped to
+//! them by the SWD file next to the movie, which is fetched while connecting.
+//!
+//! Messages from the debugger arrive on the same thread as scripts run on, so
+//! a script can't wait for them while paused. Instead, the debuggers of both
+//! AVMs record what runs for the rest of the tick once they break, the player
+//! holds still afterwards, and stepping walks through those recordings.
+
+mod avm2;
+pub mod message;
+mod password;
+mod swd;
+
+use crate::avm1::trace_recorder::{BreakReason, Breakpoint};
+use crate::avm1::{Activation, ActivationIdentifier, Object as Avm1Object, Value as Avm1Value};
+use crate::avm2::debugger::recorder::Recorder;
+use crate::avm2::debugger::StopReason;
+use crate::backend::navigator::Request;
+use crate::context::UpdateContext;
+use crate::socket::{SocketHandle, Sockets};
+use crate::string::AvmString;
+use crate::tag_utils::{self, ControlFlow, SwfMovie};
+use avm2::{debug_files, source_path};
+use gc_arena::Collect;
+use message::*;
+use password::password_matches;
+use std::sync::Arc;
+use swd::Swd;
+use swf::extensions::ReadSwfExt;
+use swf::TagCode;
+use url::Url;
+
+/// The port debuggers listen on.
+pub const DEFAULT_PORT: u16 = 7935;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SessionState {
+    /// Waiting for the connection to the debugger.
+    Connecting,
+
+    /// Connected, but the debugger hasn't been told about the movie yet.
+    Connected,
+
+    /// Waiting for the debugger to set its breakpoints and continue.
+    Configuring,
+
+    Running,
+}
+
+enum ScriptKind {
+    /// AVM1 code, located through the SWD.
+    Avm1,
+
+    /// AVM2 code, located through the path of its source file.
+    Avm2 { source: String },
+}
+
+struct Script {
+    /// The id the debugger refers to this script by.
+    id: u16,
+    name: String,
+    text: String,
+    kind: ScriptKind,
+}
+
+struct Session {
+    handle: SocketHandle,
+    state: SessionState,
+    movie: Arc<SwfMovie>,
+
+    /// The length of the SWF header, which offsets in the SWD count but
+    /// offsets into the movie data don't.
+    header_len: usize,
+
+    debug_id: Option<[u8; 16]>,
+
+    /// The source files of the AVM2 code in the movie.
+    abc_files: Vec<String>,
+
+    swd: Option<Swd>,
+
+    /// Whether the SWD is still being fetched.
+    swd_pending: bool,
+
+    scripts: Vec<Script>,
+
+    /// Incoming data that doesn't form a complete message yet.
+    buffer: Vec<u8>,
+
+    /// The breakpoint locations set by the debugger.
+    breakpoints: Vec<u32>,
+
+    /// The AVM1 breakpoints set on behalf of the debugger.
+    avm1_breakpoints: Vec<Breakpoint>,
+
+    avm2: Option<Recorder>,
+
+    /// Whether traces shouldn't be sent.
+    squelch: bool,
+
+    /// Whether the debugger has been told where the player is paused.
+    reported: bool,
+}
+
+/// A location in the source code, as the debugger refers to it.
+fn location(script: u16, line: u32) -> u32 {
+    script as u32 | (line << 16)
+}
+
+impl Session {
+    fn send(&self, sockets: &Sockets<'_>, message: Message) {
+        sockets.send(self.handle, message.encode());
+    }
+
+    fn swd_offset(&self, offset: usize) -> u32 {
+        (offset + self.header_len) as u32
+    }
+
+    fn avm1_location(&self, offset: usize) -> u32 {
+        self.swd
+            .as_ref()
+            .and_then(|swd| swd.line_at(self.swd_offset(offset)))
+            .map_or(0, |entry| location(entry.script as u16, entry.line))
+    }
+
+    /// Whether the AVM1 action at `offset` starts a line, which is true of
+    /// every action when there is no SWD.
+    fn is_avm1_line_start(&self, offset: usize) -> bool {
+        self.swd
+            .as_ref()
+            .is_none_or(|swd| swd.is_line_start(self.swd_offset(offset)))
+    }
+
+    fn avm2_location(&self, file: Option<&str>, line: u32) -> u32 {
+        self.scripts
+            .iter()
+            .find(|script| Some(script.name.as_str()) == file)
+            .map_or(0, |script| location(script.id, line))
+    }
+
+    fn list_scripts(&mut self) {
+        if let Some(swd) = &self.swd {
+            self.scripts.extend(swd.scripts.iter().map(|script| Script {
+                id: script.id as u16,
+                name: script.name.clone(),
+                text: script.text.clone(),
+                kind: ScriptKind::Avm1,
+            }));
+        }
+        let first_id = self.scripts.iter().map(|script| script.id + 1).max();
+        let first_id = first_id.unwrap_or(1);
+        self.scripts.extend(
+            self.abc_files
+                .iter()
+                .zip(first_id..)
+                .map(|(name, id)| Script {
+                    id,
+                    name: name.clone(),
+                    text: String::new(),
+                    kind: ScriptKind::Avm2 {
+                        source: source_path(name),
+                    },
+                }),
+        );
+    }
+
+    /// Whether a breakpoint can be set at `location`.
+    fn resolves(&self, location: u32) -> bool {
+        let (id, line) = (location as u16, location >> 16);
+        match self.scripts.iter().find(|script| script.id == id) {
+            Some(Script {
+                kind: ScriptKind::Avm1,
+                ..
+            }) => self
+                .swd
+                .as_ref()
+                .is_some_and(|swd| swd.line_offsets(id.into(), line).next().is_some()),
+            Some(Script {
+                kind: ScriptKind::Avm2 { .. },
+                ..
+            }) => line > 0,
+            None => false,
+        }
+    }
+}
+
+/// What the tags of a movie say about debugging it.
+#[derive(Default)]
+struct MovieInfo {
+    debugging: bool,
+    /// The hashed password of the `EnableDebugger` tag, if it sets one.
+    password: Option<String>,
+    debug_id: Option<[u8; 16]>,
+    abc_files: Vec<String>,
+}
+
+impl MovieInfo {
+    fn read(movie: &SwfMovie) -> Self {
+        let mut info = Self::default();
+        let mut reader = swf::read::Reader::new(movie.data(), movie.version());
+        let _ = tag_utils::decode_tags(&mut reader, |reader, tag_code, _tag_len| {
+            match tag_code {
+                TagCode::EnableDebugger | TagCode::EnableDebugger2 => {
+                    info.debugging = true;
+                    if tag_code == TagCode::EnableDebugger2 {
+                        reader.read_u16()?; // Reserved
+                    }
+                    let password = reader.read_str()?.to_string_lossy(reader.encoding());
+                    info.password = (!password.is_empty()).then_some(password);
+                }
+                TagCode::DebugId => info.debug_id = Some(reader.read_debug_id()?),
+                TagCode::DoAbc => info.abc_files.extend(debug_files(reader.as_slice())),
+                TagCode::DoAbc2 => info
+                    .abc_files
+                    .extend(debug_files(reader.read_do_abc_2()?.data)),
+                TagCode::End => return Ok(ControlFlow::Exit),
+                _ => {}
+            }
+            Ok(ControlFlow::Continue)
+        });
+        info
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Step {
+    In,
+    Over,
+    Out,
+}
+
+#[derive(Collect, Default)]
+#[collect(no_drop)]
+pub struct Fdb<'gc> {
+    /// The host and port to connect to when a movie enables debugging.
+    #[collect(require_static)]
+    address: Option<(String, u16)>,
+
+    /// The password given for movies that protect debugging with one.
+    #[collect(require_static)]
+    password: Option<String>,
+
+    #[collect(require_static)]
+    session: Option<Session>,
+
+    /// The AVM1 objects the debugger was told about, numbered from 1.
+    /// The debugger can only look into them until the player resumes.
+    objects: Vec<Avm1Object<'gc>>,
+}
+
+impl<'gc> Fdb<'gc> {
+    pub fn new(address: Option<(String, u16)>, password: Option<String>) -> Self {
+        Self {
+            address,
+            password,
+            session: None,
+            objects: Vec::new(),
+        }
+    }
+
+    /// Whether the player has to hold still, because the debugger is being
+    /// connected to or the code is paused.
+    pub(crate) fn is_halted(context: &UpdateContext<'gc>) -> bool {
+        let Some(session) = &context.fdb.session else {
+            return false;
+        };
+        session.state != SessionState::Running
+            || context.avm1.trace_recorder().is_paused()
+            || session.avm2.as_ref().is_some_and(|link| link.is_paused())
+    }
+
+    /// Called when the root movie has been loaded, to connect to the debugger
+    /// if the movie asks for it.
+    pub(crate) fn movie_loaded(context: &mut UpdateContext<'gc>) {
+        if let Some(session) = context.fdb.session.take() {
+            context.sockets.close(session.handle);
+            if let Some(link) = session.avm2 {
+                link.disconnect();
+            }
+        }
+
+        let Some((host, port)) = context.fdb.address.clone() else {
+            return;
+        };
+        let movie = context.root_swf.clone();
+        let info = MovieInfo::read(&movie);
+        if !info.debugging {
+            return;
+        }
+        if let Some(hash) = &info.password {
+            let given = context.fdb.password.as_deref();
+            if !given.is_some_and(|password| password_matches(hash, password)) {
+                tracing::warn!(
+                    "Not connecting to the debugger, as the movie's debugging password wasn't given"
+                );
+                return;
+            }
+        }
+
+        tracing::info!("Connecting to debugger at {host}:{port}");
+        let handle = context
+            .sockets
+            .connect_debugger(context.navigator, host, port);
+
+        // AVM2 code can only be debugged if the debugger is there before it runs.
+        let avm2 = (movie.is_action_script_3() && !context.avm2.debugger_attached()).then(|| {
+            let link = Recorder::new();
+            context.avm2.attach_debugger(Box::new(link.clone()));
+            link
+        });
+
+        let swd_url = (!movie.is_action_script_3())
+            .then(|| Url::parse(movie.url()).ok())
+            .flatten()
+            .and_then(|mut url| {
+                let path = url.path().strip_suffix(".swf")?.to_string();
+                url.set_path(&format!("{path}.swd"));
+                Some(url)
+            });
+        if let Some(swd_url) = &swd_url {
+            let player = context.player.clone();
+            let fetch = context.navigator.fetch(Request::get(swd_url.to_string()));
+            context.navigator.spawn_future(Box::pin(async move {
+                let data = match fetch.await {
+                    Ok(response) => response.body().await.ok(),
+                    Err(_) => None,
+                };
+                if let Some(player) = player.upgrade() {
+                    player
+                        .lock()
+                        .unwrap()
+                        .mutate_with_update_context(|context| context.fdb.swd_loaded(data));
+                }
+                Ok(())
+            }));
+        }
+
+        context.fdb.session = Some(Session {
+            handle,
+            state: SessionState::Connecting,
+            header_len: (movie.uncompressed_len().max(0) as usize)
+                .saturating_sub(movie.data().len()),
+            movie,
+            debug_id: info.debug_id,
+            abc_files: info.abc_files,
+            swd: None,
+            swd_pending: swd_url.is_some(),
+            scripts: Vec::new(),
+            buffer: Vec::new(),
+            breakpoints: Vec::new(),
+            avm1_breakpoints: Vec::new(),
+            avm2,
+            squelch: false,
+            reported: false,
+        });
+    }
+
+    fn swd_loaded(&mut self, data: Option<Vec<u8>>) {
+        let Some(session) = &mut self.session else {
+            return;
+        };
+        session.swd_pending = false;
+        session.swd = data.and_then(Swd::parse).filter(|swd| {
+            let matches = swd.debug_id.is_none() || swd.debug_id == session.debug_id;
+            if !matches {
+                tracing::warn!("Ignoring SWD, as it doesn't belong to the movie");
+            }
+            matches
+        });
+        if session.swd.is_none() {
+            tracing::warn!("No SWD found for the movie, so its AVM1 code can't be debugged");
+        }
+    }
+
+    pub(crate) fn connected(context: &mut UpdateContext<'gc>, success: bool) {
+        if success {
+            if let Some(session) = &mut context.fdb.session {
+                session.state = SessionState::Connected;
+            }
+        } else {
+            tracing::warn!("Couldn't connect to the debugger");
+            Self::disconnected(context);
+        }
+    }
+
+    pub(crate) fn disconnected(context: &mut UpdateContext<'gc>) {
+        let Some(session) = context.fdb.session.take() else {
+            return;
+        };
+        context.fdb.objects.clear();
+
+        let recorder = context.avm1.trace_recorder_mut();
+        for breakpoint in &session.avm1_breakpoints {
+            recorder.remove_breakpoint(breakpoint);
+        }
+        while recorder.is_paused() {
+            recorder.resume();
+        }
+        if let Some(link) = session.avm2 {
+            link.disconnect();
+        }
+    }
+
+    pub(crate) fn receive(context: &mut UpdateContext<'gc>, data: Vec<u8>) {
+        if let Some(session) = &mut context.fdb.session {
+            session.buffer.extend(data);
+        }
+        while let Some(message) = context
+            .fdb
+            .session
+            .as_mut()
+            .and_then(|session| Message::decode(&mut session.buffer))
+        {
+            Self::handle_message(context, message);
+        }
+    }
+
+    /// Called after every tick, to tell the debugger what happened during it.
+    pub(crate) fn update(context: &mut UpdateContext<'gc>) {
+        let Some(session) = &mut context.fdb.session else {
+            return;
+        };
+
+        if session.state == SessionState::Connected && !session.swd_pending {
+            session.list_scripts();
+            session.send(
+                context.sockets,
+                Message::new(IN_VERSION)
+                    .with_u32(context.player_version.into())
+                    // The size of object ids.
+                    .with_u8(4),
+            );
+            session.send(
+                context.sockets,
+                Message::new(IN_NUM_SCRIPT).with_u32(session.scripts.len() as u32),
+            );
+            for script in &session.scripts {
+                session.send(
+                    context.sockets,
+                    Message::new(IN_SCRIPT)
+                        .with_u32(script.id.into())
+                        // The bitmap, which isn't used.
+                        .with_u32(0)
+                        .with_str(&script.name)
+                        .with_str(&script.text)
+                        // The index of the SWF, as only the root movie is debugged.
+                        .with_u32(0),
+                );
+            }
+            session.send(context.sockets, Message::new(IN_ASK_BREAKPOINTS));
+            session.state = SessionState::Configuring;
+        }
+
+        if session.state == SessionState::Running && !session.reported {
+            Self::report_break(context);
+        }
+    }
+
+    /// Tell the debugger where the player is paused, if it is.
+    fn report_break(context: &mut UpdateContext<'gc>) {
+        let Some(session) = &mut context.fdb.session else {
+            return;
+        };
+
+        let (reason, offset, frames) = if let Some(record) = context.avm1.trace_recorder().current()
+        {
+            let reason = match record.reason {
+                Some(BreakReason::Breakpoint | BreakReason::Frame) => REASON_BREAKPOINT,
+                Some(BreakReason::Pause) => REASON_STOP_REQUEST,
+                Some(BreakReason::Step) | None => REASON_STEP,
+            };
+            let frames = vec![(
+                session.avm1_location(record.offset),
+                record.activation.clone(),
+            )];
+            (reason, session.swd_offset(record.offset), frames)
+        } else if let Some(snapshot) = session.avm2.as_ref().and_then(|link| link.current()) {
+            let reason = match snapshot.reason {
+                StopReason::Breakpoint | StopReason::FunctionBreakpoint => REASON_BREAKPOINT,
+                StopReason::Pause => REASON_STOP_REQUEST,
+                StopReason::Step => REASON_STEP,
+            };
+            let frames = snapshot
+                .frames
+                .iter()
+                .map(|frame| {
+                    (
+                        session.avm2_location(frame.file.as_deref(), frame.line),
+                        frame.name.clone(),
+                    )
+                })
+                .collect();
+            (reason, 0, frames)
+        } else {
+            return;
+        };
+
+        session.send(
+            context.sockets,
+            Message::new(IN_BREAK_REASON)
+                .with_u16(reason)
+                .with_u16(0)
+                .with_u32(offset),
+        );
+        let message = Message::new(IN_BREAK_AT_EXT)
+            .with_u32(frames.first().map_or(0, |(location, _)| *location))
+            .with_u32(frames.len() as u32);
+        let message = frames.iter().fold(message, |message, (location, name)| {
+            // The id of `this`, which isn't available for every frame.
+            message.with_u32(*location).with_u32(0).with_str(name)
+        });
+        session.send(context.sockets, message);
+        session.reported = true;
+    }
+
+    pub(crate) fn trace(&self, sockets: &Sockets<'gc>, message: &str) {
+        if let Some(session) = &self.session {
+            if session.state != SessionState::Connecting && !session.squelch {
+                session.send(sockets, Message::new(IN_TRACE).with_str(message));
+            }
+        }
+    }
+
+    /// Tell the debugger about an error that no script caught.
+    pub(crate) fn exception(&self, sockets: &Sockets<'gc>, message: &str) {
+        if let Some(session) = &self.session {
+            if session.state != SessionState::Connecting {
+                session.send(
+                    sockets,
+                    Message::new(IN_ERROR_EXCEPTION)
+                        .with_u32(0)
+                        .with_str(message),
+                );
+            }
+        }
+    }
+
+    fn send(&self, sockets: &Sockets<'gc>, message: Message) {
+        if let Some(session) = &self.session {
+            session.send(sockets, message);
+        }
+    }
+
+    fn handle_message(context: &mut UpdateContext<'gc>, message: Message) {
+        let Some(session) = &mut context.fdb.session else {
+            return;
+        };
+        let mut reader = message.reader();
+
+        match message.kind {
+            OUT_CONTINUE => {
+                if session.state == SessionState::Configuring {
+                    session.state = SessionState::Running;
+                } else {
+                    Self::resume(context, None);
+                }
+            }
+            OUT_STEP_INTO => Self::resume(context, Some(Step::In)),
+            OUT_STEP_OVER => Self::resume(context, Some(Step::Over)),
+            OUT_STEP_OUT => Self::resume(context, Some(Step::Out)),
+            OUT_SET_BREAKPOINTS => {
+                let count = reader.read_u32().unwrap_or(0);
+                let mut added = Vec::new();
+                for location in (0..count).map_while(|_| reader.read_u32()) {
+                    if session.resolves(location) {
+                        if !session.breakpoints.contains(&location) {
+                            session.breakpoints.push(location);
+                        }
+                        added.push(location);
+                    }
+                }
+                let message = added.iter().fold(
+                    Message::new(IN_SET_BREAKPOINT).with_u32(added.len() as u32),
+                    |message, location| message.with_u32(*location),
+                );
+                session.send(context.sockets, message);
+                Self::apply_breakpoints(context);
+            }
+            OUT_REMOVE_BREAKPOINTS => {
+                let count = reader.read_u32().unwrap_or(0);
+                let mut removed = Vec::new();
+                for location in (0..count).map_while(|_| reader.read_u32()) {
+                    if let Some(index) = session.breakpoints.iter().position(|b| *b == location) {
+                        session.breakpoints.remove(index);
+                        removed.push(location);
+                    }
+                }
+                let message = removed.iter().fold(
+                    Message::new(IN_REMOVE_BREAKPOINT).with_u32(removed.len() as u32),
+                    |message, location| message.with_u32(*location),
+                );
+                session.send(context.sockets, message);
+                Self::apply_breakpoints(context);
+            }
+            OUT_REMOVE_ALL_BREAKPOINTS => {
+                session.breakpoints.clear();
+                Self::apply_breakpoints(context);
+            }
+            OUT_SET_SQUELCH => {
+                let squelch = reader.read_u32().unwrap_or(0);
+                session.squelch = squelch != 0;
+                session.send(context.sockets, Message::new(IN_SQUELCH).with_u32(squelch));
+            }
+            OUT_GET_FIELD => {
+                let id = reader.read_u32().unwrap_or(0);
+                let name = reader.read_str().unwrap_or_default();
+                Self::send_field(context, id, name);
+            }
+            OUT_GET_FUNCTION_FRAME => {
+                let depth = reader.read_u32().unwrap_or(0);
+                Self::send_frame(context, depth);
+            }
+            OUT_GET_SWF => {
+                let mut data = Vec::new();
+                let header = session.movie.header().swf_header();
+                let header = swf::Header {
+                    compression: swf::Compression::None,
+                    ..header.clone()
+                };
+                if let Err(e) =
+                    swf::write::write_swf_raw_tags(&header, session.movie.data(), &mut data)
+                {
+                    tracing::error!("Couldn't write the movie for the debugger: {e}");
+                }
+                session.send(
+                    context.sockets,
+                    Message::new(IN_GET_SWF)
+                        .with_u32(data.len() as u32)
+                        .with_bytes(&data),
+                );
+            }
+            OUT_GET_SWD => {
+                let data = session.swd.as_ref().map_or(&[][..], |swd| &swd.data);
+                session.send(
+                    context.sockets,
+                    Message::new(IN_GET_SWD)
+                        .with_u32(data.len() as u32)
+                        .with_bytes(data),
+                );
+            }
+            OUT_SWF_INFO => {
+                session.send(
+                    context.sockets,
+                    Message::new(IN_SWF_INFO)
+                        // Only the root movie is debugged.
+                        .with_u16(1)
+                        .with_u32(0)
+                        .with_u32(session.movie.uncompressed_len().max(0) as u32)
+                        .with_u32(session.swd.as_ref().map_or(0, |swd| swd.data.len() as u32))
+                        .with_u32(session.scripts.len() as u32)
+                        .with_str(session.movie.url()),
+                );
+            }
+            OUT_GET_VERSION => {
+                session.send(
+                    context.sockets,
+                    Message::new(IN_VERSION)
+                        .with_u32(context.player_version.into())
+                        .with_u8(4),
+                );
+            }
+            OUT_GET_BREAK_REASON => {
+                session.reported = false;
+                Self::report_break(context);
+            }
+            kind => tracing::warn!("Unsupported debugger message {kind}"),
+        }
+    }
+
+    /// Pass the breakpoints set by the debugger on to the AVMs.
+    fn apply_breakpoints(context: &mut UpdateContext<'gc>) {
+        let Some(session) = &mut context.fdb.session else {
+            return;
+        };
+
+        let recorder = context.avm1.trace_recorder_mut();
+        for breakpoint in session.avm1_breakpoints.drain(..) {
+            recorder.remove_breakpoint(&breakpoint);
+        }
+        if let Some(swd) = &session.swd {
+            for &location in &session.breakpoints {
+                for offset in swd.line_offsets((location as u16).into(), location >> 16) {
+                    let breakpoint = Breakpoint::Action {
+                        url: session.movie.url().to_string(),
+                        offset: (offset as usize).saturating_sub(session.header_len),
+                    };
+                    recorder.add_breakpoint(breakpoint.clone());
+                    session.avm1_breakpoints.push(breakpoint);
+                }
+            }
+        }
+
+        if let Some(link) = &session.avm2 {
+            for script in &session.scripts {
+                let ScriptKind::Avm2 { source } = &script.kind else {
+                    continue;
+                };
+                let lines = session
+                    .breakpoints
+                    .iter()
+                    .filter(|&&location| location as u16 == script.id)
+                    .map(|location| location >> 16)
+                    .collect();
+                link.set_line_breakpoints(source.clone(), lines);
+            }
+        }
+    }
+
+    /// Continue running, either until the next breakpoint or for a step.
+    fn resume(context: &mut UpdateContext<'gc>, step: Option<Step>) {
+        let Some(session) = &mut context.fdb.session else {
+            return;
+        };
+        session.reported = false;
+        context.fdb.objects.clear();
+
+        let recorder = context.avm1.trace_recorder_mut();
+        if recorder.is_paused() {
+            match step {
+                None => recorder.resume(),
+                Some(Step::In) => recorder.step_in(),
+                Some(Step::Over) => recorder.step_over(),
+                Some(Step::Out) => recorder.step_out(),
+            }
+            // Steps are made of lines, which can be several actions long.
+            if let Some(step) = step {
+                while let Some(record) = recorder.current() {
+                    if session.is_avm1_line_start(record.offset) {
+                        break;
+                    }
+                    match step {
+                        Step::In => recorder.step_in(),
+                        Step::Over | Step::Out => recorder.step_over(),
+                    }
+                }
+            }
+        } else if let Some(link) = &session.avm2 {
+            match step {
+                None => link.resume(),
+                Some(Step::In) => link.step_in(),
+                Some(Step::Over) => link.step_over(),
+                Some(Step::Out) => link.step_out(),
+            };
+        }
+    }
+
+    /// Send the variables of the function at `depth` in the call stack.
+    fn send_frame(context: &mut UpdateContext<'gc>, depth: u32) {
+        let variables = if depth != 0 {
+            // Only the innermost frame is recorded.
+            Vec::new()
+        } else if let Some(record) = context.avm1.trace_recorder().current() {
+            let (clip, this, locals, registers) = (
+                record.clip,
+                record.this,
+                record.scope.locals_cell(),
+                record.registers.clone(),
+            );
+            let mut activation =
+                Activation::from_nothing(context, ActivationIdentifier::root("[Debugger]"), clip);
+
+            let mut variables = vec![Variable {
+                name: "this".to_string(),
+                value: Self::avm1_value(&mut activation, this),
+            }];
+            variables.extend(Self::avm1_members(&mut activation, locals));
+            for (index, value) in registers.into_iter().enumerate() {
+                variables.push(Variable {
+                    name: format!("r{index}"),
+                    value: Self::avm1_value(&mut activation, value),
+                });
+            }
+            variables
+        } else if let Some(snapshot) = context
+            .fdb
+            .session
+            .as_ref()
+            .and_then(|session| session.avm2.as_ref()?.current())
+        {
+            snapshot
+                .locals
+                .into_iter()
+                .map(|local| Variable {
+                    value: match local.type_name.as_str() {
+                        "null" => VariableValue::Null,
+                        "void" => VariableValue::Undefined,
+                        "Number" | "int" | "uint" => VariableValue::Number(local.value),
+                        "Boolean" => VariableValue::Boolean(local.value == "true"),
+                        "String" => VariableValue::String(
+                            local
+                                .value
+                                .strip_prefix('"')
+                                .and_then(|value| value.strip_suffix('"'))
+                                .unwrap_or(&local.value)
+                                .to_string(),
+                        ),
+                        // The contents of AVM2 objects are only available
+                        // while the AVM2 debugger is stopped.
+                        _ => VariableValue::Object {
+                            id: 0,
+                            class: local.type_name,
+                        },
+                    },
+                    name: local.name,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        context.fdb.send(
+            context.sockets,
+            Message::new(IN_FRAME)
+                .with_u32(depth)
+                .with_variables(&variables),
+        );
+    }
+
+    /// Send the member `name` of the object with `id`, or all of its members
+    /// if `name` is empty.
+    fn send_field(context: &mut UpdateContext<'gc>, id: u32, name: String) {
+        let object = (id as usize)
+            .checked_sub(1)
+            .and_then(|index| context.fdb.objects.get(index))
+            .copied();
+        let variables = match (object, context.stage.root_clip()) {
+            (Some(object), Some(root)) => {
+                let mut activation = Activation::from_nothing(
+                    context,
+                    ActivationIdentifier::root("[Debugger]"),
+                    root,
+                );
+                if name.is_empty() {
+                    Self::avm1_members(&mut activation, object)
+                } else {
+                    let key = AvmString::new_utf8(activation.gc(), &name);
+                    let value = object
+                        .get(key, &mut activation)
+                        .unwrap_or(Avm1Value::Undefined);
+                    vec![Variable {
+                        value: Self::avm1_value(&mut activation, value),
+                        name,
+                    }]
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        context.fdb.send(
+            context.sockets,
+            Message::new(IN_GET_VARIABLE)
+                .with_u32(id)
+                .with_variables(&variables),
+        );
+    }
+
+    fn avm1_members(
+        activation: &mut Activation<'_, 'gc>,
+        object: Avm1Object<'gc>,
+    ) -> Vec<Variable> {
+        let mut keys = object.get_keys(activation, false);
+        keys.sort();
+        keys.into_iter()
+            .map(|key| {
+                let value = object.get(key, activation).unwrap_or(Avm1Value::Undefined);
+                Variable {
+                    name: key.to_string(),
+                    value: Self::avm1_value(activation, value),
+                }
+            })
+            .collect()
+    }
+
+    fn avm1_value(activation: &mut Activation<'_, 'gc>, value: Avm1Value<'gc>) -> VariableValue {
+        let object = match value {
+            Avm1Value::Undefined => return VariableValue::Undefined,
+            Avm1Value::Null => return VariableValue::Null,
+            Avm1Value::Bool(value) => return VariableValue::Boolean(value),
+            Avm1Value::Number(_) => {
+                return VariableValue::Number(
+                    value
+                        .coerce_to_string(activation)
+                        .map(|value| value.to_string())
+                        .unwrap_or_default(),
+                )
+            }
+            Avm1Value::String(value) => return VariableValue::String(value.to_string()),
+            Avm1Value::Object(object) => object,
+            Avm1Value::MovieClip(reference) => match reference.resolve_reference(activation) {
+                Some((_, object, _)) => object,
+                None => return VariableValue::Undefined,
+            },
+        };
+
+        let objects = &mut activation.context.fdb.objects;
+        let id = match objects
+            .iter()
+            .position(|known| Avm1Object::ptr_eq(*known, object))
+        {
+            Some(index) => index + 1,
+            None => {
+                objects.push(object);
+                objects.len()
+            }
+        } as u32;
+
+        if object.as_display_object().is_some() {
+            VariableValue::MovieClip {
+                id,
+                class: "MovieClip".to_string(),
+            }
+        } else if object.as_executable().is_some() {
+            VariableValue::Object {
+                id,
+                class: "Function".to_string(),
+            }
+        } else {
+            VariableValue::Object {
+                id,
+                class: "Object".to_string(),
+            }
+        }
+    }
+}
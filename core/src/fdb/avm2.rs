@@ -0,0 +1,61 @@
+//! Finds the AVM2 source files of a movie for a debugger session.
+//!
+//! The AVM2 code itself is debugged through a
+//! [`Recorder`](crate::avm2::debugger::recorder::Recorder), as the session
+//! receives messages on the same thread as the movie runs on.
+
+use swf::avm2::read::Reader;
+use swf::avm2::types::Op;
+
+/// Lists the source files named by `debugfile` instructions in an ABC file,
+/// in the order they first appear.
+pub fn debug_files(abc: &[u8]) -> Vec<String> {
+    let Ok(abc) = Reader::new(abc).read() else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<String> = Vec::new();
+    for body in &abc.method_bodies {
+        let mut reader = Reader::new(&body.code);
+        while let Ok(op) = reader.read_op() {
+            let Op::DebugFile { file_name } = op else {
+                continue;
+            };
+            let Some(name) = (file_name.0 as usize)
+                .checked_sub(1)
+                .and_then(|index| abc.constant_pool.strings.get(index))
+            else {
+                continue;
+            };
+            let name = String::from_utf8_lossy(name);
+            if !files.iter().any(|file| *file == name) {
+                files.push(name.into_owned());
+            }
+        }
+    }
+    files
+}
+
+/// Turns the name of a source file as given by `debugfile`, which separates
+/// the source directory and package with `;`, into a path.
+pub fn source_path(file: &str) -> String {
+    file.split(';')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_paths() {
+        assert_eq!(
+            source_path("C:\\src;com\\example;Main.as"),
+            "C:\\src/com\\example/Main.as"
+        );
+        assert_eq!(source_path("/src;;Main.as"), "/src/Main.as");
+        assert_eq!(source_path("Main.as"), "Main.as");
+    }
+}
@@ -0,0 +1,307 @@
+//! Encoding of the messages exchanged with the debugger.
+//!
+//! Every message is a little-endian `u32` payload length, followed by a
+//! little-endian `u32` message type and the payload itself. Strings in the
+//! payload are UTF-8 and null-terminated. The message types are named after
+//! the direction they travel in, as seen from the debugger: `IN_` messages are
+//! sent by the player, `OUT_` messages by the debugger.
+
+// Messages sent by the player.
+pub const IN_TRACE: u32 = 5;
+pub const IN_SCRIPT: u32 = 14;
+pub const IN_ASK_BREAKPOINTS: u32 = 15;
+pub const IN_SET_BREAKPOINT: u32 = 19;
+pub const IN_NUM_SCRIPT: u32 = 20;
+pub const IN_REMOVE_BREAKPOINT: u32 = 22;
+pub const IN_VERSION: u32 = 26;
+pub const IN_BREAK_AT_EXT: u32 = 27;
+pub const IN_SQUELCH: u32 = 29;
+pub const IN_GET_VARIABLE: u32 = 30;
+pub const IN_FRAME: u32 = 31;
+pub const IN_GET_SWF: u32 = 34;
+pub const IN_GET_SWD: u32 = 35;
+pub const IN_ERROR_EXCEPTION: u32 = 36;
+pub const IN_BREAK_REASON: u32 = 40;
+pub const IN_SWF_INFO: u32 = 42;
+
+// Messages sent by the debugger.
+pub const OUT_CONTINUE: u32 = 15;
+pub const OUT_SET_BREAKPOINTS: u32 = 17;
+pub const OUT_REMOVE_BREAKPOINTS: u32 = 18;
+pub const OUT_REMOVE_ALL_BREAKPOINTS: u32 = 19;
+pub const OUT_STEP_OVER: u32 = 20;
+pub const OUT_STEP_INTO: u32 = 21;
+pub const OUT_STEP_OUT: u32 = 22;
+pub const OUT_SET_SQUELCH: u32 = 24;
+pub const OUT_GET_FIELD: u32 = 25;
+pub const OUT_GET_FUNCTION_FRAME: u32 = 26;
+pub const OUT_GET_SWF: u32 = 32;
+pub const OUT_GET_SWD: u32 = 33;
+pub const OUT_GET_VERSION: u32 = 34;
+pub const OUT_GET_BREAK_REASON: u32 = 35;
+pub const OUT_SWF_INFO: u32 = 38;
+
+// Reasons given in `IN_BREAK_REASON`.
+pub const REASON_UNKNOWN: u16 = 0;
+pub const REASON_BREAKPOINT: u16 = 1;
+pub const REASON_STOP_REQUEST: u16 = 4;
+pub const REASON_STEP: u16 = 5;
+
+/// The value of a variable, as sent in `IN_FRAME` and `IN_GET_VARIABLE`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VariableValue {
+    /// Numbers are sent as text, so that the debugger doesn't need to know
+    /// how the player formats them.
+    Number(String),
+    Boolean(bool),
+    String(String),
+    /// An object, which can be inspected with `OUT_GET_FIELD` using `id`
+    /// unless it is 0.
+    Object {
+        id: u32,
+        class: String,
+    },
+    MovieClip {
+        id: u32,
+        class: String,
+    },
+    Null,
+    Undefined,
+}
+
+impl VariableValue {
+    fn type_code(&self) -> u16 {
+        match self {
+            VariableValue::Number(_) => 0,
+            VariableValue::Boolean(_) => 1,
+            VariableValue::String(_) => 2,
+            VariableValue::Object { .. } => 3,
+            VariableValue::MovieClip { .. } => 4,
+            VariableValue::Null => 5,
+            VariableValue::Undefined => 6,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variable {
+    pub name: String,
+    pub value: VariableValue,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub kind: u32,
+    pub payload: Vec<u8>,
+}
+
+impl Message {
+    pub fn new(kind: u32) -> Self {
+        Self {
+            kind,
+            payload: Vec::new(),
+        }
+    }
+
+    pub fn with_u8(mut self, value: u8) -> Self {
+        self.payload.push(value);
+        self
+    }
+
+    pub fn with_u16(mut self, value: u16) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn with_u32(mut self, value: u32) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn with_str(mut self, value: &str) -> Self {
+        // Strings can't contain the terminator.
+        self.payload
+            .extend(value.bytes().take_while(|&byte| byte != 0));
+        self.payload.push(0);
+        self
+    }
+
+    pub fn with_bytes(mut self, value: &[u8]) -> Self {
+        self.payload.extend_from_slice(value);
+        self
+    }
+
+    pub fn with_value(self, value: &VariableValue) -> Self {
+        let message = self.with_u16(value.type_code());
+        match value {
+            VariableValue::Number(value) | VariableValue::String(value) => message.with_str(value),
+            VariableValue::Boolean(value) => message.with_u8(*value as u8),
+            VariableValue::Object { id, class } | VariableValue::MovieClip { id, class } => {
+                message.with_u32(*id).with_str(class)
+            }
+            VariableValue::Null | VariableValue::Undefined => message,
+        }
+    }
+
+    /// Appends the number of `variables`, followed by each of them.
+    pub fn with_variables(self, variables: &[Variable]) -> Self {
+        variables.iter().fold(
+            self.with_u32(variables.len() as u32),
+            |message, variable| {
+                message
+                    .with_str(&variable.name)
+                    // Flags, which are not used yet.
+                    .with_u32(0)
+                    .with_value(&variable.value)
+            },
+        )
+    }
+
+    /// Encodes the message for sending.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.payload.len() + 8);
+        data.extend_from_slice(&(self.payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(&self.kind.to_le_bytes());
+        data.extend_from_slice(&self.payload);
+        data
+    }
+
+    /// Removes the first complete message from `buffer`, if it has arrived yet.
+    pub fn decode(buffer: &mut Vec<u8>) -> Option<Self> {
+        let length = u32::from_le_bytes(buffer.get(0..4)?.try_into().ok()?) as usize;
+        let kind = u32::from_le_bytes(buffer.get(4..8)?.try_into().ok()?);
+        let payload = buffer.get(8..8 + length)?.to_vec();
+        buffer.drain(..8 + length);
+        Some(Self { kind, payload })
+    }
+
+    pub fn reader(&self) -> MessageReader<'_> {
+        MessageReader {
+            data: &self.payload,
+        }
+    }
+}
+
+/// Reads the payload of a message. Every method returns `None` once the
+/// payload runs out.
+pub struct MessageReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> MessageReader<'a> {
+    fn read_bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.data.len() < length {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        self.read_bytes(1).map(|bytes| bytes[0])
+    }
+
+    pub fn read_u16(&mut self) -> Option<u16> {
+        self.read_bytes(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn read_u32(&mut self) -> Option<u32> {
+        self.read_bytes(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn read_str(&mut self) -> Option<String> {
+        let length = self.data.iter().position(|&byte| byte == 0)?;
+        let bytes = self.read_bytes(length)?;
+        self.read_bytes(1)?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    pub fn read_value(&mut self) -> Option<VariableValue> {
+        Some(match self.read_u16()? {
+            0 => VariableValue::Number(self.read_str()?),
+            1 => VariableValue::Boolean(self.read_u8()? != 0),
+            2 => VariableValue::String(self.read_str()?),
+            3 => VariableValue::Object {
+                id: self.read_u32()?,
+                class: self.read_str()?,
+            },
+            4 => VariableValue::MovieClip {
+                id: self.read_u32()?,
+                class: self.read_str()?,
+            },
+            5 => VariableValue::Null,
+            6 => VariableValue::Undefined,
+            _ => return None,
+        })
+    }
+
+    pub fn read_variables(&mut self) -> Option<Vec<Variable>> {
+        let count = self.read_u32()?;
+        (0..count)
+            .map(|_| {
+                let name = self.read_str()?;
+                let _flags = self.read_u32()?;
+                let value = self.read_value()?;
+                Some(Variable { name, value })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_waits_for_whole_message() {
+        let data = Message::new(IN_TRACE).with_str("Hello").encode();
+        let mut buffer = data[..10].to_vec();
+        assert_eq!(Message::decode(&mut buffer), None);
+
+        buffer.extend_from_slice(&data[10..]);
+        buffer.extend_from_slice(&Message::new(IN_ASK_BREAKPOINTS).encode());
+        let message = Message::decode(&mut buffer).unwrap();
+        assert_eq!(message.kind, IN_TRACE);
+        assert_eq!(message.reader().read_str().as_deref(), Some("Hello"));
+        assert_eq!(
+            Message::decode(&mut buffer),
+            Some(Message::new(IN_ASK_BREAKPOINTS))
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn variables_round_trip() {
+        let variables = vec![
+            Variable {
+                name: "a".to_string(),
+                value: VariableValue::Number("1.5".to_string()),
+            },
+            Variable {
+                name: "this".to_string(),
+                value: VariableValue::MovieClip {
+                    id: 3,
+                    class: "MovieClip".to_string(),
+                },
+            },
+            Variable {
+                name: "b".to_string(),
+                value: VariableValue::Undefined,
+            },
+        ];
+        let message = Message::new(IN_FRAME)
+            .with_u32(0)
+            .with_variables(&variables);
+        let mut reader = message.reader();
+        assert_eq!(reader.read_u32(), Some(0));
+        assert_eq!(reader.read_variables(), Some(variables));
+        assert!(reader.is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+//! Checks the password that an `EnableDebugger` tag protects a movie with.
+//!
+//! The tag stores the password hashed with the MD5 based `crypt` scheme
+//! (`$1$salt$hash`), the same as `openssl passwd -1` produces.
+
+use md5::{Digest, Md5};
+
+const MAGIC: &str = "$1$";
+
+const ITOA64: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Whether `password` is the one hashed into `hash`.
+pub fn password_matches(hash: &str, password: &str) -> bool {
+    let Some(salt) = hash
+        .strip_prefix(MAGIC)
+        .and_then(|rest| rest.split_once('$'))
+        .map(|(salt, _)| salt)
+    else {
+        return false;
+    };
+    md5_crypt(password.as_bytes(), salt.as_bytes()) == hash
+}
+
+fn md5_crypt(password: &[u8], salt: &[u8]) -> String {
+    let salt = &salt[..salt.len().min(8)];
+
+    let alternate = Md5::new()
+        .chain_update(password)
+        .chain_update(salt)
+        .chain_update(password)
+        .finalize();
+
+    let mut hasher = Md5::new()
+        .chain_update(password)
+        .chain_update(MAGIC)
+        .chain_update(salt);
+    for chunk in password.chunks(16) {
+        hasher.update(&alternate[..chunk.len()]);
+    }
+    let mut length = password.len();
+    while length > 0 {
+        if length & 1 != 0 {
+            hasher.update([0]);
+        } else {
+            hasher.update(&password[..1]);
+        }
+        length >>= 1;
+    }
+    let mut result = hasher.finalize();
+
+    // Deliberately slow, to make guessing expensive.
+    for round in 0..1000 {
+        let mut hasher = Md5::new();
+        if round & 1 != 0 {
+            hasher.update(password);
+        } else {
+            hasher.update(result);
+        }
+        if round % 3 != 0 {
+            hasher.update(salt);
+        }
+        if round % 7 != 0 {
+            hasher.update(password);
+        }
+        if round & 1 != 0 {
+            hasher.update(result);
+        } else {
+            hasher.update(password);
+        }
+        result = hasher.finalize();
+    }
+
+    let mut output = String::from(MAGIC);
+    output.push_str(&String::from_utf8_lossy(salt));
+    output.push('$');
+    let mut push = |value: u32, characters: usize| {
+        let mut value = value;
+        for _ in 0..characters {
+            output.push(ITOA64[(value & 0x3f) as usize] as char);
+            value >>= 6;
+        }
+    };
+    for (a, b, c) in [(0, 6, 12), (1, 7, 13), (2, 8, 14), (3, 9, 15), (4, 10, 5)] {
+        push(
+            u32::from(result[a]) << 16 | u32::from(result[b]) << 8 | u32::from(result[c]),
+            4,
+        );
+    }
+    push(u32::from(result[11]), 2);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_openssl() {
+        // From `openssl passwd -1 -salt abcd secret`.
+        let hash = "$1$abcd$Oy8OD9LGKv7H9yIMreLNV1";
+        assert!(password_matches(hash, "secret"));
+        assert!(!password_matches(hash, "Secret"));
+        assert!(!password_matches(hash, ""));
+    }
+
+    #[test]
+    fn long_passwords_and_salts() {
+        // From `openssl passwd -1 -salt 0123456789 "a rather long password, really"`,
+        // which only uses the first 8 characters of the salt.
+        assert!(password_matches(
+            "$1$01234567$3wVLpa6fiCPwYIa5Bi7tH.",
+            "a rather long password, really"
+        ));
+    }
+
+    #[test]
+    fn other_schemes_never_match() {
+        assert!(!password_matches("secret", "secret"));
+        assert!(!password_matches("$5$abcd$", "secret"));
+    }
+}
@@ -0,0 +1,237 @@
+//! Parsing of SWD files, which hold the debugging information for the AVM1
+//! code of a movie.
+//!
+//! A SWD starts with `FWD` and a version byte, followed by a list of records
+//! that each start with a little-endian `u32` tag. The scripts of the movie
+//! are numbered by the SWD, and every line of a script that produced code is
+//! mapped to the offset of its first action, counted from the start of the
+//! uncompressed SWF file.
+
+const TAG_SCRIPT: u32 = 0;
+const TAG_OFFSET: u32 = 1;
+const TAG_BREAKPOINT: u32 = 2;
+const TAG_DEBUG_ID: u32 = 3;
+const TAG_REGISTERS: u32 = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwdScript {
+    pub id: u32,
+    pub name: String,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwdOffset {
+    pub script: u32,
+    pub line: u32,
+    pub offset: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Swd {
+    /// The raw file, so that it can be handed to the debugger.
+    pub data: Vec<u8>,
+
+    pub debug_id: Option<[u8; 16]>,
+
+    pub scripts: Vec<SwdScript>,
+
+    /// The start of every line, sorted by offset.
+    pub offsets: Vec<SwdOffset>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.data.len() < length {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        self.read_bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        self.read_bytes(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_str(&mut self) -> Option<String> {
+        let length = self.data.iter().position(|&byte| byte == 0)?;
+        let bytes = self.read_bytes(length)?;
+        self.read_bytes(1)?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn read_script(&mut self) -> Option<SwdScript> {
+        let id = self.read_u32()?;
+        let _bitmap = self.read_u32()?;
+        Some(SwdScript {
+            id,
+            name: self.read_str()?,
+            text: self.read_str()?,
+        })
+    }
+
+    fn read_offset(&mut self) -> Option<SwdOffset> {
+        Some(SwdOffset {
+            script: self.read_u32()?,
+            line: self.read_u32()?,
+            offset: self.read_u32()?,
+        })
+    }
+}
+
+impl Swd {
+    /// Parses a SWD file, returning `None` if it isn't one.
+    ///
+    /// Records following one that can't be read are ignored, as their
+    /// length isn't known.
+    pub fn parse(data: Vec<u8>) -> Option<Self> {
+        if data.get(..3)? != b"FWD" {
+            return None;
+        }
+
+        let mut swd = Swd::default();
+        let mut reader = Reader {
+            data: data.get(4..)?,
+        };
+        while let Some(tag) = reader.read_u32() {
+            match tag {
+                TAG_SCRIPT => match reader.read_script() {
+                    Some(script) => swd.scripts.push(script),
+                    None => break,
+                },
+                TAG_OFFSET => match reader.read_offset() {
+                    Some(offset) => swd.offsets.push(offset),
+                    None => break,
+                },
+                TAG_BREAKPOINT => {
+                    // Breakpoints saved by the authoring tool; the debugger
+                    // sets its own.
+                    if reader.read_u32().is_none() {
+                        break;
+                    }
+                }
+                TAG_DEBUG_ID => match reader.read_bytes(16) {
+                    Some(id) => swd.debug_id = id.try_into().ok(),
+                    None => break,
+                },
+                TAG_REGISTERS => {
+                    // The names of the registers that hold local variables.
+                    let Some(count) = reader.read_u32().and_then(|_offset| reader.read_u8()) else {
+                        break;
+                    };
+                    if (0..count)
+                        .any(|_| reader.read_u8().and_then(|_| reader.read_str()).is_none())
+                    {
+                        break;
+                    }
+                }
+                _ => {
+                    tracing::warn!("Unknown SWD tag {tag}");
+                    break;
+                }
+            }
+        }
+
+        swd.offsets.sort_by_key(|offset| offset.offset);
+        swd.data = data;
+        Some(swd)
+    }
+
+    /// Finds the line containing the action at `offset` in the SWF file.
+    pub fn line_at(&self, offset: u32) -> Option<&SwdOffset> {
+        let index = self
+            .offsets
+            .partition_point(|entry| entry.offset <= offset)
+            .checked_sub(1)?;
+        self.offsets.get(index)
+    }
+
+    /// Whether the action at `offset` in the SWF file starts a line.
+    pub fn is_line_start(&self, offset: u32) -> bool {
+        self.offsets
+            .binary_search_by_key(&offset, |entry| entry.offset)
+            .is_ok()
+    }
+
+    /// The offsets of every action starting `line` in `script`.
+    pub fn line_offsets(&self, script: u32, line: u32) -> impl Iterator<Item = u32> + '_ {
+        self.offsets
+            .iter()
+            .filter(move |entry| entry.script == script && entry.line == line)
+            .map(|entry| entry.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(tag: u32, fields: &[&[u8]]) -> Vec<u8> {
+        let mut data = tag.to_le_bytes().to_vec();
+        for field in fields {
+            data.extend_from_slice(field);
+        }
+        data
+    }
+
+    #[test]
+    fn parse_swd() {
+        let mut data = b"FWD\x07".to_vec();
+        data.extend(record(
+            TAG_SCRIPT,
+            &[
+                &1u32.to_le_bytes(),
+                &0u32.to_le_bytes(),
+                b"Actions for Scene 1: Frame 1 of Layer Name Layer 1\0",
+                b"trace(1);\ntrace(2);\0",
+            ],
+        ));
+        data.extend(record(
+            TAG_OFFSET,
+            &[
+                &1u32.to_le_bytes(),
+                &2u32.to_le_bytes(),
+                &40u32.to_le_bytes(),
+            ],
+        ));
+        data.extend(record(
+            TAG_OFFSET,
+            &[
+                &1u32.to_le_bytes(),
+                &1u32.to_le_bytes(),
+                &30u32.to_le_bytes(),
+            ],
+        ));
+        data.extend(record(TAG_DEBUG_ID, &[&[7; 16]]));
+        data.extend(record(
+            TAG_REGISTERS,
+            &[&30u32.to_le_bytes(), &[1, 1], b"i\0"],
+        ));
+
+        let swd = Swd::parse(data).unwrap();
+        assert_eq!(swd.scripts.len(), 1);
+        assert_eq!(swd.scripts[0].text, "trace(1);\ntrace(2);");
+        assert_eq!(swd.debug_id, Some([7; 16]));
+        assert_eq!(swd.line_at(29), None);
+        assert_eq!(swd.line_at(35).map(|entry| entry.line), Some(1));
+        assert_eq!(swd.line_at(40).map(|entry| entry.line), Some(2));
+        assert!(swd.is_line_start(40));
+        assert!(!swd.is_line_start(41));
+        assert_eq!(swd.line_offsets(1, 2).collect::<Vec<_>>(), vec![40]);
+    }
+
+    #[test]
+    fn reject_other_files() {
+        assert!(Swd::parse(b"FWS\x08".to_vec()).is_none());
+    }
+}
@@ -32,6 +32,10 @@ pub enum InputEvent {
         x: f64,
         y: f64,
     },
+    MouseMoveRelative {
+        dx: f64,
+        dy: f64,
+    },
     MouseUp {
         x: f64,
         y: f64,
@@ -79,6 +83,7 @@ pub struct InputManager {
     last_key: KeyCode,
     last_char: Option<char>,
     last_click: Option<ClickEventData>,
+    mouse_movement: (f64, f64),
 
     /// A map from gamepad buttons to key codes.
     gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
@@ -95,6 +100,7 @@ impl InputManager {
             last_key: KeyCode::UNKNOWN,
             last_char: None,
             last_click: None,
+            mouse_movement: (0.0, 0.0),
             gamepad_button_mapping,
             key_code_mapping_type: KeyCodeMappingType::Logical,
         }
@@ -191,6 +197,7 @@ impl InputManager {
             }
 
             PlayerEvent::MouseMove { x, y } => InputEvent::MouseMove { x, y },
+            PlayerEvent::MouseMoveRelative { dx, dy } => InputEvent::MouseMoveRelative { dx, dy },
             PlayerEvent::MouseUp { x, y, button } => InputEvent::MouseUp { x, y, button },
             PlayerEvent::MouseDown {
                 x,
@@ -241,6 +248,11 @@ impl InputManager {
     }
 
     fn handle_event(&mut self, event: &InputEvent) {
+        self.mouse_movement = match *event {
+            InputEvent::MouseMoveRelative { dx, dy } => (dx, dy),
+            _ => (0.0, 0.0),
+        };
+
         match *event {
             InputEvent::KeyDown {
                 key_code, key_char, ..
@@ -300,6 +312,12 @@ impl InputManager {
         self.last_char
     }
 
+    /// The relative movement of the locked mouse reported by the last event,
+    /// used for `MouseEvent.movementX` and `MouseEvent.movementY`.
+    pub fn mouse_movement(&self) -> (f64, f64) {
+        self.mouse_movement
+    }
+
     pub fn last_click_index(&self) -> usize {
         self.last_click
             .as_ref()
@@ -24,6 +24,7 @@ pub mod context_menu;
 mod drawing;
 mod ecma_conversions;
 pub mod events;
+pub mod fdb;
 pub mod focus_tracker;
 mod font;
 mod frame_lifecycle;
@@ -34,6 +35,7 @@ pub mod limits;
 pub mod loader;
 mod local_connection;
 mod locale;
+mod memory;
 mod net_connection;
 mod orphan_manager;
 pub mod pixel_bender;
@@ -60,6 +62,8 @@ pub mod external;
 pub mod i18n;
 pub mod stub;
 
+pub use avm1::trace_recorder as avm1_trace_recorder;
+pub use avm2::debugger as avm2_debugger;
 pub use context_menu::ContextMenuItem;
 pub use events::PlayerEvent;
 pub use font::{DefaultFont, FontFileData, FontQuery, FontType};
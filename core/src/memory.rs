@@ -0,0 +1,93 @@
+//! Accounting of the memory used by the movie, as reported by
+//! `System.totalMemory` and similar APIs.
+
+use crate::backend::audio::AudioBackend;
+use gc_arena::metrics::Metrics;
+use gc_arena::Mutation;
+use ruffle_render::bitmap::BitmapInfo;
+use std::fmt;
+
+/// How much memory use may grow after a collection before the arena starts
+/// the next one, as a fraction of the memory in use after the collection.
+///
+/// This matches the default pacing of `gc-arena`, and is what
+/// `System.pauseForGCIfCollectionImminent` measures how close the next
+/// collection is against.
+const COLLECTION_GROWTH: f64 = 0.5;
+
+/// The memory used by the movie, in bytes.
+///
+/// This is everything allocated in the GC arena, which includes the memory
+/// held outside of it by bitmaps and video streams (see `ExternalMemory`),
+/// along with the data of every sound in the audio backend.
+pub fn total_memory(metrics: &Metrics, audio: &dyn AudioBackend) -> usize {
+    metrics.total_allocation() + audio.sound_memory()
+}
+
+/// Whether the next collection is at least `imminence` of the way there,
+/// given the memory that was in use after the last one.
+pub fn is_collection_imminent(
+    memory: usize,
+    memory_after_collection: usize,
+    imminence: f64,
+) -> bool {
+    let growth = memory.saturating_sub(memory_after_collection) as f64;
+    let budget = memory_after_collection.max(1) as f64 * COLLECTION_GROWTH;
+    growth / budget >= imminence
+}
+
+/// Memory owned outside of the GC arena, such as the pixels of a bitmap,
+/// which is counted in the metrics of the arena for as long as this is alive.
+///
+/// This lets collections be paced according to all of the memory that they
+/// can free, and not just the memory of the arena itself.
+#[derive(Default)]
+pub struct ExternalMemory {
+    metrics: Option<Metrics>,
+    bytes: usize,
+}
+
+impl ExternalMemory {
+    pub fn new(mc: &Mutation<'_>, bytes: usize) -> Self {
+        let metrics = mc.metrics().clone();
+        metrics.mark_external_allocation(bytes);
+        Self {
+            metrics: Some(metrics),
+            bytes,
+        }
+    }
+
+    /// The memory of a bitmap held by the renderer, such as a decoded video
+    /// frame, which isn't otherwise known to the player.
+    pub fn for_bitmap(mc: &Mutation<'_>, bitmap: &BitmapInfo) -> Self {
+        Self::new(mc, bitmap.width as usize * bitmap.height as usize * 4)
+    }
+}
+
+impl Clone for ExternalMemory {
+    fn clone(&self) -> Self {
+        if let Some(metrics) = &self.metrics {
+            metrics.mark_external_allocation(self.bytes);
+        }
+        Self {
+            metrics: self.metrics.clone(),
+            bytes: self.bytes,
+        }
+    }
+}
+
+impl Drop for ExternalMemory {
+    fn drop(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.mark_external_deallocation(self.bytes);
+        }
+    }
+}
+
+impl fmt::Debug for ExternalMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExternalMemory")
+            .field("bytes", &self.bytes)
+            .finish()
+    }
+}
@@ -4,6 +4,8 @@ use crate::avm1::Object;
 use crate::avm1::Value;
 use crate::avm1::VariableDumper;
 use crate::avm1::{Activation, ActivationIdentifier};
+use crate::avm2::debugger::DebuggerConnection;
+use crate::avm2::globals::flash::display::shader_job;
 use crate::avm2::object::{EventObject as Avm2EventObject, Object as Avm2Object};
 use crate::avm2::{Activation as Avm2Activation, Avm2, CallStack};
 use crate::backend::ui::FontDefinition;
@@ -30,6 +32,7 @@ use crate::events::PlayerNotification;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, MouseButton, PlayerEvent};
 use crate::external::{ExternalInterface, ExternalInterfaceProvider, NullFsCommandProvider};
 use crate::external::{FsCommandProvider, Value as ExternalValue};
+use crate::fdb::Fdb;
 use crate::focus_tracker::NavigationDirection;
 use crate::frame_lifecycle::{run_all_phases_avm2, FramePhase};
 use crate::input::InputEvent;
@@ -39,6 +42,7 @@ use crate::limits::ExecutionLimit;
 use crate::loader::{LoadBehavior, LoadManager};
 use crate::local_connection::LocalConnections;
 use crate::locale::get_current_date_time;
+use crate::memory;
 use crate::net_connection::NetConnections;
 use crate::orphan_manager::OrphanManager;
 use crate::prelude::*;
@@ -189,6 +193,9 @@ struct GcRootData<'gc> {
 
     sockets: Sockets<'gc>,
 
+    /// The session with a debugger, if the movie is being debugged.
+    fdb: Fdb<'gc>,
+
     /// List of active NetConnection objects.
     net_connections: NetConnections<'gc>,
 
@@ -235,6 +242,7 @@ impl<'gc> GcRootData<'gc> {
         &mut AudioManager<'gc>,
         &mut StreamManager<'gc>,
         &mut Sockets<'gc>,
+        &mut Fdb<'gc>,
         &mut NetConnections<'gc>,
         &mut LocalConnections<'gc>,
         &mut OrphanManager<'gc>,
@@ -260,6 +268,7 @@ impl<'gc> GcRootData<'gc> {
             &mut self.audio_manager,
             &mut self.stream_manager,
             &mut self.sockets,
+            &mut self.fdb,
             &mut self.net_connections,
             &mut self.local_connections,
             &mut self.orphan_manager,
@@ -305,6 +314,12 @@ pub struct Player {
     run_state: RunState,
     needs_render: bool,
 
+    /// Whether a full garbage collection was requested, such as by `System.gc()`.
+    gc_requested: bool,
+
+    /// The memory used by the movie after the last garbage collection.
+    memory_after_gc: usize,
+
     renderer: Box<dyn RenderBackend>,
     audio: Box<dyn AudioBackend>,
     navigator: Box<dyn NavigatorBackend>,
@@ -511,6 +526,14 @@ impl Player {
     }
 
     pub fn tick(&mut self, dt: f64) {
+        // While a debugger has the movie halted, only keep talking to it.
+        if self.mutate_with_update_context(|context| {
+            context.avm2.debugger_paused() || Fdb::is_halted(context)
+        }) {
+            self.update_sockets();
+            return;
+        }
+
         if !self.is_playing() {
             return;
         }
@@ -543,6 +566,16 @@ impl Player {
                 self.set_run_state(RunState::Suspended);
                 break;
             }
+
+            // A debugger broke during this frame, so give its client a
+            // chance to look around before running any more frames.
+            if self.mutate_with_update_context(|context| {
+                context.avm1.trace_recorder().is_paused()
+                    || context.avm2.debugger_paused()
+                    || Fdb::is_halted(context)
+            }) {
+                break;
+            }
         }
 
         // Now that we're done running code,
@@ -1003,6 +1036,14 @@ impl Player {
     pub fn handle_event(&mut self, event: PlayerEvent) -> bool {
         match event {
             PlayerEvent::FocusGained | PlayerEvent::FocusLost => self.handle_focus_event(event),
+            PlayerEvent::MouseMoveRelative { .. } => {
+                // Relative movement is only reported to content while the mouse is locked.
+                if self.mutate_with_update_context(|context| context.stage.mouse_lock()) {
+                    self.handle_input_event(event)
+                } else {
+                    false
+                }
+            }
             PlayerEvent::KeyDown { .. }
             | PlayerEvent::KeyUp { .. }
             | PlayerEvent::MouseMove { .. }
@@ -1418,6 +1459,14 @@ impl Player {
             }
         }
 
+        if let InputEvent::MouseMoveRelative { .. } = event {
+            // The cursor stays in place while the mouse is locked, but `mouseMove`
+            // is still dispatched, with `movementX` and `movementY` set.
+            if self.update_mouse_state(&changed_mouse_buttons, true, &mut player_event_handled) {
+                self.needs_render = true;
+            }
+        }
+
         if self.should_reset_highlight(event) {
             self.mutate_with_update_context(|context| {
                 context.focus_tracker.reset_highlight();
@@ -1987,6 +2036,10 @@ impl Player {
         }
 
         self.update(|context| {
+            // Advance any `ShaderJob`s started since the last frame before running
+            // scripts, so that the render backend has had a full frame to run them.
+            shader_job::run_pending_jobs(context);
+
             // TODO: Is this order correct?
             run_all_phases_avm2(context);
             Avm1::run_frame(context);
@@ -2107,6 +2160,14 @@ impl Player {
         std::mem::swap(&mut self.storage, storage);
     }
 
+    /// Attach a debugger for AVM2 code.
+    ///
+    /// This needs to happen before the movie starts running, as methods that
+    /// already ran can't be debugged.
+    pub fn attach_avm2_debugger(&mut self, connection: Box<dyn DebuggerConnection>) {
+        self.mutate_with_update_context(|context| context.avm2.attach_debugger(connection));
+    }
+
     pub fn ui(&self) -> &dyn UiBackend {
         &*self.ui
     }
@@ -2224,6 +2285,7 @@ impl Player {
                 audio_manager,
                 stream_manager,
                 sockets,
+                fdb,
                 net_connections,
                 local_connections,
                 orphan_manager,
@@ -2264,6 +2326,8 @@ impl Player {
                 timers,
                 current_context_menu,
                 needs_render: &mut this.needs_render,
+                gc_requested: &mut this.gc_requested,
+                memory_after_gc: this.memory_after_gc,
                 avm1,
                 avm2,
                 external_interface,
@@ -2281,6 +2345,7 @@ impl Player {
                 stub_tracker: &mut this.stub_tracker,
                 stream_manager,
                 sockets,
+                fdb,
                 net_connections,
                 local_connections,
                 orphan_manager,
@@ -2354,12 +2419,47 @@ impl Player {
         });
         self.update_mouse_state(&HashSet::new(), false, &mut false);
 
-        // GC
-        self.gc_arena.borrow_mut().collect_debt();
+        self.collect_garbage();
 
         rval
     }
 
+    /// Runs the garbage collector, either for the debt accumulated by
+    /// allocations or to completion if a full collection was requested.
+    fn collect_garbage(&mut self) {
+        let full = std::mem::take(&mut self.gc_requested);
+        let mut arena = self.gc_arena.borrow_mut();
+
+        // Once marking has finished, the objects about to be collected are
+        // known, so weak `Dictionary` keys can be removed before they are.
+        let marked = if full {
+            arena.mark_all()
+        } else {
+            arena.mark_debt()
+        };
+        let finished_marking = marked.is_some();
+        if let Some(marked) = marked {
+            marked.finalize(|fc, root| {
+                root.data
+                    .borrow_mut(fc)
+                    .avm2
+                    .remove_dead_dictionary_keys(fc);
+            });
+        }
+
+        if full {
+            arena.collect_all();
+        } else {
+            arena.collect_debt();
+        }
+
+        // An incremental collection may not have finished sweeping yet, in
+        // which case this still includes some of the memory it's going to free.
+        if finished_marking {
+            self.memory_after_gc = memory::total_memory(arena.metrics(), &*self.audio);
+        }
+    }
+
     pub fn flush_shared_objects(&mut self) {
         self.update(|context| {
             if let Some(mut avm1_activation) =
@@ -2398,6 +2498,7 @@ impl Player {
     pub fn update_sockets(&mut self) {
         self.mutate_with_update_context(|context| {
             Sockets::update_sockets(context);
+            Fdb::update(context);
         })
     }
 
@@ -2531,6 +2632,8 @@ pub struct PlayerBuilder {
     #[cfg(feature = "known_stubs")]
     stub_report_output: Option<std::path::PathBuf>,
     avm2_optimizer_enabled: bool,
+    debugger_address: Option<(String, u16)>,
+    debugger_password: Option<String>,
     #[cfg(feature = "default_font")]
     default_font: bool,
 }
@@ -2587,6 +2690,8 @@ impl PlayerBuilder {
             #[cfg(feature = "known_stubs")]
             stub_report_output: None,
             avm2_optimizer_enabled: true,
+            debugger_address: None,
+            debugger_password: None,
             #[cfg(feature = "default_font")]
             default_font: true,
         }
@@ -2810,6 +2915,19 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets the host and port of a debugger to connect to when a movie that
+    /// allows debugging is loaded, such as `fdb` listening on port 7935.
+    pub fn with_debugger_address(mut self, address: Option<(String, u16)>) -> Self {
+        self.debugger_address = address;
+        self
+    }
+
+    /// Sets the password to debug movies that protect debugging with one.
+    pub fn with_debugger_password(mut self, password: Option<String>) -> Self {
+        self.debugger_password = password;
+        self
+    }
+
     #[cfg(feature = "default_font")]
     pub fn with_default_font(mut self, value: bool) -> Self {
         self.default_font = value;
@@ -2864,6 +2982,7 @@ impl PlayerBuilder {
             unbound_text_fields: Vec::new(),
             stream_manager: StreamManager::new(),
             sockets: Sockets::empty(),
+            fdb: Fdb::default(),
             net_connections: NetConnections::default(),
             local_connections: LocalConnections::empty(),
             orphan_manager: OrphanManager::default(),
@@ -2963,6 +3082,8 @@ impl PlayerBuilder {
                     RunState::Suspended
                 },
                 needs_render: true,
+                gc_requested: false,
+                memory_after_gc: 0,
                 self_reference: self_ref.clone(),
                 load_behavior: self.load_behavior,
                 spoofed_url: self.spoofed_url.clone(),
@@ -3043,6 +3164,7 @@ impl PlayerBuilder {
             stage.set_allow_fullscreen(self.allow_fullscreen);
             stage.post_instantiation(context, None, Instantiator::Movie, false);
             stage.build_matrices(context);
+            *context.fdb = Fdb::new(self.debugger_address, self.debugger_password);
             #[cfg(feature = "known_stubs")]
             if let Some(stub_path) = self.stub_report_output {
                 crate::avm2::specification::capture_specification(context, &stub_path);
@@ -3064,6 +3186,12 @@ impl PlayerBuilder {
                 context.set_root_movie(movie);
             });
         }
+
+        // Until the first collection finishes, measure how close the next one is
+        // against the memory of the freshly created player, rather than nothing.
+        let memory =
+            memory::total_memory(player_lock.gc_arena.borrow().metrics(), &*player_lock.audio);
+        player_lock.memory_after_gc = memory;
         drop(player_lock);
         player
     }
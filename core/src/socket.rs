@@ -6,6 +6,7 @@ use crate::avm2::object::{EventObject, SocketObject};
 use crate::avm2::{Activation as Avm2Activation, Avm2};
 use crate::backend::navigator::NavigatorBackend;
 use crate::context::UpdateContext;
+use crate::fdb::Fdb;
 use crate::string::AvmString;
 
 use async_channel::{unbounded, Receiver, Sender as AsyncSender, Sender};
@@ -27,6 +28,8 @@ new_key_type! {
 enum SocketKind<'gc> {
     Avm2(SocketObject<'gc>),
     Avm1(Avm1Object<'gc>),
+    /// The connection to a debugger, see [`Fdb`].
+    Debugger,
 }
 
 #[derive(Collect)]
@@ -151,6 +154,30 @@ impl<'gc> Sockets<'gc> {
         }
     }
 
+    pub fn connect_debugger(
+        &mut self,
+        backend: &mut dyn NavigatorBackend,
+        host: String,
+        port: u16,
+    ) -> SocketHandle {
+        let (sender, receiver) = unbounded();
+
+        let socket = Socket::new(SocketKind::Debugger, sender);
+        let handle = self.sockets.insert(socket);
+
+        // NOTE: This call will send SocketAction::Connect to sender with connection status.
+        backend.connect_socket(
+            host,
+            port,
+            Duration::from_secs(10),
+            handle,
+            receiver,
+            self.sender.clone(),
+        );
+
+        handle
+    }
+
     pub fn is_connected(&self, handle: SocketHandle) -> bool {
         if let Some(socket) = self.sockets.get(handle) {
             socket.connected.get()
@@ -159,8 +186,8 @@ impl<'gc> Sockets<'gc> {
         }
     }
 
-    pub fn send(&mut self, handle: SocketHandle, data: Vec<u8>) {
-        if let Some(Socket { sender, .. }) = self.sockets.get_mut(handle) {
+    pub fn send(&self, handle: SocketHandle, data: Vec<u8>) {
+        if let Some(Socket { sender, .. }) = self.sockets.get(handle) {
             // We use an unbounded socket, so this should only ever error if the channel is closed
             // (the receiver was dropped)
             if let Err(e) = sender.borrow().try_send(data) {
@@ -201,6 +228,7 @@ impl<'gc> Sockets<'gc> {
                 target.read_buffer().clear();
                 target.write_buffer().clear();
             }
+            SocketKind::Debugger => {}
         }
     }
 
@@ -244,6 +272,7 @@ impl<'gc> Sockets<'gc> {
                                 ExecutionReason::Special,
                             );
                         }
+                        SocketKind::Debugger => Fdb::connected(context, true),
                     }
                 }
                 SocketAction::Connect(
@@ -282,6 +311,7 @@ impl<'gc> Sockets<'gc> {
                                 ExecutionReason::Special,
                             );
                         }
+                        SocketKind::Debugger => Fdb::connected(context, false),
                     }
                 }
                 SocketAction::Data(handle, mut data) => {
@@ -365,6 +395,7 @@ impl<'gc> Sockets<'gc> {
                                 }
                             }
                         }
+                        SocketKind::Debugger => Fdb::receive(context, data),
                     }
                 }
                 SocketAction::Close(handle) => {
@@ -408,6 +439,7 @@ impl<'gc> Sockets<'gc> {
                                 ExecutionReason::Special,
                             );
                         }
+                        SocketKind::Debugger => Fdb::disconnected(context),
                     }
                 }
             }
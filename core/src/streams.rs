@@ -18,6 +18,7 @@ use crate::buffer::{Buffer, Slice, Substream, SubstreamError};
 use crate::context::UpdateContext;
 use crate::display_object::{MovieClip, TDisplayObject};
 use crate::loader::Error;
+use crate::memory::ExternalMemory;
 use crate::string::AvmString;
 use flv_rs::{
     AudioData as FlvAudioData, AudioDataType as FlvAudioDataType, Error as FlvError, FlvReader,
@@ -194,6 +195,10 @@ pub struct NetStreamSource {
     /// All data currently loaded in the stream.
     buffer: RefCell<Buffer>,
 
+    /// The memory used by `buffer`, counted in the metrics of the GC arena.
+    #[collect(require_static)]
+    buffer_memory: RefCell<ExternalMemory>,
+
     /// The buffer position that we are currently seeking to.
     offset: Cell<usize>,
 
@@ -250,6 +255,11 @@ pub struct NetStreamData<'gc> {
     /// this `NetStream`.
     last_decoded_bitmap: RefCell<Option<BitmapInfo>>,
 
+    /// The memory used by `last_decoded_bitmap`, counted in the metrics of the
+    /// GC arena.
+    #[collect(require_static)]
+    last_decoded_bitmap_memory: RefCell<ExternalMemory>,
+
     /// The AVM side of this stream.
     avm_object: Lock<Option<NetStreamKind<'gc>>>,
 
@@ -270,6 +280,7 @@ impl Default for NetStreamSource {
     fn default() -> Self {
         Self {
             buffer: RefCell::new(Buffer::new()),
+            buffer_memory: RefCell::new(ExternalMemory::default()),
             offset: Cell::new(0),
             expected_length: Cell::new(Some(0)),
             preload_offset: Cell::new(0),
@@ -303,6 +314,7 @@ impl<'gc> NetStream<'gc> {
                 source: Lock::new(Gc::new(gc_context, Default::default())),
                 buffer_time: Cell::new(0.1),
                 last_decoded_bitmap: RefCell::new(None),
+                last_decoded_bitmap_memory: RefCell::new(ExternalMemory::default()),
                 avm_object: Lock::new(avm_object),
                 avm2_client: Lock::new(None),
                 url: RefCell::new(None),
@@ -379,7 +391,10 @@ impl<'gc> NetStream<'gc> {
     /// that all data is appended in the correct order and that data from
     /// separate streams is not mixed together.
     pub fn load_buffer(self, context: &mut UpdateContext<'gc>, data: &mut Vec<u8>) {
-        self.source().buffer.borrow_mut().append(data);
+        let source = self.source();
+        source.buffer.borrow_mut().append(data);
+        *source.buffer_memory.borrow_mut() =
+            ExternalMemory::new(context.gc(), source.buffer.borrow().len());
 
         StreamManager::activate(context, self);
 
@@ -991,6 +1006,8 @@ impl<'gc> NetStream<'gc> {
                     context.renderer,
                 ) {
                     Ok(bitmap_info) => {
+                        *self.0.last_decoded_bitmap_memory.borrow_mut() =
+                            ExternalMemory::for_bitmap(context.gc(), &bitmap_info);
                         self.0.last_decoded_bitmap.replace(Some(bitmap_info));
                         if let Some(mc) = self.0.attached_to.get() {
                             mc.invalidate_cached_bitmap();
@@ -1036,6 +1053,8 @@ impl<'gc> NetStream<'gc> {
                     context.renderer,
                 ) {
                     Ok(bitmap_info) => {
+                        *self.0.last_decoded_bitmap_memory.borrow_mut() =
+                            ExternalMemory::for_bitmap(context.gc(), &bitmap_info);
                         self.0.last_decoded_bitmap.replace(Some(bitmap_info));
                         if let Some(mc) = self.0.attached_to.get() {
                             mc.invalidate_cached_bitmap();
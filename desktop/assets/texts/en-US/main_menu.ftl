@@ -41,6 +41,7 @@ debug-menu-open-movie = View Movie
 debug-menu-open-movie-list = Show Known Movies
 debug-menu-open-domain-list = Show Domains
 debug-menu-search-display-objects = Search Display Objects...
+debug-menu-open-avm1-trace-recorder = Show AVM1 Trace Recorder
 
 view-menu = View
 view-menu-fullscreen = Full Screen
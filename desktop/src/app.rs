@@ -17,7 +17,9 @@ use std::time::Instant;
 use url::Url;
 use winit::application::ApplicationHandler;
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Size};
-use winit::event::{ElementState, Ime, KeyEvent, Modifiers, StartCause, WindowEvent};
+use winit::event::{
+    DeviceEvent, DeviceId, ElementState, Ime, KeyEvent, Modifiers, StartCause, WindowEvent,
+};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy};
 use winit::keyboard::{Key, NamedKey};
 use winit::window::{Fullscreen, Icon, WindowAttributes, WindowId};
@@ -244,6 +246,19 @@ impl MainWindow {
         }
     }
 
+    pub fn device_event(&mut self, event: DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta: (dx, dy) } = event {
+            if self.gui.is_context_menu_visible() {
+                return;
+            }
+
+            // The player ignores this unless the movie has locked the mouse.
+            self.player
+                .handle_event(PlayerEvent::MouseMoveRelative { dx, dy });
+            self.check_redraw();
+        }
+    }
+
     fn on_metadata(&mut self, swf_header: HeaderExt) {
         let height_offset = if self.gui.window().fullscreen().is_some() || self.no_gui {
             0.0
@@ -639,6 +654,17 @@ impl ApplicationHandler<RuffleEvent> for App {
         }
     }
 
+    fn device_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        if let Some(main_window) = &mut self.main_window {
+            main_window.device_event(event);
+        }
+    }
+
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
         if let Some(main_window) = &mut self.main_window {
             main_window.about_to_wait(self.gilrs.as_mut());
@@ -23,7 +23,7 @@ use tokio::io::AsyncReadExt;
 use url::Url;
 use winit::event_loop::EventLoopProxy;
 use winit::raw_window_handle::HasDisplayHandle;
-use winit::window::{Fullscreen, Window};
+use winit::window::{CursorGrabMode, Fullscreen, Window};
 
 pub struct DesktopFileDialogResult {
     handle: Option<FileHandle>,
@@ -148,6 +148,7 @@ pub struct DesktopUiBackend {
     window: Arc<Window>,
     event_loop: EventLoopProxy<RuffleEvent>,
     cursor_visible: bool,
+    mouse_locked: bool,
     clipboard: Clipboard,
     preferences: GlobalPreferences,
     preferred_cursor: MouseCursor,
@@ -176,6 +177,7 @@ impl DesktopUiBackend {
             window,
             event_loop,
             cursor_visible: true,
+            mouse_locked: false,
             clipboard,
             preferences,
             preferred_cursor: MouseCursor::Arrow,
@@ -185,7 +187,7 @@ impl DesktopUiBackend {
     }
 
     pub fn cursor(&self) -> egui::CursorIcon {
-        if self.cursor_visible {
+        if self.cursor_visible && !self.mouse_locked {
             match self.preferred_cursor {
                 MouseCursor::Arrow => egui::CursorIcon::Default,
                 MouseCursor::Hand => egui::CursorIcon::PointingHand,
@@ -228,6 +230,21 @@ impl UiBackend for DesktopUiBackend {
         Ok(())
     }
 
+    fn set_mouse_lock(&mut self, locked: bool) {
+        let result = if locked {
+            // Not every platform can lock the cursor in place, so fall back to confining it.
+            self.window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| self.window.set_cursor_grab(CursorGrabMode::Confined))
+        } else {
+            self.window.set_cursor_grab(CursorGrabMode::None)
+        };
+        if let Err(e) = result {
+            tracing::warn!("Couldn't change the cursor grab mode: {e}");
+        }
+        self.mouse_locked = locked;
+    }
+
     fn display_root_movie_download_failed_message(&self, _invalid_swf: bool, _fetch_error: String) {
         let _ = self
             .event_loop
@@ -243,6 +243,29 @@ pub struct Opt {
     /// (like inlining constant pool entries) can't be disabled.
     #[clap(long)]
     pub no_avm2_optimizer: bool,
+
+    /// Start a Debug Adapter Protocol server on the given port, so that an editor
+    /// can debug the ActionScript 3 code of the movie.
+    /// The movie waits for the editor to attach and set its breakpoints before it
+    /// starts.
+    #[clap(long)]
+    pub dap_port: Option<u16>,
+
+    /// Connect to a Flash debugger, such as fdb, listening on the given port of
+    /// this machine when a movie that allows debugging is loaded.
+    /// The debugger's default port is 7935.
+    #[clap(long)]
+    pub fdb_port: Option<u16>,
+
+    /// The password to debug movies that only allow debugging with a password,
+    /// as set when publishing them.
+    #[clap(long)]
+    pub fdb_password: Option<String>,
+
+    /// Record every call made to the renderer into the given file, which can be rendered
+    /// again with the exporter's `replay` tool, for example to compare graphics backends.
+    #[clap(long)]
+    pub record_render: Option<std::path::PathBuf>,
 }
 
 fn parse_movie_file_or_url(path: &str) -> Result<Url, Error> {
@@ -0,0 +1,658 @@
+//! Serves the AVM2 debugger to editors over the
+//! [Debug Adapter Protocol](https://microsoft.github.io/debug-adapter-protocol/).
+//!
+//! The player never waits for the editor. Each player's debugger talks to a
+//! [`Recorder`], which holds the movie still once it stops, and the server
+//! answers the editor from what was recorded on a thread of its own.
+
+use ruffle_core::avm2_debugger::recorder::{Recorder, Snapshot};
+use ruffle_core::avm2_debugger::{source_path, DebugStackFrame, DebuggerConnection, StopReason};
+use serde_json::{json, Value as JsonValue};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The only thread reported to clients, as all ActionScript runs on one thread.
+const THREAD_ID: u32 = 1;
+
+/// The variables reference of the locals of the innermost frame, which are the
+/// only variables that get recorded.
+const LOCALS_REFERENCE: u32 = 1;
+
+enum Outgoing {
+    /// A response or event, still without its sequence number.
+    Message(JsonValue),
+    Close,
+}
+
+/// A DAP server, which serves one client at a time.
+///
+/// The server outlives the players it debugs; each player's debugger is
+/// attached with a connection from [`DapServer::connection`].
+#[derive(Clone)]
+pub struct DapServer(Arc<DapServerData>);
+
+struct DapServerData {
+    session: Mutex<Session>,
+
+    outgoing: Sender<Outgoing>,
+
+    /// Taken by the client that is currently being served.
+    outgoing_receiver: Mutex<Option<Receiver<Outgoing>>>,
+}
+
+/// What the current client asked for, so that it can be handed to the
+/// debuggers of players created later on.
+#[derive(Default)]
+struct Session {
+    /// The recorder of the current player's debugger.
+    recorder: Option<Recorder>,
+
+    line_breakpoints: Vec<(String, Vec<u32>)>,
+
+    function_breakpoints: Vec<String>,
+
+    /// Whether the client is done setting up its breakpoints.
+    configured: bool,
+}
+
+impl Default for DapServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DapServer {
+    pub fn new() -> Self {
+        let (outgoing, outgoing_receiver) = channel();
+        Self(Arc::new(DapServerData {
+            session: Mutex::new(Session::default()),
+            outgoing,
+            outgoing_receiver: Mutex::new(Some(outgoing_receiver)),
+        }))
+    }
+
+    /// Create the connection for the debugger of a new player.
+    ///
+    /// Until a client has set up its breakpoints, the player doesn't run.
+    /// Any earlier connection is let go, and its player runs freely.
+    pub fn connection(&self) -> Box<dyn DebuggerConnection> {
+        let mut session = self.session();
+        let recorder = if session.configured {
+            Recorder::new()
+        } else {
+            Recorder::waiting_for_configuration()
+        };
+        for (source, lines) in &session.line_breakpoints {
+            recorder.set_line_breakpoints(source.clone(), lines.clone());
+        }
+        if !session.function_breakpoints.is_empty() {
+            recorder.set_function_breakpoints(session.function_breakpoints.clone());
+        }
+
+        let outgoing = self.0.outgoing.clone();
+        recorder.set_listener(move |reason| {
+            let _ = outgoing.send(Outgoing::Message(stopped_event(reason)));
+        });
+
+        if let Some(previous) = session.recorder.replace(recorder.clone()) {
+            previous.disconnect();
+        }
+        Box::new(recorder)
+    }
+
+    /// Serve a single client until it disconnects.
+    pub fn serve<R: Read, W: Write + Send>(&self, reader: R, mut writer: W) -> io::Result<()> {
+        let outgoing = self
+            .0
+            .outgoing_receiver
+            .lock()
+            .expect("Lock should not be poisoned")
+            .take()
+            .ok_or_else(|| io::Error::other("Another client is already being served"))?;
+
+        // Anything left over was meant for an earlier client.
+        while outgoing.try_recv().is_ok() {}
+
+        let (outgoing, result) = std::thread::scope(|scope| {
+            let writer_thread = scope.spawn(move || {
+                let result = write_messages(&outgoing, &mut writer);
+                (outgoing, result)
+            });
+
+            let result = self.read_requests(BufReader::new(reader));
+
+            // Never leave the movie paused without a client to resume it.
+            self.end_session();
+            let _ = self.0.outgoing.send(Outgoing::Close);
+
+            let (outgoing, write_result) =
+                writer_thread.join().expect("DAP writer should not panic");
+            (outgoing, result.and(write_result))
+        });
+
+        *self
+            .0
+            .outgoing_receiver
+            .lock()
+            .expect("Lock should not be poisoned") = Some(outgoing);
+        result
+    }
+
+    fn session(&self) -> MutexGuard<'_, Session> {
+        self.0.session.lock().expect("Lock should not be poisoned")
+    }
+
+    fn end_session(&self) {
+        let mut session = self.session();
+        if let Some(recorder) = &session.recorder {
+            recorder.disconnect();
+        }
+        session.line_breakpoints.clear();
+        session.function_breakpoints.clear();
+        session.configured = false;
+    }
+
+    /// The line the movie is paused at, if it is.
+    fn current(&self) -> Option<Snapshot> {
+        self.session().recorder.as_ref()?.current()
+    }
+
+    fn read_requests(&self, mut reader: impl BufRead) -> io::Result<()> {
+        while let Some(message) = read_message(&mut reader)? {
+            if message["type"] != "request" {
+                continue;
+            }
+            let seq = message["seq"].as_i64().unwrap_or_default();
+            let command = message["command"].as_str().unwrap_or_default();
+            if !self.handle_request(seq, command, &message["arguments"]) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a request from the client, returning whether to keep serving it.
+    fn handle_request(&self, seq: i64, command: &str, arguments: &JsonValue) -> bool {
+        match command {
+            "initialize" => {
+                self.respond(
+                    seq,
+                    command,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsFunctionBreakpoints": true,
+                    }),
+                );
+                self.send(json!({ "type": "event", "event": "initialized", "body": {} }));
+            }
+            "launch" | "attach" | "setExceptionBreakpoints" => {
+                self.respond(seq, command, json!({}));
+            }
+            "setBreakpoints" => {
+                let source = &arguments["source"];
+                let source = source["path"]
+                    .as_str()
+                    .or_else(|| source["name"].as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let lines: Vec<u32> = match arguments["breakpoints"].as_array() {
+                    Some(breakpoints) => breakpoints
+                        .iter()
+                        .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                        .map(|line| line as u32)
+                        .collect(),
+                    None => arguments["lines"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(JsonValue::as_u64)
+                        .map(|line| line as u32)
+                        .collect(),
+                };
+                let breakpoints: Vec<JsonValue> = lines
+                    .iter()
+                    .map(|line| json!({ "verified": true, "line": line }))
+                    .collect();
+
+                let mut session = self.session();
+                if let Some(recorder) = &session.recorder {
+                    recorder.set_line_breakpoints(source.clone(), lines.clone());
+                }
+                session
+                    .line_breakpoints
+                    .retain(|(existing, _)| *existing != source);
+                session.line_breakpoints.push((source, lines));
+                drop(session);
+
+                self.respond(seq, command, json!({ "breakpoints": breakpoints }));
+            }
+            "setFunctionBreakpoints" => {
+                let names: Vec<String> = arguments["breakpoints"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|breakpoint| breakpoint["name"].as_str())
+                    .map(str::to_string)
+                    .collect();
+                let breakpoints: Vec<JsonValue> =
+                    names.iter().map(|_| json!({ "verified": true })).collect();
+
+                let mut session = self.session();
+                if let Some(recorder) = &session.recorder {
+                    recorder.set_function_breakpoints(names.clone());
+                }
+                session.function_breakpoints = names;
+                drop(session);
+
+                self.respond(seq, command, json!({ "breakpoints": breakpoints }));
+            }
+            "configurationDone" => {
+                let mut session = self.session();
+                session.configured = true;
+                if let Some(recorder) = &session.recorder {
+                    recorder.configuration_done();
+                }
+                drop(session);
+
+                self.respond(seq, command, json!({}));
+            }
+            "threads" => {
+                self.respond(
+                    seq,
+                    command,
+                    json!({ "threads": [{ "id": THREAD_ID, "name": "ActionScript" }] }),
+                );
+            }
+            "continue" => self.step(seq, command, Recorder::resume),
+            "next" => self.step(seq, command, Recorder::step_over),
+            "stepIn" => self.step(seq, command, Recorder::step_in),
+            "stepOut" => self.step(seq, command, Recorder::step_out),
+            "pause" => {
+                if let Some(recorder) = &self.session().recorder {
+                    recorder.pause();
+                }
+                self.respond(seq, command, json!({}));
+            }
+            "stackTrace" => match self.current() {
+                Some(snapshot) => {
+                    let total_frames = snapshot.frames.len();
+                    let frames: Vec<JsonValue> =
+                        snapshot.frames.into_iter().map(stack_frame).collect();
+                    self.respond(
+                        seq,
+                        command,
+                        json!({ "stackFrames": frames, "totalFrames": total_frames }),
+                    );
+                }
+                None => self.error(seq, command, "The movie isn't paused"),
+            },
+            "scopes" => {
+                let frame_id = arguments["frameId"].as_u64().unwrap_or_default() as usize;
+                let innermost = self
+                    .current()
+                    .and_then(|snapshot| snapshot.frames.first().map(|frame| frame.id));
+                let scopes = if innermost == Some(frame_id) {
+                    json!([{
+                        "name": "Locals",
+                        "variablesReference": LOCALS_REFERENCE,
+                        "expensive": false,
+                    }])
+                } else {
+                    json!([])
+                };
+                self.respond(seq, command, json!({ "scopes": scopes }));
+            }
+            "variables" => {
+                let reference = arguments["variablesReference"].as_u64().unwrap_or_default();
+                let locals = match self.current() {
+                    Some(snapshot) if reference == u64::from(LOCALS_REFERENCE) => snapshot.locals,
+                    _ => Vec::new(),
+                };
+                let variables: Vec<JsonValue> = locals
+                    .into_iter()
+                    .map(|variable| {
+                        // The recorded values can't be looked into any further.
+                        json!({
+                            "name": variable.name,
+                            "value": variable.value,
+                            "type": variable.type_name,
+                            "variablesReference": 0,
+                        })
+                    })
+                    .collect();
+                self.respond(seq, command, json!({ "variables": variables }));
+            }
+            "disconnect" => {
+                self.respond(seq, command, json!({}));
+                return false;
+            }
+            _ => self.error(seq, command, &format!("Unsupported request: {command}")),
+        }
+        true
+    }
+
+    /// Move through the recording, telling the client where the movie stopped.
+    fn step(&self, seq: i64, command: &str, step: fn(&Recorder) -> Option<StopReason>) {
+        let reason = self.session().recorder.as_ref().and_then(step);
+        let body = if command == "continue" {
+            json!({ "allThreadsContinued": true })
+        } else {
+            json!({})
+        };
+        self.respond(seq, command, body);
+        // Otherwise, the movie runs until the recorder's listener reports a stop.
+        if let Some(reason) = reason {
+            self.send(stopped_event(reason));
+        }
+    }
+
+    fn send(&self, message: JsonValue) {
+        let _ = self.0.outgoing.send(Outgoing::Message(message));
+    }
+
+    fn respond(&self, request_seq: i64, command: &str, body: JsonValue) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request_seq,
+            "success": true,
+            "command": command,
+            "body": body,
+        }));
+    }
+
+    fn error(&self, request_seq: i64, command: &str, message: &str) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request_seq,
+            "success": false,
+            "command": command,
+            "message": message,
+        }));
+    }
+}
+
+fn write_messages(outgoing: &Receiver<Outgoing>, writer: &mut impl Write) -> io::Result<()> {
+    let mut seq = 0;
+    for message in outgoing.iter() {
+        let Outgoing::Message(mut message) = message else {
+            break;
+        };
+        seq += 1;
+        message["seq"] = seq.into();
+        write_message(writer, &message)?;
+    }
+    Ok(())
+}
+
+fn stopped_event(reason: StopReason) -> JsonValue {
+    let reason = match reason {
+        StopReason::Breakpoint => "breakpoint",
+        StopReason::FunctionBreakpoint => "function breakpoint",
+        StopReason::Step => "step",
+        StopReason::Pause => "pause",
+    };
+    json!({
+        "type": "event",
+        "event": "stopped",
+        "body": {
+            "reason": reason,
+            "threadId": THREAD_ID,
+            "allThreadsStopped": true,
+        },
+    })
+}
+
+fn stack_frame(frame: DebugStackFrame) -> JsonValue {
+    let mut result = json!({
+        "id": frame.id,
+        "name": frame.name,
+        "line": frame.line,
+        "column": 0,
+    });
+    if let Some(file) = frame.file {
+        let path = source_path(&file);
+        let name = path
+            .as_deref()
+            .unwrap_or(&file)
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        result["column"] = 1.into();
+        result["source"] = match path {
+            Some(path) => json!({ "name": name, "path": path }),
+            None => json!({ "name": name }),
+        };
+    }
+    result
+}
+
+/// Read a single DAP message, or `None` at the end of the stream.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<JsonValue>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let mut content = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(io::Error::other)
+}
+
+/// Write a single DAP message.
+fn write_message(writer: &mut impl Write, message: &JsonValue) -> io::Result<()> {
+    let content = serde_json::to_vec(message).map_err(io::Error::other)?;
+    write!(writer, "Content-Length: {}\r\n\r\n", content.len())?;
+    writer.write_all(&content)?;
+    writer.flush()
+}
+
+/// Start listening for debugger clients on the given local port.
+///
+/// The returned server is shared by every player that gets created, so that
+/// the editor stays attached when another movie is opened.
+pub fn start_dap_server(port: u16) -> DapServer {
+    let server = DapServer::new();
+    let listener_server = server.clone();
+
+    let result = std::thread::Builder::new()
+        .name("DAP server".to_string())
+        .spawn(move || {
+            let listener = match TcpListener::bind(("127.0.0.1", port)) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::error!("Couldn't start the DAP server on port {port}: {e}");
+                    return;
+                }
+            };
+            tracing::info!("Waiting for a DAP client on port {port}");
+
+            for stream in listener.incoming() {
+                let result = stream.and_then(|stream| {
+                    tracing::info!("DAP client connected");
+                    listener_server.serve(stream.try_clone()?, stream)
+                });
+                if let Err(e) = result {
+                    tracing::warn!("DAP client connection failed: {e}");
+                }
+            }
+        });
+    if let Err(e) = result {
+        tracing::error!("Couldn't start the DAP server thread: {e}");
+    }
+
+    server
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_core::avm2_debugger::{DebugScope, DebugVariable, DebuggerMessage, DebuggerRequest};
+
+    /// Plays the part of the AVM2 debugger stopping at a line.
+    fn stop(connection: &mut dyn DebuggerConnection, reason: StopReason, line: u32) {
+        connection.send(DebuggerMessage::Stopped { reason });
+        loop {
+            match connection.try_recv() {
+                Some(DebuggerRequest::StackTrace) => {
+                    connection.send(DebuggerMessage::StackTrace(vec![DebugStackFrame {
+                        id: 0,
+                        name: "Main/update()".to_string(),
+                        file: Some("/src;com;Main.as".to_string()),
+                        line,
+                    }]))
+                }
+                Some(DebuggerRequest::Scopes { frame_id }) => {
+                    assert_eq!(frame_id, 0);
+                    connection.send(DebuggerMessage::Scopes(vec![DebugScope {
+                        name: "Locals".to_string(),
+                        reference: 5,
+                    }]))
+                }
+                Some(DebuggerRequest::Variables { reference }) => {
+                    assert_eq!(reference, 5);
+                    connection.send(DebuggerMessage::Variables(vec![DebugVariable {
+                        name: "count".to_string(),
+                        value: "3".to_string(),
+                        type_name: "int".to_string(),
+                        reference: 0,
+                    }]))
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn messages(outgoing: &Receiver<Outgoing>) -> Vec<JsonValue> {
+        outgoing
+            .try_iter()
+            .filter_map(|message| match message {
+                Outgoing::Message(message) => Some(message),
+                Outgoing::Close => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn answers_from_the_recording() {
+        let server = DapServer::new();
+        let outgoing = server.0.outgoing_receiver.lock().unwrap().take().unwrap();
+        let mut connection = server.connection();
+
+        server.handle_request(
+            1,
+            "setBreakpoints",
+            &json!({ "source": { "path": "/src/com/Main.as" }, "breakpoints": [{ "line": 3 }] }),
+        );
+        assert!(connection.is_paused(), "Movie should wait for the client");
+        server.handle_request(2, "configurationDone", &json!({}));
+        assert!(!connection.is_paused());
+        assert_eq!(
+            connection.try_recv(),
+            Some(DebuggerRequest::SetLineBreakpoints {
+                source: "/src/com/Main.as".to_string(),
+                lines: vec![3],
+            })
+        );
+
+        stop(&mut *connection, StopReason::Breakpoint, 3);
+        stop(&mut *connection, StopReason::Step, 4);
+        assert!(connection.is_paused());
+
+        server.handle_request(3, "stackTrace", &json!({ "threadId": THREAD_ID }));
+        server.handle_request(4, "variables", &json!({ "variablesReference": 1 }));
+        server.handle_request(5, "next", &json!({ "threadId": THREAD_ID }));
+        server.handle_request(6, "continue", &json!({ "threadId": THREAD_ID }));
+        assert!(!connection.is_paused());
+
+        let messages = messages(&outgoing);
+        let kinds: Vec<_> = messages
+            .iter()
+            .map(|message| {
+                message["event"]
+                    .as_str()
+                    .or_else(|| message["command"].as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "setBreakpoints",
+                "configurationDone",
+                "stopped",
+                "stackTrace",
+                "variables",
+                "next",
+                "stopped",
+                "continue",
+            ]
+        );
+        assert_eq!(messages[2]["body"]["reason"], "breakpoint");
+        let frame = &messages[3]["body"]["stackFrames"][0];
+        assert_eq!(frame["line"], 3);
+        assert_eq!(frame["source"]["path"], "/src/com/Main.as");
+        assert_eq!(frame["source"]["name"], "Main.as");
+        assert_eq!(messages[4]["body"]["variables"][0]["value"], "3");
+        assert_eq!(messages[6]["body"]["reason"], "step");
+    }
+
+    #[test]
+    fn later_players_get_the_breakpoints() {
+        let server = DapServer::new();
+        let mut first = server.connection();
+        server.handle_request(
+            1,
+            "setFunctionBreakpoints",
+            &json!({ "breakpoints": [{ "name": "update" }] }),
+        );
+        server.handle_request(2, "configurationDone", &json!({}));
+
+        let mut second = server.connection();
+        assert!(!second.is_paused());
+        assert_eq!(
+            second.try_recv(),
+            Some(DebuggerRequest::SetFunctionBreakpoints {
+                names: vec!["update".to_string()],
+            })
+        );
+
+        // The first player is let go.
+        assert!(matches!(
+            first.try_recv(),
+            Some(DebuggerRequest::SetFunctionBreakpoints { .. })
+        ));
+        assert_eq!(first.try_recv(), Some(DebuggerRequest::Disconnect));
+    }
+
+    #[test]
+    fn message_framing() {
+        let message = json!({ "seq": 1, "type": "request", "command": "threads" });
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &message).unwrap();
+        assert!(buffer.starts_with(b"Content-Length: "));
+
+        let mut reader = &buffer[..];
+        assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+}
@@ -10,6 +10,7 @@ use egui::{Context, FontData, FontDefinitions, ViewportId};
 use fontdb::{Database, Family, Query, Source};
 use ruffle_core::events::{ImeCursorArea, ImePurpose};
 use ruffle_core::{Player, PlayerEvent};
+use ruffle_render::backend::recording::RecordingBackend;
 use ruffle_render_wgpu::backend::{request_adapter_and_device, WgpuRenderBackend};
 use ruffle_render_wgpu::descriptors::Descriptors;
 use ruffle_render_wgpu::utils::{format_list, get_backend_names};
@@ -376,9 +377,13 @@ impl GuiController {
         );
 
         let movie_view = if let Some(player) = player.as_deref_mut() {
-            let renderer =
-                <dyn Any>::downcast_ref::<WgpuRenderBackend<MovieView>>(player.renderer_mut())
-                    .expect("Renderer must be correct type");
+            let renderer = player.renderer_mut();
+            let renderer = match <dyn Any>::downcast_ref::<RecordingBackend>(renderer) {
+                Some(recording) => recording.inner(),
+                None => renderer,
+            };
+            let renderer = <dyn Any>::downcast_ref::<WgpuRenderBackend<MovieView>>(renderer)
+                .expect("Renderer must be correct type");
             Some(renderer.target())
         } else {
             None
@@ -173,6 +173,12 @@ impl MenuBar {
                                 player.debug_ui().queue_message(DebugMessage::SearchForDisplayObject);
                             }
                         }
+                        if Button::new(text(locale, "debug-menu-open-avm1-trace-recorder")).ui(ui).clicked() {
+                            ui.close();
+                            if let Some(player) = &mut player {
+                                player.debug_ui().queue_message(DebugMessage::ShowAvm1TraceRecorder);
+                            }
+                        }
                     });
                 });
                 ui.menu_button(text(locale, "help-menu"), |ui| {
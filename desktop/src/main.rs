@@ -10,6 +10,7 @@ mod app;
 mod backends;
 mod cli;
 mod custom_event;
+mod dap;
 mod dbus;
 mod gui;
 mod log;
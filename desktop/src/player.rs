@@ -5,6 +5,7 @@ use crate::backends::{
 use crate::cli::FilesystemAccessMode;
 use crate::cli::GameModePreference;
 use crate::custom_event::RuffleEvent;
+use crate::dap::{start_dap_server, DapServer};
 use crate::gui::{FilePicker, MovieView};
 use crate::preferences::GlobalPreferences;
 use crate::{CALLSTACK, RENDER_INFO, SWF_INFO};
@@ -21,6 +22,7 @@ use ruffle_frontend_utils::bundle::{Bundle, BundleError};
 use ruffle_frontend_utils::content::PlayingContent;
 use ruffle_frontend_utils::player_options::PlayerOptions;
 use ruffle_frontend_utils::recents::Recent;
+use ruffle_render::backend::recording::RecordingBackend;
 use ruffle_render::backend::RenderBackend;
 use ruffle_render::quality::StageQuality;
 use ruffle_render_wgpu::backend::WgpuRenderBackend;
@@ -29,6 +31,8 @@ use ruffle_render_wgpu::descriptors::Descriptors;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufWriter;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex, MutexGuard};
@@ -51,6 +55,9 @@ pub struct LaunchOptions {
     pub filesystem_access_mode: FilesystemAccessMode,
     pub gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
     pub avm2_optimizer_enabled: bool,
+    pub fdb_port: Option<u16>,
+    pub fdb_password: Option<String>,
+    pub record_render: Option<PathBuf>,
 }
 
 impl From<&GlobalPreferences> for LaunchOptions {
@@ -97,10 +104,21 @@ impl From<&GlobalPreferences> for LaunchOptions {
             save_directory: value.cli.save_directory.clone(),
             cache_directory: value.cli.cache_directory.clone(),
             filesystem_access_mode: value.cli.filesystem_access_mode,
-            socket_allowed: HashSet::from_iter(value.cli.socket_allow.iter().cloned()),
+            socket_allowed: HashSet::from_iter(
+                value
+                    .cli
+                    .socket_allow
+                    .iter()
+                    .cloned()
+                    // The debugger is trusted, as it was asked for.
+                    .chain(value.cli.fdb_port.map(|port| format!("localhost:{port}"))),
+            ),
             tcp_connections: value.cli.tcp_connections,
             gamepad_button_mapping: HashMap::from_iter(value.cli.gamepad_button.iter().cloned()),
             avm2_optimizer_enabled: !value.cli.no_avm2_optimizer,
+            fdb_port: value.cli.fdb_port,
+            fdb_password: value.cli.fdb_password.clone(),
+            record_render: value.cli.record_render.clone(),
         }
     }
 }
@@ -138,6 +156,7 @@ impl ActivePlayer {
         font_database: Rc<fontdb::Database>,
         preferences: GlobalPreferences,
         file_picker: FilePicker,
+        dap_server: Option<&DapServer>,
     ) -> Self {
         let mut builder = PlayerBuilder::new();
 
@@ -207,6 +226,9 @@ impl ActivePlayer {
                     filesystem_access_mode: opt.filesystem_access_mode,
                     gamepad_button_mapping: opt.gamepad_button_mapping.clone(),
                     avm2_optimizer_enabled: opt.avm2_optimizer_enabled,
+                    fdb_port: opt.fdb_port,
+                    fdb_password: opt.fdb_password.clone(),
+                    record_render: opt.record_render.clone(),
                 })
             }
         };
@@ -279,6 +301,19 @@ impl ActivePlayer {
             .map_err(|e| anyhow!(e.to_string()))
             .expect("Couldn't create wgpu rendering backend");
         RENDER_INFO.with(|i| *i.borrow_mut() = Some(renderer.debug_info().to_string()));
+        let renderer: Box<dyn RenderBackend> = match &opt.record_render {
+            Some(path) => match File::create(path) {
+                Ok(file) => Box::new(RecordingBackend::new(
+                    Box::new(renderer),
+                    Box::new(BufWriter::new(file)),
+                )),
+                Err(e) => {
+                    tracing::error!("Couldn't create render recording {}: {e}", path.display());
+                    Box::new(renderer)
+                }
+            },
+            None => Box::new(renderer),
+        };
 
         if opt.player.dummy_external_interface.unwrap_or_default() {
             builder = builder.with_external_interface(Box::new(DesktopExternalInterfaceProvider {
@@ -305,7 +340,7 @@ impl ActivePlayer {
 
         builder = builder
             .with_navigator(navigator)
-            .with_renderer(renderer)
+            .with_boxed_renderer(renderer)
             .with_storage(preferences.storage_backend().create_backend(&opt))
             .with_notification_sender(notification_sender)
             .with_fs_commands(Box::new(DesktopFSCommandProvider {
@@ -340,7 +375,9 @@ impl ActivePlayer {
             .with_player_version(opt.player.player_version)
             .with_player_runtime(opt.player.player_runtime.unwrap_or_default())
             .with_frame_rate(opt.player.frame_rate)
-            .with_avm2_optimizer_enabled(opt.avm2_optimizer_enabled);
+            .with_avm2_optimizer_enabled(opt.avm2_optimizer_enabled)
+            .with_debugger_address(opt.fdb_port.map(|port| ("localhost".to_string(), port)))
+            .with_debugger_password(opt.fdb_password.clone());
         let player = builder.build();
 
         window.set_title(&format!("Ruffle - {readable_name}"));
@@ -356,6 +393,9 @@ impl ActivePlayer {
             CALLSTACK.with(|callstack| {
                 *callstack.borrow_mut() = Some(player_lock.callstack());
             });
+            if let Some(dap_server) = dap_server {
+                player_lock.attach_avm2_debugger(dap_server.connection());
+            }
             player_lock.fetch_root_movie(
                 movie_url.to_string(),
                 opt.player.parameters.to_owned(),
@@ -437,6 +477,7 @@ pub struct PlayerController {
     font_database: Rc<fontdb::Database>,
     preferences: GlobalPreferences,
     file_picker: FilePicker,
+    dap_server: Option<DapServer>,
 }
 
 impl PlayerController {
@@ -448,6 +489,7 @@ impl PlayerController {
         preferences: GlobalPreferences,
         file_picker: FilePicker,
     ) -> Self {
+        let dap_server = preferences.cli.dap_port.map(start_dap_server);
         Self {
             player: None,
             event_loop,
@@ -456,6 +498,7 @@ impl PlayerController {
             font_database: Rc::new(font_database),
             preferences,
             file_picker,
+            dap_server,
         }
     }
 
@@ -470,6 +513,7 @@ impl PlayerController {
             self.font_database.clone(),
             self.preferences.clone(),
             self.file_picker.clone(),
+            self.dap_server.as_ref(),
         ));
     }
 
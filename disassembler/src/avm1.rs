@@ -0,0 +1,257 @@
+//! AVM1 action disassembly.
+
+use crate::model::{assign_labels, Block, Instruction};
+use swf::avm1::read::Reader;
+use swf::avm1::types::{Action, CatchVar, Value};
+use swf::{Encoding, SwfStr};
+
+/// Disassembles a complete block of AVM1 actions, such as the contents of a `DoAction` tag.
+pub fn disassemble(name: String, data: &[u8], version: u8) -> Block {
+    let mut disassembler = Disassembler {
+        root: data,
+        version,
+        encoding: SwfStr::encoding_for_version(version),
+        constant_pool: vec![],
+    };
+    let mut instructions = disassembler.read_block(data);
+    assign_labels(&mut instructions, &[]);
+    Block { name, instructions }
+}
+
+struct Disassembler<'a> {
+    /// The data of the outermost block. Offsets of nested blocks are relative to this.
+    root: &'a [u8],
+    version: u8,
+    encoding: &'static Encoding,
+    /// The most recently declared constant pool, used to show the value of pool references.
+    constant_pool: Vec<String>,
+}
+
+impl<'a> Disassembler<'a> {
+    fn offset_of(&self, data: &[u8]) -> usize {
+        data.as_ptr() as usize - self.root.as_ptr() as usize
+    }
+
+    fn string(&self, string: &SwfStr) -> String {
+        format!("{:?}", string.to_str_lossy(self.encoding))
+    }
+
+    fn read_block(&mut self, data: &'a [u8]) -> Vec<Instruction> {
+        let mut reader = Reader::new(data, self.version);
+        let mut instructions = vec![];
+        while !reader.get_ref().is_empty() {
+            let offset = self.offset_of(reader.get_ref());
+            match reader.read_action() {
+                Ok(action) => {
+                    let end = self.offset_of(reader.get_ref());
+                    instructions.push(self.instruction(offset, end, action));
+                }
+                Err(e) => {
+                    instructions.push(Instruction::error(offset, e));
+                    break;
+                }
+            }
+        }
+        instructions
+    }
+
+    fn sub_block(&mut self, name: impl Into<String>, data: &'a [u8]) -> Block {
+        Block {
+            name: name.into(),
+            instructions: self.read_block(data),
+        }
+    }
+
+    /// Describes a single action, which started at `offset` and ended at `end`.
+    fn instruction(&mut self, offset: usize, end: usize, action: Action<'a>) -> Instruction {
+        let mut instruction = Instruction::new(offset, "", vec![]);
+        let (op, operands) = match action {
+            Action::ConstantPool(pool) => {
+                self.constant_pool = pool.strings.iter().map(|s| self.string(s)).collect();
+                ("ConstantPool", self.constant_pool.clone())
+            }
+            Action::DefineFunction(function) => {
+                let mut operands = vec![self.string(function.name)];
+                operands.extend(function.params.iter().map(|p| self.string(p)));
+                instruction
+                    .blocks
+                    .push(self.sub_block("body", function.actions));
+                ("DefineFunction", operands)
+            }
+            Action::DefineFunction2(function) => {
+                let mut operands = vec![
+                    self.string(function.name),
+                    format!("registers={}", function.register_count),
+                ];
+                operands.extend(function.params.iter().map(|p| match p.register_index {
+                    Some(register) => format!("{}=r:{register}", self.string(p.name)),
+                    None => self.string(p.name),
+                }));
+                operands.extend(function.flags.iter_names().map(|(name, _)| name.to_owned()));
+                instruction
+                    .blocks
+                    .push(self.sub_block("body", function.actions));
+                ("DefineFunction2", operands)
+            }
+            Action::GetUrl(get_url) => (
+                "GetUrl",
+                vec![self.string(get_url.url), self.string(get_url.target)],
+            ),
+            Action::GetUrl2(get_url) => {
+                let mut operands = vec![format!("{:?}", get_url.send_vars_method())];
+                if get_url.is_target_sprite() {
+                    operands.push("LOAD_TARGET".to_owned());
+                }
+                if get_url.is_load_vars() {
+                    operands.push("LOAD_VARIABLES".to_owned());
+                }
+                ("GetUrl2", operands)
+            }
+            Action::GotoFrame(goto) => ("GotoFrame", vec![goto.frame.to_string()]),
+            Action::GotoFrame2(goto) => (
+                "GotoFrame2",
+                vec![
+                    format!("play={}", goto.set_playing),
+                    format!("scene_offset={}", goto.scene_offset),
+                ],
+            ),
+            Action::GotoLabel(goto) => ("GotoLabel", vec![self.string(goto.label)]),
+            Action::If(action) => {
+                instruction.targets.push(branch_target(end, action.offset));
+                ("If", vec![])
+            }
+            Action::Jump(action) => {
+                instruction.targets.push(branch_target(end, action.offset));
+                ("Jump", vec![])
+            }
+            Action::Push(push) => ("Push", push.values.iter().map(|v| self.value(v)).collect()),
+            Action::SetTarget(set_target) => ("SetTarget", vec![self.string(set_target.target)]),
+            Action::StoreRegister(store) => {
+                ("StoreRegister", vec![format!("r:{}", store.register)])
+            }
+            Action::Try(try_block) => {
+                let mut operands = vec![];
+                instruction
+                    .blocks
+                    .push(self.sub_block("try", try_block.try_body));
+                if let Some((var, body)) = try_block.catch_body {
+                    operands.push(match var {
+                        CatchVar::Var(name) => self.string(name),
+                        CatchVar::Register(register) => format!("r:{register}"),
+                    });
+                    instruction.blocks.push(self.sub_block("catch", body));
+                }
+                if let Some(body) = try_block.finally_body {
+                    instruction.blocks.push(self.sub_block("finally", body));
+                }
+                ("Try", operands)
+            }
+            Action::WaitForFrame(wait) => (
+                "WaitForFrame",
+                vec![
+                    wait.frame.to_string(),
+                    format!("skip={}", wait.num_actions_to_skip),
+                ],
+            ),
+            Action::WaitForFrame2(wait) => (
+                "WaitForFrame2",
+                vec![format!("skip={}", wait.num_actions_to_skip)],
+            ),
+            Action::With(with) => {
+                instruction
+                    .blocks
+                    .push(self.sub_block("body", with.actions));
+                ("With", vec![])
+            }
+            Action::Unknown(unknown) => {
+                instruction.op = format!("Unknown(0x{:02X})", unknown.opcode);
+                instruction.operands = vec![hex(unknown.data)];
+                return instruction;
+            }
+            // Every other action has no operands, so its debug name is all there is to it.
+            action => {
+                instruction.op = format!("{action:?}");
+                return instruction;
+            }
+        };
+        instruction.op = op.to_owned();
+        instruction.operands = operands;
+        instruction
+    }
+
+    fn value(&self, value: &Value) -> String {
+        match value {
+            Value::Undefined => "undefined".to_owned(),
+            Value::Null => "null".to_owned(),
+            Value::Bool(value) => value.to_string(),
+            Value::Int(value) => value.to_string(),
+            Value::Float(value) => format!("{value:?}f"),
+            Value::Double(value) => format!("{value:?}"),
+            Value::Str(value) => self.string(value),
+            Value::Register(register) => format!("r:{register}"),
+            Value::ConstantPool(index) => match self.constant_pool.get(usize::from(*index)) {
+                Some(value) => format!("c:{index} {value}"),
+                None => format!("c:{index}"),
+            },
+        }
+    }
+}
+
+/// AVM1 branches are relative to the end of the branching action.
+fn branch_target(end: usize, offset: i16) -> usize {
+    end.saturating_add_signed(offset.into())
+}
+
+pub fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02X}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::avm1::types::{If, Jump, Push};
+    use swf::avm1::write::Writer;
+
+    fn assemble(actions: &[Action]) -> Vec<u8> {
+        let mut data = vec![];
+        let mut writer = Writer::new(&mut data, 10);
+        for action in actions {
+            writer.write_action(action).unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn branches_are_labelled() {
+        let data = assemble(&[
+            Action::Push(Push {
+                values: vec![Value::Bool(true)],
+            }),
+            Action::If(If { offset: 1 }),
+            Action::Stop,
+            Action::Play,
+            Action::Jump(Jump { offset: -17 }),
+        ]);
+        let block = disassemble("frame 0".to_owned(), &data, 10);
+        let ops: Vec<_> = block.instructions.iter().map(|i| i.op.as_str()).collect();
+        assert_eq!(ops, ["Push", "If", "Stop", "Play", "Jump"]);
+        assert_eq!(block.instructions[0].label.as_deref(), Some("L0"));
+        assert_eq!(block.instructions[1].operands, ["L1"]);
+        assert_eq!(block.instructions[3].label.as_deref(), Some("L1"));
+        assert_eq!(block.instructions[4].operands, ["L0"]);
+    }
+
+    #[test]
+    fn constant_pool_references_are_resolved() {
+        let data = assemble(&[
+            Action::ConstantPool(swf::avm1::types::ConstantPool {
+                strings: vec![SwfStr::from_utf8_str("foo")],
+            }),
+            Action::Push(Push {
+                values: vec![Value::ConstantPool(0), Value::Register(2), Value::Int(3)],
+            }),
+        ]);
+        let block = disassemble("frame 0".to_owned(), &data, 10);
+        assert_eq!(block.instructions[1].operands, ["c:0 \"foo\"", "r:2", "3"]);
+    }
+}
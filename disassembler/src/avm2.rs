@@ -0,0 +1,711 @@
+//! AVM2 ABC disassembly.
+
+use crate::model::{self, assign_labels, Instruction};
+use swf::avm2::read::Reader;
+use swf::avm2::types::{
+    AbcFile, DefaultValue, Index, Method, MethodBody, MethodFlags, Multiname, Namespace, Op, Trait,
+    TraitKind,
+};
+use swf::extensions::ReadSwfExt;
+
+/// Parses and disassembles an ABC file, such as the contents of a `DoAbc` tag.
+pub fn disassemble(name: Option<String>, data: &[u8]) -> Result<model::Abc, swf::error::Error> {
+    let abc = Reader::new(data).read()?;
+    let abc = AbcDisassembler { abc: &abc };
+    Ok(model::Abc {
+        name,
+        major_version: abc.abc.major_version,
+        minor_version: abc.abc.minor_version,
+        scripts: abc
+            .abc
+            .scripts
+            .iter()
+            .enumerate()
+            .map(|(index, script)| model::Script {
+                index,
+                init_method: script.init_method.0,
+                traits: abc.traits(&script.traits),
+            })
+            .collect(),
+        classes: abc
+            .abc
+            .instances
+            .iter()
+            .zip(&abc.abc.classes)
+            .enumerate()
+            .map(|(index, (instance, class))| {
+                let mut flags = vec![];
+                if instance.is_sealed {
+                    flags.push("sealed");
+                }
+                if instance.is_final {
+                    flags.push("final");
+                }
+                if instance.is_interface {
+                    flags.push("interface");
+                }
+                model::Class {
+                    index,
+                    name: abc.multiname(instance.name),
+                    super_name: abc.multiname(instance.super_name),
+                    interfaces: instance
+                        .interfaces
+                        .iter()
+                        .map(|i| abc.multiname(*i))
+                        .collect(),
+                    flags,
+                    protected_namespace: instance.protected_namespace.map(|ns| abc.namespace(ns)),
+                    instance_init: instance.init_method.0,
+                    class_init: class.init_method.0,
+                    instance_traits: abc.traits(&instance.traits),
+                    class_traits: abc.traits(&class.traits),
+                }
+            })
+            .collect(),
+        methods: abc
+            .abc
+            .methods
+            .iter()
+            .enumerate()
+            .map(|(index, method)| abc.method(index, method))
+            .collect(),
+    })
+}
+
+struct AbcDisassembler<'a> {
+    abc: &'a AbcFile,
+}
+
+/// Looks up a constant pool entry, where index 0 is reserved and means "no entry".
+fn lookup<T>(entries: &[T], index: u32) -> Option<&T> {
+    index
+        .checked_sub(1)
+        .and_then(|index| entries.get(index as usize))
+}
+
+impl AbcDisassembler<'_> {
+    fn raw_string(&self, index: Index<String>) -> Option<String> {
+        lookup(&self.abc.constant_pool.strings, index.0)
+            .map(|string| String::from_utf8_lossy(string).into_owned())
+    }
+
+    /// A string constant, quoted.
+    fn string(&self, index: Index<String>) -> String {
+        match self.raw_string(index) {
+            Some(string) => format!("{string:?}"),
+            None if index.0 == 0 => "null".to_owned(),
+            None => invalid(index.0),
+        }
+    }
+
+    /// A name, which matches anything when missing.
+    fn name(&self, index: Index<String>) -> String {
+        match self.raw_string(index) {
+            Some(string) => string,
+            None if index.0 == 0 => "*".to_owned(),
+            None => invalid(index.0),
+        }
+    }
+
+    fn namespace(&self, index: Index<Namespace>) -> String {
+        let Some(namespace) = lookup(&self.abc.constant_pool.namespaces, index.0) else {
+            return if index.0 == 0 {
+                "*".to_owned()
+            } else {
+                invalid(index.0)
+            };
+        };
+        let (kind, uri) = match namespace {
+            Namespace::Namespace(uri) => ("namespace", uri),
+            Namespace::Package(uri) => ("package", uri),
+            Namespace::PackageInternal(uri) => ("internal", uri),
+            Namespace::Protected(uri) => ("protected", uri),
+            Namespace::Explicit(uri) => ("explicit", uri),
+            Namespace::StaticProtected(uri) => ("static protected", uri),
+            Namespace::Private(uri) => ("private", uri),
+        };
+        match (kind, self.raw_string(*uri)) {
+            ("package" | "namespace", Some(uri)) => uri,
+            (kind, Some(uri)) if !uri.is_empty() => format!("{kind} {uri}"),
+            (kind, _) => kind.to_owned(),
+        }
+    }
+
+    fn namespace_set(&self, index: u32) -> String {
+        match lookup(&self.abc.constant_pool.namespace_sets, index) {
+            Some(set) => {
+                let namespaces: Vec<_> = set.iter().map(|ns| self.namespace(*ns)).collect();
+                format!("[{}]", namespaces.join(", "))
+            }
+            None => invalid(index),
+        }
+    }
+
+    /// Formats a multiname as `namespace::name`, omitting the public namespace.
+    fn multiname(&self, index: Index<Multiname>) -> String {
+        let Some(multiname) = lookup(&self.abc.constant_pool.multinames, index.0) else {
+            return if index.0 == 0 {
+                "*".to_owned()
+            } else {
+                invalid(index.0)
+            };
+        };
+        let qualify = |namespace: String, name: String| {
+            if namespace.is_empty() || namespace == "package" {
+                name
+            } else {
+                format!("{namespace}::{name}")
+            }
+        };
+        match multiname {
+            Multiname::QName { namespace, name } => {
+                qualify(self.namespace(*namespace), self.name(*name))
+            }
+            Multiname::QNameA { namespace, name } => {
+                format!("@{}", qualify(self.namespace(*namespace), self.name(*name)))
+            }
+            Multiname::RTQName { name } => format!("<runtime>::{}", self.name(*name)),
+            Multiname::RTQNameA { name } => format!("@<runtime>::{}", self.name(*name)),
+            Multiname::RTQNameL => "<runtime>::<runtime>".to_owned(),
+            Multiname::RTQNameLA => "@<runtime>::<runtime>".to_owned(),
+            Multiname::Multiname {
+                namespace_set,
+                name,
+            } => format!(
+                "{}::{}",
+                self.namespace_set(namespace_set.0),
+                self.name(*name)
+            ),
+            Multiname::MultinameA {
+                namespace_set,
+                name,
+            } => format!(
+                "@{}::{}",
+                self.namespace_set(namespace_set.0),
+                self.name(*name)
+            ),
+            Multiname::MultinameL { namespace_set } => {
+                format!("{}::<runtime>", self.namespace_set(namespace_set.0))
+            }
+            Multiname::MultinameLA { namespace_set } => {
+                format!("@{}::<runtime>", self.namespace_set(namespace_set.0))
+            }
+            Multiname::TypeName {
+                base_type,
+                parameters,
+            } => {
+                let parameters: Vec<_> = parameters.iter().map(|p| self.multiname(*p)).collect();
+                format!("{}.<{}>", self.multiname(*base_type), parameters.join(", "))
+            }
+        }
+    }
+
+    fn constant<T: ToString>(&self, entries: &[T], index: u32) -> String {
+        match lookup(entries, index) {
+            Some(value) => value.to_string(),
+            None => invalid(index),
+        }
+    }
+
+    fn default_value(&self, value: &DefaultValue) -> String {
+        let pool = &self.abc.constant_pool;
+        match value {
+            DefaultValue::Int(index) => self.constant(&pool.ints, index.0),
+            DefaultValue::Uint(index) => self.constant(&pool.uints, index.0),
+            DefaultValue::Double(index) => self.constant(&pool.doubles, index.0),
+            DefaultValue::String(index) => self.string(*index),
+            DefaultValue::True => "true".to_owned(),
+            DefaultValue::False => "false".to_owned(),
+            DefaultValue::Null => "null".to_owned(),
+            DefaultValue::Undefined => "undefined".to_owned(),
+            DefaultValue::Namespace(ns)
+            | DefaultValue::Package(ns)
+            | DefaultValue::PackageInternal(ns)
+            | DefaultValue::Protected(ns)
+            | DefaultValue::Explicit(ns)
+            | DefaultValue::StaticProtected(ns)
+            | DefaultValue::Private(ns) => self.namespace(*ns),
+        }
+    }
+
+    fn method_name(&self, index: Index<Method>) -> String {
+        match self.abc.methods.get(index.0 as usize) {
+            Some(method) => format!("#{} {}", index.0, self.signature(method)),
+            None => format!("#{} <invalid>", index.0),
+        }
+    }
+
+    fn signature(&self, method: &Method) -> String {
+        let mut params: Vec<_> = method
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let name = match param.name {
+                    Some(name) => self.name(name),
+                    None => format!("arg{i}"),
+                };
+                let kind = self.multiname(param.kind);
+                match &param.default_value {
+                    Some(value) => format!("{name}:{kind} = {}", self.default_value(value)),
+                    None => format!("{name}:{kind}"),
+                }
+            })
+            .collect();
+        if method.flags.contains(MethodFlags::NEED_REST) {
+            params.push("...rest".to_owned());
+        }
+        format!(
+            "{}({}):{}",
+            self.raw_string(method.name).unwrap_or_default(),
+            params.join(", "),
+            self.multiname(method.return_type)
+        )
+    }
+
+    fn method(&self, index: usize, method: &Method) -> model::Method {
+        model::Method {
+            index,
+            name: self.raw_string(method.name).unwrap_or_default(),
+            signature: self.signature(method),
+            flags: method.flags.iter_names().map(|(name, _)| name).collect(),
+            body: method
+                .body
+                .and_then(|body| self.abc.method_bodies.get(body.0 as usize))
+                .map(|body| self.body(body)),
+        }
+    }
+
+    fn traits(&self, traits: &[Trait]) -> Vec<model::Trait> {
+        traits.iter().map(|t| self.trait_(t)).collect()
+    }
+
+    fn trait_(&self, trait_: &Trait) -> model::Trait {
+        let mut result = model::Trait {
+            name: self.multiname(trait_.name),
+            kind: "",
+            id: 0,
+            type_name: None,
+            value: None,
+            method: None,
+            class: None,
+            flags: vec![],
+            metadata: trait_
+                .metadata
+                .iter()
+                .map(|index| self.metadata(index.0))
+                .collect(),
+        };
+        if trait_.is_final {
+            result.flags.push("final");
+        }
+        if trait_.is_override {
+            result.flags.push("override");
+        }
+        match &trait_.kind {
+            TraitKind::Slot {
+                slot_id,
+                type_name,
+                value,
+            }
+            | TraitKind::Const {
+                slot_id,
+                type_name,
+                value,
+            } => {
+                result.kind = if matches!(trait_.kind, TraitKind::Slot { .. }) {
+                    "slot"
+                } else {
+                    "const"
+                };
+                result.id = *slot_id;
+                result.type_name = Some(self.multiname(*type_name));
+                result.value = value.as_ref().map(|v| self.default_value(v));
+            }
+            TraitKind::Method { disp_id, method } => {
+                result.kind = "method";
+                result.id = *disp_id;
+                result.method = Some(method.0);
+            }
+            TraitKind::Getter { disp_id, method } => {
+                result.kind = "getter";
+                result.id = *disp_id;
+                result.method = Some(method.0);
+            }
+            TraitKind::Setter { disp_id, method } => {
+                result.kind = "setter";
+                result.id = *disp_id;
+                result.method = Some(method.0);
+            }
+            TraitKind::Class { slot_id, class } => {
+                result.kind = "class";
+                result.id = *slot_id;
+                result.class = Some(class.0);
+            }
+            TraitKind::Function { slot_id, function } => {
+                result.kind = "function";
+                result.id = *slot_id;
+                result.method = Some(function.0);
+            }
+        }
+        result
+    }
+
+    fn metadata(&self, index: u32) -> String {
+        let Some(metadata) = self.abc.metadata.get(index as usize) else {
+            return invalid(index);
+        };
+        let items: Vec<_> = metadata
+            .items
+            .iter()
+            .map(|item| match self.raw_string(item.key) {
+                Some(key) => format!("{key}={}", self.string(item.value)),
+                None => self.string(item.value),
+            })
+            .collect();
+        format!("[{}({})]", self.name(metadata.name), items.join(", "))
+    }
+
+    fn body(&self, body: &MethodBody) -> model::MethodBody {
+        let mut instructions = vec![];
+        let mut reader = Reader::new(&body.code);
+        while !reader.as_slice().is_empty() {
+            let offset = reader.pos(&body.code);
+            match reader.read_op() {
+                Ok(op) => {
+                    let end = reader.pos(&body.code);
+                    instructions.push(self.instruction(offset, end, op));
+                }
+                Err(e) => {
+                    instructions.push(Instruction::error(offset, e));
+                    break;
+                }
+            }
+        }
+
+        let exception_offsets: Vec<_> = body
+            .exceptions
+            .iter()
+            .flat_map(|e| [e.from_offset, e.to_offset, e.target_offset])
+            .map(|offset| offset as usize)
+            .collect();
+        let labels = assign_labels(&mut instructions, &exception_offsets);
+        let label = |offset: u32| labels[&(offset as usize)].clone();
+
+        model::MethodBody {
+            max_stack: body.max_stack,
+            num_locals: body.num_locals,
+            init_scope_depth: body.init_scope_depth,
+            max_scope_depth: body.max_scope_depth,
+            exceptions: body
+                .exceptions
+                .iter()
+                .map(|e| model::ExceptionHandler {
+                    from: label(e.from_offset),
+                    to: label(e.to_offset),
+                    target: label(e.target_offset),
+                    variable_name: self.multiname(e.variable_name),
+                    type_name: self.multiname(e.type_name),
+                })
+                .collect(),
+            traits: self.traits(&body.traits),
+            instructions,
+        }
+    }
+
+    /// Describes a single op, which started at `offset` and ended at `end`.
+    fn instruction(&self, offset: usize, end: usize, op: Op) -> Instruction {
+        let pool = &self.abc.constant_pool;
+        let mut targets = vec![];
+        let operands = match &op {
+            Op::AsType { type_name: index }
+            | Op::Coerce { index }
+            | Op::DeleteProperty { index }
+            | Op::FindDef { index }
+            | Op::FindProperty { index }
+            | Op::FindPropStrict { index }
+            | Op::GetDescendants { index }
+            | Op::GetLex { index }
+            | Op::GetProperty { index }
+            | Op::GetSuper { index }
+            | Op::InitProperty { index }
+            | Op::IsType { index }
+            | Op::SetProperty { index }
+            | Op::SetSuper { index } => vec![self.multiname(*index)],
+            Op::CallProperty { index, num_args }
+            | Op::CallPropLex { index, num_args }
+            | Op::CallPropVoid { index, num_args }
+            | Op::CallSuper { index, num_args }
+            | Op::CallSuperVoid { index, num_args }
+            | Op::ConstructProp { index, num_args } => {
+                vec![self.multiname(*index), num_args.to_string()]
+            }
+            Op::CallStatic { index, num_args } => {
+                vec![self.method_name(*index), num_args.to_string()]
+            }
+            Op::NewFunction { index } => vec![self.method_name(*index)],
+            Op::NewClass { index } => vec![match self.abc.instances.get(index.0 as usize) {
+                Some(instance) => format!("#{} {}", index.0, self.multiname(instance.name)),
+                None => format!("#{} <invalid>", index.0),
+            }],
+            Op::NewCatch { index } => vec![format!("#{}", index.0)],
+            Op::PushString { value } => vec![self.string(*value)],
+            Op::Dxns { index } | Op::DebugFile { file_name: index } => vec![self.string(*index)],
+            Op::PushInt { value } => vec![self.constant(&pool.ints, value.0)],
+            Op::PushUint { value } => vec![self.constant(&pool.uints, value.0)],
+            Op::PushDouble { value } => vec![self.constant(&pool.doubles, value.0)],
+            Op::PushNamespace { value } => vec![self.namespace(*value)],
+            Op::PushByte { value } => vec![value.to_string()],
+            Op::PushShort { value } => vec![value.to_string()],
+            Op::GetScopeObject { index } => vec![index.to_string()],
+            Op::Debug {
+                is_local_register,
+                register_name,
+                register,
+            } => vec![
+                is_local_register.to_string(),
+                self.string(*register_name),
+                register.to_string(),
+            ],
+            Op::HasNext2 {
+                object_register,
+                index_register,
+            } => vec![object_register.to_string(), index_register.to_string()],
+            Op::CallMethod { index, num_args } => vec![index.to_string(), num_args.to_string()],
+            Op::ApplyType { num_types: value }
+            | Op::BkptLine { line_num: value }
+            | Op::Call { num_args: value }
+            | Op::Construct { num_args: value }
+            | Op::ConstructSuper { num_args: value }
+            | Op::DebugLine { line_num: value }
+            | Op::DecLocal { index: value }
+            | Op::DecLocalI { index: value }
+            | Op::GetGlobalSlot { index: value }
+            | Op::GetLocal { index: value }
+            | Op::GetOuterScope { index: value }
+            | Op::GetSlot { index: value }
+            | Op::IncLocal { index: value }
+            | Op::IncLocalI { index: value }
+            | Op::Kill { index: value }
+            | Op::NewArray { num_args: value }
+            | Op::NewObject { num_args: value }
+            | Op::SetGlobalSlot { index: value }
+            | Op::SetLocal { index: value }
+            | Op::SetSlot { index: value } => vec![value.to_string()],
+            Op::IfEq { offset }
+            | Op::IfFalse { offset }
+            | Op::IfGe { offset }
+            | Op::IfGt { offset }
+            | Op::IfLe { offset }
+            | Op::IfLt { offset }
+            | Op::IfNe { offset }
+            | Op::IfNge { offset }
+            | Op::IfNgt { offset }
+            | Op::IfNle { offset }
+            | Op::IfNlt { offset }
+            | Op::IfStrictEq { offset }
+            | Op::IfStrictNe { offset }
+            | Op::IfTrue { offset }
+            | Op::Jump { offset } => {
+                // Branches are relative to the end of the branching op.
+                targets.push(end.saturating_add_signed(*offset as isize));
+                vec![]
+            }
+            Op::LookupSwitch(switch) => {
+                // Unlike other branches, lookup switches are relative to the start of the op.
+                targets.push(offset.saturating_add_signed(switch.default_offset as isize));
+                targets.extend(
+                    switch
+                        .case_offsets
+                        .iter()
+                        .map(|case| offset.saturating_add_signed(*case as isize)),
+                );
+                vec![]
+            }
+            _ => vec![],
+        };
+        let mut instruction = Instruction::new(offset, op_name(&op), operands);
+        instruction.targets = targets;
+        instruction
+    }
+}
+
+/// The name of an op, as it appears in the `Op` enum.
+fn op_name(op: &Op) -> String {
+    let debug = format!("{op:?}");
+    match debug.find([' ', '(', '{']) {
+        Some(end) => debug[..end].to_owned(),
+        None => debug,
+    }
+}
+
+fn invalid(index: u32) -> String {
+    format!("<invalid #{index}>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::avm2::types::{ConstantPool, Exception, MethodParam};
+    use swf::avm2::write::Writer;
+
+    fn abc(multinames: Vec<Multiname>, code: &[Op], exceptions: Vec<Exception>) -> Vec<u8> {
+        let mut code_bytes = vec![];
+        let mut writer = Writer::new(&mut code_bytes);
+        for op in code {
+            writer.write_op(op).unwrap();
+        }
+        let abc = AbcFile {
+            major_version: 46,
+            minor_version: 16,
+            constant_pool: ConstantPool {
+                ints: vec![],
+                uints: vec![],
+                doubles: vec![],
+                strings: vec![
+                    b"flash.display".to_vec(),
+                    b"Sprite".to_vec(),
+                    b"".to_vec(),
+                    b"x".to_vec(),
+                ],
+                namespaces: vec![
+                    Namespace::Package(Index::new(1)),
+                    Namespace::Package(Index::new(3)),
+                    Namespace::Private(Index::new(3)),
+                ],
+                namespace_sets: vec![vec![Index::new(2), Index::new(3)]],
+                multinames,
+            },
+            methods: vec![Method {
+                name: Index::new(0),
+                params: vec![MethodParam {
+                    name: None,
+                    kind: Index::new(1),
+                    default_value: None,
+                }],
+                return_type: Index::new(0),
+                flags: MethodFlags::empty(),
+                body: Some(Index::new(0)),
+            }],
+            metadata: vec![],
+            instances: vec![],
+            classes: vec![],
+            scripts: vec![],
+            method_bodies: vec![MethodBody {
+                method: Index::new(0),
+                max_stack: 1,
+                num_locals: 2,
+                init_scope_depth: 0,
+                max_scope_depth: 1,
+                code: code_bytes,
+                exceptions,
+                traits: vec![],
+            }],
+        };
+        let mut data = vec![];
+        Writer::new(&mut data).write(abc).unwrap();
+        data
+    }
+
+    #[test]
+    fn multinames_are_resolved() {
+        let data = abc(
+            vec![
+                Multiname::QName {
+                    namespace: Index::new(1),
+                    name: Index::new(2),
+                },
+                Multiname::QName {
+                    namespace: Index::new(3),
+                    name: Index::new(4),
+                },
+                Multiname::Multiname {
+                    namespace_set: Index::new(1),
+                    name: Index::new(4),
+                },
+                Multiname::TypeName {
+                    base_type: Index::new(1),
+                    parameters: vec![Index::new(0)],
+                },
+            ],
+            &[
+                Op::GetLex {
+                    index: Index::new(1),
+                },
+                Op::GetProperty {
+                    index: Index::new(2),
+                },
+                Op::SetProperty {
+                    index: Index::new(3),
+                },
+                Op::Coerce {
+                    index: Index::new(4),
+                },
+                Op::ReturnVoid,
+            ],
+            vec![],
+        );
+        let abc = disassemble(None, &data).unwrap();
+        let method = &abc.methods[0];
+        assert_eq!(method.signature, "(arg0:flash.display::Sprite):*");
+        let operands: Vec<_> = method.body.as_ref().unwrap().instructions[..4]
+            .iter()
+            .map(|i| i.operands[0].as_str())
+            .collect();
+        assert_eq!(
+            operands,
+            [
+                "flash.display::Sprite",
+                "private::x",
+                "[, private]::x",
+                "flash.display::Sprite.<*>"
+            ]
+        );
+    }
+
+    #[test]
+    fn branches_and_exceptions_are_labelled() {
+        let data = abc(
+            vec![],
+            &[
+                Op::PushTrue,
+                Op::IfFalse { offset: 1 },
+                Op::Nop,
+                Op::Label,
+                Op::Jump { offset: -11 },
+                Op::ReturnVoid,
+            ],
+            vec![Exception {
+                from_offset: 0,
+                to_offset: 5,
+                target_offset: 11,
+                variable_name: Index::new(0),
+                type_name: Index::new(0),
+            }],
+        );
+        let abc = disassemble(None, &data).unwrap();
+        let body = abc.methods[0].body.as_ref().unwrap();
+        let listing: Vec<_> = body
+            .instructions
+            .iter()
+            .map(|i| (i.label.as_deref(), i.op.as_str(), i.operands.join(" ")))
+            .collect();
+        assert_eq!(
+            listing,
+            [
+                (Some("L0"), "PushTrue", "".to_owned()),
+                (None, "IfFalse", "L2".to_owned()),
+                (Some("L1"), "Nop", "".to_owned()),
+                (Some("L2"), "Label", "".to_owned()),
+                (None, "Jump", "L0".to_owned()),
+                (Some("L3"), "ReturnVoid", "".to_owned()),
+            ]
+        );
+        let exception = &body.exceptions[0];
+        assert_eq!(
+            (exception.from.as_str(), exception.to.as_str()),
+            ("L0", "L1")
+        );
+        assert_eq!(exception.target, "L3");
+    }
+}
@@ -0,0 +1,25 @@
+//! CLI Options
+
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[clap(version, about)]
+pub struct Opt {
+    /// The SWF file to disassemble
+    #[clap(name = "swf")]
+    pub input_path: PathBuf,
+
+    /// The format to print the disassembly in
+    #[clap(short, long, value_enum, default_value_t)]
+    pub format: OutputFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum OutputFormat {
+    /// Indented plain text
+    #[default]
+    Text,
+    /// Pretty-printed JSON
+    Json,
+}
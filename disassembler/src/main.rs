@@ -0,0 +1,49 @@
+//! Disassembles the tags, AVM1 actions and AVM2 bytecode of a SWF file.
+
+use crate::cli_options::{Opt, OutputFormat};
+use crate::model::{Document, Header};
+use crate::tags::TagWalker;
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::io::{BufWriter, Write};
+
+mod avm1;
+mod avm2;
+mod cli_options;
+mod model;
+mod tags;
+mod text;
+
+fn main() -> Result<()> {
+    let opt = Opt::parse();
+
+    let data = std::fs::read(&opt.input_path)
+        .with_context(|| format!("Couldn't read {}", opt.input_path.display()))?;
+    let swf_buf = swf::decompress_swf(&data[..])?;
+    let swf = swf::parse_swf(&swf_buf)?;
+
+    let header = &swf.header;
+    let document = Document {
+        header: Header {
+            version: header.version(),
+            compression: format!("{:?}", header.compression()),
+            uncompressed_length: header.uncompressed_len(),
+            width: header.stage_size().width().to_pixels(),
+            height: header.stage_size().height().to_pixels(),
+            frame_rate: header.frame_rate().to_f32(),
+            num_frames: header.num_frames(),
+        },
+        tags: TagWalker::new(header.version()).walk(&swf.tags),
+    };
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    match opt.format {
+        OutputFormat::Text => text::write_document(&mut out, &document)?,
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &document)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
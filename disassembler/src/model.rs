@@ -0,0 +1,213 @@
+//! The disassembled representation of a SWF, shared by every output format.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Debug)]
+pub struct Document {
+    pub header: Header,
+    pub tags: Vec<TagNode>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Header {
+    pub version: u8,
+    pub compression: String,
+    pub uncompressed_length: i32,
+    pub width: f64,
+    pub height: f64,
+    pub frame_rate: f32,
+    pub num_frames: u16,
+}
+
+/// A single tag, along with everything that was decoded out of it.
+#[derive(Serialize, Debug)]
+pub struct TagNode {
+    pub name: &'static str,
+    /// The (zero-based) frame of the timeline that this tag belongs to.
+    pub frame: u16,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    /// The tags of a `DefineSprite`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TagNode>,
+    /// Every AVM1 action block within this tag: frame scripts, button conditions and clip events.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<Block>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abc: Option<Abc>,
+}
+
+/// A named sequence of instructions.
+#[derive(Serialize, Debug)]
+pub struct Block {
+    pub name: String,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Instruction {
+    /// The byte offset of this instruction from the start of its action or method body.
+    pub offset: usize,
+    /// The label of this instruction, if anything jumps to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub op: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub operands: Vec<String>,
+    /// Nested code, such as the body of an AVM1 `DefineFunction`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<Block>,
+    /// The offsets this instruction may branch to, in operand order. Resolved into labels
+    /// and appended to `operands` once a whole block has been read.
+    #[serde(skip)]
+    pub targets: Vec<usize>,
+}
+
+impl Instruction {
+    pub fn new(offset: usize, op: impl Into<String>, operands: Vec<String>) -> Self {
+        Self {
+            offset,
+            label: None,
+            op: op.into(),
+            operands,
+            blocks: vec![],
+            targets: vec![],
+        }
+    }
+
+    pub fn error(offset: usize, error: impl std::fmt::Display) -> Self {
+        Self::new(offset, "<error>", vec![error.to_string()])
+    }
+}
+
+/// Names every branch target within `instructions` (and any nested blocks), as well as
+/// the offsets in `extra_targets`, then labels the instructions at those offsets.
+///
+/// Returns the label of each targeted offset.
+pub fn assign_labels(
+    instructions: &mut [Instruction],
+    extra_targets: &[usize],
+) -> BTreeMap<usize, String> {
+    fn collect(instructions: &[Instruction], labels: &mut BTreeMap<usize, String>) {
+        for instruction in instructions {
+            for target in &instruction.targets {
+                labels.insert(*target, String::new());
+            }
+            for block in &instruction.blocks {
+                collect(&block.instructions, labels);
+            }
+        }
+    }
+
+    fn apply(instructions: &mut [Instruction], labels: &BTreeMap<usize, String>) {
+        for instruction in instructions {
+            instruction.label = labels.get(&instruction.offset).cloned();
+            for target in &instruction.targets {
+                instruction.operands.push(labels[target].clone());
+            }
+            for block in &mut instruction.blocks {
+                apply(&mut block.instructions, labels);
+            }
+        }
+    }
+
+    let mut labels = BTreeMap::new();
+    collect(instructions, &mut labels);
+    for target in extra_targets {
+        labels.insert(*target, String::new());
+    }
+    for (i, label) in labels.values_mut().enumerate() {
+        *label = format!("L{i}");
+    }
+    apply(instructions, &labels);
+    labels
+}
+
+#[derive(Serialize, Debug)]
+pub struct Abc {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub scripts: Vec<Script>,
+    pub classes: Vec<Class>,
+    pub methods: Vec<Method>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Script {
+    pub index: usize,
+    pub init_method: u32,
+    pub traits: Vec<Trait>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Class {
+    pub index: usize,
+    pub name: String,
+    pub super_name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub interfaces: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protected_namespace: Option<String>,
+    pub instance_init: u32,
+    pub class_init: u32,
+    pub instance_traits: Vec<Trait>,
+    pub class_traits: Vec<Trait>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Trait {
+    pub name: String,
+    pub kind: &'static str,
+    /// The slot id of a slot, const, class or function trait, or the dispatch id of a method.
+    pub id: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub metadata: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Method {
+    pub index: usize,
+    pub name: String,
+    pub signature: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<MethodBody>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MethodBody {
+    pub max_stack: u32,
+    pub num_locals: u32,
+    pub init_scope_depth: u32,
+    pub max_scope_depth: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exceptions: Vec<ExceptionHandler>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub traits: Vec<Trait>,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExceptionHandler {
+    pub from: String,
+    pub to: String,
+    pub target: String,
+    pub variable_name: String,
+    pub type_name: String,
+}
@@ -0,0 +1,293 @@
+//! Walks the tag tree of a SWF, disassembling any code found along the way.
+
+use crate::model::{Abc, TagNode};
+use crate::{avm1, avm2};
+use swf::{ButtonActionCondition, CharacterId, Encoding, PlaceObjectAction, SwfStr, Tag};
+
+pub struct TagWalker {
+    version: u8,
+    encoding: &'static Encoding,
+}
+
+impl TagWalker {
+    pub fn new(version: u8) -> Self {
+        Self {
+            version,
+            encoding: SwfStr::encoding_for_version(version),
+        }
+    }
+
+    /// Describes a timeline of tags, such as the main timeline or the contents of a sprite.
+    pub fn walk(&self, tags: &[Tag]) -> Vec<TagNode> {
+        let mut frame = 0;
+        let mut nodes = Vec::with_capacity(tags.len());
+        for tag in tags {
+            nodes.push(self.tag(tag, frame));
+            if matches!(tag, Tag::ShowFrame) {
+                frame += 1;
+            }
+        }
+        nodes
+    }
+
+    fn string(&self, string: &SwfStr) -> String {
+        format!("{:?}", string.to_str_lossy(self.encoding))
+    }
+
+    fn tag(&self, tag: &Tag, frame: u16) -> TagNode {
+        let mut node = TagNode {
+            name: tag_name(tag),
+            frame,
+            details: vec![],
+            children: vec![],
+            actions: vec![],
+            abc: None,
+        };
+        if let Some(id) = character_id(tag) {
+            node.details.push(format!("id={id}"));
+        }
+
+        match tag {
+            Tag::DefineSprite(sprite) => {
+                node.details.push(format!("frames={}", sprite.num_frames));
+                node.children = self.walk(&sprite.tags);
+            }
+            Tag::DoAction(data) => {
+                node.actions.push(avm1::disassemble(
+                    format!("frame {frame}"),
+                    data,
+                    self.version,
+                ));
+            }
+            Tag::DoInitAction { action_data, .. } => {
+                node.actions.push(avm1::disassemble(
+                    "init".to_owned(),
+                    action_data,
+                    self.version,
+                ));
+            }
+            Tag::DefineButton(button) | Tag::DefineButton2(button) => {
+                for action in &button.actions {
+                    let mut conditions: Vec<_> = (action.conditions
+                        - ButtonActionCondition::KEY_PRESS)
+                        .iter_names()
+                        .map(|(name, _)| name.to_owned())
+                        .collect();
+                    if let Some(key_code) = action.key_press() {
+                        conditions.push(format!("KEY_PRESS({key_code})"));
+                    }
+                    node.actions.push(avm1::disassemble(
+                        format!("on({})", conditions.join(" | ")),
+                        action.action_data,
+                        self.version,
+                    ));
+                }
+            }
+            Tag::PlaceObject(place_object) => {
+                node.details.push(format!("depth={}", place_object.depth));
+                match place_object.action {
+                    PlaceObjectAction::Place(id) => node.details.push(format!("place={id}")),
+                    PlaceObjectAction::Replace(id) => node.details.push(format!("replace={id}")),
+                    PlaceObjectAction::Modify => node.details.push("modify".to_owned()),
+                }
+                if let Some(name) = place_object.name {
+                    node.details.push(format!("name={}", self.string(name)));
+                }
+                if let Some(class_name) = place_object.class_name {
+                    node.details
+                        .push(format!("class={}", self.string(class_name)));
+                }
+                if let Some(clip_depth) = place_object.clip_depth {
+                    node.details.push(format!("clip_depth={clip_depth}"));
+                }
+                for clip_action in place_object.clip_actions.iter().flatten() {
+                    let mut events: Vec<_> = clip_action
+                        .events
+                        .iter_names()
+                        .map(|(name, _)| name.to_owned())
+                        .collect();
+                    if let Some(key_code) = clip_action.key_code {
+                        events.push(format!("key={key_code}"));
+                    }
+                    node.actions.push(avm1::disassemble(
+                        format!("onClipEvent({})", events.join(" | ")),
+                        clip_action.action_data,
+                        self.version,
+                    ));
+                }
+            }
+            Tag::RemoveObject(remove_object) => {
+                node.details.push(format!("depth={}", remove_object.depth));
+            }
+            Tag::DoAbc(data) => node.abc = self.abc(None, data, &mut node),
+            Tag::DoAbc2(do_abc) => {
+                let name = do_abc.name.to_string_lossy(self.encoding);
+                node.details
+                    .extend(do_abc.flags.iter_names().map(|(name, _)| name.to_owned()));
+                node.abc = self.abc(Some(name), do_abc.data, &mut node);
+            }
+            Tag::FrameLabel(frame_label) => {
+                node.details.push(self.string(frame_label.label));
+                if frame_label.is_anchor {
+                    node.details.push("anchor".to_owned());
+                }
+            }
+            Tag::SymbolClass(links) => {
+                node.details.extend(
+                    links
+                        .iter()
+                        .map(|link| format!("{}={}", link.id, self.string(link.class_name))),
+                );
+            }
+            Tag::ExportAssets(assets) => {
+                node.details.extend(
+                    assets
+                        .iter()
+                        .map(|asset| format!("{}={}", asset.id, self.string(asset.name))),
+                );
+            }
+            Tag::ImportAssets { url, imports } => {
+                node.details.push(format!("url={}", self.string(url)));
+                node.details.extend(
+                    imports
+                        .iter()
+                        .map(|asset| format!("{}={}", asset.id, self.string(asset.name))),
+                );
+            }
+            Tag::SetBackgroundColor(color) => {
+                node.details
+                    .push(format!("#{:02X}{:02X}{:02X}", color.r, color.g, color.b));
+            }
+            Tag::FileAttributes(attributes) => {
+                node.details
+                    .extend(attributes.iter_names().map(|(name, _)| name.to_owned()));
+            }
+            Tag::DefineSceneAndFrameLabelData(data) => {
+                for scene in &data.scenes {
+                    node.details.push(format!(
+                        "scene {}={}",
+                        scene.frame_num,
+                        self.string(scene.label)
+                    ));
+                }
+                for label in &data.frame_labels {
+                    node.details.push(format!(
+                        "label {}={}",
+                        label.frame_num,
+                        self.string(label.label)
+                    ));
+                }
+            }
+            Tag::Unknown { tag_code, data } => {
+                node.details.push(format!("code={tag_code}"));
+                node.details.push(format!("length={}", data.len()));
+            }
+            _ => {}
+        }
+        node
+    }
+
+    /// Disassembles an ABC file, recording any parsing error as a detail of `node`.
+    fn abc(&self, name: Option<String>, data: &[u8], node: &mut TagNode) -> Option<Abc> {
+        match avm2::disassemble(name, data) {
+            Ok(abc) => Some(abc),
+            Err(e) => {
+                node.details.push(format!("error: {e}"));
+                None
+            }
+        }
+    }
+}
+
+fn character_id(tag: &Tag) -> Option<CharacterId> {
+    Some(match tag {
+        Tag::DefineBinaryData(data) => data.id,
+        Tag::DefineBits { id, .. } | Tag::DefineBitsJpeg2 { id, .. } => *id,
+        Tag::DefineBitsJpeg3(jpeg) => jpeg.id,
+        Tag::DefineBitsLossless(bitmap) => bitmap.id,
+        Tag::DefineButton(button) | Tag::DefineButton2(button) => button.id,
+        Tag::DefineButtonColorTransform(transform) => transform.id,
+        Tag::DefineButtonSound(sounds) => sounds.id,
+        Tag::DefineEditText(edit_text) => edit_text.id(),
+        Tag::DefineFont(font) => font.id,
+        Tag::DefineFont2(font) => font.id,
+        Tag::DefineFont4(font) => font.id,
+        Tag::DefineFontAlignZones { id, .. } | Tag::DefineFontName { id, .. } => *id,
+        Tag::DefineFontInfo(info) => info.id,
+        Tag::DefineMorphShape(morph_shape) => morph_shape.id,
+        Tag::DefineScalingGrid { id, .. } => *id,
+        Tag::DefineShape(shape) => shape.id,
+        Tag::DefineSound(sound) => sound.id,
+        Tag::DefineSprite(sprite) => sprite.id,
+        Tag::DefineText(text) | Tag::DefineText2(text) => text.id,
+        Tag::DefineVideoStream(video) => video.id,
+        Tag::DoInitAction { id, .. } => *id,
+        Tag::NameCharacter(name) => name.id,
+        Tag::StartSound(sound) => sound.id,
+        Tag::VideoFrame(frame) => frame.stream_id,
+        Tag::CsmTextSettings(settings) => settings.id,
+        _ => return None,
+    })
+}
+
+fn tag_name(tag: &Tag) -> &'static str {
+    match tag {
+        Tag::ExportAssets(_) => "ExportAssets",
+        Tag::ScriptLimits { .. } => "ScriptLimits",
+        Tag::ShowFrame => "ShowFrame",
+        Tag::Protect(_) => "Protect",
+        Tag::CsmTextSettings(_) => "CsmTextSettings",
+        Tag::DebugId(_) => "DebugId",
+        Tag::DefineBinaryData(_) => "DefineBinaryData",
+        Tag::DefineBits { .. } => "DefineBits",
+        Tag::DefineBitsJpeg2 { .. } => "DefineBitsJpeg2",
+        Tag::DefineBitsJpeg3(_) => "DefineBitsJpeg3",
+        Tag::DefineBitsLossless(_) => "DefineBitsLossless",
+        Tag::DefineButton(_) => "DefineButton",
+        Tag::DefineButton2(_) => "DefineButton2",
+        Tag::DefineButtonColorTransform(_) => "DefineButtonColorTransform",
+        Tag::DefineButtonSound(_) => "DefineButtonSound",
+        Tag::DefineEditText(_) => "DefineEditText",
+        Tag::DefineFont(_) => "DefineFont",
+        Tag::DefineFont2(_) => "DefineFont2",
+        Tag::DefineFont4(_) => "DefineFont4",
+        Tag::DefineFontAlignZones { .. } => "DefineFontAlignZones",
+        Tag::DefineFontInfo(_) => "DefineFontInfo",
+        Tag::DefineFontName { .. } => "DefineFontName",
+        Tag::DefineMorphShape(_) => "DefineMorphShape",
+        Tag::DefineScalingGrid { .. } => "DefineScalingGrid",
+        Tag::DefineShape(_) => "DefineShape",
+        Tag::DefineSound(_) => "DefineSound",
+        Tag::DefineSprite(_) => "DefineSprite",
+        Tag::DefineText(_) => "DefineText",
+        Tag::DefineText2(_) => "DefineText2",
+        Tag::DefineVideoStream(_) => "DefineVideoStream",
+        Tag::DoAbc(_) => "DoAbc",
+        Tag::DoAbc2(_) => "DoAbc2",
+        Tag::DoAction(_) => "DoAction",
+        Tag::DoInitAction { .. } => "DoInitAction",
+        Tag::EnableDebugger(_) => "EnableDebugger",
+        Tag::EnableTelemetry { .. } => "EnableTelemetry",
+        Tag::End => "End",
+        Tag::Metadata(_) => "Metadata",
+        Tag::ImportAssets { .. } => "ImportAssets",
+        Tag::JpegTables(_) => "JpegTables",
+        Tag::NameCharacter(_) => "NameCharacter",
+        Tag::SetBackgroundColor(_) => "SetBackgroundColor",
+        Tag::SetTabIndex { .. } => "SetTabIndex",
+        Tag::SoundStreamBlock(_) => "SoundStreamBlock",
+        Tag::SoundStreamHead(_) => "SoundStreamHead",
+        Tag::SoundStreamHead2(_) => "SoundStreamHead2",
+        Tag::StartSound(_) => "StartSound",
+        Tag::StartSound2 { .. } => "StartSound2",
+        Tag::SymbolClass(_) => "SymbolClass",
+        Tag::PlaceObject(_) => "PlaceObject",
+        Tag::RemoveObject(_) => "RemoveObject",
+        Tag::VideoFrame(_) => "VideoFrame",
+        Tag::FileAttributes(_) => "FileAttributes",
+        Tag::FrameLabel(_) => "FrameLabel",
+        Tag::DefineSceneAndFrameLabelData(_) => "DefineSceneAndFrameLabelData",
+        Tag::ProductInfo(_) => "ProductInfo",
+        Tag::Unknown { .. } => "Unknown",
+    }
+}
@@ -0,0 +1,189 @@
+//! Plain text output, meant to be read by humans.
+
+use crate::model::{Abc, Block, Document, Instruction, Method, TagNode, Trait};
+use std::io::{self, Write};
+
+const INDENT: usize = 2;
+
+pub fn write_document(out: &mut impl Write, document: &Document) -> io::Result<()> {
+    let header = &document.header;
+    writeln!(
+        out,
+        "SWF version {}, {} compression, {}x{}, {} fps, {} frame(s), {} bytes uncompressed",
+        header.version,
+        header.compression,
+        header.width,
+        header.height,
+        header.frame_rate,
+        header.num_frames,
+        header.uncompressed_length
+    )?;
+    for tag in &document.tags {
+        write_tag(out, tag, 0)?;
+    }
+    Ok(())
+}
+
+fn write_tag(out: &mut impl Write, tag: &TagNode, indent: usize) -> io::Result<()> {
+    write!(out, "{:indent$}[{}] {}", "", tag.frame, tag.name)?;
+    for detail in &tag.details {
+        write!(out, " {detail}")?;
+    }
+    writeln!(out)?;
+
+    let indent = indent + INDENT;
+    for child in &tag.children {
+        write_tag(out, child, indent)?;
+    }
+    for block in &tag.actions {
+        write_block(out, block, indent)?;
+    }
+    if let Some(abc) = &tag.abc {
+        write_abc(out, abc, indent)?;
+    }
+    Ok(())
+}
+
+fn write_block(out: &mut impl Write, block: &Block, indent: usize) -> io::Result<()> {
+    writeln!(out, "{:indent$}{}:", "", block.name)?;
+    write_instructions(out, &block.instructions, indent + INDENT)
+}
+
+fn write_instructions(
+    out: &mut impl Write,
+    instructions: &[Instruction],
+    indent: usize,
+) -> io::Result<()> {
+    for instruction in instructions {
+        if let Some(label) = &instruction.label {
+            writeln!(out, "{:indent$}{label}:", "")?;
+        }
+        write!(
+            out,
+            "{:indent$}{:04X}  {}",
+            "",
+            instruction.offset,
+            instruction.op,
+            indent = indent + INDENT
+        )?;
+        if !instruction.operands.is_empty() {
+            write!(out, " {}", instruction.operands.join(", "))?;
+        }
+        writeln!(out)?;
+        for block in &instruction.blocks {
+            write_block(out, block, indent + INDENT * 2)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_abc(out: &mut impl Write, abc: &Abc, indent: usize) -> io::Result<()> {
+    write!(out, "{:indent$}abc", "")?;
+    if let Some(name) = &abc.name {
+        write!(out, " {name:?}")?;
+    }
+    writeln!(out, " version {}.{}", abc.major_version, abc.minor_version)?;
+
+    let indent = indent + INDENT;
+    for script in &abc.scripts {
+        writeln!(
+            out,
+            "{:indent$}script #{} init=method #{}",
+            "", script.index, script.init_method
+        )?;
+        write_traits(out, &script.traits, indent + INDENT)?;
+    }
+    for class in &abc.classes {
+        write!(
+            out,
+            "{:indent$}class #{} {} extends {}",
+            "", class.index, class.name, class.super_name
+        )?;
+        if !class.interfaces.is_empty() {
+            write!(out, " implements {}", class.interfaces.join(", "))?;
+        }
+        for flag in &class.flags {
+            write!(out, " {flag}")?;
+        }
+        if let Some(namespace) = &class.protected_namespace {
+            write!(out, " protected_ns={namespace}")?;
+        }
+        writeln!(out)?;
+        let indent = indent + INDENT;
+        writeln!(
+            out,
+            "{:indent$}instance init=method #{}",
+            "", class.instance_init
+        )?;
+        write_traits(out, &class.instance_traits, indent + INDENT)?;
+        writeln!(out, "{:indent$}class init=method #{}", "", class.class_init)?;
+        write_traits(out, &class.class_traits, indent + INDENT)?;
+    }
+    for method in &abc.methods {
+        write_method(out, method, indent)?;
+    }
+    Ok(())
+}
+
+fn write_traits(out: &mut impl Write, traits: &[Trait], indent: usize) -> io::Result<()> {
+    for trait_ in traits {
+        for metadata in &trait_.metadata {
+            writeln!(out, "{:indent$}{metadata}", "")?;
+        }
+        write!(out, "{:indent$}", "")?;
+        for flag in &trait_.flags {
+            write!(out, "{flag} ")?;
+        }
+        write!(out, "{} {}", trait_.kind, trait_.name)?;
+        if let Some(type_name) = &trait_.type_name {
+            write!(out, ":{type_name}")?;
+        }
+        if let Some(value) = &trait_.value {
+            write!(out, " = {value}")?;
+        }
+        if let Some(method) = trait_.method {
+            write!(out, " method #{method}")?;
+        }
+        if let Some(class) = trait_.class {
+            write!(out, " class #{class}")?;
+        }
+        writeln!(out, " id={}", trait_.id)?;
+    }
+    Ok(())
+}
+
+fn write_method(out: &mut impl Write, method: &Method, indent: usize) -> io::Result<()> {
+    write!(
+        out,
+        "{:indent$}method #{} {}",
+        "", method.index, method.signature
+    )?;
+    for flag in &method.flags {
+        write!(out, " {flag}")?;
+    }
+    writeln!(out)?;
+
+    let Some(body) = &method.body else {
+        return Ok(());
+    };
+    let indent = indent + INDENT;
+    writeln!(
+        out,
+        "{:indent$}max_stack={} num_locals={} init_scope_depth={} max_scope_depth={}",
+        "", body.max_stack, body.num_locals, body.init_scope_depth, body.max_scope_depth
+    )?;
+    write_traits(out, &body.traits, indent)?;
+    for exception in &body.exceptions {
+        writeln!(
+            out,
+            "{:indent$}try {}..{} catch {}:{} -> {}",
+            "",
+            exception.from,
+            exception.to,
+            exception.variable_name,
+            exception.type_name,
+            exception.target
+        )?;
+    }
+    write_instructions(out, &body.instructions, indent)
+}
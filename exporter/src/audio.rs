@@ -0,0 +1,92 @@
+use ruffle_core::backend::audio::{
+    swf, AudioBackend, AudioMixer, DecodeError, RegisterError, SoundHandle, SoundInstanceHandle,
+    SoundStreamInfo, SoundTransform,
+};
+use ruffle_core::impl_audio_mixer_backend;
+use std::io::{self, Write};
+
+/// An audio backend that doesn't play anything, but lets the exporter pull
+/// mixed samples from the movie in lockstep with `Player::run_frame`.
+pub struct ExporterAudioBackend {
+    mixer: AudioMixer,
+    frame_rate: f64,
+    frames_mixed: u64,
+    samples_mixed: u64,
+}
+
+impl ExporterAudioBackend {
+    pub const NUM_CHANNELS: u8 = 2;
+    pub const SAMPLE_RATE: u32 = 44100;
+
+    pub fn new() -> Self {
+        Self {
+            mixer: AudioMixer::new(Self::NUM_CHANNELS, Self::SAMPLE_RATE),
+            frame_rate: 1.0,
+            frames_mixed: 0,
+            samples_mixed: 0,
+        }
+    }
+
+    /// Mixes the audio of exactly one frame, returning interleaved stereo samples.
+    ///
+    /// The amount of samples is derived from the total number of frames mixed so far,
+    /// so that fractional samples per frame never accumulate into a drift.
+    pub fn mix_frame(&mut self) -> Vec<i16> {
+        self.frames_mixed += 1;
+        let total_samples =
+            (self.frames_mixed as f64 * Self::SAMPLE_RATE as f64 / self.frame_rate).round() as u64;
+        let num_samples = total_samples.saturating_sub(self.samples_mixed);
+        self.samples_mixed = total_samples;
+
+        let mut buffer = vec![0i16; num_samples as usize * Self::NUM_CHANNELS as usize];
+        self.mixer.mix::<i16>(&mut buffer);
+        buffer
+    }
+}
+
+impl Default for ExporterAudioBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioBackend for ExporterAudioBackend {
+    impl_audio_mixer_backend!(mixer);
+    fn play(&mut self) {}
+    fn pause(&mut self) {}
+
+    fn set_frame_rate(&mut self, frame_rate: f64) {
+        if frame_rate > 0.0 {
+            self.frame_rate = frame_rate;
+        }
+    }
+}
+
+/// Writes interleaved 16-bit stereo samples as a PCM WAV file.
+pub fn write_wav(mut writer: impl Write, samples: &[i16]) -> io::Result<()> {
+    let num_channels = ExporterAudioBackend::NUM_CHANNELS as u16;
+    let sample_rate = ExporterAudioBackend::SAMPLE_RATE;
+    let block_align = num_channels * 2;
+    let data_len = u32::try_from(samples.len() * 2)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Audio is too long for WAV"))?;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_len).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    // PCM
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&num_channels.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+    writer.write_all(&block_align.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())?;
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    writer.write_all(&bytes)?;
+    writer.flush()
+}
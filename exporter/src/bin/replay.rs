@@ -0,0 +1,8 @@
+use anyhow::Result;
+use clap::Parser;
+use exporter::{run_replay, ReplayOpt};
+
+fn main() -> Result<()> {
+    let opt: ReplayOpt = ReplayOpt::parse();
+    run_replay(opt)
+}
@@ -1,21 +1,28 @@
+mod audio;
+mod output;
 mod player_ext;
+mod renderer;
+mod replay;
 
 use anyhow::{anyhow, Result};
+use audio::{write_wav, ExporterAudioBackend};
 use clap::Parser;
 use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
+use output::{encode_animation, OutputFormat};
 use player_ext::PlayerExporterExt;
 use rayon::prelude::*;
+use renderer::{GraphicsOption, Renderer};
+pub use replay::{run_replay, ReplayOpt};
 use ruffle_core::limits::ExecutionLimit;
 use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::{Player, PlayerBuilder};
-use ruffle_render_wgpu::backend::{request_adapter_and_device, WgpuRenderBackend};
-use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
-use ruffle_render_wgpu::descriptors::Descriptors;
-use ruffle_render_wgpu::target::TextureTarget;
-use ruffle_render_wgpu::wgpu;
-use std::fs::create_dir_all;
-use std::io::{self, Write};
+use ruffle_core::{Player, PlayerBuilder, PlayerEvent, StageScaleMode};
+use ruffle_input_format::{automated_event_to_player_event, AutomatedEvent, InputInjector};
+use ruffle_render::backend::recording::RecordingBackend;
+use ruffle_render::quality::StageQuality;
+use ruffle_render_wgpu::clap::PowerPreference;
+use std::fs::{create_dir_all, File};
+use std::io::{self, BufWriter, Write};
 use std::num::NonZeroU32;
 use std::panic::catch_unwind;
 use std::path::{Path, PathBuf};
@@ -101,6 +108,15 @@ pub struct Opt {
     #[clap(long = "skipframes", default_value = "0")]
     skipframes: u32,
 
+    /// Format of the capture. Animated formats store all captured frames in a single file.
+    #[clap(long, default_value = "png")]
+    format: OutputFormat,
+
+    /// Also record the mixed audio of the captured frames, stored as a WAV file
+    /// next to the output (or inside it, when capturing multiple PNG frames).
+    #[clap(long, action)]
+    audio: bool,
+
     /// Don't show a progress bar
     #[clap(short, long, action)]
     silent: bool,
@@ -114,10 +130,34 @@ pub struct Opt {
     #[clap(long)]
     force_play: bool,
 
+    /// A JSON file of input events to inject while capturing, in the same format as the input of regression tests.
+    /// The events are injected after each frame runs, up to the next "Wait" event.
+    #[clap(long)]
+    input: Option<PathBuf>,
+
+    /// Record every call made to the renderer into the given file, which can be rendered again
+    /// with the `replay` tool. Only supported when exporting a single file.
+    #[clap(long)]
+    record_render: Option<PathBuf>,
+
+    /// A "flashvars" parameter to provide to the movie.
+    /// This can be repeated multiple times, for example -Pkey=value -Pfoo=bar.
+    #[clap(short = 'P', action = clap::ArgAction::Append)]
+    parameters: Vec<String>,
+
+    /// Quality of the movie.
+    #[clap(long, short)]
+    quality: Option<StageQuality>,
+
+    /// The scale mode of the stage.
+    #[clap(long)]
+    scale_mode: Option<StageScaleMode>,
+
     /// Type of graphics backend to use. Not all options may be supported by your current system.
-    /// Default will attempt to pick the most supported graphics backend.
+    /// Default will attempt to pick the most supported graphics backend, and falls back to
+    /// software rendering if no GPU is available.
     #[clap(long, short, default_value = "default")]
-    graphics: GraphicsBackend,
+    graphics: GraphicsOption,
 
     /// Power preference for the graphics device used. High power usage tends to prefer dedicated GPUs,
     /// whereas a low power usage tends prefer integrated GPUs.
@@ -129,17 +169,113 @@ pub struct Opt {
     skip_unsupported: bool,
 }
 
-/// Captures a screenshot. The resulting image uses straight alpha
+impl Opt {
+    fn parameters(&self) -> impl '_ + Iterator<Item = (String, String)> {
+        self.parameters.iter().map(|parameter| {
+            let mut split = parameter.splitn(2, '=');
+            if let (Some(key), Some(value)) = (split.next(), split.next()) {
+                (key.to_owned(), value.to_owned())
+            } else {
+                (parameter.clone(), "".to_string())
+            }
+        })
+    }
+}
+
+/// Everything captured from a single movie.
+struct Capture {
+    /// The captured frames. The images use straight alpha.
+    frames: Vec<RgbaImage>,
+
+    /// The frame rate of the movie at the end of the capture.
+    frame_rate: f64,
+
+    /// Interleaved 16-bit stereo samples of the captured frames, if requested.
+    audio: Option<Vec<i16>>,
+}
+
+impl Capture {
+    /// Saves the frames either as a single file, or as a directory of numbered PNG files.
+    fn save(&self, output: &Path, format: OutputFormat, single_file: bool) -> Result<()> {
+        if format.is_animated() {
+            let bytes = encode_animation(format, &self.frames, self.frame_rate)?;
+            std::fs::write(output, bytes)?;
+        } else if single_file {
+            self.frames.first().unwrap().save(output)?;
+        } else {
+            let digits = self.frames.len().to_string().len();
+            for (frame, image) in self.frames.iter().enumerate() {
+                let mut path: PathBuf = output.into();
+                path.push(format!("{frame:0digits$}.png"));
+                image.save(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn save_audio(&self, output: &Path, single_file: bool) -> Result<()> {
+        if let Some(audio) = &self.audio {
+            let path = if single_file {
+                output.with_extension("wav")
+            } else {
+                output.join("audio.wav")
+            };
+            write_wav(BufWriter::new(File::create(path)?), audio)?;
+        }
+        Ok(())
+    }
+}
+
+/// Input to give to the player after a frame runs.
+#[derive(Debug)]
+enum FrameInput {
+    Event(PlayerEvent),
+    SetClipboardText(String),
+}
+
+/// Reads the input to inject after the current frame, up to the next "Wait" event.
+fn next_frame_input(injector: &mut InputInjector) -> Result<Vec<FrameInput>> {
+    let mut inputs = Vec::new();
+    let mut invalid_event = None;
+    injector.next(|evt, _btns_down| {
+        if let AutomatedEvent::SetClipboardText { text } = evt {
+            inputs.push(FrameInput::SetClipboardText(text.to_owned()));
+            return;
+        }
+        match automated_event_to_player_event(evt) {
+            Ok(Some(event)) => inputs.push(FrameInput::Event(event)),
+            Ok(None) => {}
+            Err(e) => {
+                invalid_event.get_or_insert(e);
+            }
+        }
+    });
+    if let Some(e) = invalid_event {
+        return Err(anyhow!("Invalid event in input file: {e}"));
+    }
+    Ok(inputs)
+}
+
+/// Captures a screenshot (and audio, if requested) of the given movie.
 fn take_screenshot(
-    descriptors: Arc<Descriptors>,
+    renderer: &Renderer,
     swf_path: &Path,
-    frames: FrameSelection, // TODO Figure out a way to get framecount before calling take_screenshot, so that we can have accurate progress bars when using --frames all
-    skipframes: u32,
+    opt: &Opt,
     progress: &Option<ProgressBar>,
-    size: SizeOpt,
-    force_play: bool,
-) -> Result<Vec<RgbaImage>> {
-    let movie = SwfMovie::from_path(swf_path, None).map_err(|e| anyhow!(e.to_string()))?;
+) -> Result<Capture> {
+    // TODO Figure out a way to get framecount before calling take_screenshot, so that we can have accurate progress bars when using --frames all
+    let frames = opt.frames;
+    let skipframes = opt.skipframes;
+    let size = opt.size;
+
+    let mut movie = SwfMovie::from_path(swf_path, None).map_err(|e| anyhow!(e.to_string()))?;
+    movie.append_parameters(opt.parameters());
+
+    let mut injector = match &opt.input {
+        Some(path) => InputInjector::from_file(path)
+            .map_err(|e| anyhow!("Unable to read input file {:?}: {}", path, e))?,
+        None => InputInjector::empty(),
+    };
 
     let width = size
         .width
@@ -153,17 +289,27 @@ fn take_screenshot(
         .unwrap_or_else(|| movie.height().to_pixels());
     let height = (height * size.scale).round() as u32;
 
-    let target = TextureTarget::new(&descriptors.device, (width, height))
-        .map_err(|e| anyhow!(e.to_string()))?;
-    let player = PlayerBuilder::new()
-        .with_renderer(
-            WgpuRenderBackend::new(descriptors, target).map_err(|e| anyhow!(e.to_string()))?,
-        )
+    let mut backend = renderer.create_backend(width, height)?;
+    if let Some(path) = &opt.record_render {
+        let writer = BufWriter::new(File::create(path)?);
+        backend = Box::new(RecordingBackend::new(backend, Box::new(writer)));
+    }
+
+    let mut builder = PlayerBuilder::new()
+        .with_boxed_renderer(backend)
+        .with_audio(ExporterAudioBackend::new())
         .with_movie(movie)
-        .with_viewport_dimensions(width, height, size.scale)
-        .build();
+        .with_viewport_dimensions(width, height, size.scale);
+    if let Some(quality) = opt.quality {
+        builder = builder.with_quality(quality);
+    }
+    if let Some(scale_mode) = opt.scale_mode {
+        builder = builder.with_scale_mode(scale_mode, false);
+    }
+    let player = builder.build();
 
     let mut result = Vec::new();
+    let mut audio = Vec::new();
     let totalframes = frames.total_frames(&player, skipframes);
 
     for i in 0..totalframes {
@@ -175,14 +321,26 @@ fn take_screenshot(
             ));
         }
 
-        if force_play {
+        if opt.force_play {
             player.force_root_clip_play();
         }
 
         player.lock().unwrap().preload(&mut ExecutionLimit::none());
 
         player.lock().unwrap().run_frame();
+        for input in next_frame_input(&mut injector)? {
+            let mut player = player.lock().unwrap();
+            match input {
+                FrameInput::Event(event) => player.handle_event(event),
+                FrameInput::SetClipboardText(text) => player.ui_mut().set_clipboard_content(text),
+            }
+        }
+        // Sounds keep playing during skipped frames, they just don't end up in the capture.
+        let frame_audio = player.mix_audio_frame();
         if i >= skipframes {
+            if opt.audio {
+                audio.extend_from_slice(&frame_audio);
+            }
             let image = || {
                 player.lock().unwrap().render();
                 player.capture_frame()
@@ -207,7 +365,13 @@ fn take_screenshot(
             }
         }
     }
-    Ok(result)
+
+    let frame_rate = player.lock().unwrap().frame_rate();
+    Ok(Capture {
+        frames: result,
+        frame_rate,
+        audio: opt.audio.then_some(audio),
+    })
 }
 
 fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
@@ -240,18 +404,24 @@ fn find_files(root: &Path, with_progress: bool) -> Vec<DirEntry> {
     results
 }
 
-fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
+fn capture_single_swf(renderer: &Renderer, opt: &Opt) -> Result<()> {
+    let to_stdout = opt.output_path == Some(PathBuf::from("-"));
+    if to_stdout && opt.audio {
+        return Err(anyhow!("Cannot write audio when the output is stdout"));
+    }
+
     let is_single_frame = opt.frames.is_single_frame();
+    let is_single_file = is_single_frame || opt.format.is_animated();
     let output = opt.output_path.clone().unwrap_or_else(|| {
         let mut result = PathBuf::new();
         result.set_file_name(opt.swf.file_stem().unwrap());
-        if is_single_frame {
-            result.set_extension("png");
+        if is_single_file {
+            result.set_extension(opt.format.extension());
         }
         result
     });
 
-    if !is_single_frame {
+    if !is_single_file {
         let _ = create_dir_all(&output);
     }
 
@@ -272,40 +442,31 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
         None
     };
 
-    let frames = take_screenshot(
-        descriptors,
-        &opt.swf,
-        opt.frames,
-        opt.skipframes,
-        &progress,
-        opt.size,
-        opt.force_play,
-    )?;
+    let capture = take_screenshot(renderer, &opt.swf, opt, &progress)?;
+    let frames = &capture.frames;
 
     if let Some(progress) = &progress {
         progress.set_message(opt.swf.file_stem().unwrap().to_string_lossy().into_owned());
     }
 
-    if is_single_frame {
-        let image = frames.first().unwrap();
-        if opt.output_path == Some(PathBuf::from("-")) {
+    if to_stdout && is_single_file {
+        let bytes = if opt.format.is_animated() {
+            encode_animation(opt.format, frames, capture.frame_rate)?
+        } else {
             let mut bytes: Vec<u8> = Vec::new();
-            image
+            frames
+                .first()
+                .unwrap()
                 .write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)
                 .expect("Encoding failed");
-            io::stdout()
-                .write_all(bytes.as_slice())
-                .expect("Writing to stdout failed");
-        } else {
-            image.save(&output)?;
-        }
+            bytes
+        };
+        io::stdout()
+            .write_all(bytes.as_slice())
+            .expect("Writing to stdout failed");
     } else {
-        let digits = frames.len().to_string().len();
-        for (frame, image) in frames.iter().enumerate() {
-            let mut path: PathBuf = (&output).into();
-            path.push(format!("{frame:0digits$}.png"));
-            image.save(&path)?;
-        }
+        capture.save(&output, opt.format, is_single_file)?;
+        capture.save_audio(&output, is_single_file)?;
     }
 
     let message = if frames.len() == 1 {
@@ -338,7 +499,7 @@ fn capture_single_swf(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
     Ok(())
 }
 
-fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()> {
+fn capture_multiple_swfs(renderer: &Renderer, opt: &Opt) -> Result<()> {
     let output = opt.output_path.clone().unwrap();
     let files = find_files(&opt.swf, !opt.silent);
 
@@ -369,41 +530,28 @@ fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()>
                     .into_owned(),
             );
         }
-        if let Ok(frames) = take_screenshot(
-            descriptors.clone(),
-            file.path(),
-            opt.frames,
-            opt.skipframes,
-            &progress,
-            opt.size,
-            opt.force_play,
-        ) {
+        if let Ok(capture) = take_screenshot(renderer, file.path(), opt, &progress) {
             let mut relative_path = file
                 .path()
                 .strip_prefix(&opt.swf)
                 .unwrap_or_else(|_| file.path())
                 .to_path_buf();
 
-            if frames.len() == 1 {
-                let mut destination: PathBuf = (&output).into();
-                relative_path.set_extension("png");
+            let mut destination: PathBuf = (&output).into();
+            let is_single_file = capture.frames.len() == 1 || opt.format.is_animated();
+            if is_single_file {
+                relative_path.set_extension(opt.format.extension());
                 destination.push(relative_path);
                 if let Some(parent) = destination.parent() {
                     let _ = create_dir_all(parent);
                 }
-                frames.first().unwrap().save(&destination)?;
             } else {
-                let mut parent: PathBuf = (&output).into();
                 relative_path.set_extension("");
-                parent.push(&relative_path);
-                let _ = create_dir_all(&parent);
-                let digits = frames.len().to_string().len();
-                for (frame, image) in frames.iter().enumerate() {
-                    let mut destination = parent.clone();
-                    destination.push(format!("{frame:0digits$}.png"));
-                    image.save(&destination)?;
-                }
+                destination.push(&relative_path);
+                let _ = create_dir_all(&destination);
             }
+            capture.save(&destination, opt.format, is_single_file)?;
+            capture.save_audio(&destination, is_single_file)?;
         }
 
         Ok(())
@@ -438,29 +586,21 @@ fn capture_multiple_swfs(descriptors: Arc<Descriptors>, opt: &Opt) -> Result<()>
 }
 
 pub fn run_main(opt: Opt) -> Result<()> {
-    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-        backends: opt.graphics.into(),
-        ..Default::default()
-    });
-    let (adapter, device, queue) = futures::executor::block_on(request_adapter_and_device(
-        opt.graphics.into(),
-        &instance,
-        None,
-        opt.power.into(),
-    ))
-    .map_err(|e| anyhow!(e.to_string()))?;
-
-    let descriptors = Arc::new(Descriptors::new(instance, adapter, device, queue));
+    let renderer = Renderer::new(opt.graphics, opt.power)?;
 
     if opt.swf.is_file() {
-        capture_single_swf(descriptors, &opt)?;
+        capture_single_swf(&renderer, &opt)?;
     } else if !opt.swf.is_dir() {
         return Err(anyhow!(
             "Not a file or directory: {}",
             opt.swf.to_string_lossy()
         ));
+    } else if opt.record_render.is_some() {
+        return Err(anyhow!(
+            "Recording the renderer is only supported when exporting a single file."
+        ));
     } else if opt.output_path.is_some() {
-        capture_multiple_swfs(descriptors, &opt)?;
+        capture_multiple_swfs(&renderer, &opt)?;
     } else {
         return Err(anyhow!(
             "Output directory is required when exporting multiple files."
@@ -469,3 +609,101 @@ pub fn run_main(opt: Opt) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Opt {
+        Opt::try_parse_from(["exporter"].iter().chain(args)).expect("valid arguments")
+    }
+
+    #[test]
+    fn defaults() {
+        let opt = parse(&["movie.swf"]);
+        assert_eq!(opt.quality, None);
+        assert_eq!(opt.scale_mode, None);
+        assert_eq!(opt.input, None);
+        assert_eq!(opt.parameters().count(), 0);
+    }
+
+    #[test]
+    fn quality_and_scale_mode() {
+        let opt = parse(&["movie.swf", "--quality", "low", "--scale-mode", "no-border"]);
+        assert_eq!(opt.quality, Some(StageQuality::Low));
+        assert_eq!(opt.scale_mode, Some(StageScaleMode::NoBorder));
+
+        let opt = parse(&[
+            "movie.swf",
+            "-q",
+            "high8x8-linear",
+            "--scale-mode",
+            "exact-fit",
+        ]);
+        assert_eq!(opt.quality, Some(StageQuality::High8x8Linear));
+        assert_eq!(opt.scale_mode, Some(StageScaleMode::ExactFit));
+
+        assert!(Opt::try_parse_from(["exporter", "movie.swf", "--quality", "ultra"]).is_err());
+        assert!(Opt::try_parse_from(["exporter", "movie.swf", "--scale-mode", "fill"]).is_err());
+    }
+
+    #[test]
+    fn flashvars() {
+        let opt = parse(&[
+            "movie.swf",
+            "-Pkey=value",
+            "-P",
+            "url=http://example.com/?a=b",
+            "-Pflag",
+        ]);
+        assert_eq!(
+            opt.parameters().collect::<Vec<_>>(),
+            vec![
+                ("key".to_string(), "value".to_string()),
+                ("url".to_string(), "http://example.com/?a=b".to_string()),
+                ("flag".to_string(), "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn input_script() {
+        let script = br#"[
+            { "type": "MouseMove", "pos": [10.0, 20.0] },
+            { "type": "SetClipboardText", "text": "hello" },
+            { "type": "Wait" },
+            { "type": "MouseDown", "pos": [10.0, 20.0], "btn": "Left" },
+            { "type": "MouseUp", "pos": [10.0, 20.0], "btn": "Left" }
+        ]"#;
+        let mut injector = InputInjector::from_reader(&script[..]).expect("valid input script");
+
+        let first = next_frame_input(&mut injector).unwrap();
+        assert!(matches!(
+            first.as_slice(),
+            [
+                FrameInput::Event(PlayerEvent::MouseMove { x, y }),
+                FrameInput::SetClipboardText(text),
+            ] if *x == 10.0 && *y == 20.0 && text == "hello"
+        ));
+
+        let second = next_frame_input(&mut injector).unwrap();
+        assert!(matches!(
+            second.as_slice(),
+            [
+                FrameInput::Event(PlayerEvent::MouseDown { index: Some(0), .. }),
+                FrameInput::Event(PlayerEvent::MouseUp { .. }),
+            ]
+        ));
+
+        assert!(next_frame_input(&mut injector).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_input_script() {
+        let script = br#"[{ "type": "MouseWheel", "lines": 1.0, "pixels": 1.0 }]"#;
+        let mut injector = InputInjector::from_reader(&script[..]).expect("valid JSON");
+        assert!(next_frame_input(&mut injector).is_err());
+
+        assert!(InputInjector::from_reader(&br#"[{ "type": "Jump" }]"#[..]).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Result};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum OutputFormat {
+    /// One PNG image per captured frame
+    #[default]
+    Png,
+    /// A single animated PNG containing every captured frame
+    Apng,
+    /// A single animated GIF containing every captured frame
+    Gif,
+}
+
+impl OutputFormat {
+    /// Whether all frames are stored in a single file.
+    pub fn is_animated(self) -> bool {
+        match self {
+            OutputFormat::Png => false,
+            OutputFormat::Apng | OutputFormat::Gif => true,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png | OutputFormat::Apng => "png",
+            OutputFormat::Gif => "gif",
+        }
+    }
+}
+
+/// Encodes the given frames into a single animated image, played back at `frame_rate`.
+pub fn encode_animation(
+    format: OutputFormat,
+    frames: &[RgbaImage],
+    frame_rate: f64,
+) -> Result<Vec<u8>> {
+    let first = frames
+        .first()
+        .ok_or_else(|| anyhow!("No frames were captured"))?;
+
+    // SWF frame rates are 8.8 fixed point, so this is exact for every valid movie.
+    let delay_denominator = (frame_rate * 256.0).round().clamp(1.0, u16::MAX as f64) as u16;
+    let delay_numerator = 256;
+
+    let mut bytes = Vec::new();
+    match format {
+        OutputFormat::Png => {
+            return Err(anyhow!("PNG output is not an animated format"));
+        }
+        OutputFormat::Apng => {
+            let mut encoder = png::Encoder::new(&mut bytes, first.width(), first.height());
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_animated(frames.len() as u32, 0)?;
+            encoder.set_frame_delay(delay_numerator, delay_denominator)?;
+            let mut writer = encoder.write_header()?;
+            for frame in frames {
+                writer.write_image_data(frame.as_raw())?;
+            }
+            writer.finish()?;
+        }
+        OutputFormat::Gif => {
+            let delay =
+                Delay::from_numer_denom_ms(1000 * delay_numerator as u32, delay_denominator as u32);
+            let mut encoder = GifEncoder::new(&mut bytes);
+            encoder.set_repeat(Repeat::Infinite)?;
+            encoder.encode_frames(
+                frames
+                    .iter()
+                    .map(|frame| Frame::from_parts(frame.clone(), 0, 0, delay)),
+            )?;
+        }
+    }
+    Ok(bytes)
+}
@@ -4,11 +4,15 @@ use std::{
 };
 
 use ruffle_core::Player;
-use ruffle_render_wgpu::{backend::WgpuRenderBackend, target::TextureTarget};
+
+use crate::audio::ExporterAudioBackend;
+use crate::renderer::capture_frame;
 
 pub trait PlayerExporterExt {
     fn capture_frame(&self) -> Option<image::RgbaImage>;
 
+    fn mix_audio_frame(&self) -> Vec<i16>;
+
     fn header_frames(&self) -> u16;
 
     fn force_root_clip_play(&self);
@@ -17,10 +21,13 @@ pub trait PlayerExporterExt {
 impl PlayerExporterExt for Arc<Mutex<Player>> {
     fn capture_frame(&self) -> Option<image::RgbaImage> {
         let mut player = self.lock().unwrap();
-        let renderer =
-            <dyn Any>::downcast_mut::<WgpuRenderBackend<TextureTarget>>(player.renderer_mut())
-                .unwrap();
-        renderer.capture_frame()
+        capture_frame(player.renderer_mut())
+    }
+
+    fn mix_audio_frame(&self) -> Vec<i16> {
+        let mut player = self.lock().unwrap();
+        let audio = <dyn Any>::downcast_mut::<ExporterAudioBackend>(player.audio_mut()).unwrap();
+        audio.mix_frame()
     }
 
     fn header_frames(&self) -> u16 {
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use ruffle_render::backend::recording::RecordingBackend;
+use ruffle_render::backend::RenderBackend;
+use ruffle_render_software::SoftwareRenderBackend;
+use ruffle_render_wgpu::backend::{request_adapter_and_device, WgpuRenderBackend};
+use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
+use ruffle_render_wgpu::descriptors::Descriptors;
+use ruffle_render_wgpu::target::TextureTarget;
+use ruffle_render_wgpu::wgpu;
+use std::any::Any;
+use std::sync::Arc;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum GraphicsOption {
+    /// Pick the most supported GPU backend, or render on the CPU if there is no GPU
+    #[default]
+    Default,
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+    /// Render on the CPU, without requiring a GPU
+    Software,
+}
+
+impl GraphicsOption {
+    fn wgpu_backend(self) -> Option<GraphicsBackend> {
+        match self {
+            GraphicsOption::Default => Some(GraphicsBackend::Default),
+            GraphicsOption::Vulkan => Some(GraphicsBackend::Vulkan),
+            GraphicsOption::Metal => Some(GraphicsBackend::Metal),
+            GraphicsOption::Dx12 => Some(GraphicsBackend::Dx12),
+            GraphicsOption::Gl => Some(GraphicsBackend::Gl),
+            GraphicsOption::Software => None,
+        }
+    }
+}
+
+/// The device that every exported movie is rendered with.
+pub enum Renderer {
+    Wgpu(Arc<Descriptors>),
+    Software,
+}
+
+impl Renderer {
+    pub fn new(graphics: GraphicsOption, power: PowerPreference) -> Result<Self> {
+        let Some(backend) = graphics.wgpu_backend() else {
+            return Ok(Renderer::Software);
+        };
+
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: backend.into(),
+            ..Default::default()
+        });
+        let result = futures::executor::block_on(request_adapter_and_device(
+            backend.into(),
+            &instance,
+            None,
+            power.into(),
+        ));
+        match result {
+            Ok((adapter, device, queue)) => Ok(Renderer::Wgpu(Arc::new(Descriptors::new(
+                instance, adapter, device, queue,
+            )))),
+            Err(e) if graphics == GraphicsOption::Default => {
+                tracing::warn!("{e}, falling back to software rendering");
+                Ok(Renderer::Software)
+            }
+            Err(e) => Err(anyhow!(e.to_string())),
+        }
+    }
+
+    pub fn create_backend(&self, width: u32, height: u32) -> Result<Box<dyn RenderBackend>> {
+        match self {
+            Renderer::Wgpu(descriptors) => {
+                let target = TextureTarget::new(&descriptors.device, (width, height))
+                    .map_err(|e| anyhow!(e.to_string()))?;
+                let backend = WgpuRenderBackend::new(descriptors.clone(), target)
+                    .map_err(|e| anyhow!(e.to_string()))?;
+                Ok(Box::new(backend))
+            }
+            Renderer::Software => Ok(Box::new(SoftwareRenderBackend::new(width, height))),
+        }
+    }
+}
+
+/// Reads back the last frame rendered by a backend created by `Renderer::create_backend`.
+/// The image uses straight alpha.
+pub fn capture_frame(renderer: &mut dyn RenderBackend) -> Option<image::RgbaImage> {
+    if let Some(renderer) = <dyn Any>::downcast_mut::<RecordingBackend>(renderer) {
+        return capture_frame(renderer.inner_mut());
+    }
+    if let Some(renderer) = <dyn Any>::downcast_ref::<SoftwareRenderBackend>(renderer) {
+        return renderer.capture_frame();
+    }
+    let renderer = <dyn Any>::downcast_mut::<WgpuRenderBackend<TextureTarget>>(renderer)?;
+    renderer.capture_frame()
+}
@@ -0,0 +1,282 @@
+use crate::renderer::{capture_frame, GraphicsOption, Renderer};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use image::RgbaImage;
+use ruffle_render::backend::recording::{read_recording, RecordedEvent, Replayer};
+use ruffle_render::backend::RenderBackend;
+use ruffle_render_wgpu::clap::PowerPreference;
+use std::fs::{create_dir_all, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug)]
+#[clap(name = "Ruffle Render Replay", author, version)]
+pub struct ReplayOpt {
+    /// A recording of the renderer, as made with the `--record-render` option of the exporter
+    #[clap(name = "recording")]
+    recording: PathBuf,
+
+    /// The directory to store the rendered frames in, or the file to store the frame in when using --frame.
+    /// The default value is the name of the recording.
+    #[clap(name = "output")]
+    output_path: Option<PathBuf>,
+
+    /// Only save the given frame (starting from 0), instead of every frame.
+    #[clap(long)]
+    frame: Option<usize>,
+
+    /// Type of graphics backend to use. Not all options may be supported by your current system.
+    /// Default will attempt to pick the most supported graphics backend, and falls back to
+    /// software rendering if no GPU is available.
+    #[clap(long, short, default_value = "default")]
+    graphics: GraphicsOption,
+
+    /// Power preference for the graphics device used. High power usage tends to prefer dedicated GPUs,
+    /// whereas a low power usage tends prefer integrated GPUs.
+    #[clap(long, short, default_value = "high")]
+    power: PowerPreference,
+
+    /// Also render the recording with another graphics backend, and compare the saved frames.
+    /// An image of the differences is saved next to every frame that doesn't match.
+    #[clap(long)]
+    compare: Option<GraphicsOption>,
+
+    /// The largest difference in any channel of a pixel that --compare ignores.
+    #[clap(long, default_value = "0")]
+    tolerance: u8,
+}
+
+/// A backend that the recording is replayed with.
+struct ReplayTarget {
+    backend: Box<dyn RenderBackend>,
+    replayer: Replayer,
+}
+
+impl ReplayTarget {
+    fn new(
+        graphics: GraphicsOption,
+        power: PowerPreference,
+        width: u32,
+        height: u32,
+    ) -> Result<Self> {
+        let renderer = Renderer::new(graphics, power)?;
+        Ok(Self {
+            backend: renderer.create_backend(width, height)?,
+            replayer: Replayer::new(),
+        })
+    }
+
+    fn replay(&mut self, event: &RecordedEvent) -> Result<()> {
+        self.replayer
+            .replay(&mut *self.backend, event)
+            .map_err(|e| anyhow!(e.to_string()))
+    }
+
+    fn capture(&mut self, frame: usize) -> Result<RgbaImage> {
+        capture_frame(&mut *self.backend)
+            .ok_or_else(|| anyhow!("Unable to capture frame {}", frame))
+    }
+}
+
+/// The pixels that differ between two frames by more than a tolerance.
+struct FrameDiff {
+    outliers: usize,
+    max_difference: u8,
+    /// The absolute difference of every channel, with an opaque alpha channel.
+    image: RgbaImage,
+}
+
+impl FrameDiff {
+    fn new(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> Result<Self> {
+        if expected.dimensions() != actual.dimensions() {
+            return Err(anyhow!(
+                "The frames have different sizes: {:?} and {:?}",
+                expected.dimensions(),
+                actual.dimensions()
+            ));
+        }
+
+        let mut outliers = 0;
+        let mut max_difference = 0;
+        let image = RgbaImage::from_fn(expected.width(), expected.height(), |x, y| {
+            let expected = expected.get_pixel(x, y);
+            let actual = actual.get_pixel(x, y);
+            let mut difference = [0, 0, 0, 255];
+            let mut is_outlier = false;
+            for channel in 0..4 {
+                let channel_difference = expected[channel].abs_diff(actual[channel]);
+                max_difference = max_difference.max(channel_difference);
+                is_outlier |= channel_difference > tolerance;
+                if channel < 3 {
+                    difference[channel] = channel_difference;
+                } else {
+                    // Differences in alpha alone would be invisible otherwise.
+                    difference[0] = difference[0].max(channel_difference);
+                    difference[1] = difference[1].max(channel_difference);
+                    difference[2] = difference[2].max(channel_difference);
+                }
+            }
+            if is_outlier {
+                outliers += 1;
+            }
+            image::Rgba(difference)
+        });
+        Ok(Self {
+            outliers,
+            max_difference,
+            image,
+        })
+    }
+}
+
+/// The path of the image of the differences of a frame saved to `path`.
+fn diff_path(path: &Path) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(".diff.png");
+    path.with_file_name(name)
+}
+
+/// Renders the frames of a recording again, without running the movie.
+pub fn run_replay(opt: ReplayOpt) -> Result<()> {
+    let events = read_recording(BufReader::new(File::open(&opt.recording)?))?;
+    let dimensions = events
+        .iter()
+        .find_map(|event| match event {
+            RecordedEvent::SetViewportDimensions(dimensions) => Some(*dimensions),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("The recording doesn't contain the viewport dimensions"))?;
+
+    let mut target =
+        ReplayTarget::new(opt.graphics, opt.power, dimensions.width, dimensions.height)?;
+    let mut compared = opt
+        .compare
+        .map(|graphics| ReplayTarget::new(graphics, opt.power, dimensions.width, dimensions.height))
+        .transpose()?;
+
+    let output = opt.output_path.clone().unwrap_or_else(|| {
+        let mut result = PathBuf::new();
+        result.set_file_name(opt.recording.file_stem().unwrap());
+        if opt.frame.is_some() {
+            result.set_extension("png");
+        }
+        result
+    });
+    if opt.frame.is_none() {
+        create_dir_all(&output)?;
+    }
+
+    let total_frames = events
+        .iter()
+        .filter(|event| matches!(event, RecordedEvent::SubmitFrame { .. }))
+        .count();
+    let digits = total_frames.to_string().len();
+    let mut frame = 0;
+    let mut mismatched_frames = 0;
+    for event in &events {
+        target.replay(event)?;
+        if let Some(compared) = &mut compared {
+            compared.replay(event)?;
+        }
+        if !matches!(event, RecordedEvent::SubmitFrame { .. }) {
+            continue;
+        }
+
+        if opt.frame.is_none_or(|selected| selected == frame) {
+            let image = target.capture(frame)?;
+            let path = if opt.frame.is_some() {
+                output.clone()
+            } else {
+                output.join(format!("{frame:0digits$}.png"))
+            };
+            image.save(&path)?;
+
+            if let Some(compared) = &mut compared {
+                let diff = FrameDiff::new(&image, &compared.capture(frame)?, opt.tolerance)?;
+                if diff.outliers > 0 {
+                    mismatched_frames += 1;
+                    diff.image.save(diff_path(&path))?;
+                    println!(
+                        "Frame {} has {} pixels that differ, by up to {}",
+                        frame, diff.outliers, diff.max_difference
+                    );
+                }
+            }
+
+            if opt.frame.is_some() {
+                println!("Saved frame {} to {}", frame, output.to_string_lossy());
+                break;
+            }
+        }
+        frame += 1;
+    }
+
+    if let Some(selected) = opt.frame {
+        if selected >= total_frames {
+            return Err(anyhow!("The recording only contains {} frames", frame));
+        }
+    } else {
+        println!("Saved {} frames to {}", frame, output.to_string_lossy());
+    }
+    if mismatched_frames > 0 {
+        return Err(anyhow!(
+            "{} frames differ between the {:?} and {:?} backends",
+            mismatched_frames,
+            opt.graphics,
+            opt.compare.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_frames_match() {
+        let image = RgbaImage::from_pixel(2, 2, image::Rgba([10, 20, 30, 255]));
+        let diff = FrameDiff::new(&image, &image, 0).expect("same size");
+        assert_eq!(diff.outliers, 0);
+        assert_eq!(diff.max_difference, 0);
+    }
+
+    #[test]
+    fn differences_above_the_tolerance_are_outliers() {
+        let expected = RgbaImage::from_pixel(2, 1, image::Rgba([100, 100, 100, 255]));
+        let mut actual = expected.clone();
+        actual.put_pixel(0, 0, image::Rgba([102, 100, 100, 255]));
+        actual.put_pixel(1, 0, image::Rgba([100, 100, 100, 200]));
+
+        let diff = FrameDiff::new(&expected, &actual, 2).expect("same size");
+        assert_eq!(diff.outliers, 1);
+        assert_eq!(diff.max_difference, 55);
+        assert_eq!(diff.image.get_pixel(0, 0).0, [2, 0, 0, 255]);
+        assert_eq!(diff.image.get_pixel(1, 0).0, [55, 55, 55, 255]);
+
+        assert!(FrameDiff::new(&expected, &RgbaImage::new(1, 1), 2).is_err());
+    }
+
+    #[test]
+    fn diff_is_saved_next_to_the_frame() {
+        assert_eq!(
+            diff_path(Path::new("movie/07.png")),
+            PathBuf::from("movie/07.diff.png")
+        );
+    }
+
+    #[test]
+    fn parse_compare() {
+        let opt = ReplayOpt::try_parse_from([
+            "replay",
+            "movie.rec",
+            "--compare",
+            "software",
+            "--tolerance",
+            "3",
+        ])
+        .expect("valid arguments");
+        assert_eq!(opt.compare, Some(GraphicsOption::Software));
+        assert_eq!(opt.tolerance, 3);
+    }
+}
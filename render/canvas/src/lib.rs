@@ -3,8 +3,8 @@
 #![allow(clippy::arc_with_non_send_sync)]
 
 use ruffle_render::backend::{
-    BitmapCacheEntry, Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget,
-    RenderBackend, ShapeHandle, ShapeHandleImpl, ViewportDimensions,
+    BitmapCacheEntry, Context3D, Context3DProfile, RenderBackend, ShapeHandle, ShapeHandleImpl,
+    ViewportDimensions,
 };
 use ruffle_render::bitmap::{
     Bitmap, BitmapHandle, BitmapHandleImpl, BitmapSource, PixelRegion, PixelSnapping, RgbaBufRead,
@@ -561,22 +561,6 @@ impl RenderBackend for WebCanvasRenderBackend {
 
     fn set_quality(&mut self, _quality: StageQuality) {}
 
-    fn compile_pixelbender_shader(
-        &mut self,
-        _shader: ruffle_render::pixel_bender::PixelBenderShader,
-    ) -> Result<ruffle_render::pixel_bender::PixelBenderShaderHandle, Error> {
-        Err(Error::Unimplemented("compile_pixelbender_shader".into()))
-    }
-
-    fn run_pixelbender_shader(
-        &mut self,
-        _handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
-        _arguments: &[ruffle_render::pixel_bender_support::PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
-    ) -> Result<PixelBenderOutput, Error> {
-        Err(Error::Unimplemented("run_pixelbender_shader".into()))
-    }
-
     fn resolve_sync_handle(
         &mut self,
         _handle: Box<dyn SyncHandle>,
@@ -1009,6 +993,9 @@ fn swf_shape_to_canvas_commands(
                     fill_style,
                 });
             }
+            DrawPath::ShaderFill { .. } => {
+                log::warn!("Shader fills are not supported by the canvas renderer");
+            }
             DrawPath::Stroke {
                 commands,
                 style,
@@ -15,12 +15,13 @@ use num_traits::FromPrimitive;
 
 use crate::varying::VaryingRegisters;
 use crate::{
-    types::*, Error, ShaderType, VertexAttributeFormat, MAX_TEXTURES, MAX_VERTEX_ATTRIBUTES,
-    SHADER_ENTRY_POINT,
+    types::*, Error, ShaderType, VertexAttributeFormat, MAX_FRAGMENT_PROGRAM_CONSTANTS,
+    MAX_TEXTURES, MAX_VERTEX_ATTRIBUTES, MAX_VERTEX_PROGRAM_CONSTANTS, SHADER_ENTRY_POINT,
 };
 
-const VERTEX_PROGRAM_CONSTANTS: u64 = 128;
-const FRAGMENT_PROGRAM_CONSTANTS: u64 = 28;
+/// The number of color outputs (`oc0` to `oc3`) a fragment shader can write to.
+/// Only AGAL version 2 and later allow writing to anything other than `oc0`.
+pub const MAX_COLOR_OUTPUTS: usize = 4;
 
 pub const TEXTURE_SAMPLER_START_BIND_INDEX: u32 = 2;
 pub const TEXTURE_START_BIND_INDEX: u32 = 10;
@@ -103,6 +104,30 @@ pub(crate) struct NagaBuilder<'a> {
     // A stack of if/else blocks, using to push statements
     // into the correct block.
     blocks: Vec<BlockStackEntry>,
+
+    // Whenever a fragment shader writes to one of the additional
+    // color outputs (`oc1` to `oc3`) for the first time, we create
+    // a new local variable and add a member to the return type.
+    // The main color output (`oc0`) is always stored in `dest`.
+    pub(crate) color_outputs: [Option<OutputRegister>; MAX_COLOR_OUTPUTS],
+
+    // The fragment depth output (`fd`), created the first time
+    // a fragment shader writes to it.
+    pub(crate) depth_output: Option<OutputRegister>,
+
+    // An `Expression::FunctionArgument` for the instance index,
+    // created the first time a vertex shader reads from `iid`.
+    instance_id: Option<Handle<Expression>>,
+}
+
+/// An output register other than the 'main' output, which is returned
+/// as an additional member of the function return type.
+#[derive(Copy, Clone)]
+pub(crate) struct OutputRegister {
+    // A Pointer to a `vec4f` local variable holding the value we're going to return.
+    pub(crate) pointer: Handle<Expression>,
+    // The index of the corresponding member in the function return type.
+    pub(crate) output_struct_index: usize,
 }
 
 /// Handles 'if' and 'else' blocks in AGAL bytecode.
@@ -254,6 +279,27 @@ pub struct ShaderConfig<'a> {
 pub enum AgalVersion {
     Agal1,
     Agal2,
+    Agal3,
+}
+
+impl AgalVersion {
+    /// The number of program constants available to a shader of this version
+    pub fn num_constants(&self, shader_type: &ShaderType) -> u32 {
+        match (self, shader_type) {
+            (AgalVersion::Agal1, ShaderType::Vertex) => 128,
+            (AgalVersion::Agal1, ShaderType::Fragment) => 28,
+            (_, ShaderType::Vertex) => MAX_VERTEX_PROGRAM_CONSTANTS as u32,
+            (_, ShaderType::Fragment) => MAX_FRAGMENT_PROGRAM_CONSTANTS as u32,
+        }
+    }
+
+    /// The number of temporary registers available to a shader of this version
+    pub fn num_temporaries(&self) -> usize {
+        match self {
+            AgalVersion::Agal1 => 8,
+            AgalVersion::Agal2 | AgalVersion::Agal3 => 26,
+        }
+    }
 }
 
 struct ParsedBytecode {
@@ -277,6 +323,7 @@ impl<'a> NagaBuilder<'a> {
         let version = match version {
             1 => AgalVersion::Agal1,
             2 => AgalVersion::Agal2,
+            3 => AgalVersion::Agal3,
             _ => return Err(Error::InvalidVersion(version)),
         };
 
@@ -572,10 +619,11 @@ impl<'a> NagaBuilder<'a> {
                         inner: TypeInner::Array {
                             base: vec4f,
                             size: ArraySize::Constant(
-                                NonZeroU32::new(match shader_config.shader_type {
-                                    ShaderType::Vertex => VERTEX_PROGRAM_CONSTANTS as u32,
-                                    ShaderType::Fragment => FRAGMENT_PROGRAM_CONSTANTS as u32,
-                                })
+                                NonZeroU32::new(
+                                    shader_config
+                                        .version
+                                        .num_constants(&shader_config.shader_type),
+                                )
                                 .unwrap(),
                             ),
                             stride: std::mem::size_of::<f32>() as u32 * 4,
@@ -593,11 +641,7 @@ impl<'a> NagaBuilder<'a> {
             Span::UNDEFINED,
         );
 
-        // FIXME - expose this to the wgpu code
-        let num_temporaries = match shader_config.version {
-            AgalVersion::Agal1 => 8,
-            AgalVersion::Agal2 => 26,
-        };
+        let num_temporaries = shader_config.version.num_temporaries();
 
         NagaBuilder {
             module,
@@ -619,6 +663,9 @@ impl<'a> NagaBuilder<'a> {
             image2d,
             imagecube,
             blocks: vec![BlockStackEntry::Normal(Block::new())],
+            color_outputs: [None; MAX_COLOR_OUTPUTS],
+            depth_output: None,
+            instance_id: None,
         }
     }
 
@@ -660,6 +707,133 @@ impl<'a> NagaBuilder<'a> {
         Ok(self.vertex_input_expressions[index].unwrap())
     }
 
+    fn get_instance_id(&mut self) -> Result<Handle<Expression>> {
+        if !matches!(self.shader_config.shader_type, ShaderType::Vertex) {
+            return Err(Error::Unimplemented(
+                "Instance id register used outside of a vertex shader".to_string(),
+            ));
+        }
+
+        if self.instance_id.is_none() {
+            self.func.arguments.push(FunctionArgument {
+                name: Some("instance_id".to_string()),
+                ty: self.u32_type,
+                binding: Some(Binding::BuiltIn(BuiltIn::InstanceIndex)),
+            });
+
+            let arg_index = self.func.arguments.len() - 1;
+            self.instance_id = Some(self.func.expressions.append(
+                Expression::FunctionArgument(arg_index as u32),
+                Span::UNDEFINED,
+            ));
+        }
+        Ok(self.instance_id.unwrap())
+    }
+
+    /// Loads the instance id register (`iid`) as a vec4f, with the instance index
+    /// in every component.
+    fn emit_instance_id_load(&mut self) -> Result<Handle<Expression>> {
+        let instance_id = self.get_instance_id()?;
+        let as_float = self.evaluate_expr(Expression::As {
+            expr: instance_id,
+            kind: ScalarKind::Float,
+            convert: Some(4),
+        });
+        Ok(self.evaluate_expr(Expression::Splat {
+            size: VectorSize::Quad,
+            value: as_float,
+        }))
+    }
+
+    /// Adds a new member to the function return type, returning its index.
+    fn push_return_member(&mut self, name: String, ty: Handle<Type>, binding: Binding) -> usize {
+        if let TypeInner::Struct { members, .. } = &mut self.return_type.inner {
+            members.push(StructMember {
+                name: Some(name),
+                ty,
+                binding: Some(binding),
+                offset: 0,
+            });
+            members.len() - 1
+        } else {
+            unreachable!();
+        }
+    }
+
+    fn create_output_register(&mut self, name: String) -> Handle<Expression> {
+        let local = self.func.local_variables.append(
+            LocalVariable {
+                name: Some(name),
+                ty: self.vec4f,
+                init: None,
+            },
+            Span::UNDEFINED,
+        );
+        self.func
+            .expressions
+            .append(Expression::LocalVariable(local), Span::UNDEFINED)
+    }
+
+    /// Returns a pointer to the local variable backing a color output register (`ocN`).
+    /// Register 0 is the 'main' output, and the others are additional
+    /// render targets, which can only be written from a fragment shader.
+    fn get_color_output_pointer(&mut self, index: usize) -> Result<Handle<Expression>> {
+        if index == 0 {
+            return Ok(self.dest);
+        }
+        if !matches!(self.shader_config.shader_type, ShaderType::Fragment)
+            || index >= MAX_COLOR_OUTPUTS
+        {
+            return Err(Error::Unimplemented(format!(
+                "Unsupported output register {index} in {:?} shader",
+                self.shader_config.shader_type
+            )));
+        }
+
+        if self.color_outputs[index].is_none() {
+            let pointer = self.create_output_register(format!("dest_temp{index}"));
+            let output_struct_index = self.push_return_member(
+                format!("color_output_{index}"),
+                self.vec4f,
+                Binding::Location {
+                    location: index as u32,
+                    interpolation: None,
+                    sampling: None,
+                    blend_src: None,
+                },
+            );
+            self.color_outputs[index] = Some(OutputRegister {
+                pointer,
+                output_struct_index,
+            });
+        }
+        Ok(self.color_outputs[index].unwrap().pointer)
+    }
+
+    /// Returns a pointer to the local variable backing the fragment depth output (`fd`).
+    /// Only the first component is used as the depth.
+    fn get_depth_output_pointer(&mut self) -> Result<Handle<Expression>> {
+        if !matches!(self.shader_config.shader_type, ShaderType::Fragment) {
+            return Err(Error::Unimplemented(
+                "Depth output register used outside of a fragment shader".to_string(),
+            ));
+        }
+
+        if self.depth_output.is_none() {
+            let pointer = self.create_output_register("depth_temp".to_string());
+            let output_struct_index = self.push_return_member(
+                "depth".to_string(),
+                self.f32_type,
+                Binding::BuiltIn(BuiltIn::FragDepth),
+            );
+            self.depth_output = Some(OutputRegister {
+                pointer,
+                output_struct_index,
+            });
+        }
+        Ok(self.depth_output.unwrap().pointer)
+    }
+
     fn get_temporary_register(&mut self, index: usize) -> Result<Handle<Expression>> {
         if self.temporary_registers[index].is_none() {
             let local = self.func.local_variables.append(
@@ -821,9 +995,11 @@ impl<'a> NagaBuilder<'a> {
                         VertexAttributeFormat::Float4,
                     )
                 }),
+                RegisterType::InstanceId => {
+                    Ok((self.emit_instance_id_load()?, VertexAttributeFormat::Float4))
+                }
                 _ => Err(Error::Unimplemented(format!(
-                    "Unimplemented source reg type {:?}",
-                    source.register_type
+                    "Unimplemented source reg type {register_type:?}",
                 ))),
             }
         };
@@ -940,7 +1116,8 @@ impl<'a> NagaBuilder<'a> {
 
     fn emit_dest_store(&mut self, dest: &DestField, expr: Handle<Expression>) -> Result<()> {
         let base_expr = match dest.register_type {
-            RegisterType::Output => self.dest,
+            RegisterType::Output => self.get_color_output_pointer(dest.reg_num as usize)?,
+            RegisterType::FragmentRegister => self.get_depth_output_pointer()?,
             RegisterType::Varying => self.get_varying_pointer(dest.reg_num as usize)?,
             RegisterType::Temporary => self.get_temporary_register(dest.reg_num as usize)?,
             _ => {
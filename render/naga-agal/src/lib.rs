@@ -11,6 +11,11 @@ pub const SHADER_ENTRY_POINT: &str = "main";
 pub const MAX_VERTEX_ATTRIBUTES: usize = 8;
 pub const MAX_TEXTURES: usize = 8;
 
+/// The largest number of vertex program constants available to any AGAL version.
+pub const MAX_VERTEX_PROGRAM_CONSTANTS: u64 = 250;
+/// The largest number of fragment program constants available to any AGAL version.
+pub const MAX_FRAGMENT_PROGRAM_CONSTANTS: u64 = 64;
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum VertexAttributeFormat {
     Float1,
@@ -48,7 +53,7 @@ pub enum ShaderType {
     Fragment,
 }
 
-pub use builder::{TEXTURE_SAMPLER_START_BIND_INDEX, TEXTURE_START_BIND_INDEX};
+pub use builder::{MAX_COLOR_OUTPUTS, TEXTURE_SAMPLER_START_BIND_INDEX, TEXTURE_START_BIND_INDEX};
 pub use types::{Filter, Mipmap, SamplerConfig, Wrapping};
 
 /// Compiles an Adobe AGAL shader to a Naga Module.
@@ -79,10 +84,15 @@ pub use types::{Filter, Mipmap, SamplerConfig, Wrapping};
 ///   If a shader does not write to a varying register, then it is not included in the
 ///   Naga output struct type.
 ///
-/// * Program constants - An AGAL vertex shader has access to 128 program constants.
+/// * Program constants - An AGAL vertex shader has access to 128 program constants
+///   (250 in AGAL version 2 and later).
 ///   These are mapped to a single Naga uniform buffer, with a binding id of 0.
 ///   Each program constant is a vec4, and are stored in increasing order of register number.
 ///
+/// * Instance id - An AGAL version 3 vertex shader can read the instance index from `iid`.
+///   This is mapped to the Naga 'InstanceIndex' input, converted to a float and
+///   splatted across all four components.
+///
 /// # Fragment Shader
 ///
 /// * Fragment input - An AGAL fragment shader can read from the 8 varying registers
@@ -90,8 +100,16 @@ pub use types::{Filter, Mipmap, SamplerConfig, Wrapping};
 ///   binding in the Naga input type. For example, if a fragment shader uses varying registers
 ///   2 and 5, then the Naga input type will have two members, with binding ids 2 and 5.
 ///
-/// * Program constants - An AGAL fragment shader has access to 28 program constants.
+/// * Program constants - An AGAL fragment shader has access to 28 program constants
+///   (64 in AGAL version 2 and later).
 ///   These are mapped to a single Naga uniform buffer, with a binding id of 1.
+///
+/// * Fragment output - The main color output (`oc0`) is mapped to location 0.
+///   In AGAL version 2 and later, the additional color outputs `oc1` to `oc3`
+///   are mapped to the corresponding locations (for multiple render targets),
+///   and the first component of the depth output (`fd`) is mapped to the
+///   Naga 'FragDepth' output. As with varyings, only *used* outputs are
+///   included in the Naga output struct.
 pub fn agal_to_naga(
     agal: &[u8],
     vertex_attributes: &[Option<VertexAttributeFormat>; MAX_VERTEX_ATTRIBUTES],
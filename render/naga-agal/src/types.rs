@@ -54,7 +54,10 @@ pub enum RegisterType {
     Output = 3,
     Varying = 4,
     Sampler = 5,
+    /// The fragment depth output (`fd`), only available in AGAL version 2 and later.
     FragmentRegister = 6,
+    /// The instance id (`iid`), only available in AGAL version 3.
+    InstanceId = 7,
 }
 
 #[derive(Debug, FromPrimitive, Clone)]
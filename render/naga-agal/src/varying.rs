@@ -109,7 +109,8 @@ impl NagaBuilder<'_> {
     }
 
     /// Builds the final output struct expression, using the 'main' output (a position or color)
-    /// and any varying registers that were written to (if this is a vertex shader)
+    /// and any varying registers that were written to (if this is a vertex shader),
+    /// or any additional color or depth outputs (if this is a fragment shader)
     pub fn build_output_expr(&mut self, return_ty: Handle<Type>) -> Result<Handle<Expression>> {
         // Load the 'main' output (a position or color) from our temporary location.
         let dest_load = self.evaluate_expr(Expression::Load { pointer: self.dest });
@@ -131,6 +132,33 @@ impl NagaBuilder<'_> {
             }
         }
 
+        // Likewise, a fragment shader can write to additional color outputs
+        // and the depth output.
+        for i in 0..self.color_outputs.len() {
+            if let Some(register) = self.color_outputs[i] {
+                if register.output_struct_index >= components.len() {
+                    components.resize(register.output_struct_index + 1, None);
+                }
+                components[register.output_struct_index] =
+                    Some(self.evaluate_expr(Expression::Load {
+                        pointer: register.pointer,
+                    }));
+            }
+        }
+        if let Some(register) = self.depth_output {
+            let depth = self.evaluate_expr(Expression::Load {
+                pointer: register.pointer,
+            });
+            if register.output_struct_index >= components.len() {
+                components.resize(register.output_struct_index + 1, None);
+            }
+            components[register.output_struct_index] =
+                Some(self.evaluate_expr(Expression::AccessIndex {
+                    base: depth,
+                    index: 0,
+                }));
+        }
+
         let components = components.into_iter().map(|c| c.unwrap()).collect();
 
         Ok(self.evaluate_expr(Expression::Compose {
@@ -198,3 +198,83 @@ fn test_misc_opcodes() {
         ShaderType::Fragment
     );
 }
+
+#[test]
+fn test_branching() {
+    // ife v0.x, fc0.x
+    // mov ft0, v0
+    // els
+    // ifg v0.y, fc0.y
+    // mov ft0, fc1
+    // els
+    // mov ft0, fc2
+    // eif
+    // eif
+    // ine v0.z, fc0.z
+    // sub ft0, ft0, fc2
+    // eif
+    // ifl v0.w, fc63.w
+    // mul ft0, ft0, fc63
+    // eif
+    // mov oc, ft0
+    const BRANCHING_FRAGMENT: &[u8] = include!("branching_fragment.agal");
+
+    test_shader!(
+        BRANCHING_FRAGMENT,
+        &[None, None, None, None, None, None, None, None],
+        ShaderType::Fragment
+    );
+}
+
+#[test]
+fn test_derivatives() {
+    // ddx ft0, v0
+    // ddy ft1, v0.yxzw
+    // add oc, ft0, ft1
+    const DERIVATIVES_FRAGMENT: &[u8] = include!("derivatives_fragment.agal");
+
+    test_shader!(
+        DERIVATIVES_FRAGMENT,
+        &[None, None, None, None, None, None, None, None],
+        ShaderType::Fragment
+    );
+}
+
+#[test]
+fn test_fragment_outputs() {
+    // mov oc0, v0
+    // mov oc1, v1
+    // mov oc3, fc0
+    // mov fd.x, v0.z
+    const OUTPUTS_FRAGMENT: &[u8] = include!("outputs_fragment.agal");
+
+    test_shader!(
+        OUTPUTS_FRAGMENT,
+        &[None, None, None, None, None, None, None, None],
+        ShaderType::Fragment
+    );
+}
+
+#[test]
+fn test_instance_id() {
+    // add vt0, va0, vc[iid.x+4]
+    // m44 op, vt0, vc0
+    // mov v0, iid
+    // mov v1, vc249
+    const INSTANCE_VERTEX: &[u8] = include!("instance_vertex.agal");
+
+    test_shader!(
+        INSTANCE_VERTEX,
+        &[
+            Some(VertexAttributeFormat::Float4),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None
+        ],
+        ShaderType::Vertex
+    );
+}
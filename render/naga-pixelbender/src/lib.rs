@@ -18,6 +18,11 @@ use ruffle_render::pixel_bender::{
 pub const VERTEX_SHADER_ENTRYPOINT: &str = "filter__vertex_entry_point";
 pub const FRAGMENT_SHADER_ENTRYPOINT: &str = "main";
 
+/// The bind group used for all shader resources when the shader is run as a
+/// shape fill (see `ShaderBuilder::build_fill`). Groups 0 to 2 are used by the
+/// shape pipeline itself.
+pub const SHAPE_FILL_BIND_GROUP: u32 = 3;
+
 pub struct NagaModules {
     pub vertex: naga::Module,
     pub fragment: naga::Module,
@@ -30,6 +35,7 @@ pub struct ShaderBuilder<'a> {
     module: Module,
     func: Function,
     shader: &'a PixelBenderShader,
+    invocation: Invocation,
 
     vec2f: Handle<Type>,
     vec4f: Handle<Type>,
@@ -78,6 +84,27 @@ pub struct ShaderBuilder<'a> {
     blocks: Vec<BlockStackEntry>,
 }
 
+/// Determines how the generated fragment shader is going to be invoked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Invocation {
+    /// The shader is drawn over a quad covering the target (`ShaderFilter` and `ShaderJob`).
+    /// All resources are bound in group 0, and `outCoord` is the fragment position.
+    Filter,
+    /// The shader is evaluated for every fragment of a shape (`Graphics.beginShaderFill`).
+    /// All resources are bound in `SHAPE_FILL_BIND_GROUP`, and `outCoord` is the
+    /// interpolated vertex output at location 0 (in shader space).
+    ShapeFill,
+}
+
+impl Invocation {
+    fn bind_group(self) -> u32 {
+        match self {
+            Invocation::Filter => 0,
+            Invocation::ShapeFill => SHAPE_FILL_BIND_GROUP,
+        }
+    }
+}
+
 /// Handles 'if' and 'else' blocks in PixelBender bytecode.
 /// When we encounter an 'OpIf' opcode, we push an `IfElse` entry onto the block stack.
 /// Any subsequent opcodes will be added to the `after_if` block.
@@ -124,14 +151,40 @@ pub const TEXTURE_START_BIND_INDEX: u32 = 6;
 
 impl ShaderBuilder<'_> {
     pub fn build(shader: &PixelBenderShader) -> Result<NagaModules> {
-        let mut module = Module::default();
-
         static VERTEX_SHADER: LazyLock<Module> = LazyLock::new(|| {
             naga::front::wgsl::parse_str(ruffle_render::shader_source::SHADER_FILTER_COMMON)
                 .expect("Failed to parse vertex shader")
         });
         let vertex_shader = VERTEX_SHADER.clone();
 
+        let (fragment, float_parameters_buffer_size, int_parameters_buffer_size) =
+            Self::build_fragment(shader, Invocation::Filter)?;
+
+        Ok(NagaModules {
+            vertex: vertex_shader,
+            fragment,
+            float_parameters_buffer_size,
+            int_parameters_buffer_size,
+        })
+    }
+
+    /// Builds a fragment shader module for evaluating the shader as a shape fill.
+    ///
+    /// The vertex shader is provided by the renderer, and must output the shader space
+    /// coordinate of each vertex as a `vec2<f32>` at location 0. The shader resources
+    /// are laid out like in `build`, but are bound in `SHAPE_FILL_BIND_GROUP`.
+    pub fn build_fill(shader: &PixelBenderShader) -> Result<Module> {
+        let (fragment, _, _) = Self::build_fragment(shader, Invocation::ShapeFill)?;
+        Ok(fragment)
+    }
+
+    fn build_fragment(
+        shader: &PixelBenderShader,
+        invocation: Invocation,
+    ) -> Result<(Module, u64, u64)> {
+        let mut module = Module::default();
+        let bind_group = invocation.bind_group();
+
         let vec2f = module.types.insert(
             Type {
                 name: None,
@@ -231,8 +284,9 @@ impl ShaderBuilder<'_> {
             ty: vec4f,
             binding: Some(Binding::BuiltIn(BuiltIn::Position { invariant: false })),
         });
-        // UV coordinates from vertex shader - unused, but wgpu
-        // requires that we consume all outputs from the vertex shader
+        // UV coordinates from vertex shader. When running as a filter, these are
+        // unused, but wgpu requires that we consume all outputs from the vertex shader.
+        // When running as a shape fill, this is the shader space coordinate.
         func.arguments.push(FunctionArgument {
             name: None,
             ty: vec2f,
@@ -243,6 +297,21 @@ impl ShaderBuilder<'_> {
                 blend_src: None,
             }),
         });
+        if invocation == Invocation::ShapeFill {
+            // The color transform of the shape, as `mult_color` and `add_color`.
+            for location in [1, 2] {
+                func.arguments.push(FunctionArgument {
+                    name: None,
+                    ty: vec4f,
+                    binding: Some(Binding::Location {
+                        location,
+                        interpolation: Some(naga::Interpolation::Flat),
+                        sampling: None,
+                        blend_src: None,
+                    }),
+                });
+            }
+        }
 
         func.result = Some(FunctionResult {
             ty: vec4f,
@@ -260,7 +329,7 @@ impl ShaderBuilder<'_> {
                     name: Some(format!("sampler{i}")),
                     space: naga::AddressSpace::Handle,
                     binding: Some(naga::ResourceBinding {
-                        group: 0,
+                        group: bind_group,
                         binding: TEXTURE_SAMPLER_START_BIND_INDEX + i as u32,
                     }),
                     ty: module.types.insert(
@@ -339,6 +408,7 @@ impl ShaderBuilder<'_> {
             clamp_bilinear: samplers[SAMPLER_CLAMP_BILINEAR as usize],
 
             shader,
+            invocation,
             textures: Vec::new(),
             float_registers: Vec::new(),
             int_registers: Vec::new(),
@@ -350,7 +420,7 @@ impl ShaderBuilder<'_> {
                 name: Some("zeroed_out_of_range_mode".to_string()),
                 space: naga::AddressSpace::Uniform,
                 binding: Some(naga::ResourceBinding {
-                    group: 0,
+                    group: bind_group,
                     binding: ZEROED_OUT_OF_RANGE_MODE_INDEX,
                 }),
                 ty: vec4f,
@@ -417,6 +487,11 @@ impl ShaderBuilder<'_> {
         } else {
             dst_load
         };
+        let dst = if invocation == Invocation::ShapeFill {
+            builder.apply_color_transform(dst)
+        } else {
+            dst
+        };
         builder.push_statement(Statement::Return { value: Some(dst) });
 
         let block = match builder.blocks.pop().unwrap() {
@@ -444,12 +519,11 @@ impl ShaderBuilder<'_> {
             function: builder.func,
         });
 
-        Ok(NagaModules {
-            vertex: vertex_shader,
-            fragment: builder.module,
+        Ok((
+            builder.module,
             float_parameters_buffer_size,
             int_parameters_buffer_size,
-        })
+        ))
     }
 
     fn add_arguments(&mut self) -> (u64, u64) {
@@ -536,7 +610,7 @@ impl ShaderBuilder<'_> {
                             name: Some(format!("texture{index}")),
                             space: AddressSpace::Handle,
                             binding: Some(ResourceBinding {
-                                group: 0,
+                                group: self.invocation.bind_group(),
                                 binding: TEXTURE_START_BIND_INDEX + index as u32,
                             }),
                             ty: self.image2d,
@@ -566,7 +640,7 @@ impl ShaderBuilder<'_> {
                 name: Some("shader_float_parameters".to_string()),
                 space: naga::AddressSpace::Uniform,
                 binding: Some(naga::ResourceBinding {
-                    group: 0,
+                    group: self.invocation.bind_group(),
                     binding: SHADER_FLOAT_PARAMETERS_INDEX,
                 }),
                 ty: self.module.types.insert(
@@ -590,7 +664,7 @@ impl ShaderBuilder<'_> {
                 name: Some("shader_int_parameters".to_string()),
                 space: naga::AddressSpace::Uniform,
                 binding: Some(naga::ResourceBinding {
-                    group: 0,
+                    group: self.invocation.bind_group(),
                     binding: SHADER_INT_PARAMETERS_INDEX,
                 }),
                 ty: self.module.types.insert(
@@ -647,10 +721,14 @@ impl ShaderBuilder<'_> {
         // (it may use te same register as another parameter, but with different components)
 
         if let Some(coord_reg) = out_coord {
-            let coord_val = self
-                .func
-                .expressions
-                .append(Expression::FunctionArgument(0), Span::UNDEFINED);
+            let coord_argument = match self.invocation {
+                Invocation::Filter => 0,
+                Invocation::ShapeFill => 1,
+            };
+            let coord_val = self.func.expressions.append(
+                Expression::FunctionArgument(coord_argument),
+                Span::UNDEFINED,
+            );
             self.emit_dest_store(coord_val, coord_reg);
         }
 
@@ -1600,6 +1678,73 @@ impl ShaderBuilder<'_> {
         evaluate_expr(&mut self.func, &mut self.blocks, expr)
     }
 
+    /// Applies the color transform passed in by the shape fill vertex shader to `color`.
+    /// Like bitmap fills, the (premultiplied) color is unmultiplied before the transform.
+    fn apply_color_transform(&mut self, color: Handle<Expression>) -> Handle<Expression> {
+        let [mult_color, add_color] = [2, 3].map(|argument| {
+            self.func
+                .expressions
+                .append(Expression::FunctionArgument(argument), Span::UNDEFINED)
+        });
+        let min_alpha = self
+            .func
+            .expressions
+            .append(Expression::Literal(Literal::F32(1e-6)), Span::UNDEFINED);
+
+        let alpha = self.evaluate_expr(Expression::AccessIndex {
+            base: color,
+            index: 3,
+        });
+        let alpha = self.evaluate_expr(Expression::Math {
+            fun: MathFunction::Max,
+            arg: alpha,
+            arg1: Some(min_alpha),
+            arg2: None,
+            arg3: None,
+        });
+        let divisor = self.evaluate_expr(Expression::Compose {
+            ty: self.vec4f,
+            components: vec![alpha, alpha, alpha, self.onef32],
+        });
+        let color = self.evaluate_expr(Expression::Binary {
+            op: BinaryOperator::Divide,
+            left: color,
+            right: divisor,
+        });
+
+        let color = self.evaluate_expr(Expression::Binary {
+            op: BinaryOperator::Multiply,
+            left: color,
+            right: mult_color,
+        });
+        let color = self.evaluate_expr(Expression::Binary {
+            op: BinaryOperator::Add,
+            left: color,
+            right: add_color,
+        });
+        let color = self.evaluate_expr(Expression::Math {
+            fun: MathFunction::Saturate,
+            arg: color,
+            arg1: None,
+            arg2: None,
+            arg3: None,
+        });
+
+        let alpha = self.evaluate_expr(Expression::AccessIndex {
+            base: color,
+            index: 3,
+        });
+        let multiplier = self.evaluate_expr(Expression::Compose {
+            ty: self.vec4f,
+            components: vec![alpha, alpha, alpha, self.onef32],
+        });
+        self.evaluate_expr(Expression::Binary {
+            op: BinaryOperator::Multiply,
+            left: color,
+            right: multiplier,
+        })
+    }
+
     /// Normally, we pad all loads (including scalar loads) to a vec4, and operate component-wise
     /// on them. This removes the need to check for scalar vs vector everywhere.
     ///
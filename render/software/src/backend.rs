@@ -0,0 +1,384 @@
+use crate::bitmap::{as_bitmap, SoftwareBitmap, SoftwareSyncHandle};
+use crate::commands::CommandRenderer;
+use crate::filters;
+use crate::mesh::Mesh;
+use crate::raster::Affine;
+use crate::surface::{color_to_pixel, Surface};
+use ruffle_render::backend::{
+    BitmapCacheEntry, Context3D, Context3DProfile, RenderBackend, ShapeHandle, ViewportDimensions,
+};
+use ruffle_render::bitmap::{
+    Bitmap, BitmapHandle, BitmapSource, PixelRegion, RgbaBufRead, SyncHandle,
+};
+use ruffle_render::commands::CommandList;
+use ruffle_render::error::Error;
+use ruffle_render::filters::Filter;
+use ruffle_render::quality::StageQuality;
+use ruffle_render::shape_utils::DistilledShape;
+use ruffle_render::tessellator::ShapeTessellator;
+use ruffle_render::utils::unmultiply_alpha_rgba;
+use std::any::Any;
+use std::borrow::Cow;
+use std::sync::Arc;
+use swf::Color;
+
+/// A render backend that rasterizes everything on the CPU.
+///
+/// Shapes are tessellated into triangles just like the GPU backends do, and anti-aliasing
+/// is achieved by supersampling according to the stage quality.
+pub struct SoftwareRenderBackend {
+    shape_tessellator: ShapeTessellator,
+    viewport_dimensions: ViewportDimensions,
+    quality: StageQuality,
+    /// The last rendered frame, as premultiplied RGBA8.
+    frame: Vec<u8>,
+}
+
+impl SoftwareRenderBackend {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            shape_tessellator: ShapeTessellator::new(),
+            viewport_dimensions: ViewportDimensions {
+                width,
+                height,
+                scale_factor: 1.0,
+            },
+            quality: StageQuality::default(),
+            frame: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Returns the last rendered frame, with straight alpha.
+    pub fn capture_frame(&self) -> Option<image::RgbaImage> {
+        let mut pixels = self.frame.clone();
+        unmultiply_alpha_rgba(&mut pixels);
+        image::RgbaImage::from_raw(
+            self.viewport_dimensions.width,
+            self.viewport_dimensions.height,
+            pixels,
+        )
+    }
+
+    /// Renders `commands` on top of the current contents of `bitmap`, only keeping
+    /// the pixels within `region`. Returns the resulting pixels of that region.
+    fn render_to_bitmap(
+        bitmap: &SoftwareBitmap,
+        commands: CommandList,
+        clear: Option<Color>,
+        quality: StageQuality,
+        region: PixelRegion,
+    ) -> Vec<u8> {
+        let scale = supersampling(quality);
+        let mut surface = match clear {
+            Some(clear) => Surface::new(
+                bitmap.width() * scale,
+                bitmap.height() * scale,
+                color_to_pixel(clear),
+            ),
+            None => Surface::from_rgba(bitmap.width(), bitmap.height(), &bitmap.pixels(), scale),
+        };
+        commands.execute(&mut CommandRenderer::new(
+            &mut surface,
+            quality,
+            Affine::scale(scale as f32),
+        ));
+        bitmap.copy_region(&surface.to_rgba(scale), region);
+        bitmap.read_region(region)
+    }
+}
+
+/// The number of samples taken per pixel along each axis for the given quality.
+fn supersampling(quality: StageQuality) -> u32 {
+    match quality.sample_count() {
+        0..=1 => 1,
+        2..=4 => 2,
+        5..=8 => 3,
+        _ => 4,
+    }
+}
+
+impl RenderBackend for SoftwareRenderBackend {
+    fn viewport_dimensions(&self) -> ViewportDimensions {
+        self.viewport_dimensions
+    }
+
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        self.viewport_dimensions = dimensions;
+        self.frame = vec![0; dimensions.width as usize * dimensions.height as usize * 4];
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        let mesh = self
+            .shape_tessellator
+            .tessellate_shape(shape, bitmap_source);
+        ShapeHandle(Arc::new(Mesh::new(mesh, bitmap_source, self)))
+    }
+
+    fn render_offscreen(
+        &mut self,
+        handle: BitmapHandle,
+        commands: CommandList,
+        quality: StageQuality,
+        mut bounds: PixelRegion,
+    ) -> Option<Box<dyn SyncHandle>> {
+        let bitmap = as_bitmap(&handle);
+        bounds.clamp(bitmap.width(), bitmap.height());
+        let pixels = Self::render_to_bitmap(bitmap, commands, None, quality, bounds);
+        Some(Box::new(SoftwareSyncHandle {
+            width: bounds.width(),
+            pixels,
+        }))
+    }
+
+    fn is_offscreen_supported(&self) -> bool {
+        true
+    }
+
+    fn is_filter_supported(&self, filter: &Filter) -> bool {
+        filters::is_filter_supported(filter)
+    }
+
+    fn apply_filter(
+        &mut self,
+        source: BitmapHandle,
+        source_point: (u32, u32),
+        source_size: (u32, u32),
+        destination: BitmapHandle,
+        dest_point: (u32, u32),
+        filter: Filter,
+    ) -> Option<Box<dyn SyncHandle>> {
+        let source = as_bitmap(&source);
+        let destination = as_bitmap(&destination);
+        let (width, height) = source_size;
+
+        // Anything outside of the source bitmap is transparent.
+        let mut rgba = vec![0; width as usize * height as usize * 4];
+        {
+            let source_pixels = source.pixels();
+            let copy_width = width.min(source.width().saturating_sub(source_point.0));
+            let copy_height = height.min(source.height().saturating_sub(source_point.1));
+            let row_length = copy_width as usize * 4;
+            for y in 0..copy_height {
+                let from = ((source_point.1 + y) * source.width() + source_point.0) as usize * 4;
+                let to = (y * width) as usize * 4;
+                rgba[to..to + row_length].copy_from_slice(&source_pixels[from..from + row_length]);
+            }
+        }
+        let mut surface = Surface::from_rgba(width, height, &rgba, 1);
+        if !filters::apply_filter(&mut surface, &filter) {
+            return None;
+        }
+
+        let filtered = surface.to_rgba(1);
+        {
+            let mut dest_pixels = destination.pixels_mut();
+            let copy_width = width.min(destination.width().saturating_sub(dest_point.0));
+            let copy_height = height.min(destination.height().saturating_sub(dest_point.1));
+            let row_length = copy_width as usize * 4;
+            for y in 0..copy_height {
+                let from = (y * width) as usize * 4;
+                let to = ((dest_point.1 + y) * destination.width() + dest_point.0) as usize * 4;
+                dest_pixels[to..to + row_length]
+                    .copy_from_slice(&filtered[from..from + row_length]);
+            }
+        }
+
+        Some(Box::new(SoftwareSyncHandle {
+            width: destination.width(),
+            pixels: destination.pixels().clone(),
+        }))
+    }
+
+    fn submit_frame(
+        &mut self,
+        clear: Color,
+        commands: CommandList,
+        cache_entries: Vec<BitmapCacheEntry>,
+    ) {
+        for entry in cache_entries {
+            let bitmap = as_bitmap(&entry.handle);
+            let region = PixelRegion::for_whole_size(bitmap.width(), bitmap.height());
+            Self::render_to_bitmap(
+                bitmap,
+                entry.commands,
+                Some(entry.clear),
+                self.quality,
+                region,
+            );
+            if !entry.filters.is_empty() {
+                let mut surface =
+                    Surface::from_rgba(bitmap.width(), bitmap.height(), &bitmap.pixels(), 1);
+                for filter in &entry.filters {
+                    if !filters::apply_filter(&mut surface, filter) {
+                        tracing::warn!("Filter not supported by the software renderer: {filter:?}");
+                    }
+                }
+                bitmap.copy_region(&surface.to_rgba(1), region);
+            }
+        }
+
+        let scale = supersampling(self.quality);
+        let mut surface = Surface::new(
+            self.viewport_dimensions.width * scale,
+            self.viewport_dimensions.height * scale,
+            color_to_pixel(clear),
+        );
+        commands.execute(&mut CommandRenderer::new(
+            &mut surface,
+            self.quality,
+            Affine::scale(scale as f32),
+        ));
+        self.frame = surface.to_rgba(scale);
+    }
+
+    fn create_empty_texture(&mut self, width: u32, height: u32) -> Result<BitmapHandle, Error> {
+        let pixels = vec![0; width as usize * height as usize * 4];
+        Ok(BitmapHandle(Arc::new(SoftwareBitmap::new(
+            width, height, pixels,
+        ))))
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap<'_>) -> Result<BitmapHandle, Error> {
+        let bitmap = bitmap.to_rgba();
+        Ok(BitmapHandle(Arc::new(SoftwareBitmap::new(
+            bitmap.width(),
+            bitmap.height(),
+            bitmap.data().to_vec(),
+        ))))
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: &BitmapHandle,
+        bitmap: Bitmap<'_>,
+        mut region: PixelRegion,
+    ) -> Result<(), Error> {
+        let texture = as_bitmap(handle);
+        let bitmap = bitmap.to_rgba();
+        region.clamp(texture.width(), texture.height());
+        texture.copy_region(bitmap.data(), region);
+        Ok(())
+    }
+
+    fn create_context3d(
+        &mut self,
+        _profile: Context3DProfile,
+    ) -> Result<Box<dyn Context3D>, Error> {
+        Err(Error::Unimplemented("createContext3D".into()))
+    }
+
+    fn debug_info(&self) -> Cow<'static, str> {
+        Cow::Borrowed("Renderer: Software")
+    }
+
+    fn name(&self) -> &'static str {
+        "software"
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.quality = quality;
+    }
+
+    fn resolve_sync_handle(
+        &mut self,
+        handle: Box<dyn SyncHandle>,
+        with_rgba: RgbaBufRead,
+    ) -> Result<(), Error> {
+        let handle = Box::<dyn Any>::downcast::<SoftwareSyncHandle>(handle).unwrap();
+        with_rgba(&handle.pixels, handle.width * 4);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_render::commands::{CommandHandler, RenderBlendMode};
+    use ruffle_render::matrix::Matrix;
+    use swf::{BlendMode, Twips};
+
+    fn rect(x: i32, y: i32, width: f32, height: f32) -> Matrix {
+        Matrix::create_box(
+            width,
+            height,
+            Twips::from_pixels_i32(x),
+            Twips::from_pixels_i32(y),
+        )
+    }
+
+    fn render(commands: CommandList) -> image::RgbaImage {
+        let mut backend = SoftwareRenderBackend::new(4, 4);
+        backend.set_quality(StageQuality::Low);
+        backend.submit_frame(Color::WHITE, commands, vec![]);
+        backend.capture_frame().unwrap()
+    }
+
+    #[test]
+    fn draw_rect() {
+        let mut commands = CommandList::new();
+        commands.draw_rect(Color::RED, rect(1, 1, 2.0, 2.0));
+        let image = render(commands);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(1, 1).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2, 2).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(3, 3).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn masks_clip_content() {
+        let mut commands = CommandList::new();
+        commands.push_mask();
+        commands.draw_rect(Color::BLACK, rect(0, 0, 2.0, 4.0));
+        commands.activate_mask();
+        commands.draw_rect(Color::BLUE, rect(0, 0, 4.0, 4.0));
+        commands.deactivate_mask();
+        commands.draw_rect(Color::BLACK, rect(0, 0, 2.0, 4.0));
+        commands.pop_mask();
+        let image = render(commands);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(2, 0).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn blend_modes_composite_layers() {
+        let mut layer = CommandList::new();
+        layer.draw_rect(Color::GREEN, rect(0, 0, 4.0, 4.0));
+        let mut commands = CommandList::new();
+        commands.draw_rect(Color::YELLOW, rect(0, 0, 4.0, 4.0));
+        commands.blend(layer, RenderBlendMode::Builtin(BlendMode::Multiply));
+        let image = render(commands);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn blend_shaders_run_on_the_cpu() {
+        // Takes the red channel of the background and the green channel of the foreground.
+        const MIX: &[u8] = &[
+            165, 1, 0, 0, 0, 164, 3, 0, 77, 105, 120, 161, 1, 2, 0, 0, 12, 95, 79, 117, 116, 67,
+            111, 111, 114, 100, 0, 163, 0, 4, 98, 97, 99, 107, 103, 114, 111, 117, 110, 100, 0,
+            163, 1, 4, 102, 111, 114, 101, 103, 114, 111, 117, 110, 100, 0, 161, 2, 4, 1, 0, 15,
+            100, 115, 116, 0, 48, 2, 0, 240, 0, 0, 16, 0, 48, 3, 0, 240, 0, 0, 16, 1, 29, 1, 0,
+            128, 2, 0, 0, 0, 29, 1, 0, 64, 3, 0, 64, 0, 50, 1, 0, 32, 0, 0, 0, 0, 50, 1, 0, 16, 63,
+            128, 0, 0,
+        ];
+        let shader = ruffle_render::pixel_bender::parse_shader(MIX, true).unwrap();
+        let mut backend = SoftwareRenderBackend::new(4, 4);
+        let shader = backend.compile_pixelbender_shader(shader).unwrap();
+
+        let mut layer = CommandList::new();
+        layer.draw_rect(Color::GREEN, rect(0, 0, 4.0, 4.0));
+        let mut commands = CommandList::new();
+        commands.draw_rect(Color::RED, rect(0, 0, 2.0, 4.0));
+        commands.draw_rect(Color::BLUE, rect(2, 0, 2.0, 4.0));
+        commands.blend(layer, RenderBlendMode::Shader(shader));
+        backend.set_quality(StageQuality::Low);
+        backend.submit_frame(Color::WHITE, commands, vec![]);
+        let image = backend.capture_frame().unwrap();
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 0, 255]);
+        assert_eq!(image.get_pixel(3, 0).0, [0, 255, 0, 255]);
+    }
+}
@@ -0,0 +1,75 @@
+use ruffle_render::bitmap::{BitmapHandle, BitmapHandleImpl, PixelRegion, SyncHandle};
+use std::any::Any;
+use std::cell::{Ref, RefCell, RefMut};
+
+/// A bitmap stored in main memory, as premultiplied RGBA8.
+#[derive(Debug)]
+pub struct SoftwareBitmap {
+    width: u32,
+    height: u32,
+    pixels: RefCell<Vec<u8>>,
+}
+
+impl BitmapHandleImpl for SoftwareBitmap {}
+
+impl SoftwareBitmap {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        debug_assert_eq!(pixels.len(), width as usize * height as usize * 4);
+        Self {
+            width,
+            height,
+            pixels: RefCell::new(pixels),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> Ref<'_, Vec<u8>> {
+        self.pixels.borrow()
+    }
+
+    pub fn pixels_mut(&self) -> RefMut<'_, Vec<u8>> {
+        self.pixels.borrow_mut()
+    }
+
+    /// Copies `region` of the full-size `source` image into this bitmap.
+    pub fn copy_region(&self, source: &[u8], region: PixelRegion) {
+        let mut pixels = self.pixels_mut();
+        let row_length = region.width() as usize * 4;
+        for y in region.y_min..region.y_max {
+            let start = (y * self.width + region.x_min) as usize * 4;
+            pixels[start..start + row_length].copy_from_slice(&source[start..start + row_length]);
+        }
+    }
+
+    /// Returns a copy of `region` of this bitmap, tightly packed.
+    pub fn read_region(&self, region: PixelRegion) -> Vec<u8> {
+        let pixels = self.pixels();
+        let row_length = region.width() as usize * 4;
+        let mut result = Vec::with_capacity(row_length * region.height() as usize);
+        for y in region.y_min..region.y_max {
+            let start = (y * self.width + region.x_min) as usize * 4;
+            result.extend_from_slice(&pixels[start..start + row_length]);
+        }
+        result
+    }
+}
+
+pub fn as_bitmap(handle: &BitmapHandle) -> &SoftwareBitmap {
+    <dyn Any>::downcast_ref(&*handle.0).unwrap()
+}
+
+/// The pixels of a region that was rendered to, captured at the time of rendering.
+#[derive(Debug)]
+pub struct SoftwareSyncHandle {
+    pub width: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl SyncHandle for SoftwareSyncHandle {}
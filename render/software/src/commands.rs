@@ -0,0 +1,439 @@
+use crate::bitmap::as_bitmap;
+use crate::mesh::{sample_bitmap, transform_color, Mesh, Paint};
+use crate::raster::{fill_triangle, Affine, Point};
+use crate::surface::{blend, color_to_pixel, Pixel, Surface, TRANSPARENT};
+use ruffle_render::backend::ShapeHandle;
+use ruffle_render::bitmap::{BitmapHandle, PixelSnapping};
+use ruffle_render::commands::{CommandHandler, CommandList, RenderBlendMode};
+use ruffle_render::matrix::Matrix;
+use ruffle_render::pixel_bender::PixelBenderShaderHandle;
+use ruffle_render::pixel_bender_interpreter::{self, ShaderMode};
+use ruffle_render::pixel_bender_support::{ImageInputTexture, PixelBenderShaderArgument};
+use ruffle_render::quality::StageQuality;
+use ruffle_render::transform::Transform;
+use std::any::Any;
+use swf::{BlendMode, Color, Twips};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum MaskState {
+    NoMask,
+    DrawMask,
+    DrawMaskedContent,
+    ClearMask,
+}
+
+/// Executes a [`CommandList`] onto a [`Surface`].
+pub struct CommandRenderer<'a> {
+    target: &'a mut Surface,
+    quality: StageQuality,
+    /// Maps stage pixels to surface pixels.
+    view_matrix: Affine,
+    /// One coverage buffer per active mask, each already intersected with its parent.
+    masks: Vec<Vec<bool>>,
+    mask_state: MaskState,
+}
+
+impl<'a> CommandRenderer<'a> {
+    pub fn new(target: &'a mut Surface, quality: StageQuality, view_matrix: Affine) -> Self {
+        Self {
+            target,
+            quality,
+            view_matrix,
+            masks: Vec::new(),
+            mask_state: MaskState::NoMask,
+        }
+    }
+
+    /// Rasterizes the given triangles, either onto the mask being drawn or onto the target.
+    /// `shade` receives the surface position of each pixel center, the vertex indices
+    /// of the triangle and their barycentric weights.
+    fn fill_triangles(
+        &mut self,
+        positions: &[Point],
+        indices: &[u32],
+        mut shade: impl FnMut(Point, [usize; 3], [f32; 3]) -> Pixel,
+    ) {
+        let (width, height) = (self.target.width(), self.target.height());
+        let triangles = indices.chunks_exact(3).map(|triangle| {
+            let triangle = [
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            ];
+            (triangle, triangle.map(|i| positions[i]))
+        });
+
+        match self.mask_state {
+            MaskState::ClearMask => {}
+            MaskState::DrawMask => {
+                let (mask, parents) = self.masks.split_last_mut().expect("Mask must be pushed");
+                let parent = parents.last();
+                for (_, vertices) in triangles {
+                    fill_triangle(width, height, vertices, |x, y, _| {
+                        let index = (y * width + x) as usize;
+                        if parent.is_none_or(|parent| parent[index]) {
+                            mask[index] = true;
+                        }
+                    });
+                }
+            }
+            MaskState::NoMask | MaskState::DrawMaskedContent => {
+                let clip = self.masks.last();
+                let pixels = self.target.pixels_mut();
+                for (triangle, vertices) in triangles {
+                    fill_triangle(width, height, vertices, |x, y, weights| {
+                        let index = (y * width + x) as usize;
+                        if clip.is_none_or(|clip| clip[index]) {
+                            let center = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+                            let color = shade(center, triangle, weights);
+                            pixels[index] = blend(color, pixels[index], BlendMode::Normal);
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    fn fill_quad(
+        &mut self,
+        matrix: &Affine,
+        width: f32,
+        height: f32,
+        shade: impl FnMut(Point, [usize; 3], [f32; 3]) -> Pixel,
+    ) {
+        let positions = [
+            Point::new(0.0, 0.0),
+            Point::new(width, 0.0),
+            Point::new(width, height),
+            Point::new(0.0, height),
+        ]
+        .map(|point| matrix.apply(point));
+        self.fill_triangles(&positions, &[0, 1, 2, 0, 2, 3], shade);
+    }
+
+    /// Draws one pixel wide lines between consecutive points of `points`.
+    fn stroke_lines(&mut self, color: Color, mut matrix: Matrix, points: &[Point]) {
+        matrix.tx += Twips::HALF_PX;
+        matrix.ty += Twips::HALF_PX;
+        let matrix = self.view_matrix.then(&Affine::from(matrix));
+        let half_width = 0.5 * self.view_matrix.a.abs().max(self.view_matrix.d.abs());
+        let color = color_to_pixel(color);
+
+        for segment in points.windows(2) {
+            let (from, to) = (matrix.apply(segment[0]), matrix.apply(segment[1]));
+            let (dx, dy) = (to.x - from.x, to.y - from.y);
+            let length = (dx * dx + dy * dy).sqrt();
+            if length == 0.0 {
+                continue;
+            }
+            let (nx, ny) = (-dy / length * half_width, dx / length * half_width);
+            let positions = [
+                Point::new(from.x + nx, from.y + ny),
+                Point::new(to.x + nx, to.y + ny),
+                Point::new(to.x - nx, to.y - ny),
+                Point::new(from.x - nx, from.y - ny),
+            ];
+            self.fill_triangles(&positions, &[0, 1, 2, 0, 2, 3], |_, _, _| color);
+        }
+    }
+
+    /// Renders `commands` onto a fresh transparent surface of the same size as the target.
+    fn render_layer(&self, commands: CommandList) -> Surface {
+        let mut layer = Surface::new(self.target.width(), self.target.height(), TRANSPARENT);
+        let mut renderer = CommandRenderer::new(&mut layer, self.quality, self.view_matrix);
+        commands.execute(&mut renderer);
+        layer
+    }
+
+    /// Composites a layer onto the target, or onto the mask being drawn.
+    fn composite(&mut self, layer: &Surface, blend_mode: BlendMode) {
+        match self.mask_state {
+            MaskState::ClearMask => {}
+            MaskState::DrawMask => {
+                let (mask, parents) = self.masks.split_last_mut().expect("Mask must be pushed");
+                let parent = parents.last();
+                for (index, pixel) in layer.pixels().iter().enumerate() {
+                    if pixel[3] > 0.0 && parent.is_none_or(|parent| parent[index]) {
+                        mask[index] = true;
+                    }
+                }
+            }
+            MaskState::NoMask | MaskState::DrawMaskedContent => {
+                let clip = self.masks.last();
+                let pixels = self.target.pixels_mut();
+                for (index, (dst, src)) in pixels.iter_mut().zip(layer.pixels()).enumerate() {
+                    if clip.is_none_or(|clip| clip[index]) {
+                        *dst = blend(*src, *dst, blend_mode);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs a blend shader with the target as its background and `layer` as its foreground,
+    /// replacing the target with the output of the shader.
+    fn blend_shader(&mut self, layer: &Surface, shader: &PixelBenderShaderHandle) {
+        if matches!(self.mask_state, MaskState::DrawMask | MaskState::ClearMask) {
+            self.composite(layer, BlendMode::Normal);
+            return;
+        }
+
+        let input = |index, name: &str, surface: &Surface| PixelBenderShaderArgument::ImageInput {
+            index,
+            channels: 4,
+            name: name.to_string(),
+            texture: Some(ImageInputTexture::Bytes {
+                width: surface.width(),
+                height: surface.height(),
+                channels: 4,
+                bytes: surface
+                    .pixels()
+                    .iter()
+                    .flatten()
+                    .flat_map(|value| value.to_le_bytes())
+                    .collect(),
+            }),
+        };
+        let arguments = [
+            input(0, "background", self.target),
+            input(1, "foreground", layer),
+        ];
+        let shader = shader.0.parsed_shader();
+        let output = match pixel_bender_interpreter::run_shader(
+            shader,
+            &arguments,
+            self.target.width(),
+            self.target.height(),
+            ShaderMode::Filter,
+        ) {
+            Ok(output) => output,
+            Err(e) => {
+                tracing::warn!(
+                    "Shader blend mode is unsupported by the software renderer, drawing it as normal: {e}"
+                );
+                self.composite(layer, BlendMode::Normal);
+                return;
+            }
+        };
+
+        let channels = shader.output_channels().unwrap_or(4);
+        let values: Vec<f32> = output
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let clip = self.masks.last();
+        let pixels = self.target.pixels_mut();
+        for (index, (dst, src)) in pixels
+            .iter_mut()
+            .zip(values.chunks_exact(channels))
+            .enumerate()
+        {
+            if clip.is_none_or(|clip| clip[index]) {
+                let mut pixel = [0.0, 0.0, 0.0, 1.0];
+                for (pixel, value) in pixel.iter_mut().zip(src) {
+                    *pixel = value.clamp(0.0, 1.0);
+                }
+                *dst = pixel;
+            }
+        }
+    }
+
+    fn smoothing(&self, smoothing: bool) -> bool {
+        smoothing && self.quality != StageQuality::Low
+    }
+}
+
+impl CommandHandler for CommandRenderer<'_> {
+    fn render_bitmap(
+        &mut self,
+        bitmap: BitmapHandle,
+        transform: Transform,
+        smoothing: bool,
+        pixel_snapping: PixelSnapping,
+    ) {
+        let mut matrix = transform.matrix;
+        pixel_snapping.apply(&mut matrix);
+        let matrix = self.view_matrix.then(&Affine::from(matrix));
+        let Some(inverse) = matrix.inverse() else {
+            return;
+        };
+
+        let texture = as_bitmap(&bitmap);
+        let (width, height) = (texture.width() as f32, texture.height() as f32);
+        let uv_matrix = Affine {
+            a: 1.0 / width,
+            d: 1.0 / height,
+            ..Affine::IDENTITY
+        }
+        .then(&inverse);
+        let smoothing = self.smoothing(smoothing);
+        let pixels = texture.pixels();
+        let color_transform = transform.color_transform;
+
+        self.fill_quad(&matrix, width, height, |point, _, _| {
+            let color = sample_bitmap(texture, &pixels, uv_matrix.apply(point), smoothing, false);
+            if color[3] > 0.0 {
+                transform_color(color, &color_transform)
+            } else {
+                TRANSPARENT
+            }
+        });
+    }
+
+    fn render_stage3d(&mut self, bitmap: BitmapHandle, transform: Transform) {
+        self.render_bitmap(bitmap, transform, false, PixelSnapping::Never);
+    }
+
+    fn render_shape(&mut self, shape: ShapeHandle, transform: Transform) {
+        let Some(mesh) = <dyn Any>::downcast_ref::<Mesh>(&*shape.0) else {
+            return;
+        };
+        let matrix = self.view_matrix.then(&Affine::from(transform.matrix));
+        let Some(inverse) = matrix.inverse() else {
+            return;
+        };
+        let color_transform = transform.color_transform;
+        let is_mask = self.mask_state == MaskState::DrawMask;
+
+        for draw in &mesh.draws {
+            let positions: Vec<_> = draw
+                .vertices
+                .iter()
+                .map(|vertex| matrix.apply(vertex.position))
+                .collect();
+            let indices = if is_mask {
+                &draw.indices[..draw.num_mask_indices.min(draw.indices.len())]
+            } else {
+                &draw.indices[..]
+            };
+
+            match &draw.paint {
+                Paint::Color => {
+                    self.fill_triangles(&positions, indices, |_, triangle, weights| {
+                        let mut color = [0.0; 4];
+                        for (vertex, weight) in triangle.into_iter().zip(weights) {
+                            for (channel, value) in
+                                color.iter_mut().zip(draw.vertices[vertex].color)
+                            {
+                                *channel += value * weight;
+                            }
+                        }
+                        transform_color(color, &color_transform)
+                    });
+                }
+                Paint::Gradient {
+                    matrix: gradient_matrix,
+                    gradient,
+                } => {
+                    let uv_matrix = gradient_matrix.then(&inverse);
+                    self.fill_triangles(&positions, indices, |point, _, _| {
+                        transform_color(gradient.sample(uv_matrix.apply(point)), &color_transform)
+                    });
+                }
+                Paint::Bitmap {
+                    matrix: bitmap_matrix,
+                    bitmap,
+                    is_smoothed,
+                    is_repeating,
+                } => {
+                    let texture = as_bitmap(bitmap);
+                    let pixels = texture.pixels();
+                    let uv_matrix = bitmap_matrix.then(&inverse);
+                    let smoothing = self.smoothing(*is_smoothed);
+                    self.fill_triangles(&positions, indices, |point, _, _| {
+                        let color = sample_bitmap(
+                            texture,
+                            &pixels,
+                            uv_matrix.apply(point),
+                            smoothing,
+                            *is_repeating,
+                        );
+                        if color[3] > 0.0 {
+                            transform_color(color, &color_transform)
+                        } else {
+                            TRANSPARENT
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    fn render_alpha_mask(&mut self, maskee_commands: CommandList, mask_commands: CommandList) {
+        let mut maskee = self.render_layer(maskee_commands);
+        let mask = self.render_layer(mask_commands);
+        for (pixel, mask) in maskee.pixels_mut().iter_mut().zip(mask.pixels()) {
+            *pixel = pixel.map(|channel| channel * mask[3]);
+        }
+        self.composite(&maskee, BlendMode::Normal);
+    }
+
+    fn draw_rect(&mut self, color: Color, matrix: Matrix) {
+        let matrix = self.view_matrix.then(&Affine::from(matrix));
+        let color = color_to_pixel(color);
+        self.fill_quad(&matrix, 1.0, 1.0, |_, _, _| color);
+    }
+
+    fn draw_line(&mut self, color: Color, matrix: Matrix) {
+        self.stroke_lines(color, matrix, &[Point::new(0.0, 0.0), Point::new(1.0, 0.0)]);
+    }
+
+    fn draw_line_rect(&mut self, color: Color, matrix: Matrix) {
+        self.stroke_lines(
+            color,
+            matrix,
+            &[
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 0.0),
+                Point::new(1.0, 1.0),
+                Point::new(0.0, 1.0),
+                Point::new(0.0, 0.0),
+            ],
+        );
+    }
+
+    fn push_mask(&mut self) {
+        debug_assert!(
+            self.mask_state == MaskState::NoMask || self.mask_state == MaskState::DrawMaskedContent
+        );
+        let len = self.target.width() as usize * self.target.height() as usize;
+        self.masks.push(vec![false; len]);
+        self.mask_state = MaskState::DrawMask;
+    }
+
+    fn activate_mask(&mut self) {
+        debug_assert!(self.mask_state == MaskState::DrawMask);
+        self.mask_state = MaskState::DrawMaskedContent;
+    }
+
+    fn deactivate_mask(&mut self) {
+        debug_assert!(self.mask_state == MaskState::DrawMaskedContent);
+        self.mask_state = MaskState::ClearMask;
+    }
+
+    fn pop_mask(&mut self) {
+        self.masks.pop();
+        self.mask_state = if self.masks.is_empty() {
+            MaskState::NoMask
+        } else {
+            MaskState::DrawMaskedContent
+        };
+    }
+
+    fn blend(&mut self, commands: CommandList, blend_mode: RenderBlendMode) {
+        let blend_mode = match blend_mode {
+            RenderBlendMode::Builtin(BlendMode::Normal) => {
+                commands.execute(self);
+                return;
+            }
+            RenderBlendMode::Builtin(blend_mode) => blend_mode,
+            RenderBlendMode::Shader(shader) => {
+                let layer = self.render_layer(commands);
+                self.blend_shader(&layer, &shader);
+                return;
+            }
+        };
+        let layer = self.render_layer(commands);
+        self.composite(&layer, blend_mode);
+    }
+}
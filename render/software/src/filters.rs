@@ -0,0 +1,284 @@
+//! CPU implementations of the filters that are most commonly used.
+//!
+//! These are simpler than the ones of the GPU backends: every blur is a plain box blur,
+//! and shadows are offset by whole pixels.
+
+use crate::surface::{color_to_pixel, premultiply, unmultiply, Pixel, Surface, TRANSPARENT};
+use ruffle_render::filters::Filter;
+use swf::{BlurFilter, ColorMatrixFilter, DropShadowFilter, GlowFilter};
+
+pub fn is_filter_supported(filter: &Filter) -> bool {
+    matches!(
+        filter,
+        Filter::BlurFilter(_)
+            | Filter::ColorMatrixFilter(_)
+            | Filter::GlowFilter(_)
+            | Filter::DropShadowFilter(_)
+    )
+}
+
+/// Applies `filter` to `surface` in place, keeping its size.
+///
+/// Returns `false` if the filter isn't supported, leaving the surface untouched.
+pub fn apply_filter(surface: &mut Surface, filter: &Filter) -> bool {
+    match filter {
+        Filter::BlurFilter(filter) => apply_blur(surface, filter),
+        Filter::ColorMatrixFilter(filter) => apply_color_matrix(surface, filter),
+        Filter::GlowFilter(filter) => apply_glow(surface, filter, (0, 0)),
+        Filter::DropShadowFilter(filter) => apply_drop_shadow(surface, filter),
+        _ => return false,
+    }
+    true
+}
+
+fn apply_blur(surface: &mut Surface, filter: &BlurFilter) {
+    let (width, height) = (surface.width(), surface.height());
+    box_blur(
+        surface.pixels_mut(),
+        width,
+        height,
+        filter.blur_x.to_f32(),
+        filter.blur_y.to_f32(),
+        filter.num_passes(),
+    );
+}
+
+fn apply_color_matrix(surface: &mut Surface, filter: &ColorMatrixFilter) {
+    let matrix = &filter.matrix;
+    for pixel in surface.pixels_mut() {
+        let color = unmultiply(*pixel);
+        let mut result = [0.0; 4];
+        for (channel, row) in result.iter_mut().zip(matrix.chunks_exact(5)) {
+            let value = row[0] * color[0]
+                + row[1] * color[1]
+                + row[2] * color[2]
+                + row[3] * color[3]
+                + row[4] / 255.0;
+            *channel = value.clamp(0.0, 1.0);
+        }
+        *pixel = premultiply(result);
+    }
+}
+
+fn apply_drop_shadow(surface: &mut Surface, filter: &DropShadowFilter) {
+    let distance = filter.distance.to_f64();
+    let angle = filter.angle.to_f64();
+    let offset = (
+        (angle.cos() * distance).round() as i32,
+        (angle.sin() * distance).round() as i32,
+    );
+    apply_glow(surface, &filter.inner_glow_filter(), offset);
+}
+
+/// Draws a glow around (or inside) the source, offset by `offset` pixels for drop shadows.
+fn apply_glow(surface: &mut Surface, filter: &GlowFilter, offset: (i32, i32)) {
+    let (width, height) = (surface.width(), surface.height());
+    let inner = filter.is_inner();
+    let source_alpha = |x: i32, y: i32| {
+        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+            0.0
+        } else {
+            surface.pixels()[(y as u32 * width + x as u32) as usize][3]
+        }
+    };
+
+    let (blur_x, blur_y) = (filter.blur_x.to_f32(), filter.blur_y.to_f32());
+    let passes = filter.num_passes();
+
+    // An inner glow spreads from the transparent area around the source inwards.
+    // That area continues past the edges of the surface, so the mask is padded by
+    // as far as the blur reaches for the glow to also spread in from the edges.
+    let (pad_x, pad_y) = if inner {
+        (blur_reach(blur_x, passes), blur_reach(blur_y, passes))
+    } else {
+        (0, 0)
+    };
+    let (mask_width, mask_height) = (width + 2 * pad_x, height + 2 * pad_y);
+    let mut mask = Vec::with_capacity(mask_width as usize * mask_height as usize);
+    for y in 0..mask_height as i32 {
+        for x in 0..mask_width as i32 {
+            let alpha = source_alpha(x - pad_x as i32 - offset.0, y - pad_y as i32 - offset.1);
+            let alpha = if inner { 1.0 - alpha } else { alpha };
+            mask.push([0.0, 0.0, 0.0, alpha]);
+        }
+    }
+    box_blur(&mut mask, mask_width, mask_height, blur_x, blur_y, passes);
+    let mask = mask
+        .chunks_exact(mask_width as usize)
+        .skip(pad_y as usize)
+        .take(height as usize)
+        .flat_map(|row| &row[pad_x as usize..(pad_x + width) as usize]);
+
+    let color = color_to_pixel(filter.color);
+    let strength = filter.strength.to_f32();
+    for (pixel, mask) in surface.pixels_mut().iter_mut().zip(mask) {
+        let coverage = (mask[3] * strength).min(1.0);
+        let glow = color.map(|channel| channel * coverage);
+        let source = *pixel;
+        let source_alpha = source[3];
+        *pixel = if inner {
+            if filter.is_knockout() || !filter.composite_source() {
+                glow.map(|channel| channel * source_alpha)
+            } else {
+                // The glow is drawn on top of the source, but only where the source is.
+                let mut result = [0.0; 4];
+                for i in 0..3 {
+                    result[i] = glow[i] * source_alpha + source[i] * (1.0 - glow[3]);
+                }
+                result[3] = source_alpha;
+                result
+            }
+        } else if filter.is_knockout() {
+            glow.map(|channel| channel * (1.0 - source_alpha))
+        } else if filter.composite_source() {
+            let mut result = TRANSPARENT;
+            for i in 0..4 {
+                result[i] = source[i] + glow[i] * (1.0 - source_alpha);
+            }
+            result
+        } else {
+            glow
+        };
+    }
+}
+
+/// How many pixels away from a pixel a blur of `size` pixels can spread it, over all `passes`.
+fn blur_reach(size: f32, passes: u8) -> u32 {
+    (size / 2.0).ceil() as u32 * passes as u32
+}
+
+/// Blurs `pixels` with a box of `blur_x` by `blur_y` pixels, `passes` times.
+///
+/// Pixels outside of the image are treated as transparent.
+fn box_blur(pixels: &mut [Pixel], width: u32, height: u32, blur_x: f32, blur_y: f32, passes: u8) {
+    let (width, height) = (width as usize, height as usize);
+    for _ in 0..passes {
+        if blur_x > 1.0 {
+            for row in pixels.chunks_exact_mut(width) {
+                let line: Vec<Pixel> = row.to_vec();
+                let blurred = box_blur_line(&line, blur_x);
+                row.copy_from_slice(&blurred);
+            }
+        }
+        if blur_y > 1.0 {
+            for x in 0..width {
+                let line: Vec<Pixel> = (0..height).map(|y| pixels[y * width + x]).collect();
+                for (y, pixel) in box_blur_line(&line, blur_y).into_iter().enumerate() {
+                    pixels[y * width + x] = pixel;
+                }
+            }
+        }
+    }
+}
+
+/// Averages every pixel of `line` with its neighbours in a box `size` pixels wide.
+///
+/// A fractional size gives the outermost pixels of the box a partial weight.
+fn box_blur_line(line: &[Pixel], size: f32) -> Vec<Pixel> {
+    let half = (size - 1.0) / 2.0;
+    let radius = half.floor() as isize;
+    let edge_weight = half - radius as f32;
+
+    // `sums[i]` is the sum of every pixel before `i`.
+    let mut sums = Vec::with_capacity(line.len() + 1);
+    let mut sum = TRANSPARENT;
+    sums.push(sum);
+    for pixel in line {
+        for (sum, channel) in sum.iter_mut().zip(pixel) {
+            *sum += channel;
+        }
+        sums.push(sum);
+    }
+
+    let len = line.len() as isize;
+    let at = |index: isize| {
+        if index < 0 || index >= len {
+            TRANSPARENT
+        } else {
+            line[index as usize]
+        }
+    };
+    (0..len)
+        .map(|center| {
+            let start = (center - radius).clamp(0, len) as usize;
+            let end = (center + radius + 1).clamp(0, len) as usize;
+            let (before, after) = (at(center - radius - 1), at(center + radius + 1));
+            let mut result = TRANSPARENT;
+            for i in 0..4 {
+                let total = sums[end][i] - sums[start][i] + (before[i] + after[i]) * edge_weight;
+                result[i] = (total / size).clamp(0.0, 1.0);
+            }
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::{BlurFilterFlags, Color, Fixed16, Fixed8, GlowFilterFlags};
+
+    #[test]
+    fn blur_spreads_and_preserves_total() {
+        let mut line = vec![TRANSPARENT; 5];
+        line[2] = [0.0, 0.0, 0.0, 1.0];
+        let blurred = box_blur_line(&line, 3.0);
+        let total: f32 = blurred.iter().map(|pixel| pixel[3]).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!((blurred[1][3] - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(blurred[0][3], 0.0);
+    }
+
+    #[test]
+    fn small_blur_is_noop() {
+        let mut surface = Surface::new(3, 1, TRANSPARENT);
+        surface.pixels_mut()[1] = [1.0, 0.0, 0.0, 1.0];
+        let filter = Filter::BlurFilter(BlurFilter {
+            blur_x: Fixed16::ONE,
+            blur_y: Fixed16::ONE,
+            flags: BlurFilterFlags::from_passes(1),
+        });
+        assert!(apply_filter(&mut surface, &filter));
+        assert_eq!(surface.pixels()[0], TRANSPARENT);
+        assert_eq!(surface.pixels()[1], [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn color_matrix_swaps_channels() {
+        let mut surface = Surface::new(1, 1, [1.0, 0.0, 0.0, 1.0]);
+        #[rustfmt::skip]
+        let matrix = [
+            0.0, 0.0, 1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ];
+        let filter = Filter::ColorMatrixFilter(ColorMatrixFilter { matrix });
+        assert!(apply_filter(&mut surface, &filter));
+        assert_eq!(surface.pixels()[0], [0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn inner_glow_spreads_from_surface_edges() {
+        let mut surface = Surface::new(5, 5, [1.0, 1.0, 1.0, 1.0]);
+        let filter = Filter::GlowFilter(GlowFilter {
+            color: Color::from_rgba(0xFFFF0000),
+            blur_x: Fixed16::from_f32(3.0),
+            blur_y: Fixed16::from_f32(3.0),
+            strength: Fixed8::ONE,
+            flags: GlowFilterFlags::INNER_GLOW
+                | GlowFilterFlags::COMPOSITE_SOURCE
+                | GlowFilterFlags::from_passes(1),
+        });
+        assert!(apply_filter(&mut surface, &filter));
+
+        let pixel = |x: usize, y: usize| surface.pixels()[y * 5 + x];
+        // The pixels along the edges border on the transparent area outside of the surface.
+        assert!(pixel(0, 2)[1] < 1.0);
+        assert!(pixel(2, 4)[1] < 1.0);
+        assert!(pixel(0, 0)[1] < pixel(0, 2)[1]);
+        // The blur doesn't reach the center.
+        assert_eq!(pixel(2, 2), [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(pixel(0, 2)[3], 1.0);
+    }
+}
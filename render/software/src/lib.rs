@@ -0,0 +1,14 @@
+//! A render backend that draws everything on the CPU, for use where no GPU is available.
+
+// Handles are shared through `Arc`, but nothing in this backend is used across threads.
+#![allow(clippy::arc_with_non_send_sync)]
+
+mod backend;
+mod bitmap;
+mod commands;
+mod filters;
+mod mesh;
+mod raster;
+mod surface;
+
+pub use backend::SoftwareRenderBackend;
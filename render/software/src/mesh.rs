@@ -0,0 +1,308 @@
+use crate::bitmap::SoftwareBitmap;
+use crate::raster::{Affine, Point};
+use crate::surface::{premultiply, Pixel, TRANSPARENT};
+use ruffle_render::backend::{RenderBackend, ShapeHandleImpl};
+use ruffle_render::bitmap::{BitmapHandle, BitmapSource};
+use ruffle_render::shape_utils::GradientType;
+use ruffle_render::tessellator::{
+    DrawType as TessDrawType, Gradient as TessGradient, Mesh as TessMesh,
+};
+use std::sync::Arc;
+use swf::{ColorTransform, GradientInterpolation, GradientSpread};
+
+const GRADIENT_SIZE: usize = 256;
+
+#[derive(Debug)]
+pub struct Mesh {
+    pub draws: Vec<Draw>,
+}
+
+impl ShapeHandleImpl for Mesh {}
+
+#[derive(Debug)]
+pub struct Draw {
+    pub paint: Paint,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub num_mask_indices: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Vertex {
+    pub position: Point,
+    /// The straight (non-premultiplied) color of this vertex.
+    pub color: Pixel,
+}
+
+#[derive(Debug)]
+pub enum Paint {
+    Color,
+    Gradient {
+        /// Maps shape coordinates to gradient coordinates.
+        matrix: Affine,
+        gradient: Arc<GradientRamp>,
+    },
+    Bitmap {
+        /// Maps shape coordinates to normalized texture coordinates.
+        matrix: Affine,
+        bitmap: BitmapHandle,
+        is_smoothed: bool,
+        is_repeating: bool,
+    },
+}
+
+impl Mesh {
+    pub fn new(
+        mesh: TessMesh,
+        bitmap_source: &dyn BitmapSource,
+        backend: &mut dyn RenderBackend,
+    ) -> Self {
+        let gradients: Vec<_> = mesh
+            .gradients
+            .into_iter()
+            .map(|gradient| Arc::new(GradientRamp::new(gradient)))
+            .collect();
+
+        let mut draws = Vec::with_capacity(mesh.draws.len());
+        for draw in mesh.draws {
+            let paint = match draw.draw_type {
+                TessDrawType::Color => Paint::Color,
+                TessDrawType::Gradient { matrix, gradient } => Paint::Gradient {
+                    matrix: Affine::from_gl(matrix),
+                    gradient: gradients[gradient].clone(),
+                },
+                TessDrawType::Bitmap(bitmap) => {
+                    let Some(handle) = bitmap_source.bitmap_handle(bitmap.bitmap_id, backend)
+                    else {
+                        tracing::warn!("Couldn't find bitmap {} for shape", bitmap.bitmap_id);
+                        continue;
+                    };
+                    Paint::Bitmap {
+                        matrix: Affine::from_gl(bitmap.matrix),
+                        bitmap: handle,
+                        is_smoothed: bitmap.is_smoothed,
+                        is_repeating: bitmap.is_repeating,
+                    }
+                }
+                TessDrawType::Shader(_) => {
+                    tracing::warn!("Shader fills are not supported by the software renderer");
+                    continue;
+                }
+            };
+            let vertices = draw
+                .vertices
+                .into_iter()
+                .map(|vertex| Vertex {
+                    position: Point::new(vertex.x, vertex.y),
+                    color: [
+                        f32::from(vertex.color.r) / 255.0,
+                        f32::from(vertex.color.g) / 255.0,
+                        f32::from(vertex.color.b) / 255.0,
+                        f32::from(vertex.color.a) / 255.0,
+                    ],
+                })
+                .collect();
+            draws.push(Draw {
+                paint,
+                vertices,
+                indices: draw.indices,
+                num_mask_indices: draw.mask_index_count as usize,
+            });
+        }
+        Self { draws }
+    }
+}
+
+/// A gradient baked into a lookup table, matching the textures used by the GPU backends.
+#[derive(Debug)]
+pub struct GradientRamp {
+    colors: Box<[Pixel; GRADIENT_SIZE]>,
+    gradient_type: GradientType,
+    spread: GradientSpread,
+    focal_point: f32,
+    linear_rgb: bool,
+}
+
+impl GradientRamp {
+    fn new(gradient: TessGradient) -> Self {
+        let linear_rgb = gradient.interpolation == GradientInterpolation::LinearRgb;
+        let mut colors = Box::new([TRANSPARENT; GRADIENT_SIZE]);
+        if !gradient.records.is_empty() {
+            let convert = |c: u8| {
+                let c = f32::from(c) / 255.0;
+                if linear_rgb {
+                    srgb_to_linear(c)
+                } else {
+                    c
+                }
+            };
+            for (t, color) in colors.iter_mut().enumerate() {
+                let next = gradient
+                    .records
+                    .iter()
+                    .position(|record| record.ratio as usize >= t)
+                    .unwrap_or(gradient.records.len());
+                let last = next.saturating_sub(1);
+                let next = next.min(gradient.records.len() - 1);
+                let (last, next) = (&gradient.records[last], &gradient.records[next]);
+
+                let a = if next.ratio > last.ratio {
+                    (t as f32 - f32::from(last.ratio)) / f32::from(next.ratio - last.ratio)
+                } else {
+                    0.0
+                };
+                *color = [
+                    lerp(convert(last.color.r), convert(next.color.r), a),
+                    lerp(convert(last.color.g), convert(next.color.g), a),
+                    lerp(convert(last.color.b), convert(next.color.b), a),
+                    lerp(
+                        f32::from(last.color.a) / 255.0,
+                        f32::from(next.color.a) / 255.0,
+                        a,
+                    ),
+                ];
+            }
+        }
+        Self {
+            colors,
+            gradient_type: gradient.gradient_type,
+            spread: gradient.repeat_mode,
+            focal_point: gradient.focal_point.to_f32(),
+            linear_rgb,
+        }
+    }
+
+    /// Returns the straight color of the gradient at the given gradient coordinates.
+    pub fn sample(&self, uv: Point) -> Pixel {
+        let t = match self.gradient_type {
+            GradientType::Linear => uv.x,
+            GradientType::Radial => {
+                let (x, y) = (uv.x * 2.0 - 1.0, uv.y * 2.0 - 1.0);
+                (x * x + y * y).sqrt()
+            }
+            GradientType::Focal => {
+                let (x, y) = (uv.x * 2.0 - 1.0, uv.y * 2.0 - 1.0);
+                let focal = self.focal_point;
+                let (dx, dy) = (focal - x, -y);
+                let length = (dx * dx + dy * dy).sqrt();
+                if length > 0.0 {
+                    let (dx, dy) = (dx / length, dy / length);
+                    length / ((1.0 - focal * focal * dy * dy).sqrt() + focal * dx)
+                } else {
+                    0.0
+                }
+            }
+        };
+        let t = match self.spread {
+            GradientSpread::Pad => t.clamp(0.0, 1.0),
+            GradientSpread::Reflect => {
+                let t = t.abs();
+                if (t as i32) & 1 == 0 {
+                    t.fract()
+                } else {
+                    1.0 - t.fract()
+                }
+            }
+            GradientSpread::Repeat => t - t.floor(),
+        };
+
+        // Linearly filter between the two nearest entries, like a texture lookup would.
+        let position = (t * GRADIENT_SIZE as f32 - 0.5).clamp(0.0, (GRADIENT_SIZE - 1) as f32);
+        let index = position as usize;
+        let next = (index + 1).min(GRADIENT_SIZE - 1);
+        let fraction = position - index as f32;
+        let mut color = [0.0; 4];
+        for (i, channel) in color.iter_mut().enumerate() {
+            *channel = lerp(self.colors[index][i], self.colors[next][i], fraction);
+        }
+        if self.linear_rgb {
+            for channel in &mut color[..3] {
+                *channel = linear_to_srgb(*channel);
+            }
+        }
+        color
+    }
+}
+
+/// Applies a color transform to a straight color, returning a premultiplied pixel.
+pub fn transform_color(color: Pixel, color_transform: &ColorTransform) -> Pixel {
+    let mult = color_transform.mult_rgba_normalized();
+    let add = color_transform.add_rgba_normalized();
+    let mut out = [0.0; 4];
+    for i in 0..4 {
+        out[i] = (color[i] * mult[i] + add[i]).clamp(0.0, 1.0);
+    }
+    premultiply(out)
+}
+
+/// Samples a bitmap at the given normalized texture coordinates,
+/// returning a straight color.
+pub fn sample_bitmap(
+    bitmap: &SoftwareBitmap,
+    pixels: &[u8],
+    uv: Point,
+    smoothed: bool,
+    repeating: bool,
+) -> Pixel {
+    let (width, height) = (bitmap.width() as i32, bitmap.height() as i32);
+    if width == 0 || height == 0 {
+        return TRANSPARENT;
+    }
+    let texel = |x: i32, y: i32| -> Pixel {
+        let (x, y) = if repeating {
+            (x.rem_euclid(width), y.rem_euclid(height))
+        } else {
+            (x.clamp(0, width - 1), y.clamp(0, height - 1))
+        };
+        let index = ((y * width + x) * 4) as usize;
+        [
+            f32::from(pixels[index]) / 255.0,
+            f32::from(pixels[index + 1]) / 255.0,
+            f32::from(pixels[index + 2]) / 255.0,
+            f32::from(pixels[index + 3]) / 255.0,
+        ]
+    };
+
+    let x = uv.x * width as f32;
+    let y = uv.y * height as f32;
+    let premultiplied = if smoothed {
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let [p00, p10, p01, p11] = [
+            texel(x0, y0),
+            texel(x0 + 1, y0),
+            texel(x0, y0 + 1),
+            texel(x0 + 1, y0 + 1),
+        ];
+        let mut color = [0.0; 4];
+        for (i, channel) in color.iter_mut().enumerate() {
+            *channel = lerp(lerp(p00[i], p10[i], fx), lerp(p01[i], p11[i], fx), fy);
+        }
+        color
+    } else {
+        texel(x.floor() as i32, y.floor() as i32)
+    };
+    crate::surface::unmultiply(premultiplied)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn srgb_to_linear(color: f32) -> f32 {
+    if color <= 0.04045 {
+        color / 12.92
+    } else {
+        f32::powf((color + 0.055) / 1.055, 2.4)
+    }
+}
+
+fn linear_to_srgb(color: f32) -> f32 {
+    if color <= 0.0031308 {
+        color * 12.92
+    } else {
+        1.055 * f32::powf(color, 1.0 / 2.4) - 0.055
+    }
+}
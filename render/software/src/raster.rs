@@ -0,0 +1,224 @@
+use ruffle_render::matrix::Matrix;
+
+/// A point on a surface, in surface pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A 2D affine transform, mapping `(x, y)` to `(a * x + c * y + tx, b * x + d * y + ty)`.
+///
+/// Unlike [`Matrix`], the translation is stored in pixels rather than twips,
+/// and every component is a plain float.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Affine {
+    pub const IDENTITY: Self = Self::scale(1.0);
+
+    pub const fn scale(scale: f32) -> Self {
+        Self {
+            a: scale,
+            b: 0.0,
+            c: 0.0,
+            d: scale,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+
+    /// Converts a texture matrix produced by the tessellator, which is laid out
+    /// column-major like a GL `mat3`.
+    pub fn from_gl(matrix: [[f32; 3]; 3]) -> Self {
+        Self {
+            a: matrix[0][0],
+            b: matrix[0][1],
+            c: matrix[1][0],
+            d: matrix[1][1],
+            tx: matrix[2][0],
+            ty: matrix[2][1],
+        }
+    }
+
+    pub fn apply(&self, point: Point) -> Point {
+        Point {
+            x: self.a * point.x + self.c * point.y + self.tx,
+            y: self.b * point.x + self.d * point.y + self.ty,
+        }
+    }
+
+    /// Returns the transform that applies `other` first, and then `self`.
+    pub fn then(&self, other: &Affine) -> Affine {
+        Affine {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            tx: self.a * other.tx + self.c * other.ty + self.tx,
+            ty: self.b * other.tx + self.d * other.ty + self.ty,
+        }
+    }
+
+    pub fn inverse(&self) -> Option<Affine> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let a = self.d / det;
+        let b = -self.b / det;
+        let c = -self.c / det;
+        let d = self.a / det;
+        Some(Affine {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+}
+
+impl From<Matrix> for Affine {
+    fn from(matrix: Matrix) -> Self {
+        Self {
+            a: matrix.a,
+            b: matrix.b,
+            c: matrix.c,
+            d: matrix.d,
+            tx: matrix.tx.to_pixels() as f32,
+            ty: matrix.ty.to_pixels() as f32,
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `[from, to, point]`.
+fn edge(from: Point, to: Point, point: Point) -> f32 {
+    (to.x - from.x) * (point.y - from.y) - (to.y - from.y) * (point.x - from.x)
+}
+
+/// Whether a pixel center lying exactly on this edge belongs to the triangle.
+///
+/// Two adjacent triangles with the same winding walk their shared edge in opposite
+/// directions, so exactly one of them owns it and no pixel is ever drawn twice.
+fn owns_edge(from: Point, to: Point) -> bool {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    dy < 0.0 || (dy == 0.0 && dx > 0.0)
+}
+
+/// Calls `shade` for every pixel of a `width` x `height` surface whose center lies
+/// inside the given triangle, along with the barycentric weights of each vertex.
+pub fn fill_triangle(
+    width: u32,
+    height: u32,
+    vertices: [Point; 3],
+    mut shade: impl FnMut(u32, u32, [f32; 3]),
+) {
+    let [a, mut b, mut c] = vertices;
+    let mut area = edge(a, b, c);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+    let flipped = area < 0.0;
+    if flipped {
+        std::mem::swap(&mut b, &mut c);
+        area = -area;
+    }
+
+    let x_min = a.x.min(b.x).min(c.x).floor().max(0.0) as u32;
+    let y_min = a.y.min(b.y).min(c.y).floor().max(0.0) as u32;
+    let x_max = (a.x.max(b.x).max(c.x).ceil().max(0.0) as u32).min(width);
+    let y_max = (a.y.max(b.y).max(c.y).ceil().max(0.0) as u32).min(height);
+
+    let owns = [owns_edge(b, c), owns_edge(c, a), owns_edge(a, b)];
+    let inside = |value: f32, owned: bool| value > 0.0 || (value == 0.0 && owned);
+
+    for y in y_min..y_max {
+        for x in x_min..x_max {
+            let point = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+            let w_a = edge(b, c, point);
+            let w_b = edge(c, a, point);
+            let w_c = edge(a, b, point);
+            if inside(w_a, owns[0]) && inside(w_b, owns[1]) && inside(w_c, owns[2]) {
+                let (w_b, w_c) = if flipped { (w_c, w_b) } else { (w_b, w_c) };
+                shade(x, y, [w_a / area, w_b / area, w_c / area]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coverage(width: u32, height: u32, triangles: &[[Point; 3]]) -> Vec<u32> {
+        let mut hits = vec![0; (width * height) as usize];
+        for triangle in triangles {
+            fill_triangle(width, height, *triangle, |x, y, _| {
+                hits[(y * width + x) as usize] += 1;
+            });
+        }
+        hits
+    }
+
+    #[test]
+    fn shared_edges_are_drawn_once() {
+        let (p0, p1, p2, p3) = (
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 4.0),
+            Point::new(0.0, 4.0),
+        );
+        let hits = coverage(4, 4, &[[p0, p1, p2], [p0, p2, p3]]);
+        assert_eq!(hits, vec![1; 16]);
+
+        // The same quad, with the second triangle wound the other way.
+        let hits = coverage(4, 4, &[[p0, p1, p2], [p0, p3, p2]]);
+        assert_eq!(hits, vec![1; 16]);
+    }
+
+    #[test]
+    fn barycentric_weights_interpolate() {
+        let triangle = [
+            Point::new(0.0, 0.0),
+            Point::new(8.0, 0.0),
+            Point::new(0.0, 8.0),
+        ];
+        fill_triangle(8, 8, triangle, |x, y, weights| {
+            let interpolated = weights[1] * 8.0;
+            assert!((interpolated - (x as f32 + 0.5)).abs() < 1e-4);
+            let interpolated = weights[2] * 8.0;
+            assert!((interpolated - (y as f32 + 0.5)).abs() < 1e-4);
+        });
+    }
+
+    #[test]
+    fn affine_inverse_roundtrips() {
+        let transform = Affine {
+            a: 2.0,
+            b: 0.5,
+            c: -1.0,
+            d: 3.0,
+            tx: 10.0,
+            ty: -4.0,
+        };
+        let point = Point::new(3.0, 7.0);
+        let roundtrip = transform.inverse().unwrap().apply(transform.apply(point));
+        assert!((roundtrip.x - point.x).abs() < 1e-4);
+        assert!((roundtrip.y - point.y).abs() < 1e-4);
+    }
+}
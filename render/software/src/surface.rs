@@ -0,0 +1,209 @@
+use swf::{BlendMode, Color};
+
+/// A premultiplied RGBA color, with every channel in the range `[0.0, 1.0]`.
+pub type Pixel = [f32; 4];
+
+pub const TRANSPARENT: Pixel = [0.0; 4];
+
+pub fn premultiply([r, g, b, a]: Pixel) -> Pixel {
+    [r * a, g * a, b * a, a]
+}
+
+pub fn unmultiply([r, g, b, a]: Pixel) -> Pixel {
+    if a > 0.0 {
+        [r / a, g / a, b / a, a]
+    } else {
+        TRANSPARENT
+    }
+}
+
+pub fn color_to_pixel(color: Color) -> Pixel {
+    premultiply([
+        f32::from(color.r) / 255.0,
+        f32::from(color.g) / 255.0,
+        f32::from(color.b) / 255.0,
+        f32::from(color.a) / 255.0,
+    ])
+}
+
+/// A grid of premultiplied pixels that commands are rendered onto.
+///
+/// Surfaces are usually supersampled, and only scaled down to
+/// their real size once rendering is complete.
+#[derive(Clone, Debug)]
+pub struct Surface {
+    width: u32,
+    height: u32,
+    pixels: Vec<Pixel>,
+}
+
+impl Surface {
+    pub fn new(width: u32, height: u32, fill: Pixel) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width as usize * height as usize],
+        }
+    }
+
+    /// Creates a surface from premultiplied RGBA8 data, scaling every pixel up by `scale`.
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8], scale: u32) -> Self {
+        let mut surface = Self::new(width * scale, height * scale, TRANSPARENT);
+        for y in 0..surface.height {
+            let row = (y / scale * width) as usize;
+            for x in 0..surface.width {
+                let index = (row + (x / scale) as usize) * 4;
+                surface.pixels[(y * surface.width + x) as usize] = [
+                    f32::from(rgba[index]) / 255.0,
+                    f32::from(rgba[index + 1]) / 255.0,
+                    f32::from(rgba[index + 2]) / 255.0,
+                    f32::from(rgba[index + 3]) / 255.0,
+                ];
+            }
+        }
+        surface
+    }
+
+    /// Converts this surface to premultiplied RGBA8 data, averaging every `scale` x `scale` block.
+    pub fn to_rgba(&self, scale: u32) -> Vec<u8> {
+        let width = self.width / scale;
+        let height = self.height / scale;
+        let samples = (scale * scale) as f32;
+        let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = TRANSPARENT;
+                for sample_y in y * scale..(y + 1) * scale {
+                    for sample_x in x * scale..(x + 1) * scale {
+                        let pixel = self.pixels[(sample_y * self.width + sample_x) as usize];
+                        for (sum, channel) in sum.iter_mut().zip(pixel) {
+                            *sum += channel;
+                        }
+                    }
+                }
+                rgba.extend(
+                    sum.map(|channel| (channel / samples * 255.0).round().clamp(0.0, 255.0) as u8),
+                );
+            }
+        }
+        rgba
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Pixel] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Pixel] {
+        &mut self.pixels
+    }
+}
+
+/// Blends the premultiplied `src` onto the premultiplied `dst`, in the same way as Flash.
+pub fn blend(src: Pixel, dst: Pixel, mode: BlendMode) -> Pixel {
+    let [sr, sg, sb, sa] = src;
+    let [dr, dg, db, da] = dst;
+    let over_alpha = sa + da * (1.0 - sa);
+    match mode {
+        BlendMode::Normal | BlendMode::Layer => [
+            sr + dr * (1.0 - sa),
+            sg + dg * (1.0 - sa),
+            sb + db * (1.0 - sa),
+            over_alpha,
+        ],
+        BlendMode::Add => [
+            (sr + dr).min(1.0),
+            (sg + dg).min(1.0),
+            (sb + db).min(1.0),
+            over_alpha,
+        ],
+        BlendMode::Subtract => [
+            (dr - sr).max(0.0),
+            (dg - sg).max(0.0),
+            (db - sb).max(0.0),
+            over_alpha,
+        ],
+        BlendMode::Screen => [
+            sr + dr * (1.0 - sr),
+            sg + dg * (1.0 - sg),
+            sb + db * (1.0 - sb),
+            over_alpha,
+        ],
+        // The remaining modes leave the destination untouched wherever the source is transparent.
+        _ if sa <= 0.0 => dst,
+        BlendMode::Alpha => [dr * sa, dg * sa, db * sa, da * sa],
+        BlendMode::Erase => [
+            dr * (1.0 - sa),
+            dg * (1.0 - sa),
+            db * (1.0 - sa),
+            da * (1.0 - sa),
+        ],
+        // Flash replaces fully transparent pixels rather than multiplying them.
+        BlendMode::Multiply if da <= 0.0 => src,
+        _ => {
+            let function: fn(f32, f32) -> f32 = match mode {
+                BlendMode::Multiply => |s, d| s * d,
+                BlendMode::Lighten => f32::max,
+                BlendMode::Darken => f32::min,
+                BlendMode::Difference => |s, d| (d - s).abs(),
+                BlendMode::Invert => |_, d| 1.0 - d,
+                BlendMode::Overlay => overlay,
+                BlendMode::HardLight => |s, d| overlay(d, s),
+                _ => unreachable!(),
+            };
+            let [us, ud] = [unmultiply(src), unmultiply(dst)];
+            let mut out = [0.0, 0.0, 0.0, over_alpha];
+            for i in 0..3 {
+                out[i] =
+                    src[i] * (1.0 - da) + dst[i] * (1.0 - sa) + sa * da * function(us[i], ud[i]);
+            }
+            out.map(|channel| channel.clamp(0.0, 1.0))
+        }
+    }
+}
+
+fn overlay(src: f32, dst: f32) -> f32 {
+    if dst <= 0.5 {
+        2.0 * src * dst
+    } else {
+        1.0 - 2.0 * (1.0 - dst) * (1.0 - src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsample_averages_blocks() {
+        let mut surface = Surface::new(2, 2, TRANSPARENT);
+        surface.pixels_mut()[0] = [1.0, 0.0, 0.0, 1.0];
+        surface.pixels_mut()[3] = [0.0, 0.0, 1.0, 1.0];
+        assert_eq!(surface.to_rgba(2), vec![64, 0, 64, 128]);
+    }
+
+    #[test]
+    fn rgba_roundtrips() {
+        let rgba = [10, 20, 30, 40, 0, 0, 0, 0, 255, 255, 255, 255, 1, 2, 3, 4];
+        let surface = Surface::from_rgba(2, 2, &rgba, 3);
+        assert_eq!(surface.width(), 6);
+        assert_eq!(surface.to_rgba(3), rgba);
+    }
+
+    #[test]
+    fn multiply_onto_transparent_replaces() {
+        let src = [0.25, 0.5, 0.0, 0.5];
+        assert_eq!(blend(src, TRANSPARENT, BlendMode::Multiply), src);
+        assert_eq!(
+            blend(src, [1.0, 1.0, 1.0, 1.0], BlendMode::Multiply),
+            [0.75, 1.0, 0.5, 1.0]
+        );
+    }
+}
@@ -1,10 +1,13 @@
 pub mod null;
+#[cfg(feature = "recording")]
+pub mod recording;
 
 use crate::bitmap::{Bitmap, BitmapHandle, BitmapSource, PixelRegion, RgbaBufRead, SyncHandle};
 use crate::commands::CommandList;
 use crate::error::Error;
 use crate::filters::Filter;
 use crate::pixel_bender::{PixelBenderShader, PixelBenderShaderHandle};
+use crate::pixel_bender_interpreter;
 use crate::pixel_bender_support::PixelBenderShaderArgument;
 use crate::quality::StageQuality;
 use crate::shape_utils::DistilledShape;
@@ -94,17 +97,40 @@ pub trait RenderBackend: Any {
 
     fn set_quality(&mut self, quality: StageQuality);
 
+    /// Compiles a Pixel Bender shader for use with `run_pixelbender_shader`.
+    ///
+    /// By default, shaders are run on the CPU by `pixel_bender_interpreter`.
     fn compile_pixelbender_shader(
         &mut self,
         shader: PixelBenderShader,
-    ) -> Result<PixelBenderShaderHandle, Error>;
+    ) -> Result<PixelBenderShaderHandle, Error> {
+        Ok(pixel_bender_interpreter::compile_shader(shader))
+    }
 
+    /// Runs a Pixel Bender shader over every pixel of `target`.
+    ///
+    /// The output might not be ready yet when this returns - see `PixelBenderOutput`.
     fn run_pixelbender_shader(
         &mut self,
         handle: PixelBenderShaderHandle,
         arguments: &[PixelBenderShaderArgument],
         target: &PixelBenderTarget,
-    ) -> Result<PixelBenderOutput, Error>;
+    ) -> Result<PixelBenderOutput, Error> {
+        match target {
+            PixelBenderTarget::Bytes { width, height } => Ok(PixelBenderOutput::Bytes(
+                pixel_bender_interpreter::run_shader(
+                    handle.0.parsed_shader(),
+                    arguments,
+                    *width,
+                    *height,
+                    pixel_bender_interpreter::ShaderMode::ShaderJob,
+                )?,
+            )),
+            PixelBenderTarget::Bitmap(_) => Err(Error::Unimplemented(
+                "Pixel Bender shader with a bitmap target".into(),
+            )),
+        }
+    }
 
     fn resolve_sync_handle(
         &mut self,
@@ -126,6 +152,67 @@ pub enum PixelBenderTarget {
 pub enum PixelBenderOutput {
     Bitmap(Box<dyn SyncHandle>),
     Bytes(Vec<u8>),
+    // The shader is still writing to a temporary texture. The bytes can be read
+    // back with `PendingPixelBenderBytes::resolve` once they are needed.
+    PendingBytes(PendingPixelBenderBytes),
+}
+
+/// The output of a Pixel Bender shader run with a `PixelBenderTarget::Bytes` target,
+/// which has not been read back from the backend yet.
+#[derive(Debug)]
+pub struct PendingPixelBenderBytes {
+    pub handle: Box<dyn SyncHandle>,
+    pub width: u32,
+    /// The number of output channels of the shader.
+    pub channels: usize,
+    /// Whether a three-channel output was rendered to a four-channel texture,
+    /// in which case the extra channel is dropped when reading it back.
+    pub has_padding: bool,
+}
+
+impl PendingPixelBenderBytes {
+    /// Waits for the shader to complete, and returns its output as little-endian `f32` values.
+    pub fn resolve(self, renderer: &mut dyn RenderBackend) -> Result<Vec<u8>, Error> {
+        let Self {
+            handle,
+            width,
+            channels,
+            has_padding,
+        } = self;
+        let mut output = Vec::new();
+        renderer.resolve_sync_handle(
+            handle,
+            Box::new(|raw_pixels, buffer_width| {
+                if buffer_width as usize != width as usize * channels * std::mem::size_of::<f32>() {
+                    let channels_in_raw_pixels = if has_padding { 4 } else { channels };
+
+                    for row in raw_pixels.chunks(buffer_width as usize) {
+                        // Ignore any backend-added padding (this is distinct from the alpha-channel padding
+                        // that we add for pixelbender)
+                        let actual_row = &row[0..(width as usize
+                            * channels_in_raw_pixels
+                            * std::mem::size_of::<f32>())];
+
+                        for pixel in actual_row
+                            .chunks_exact(channels_in_raw_pixels * std::mem::size_of::<f32>())
+                        {
+                            if has_padding {
+                                // Take the first three channels
+                                output
+                                    .extend_from_slice(&pixel[0..(3 * std::mem::size_of::<f32>())]);
+                            } else {
+                                // Copy the pixel as-is
+                                output.extend_from_slice(pixel);
+                            }
+                        }
+                    }
+                } else {
+                    output = raw_pixels.to_vec();
+                }
+            }),
+        )?;
+        Ok(output)
+    }
 }
 
 pub trait IndexBuffer: Any {}
@@ -243,13 +330,11 @@ pub trait Context3D: Any {
     // as part of stage rendering
     fn should_render(&self) -> bool;
 
-    // Get a 'disposed' handle - this is what we store in all IndexBuffer3D
-    // objects after dispose() has been called.
-    fn disposed_index_buffer_handle(&self) -> Rc<dyn IndexBuffer>;
-
-    // Get a 'disposed' handle - this is what we store in all VertexBuffer3D
-    // objects after dispose() has been called.
-    fn disposed_vertex_buffer_handle(&self) -> Rc<dyn VertexBuffer>;
+    // A description of the underlying graphics driver, as reported
+    // by `Context3D.driverInfo`
+    fn driver_info(&self) -> String;
+    // The largest width and height that the back buffer can be configured with
+    fn max_back_buffer_size(&self) -> u32;
 
     fn create_index_buffer(&mut self, usage: BufferUsage, num_indices: u32)
         -> Box<dyn IndexBuffer>;
@@ -298,6 +383,22 @@ pub enum Context3DTriangleFace {
     FrontAndBack,
 }
 
+impl Context3DTriangleFace {
+    pub fn from_wstr(s: &WStr) -> Option<Self> {
+        if s == b"none" {
+            Some(Context3DTriangleFace::None)
+        } else if s == b"back" {
+            Some(Context3DTriangleFace::Back)
+        } else if s == b"front" {
+            Some(Context3DTriangleFace::Front)
+        } else if s == b"frontAndBack" {
+            Some(Context3DTriangleFace::FrontAndBack)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Context3DProfile {
     Baseline,
@@ -364,6 +465,42 @@ impl Context3DCompareMode {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub enum Context3DStencilAction {
+    DecrementSaturate,
+    DecrementWrap,
+    IncrementSaturate,
+    IncrementWrap,
+    Invert,
+    Keep,
+    Set,
+    Zero,
+}
+
+impl Context3DStencilAction {
+    pub fn from_wstr(s: &WStr) -> Option<Self> {
+        if s == b"decrementSaturate" {
+            Some(Context3DStencilAction::DecrementSaturate)
+        } else if s == b"decrementWrap" {
+            Some(Context3DStencilAction::DecrementWrap)
+        } else if s == b"incrementSaturate" {
+            Some(Context3DStencilAction::IncrementSaturate)
+        } else if s == b"incrementWrap" {
+            Some(Context3DStencilAction::IncrementWrap)
+        } else if s == b"invert" {
+            Some(Context3DStencilAction::Invert)
+        } else if s == b"keep" {
+            Some(Context3DStencilAction::Keep)
+        } else if s == b"set" {
+            Some(Context3DStencilAction::Set)
+        } else if s == b"zero" {
+            Some(Context3DStencilAction::Zero)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Context3DWrapMode {
     Clamp,
@@ -440,6 +577,7 @@ pub enum Context3DCommand<'a> {
         enable_depth_and_stencil: bool,
         anti_alias: u32,
         surface_selector: u32,
+        color_output_index: u32,
     },
     SetRenderToBackBuffer,
 
@@ -519,6 +657,34 @@ pub enum Context3DCommand<'a> {
     SetScissorRectangle {
         rect: Option<Rectangle<Twips>>,
     },
+    SetStencilActions {
+        face: Context3DTriangleFace,
+        compare_mode: Context3DCompareMode,
+        action_on_both_pass: Context3DStencilAction,
+        action_on_depth_fail: Context3DStencilAction,
+        action_on_depth_pass_stencil_fail: Context3DStencilAction,
+    },
+    SetStencilReferenceValue {
+        reference_value: u32,
+        read_mask: u32,
+        write_mask: u32,
+    },
+
+    // The `Dispose*` commands hand over the last handle to a resource owned by
+    // ActionScript. The backend should stop using it, and release it once any
+    // pending work that refers to it has completed.
+    DisposeIndexBuffer {
+        buffer: Box<dyn IndexBuffer>,
+    },
+    DisposeVertexBuffer {
+        buffer: Rc<dyn VertexBuffer>,
+    },
+    DisposeTexture {
+        texture: Rc<dyn Texture>,
+    },
+    DisposeShaders {
+        module: Rc<dyn ShaderModule>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -10,13 +10,11 @@ use crate::bitmap::{
 };
 use crate::commands::CommandList;
 use crate::error::Error;
-use crate::pixel_bender::{PixelBenderShader, PixelBenderShaderHandle};
-use crate::pixel_bender_support::PixelBenderShaderArgument;
 use crate::quality::StageQuality;
 use crate::shape_utils::DistilledShape;
 use swf::Color;
 
-use super::{Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget};
+use super::{Context3D, Context3DProfile};
 
 pub struct NullBitmapSource;
 
@@ -109,15 +107,6 @@ impl RenderBackend for NullRenderer {
 
     fn set_quality(&mut self, _quality: StageQuality) {}
 
-    fn run_pixelbender_shader(
-        &mut self,
-        _shader: PixelBenderShaderHandle,
-        _arguments: &[PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
-    ) -> Result<PixelBenderOutput, Error> {
-        Err(Error::Unimplemented("Pixel bender shader".into()))
-    }
-
     fn resolve_sync_handle(
         &mut self,
         _handle: Box<dyn SyncHandle>,
@@ -126,15 +115,6 @@ impl RenderBackend for NullRenderer {
         Err(Error::Unimplemented("Sync handle resolution".into()))
     }
 
-    fn compile_pixelbender_shader(
-        &mut self,
-        _shader: PixelBenderShader,
-    ) -> Result<PixelBenderShaderHandle, Error> {
-        Err(Error::Unimplemented(
-            "Pixel bender shader compilation".into(),
-        ))
-    }
-
     fn create_empty_texture(&mut self, _width: u32, _height: u32) -> Result<BitmapHandle, Error> {
         Ok(BitmapHandle(Arc::new(NullBitmapHandle)))
     }
@@ -0,0 +1,1333 @@
+//! Recording and replaying of the calls made to a `RenderBackend`.
+//!
+//! `RecordingBackend` wraps another backend, and writes every bitmap upload, shape and
+//! rendering call to a stream of `RecordedEvent`s. A `Replayer` can later submit the same
+//! calls to any backend, so that a frame can be compared between backends without running the movie.
+//!
+//! Pixel Bender shaders and Stage3D aren't recorded: shader blend modes, fills and filters are
+//! skipped when replaying, and so are Stage3D outputs and bitmaps written to by a `ShaderJob`.
+//!
+//! A recording starts with [`MAGIC`] and the format version as a little-endian `u32`, followed
+//! by two chunks per event: the event as JSON without any pixels, then the raw pixels of the
+//! bitmap that it uploads (which is empty for other events). Each chunk is prefixed by its
+//! length as a little-endian `u32`.
+
+#![allow(clippy::arc_with_non_send_sync)]
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use swf::{BlendMode, CharacterId, Color, FillStyle, LineStyle, Rectangle, Twips};
+
+use crate::backend::{
+    BitmapCacheEntry, Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget,
+    RenderBackend, ShapeHandle, ShapeHandleImpl, ViewportDimensions,
+};
+use crate::bitmap::{
+    Bitmap, BitmapFormat, BitmapHandle, BitmapHandleImpl, BitmapSize, BitmapSource, PixelRegion,
+    PixelSnapping, RgbaBufRead, SyncHandle,
+};
+use crate::commands::{Command, CommandList, RenderBlendMode};
+use crate::error::Error;
+use crate::filters::{DisplacementMapFilter, Filter, ShaderFilter};
+use crate::matrix::Matrix;
+use crate::pixel_bender::{PixelBenderShader, PixelBenderShaderHandle};
+use crate::pixel_bender_support::{ImageInputTexture, PixelBenderShaderArgument, ShaderFill};
+use crate::quality::StageQuality;
+use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
+use crate::transform::Transform;
+
+/// The first bytes of a recording.
+pub const MAGIC: &[u8; 8] = b"RUFFLERR";
+
+/// The version of the recording format, which is written after [`MAGIC`].
+const VERSION: u32 = 1;
+
+/// Identifies a bitmap or shape registered during a recording.
+pub type RecordedId = u32;
+
+/// A single call made to the recorded backend.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    SetViewportDimensions(ViewportDimensions),
+    SetQuality(StageQuality),
+    RegisterBitmap {
+        id: RecordedId,
+        bitmap: RecordedBitmap,
+    },
+    CreateEmptyTexture {
+        id: RecordedId,
+        width: u32,
+        height: u32,
+    },
+    UpdateTexture {
+        id: RecordedId,
+        bitmap: RecordedBitmap,
+        region: PixelRegion,
+    },
+    RegisterShape {
+        id: RecordedId,
+        shape: RecordedShape,
+    },
+    RenderOffscreen {
+        id: RecordedId,
+        commands: Vec<RecordedCommand>,
+        quality: StageQuality,
+        bounds: PixelRegion,
+    },
+    ApplyFilter {
+        source: RecordedId,
+        source_point: (u32, u32),
+        source_size: (u32, u32),
+        destination: RecordedId,
+        dest_point: (u32, u32),
+        filter: RecordedFilter,
+    },
+    SubmitFrame {
+        clear: Color,
+        commands: Vec<RecordedCommand>,
+        cache_entries: Vec<RecordedCacheEntry>,
+    },
+}
+
+impl RecordedEvent {
+    /// The pixels uploaded by this event, which are stored outside of its JSON.
+    fn pixels_mut(&mut self) -> Option<&mut Vec<u8>> {
+        match self {
+            RecordedEvent::RegisterBitmap { bitmap, .. }
+            | RecordedEvent::UpdateTexture { bitmap, .. } => Some(&mut bitmap.data),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedBitmap {
+    pub width: u32,
+    pub height: u32,
+    pub format: BitmapFormat,
+    #[serde(skip)]
+    pub data: Vec<u8>,
+}
+
+impl From<&Bitmap<'_>> for RecordedBitmap {
+    fn from(bitmap: &Bitmap<'_>) -> Self {
+        Self {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            format: bitmap.format(),
+            data: bitmap.data().to_vec(),
+        }
+    }
+}
+
+impl RecordedBitmap {
+    pub fn to_bitmap(&self) -> Bitmap<'_> {
+        Bitmap::new(self.width, self.height, self.format, &self.data[..])
+    }
+}
+
+/// An owned copy of a `DistilledShape`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedShape {
+    pub paths: Vec<RecordedPath>,
+    pub shape_bounds: Rectangle<Twips>,
+    pub edge_bounds: Rectangle<Twips>,
+    pub id: CharacterId,
+    /// The bitmaps used by bitmap fills, which are looked up by character ID.
+    pub bitmaps: Vec<RecordedShapeBitmap>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RecordedPath {
+    Stroke {
+        style: LineStyle,
+        is_closed: bool,
+        commands: Vec<DrawCommand>,
+    },
+    Fill {
+        style: FillStyle,
+        commands: Vec<DrawCommand>,
+        winding_rule: FillRule,
+    },
+    /// A Pixel Bender shader fill, which can't be replayed.
+    ShaderFill {
+        commands: Vec<DrawCommand>,
+        winding_rule: FillRule,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedShapeBitmap {
+    pub character_id: CharacterId,
+    pub width: u16,
+    pub height: u16,
+    pub bitmap: Option<RecordedId>,
+}
+
+/// A `Command` of a `CommandList`.
+///
+/// Handles which weren't created by the recording backend (such as Stage3D outputs) are `None`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RecordedCommand {
+    RenderBitmap {
+        bitmap: Option<RecordedId>,
+        transform: Transform,
+        smoothing: bool,
+        pixel_snapping: PixelSnapping,
+    },
+    RenderStage3D {
+        bitmap: Option<RecordedId>,
+        transform: Transform,
+    },
+    RenderShape {
+        shape: Option<RecordedId>,
+        transform: Transform,
+    },
+    RenderAlphaMask {
+        maskee_commands: Vec<RecordedCommand>,
+        mask_commands: Vec<RecordedCommand>,
+    },
+    DrawRect {
+        color: Color,
+        matrix: Matrix,
+    },
+    DrawLine {
+        color: Color,
+        matrix: Matrix,
+    },
+    DrawLineRect {
+        color: Color,
+        matrix: Matrix,
+    },
+    PushMask,
+    ActivateMask,
+    DeactivateMask,
+    PopMask,
+    Blend(Vec<RecordedCommand>, RecordedBlendMode),
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum RecordedBlendMode {
+    Builtin(BlendMode),
+    /// `BlendMode.SHADER`, which is replayed as `BlendMode.NORMAL`.
+    Shader,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum RecordedFilter {
+    BevelFilter(swf::BevelFilter),
+    BlurFilter(swf::BlurFilter),
+    ColorMatrixFilter(swf::ColorMatrixFilter),
+    ConvolutionFilter(swf::ConvolutionFilter),
+    DisplacementMapFilter {
+        filter: DisplacementMapFilter,
+        map_bitmap: Option<RecordedId>,
+    },
+    DropShadowFilter(swf::DropShadowFilter),
+    GlowFilter(swf::GlowFilter),
+    GradientBevelFilter(swf::GradientFilter),
+    GradientGlowFilter(swf::GradientFilter),
+    /// A Pixel Bender shader filter, which can't be replayed.
+    ShaderFilter,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedCacheEntry {
+    pub handle: RecordedId,
+    pub commands: Vec<RecordedCommand>,
+    pub clear: Color,
+    pub filters: Vec<RecordedFilter>,
+}
+
+/// Reads the events written by a `RecordingBackend`.
+pub fn read_recording(mut reader: impl Read) -> io::Result<Vec<RecordedEvent>> {
+    let mut magic = [0; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Not a render recording",
+        ));
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported render recording version {version}"),
+        ));
+    }
+
+    let mut events = Vec::new();
+    while let Some(json) = read_chunk(&mut reader)? {
+        let mut event: RecordedEvent = serde_json::from_slice(&json)?;
+        let pixels = read_chunk(&mut reader)?.ok_or(io::ErrorKind::UnexpectedEof)?;
+        if let Some(data) = event.pixels_mut() {
+            *data = pixels;
+        }
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Reads a chunk prefixed by its length, or `None` at the end of the recording.
+fn read_chunk(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    if reader.read(&mut len[..1])? == 0 {
+        return Ok(None);
+    }
+    reader.read_exact(&mut len[1..])?;
+    let mut chunk = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut chunk)?;
+    Ok(Some(chunk))
+}
+
+fn write_chunk(writer: &mut dyn Write, chunk: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(chunk.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Chunk is too large"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(chunk)
+}
+
+#[derive(Debug)]
+struct RecordedBitmapHandle {
+    id: RecordedId,
+    inner: BitmapHandle,
+}
+
+impl BitmapHandleImpl for RecordedBitmapHandle {}
+
+#[derive(Debug)]
+struct RecordedShapeHandle {
+    id: RecordedId,
+    inner: ShapeHandle,
+}
+
+impl ShapeHandleImpl for RecordedShapeHandle {}
+
+/// Returns the recorded ID of a bitmap, and the handle of the wrapped backend.
+fn unwrap_bitmap(handle: &BitmapHandle) -> (Option<RecordedId>, BitmapHandle) {
+    match <dyn Any>::downcast_ref::<RecordedBitmapHandle>(&*handle.0) {
+        Some(recorded) => (Some(recorded.id), recorded.inner.clone()),
+        None => (None, handle.clone()),
+    }
+}
+
+fn unwrap_shape(handle: &ShapeHandle) -> (Option<RecordedId>, ShapeHandle) {
+    match <dyn Any>::downcast_ref::<RecordedShapeHandle>(&*handle.0) {
+        Some(recorded) => (Some(recorded.id), recorded.inner.clone()),
+        None => (None, handle.clone()),
+    }
+}
+
+fn unwrap_shader_arguments<'a>(
+    arguments: &[PixelBenderShaderArgument<'a>],
+) -> Vec<PixelBenderShaderArgument<'a>> {
+    arguments
+        .iter()
+        .map(|argument| match argument {
+            PixelBenderShaderArgument::ImageInput {
+                index,
+                channels,
+                name,
+                texture: Some(ImageInputTexture::Bitmap(bitmap)),
+            } => PixelBenderShaderArgument::ImageInput {
+                index: *index,
+                channels: *channels,
+                name: name.clone(),
+                texture: Some(ImageInputTexture::Bitmap(unwrap_bitmap(bitmap).1)),
+            },
+            argument => argument.clone(),
+        })
+        .collect()
+}
+
+/// Converts a command list into its recorded form, and the list to submit to the wrapped backend.
+fn record_commands(commands: CommandList) -> (Vec<RecordedCommand>, CommandList) {
+    let mut recorded = Vec::with_capacity(commands.commands.len());
+    let mut unwrapped = CommandList::new();
+    for command in commands.commands {
+        let (recorded_command, command) = match command {
+            Command::RenderBitmap {
+                bitmap,
+                transform,
+                smoothing,
+                pixel_snapping,
+            } => {
+                let (id, bitmap) = unwrap_bitmap(&bitmap);
+                (
+                    RecordedCommand::RenderBitmap {
+                        bitmap: id,
+                        transform: transform.clone(),
+                        smoothing,
+                        pixel_snapping,
+                    },
+                    Command::RenderBitmap {
+                        bitmap,
+                        transform,
+                        smoothing,
+                        pixel_snapping,
+                    },
+                )
+            }
+            Command::RenderStage3D { bitmap, transform } => {
+                let (id, bitmap) = unwrap_bitmap(&bitmap);
+                (
+                    RecordedCommand::RenderStage3D {
+                        bitmap: id,
+                        transform: transform.clone(),
+                    },
+                    Command::RenderStage3D { bitmap, transform },
+                )
+            }
+            Command::RenderShape { shape, transform } => {
+                let (id, shape) = unwrap_shape(&shape);
+                (
+                    RecordedCommand::RenderShape {
+                        shape: id,
+                        transform: transform.clone(),
+                    },
+                    Command::RenderShape { shape, transform },
+                )
+            }
+            Command::RenderAlphaMask {
+                maskee_commands,
+                mask_commands,
+            } => {
+                let (recorded_maskee, maskee_commands) = record_commands(maskee_commands);
+                let (recorded_mask, mask_commands) = record_commands(mask_commands);
+                (
+                    RecordedCommand::RenderAlphaMask {
+                        maskee_commands: recorded_maskee,
+                        mask_commands: recorded_mask,
+                    },
+                    Command::RenderAlphaMask {
+                        maskee_commands,
+                        mask_commands,
+                    },
+                )
+            }
+            Command::DrawRect { color, matrix } => (
+                RecordedCommand::DrawRect { color, matrix },
+                Command::DrawRect { color, matrix },
+            ),
+            Command::DrawLine { color, matrix } => (
+                RecordedCommand::DrawLine { color, matrix },
+                Command::DrawLine { color, matrix },
+            ),
+            Command::DrawLineRect { color, matrix } => (
+                RecordedCommand::DrawLineRect { color, matrix },
+                Command::DrawLineRect { color, matrix },
+            ),
+            Command::PushMask => (RecordedCommand::PushMask, Command::PushMask),
+            Command::ActivateMask => (RecordedCommand::ActivateMask, Command::ActivateMask),
+            Command::DeactivateMask => (RecordedCommand::DeactivateMask, Command::DeactivateMask),
+            Command::PopMask => (RecordedCommand::PopMask, Command::PopMask),
+            Command::Blend(commands, blend_mode) => {
+                let (recorded_commands, commands) = record_commands(commands);
+                let recorded_blend_mode = match &blend_mode {
+                    RenderBlendMode::Builtin(blend_mode) => RecordedBlendMode::Builtin(*blend_mode),
+                    RenderBlendMode::Shader(_) => RecordedBlendMode::Shader,
+                };
+                (
+                    RecordedCommand::Blend(recorded_commands, recorded_blend_mode),
+                    Command::Blend(commands, blend_mode),
+                )
+            }
+        };
+        recorded.push(recorded_command);
+        unwrapped.commands.push(command);
+    }
+    (recorded, unwrapped)
+}
+
+/// Converts a filter into its recorded form, and the filter to apply with the wrapped backend.
+fn record_filter(filter: Filter) -> (RecordedFilter, Filter) {
+    match filter {
+        Filter::BevelFilter(filter) => (
+            RecordedFilter::BevelFilter(filter.clone()),
+            Filter::BevelFilter(filter),
+        ),
+        Filter::BlurFilter(filter) => (
+            RecordedFilter::BlurFilter(filter.clone()),
+            Filter::BlurFilter(filter),
+        ),
+        Filter::ColorMatrixFilter(filter) => (
+            RecordedFilter::ColorMatrixFilter(filter.clone()),
+            Filter::ColorMatrixFilter(filter),
+        ),
+        Filter::ConvolutionFilter(filter) => (
+            RecordedFilter::ConvolutionFilter(filter.clone()),
+            Filter::ConvolutionFilter(filter),
+        ),
+        Filter::DisplacementMapFilter(mut filter) => {
+            let mut map_bitmap = None;
+            if let Some(bitmap) = filter.map_bitmap.take() {
+                let (id, bitmap) = unwrap_bitmap(&bitmap);
+                map_bitmap = id;
+                filter.map_bitmap = Some(bitmap);
+            }
+            (
+                RecordedFilter::DisplacementMapFilter {
+                    filter: filter.clone(),
+                    map_bitmap,
+                },
+                Filter::DisplacementMapFilter(filter),
+            )
+        }
+        Filter::DropShadowFilter(filter) => (
+            RecordedFilter::DropShadowFilter(filter.clone()),
+            Filter::DropShadowFilter(filter),
+        ),
+        Filter::GlowFilter(filter) => (
+            RecordedFilter::GlowFilter(filter.clone()),
+            Filter::GlowFilter(filter),
+        ),
+        Filter::GradientBevelFilter(filter) => (
+            RecordedFilter::GradientBevelFilter(filter.clone()),
+            Filter::GradientBevelFilter(filter),
+        ),
+        Filter::GradientGlowFilter(filter) => (
+            RecordedFilter::GradientGlowFilter(filter.clone()),
+            Filter::GradientGlowFilter(filter),
+        ),
+        Filter::ShaderFilter(filter) => (
+            RecordedFilter::ShaderFilter,
+            Filter::ShaderFilter(ShaderFilter {
+                shader_args: unwrap_shader_arguments(&filter.shader_args),
+                ..filter
+            }),
+        ),
+    }
+}
+
+/// The bitmaps of a shape's bitmap fills, which have already been looked up.
+struct ResolvedBitmapSource(HashMap<CharacterId, (BitmapSize, Option<BitmapHandle>)>);
+
+impl BitmapSource for ResolvedBitmapSource {
+    fn bitmap_size(&self, id: u16) -> Option<BitmapSize> {
+        self.0.get(&id).map(|(size, _)| *size)
+    }
+
+    fn bitmap_handle(&self, id: u16, _renderer: &mut dyn RenderBackend) -> Option<BitmapHandle> {
+        self.0.get(&id).and_then(|(_, handle)| handle.clone())
+    }
+}
+
+fn bitmap_fill_id(style: &FillStyle) -> Option<CharacterId> {
+    match style {
+        FillStyle::Bitmap { id, .. } => Some(*id),
+        _ => None,
+    }
+}
+
+/// A `RenderBackend` which forwards every call to another backend,
+/// and records them as `RecordedEvent`s in the format described in the module documentation.
+///
+/// The recording can be read back with `read_recording`, and replayed with a `Replayer`.
+pub struct RecordingBackend {
+    inner: Box<dyn RenderBackend>,
+    writer: Option<Box<dyn Write>>,
+    next_id: RecordedId,
+}
+
+impl RecordingBackend {
+    pub fn new(inner: Box<dyn RenderBackend>, writer: Box<dyn Write>) -> Self {
+        let mut backend = Self {
+            inner,
+            writer: Some(writer),
+            next_id: 0,
+        };
+        backend.write(|writer| {
+            writer.write_all(MAGIC)?;
+            writer.write_all(&VERSION.to_le_bytes())
+        });
+        let dimensions = backend.inner.viewport_dimensions();
+        backend.record(RecordedEvent::SetViewportDimensions(dimensions));
+        backend
+    }
+
+    pub fn inner(&self) -> &dyn RenderBackend {
+        &*self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut dyn RenderBackend {
+        &mut *self.inner
+    }
+
+    /// Writes any buffered events, and completes the recording.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, mut event: RecordedEvent) {
+        let pixels = event.pixels_mut().map(std::mem::take).unwrap_or_default();
+        self.write(|writer| {
+            let json = serde_json::to_vec(&event)?;
+            write_chunk(writer, &json)?;
+            write_chunk(writer, &pixels)
+        });
+    }
+
+    fn flush(&mut self) {
+        self.write(|writer| writer.flush());
+    }
+
+    /// Writes to the recording, stopping it if that fails.
+    fn write(&mut self, write: impl FnOnce(&mut dyn Write) -> io::Result<()>) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        if let Err(e) = write(&mut **writer) {
+            tracing::error!("Couldn't write render recording, stopping: {e}");
+            self.writer = None;
+        }
+    }
+
+    fn next_id(&mut self) -> RecordedId {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn wrap_bitmap(&mut self, inner: BitmapHandle) -> (RecordedId, BitmapHandle) {
+        let id = self.next_id();
+        let handle = BitmapHandle(Arc::new(RecordedBitmapHandle { id, inner }));
+        (id, handle)
+    }
+}
+
+impl Drop for RecordingBackend {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            tracing::error!("Couldn't finish render recording: {e}");
+        }
+    }
+}
+
+impl RenderBackend for RecordingBackend {
+    fn viewport_dimensions(&self) -> ViewportDimensions {
+        self.inner.viewport_dimensions()
+    }
+
+    fn set_viewport_dimensions(&mut self, dimensions: ViewportDimensions) {
+        self.record(RecordedEvent::SetViewportDimensions(dimensions));
+        self.inner.set_viewport_dimensions(dimensions);
+    }
+
+    fn register_shape(
+        &mut self,
+        shape: DistilledShape,
+        bitmap_source: &dyn BitmapSource,
+    ) -> ShapeHandle {
+        // Look up the bitmaps of bitmap fills with ourselves as the renderer,
+        // so that any bitmap registered by the source is recorded too.
+        let mut bitmaps = HashMap::new();
+        let mut recorded_bitmaps = Vec::new();
+        for path in &shape.paths {
+            let id = match path {
+                DrawPath::Stroke { style, .. } => bitmap_fill_id(style.fill_style()),
+                DrawPath::Fill { style, .. } => bitmap_fill_id(style),
+                DrawPath::ShaderFill { .. } => None,
+            };
+            let Some(id) = id else {
+                continue;
+            };
+            if bitmaps.contains_key(&id) {
+                continue;
+            }
+            let Some(size) = bitmap_source.bitmap_size(id) else {
+                continue;
+            };
+            let (recorded_id, handle) = match bitmap_source.bitmap_handle(id, self) {
+                Some(handle) => {
+                    let (recorded_id, handle) = unwrap_bitmap(&handle);
+                    (recorded_id, Some(handle))
+                }
+                None => (None, None),
+            };
+            bitmaps.insert(id, (size, handle));
+            recorded_bitmaps.push(RecordedShapeBitmap {
+                character_id: id,
+                width: size.width,
+                height: size.height,
+                bitmap: recorded_id,
+            });
+        }
+
+        let shader_fills: Vec<ShaderFill> = shape
+            .paths
+            .iter()
+            .filter_map(|path| match path {
+                DrawPath::ShaderFill { fill, .. } => Some(ShaderFill {
+                    arguments: unwrap_shader_arguments(&fill.arguments),
+                    ..(*fill).clone()
+                }),
+                _ => None,
+            })
+            .collect();
+        let mut shader_fills_iter = shader_fills.iter();
+
+        let mut recorded_paths = Vec::with_capacity(shape.paths.len());
+        let mut paths = Vec::with_capacity(shape.paths.len());
+        for path in shape.paths {
+            match path {
+                DrawPath::Stroke {
+                    style,
+                    is_closed,
+                    commands,
+                } => {
+                    recorded_paths.push(RecordedPath::Stroke {
+                        style: style.clone(),
+                        is_closed,
+                        commands: commands.clone(),
+                    });
+                    paths.push(DrawPath::Stroke {
+                        style,
+                        is_closed,
+                        commands,
+                    });
+                }
+                DrawPath::Fill {
+                    style,
+                    commands,
+                    winding_rule,
+                } => {
+                    recorded_paths.push(RecordedPath::Fill {
+                        style: style.clone(),
+                        commands: commands.clone(),
+                        winding_rule,
+                    });
+                    paths.push(DrawPath::Fill {
+                        style,
+                        commands,
+                        winding_rule,
+                    });
+                }
+                DrawPath::ShaderFill {
+                    fill,
+                    commands,
+                    winding_rule,
+                } => {
+                    recorded_paths.push(RecordedPath::ShaderFill {
+                        commands: commands.clone(),
+                        winding_rule,
+                    });
+                    paths.push(DrawPath::ShaderFill {
+                        fill: shader_fills_iter.next().unwrap_or(fill),
+                        commands,
+                        winding_rule,
+                    });
+                }
+            }
+        }
+
+        let id = self.next_id();
+        self.record(RecordedEvent::RegisterShape {
+            id,
+            shape: RecordedShape {
+                paths: recorded_paths,
+                shape_bounds: shape.shape_bounds,
+                edge_bounds: shape.edge_bounds,
+                id: shape.id,
+                bitmaps: recorded_bitmaps,
+            },
+        });
+
+        let inner = self.inner.register_shape(
+            DistilledShape {
+                paths,
+                shape_bounds: shape.shape_bounds,
+                edge_bounds: shape.edge_bounds,
+                id: shape.id,
+            },
+            &ResolvedBitmapSource(bitmaps),
+        );
+        ShapeHandle(Arc::new(RecordedShapeHandle { id, inner }))
+    }
+
+    fn render_offscreen(
+        &mut self,
+        handle: BitmapHandle,
+        commands: CommandList,
+        quality: StageQuality,
+        bounds: PixelRegion,
+    ) -> Option<Box<dyn SyncHandle>> {
+        let (id, handle) = unwrap_bitmap(&handle);
+        let (recorded_commands, commands) = record_commands(commands);
+        if let Some(id) = id {
+            self.record(RecordedEvent::RenderOffscreen {
+                id,
+                commands: recorded_commands,
+                quality,
+                bounds,
+            });
+        }
+        self.inner
+            .render_offscreen(handle, commands, quality, bounds)
+    }
+
+    fn apply_filter(
+        &mut self,
+        source: BitmapHandle,
+        source_point: (u32, u32),
+        source_size: (u32, u32),
+        destination: BitmapHandle,
+        dest_point: (u32, u32),
+        filter: Filter,
+    ) -> Option<Box<dyn SyncHandle>> {
+        let (source_id, source) = unwrap_bitmap(&source);
+        let (destination_id, destination) = unwrap_bitmap(&destination);
+        let (recorded_filter, filter) = record_filter(filter);
+        if let (Some(source), Some(destination)) = (source_id, destination_id) {
+            self.record(RecordedEvent::ApplyFilter {
+                source,
+                source_point,
+                source_size,
+                destination,
+                dest_point,
+                filter: recorded_filter,
+            });
+        }
+        self.inner.apply_filter(
+            source,
+            source_point,
+            source_size,
+            destination,
+            dest_point,
+            filter,
+        )
+    }
+
+    fn is_filter_supported(&self, filter: &Filter) -> bool {
+        self.inner.is_filter_supported(filter)
+    }
+
+    fn is_offscreen_supported(&self) -> bool {
+        self.inner.is_offscreen_supported()
+    }
+
+    fn submit_frame(
+        &mut self,
+        clear: Color,
+        commands: CommandList,
+        cache_entries: Vec<BitmapCacheEntry>,
+    ) {
+        let (recorded_commands, commands) = record_commands(commands);
+        let mut recorded_cache_entries = Vec::with_capacity(cache_entries.len());
+        let mut unwrapped_cache_entries = Vec::with_capacity(cache_entries.len());
+        for entry in cache_entries {
+            let (id, handle) = unwrap_bitmap(&entry.handle);
+            let (recorded_commands, commands) = record_commands(entry.commands);
+            let (recorded_filters, filters): (Vec<_>, Vec<_>) =
+                entry.filters.into_iter().map(record_filter).unzip();
+            if let Some(id) = id {
+                recorded_cache_entries.push(RecordedCacheEntry {
+                    handle: id,
+                    commands: recorded_commands,
+                    clear: entry.clear,
+                    filters: recorded_filters,
+                });
+            }
+            unwrapped_cache_entries.push(BitmapCacheEntry {
+                handle,
+                commands,
+                clear: entry.clear,
+                filters,
+            });
+        }
+        self.record(RecordedEvent::SubmitFrame {
+            clear,
+            commands: recorded_commands,
+            cache_entries: recorded_cache_entries,
+        });
+        // Make sure every complete frame ends up in the recording, even if we crash later on.
+        self.flush();
+        self.inner
+            .submit_frame(clear, commands, unwrapped_cache_entries);
+    }
+
+    fn create_empty_texture(&mut self, width: u32, height: u32) -> Result<BitmapHandle, Error> {
+        let inner = self.inner.create_empty_texture(width, height)?;
+        let (id, handle) = self.wrap_bitmap(inner);
+        self.record(RecordedEvent::CreateEmptyTexture { id, width, height });
+        Ok(handle)
+    }
+
+    fn register_bitmap(&mut self, bitmap: Bitmap<'_>) -> Result<BitmapHandle, Error> {
+        let recorded_bitmap = RecordedBitmap::from(&bitmap);
+        let inner = self.inner.register_bitmap(bitmap)?;
+        let (id, handle) = self.wrap_bitmap(inner);
+        self.record(RecordedEvent::RegisterBitmap {
+            id,
+            bitmap: recorded_bitmap,
+        });
+        Ok(handle)
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: &BitmapHandle,
+        bitmap: Bitmap<'_>,
+        region: PixelRegion,
+    ) -> Result<(), Error> {
+        let (id, handle) = unwrap_bitmap(handle);
+        if let Some(id) = id {
+            self.record(RecordedEvent::UpdateTexture {
+                id,
+                bitmap: RecordedBitmap::from(&bitmap),
+                region,
+            });
+        }
+        self.inner.update_texture(&handle, bitmap, region)
+    }
+
+    fn create_context3d(&mut self, profile: Context3DProfile) -> Result<Box<dyn Context3D>, Error> {
+        self.inner.create_context3d(profile)
+    }
+
+    fn debug_info(&self) -> Cow<'static, str> {
+        let mut result = self.inner.debug_info().into_owned();
+        result.push_str("Recording render calls\n");
+        Cow::Owned(result)
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.record(RecordedEvent::SetQuality(quality));
+        self.inner.set_quality(quality);
+    }
+
+    fn compile_pixelbender_shader(
+        &mut self,
+        shader: PixelBenderShader,
+    ) -> Result<PixelBenderShaderHandle, Error> {
+        self.inner.compile_pixelbender_shader(shader)
+    }
+
+    fn run_pixelbender_shader(
+        &mut self,
+        handle: PixelBenderShaderHandle,
+        arguments: &[PixelBenderShaderArgument],
+        target: &PixelBenderTarget,
+    ) -> Result<PixelBenderOutput, Error> {
+        let arguments = unwrap_shader_arguments(arguments);
+        let target = match target {
+            PixelBenderTarget::Bitmap(bitmap) => PixelBenderTarget::Bitmap(unwrap_bitmap(bitmap).1),
+            PixelBenderTarget::Bytes { width, height } => PixelBenderTarget::Bytes {
+                width: *width,
+                height: *height,
+            },
+        };
+        self.inner
+            .run_pixelbender_shader(handle, &arguments, &target)
+    }
+
+    fn resolve_sync_handle(
+        &mut self,
+        handle: Box<dyn SyncHandle>,
+        with_rgba: RgbaBufRead,
+    ) -> Result<(), Error> {
+        self.inner.resolve_sync_handle(handle, with_rgba)
+    }
+}
+
+/// Submits recorded events to a `RenderBackend`.
+#[derive(Default)]
+pub struct Replayer {
+    bitmaps: HashMap<RecordedId, BitmapHandle>,
+    shapes: HashMap<RecordedId, ShapeHandle>,
+}
+
+impl Replayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn replay(
+        &mut self,
+        renderer: &mut dyn RenderBackend,
+        event: &RecordedEvent,
+    ) -> Result<(), Error> {
+        match event {
+            RecordedEvent::SetViewportDimensions(dimensions) => {
+                renderer.set_viewport_dimensions(*dimensions)
+            }
+            RecordedEvent::SetQuality(quality) => renderer.set_quality(*quality),
+            RecordedEvent::RegisterBitmap { id, bitmap } => {
+                let handle = renderer.register_bitmap(bitmap.to_bitmap())?;
+                self.bitmaps.insert(*id, handle);
+            }
+            RecordedEvent::CreateEmptyTexture { id, width, height } => {
+                let handle = renderer.create_empty_texture(*width, *height)?;
+                self.bitmaps.insert(*id, handle);
+            }
+            RecordedEvent::UpdateTexture { id, bitmap, region } => {
+                let handle = self.bitmap(*id)?;
+                renderer.update_texture(&handle, bitmap.to_bitmap(), *region)?;
+            }
+            RecordedEvent::RegisterShape { id, shape } => {
+                let handle = self.register_shape(renderer, shape);
+                self.shapes.insert(*id, handle);
+            }
+            RecordedEvent::RenderOffscreen {
+                id,
+                commands,
+                quality,
+                bounds,
+            } => {
+                let handle = self.bitmap(*id)?;
+                let commands = self.commands(commands);
+                // The result is only read back by the movie, so there's nothing to wait for.
+                let _ = renderer.render_offscreen(handle, commands, *quality, *bounds);
+            }
+            RecordedEvent::ApplyFilter {
+                source,
+                source_point,
+                source_size,
+                destination,
+                dest_point,
+                filter,
+            } => {
+                let source = self.bitmap(*source)?;
+                let destination = self.bitmap(*destination)?;
+                if let Some(filter) = self.filter(filter) {
+                    let _ = renderer.apply_filter(
+                        source,
+                        *source_point,
+                        *source_size,
+                        destination,
+                        *dest_point,
+                        filter,
+                    );
+                }
+            }
+            RecordedEvent::SubmitFrame {
+                clear,
+                commands,
+                cache_entries,
+            } => {
+                let commands = self.commands(commands);
+                let cache_entries = cache_entries
+                    .iter()
+                    .map(|entry| {
+                        Ok(BitmapCacheEntry {
+                            handle: self.bitmap(entry.handle)?,
+                            commands: self.commands(&entry.commands),
+                            clear: entry.clear,
+                            filters: entry
+                                .filters
+                                .iter()
+                                .filter_map(|filter| self.filter(filter))
+                                .collect(),
+                        })
+                    })
+                    .collect::<Result<_, Error>>()?;
+                renderer.submit_frame(*clear, commands, cache_entries);
+            }
+        }
+        Ok(())
+    }
+
+    fn bitmap(&self, id: RecordedId) -> Result<BitmapHandle, Error> {
+        self.bitmaps
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Error::Unimplemented(format!("Unknown recorded bitmap {id}").into()))
+    }
+
+    fn register_shape(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        shape: &RecordedShape,
+    ) -> ShapeHandle {
+        let paths = shape
+            .paths
+            .iter()
+            .filter_map(|path| match path {
+                RecordedPath::Stroke {
+                    style,
+                    is_closed,
+                    commands,
+                } => Some(DrawPath::Stroke {
+                    style,
+                    is_closed: *is_closed,
+                    commands: commands.clone(),
+                }),
+                RecordedPath::Fill {
+                    style,
+                    commands,
+                    winding_rule,
+                } => Some(DrawPath::Fill {
+                    style,
+                    commands: commands.clone(),
+                    winding_rule: *winding_rule,
+                }),
+                RecordedPath::ShaderFill { .. } => {
+                    tracing::warn!("Skipping shader fill of recorded shape {}", shape.id);
+                    None
+                }
+            })
+            .collect();
+        let bitmaps = shape
+            .bitmaps
+            .iter()
+            .map(|bitmap| {
+                let size = BitmapSize {
+                    width: bitmap.width,
+                    height: bitmap.height,
+                };
+                let handle = bitmap.bitmap.and_then(|id| self.bitmaps.get(&id).cloned());
+                (bitmap.character_id, (size, handle))
+            })
+            .collect();
+        renderer.register_shape(
+            DistilledShape {
+                paths,
+                shape_bounds: shape.shape_bounds,
+                edge_bounds: shape.edge_bounds,
+                id: shape.id,
+            },
+            &ResolvedBitmapSource(bitmaps),
+        )
+    }
+
+    /// Rebuilds a command list. Commands using unknown handles are skipped.
+    fn commands(&self, commands: &[RecordedCommand]) -> CommandList {
+        let mut list = CommandList::new();
+        for command in commands {
+            let command = match command {
+                RecordedCommand::RenderBitmap {
+                    bitmap,
+                    transform,
+                    smoothing,
+                    pixel_snapping,
+                } => {
+                    let Some(bitmap) = bitmap.and_then(|id| self.bitmaps.get(&id)) else {
+                        continue;
+                    };
+                    Command::RenderBitmap {
+                        bitmap: bitmap.clone(),
+                        transform: transform.clone(),
+                        smoothing: *smoothing,
+                        pixel_snapping: *pixel_snapping,
+                    }
+                }
+                RecordedCommand::RenderStage3D { bitmap, transform } => {
+                    let Some(bitmap) = bitmap.and_then(|id| self.bitmaps.get(&id)) else {
+                        continue;
+                    };
+                    Command::RenderStage3D {
+                        bitmap: bitmap.clone(),
+                        transform: transform.clone(),
+                    }
+                }
+                RecordedCommand::RenderShape { shape, transform } => {
+                    let Some(shape) = shape.and_then(|id| self.shapes.get(&id)) else {
+                        continue;
+                    };
+                    Command::RenderShape {
+                        shape: shape.clone(),
+                        transform: transform.clone(),
+                    }
+                }
+                RecordedCommand::RenderAlphaMask {
+                    maskee_commands,
+                    mask_commands,
+                } => Command::RenderAlphaMask {
+                    maskee_commands: self.commands(maskee_commands),
+                    mask_commands: self.commands(mask_commands),
+                },
+                RecordedCommand::DrawRect { color, matrix } => Command::DrawRect {
+                    color: *color,
+                    matrix: *matrix,
+                },
+                RecordedCommand::DrawLine { color, matrix } => Command::DrawLine {
+                    color: *color,
+                    matrix: *matrix,
+                },
+                RecordedCommand::DrawLineRect { color, matrix } => Command::DrawLineRect {
+                    color: *color,
+                    matrix: *matrix,
+                },
+                RecordedCommand::PushMask => Command::PushMask,
+                RecordedCommand::ActivateMask => Command::ActivateMask,
+                RecordedCommand::DeactivateMask => Command::DeactivateMask,
+                RecordedCommand::PopMask => Command::PopMask,
+                RecordedCommand::Blend(commands, blend_mode) => {
+                    let blend_mode = match blend_mode {
+                        RecordedBlendMode::Builtin(blend_mode) => *blend_mode,
+                        RecordedBlendMode::Shader => BlendMode::Normal,
+                    };
+                    Command::Blend(
+                        self.commands(commands),
+                        RenderBlendMode::Builtin(blend_mode),
+                    )
+                }
+            };
+            list.commands.push(command);
+        }
+        list
+    }
+
+    fn filter(&self, filter: &RecordedFilter) -> Option<Filter> {
+        Some(match filter {
+            RecordedFilter::BevelFilter(filter) => Filter::BevelFilter(filter.clone()),
+            RecordedFilter::BlurFilter(filter) => Filter::BlurFilter(filter.clone()),
+            RecordedFilter::ColorMatrixFilter(filter) => Filter::ColorMatrixFilter(filter.clone()),
+            RecordedFilter::ConvolutionFilter(filter) => Filter::ConvolutionFilter(filter.clone()),
+            RecordedFilter::DisplacementMapFilter { filter, map_bitmap } => {
+                Filter::DisplacementMapFilter(DisplacementMapFilter {
+                    map_bitmap: map_bitmap.and_then(|id| self.bitmaps.get(&id).cloned()),
+                    ..filter.clone()
+                })
+            }
+            RecordedFilter::DropShadowFilter(filter) => Filter::DropShadowFilter(filter.clone()),
+            RecordedFilter::GlowFilter(filter) => Filter::GlowFilter(filter.clone()),
+            RecordedFilter::GradientBevelFilter(filter) => {
+                Filter::GradientBevelFilter(filter.clone())
+            }
+            RecordedFilter::GradientGlowFilter(filter) => {
+                Filter::GradientGlowFilter(filter.clone())
+            }
+            RecordedFilter::ShaderFilter => {
+                tracing::warn!("Skipping recorded shader filter");
+                return None;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::null::{NullBitmapSource, NullRenderer};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A writer whose contents can still be read after the backend is dropped.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn dimensions() -> ViewportDimensions {
+        ViewportDimensions {
+            width: 20,
+            height: 10,
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn record_and_read_back() {
+        let buffer = SharedBuffer::default();
+        let mut backend = RecordingBackend::new(
+            Box::new(NullRenderer::new(dimensions())),
+            Box::new(buffer.clone()),
+        );
+
+        let bitmap = backend
+            .register_bitmap(Bitmap::new(1, 1, BitmapFormat::Rgba, vec![255, 0, 0, 255]))
+            .expect("Bitmap should be registered");
+        let shape = backend.register_shape(
+            DistilledShape {
+                paths: vec![],
+                shape_bounds: Default::default(),
+                edge_bounds: Default::default(),
+                id: 1,
+            },
+            &NullBitmapSource,
+        );
+        let mut commands = CommandList::new();
+        commands.commands.push(Command::RenderBitmap {
+            bitmap,
+            transform: Default::default(),
+            smoothing: true,
+            pixel_snapping: PixelSnapping::Auto,
+        });
+        commands.commands.push(Command::RenderShape {
+            shape,
+            transform: Default::default(),
+        });
+        backend.submit_frame(Color::WHITE, commands, vec![]);
+        drop(backend);
+
+        assert!(buffer.0.borrow().starts_with(MAGIC));
+        let events = read_recording(&buffer.0.borrow()[..]).expect("Recording should be valid");
+        assert_eq!(events.len(), 4);
+        assert!(matches!(
+            events[0],
+            RecordedEvent::SetViewportDimensions(ViewportDimensions {
+                width: 20,
+                height: 10,
+                ..
+            })
+        ));
+        assert!(matches!(
+            &events[1],
+            RecordedEvent::RegisterBitmap { id: 0, bitmap } if bitmap.data == [255, 0, 0, 255]
+        ));
+        assert!(matches!(
+            events[2],
+            RecordedEvent::RegisterShape { id: 1, .. }
+        ));
+        let RecordedEvent::SubmitFrame { commands, .. } = &events[3] else {
+            panic!("Expected a frame, got {:?}", events[3]);
+        };
+        assert!(matches!(
+            commands[..],
+            [
+                RecordedCommand::RenderBitmap {
+                    bitmap: Some(0),
+                    ..
+                },
+                RecordedCommand::RenderShape { shape: Some(1), .. }
+            ]
+        ));
+
+        let mut replayer = Replayer::new();
+        let mut renderer = NullRenderer::new(dimensions());
+        for event in &events {
+            replayer
+                .replay(&mut renderer, event)
+                .expect("Event should be replayed");
+        }
+    }
+    #[test]
+    fn truncated_recording_is_an_error() {
+        let buffer = SharedBuffer::default();
+        let mut backend = RecordingBackend::new(
+            Box::new(NullRenderer::new(dimensions())),
+            Box::new(buffer.clone()),
+        );
+        backend
+            .register_bitmap(Bitmap::new(1, 1, BitmapFormat::Rgba, vec![255, 0, 0, 255]))
+            .expect("Bitmap should be registered");
+        drop(backend);
+
+        let recording = buffer.0.borrow();
+        assert!(read_recording(&recording[..recording.len() - 1]).is_err());
+        assert!(read_recording(&recording[1..]).is_err());
+    }
+}
@@ -55,6 +55,7 @@ impl Clone for Box<dyn SyncHandle> {
 
 /// How bitmaps should snap to the pixel grid when rendering
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PixelSnapping {
     /// Always snap the bitmap to the pixel grid
     Always,
@@ -267,6 +268,7 @@ impl<'a> Bitmap<'a> {
 
 /// The pixel format of the bitmap data.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BitmapFormat {
     /// 24-bit RGB.
     Rgb,
@@ -333,6 +335,7 @@ fn translate_region(
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PixelRegion {
     pub x_min: u32,
     pub y_min: u32,
@@ -33,6 +33,17 @@ pub struct ShaderFilter<'a> {
     pub shader_args: Vec<PixelBenderShaderArgument<'a>>,
 }
 
+impl ShaderFilter<'_> {
+    pub fn calculate_dest_rect(&self, source_rect: Rectangle<Twips>) -> Rectangle<Twips> {
+        Rectangle {
+            x_min: source_rect.x_min - Twips::from_pixels_i32(self.left_extension),
+            x_max: source_rect.x_max + Twips::from_pixels_i32(self.right_extension),
+            y_min: source_rect.y_min - Twips::from_pixels_i32(self.top_extension),
+            y_max: source_rect.y_max + Twips::from_pixels_i32(self.bottom_extension),
+        }
+    }
+}
+
 impl PartialEq for ShaderFilter<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.bottom_extension == other.bottom_extension
@@ -77,8 +88,38 @@ impl Filter {
             Filter::GlowFilter(filter) => filter.calculate_dest_rect(source_rect),
             Filter::DropShadowFilter(filter) => filter.calculate_dest_rect(source_rect),
             Filter::BevelFilter(filter) => filter.calculate_dest_rect(source_rect),
+            Filter::GradientGlowFilter(filter) => filter.calculate_dest_rect(source_rect),
+            Filter::GradientBevelFilter(filter) => filter.calculate_dest_rect(source_rect),
             Filter::DisplacementMapFilter(filter) => filter.calculate_dest_rect(source_rect),
-            _ => source_rect,
+            Filter::ShaderFilter(filter) => filter.calculate_dest_rect(source_rect),
+            Filter::ColorMatrixFilter(_) | Filter::ConvolutionFilter(_) => source_rect,
+        }
+    }
+
+    /// Calculates the area affected by applying this filter to a pixel-aligned source area,
+    /// rounded outwards to whole pixels, as reported by `BitmapData.generateFilterRect`.
+    ///
+    /// The source area is clamped to half of the range of `Twips`, leaving room for the
+    /// filter to grow it without overflowing.
+    pub fn calculate_dest_pixel_rect(&self, source_rect: Rectangle<i32>) -> Rectangle<i32> {
+        const LIMIT: i32 = i32::MAX / Twips::TWIPS_PER_PIXEL / 2;
+        let source_rect = Rectangle {
+            x_min: source_rect.x_min.clamp(-LIMIT, LIMIT),
+            x_max: source_rect.x_max.clamp(-LIMIT, LIMIT),
+            y_min: source_rect.y_min.clamp(-LIMIT, LIMIT),
+            y_max: source_rect.y_max.clamp(-LIMIT, LIMIT),
+        };
+        let dest_rect = self.calculate_dest_rect(Rectangle {
+            x_min: Twips::from_pixels_i32(source_rect.x_min),
+            x_max: Twips::from_pixels_i32(source_rect.x_max),
+            y_min: Twips::from_pixels_i32(source_rect.y_min),
+            y_max: Twips::from_pixels_i32(source_rect.y_max),
+        });
+        Rectangle {
+            x_min: dest_rect.x_min.to_pixels().floor() as i32,
+            x_max: dest_rect.x_max.to_pixels().ceil() as i32,
+            y_min: dest_rect.y_min.to_pixels().floor() as i32,
+            y_max: dest_rect.y_max.to_pixels().ceil() as i32,
         }
     }
 
@@ -128,6 +169,7 @@ impl Default for Filter {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisplacementMapFilterComponent {
     Alpha,
     Blue,
@@ -136,6 +178,7 @@ pub enum DisplacementMapFilterComponent {
 }
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisplacementMapFilterMode {
     Clamp,
     Color,
@@ -145,10 +188,12 @@ pub enum DisplacementMapFilterMode {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DisplacementMapFilter {
     pub color: Color,
     pub component_x: u8,
     pub component_y: u8,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub map_bitmap: Option<BitmapHandle>,
     pub map_point: (i32, i32),
     pub mode: DisplacementMapFilterMode,
@@ -182,3 +227,130 @@ impl DisplacementMapFilter {
         // }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::{BlurFilterFlags, DropShadowFilterFlags, Fixed16, Fixed8, GlowFilterFlags};
+
+    const SOURCE: Rectangle<i32> = Rectangle {
+        x_min: 10,
+        x_max: 110,
+        y_min: 20,
+        y_max: 70,
+    };
+
+    fn pixel_rect(x_min: i32, y_min: i32, x_max: i32, y_max: i32) -> Rectangle<i32> {
+        Rectangle {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+        }
+    }
+
+    fn blur(blur_x: f64, blur_y: f64, passes: u8) -> swf::BlurFilter {
+        swf::BlurFilter {
+            blur_x: Fixed16::from_f64(blur_x),
+            blur_y: Fixed16::from_f64(blur_y),
+            flags: BlurFilterFlags::from_passes(passes),
+        }
+    }
+
+    fn glow(flags: GlowFilterFlags) -> swf::GlowFilter {
+        swf::GlowFilter {
+            color: Color::RED,
+            blur_x: Fixed16::from_f64(4.0),
+            blur_y: Fixed16::from_f64(6.0),
+            strength: Fixed8::ONE,
+            flags: flags | GlowFilterFlags::from_passes(1),
+        }
+    }
+
+    #[test]
+    fn blur_grows_with_quality() {
+        let low = Filter::BlurFilter(blur(4.0, 2.0, 1)).calculate_dest_pixel_rect(SOURCE);
+        assert_eq!(low, pixel_rect(6, 18, 114, 72));
+        let high = Filter::BlurFilter(blur(4.0, 2.0, 3)).calculate_dest_pixel_rect(SOURCE);
+        assert!(high.x_min < low.x_min && high.y_max > low.y_max);
+    }
+
+    #[test]
+    fn blur_without_spread_keeps_source() {
+        for filter in [blur(4.0, 4.0, 0), blur(1.0, 1.0, 3), blur(0.0, 0.0, 1)] {
+            assert_eq!(
+                Filter::BlurFilter(filter).calculate_dest_pixel_rect(SOURCE),
+                SOURCE
+            );
+        }
+        let horizontal = Filter::BlurFilter(blur(8.0, 1.0, 1)).calculate_dest_pixel_rect(SOURCE);
+        assert_eq!(horizontal, pixel_rect(2, 20, 118, 70));
+    }
+
+    #[test]
+    fn inner_glows_keep_source() {
+        let outer = Filter::GlowFilter(glow(GlowFilterFlags::KNOCKOUT));
+        assert_eq!(
+            outer.calculate_dest_pixel_rect(SOURCE),
+            pixel_rect(6, 14, 114, 76)
+        );
+        let inner = Filter::GlowFilter(glow(GlowFilterFlags::INNER_GLOW));
+        assert_eq!(inner.calculate_dest_pixel_rect(SOURCE), SOURCE);
+    }
+
+    #[test]
+    fn drop_shadow_grows_towards_its_angle() {
+        let shadow = swf::DropShadowFilter {
+            color: Color::BLACK,
+            blur_x: Fixed16::from_f64(2.0),
+            blur_y: Fixed16::from_f64(2.0),
+            angle: Fixed16::from_f64(std::f64::consts::FRAC_PI_2),
+            distance: Fixed16::from_f64(5.0),
+            strength: Fixed8::ONE,
+            flags: DropShadowFilterFlags::from_passes(1),
+        };
+        assert_eq!(
+            Filter::DropShadowFilter(shadow.clone()).calculate_dest_pixel_rect(SOURCE),
+            pixel_rect(8, 18, 112, 77)
+        );
+
+        let inner = swf::DropShadowFilter {
+            flags: shadow.flags | DropShadowFilterFlags::INNER_SHADOW,
+            ..shadow
+        };
+        assert_eq!(
+            Filter::DropShadowFilter(inner).calculate_dest_pixel_rect(SOURCE),
+            SOURCE
+        );
+    }
+
+    #[test]
+    fn displacement_map_keeps_source() {
+        // Flash seems to grow the area in `Color` mode, see the TODO in
+        // `DisplacementMapFilter::calculate_dest_rect`.
+        for mode in [
+            DisplacementMapFilterMode::Wrap,
+            DisplacementMapFilterMode::Clamp,
+            DisplacementMapFilterMode::Ignore,
+            DisplacementMapFilterMode::Color,
+        ] {
+            let filter = Filter::DisplacementMapFilter(DisplacementMapFilter {
+                mode,
+                scale_x: 9.0,
+                scale_y: -4.0,
+                viewscale_x: 1.0,
+                viewscale_y: 1.0,
+                ..Default::default()
+            });
+            assert_eq!(filter.calculate_dest_pixel_rect(SOURCE), SOURCE);
+        }
+    }
+
+    #[test]
+    fn huge_source_does_not_overflow() {
+        let source = pixel_rect(i32::MIN, -10, i32::MAX, 10);
+        let dest = Filter::BlurFilter(blur(4.0, 2.0, 1)).calculate_dest_pixel_rect(source);
+        assert!(dest.x_min < 0 && dest.x_max > 0);
+        assert_eq!((dest.y_min, dest.y_max), (-12, 12));
+    }
+}
@@ -10,6 +10,7 @@ pub mod lines;
 pub mod matrix;
 pub mod matrix3d;
 pub mod perspective_projection;
+pub mod pixel_bender_interpreter;
 pub mod pixel_bender_support;
 // The `renderdoc` crate doesn't compile on apple platforms
 #[cfg(all(feature = "renderdoc", not(target_vendor = "apple")))]
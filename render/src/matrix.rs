@@ -4,6 +4,7 @@ use swf::{Fixed16, Point, PointDelta, Rectangle, Twips};
 
 /// The transformation matrix used by Flash display objects.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Matrix {
     /// Serialized as `scale_x` in SWF files
     pub a: f32,
@@ -3,6 +3,7 @@ use std::f64::consts::PI;
 use crate::matrix3d::Matrix3D;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerspectiveProjection {
     /// Unit: degree. Must be greater than 0 and less than 180.
     pub field_of_view: f64,
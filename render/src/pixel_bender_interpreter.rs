@@ -0,0 +1,904 @@
+//! A CPU interpreter for Pixel Bender shaders.
+//!
+//! This is used as the fallback implementation of `RenderBackend::run_pixelbender_shader`
+//! for backends which cannot run Pixel Bender shaders on the GPU, and core uses it directly
+//! for `ShaderFilter`s with those shaders (see [`is_cpu_shader`]). Registers are treated
+//! the same way as in `naga-pixelbender`: every register is a 4-component vector, and
+//! operations work on padded vectors, with the destination mask selecting which
+//! components get written. Matrices are stored in consecutive registers, one column each.
+
+use std::any::Any;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::pixel_bender::{
+    Opcode, Operation, PixelBenderParam, PixelBenderReg, PixelBenderRegChannel, PixelBenderRegKind,
+    PixelBenderShader, PixelBenderShaderHandle, PixelBenderShaderImpl, PixelBenderType,
+    OUT_COORD_NAME,
+};
+use crate::pixel_bender_support::{ImageInputTexture, PixelBenderShaderArgument};
+
+/// A Pixel Bender shader which is run on the CPU.
+#[derive(Debug)]
+pub struct CpuPixelBenderShader {
+    shader: PixelBenderShader,
+}
+
+impl PixelBenderShaderImpl for CpuPixelBenderShader {
+    fn parsed_shader(&self) -> &PixelBenderShader {
+        &self.shader
+    }
+}
+
+/// Wraps a parsed shader in a handle which can be run by [`run_shader`].
+pub fn compile_shader(shader: PixelBenderShader) -> PixelBenderShaderHandle {
+    PixelBenderShaderHandle(Arc::new(CpuPixelBenderShader { shader }))
+}
+
+/// Returns `true` if `handle` was created by [`compile_shader`].
+///
+/// The render backend can't read the pixels of a `BitmapHandle` for these shaders,
+/// so image inputs need to be passed in as [`ImageInputTexture::Bytes`] instead.
+pub fn is_cpu_shader(handle: &PixelBenderShaderHandle) -> bool {
+    <dyn Any>::downcast_ref::<CpuPixelBenderShader>(&*handle.0).is_some()
+}
+
+/// How texture samples with out-of-range coordinates behave, matching `naga-pixelbender`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderMode {
+    /// Coordinates are clamped to the edge of the image (`ShaderJob`).
+    ShaderJob,
+    /// Samples outside of the image are transparent black (`ShaderFilter` and blend shaders).
+    Filter,
+}
+
+/// Creates a four-channel image input from premultiplied RGBA8 pixels,
+/// which is how a `BitmapData` input is seen by the shader.
+pub fn image_input_from_rgba(width: u32, height: u32, rgba: &[u8]) -> ImageInputTexture<'static> {
+    ImageInputTexture::Bytes {
+        width,
+        height,
+        channels: 4,
+        bytes: rgba
+            .iter()
+            .flat_map(|value| (f32::from(*value) / 255.0).to_le_bytes())
+            .collect(),
+    }
+}
+
+/// Converts the output of [`run_shader`] with `channels` channels to RGBA8 pixels,
+/// in the same way as writing it to a `BitmapData` texture.
+pub fn output_to_rgba(output: &[u8], channels: usize) -> Vec<u8> {
+    let values: Vec<f32> = output
+        .chunks_exact(std::mem::size_of::<f32>())
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    let mut rgba = Vec::with_capacity(values.len() / channels.max(1) * 4);
+    for pixel in values.chunks_exact(channels.max(1)) {
+        let mut color = [0.0, 0.0, 0.0, 1.0];
+        for (color, value) in color.iter_mut().zip(pixel) {
+            *color = *value;
+        }
+        rgba.extend(color.map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u8));
+    }
+    rgba
+}
+
+/// Runs `shader` for every pixel of a `width` by `height` image, returning the
+/// output as little-endian `f32` values, with one value per output channel.
+///
+/// Any shader handle can be used here - only the parsed shader is needed.
+pub fn run_shader(
+    shader: &PixelBenderShader,
+    arguments: &[PixelBenderShaderArgument],
+    width: u32,
+    height: u32,
+    mode: ShaderMode,
+) -> Result<Vec<u8>, Error> {
+    run_shader_rows(shader, arguments, width, 0..height, mode)
+}
+
+/// Like [`run_shader`], but only runs `shader` for the given rows of the image,
+/// so that a large image can be processed a few rows at a time.
+pub fn run_shader_rows(
+    shader: &PixelBenderShader,
+    arguments: &[PixelBenderShaderArgument],
+    width: u32,
+    rows: Range<u32>,
+    mode: ShaderMode,
+) -> Result<Vec<u8>, Error> {
+    let (output_reg, _) = shader
+        .output_reg()
+        .ok_or_else(|| Error::Unimplemented("Pixel Bender shader without output".into()))?;
+    let output_channels = output_reg.channels.len();
+
+    let program = Program::new(shader)?;
+    let mut initial = Registers::default();
+    let mut images = Vec::new();
+    let out_coord = shader.params.iter().find_map(|param| match param {
+        PixelBenderParam::Normal { reg, name, .. } if name == OUT_COORD_NAME => Some(reg),
+        _ => None,
+    });
+
+    for argument in arguments {
+        match argument {
+            PixelBenderShaderArgument::ValueInput { index, value } => {
+                let Some(PixelBenderParam::Normal { reg, name, .. }) =
+                    shader.params.get(*index as usize)
+                else {
+                    continue;
+                };
+                // The output coordinate is set for every pixel below.
+                if name == OUT_COORD_NAME {
+                    continue;
+                }
+                store_param(&mut initial, value, reg);
+            }
+            PixelBenderShaderArgument::ImageInput { index, texture, .. } => {
+                let index = *index as usize;
+                if index >= images.len() {
+                    images.resize_with(index + 1, || None);
+                }
+                images[index] = texture.as_ref().map(Image::from_texture).transpose()?;
+            }
+        }
+    }
+
+    let mut output = Vec::with_capacity(
+        width as usize * rows.len() * output_channels * std::mem::size_of::<f32>(),
+    );
+    let mut registers = initial.clone();
+    for y in rows {
+        for x in 0..width {
+            registers.clone_from(&initial);
+            if let Some(reg) = out_coord {
+                registers.store([x as f32 + 0.5, y as f32 + 0.5, 0.0, 0.0], reg);
+            }
+            program.run(&mut registers, &images, mode)?;
+
+            let mut pixel = registers.load(output_reg);
+            // Like the GPU implementation, shaders with fewer than four output
+            // channels have an opaque alpha channel.
+            if output_channels < 4 {
+                pixel[3] = 1.0;
+            }
+            for value in &pixel[..output_channels] {
+                output.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Stores the value of a parameter in its register.
+fn store_param(registers: &mut Registers, value: &PixelBenderType, reg: &PixelBenderReg) {
+    // Matrix parameters are in column-major order, like in the wgpu backend.
+    let columns = |size: usize, values: &[f32]| {
+        let mut columns = [[0.0; 4]; 4];
+        for (column, values) in columns.iter_mut().zip(values.chunks_exact(size)) {
+            column[..size].copy_from_slice(values);
+        }
+        columns
+    };
+    let value = match value {
+        PixelBenderType::TFloat(x) => [*x, 0.0, 0.0, 0.0],
+        PixelBenderType::TFloat2(x, y) => [*x, *y, 0.0, 0.0],
+        PixelBenderType::TFloat3(x, y, z) => [*x, *y, *z, 0.0],
+        PixelBenderType::TFloat4(x, y, z, w) => [*x, *y, *z, *w],
+        PixelBenderType::TInt(x) | PixelBenderType::TBool(x) => [*x as f32, 0.0, 0.0, 0.0],
+        PixelBenderType::TInt2(x, y) | PixelBenderType::TBool2(x, y) => {
+            [*x as f32, *y as f32, 0.0, 0.0]
+        }
+        PixelBenderType::TInt3(x, y, z) | PixelBenderType::TBool3(x, y, z) => {
+            [*x as f32, *y as f32, *z as f32, 0.0]
+        }
+        PixelBenderType::TInt4(x, y, z, w) | PixelBenderType::TBool4(x, y, z, w) => {
+            [*x as f32, *y as f32, *z as f32, *w as f32]
+        }
+        PixelBenderType::TString(_) => return,
+        PixelBenderType::TFloat2x2(values) => {
+            return registers.store_matrix(columns(2, values), 2, reg.index)
+        }
+        PixelBenderType::TFloat3x3(values) => {
+            return registers.store_matrix(columns(3, values), 3, reg.index)
+        }
+        PixelBenderType::TFloat4x4(values) => {
+            return registers.store_matrix(columns(4, values), 4, reg.index)
+        }
+    };
+    registers.store(value, reg);
+}
+
+/// Returns the size of the matrix stored in `reg`, or `None` if it's a vector register.
+fn matrix_size(reg: &PixelBenderReg) -> Option<usize> {
+    match reg.channels.as_slice() {
+        [PixelBenderRegChannel::M2x2] => Some(2),
+        [PixelBenderRegChannel::M3x3] => Some(3),
+        [PixelBenderRegChannel::M4x4] => Some(4),
+        _ => None,
+    }
+}
+
+/// The columns of a matrix. Only the first `size` components of the first `size`
+/// columns are used.
+type Matrix = [[f32; 4]; 4];
+
+/// The register file of a single shader invocation.
+#[derive(Clone, Default)]
+struct Registers {
+    float: Vec<[f32; 4]>,
+    int: Vec<[i32; 4]>,
+}
+
+impl Registers {
+    /// Loads a register, swizzled by its channels. Like in `naga-pixelbender`, the unused
+    /// components are padded with the `w` component of the register.
+    fn load(&self, reg: &PixelBenderReg) -> [f32; 4] {
+        let index = reg.index as usize;
+        let raw = match reg.kind {
+            PixelBenderRegKind::Float => self.float.get(index).copied().unwrap_or_default(),
+            PixelBenderRegKind::Int => self
+                .int
+                .get(index)
+                .map(|r| r.map(|v| v as f32))
+                .unwrap_or_default(),
+        };
+        let mut result = [raw[3]; 4];
+        for (value, channel) in result.iter_mut().zip(&reg.channels) {
+            *value = raw[*channel as usize];
+        }
+        result
+    }
+
+    /// Loads the matrix stored in `reg`.
+    fn load_matrix(&self, reg: &PixelBenderReg, size: usize) -> Matrix {
+        let column = |i: usize| {
+            self.float
+                .get(reg.index as usize + i)
+                .copied()
+                .unwrap_or_default()
+        };
+        let mut matrix = [[0.0; 4]; 4];
+        if size == 2 {
+            // A 2x2 matrix fits into a single register.
+            let [x, y, z, w] = column(0);
+            matrix[0] = [x, y, 0.0, 0.0];
+            matrix[1] = [z, w, 0.0, 0.0];
+        } else {
+            for (i, matrix_column) in matrix.iter_mut().enumerate().take(size) {
+                matrix_column[..size].copy_from_slice(&column(i)[..size]);
+            }
+        }
+        matrix
+    }
+
+    /// Stores a matrix in the registers starting at `index`, in the same layout as `load_matrix`.
+    fn store_matrix(&mut self, matrix: Matrix, size: usize, index: u32) {
+        let index = index as usize;
+        let used = if size == 2 { 1 } else { size };
+        if index + used > self.float.len() {
+            self.float.resize(index + used, [0.0; 4]);
+        }
+        if size == 2 {
+            self.float[index] = [matrix[0][0], matrix[0][1], matrix[1][0], matrix[1][1]];
+        } else {
+            for (i, column) in matrix.iter().enumerate().take(size) {
+                self.float[index + i][..size].copy_from_slice(&column[..size]);
+            }
+        }
+    }
+
+    /// Writes the first components of `value` to the channels of `reg`.
+    fn store(&mut self, value: [f32; 4], reg: &PixelBenderReg) {
+        let index = reg.index as usize;
+        match reg.kind {
+            PixelBenderRegKind::Float => {
+                if index >= self.float.len() {
+                    self.float.resize(index + 1, [0.0; 4]);
+                }
+                for (value, channel) in value.iter().zip(&reg.channels) {
+                    self.float[index][*channel as usize] = *value;
+                }
+            }
+            PixelBenderRegKind::Int => {
+                if index >= self.int.len() {
+                    self.int.resize(index + 1, [0; 4]);
+                }
+                for (value, channel) in value.iter().zip(&reg.channels) {
+                    self.int[index][*channel as usize] = *value as i32;
+                }
+            }
+        }
+    }
+}
+
+/// A decoded image input.
+struct Image {
+    width: u32,
+    height: u32,
+    channels: u32,
+    data: Vec<f32>,
+}
+
+impl Image {
+    fn from_texture(texture: &ImageInputTexture) -> Result<Self, Error> {
+        match texture {
+            ImageInputTexture::Bytes {
+                width,
+                height,
+                channels,
+                bytes,
+            } => Ok(Self {
+                width: *width,
+                height: *height,
+                channels: *channels,
+                data: bytes
+                    .chunks_exact(std::mem::size_of::<f32>())
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+            }),
+            ImageInputTexture::Bitmap(_) | ImageInputTexture::TextureRef(_) => Err(
+                Error::Unimplemented("Pixel Bender texture inputs on the CPU".into()),
+            ),
+        }
+    }
+
+    /// Returns the texel at the given position, clamped to the edge of the image.
+    fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = x.clamp(0, i64::from(self.width) - 1) as usize;
+        let y = y.clamp(0, i64::from(self.height) - 1) as usize;
+        let start = (y * self.width as usize + x) * self.channels as usize;
+        let texel = self
+            .data
+            .get(start..start + self.channels as usize)
+            .unwrap_or_default();
+
+        // Match the texture formats used by the GPU implementation: one- and
+        // two-channel images have an opaque alpha, and three-channel images are
+        // padded with zero.
+        let mut result = match self.channels {
+            1 | 2 => [0.0, 0.0, 0.0, 1.0],
+            _ => [0.0; 4],
+        };
+        for (value, texel) in result.iter_mut().zip(texel) {
+            *value = *texel;
+        }
+        result
+    }
+
+    fn sample(&self, coord: [f32; 4], linear: bool, mode: ShaderMode) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+        if mode == ShaderMode::Filter {
+            let x = coord[0] / self.width as f32;
+            let y = coord[1] / self.height as f32;
+            if !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+                return [0.0; 4];
+            }
+        }
+
+        if !linear {
+            return self.texel(coord[0].floor() as i64, coord[1].floor() as i64);
+        }
+
+        // Interpolate between the four nearest texel centers, like a linear GPU sampler.
+        let x = coord[0] - 0.5;
+        let y = coord[1] - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let lerp = |a: [f32; 4], b: [f32; 4], t: f32| -> [f32; 4] {
+            std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+        };
+        let top = lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+        let bottom = lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+/// A validated list of operations, with the jump targets of `If` and `Else` resolved.
+struct Program<'a> {
+    operations: &'a [Operation],
+    /// For an `If`, the index of its `Else` or `EndIf`. For an `Else`, the index of its `EndIf`.
+    jumps: Vec<usize>,
+}
+
+impl<'a> Program<'a> {
+    fn new(shader: &'a PixelBenderShader) -> Result<Self, Error> {
+        let operations = shader.operations.as_slice();
+        let mut jumps = vec![0; operations.len()];
+        let mut blocks: Vec<usize> = Vec::new();
+
+        for (index, op) in operations.iter().enumerate() {
+            match op {
+                Operation::Normal { opcode, dst, src } => {
+                    let supported = match (matrix_size(dst), matrix_size(src)) {
+                        (None, None) => is_supported(*opcode),
+                        (None, Some(_)) => {
+                            matches!(opcode, Opcode::MatVecMul | Opcode::VecMatMul)
+                        }
+                        (Some(_), Some(_)) => matches!(
+                            opcode,
+                            Opcode::Mov
+                                | Opcode::Add
+                                | Opcode::Sub
+                                | Opcode::Mul
+                                | Opcode::MatMatMul
+                        ),
+                        (Some(_), None) => false,
+                    };
+                    if !supported {
+                        return Err(Error::Unimplemented(
+                            format!("Pixel Bender opcode {opcode:?} on the CPU").into(),
+                        ));
+                    }
+                }
+                Operation::If { .. } => blocks.push(index),
+                Operation::Else => {
+                    let Some(start) = blocks.pop() else {
+                        return Err(Error::Unimplemented("Unbalanced Pixel Bender 'if'".into()));
+                    };
+                    jumps[start] = index;
+                    blocks.push(index);
+                }
+                Operation::EndIf => {
+                    let Some(start) = blocks.pop() else {
+                        return Err(Error::Unimplemented("Unbalanced Pixel Bender 'if'".into()));
+                    };
+                    jumps[start] = index;
+                }
+                Operation::Nop
+                | Operation::LoadInt { .. }
+                | Operation::LoadFloat { .. }
+                | Operation::SampleNearest { .. }
+                | Operation::SampleLinear { .. }
+                | Operation::Select { .. } => {}
+            }
+        }
+        if !blocks.is_empty() {
+            return Err(Error::Unimplemented("Unbalanced Pixel Bender 'if'".into()));
+        }
+
+        Ok(Self { operations, jumps })
+    }
+
+    fn run(
+        &self,
+        registers: &mut Registers,
+        images: &[Option<Image>],
+        mode: ShaderMode,
+    ) -> Result<(), Error> {
+        let mut pc = 0;
+        while let Some(op) = self.operations.get(pc) {
+            pc += 1;
+            match op {
+                Operation::Nop | Operation::EndIf => {}
+                Operation::Normal { opcode, dst, src } => {
+                    match (matrix_size(dst), matrix_size(src)) {
+                        (None, None) => run_normal(registers, *opcode, dst, src),
+                        (dst_size, Some(size)) => {
+                            run_matrix(registers, *opcode, dst, dst_size, src, size)
+                        }
+                        (Some(_), None) => unreachable!("Rejected by Program::new"),
+                    }
+                }
+                Operation::LoadInt { dst, val } => registers.store([*val as f32; 4], dst),
+                Operation::LoadFloat { dst, val } => registers.store([*val; 4], dst),
+                Operation::SampleNearest { dst, src, tf }
+                | Operation::SampleLinear { dst, src, tf } => {
+                    let coord = registers.load(src);
+                    let Some(Some(image)) = images.get(*tf as usize) else {
+                        return Err(Error::Unimplemented(
+                            format!("Missing Pixel Bender image input {tf}").into(),
+                        ));
+                    };
+                    let linear = matches!(op, Operation::SampleLinear { .. });
+                    registers.store(image.sample(coord, linear, mode), dst);
+                }
+                Operation::If { src } => {
+                    if registers.load(src)[0] == 0.0 {
+                        pc = self.jumps[pc - 1] + 1;
+                    }
+                }
+                Operation::Else => {
+                    // We only reach an 'else' at the end of the taken 'if' branch.
+                    pc = self.jumps[pc - 1] + 1;
+                }
+                Operation::Select {
+                    src1,
+                    src2,
+                    condition,
+                    dst,
+                } => {
+                    let value = if registers.load(condition)[0] == 1.0 {
+                        registers.load(src1)
+                    } else {
+                        registers.load(src2)
+                    };
+                    registers.store(value, dst);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_supported(opcode: Opcode) -> bool {
+    !matches!(
+        opcode,
+        Opcode::MatMatMul
+            | Opcode::VecMatMul
+            | Opcode::MatVecMul
+            // These aren't supported by `naga-pixelbender` either.
+            | Opcode::VectorEqual
+            | Opcode::VectorNotEqual
+            | Opcode::BoolAny
+            | Opcode::BoolAll
+            | Opcode::BoolToInt
+            | Opcode::SampleNearest
+            | Opcode::SampleLinear
+            | Opcode::LoadIntOrFloat
+            | Opcode::Select
+            | Opcode::If
+            | Opcode::Else
+            | Opcode::EndIf
+            | Opcode::PBJMeta1
+            | Opcode::PBJParam
+            | Opcode::PBJMeta2
+            | Opcode::PBJParamTexture
+            | Opcode::Name
+            | Opcode::Version
+    )
+}
+
+/// Returns the channels of `reg` which are actually used (without padding).
+fn unpadded(registers: &Registers, reg: &PixelBenderReg) -> ([f32; 4], usize) {
+    (registers.load(reg), reg.channels.len())
+}
+
+fn run_normal(
+    registers: &mut Registers,
+    opcode: Opcode,
+    dst: &PixelBenderReg,
+    src_reg: &PixelBenderReg,
+) {
+    let src = registers.load(src_reg);
+    let left = registers.load(dst);
+    let map = |f: &dyn Fn(f32) -> f32| src.map(f);
+    let zip =
+        |f: &dyn Fn(f32, f32) -> f32| -> [f32; 4] { std::array::from_fn(|i| f(left[i], src[i])) };
+    let bool_to_f32 = |b: bool| if b { 1.0 } else { 0.0 };
+
+    let mut dst = dst;
+    let comparison_dst;
+    let result = match opcode {
+        Opcode::Nop => return,
+        Opcode::Mov => src,
+        Opcode::Add => zip(&|a, b| a + b),
+        Opcode::Sub => zip(&|a, b| a - b),
+        Opcode::Mul => zip(&|a, b| a * b),
+        Opcode::Div => zip(&|a, b| a / b),
+        Opcode::Rcp => map(&|v| 1.0 / v),
+        Opcode::Mod => zip(&|a, b| a % b),
+        Opcode::Pow => zip(&|a, b| a.powf(b)),
+        Opcode::Atan2 => zip(&|a, b| a.atan2(b)),
+        Opcode::Min => zip(&|a, b| b.min(a)),
+        Opcode::Max => zip(&|a, b| b.max(a)),
+        // `step(edge, x)`, where `src` is the edge
+        Opcode::Step => zip(&|a, b| bool_to_f32(a >= b)),
+        Opcode::Sin => map(&f32::sin),
+        Opcode::Cos => map(&f32::cos),
+        Opcode::Tan => map(&f32::tan),
+        Opcode::Asin => map(&f32::asin),
+        Opcode::Acos => map(&f32::acos),
+        Opcode::Atan => map(&f32::atan),
+        Opcode::Exp => map(&f32::exp),
+        Opcode::Exp2 => map(&f32::exp2),
+        Opcode::Log => map(&f32::ln),
+        Opcode::Log2 => map(&f32::log2),
+        Opcode::Sqrt => map(&f32::sqrt),
+        Opcode::RSqrt => map(&|v| 1.0 / v.sqrt()),
+        Opcode::Abs => map(&f32::abs),
+        Opcode::Sign => map(&|v| if v == 0.0 { 0.0 } else { v.signum() }),
+        Opcode::Floor => map(&f32::floor),
+        Opcode::Ceil => map(&f32::ceil),
+        Opcode::Fract => map(&|v| v - v.floor()),
+        Opcode::FloatToInt => map(&f32::round_ties_even),
+        Opcode::IntToFloat | Opcode::IntToBool => src,
+        Opcode::FloatToBool => map(&|v| bool_to_f32(v != 0.0)),
+        // Flash Player always produces zero here, see `naga-pixelbender`.
+        Opcode::BoolToFloat => [0.0; 4],
+        // Flash Player implements this as a bitwise not, see `naga-pixelbender`.
+        Opcode::LogicalNot => map(&|v| !(v as i32) as f32),
+        Opcode::LogicalAnd => zip(&|a, b| bool_to_f32(a != 0.0 && b != 0.0)),
+        Opcode::LogicalOr => zip(&|a, b| bool_to_f32(a != 0.0 || b != 0.0)),
+        Opcode::LogicalXor => zip(&|a, b| bool_to_f32((a != 0.0) != (b != 0.0))),
+        Opcode::Length => {
+            let (src, len) = unpadded(registers, src_reg);
+            [src[..len].iter().map(|v| v * v).sum::<f32>().sqrt(); 4]
+        }
+        Opcode::Distance => {
+            let (left, len) = unpadded(registers, dst);
+            let distance = left[..len]
+                .iter()
+                .zip(&src)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>()
+                .sqrt();
+            [distance; 4]
+        }
+        Opcode::DotProduct => {
+            let (left, len) = unpadded(registers, dst);
+            [left[..len].iter().zip(&src).map(|(a, b)| a * b).sum(); 4]
+        }
+        Opcode::Normalize => {
+            let (src, len) = unpadded(registers, src_reg);
+            let length = src[..len].iter().map(|v| v * v).sum::<f32>().sqrt();
+            if src_reg.is_scalar() {
+                [src[0] / length; 4]
+            } else {
+                src.map(|v| v / length)
+            }
+        }
+        Opcode::CrossProduct => [
+            left[1] * src[2] - left[2] * src[1],
+            left[2] * src[0] - left[0] * src[2],
+            left[0] * src[1] - left[1] * src[0],
+            0.0,
+        ],
+        Opcode::Equal | Opcode::NotEqual | Opcode::LessThan | Opcode::LessThanEqual => {
+            let result = match opcode {
+                Opcode::Equal => left[0] == src[0],
+                Opcode::NotEqual => left[0] != src[0],
+                Opcode::LessThan => left[0] < src[0],
+                _ => left[0] <= src[0],
+            };
+            // Comparisons write their result to the first component of int register 0.
+            comparison_dst = PixelBenderReg {
+                index: 0,
+                channels: vec![PixelBenderRegChannel::R],
+                kind: PixelBenderRegKind::Int,
+            };
+            dst = &comparison_dst;
+            [bool_to_f32(result); 4]
+        }
+        _ => unreachable!("Rejected by Program::new"),
+    };
+    registers.store(result, dst);
+}
+
+/// Runs an operation with a matrix source. The destination is either a matrix,
+/// or the vector operand of `MatVecMul` and `VecMatMul`.
+fn run_matrix(
+    registers: &mut Registers,
+    opcode: Opcode,
+    dst: &PixelBenderReg,
+    dst_size: Option<usize>,
+    src: &PixelBenderReg,
+    size: usize,
+) {
+    let src = registers.load_matrix(src, size);
+    let Some(dst_size) = dst_size else {
+        let (vector, _) = unpadded(registers, dst);
+        let result: [f32; 4] = match opcode {
+            // `matrix * vector`
+            Opcode::MatVecMul => {
+                std::array::from_fn(|row| (0..size).map(|col| src[col][row] * vector[col]).sum())
+            }
+            // `vector * matrix`
+            _ => std::array::from_fn(|col| (0..size).map(|row| vector[row] * src[col][row]).sum()),
+        };
+        registers.store(result, dst);
+        return;
+    };
+
+    let left = registers.load_matrix(dst, dst_size);
+    let zip = |f: fn(f32, f32) -> f32| -> Matrix {
+        std::array::from_fn(|col| std::array::from_fn(|row| f(left[col][row], src[col][row])))
+    };
+    let result = match opcode {
+        Opcode::Mov => src,
+        Opcode::Add => zip(|a, b| a + b),
+        Opcode::Sub => zip(|a, b| a - b),
+        // `dst * src`, as a matrix product
+        _ => std::array::from_fn(|col| {
+            std::array::from_fn(|row| (0..size).map(|k| left[k][row] * src[col][k]).sum())
+        }),
+    };
+    registers.store_matrix(result, dst_size, dst.index);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_bender::{PixelBenderParamQualifier, PixelBenderTypeOpcode};
+    use PixelBenderRegChannel::*;
+
+    fn float_reg(index: u32, channels: &[PixelBenderRegChannel]) -> PixelBenderReg {
+        PixelBenderReg {
+            index,
+            channels: channels.to_vec(),
+            kind: PixelBenderRegKind::Float,
+        }
+    }
+
+    fn param(
+        qualifier: PixelBenderParamQualifier,
+        param_type: PixelBenderTypeOpcode,
+        reg: PixelBenderReg,
+        name: &str,
+    ) -> PixelBenderParam {
+        PixelBenderParam::Normal {
+            qualifier,
+            param_type,
+            reg,
+            name: name.to_string(),
+            metadata: vec![],
+        }
+    }
+
+    fn shader(params: Vec<PixelBenderParam>, operations: Vec<Operation>) -> PixelBenderShader {
+        PixelBenderShader {
+            name: "test".to_string(),
+            version: 1,
+            params,
+            metadata: vec![],
+            operations,
+        }
+    }
+
+    fn to_floats(output: &[u8]) -> Vec<f32> {
+        output
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn sample_linear() {
+        // Samples a two pixel wide image half a pixel to the right of the output coordinate.
+        let shader = shader(
+            vec![
+                param(
+                    PixelBenderParamQualifier::Input,
+                    PixelBenderTypeOpcode::TFloat2,
+                    float_reg(0, &[R, G]),
+                    OUT_COORD_NAME,
+                ),
+                PixelBenderParam::Texture {
+                    index: 0,
+                    channels: 1,
+                    name: "src".to_string(),
+                },
+                param(
+                    PixelBenderParamQualifier::Output,
+                    PixelBenderTypeOpcode::TFloat,
+                    float_reg(1, &[R]),
+                    "dst",
+                ),
+            ],
+            vec![
+                Operation::LoadFloat {
+                    dst: float_reg(2, &[R]),
+                    val: 0.5,
+                },
+                Operation::Normal {
+                    opcode: Opcode::Add,
+                    dst: float_reg(0, &[R]),
+                    src: float_reg(2, &[R]),
+                },
+                Operation::SampleLinear {
+                    dst: float_reg(1, &[R]),
+                    src: float_reg(0, &[R, G]),
+                    tf: 0,
+                },
+            ],
+        );
+        let arguments = [PixelBenderShaderArgument::ImageInput {
+            index: 0,
+            channels: 1,
+            name: "src".to_string(),
+            texture: Some(ImageInputTexture::Bytes {
+                width: 2,
+                height: 1,
+                channels: 1,
+                bytes: [0.0f32, 1.0].iter().flat_map(|v| v.to_le_bytes()).collect(),
+            }),
+        }];
+
+        let output = run_shader(&shader, &arguments, 3, 1, ShaderMode::ShaderJob)
+            .expect("Shader should run");
+        assert_eq!(to_floats(&output), [0.5, 1.0, 1.0]);
+
+        // The last sample is outside of the image.
+        let output =
+            run_shader(&shader, &arguments, 3, 1, ShaderMode::Filter).expect("Shader should run");
+        assert_eq!(to_floats(&output), [0.5, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn matrix_vector_multiplication() {
+        let run = |opcode| {
+            let shader = shader(
+                vec![
+                    param(
+                        PixelBenderParamQualifier::Input,
+                        PixelBenderTypeOpcode::TFloat2x2,
+                        float_reg(0, &[M2x2]),
+                        "matrix",
+                    ),
+                    param(
+                        PixelBenderParamQualifier::Input,
+                        PixelBenderTypeOpcode::TFloat2,
+                        float_reg(1, &[R, G]),
+                        "vector",
+                    ),
+                    param(
+                        PixelBenderParamQualifier::Output,
+                        PixelBenderTypeOpcode::TFloat2,
+                        float_reg(1, &[R, G]),
+                        "dst",
+                    ),
+                ],
+                vec![Operation::Normal {
+                    opcode,
+                    dst: float_reg(1, &[R, G]),
+                    src: float_reg(0, &[M2x2]),
+                }],
+            );
+            let arguments = [
+                PixelBenderShaderArgument::ValueInput {
+                    index: 0,
+                    value: PixelBenderType::TFloat2x2([1.0, 2.0, 3.0, 4.0]),
+                },
+                PixelBenderShaderArgument::ValueInput {
+                    index: 1,
+                    value: PixelBenderType::TFloat2(1.0, 1.0),
+                },
+            ];
+            to_floats(
+                &run_shader(&shader, &arguments, 1, 1, ShaderMode::ShaderJob)
+                    .expect("Shader should run"),
+            )
+        };
+
+        // The matrix is in column-major order.
+        assert_eq!(run(Opcode::MatVecMul), [4.0, 6.0]);
+        assert_eq!(run(Opcode::VecMatMul), [3.0, 7.0]);
+    }
+
+    #[test]
+    fn run_rows() {
+        // Outputs the y coordinate of every pixel.
+        let shader = shader(
+            vec![
+                param(
+                    PixelBenderParamQualifier::Input,
+                    PixelBenderTypeOpcode::TFloat2,
+                    float_reg(0, &[R, G]),
+                    OUT_COORD_NAME,
+                ),
+                param(
+                    PixelBenderParamQualifier::Output,
+                    PixelBenderTypeOpcode::TFloat,
+                    float_reg(1, &[R]),
+                    "dst",
+                ),
+            ],
+            vec![Operation::Normal {
+                opcode: Opcode::Mov,
+                dst: float_reg(1, &[R]),
+                src: float_reg(0, &[G]),
+            }],
+        );
+
+        let output = run_shader_rows(&shader, &[], 2, 1..3, ShaderMode::ShaderJob)
+            .expect("Shader should run");
+        assert_eq!(to_floats(&output), [1.5, 1.5, 2.5, 2.5]);
+        let all = run_shader(&shader, &[], 2, 3, ShaderMode::ShaderJob).expect("Shader should run");
+        assert_eq!(&all[2 * 4..], &output[..]);
+    }
+}
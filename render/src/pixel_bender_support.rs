@@ -1,6 +1,6 @@
 use crate::backend::RawTexture;
 use crate::bitmap::BitmapHandle;
-use crate::pixel_bender::PixelBenderType;
+use crate::pixel_bender::{PixelBenderShaderHandle, PixelBenderType};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PixelBenderShaderArgument<'a> {
@@ -16,6 +16,16 @@ pub enum PixelBenderShaderArgument<'a> {
     },
 }
 
+/// A shape fill which evaluates a Pixel Bender shader for every pixel,
+/// as created by `Graphics.beginShaderFill`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderFill {
+    pub shader: PixelBenderShaderHandle,
+    pub arguments: Vec<PixelBenderShaderArgument<'static>>,
+    /// Transforms from shader space (in pixels) to shape space (in twips).
+    pub matrix: swf::Matrix,
+}
+
 /// An image input.
 ///
 /// This accepts both an owned BitmapHandle, and a borrowed texture
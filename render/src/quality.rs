@@ -10,6 +10,7 @@ use std::str::FromStr;
 /// [StageQuality in the AS3 Reference](https://help.adobe.com/en_US/FlashPlatform/reference/actionscript/3/flash/display/StageQuality.html)
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StageQuality {
     /// No anti-aliasing, and bitmaps are never smoothed.
     Low,
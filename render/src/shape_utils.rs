@@ -1,4 +1,5 @@
 use crate::matrix::Matrix;
+use crate::pixel_bender_support::ShaderFill;
 use enum_map::Enum;
 use smallvec::SmallVec;
 use swf::{CharacterId, FillStyle, LineStyle, Rectangle, Shape, ShapeRecord, Twips};
@@ -7,6 +8,7 @@ use swf::{CharacterId, FillStyle, LineStyle, Rectangle, Shape, ShapeRecord, Twip
 const CUBIC_CURVE_TOLERANCE: f64 = 0.01;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FillRule {
     EvenOdd,
     NonZero,
@@ -95,7 +97,7 @@ pub fn calculate_shape_bounds(shape_records: &[swf::ShapeRecord]) -> swf::Rectan
     bounds
 }
 
-/// `DrawPath` represents a solid fill or a stroke.
+/// `DrawPath` represents a solid fill, a shader fill or a stroke.
 /// Fills are always closed paths, while strokes may be open or closed.
 /// Closed paths will have the first point equal to the last point.
 #[derive(Clone, Debug, PartialEq)]
@@ -110,6 +112,11 @@ pub enum DrawPath<'a> {
         commands: Vec<DrawCommand>,
         winding_rule: FillRule,
     },
+    ShaderFill {
+        fill: &'a ShaderFill,
+        commands: Vec<DrawCommand>,
+        winding_rule: FillRule,
+    },
 }
 
 /// `DistilledShape` represents a ready-to-be-consumed collection of paths (both fills and strokes)
@@ -136,6 +143,7 @@ impl<'a> From<&'a swf::Shape> for DistilledShape<'a> {
 /// `DrawCommands` trace the outline of a path.
 /// Fills follow the even-odd fill rule, with opposite winding for holes.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrawCommand {
     MoveTo(swf::Point<Twips>),
     LineTo(swf::Point<Twips>),
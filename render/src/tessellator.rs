@@ -1,4 +1,5 @@
 use crate::bitmap::BitmapSource;
+use crate::pixel_bender_support::{PixelBenderShaderArgument, ShaderFill};
 use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath, GradientType};
 use indexmap::IndexSet;
 use lyon::path::Path;
@@ -50,21 +51,54 @@ impl ShapeTessellator {
                     style,
                     commands,
                     winding_rule: _,
-                } => (*style, ruffle_path_to_lyon_path(commands, true), false),
+                } => (
+                    PathFill::Style(style),
+                    ruffle_path_to_lyon_path(commands, true),
+                    false,
+                ),
+                DrawPath::ShaderFill {
+                    fill,
+                    commands,
+                    winding_rule: _,
+                } => (
+                    PathFill::Shader(fill),
+                    ruffle_path_to_lyon_path(commands, true),
+                    false,
+                ),
                 DrawPath::Stroke {
                     style,
                     commands,
                     is_closed,
                 } => (
-                    style.fill_style(),
+                    PathFill::Style(style.fill_style()),
                     ruffle_path_to_lyon_path(commands, *is_closed),
                     true,
                 ),
             };
 
             let (draw, color, needs_flush) = match fill_style {
-                swf::FillStyle::Color(color) => (DrawType::Color, *color, false),
-                swf::FillStyle::LinearGradient(gradient) => {
+                PathFill::Shader(fill) => {
+                    let has_missing_input = fill.arguments.iter().any(|argument| {
+                        matches!(
+                            argument,
+                            PixelBenderShaderArgument::ImageInput { texture: None, .. }
+                        )
+                    });
+                    if has_missing_input {
+                        // Nothing to sample from -- the shader can't be evaluated.
+                        continue;
+                    }
+                    (
+                        DrawType::Shader(Shader {
+                            matrix: swf_bitmap_to_gl_matrix(fill.matrix.into(), 1, 1),
+                            fill: fill.clone(),
+                        }),
+                        swf::Color::WHITE,
+                        true,
+                    )
+                }
+                PathFill::Style(swf::FillStyle::Color(color)) => (DrawType::Color, *color, false),
+                PathFill::Style(swf::FillStyle::LinearGradient(gradient)) => {
                     let uniform =
                         swf_gradient_to_uniforms(GradientType::Linear, gradient, swf::Fixed8::ZERO);
                     let (gradient_index, _) = self.gradients.insert_full(uniform);
@@ -78,7 +112,7 @@ impl ShapeTessellator {
                         true,
                     )
                 }
-                swf::FillStyle::RadialGradient(gradient) => {
+                PathFill::Style(swf::FillStyle::RadialGradient(gradient)) => {
                     let uniform =
                         swf_gradient_to_uniforms(GradientType::Radial, gradient, swf::Fixed8::ZERO);
                     let (gradient_index, _) = self.gradients.insert_full(uniform);
@@ -91,10 +125,10 @@ impl ShapeTessellator {
                         true,
                     )
                 }
-                swf::FillStyle::FocalGradient {
+                PathFill::Style(swf::FillStyle::FocalGradient {
                     gradient,
                     focal_point,
-                } => {
+                }) => {
                     let uniform =
                         swf_gradient_to_uniforms(GradientType::Focal, gradient, *focal_point);
                     let (gradient_index, _) = self.gradients.insert_full(uniform);
@@ -107,12 +141,12 @@ impl ShapeTessellator {
                         true,
                     )
                 }
-                swf::FillStyle::Bitmap {
+                PathFill::Style(swf::FillStyle::Bitmap {
                     id,
                     matrix,
                     is_smoothed,
                     is_repeating,
-                } => {
+                }) => {
                     if let Some(bitmap) = bitmap_source.bitmap_size(*id) {
                         (
                             DrawType::Bitmap(Bitmap {
@@ -152,11 +186,13 @@ impl ShapeTessellator {
             let mut buffers_builder =
                 BuffersBuilder::new(&mut self.lyon_mesh, RuffleVertexCtor { color });
             let result = match path {
-                DrawPath::Fill { winding_rule, .. } => self.fill_tess.tessellate_path(
-                    &lyon_path,
-                    &FillOptions::default().with_fill_rule(winding_rule.into()),
-                    &mut buffers_builder,
-                ),
+                DrawPath::Fill { winding_rule, .. } | DrawPath::ShaderFill { winding_rule, .. } => {
+                    self.fill_tess.tessellate_path(
+                        &lyon_path,
+                        &FillOptions::default().with_fill_rule(winding_rule.into()),
+                        &mut buffers_builder,
+                    )
+                }
                 DrawPath::Stroke { style, .. } => {
                     // TODO(Herschel): 0 width indicates "hairline".
                     let width = (style.width().to_pixels() as f32).max(1.0);
@@ -264,6 +300,7 @@ pub enum DrawType {
         gradient: usize,
     },
     Bitmap(Bitmap),
+    Shader(Shader),
 }
 
 impl DrawType {
@@ -272,10 +309,17 @@ impl DrawType {
             Self::Color => "Color",
             Self::Gradient { .. } => "Gradient",
             Self::Bitmap { .. } => "Bitmap",
+            Self::Shader { .. } => "Shader",
         }
     }
 }
 
+/// The fill style of a path being tessellated.
+enum PathFill<'a> {
+    Style(&'a swf::FillStyle),
+    Shader(&'a ShaderFill),
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Gradient {
     pub gradient_type: GradientType,
@@ -300,6 +344,13 @@ pub struct Bitmap {
     pub is_repeating: bool,
 }
 
+#[derive(Clone, Debug)]
+pub struct Shader {
+    /// Transforms from object space (in pixels) to shader space (in pixels).
+    pub matrix: [[f32; 3]; 3],
+    pub fill: ShaderFill,
+}
+
 #[expect(clippy::many_single_char_names)]
 fn swf_to_gl_matrix(m: crate::matrix::Matrix) -> [[f32; 3]; 3] {
     let tx = m.tx.get() as f32;
@@ -5,6 +5,7 @@ use swf::ColorTransform;
 /// Represents the transform for a DisplayObject.
 /// This includes both the transformation matrix and the color transform.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transform {
     pub matrix: Matrix,
     pub color_transform: ColorTransform,
@@ -4,8 +4,8 @@
 
 use bytemuck::{Pod, Zeroable};
 use ruffle_render::backend::{
-    BitmapCacheEntry, Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget,
-    RenderBackend, ShapeHandle, ShapeHandleImpl, ViewportDimensions,
+    BitmapCacheEntry, Context3D, Context3DProfile, RenderBackend, ShapeHandle, ShapeHandleImpl,
+    ViewportDimensions,
 };
 use ruffle_render::bitmap::{
     Bitmap, BitmapFormat, BitmapHandle, BitmapHandleImpl, BitmapSource, PixelRegion, PixelSnapping,
@@ -575,6 +575,11 @@ impl WebGlRenderBackend {
 
         let mut draws = Vec::with_capacity(lyon_mesh.draws.len());
         for draw in lyon_mesh.draws {
+            if let TessDrawType::Shader(_) = draw.draw_type {
+                log::warn!("Shader fills are not supported by the WebGL renderer");
+                continue;
+            }
+
             let num_indices = draw.indices.len() as i32;
             let num_mask_indices = draw.mask_index_count as i32;
 
@@ -602,6 +607,7 @@ impl WebGlRenderBackend {
                 TessDrawType::Color => &self.color_program,
                 TessDrawType::Gradient { .. } => &self.gradient_program,
                 TessDrawType::Bitmap(_) => &self.bitmap_program,
+                TessDrawType::Shader(_) => unreachable!("Shader fills are skipped above"),
             };
 
             // Unfortunately it doesn't seem to be possible to ensure that vertex attributes will be in
@@ -687,6 +693,7 @@ impl WebGlRenderBackend {
                     num_indices,
                     num_mask_indices,
                 },
+                TessDrawType::Shader(_) => unreachable!("Shader fills are skipped above"),
             });
 
             self.bind_vertex_array(None);
@@ -1192,15 +1199,6 @@ impl RenderBackend for WebGlRenderBackend {
 
     fn set_quality(&mut self, _quality: StageQuality) {}
 
-    fn compile_pixelbender_shader(
-        &mut self,
-        _shader: ruffle_render::pixel_bender::PixelBenderShader,
-    ) -> Result<ruffle_render::pixel_bender::PixelBenderShaderHandle, BitmapError> {
-        Err(BitmapError::Unimplemented(
-            "compile_pixelbender_shader".into(),
-        ))
-    }
-
     fn resolve_sync_handle(
         &mut self,
         _handle: Box<dyn SyncHandle>,
@@ -1211,15 +1209,6 @@ impl RenderBackend for WebGlRenderBackend {
         ))
     }
 
-    fn run_pixelbender_shader(
-        &mut self,
-        _handle: ruffle_render::pixel_bender::PixelBenderShaderHandle,
-        _arguments: &[ruffle_render::pixel_bender_support::PixelBenderShaderArgument],
-        _target: &PixelBenderTarget,
-    ) -> Result<PixelBenderOutput, BitmapError> {
-        Err(BitmapError::Unimplemented("run_pixelbender_shader".into()))
-    }
-
     fn create_empty_texture(
         &mut self,
         width: u32,
@@ -0,0 +1,69 @@
+// NOTE: The `shader_filter_common.wgsl` source is prepended to this before compilation.
+
+struct Filter {
+    default_color: vec4<f32>,
+    matrix_cols: u32,
+    matrix_rows: u32,
+    divisor: f32,
+    bias: f32,
+    source_x: u32,
+    source_y: u32,
+    source_width: u32,
+    source_height: u32,
+    clamp: u32,
+    preserve_alpha: u32,
+    _padding: vec2<u32>,
+}
+
+@group(0) @binding(0) var texture: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> filter_args: Filter;
+@group(0) @binding(2) var matrix_texture: texture_2d<f32>;
+
+@vertex
+fn main_vertex(in: filter__VertexInput) -> filter__VertexOutput {
+    return filter__main_vertex(in);
+}
+
+/// Loads the un-multiplied color of a pixel, relative to the top left of the source area.
+/// Pixels outside of that area are either clamped to its edges or take the default color.
+fn load_pixel(position: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(i32(filter_args.source_width), i32(filter_args.source_height));
+    var color = filter_args.default_color;
+    if (filter_args.clamp > 0u) {
+        let clamped = clamp(position, vec2<i32>(0, 0), size - vec2<i32>(1, 1));
+        color = textureLoad(texture, clamped + vec2<i32>(i32(filter_args.source_x), i32(filter_args.source_y)), 0);
+    } else if (all(position >= vec2<i32>(0, 0)) && all(position < size)) {
+        color = textureLoad(texture, position + vec2<i32>(i32(filter_args.source_x), i32(filter_args.source_y)), 0);
+    }
+    if (color.a > 0.0) {
+        return vec4<f32>(color.rgb / color.a, color.a);
+    }
+    return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+}
+
+@fragment
+fn main_fragment(in: filter__VertexOutput) -> @location(0) vec4<f32> {
+    let position = vec2<i32>(floor(in.position.xy));
+    let cols = i32(filter_args.matrix_cols);
+    let rows = i32(filter_args.matrix_rows);
+    let center = vec2<i32>(cols / 2, rows / 2);
+
+    var sum = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    for (var y = 0; y < rows; y++) {
+        for (var x = 0; x < cols; x++) {
+            let weight = textureLoad(matrix_texture, vec2<i32>(x, y), 0).r;
+            sum += load_pixel(position + vec2<i32>(x, y) - center) * weight;
+        }
+    }
+
+    // Flash treats a divisor of 0 as 1, and the bias is in the 0-255 range of a color channel.
+    var divisor = filter_args.divisor;
+    if (divisor == 0.0) {
+        divisor = 1.0;
+    }
+    var color = saturate(sum / divisor + vec4<f32>(filter_args.bias / 255.0));
+    if (filter_args.preserve_alpha > 0u) {
+        color.a = load_pixel(position).a;
+    }
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
@@ -0,0 +1,23 @@
+/// Vertex shader used for drawing Pixel Bender shader fills.
+/// The fragment shader is generated from the Pixel Bender shader by `naga_pixelbender`,
+/// and applies the color transform passed along here to the shader output.
+/// NOTE: The `common.wgsl` source is prepended to this before compilation.
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    /// The position in shader space, passed to the shader as `outCoord()`.
+    @location(0) coord: vec2<f32>,
+    @location(1) @interpolate(flat) mult_color: vec4<f32>,
+    @location(2) @interpolate(flat) add_color: vec4<f32>,
+};
+
+@group(1) @binding(0) var<uniform> transforms: common__Transforms;
+@group(2) @binding(0) var<uniform> textureTransforms: common__TextureTransforms;
+
+@vertex
+fn main_vertex(in: common__VertexInput) -> VertexOutput {
+    let matrix_ = textureTransforms.texture_matrix;
+    let coord = (mat3x3<f32>(matrix_[0].xyz, matrix_[1].xyz, matrix_[2].xyz) * vec3<f32>(in.position, 1.0)).xy;
+    let pos = common__globals.view_matrix * transforms.world_matrix * vec4<f32>(in.position.x, in.position.y, 0.0, 1.0);
+    return VertexOutput(pos, coord, transforms.mult_color, transforms.add_color);
+}
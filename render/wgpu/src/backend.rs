@@ -15,7 +15,8 @@ use crate::{
 };
 use image::imageops::FilterType;
 use ruffle_render::backend::{
-    BitmapCacheEntry, Context3D, Context3DProfile, PixelBenderOutput, PixelBenderTarget,
+    BitmapCacheEntry, Context3D, Context3DProfile, PendingPixelBenderBytes, PixelBenderOutput,
+    PixelBenderTarget,
 };
 use ruffle_render::backend::{RenderBackend, ShapeHandle, ViewportDimensions};
 use ruffle_render::bitmap::{
@@ -764,6 +765,9 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 | Filter::ShaderFilter(_)
                 | Filter::BevelFilter(_)
                 | Filter::DisplacementMapFilter(_)
+                | Filter::ConvolutionFilter(_)
+                | Filter::GradientGlowFilter(_)
+                | Filter::GradientBevelFilter(_)
         )
     }
 
@@ -970,44 +974,12 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         match target {
             PixelBenderTarget::Bitmap(_) => Ok(PixelBenderOutput::Bitmap(sync_handle)),
             PixelBenderTarget::Bytes { width, .. } => {
-                let mut output = None;
-                self.resolve_sync_handle(
-                    sync_handle,
-                    Box::new(|raw_pixels, buffer_width| {
-                        if buffer_width as usize
-                            != *width as usize * output_channels * std::mem::size_of::<f32>()
-                        {
-                            let channels_in_raw_pixels = if has_padding { 4usize } else { 3usize };
-
-                            let mut new_pixels = Vec::new();
-                            for row in raw_pixels.chunks(buffer_width as usize) {
-                                // Ignore any wgpu-added padding (this is distinct from the alpha-channel padding
-                                // that we add for pixelbender)
-                                let actual_row = &row[0..(*width as usize
-                                    * channels_in_raw_pixels
-                                    * std::mem::size_of::<f32>())];
-
-                                for pixel in actual_row.chunks_exact(
-                                    channels_in_raw_pixels * std::mem::size_of::<f32>(),
-                                ) {
-                                    if has_padding {
-                                        // Take the first three channels
-                                        new_pixels.extend_from_slice(
-                                            &pixel[0..(3 * std::mem::size_of::<f32>())],
-                                        );
-                                    } else {
-                                        // Copy the pixel as-is
-                                        new_pixels.extend_from_slice(pixel);
-                                    }
-                                }
-                            }
-                            output = Some(new_pixels);
-                        } else {
-                            output = Some(raw_pixels.to_vec());
-                        };
-                    }),
-                )?;
-                Ok(PixelBenderOutput::Bytes(output.unwrap()))
+                Ok(PixelBenderOutput::PendingBytes(PendingPixelBenderBytes {
+                    handle: sync_handle,
+                    width: *width,
+                    channels: output_channels,
+                    has_padding,
+                }))
             }
         }
     }
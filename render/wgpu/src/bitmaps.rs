@@ -6,6 +6,9 @@ pub struct BitmapSamplers {
     pub repeat_nearest: wgpu::Sampler,
     pub clamp_linear: wgpu::Sampler,
     pub clamp_nearest: wgpu::Sampler,
+    /// Like `clamp_linear`, but without interpolating between mip levels.
+    /// Used for the bilinear sampler slot of Pixel Bender shaders.
+    pub clamp_bilinear: wgpu::Sampler,
     pub clamp_u_repeat_v_linear: wgpu::Sampler,
     pub clamp_u_repeat_v_nearest: wgpu::Sampler,
     pub repeat_u_clamp_v_linear: wgpu::Sampler,
@@ -75,6 +78,15 @@ impl BitmapSamplers {
             wgpu::FilterMode::Nearest,
             create_debug_label!("Clamp & Nearest sampler"),
         );
+        let clamp_bilinear = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: create_debug_label!("Clamp & Bilinear sampler").as_deref(),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
 
         let clamp_u_repeat_v_linear = create_sampler(
             device,
@@ -141,6 +153,7 @@ impl BitmapSamplers {
             repeat_nearest,
             clamp_linear,
             clamp_nearest,
+            clamp_bilinear,
             clamp_u_repeat_v_linear,
             clamp_u_repeat_v_nearest,
             repeat_u_clamp_v_linear,
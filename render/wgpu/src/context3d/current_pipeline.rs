@@ -22,14 +22,16 @@ use crate::descriptors::Descriptors;
 
 use super::{ShaderPairAgal, VertexAttributeInfo, MAX_VERTEX_ATTRIBUTES};
 
-const AGAL_NUM_VERTEX_CONSTANTS: u64 = 128;
-const AGAL_NUM_FRAGMENT_CONSTANTS: u64 = 28;
 pub(super) const AGAL_FLOATS_PER_REGISTER: u64 = 4;
 
-const VERTEX_SHADER_UNIFORMS_BUFFER_SIZE: u64 =
-    AGAL_NUM_VERTEX_CONSTANTS * AGAL_FLOATS_PER_REGISTER * std::mem::size_of::<f32>() as u64;
-const FRAGMENT_SHADER_UNIFORMS_BUFFER_SIZE: u64 =
-    AGAL_NUM_FRAGMENT_CONSTANTS * AGAL_FLOATS_PER_REGISTER * std::mem::size_of::<f32>() as u64;
+// The uniform buffers are always large enough to hold the constants of
+// an AGAL2 program, even if the current program uses fewer registers.
+const VERTEX_SHADER_UNIFORMS_BUFFER_SIZE: u64 = naga_agal::MAX_VERTEX_PROGRAM_CONSTANTS
+    * AGAL_FLOATS_PER_REGISTER
+    * std::mem::size_of::<f32>() as u64;
+const FRAGMENT_SHADER_UNIFORMS_BUFFER_SIZE: u64 = naga_agal::MAX_FRAGMENT_PROGRAM_CONSTANTS
+    * AGAL_FLOATS_PER_REGISTER
+    * std::mem::size_of::<f32>() as u64;
 
 // The flash Context3D API is similar to OpenGL - it has many methods
 // which modify the current state (`setVertexBufferAt`, `setCulling`, etc.)
@@ -64,6 +66,11 @@ pub struct CurrentPipeline {
     depth_mask: bool,
     pass_compare_mode: wgpu::CompareFunction,
 
+    stencil_front: StencilFaceState,
+    stencil_back: StencilFaceState,
+    stencil_read_mask: u32,
+    stencil_write_mask: u32,
+
     color_component: wgpu::BlendComponent,
     alpha_component: wgpu::BlendComponent,
 
@@ -71,6 +78,10 @@ pub struct CurrentPipeline {
 
     target_format: TextureFormat,
 
+    // The formats of the additional render targets (`oc1` to `oc3`),
+    // set by `Context3D.setRenderToTexture` with a non-zero `colorOutputIndex`.
+    extra_target_formats: [Option<TextureFormat>; naga_agal::MAX_COLOR_OUTPUTS - 1],
+
     dirty: Cell<bool>,
 
     // Sampler configuration information for each texture slot.
@@ -122,11 +133,18 @@ impl CurrentPipeline {
 
             depth_mask: true,
             pass_compare_mode: wgpu::CompareFunction::LessEqual,
+
+            stencil_front: StencilFaceState::IGNORE,
+            stencil_back: StencilFaceState::IGNORE,
+            stencil_read_mask: 0xFF,
+            stencil_write_mask: 0xFF,
+
             color_component: wgpu::BlendComponent::REPLACE,
             alpha_component: wgpu::BlendComponent::REPLACE,
             sample_count: 1,
 
             target_format: TextureFormat::Rgba8Unorm,
+            extra_target_formats: [None; naga_agal::MAX_COLOR_OUTPUTS - 1],
 
             sampler_configs: [SamplerConfig::default(); 8],
         }
@@ -184,6 +202,45 @@ impl CurrentPipeline {
         self.pass_compare_mode = pass_compare_mode;
     }
 
+    pub fn update_stencil_actions(
+        &mut self,
+        face: Context3DTriangleFace,
+        face_state: StencilFaceState,
+    ) {
+        let (front, back) = match face {
+            Context3DTriangleFace::None => (false, false),
+            Context3DTriangleFace::Front => (true, false),
+            Context3DTriangleFace::Back => (false, true),
+            Context3DTriangleFace::FrontAndBack => (true, true),
+        };
+        if front && self.stencil_front != face_state {
+            self.dirty.set(true);
+            self.stencil_front = face_state;
+        }
+        if back && self.stencil_back != face_state {
+            self.dirty.set(true);
+            self.stencil_back = face_state;
+        }
+    }
+
+    pub fn update_stencil_masks(&mut self, read_mask: u32, write_mask: u32) {
+        if self.stencil_read_mask != read_mask || self.stencil_write_mask != write_mask {
+            self.dirty.set(true);
+        }
+        self.stencil_read_mask = read_mask;
+        self.stencil_write_mask = write_mask;
+    }
+
+    pub fn remove_shaders(&mut self, shaders: &Rc<ShaderPairAgal>) {
+        if self
+            .shaders
+            .as_ref()
+            .is_some_and(|current| Rc::ptr_eq(current, shaders))
+        {
+            self.set_shaders(None);
+        }
+    }
+
     pub fn update_has_depth_texture(&mut self, has_depth_texture: bool) {
         if self.has_depth_texture != has_depth_texture {
             self.dirty.set(true);
@@ -191,6 +248,10 @@ impl CurrentPipeline {
         }
     }
 
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
     pub fn update_sample_count(&mut self, sample_count: u32) {
         if self.sample_count != sample_count {
             self.dirty.set(true);
@@ -205,6 +266,14 @@ impl CurrentPipeline {
         }
     }
 
+    /// Sets the format of the additional render target for `colorOutputIndex` `index + 1`
+    pub fn update_extra_target_format(&mut self, index: usize, format: Option<TextureFormat>) {
+        if self.extra_target_formats[index] != format {
+            self.dirty.set(true);
+            self.extra_target_formats[index] = format;
+        }
+    }
+
     /// If the pipeline is dirty, recompiles it and returns `Some(freshly_compiled_pipeline`)
     /// Otherwise, returns `None`.
     pub fn rebuild_pipeline(
@@ -446,12 +515,11 @@ impl CurrentPipeline {
                 format: TextureFormat::Depth24PlusStencil8,
                 depth_write_enabled: self.depth_mask,
                 depth_compare: self.pass_compare_mode,
-                // FIXME - implement this
                 stencil: wgpu::StencilState {
-                    front: StencilFaceState::IGNORE,
-                    back: StencilFaceState::IGNORE,
-                    read_mask: !0,
-                    write_mask: !0,
+                    front: self.stencil_front,
+                    back: self.stencil_back,
+                    read_mask: self.stencil_read_mask,
+                    write_mask: self.stencil_write_mask,
                 },
                 bias: Default::default(),
             })
@@ -483,6 +551,30 @@ impl CurrentPipeline {
             })
             .collect::<Vec<_>>();
 
+        let color_target = |format| {
+            Some(ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState {
+                    color: self.color_component,
+                    alpha: self.alpha_component,
+                }),
+                write_mask: self.color_mask,
+            })
+        };
+        // This must line up with the color attachments created in `WgpuContext3D::make_render_pass`
+        let num_extra_targets = self
+            .extra_target_formats
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |i| i + 1);
+        let targets = std::iter::once(color_target(self.target_format))
+            .chain(
+                self.extra_target_formats[..num_extra_targets]
+                    .iter()
+                    .map(|format| format.and_then(color_target)),
+            )
+            .collect::<Vec<_>>();
+
         let compiled = descriptors
             .device
             .create_render_pipeline(&RenderPipelineDescriptor {
@@ -497,14 +589,7 @@ impl CurrentPipeline {
                 fragment: Some(wgpu::FragmentState {
                     module: &compiled_shaders.fragment_module,
                     entry_point: Some(naga_agal::SHADER_ENTRY_POINT),
-                    targets: &[Some(ColorTargetState {
-                        format: self.target_format,
-                        blend: Some(wgpu::BlendState {
-                            color: self.color_component,
-                            alpha: self.alpha_component,
-                        }),
-                        write_mask: self.color_mask,
-                    })],
+                    targets: &targets,
                     compilation_options: Default::default(),
                 }),
                 primitive: wgpu::PrimitiveState {
@@ -1,6 +1,7 @@
 use ruffle_render::backend::{
     Context3D, Context3DBlendFactor, Context3DCommand, Context3DCompareMode, Context3DProfile,
-    Context3DTextureFormat, Context3DVertexBufferFormat, IndexBuffer, ProgramType, VertexBuffer,
+    Context3DStencilAction, Context3DTextureFormat, Context3DVertexBufferFormat, IndexBuffer,
+    ProgramType, VertexBuffer,
 };
 use ruffle_render::bitmap::BitmapHandle;
 use ruffle_render::error::Error;
@@ -51,6 +52,9 @@ pub struct WgpuContext3D {
     current_texture_size: Option<Extent3d>,
     current_depth_texture_view: Option<wgpu::TextureView>,
     current_texture_resolve_view: Option<wgpu::TextureView>,
+    // The additional render targets (`oc1` to `oc3`) set by
+    // `Context3D.setRenderToTexture` with a non-zero `colorOutputIndex`
+    current_extra_color_views: [Option<wgpu::TextureView>; naga_agal::MAX_COLOR_OUTPUTS - 1],
 
     back_buffer_sample_count: u32,
     back_buffer_size: Option<Extent3d>,
@@ -87,6 +91,10 @@ pub struct WgpuContext3D {
     seen_clear_command: bool,
 
     scissor_rectangle: Option<Rectangle<Twips>>,
+    // Set by `Context3D.setStencilReferenceValue`. Unlike the rest of the
+    // stencil state, this is dynamic state of the render pass, so it isn't
+    // part of `CurrentPipeline`.
+    stencil_reference: u32,
 }
 
 impl WgpuContext3D {
@@ -146,6 +154,7 @@ impl WgpuContext3D {
             current_texture_size: None,
             current_depth_texture_view: None,
             current_texture_resolve_view: None,
+            current_extra_color_views: Default::default(),
 
             back_buffer_sample_count: 1,
             back_buffer_size: None,
@@ -161,6 +170,7 @@ impl WgpuContext3D {
             clear_color: None,
             seen_clear_command: false,
             scissor_rectangle: None,
+            stencil_reference: 0,
         }
     }
 
@@ -192,6 +202,7 @@ impl WgpuContext3D {
         self.current_texture_view = self.back_buffer_texture_view.clone();
         self.current_texture_resolve_view = self.back_buffer_resolve_texture_view.clone();
         self.current_depth_texture_view = self.back_buffer_depth_texture_view.clone();
+        self.clear_extra_color_outputs();
         self.current_pipeline
             .update_has_depth_texture(self.current_depth_texture_view.is_some());
         self.current_pipeline
@@ -200,6 +211,50 @@ impl WgpuContext3D {
             .update_target_format(TextureFormat::Rgba8Unorm);
     }
 
+    /// Binds `texture` as an additional render target, which is written to by
+    /// the `oc1` to `oc3` fragment shader outputs.
+    /// The depth buffer and sample count are always shared with the main render target.
+    fn set_extra_color_output(
+        &mut self,
+        texture: Rc<dyn ruffle_render::backend::Texture>,
+        surface_selector: u32,
+        color_output_index: u32,
+    ) {
+        let index = color_output_index as usize - 1;
+        if index >= self.current_extra_color_views.len() {
+            tracing::warn!(
+                "Context3D.setRenderToTexture: unsupported colorOutputIndex {color_output_index}"
+            );
+            return;
+        }
+
+        let texture_wrapper = <dyn Any>::downcast_ref::<TextureWrapper>(texture.as_ref()).unwrap();
+        if texture_wrapper.texture.sample_count() != self.current_pipeline.sample_count() {
+            tracing::warn!(
+                "Context3D.setRenderToTexture: colorOutputIndex {color_output_index} is not supported with antiAliasing"
+            );
+            return;
+        }
+
+        self.current_extra_color_views[index] =
+            Some(texture_wrapper.texture.create_view(&TextureViewDescriptor {
+                base_array_layer: surface_selector,
+                array_layer_count: Some(1),
+                dimension: Some(wgpu::TextureViewDimension::D2),
+                ..Default::default()
+            }));
+        self.current_pipeline.remove_texture(&texture);
+        self.current_pipeline
+            .update_extra_target_format(index, Some(texture_wrapper.texture.format()));
+    }
+
+    fn clear_extra_color_outputs(&mut self) {
+        for (i, view) in self.current_extra_color_views.iter_mut().enumerate() {
+            *view = None;
+            self.current_pipeline.update_extra_target_format(i, None);
+        }
+    }
+
     fn make_render_pass<'a>(
         &'a mut self,
         command_encoder: &'a mut CommandEncoder,
@@ -239,16 +294,36 @@ impl WgpuContext3D {
             None
         };
 
-        let mut pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Context3D render pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: self.current_texture_view.as_ref().unwrap(),
-                resolve_target: self.current_texture_resolve_view.as_ref(),
+        // This must line up with the color targets created in `CurrentPipeline::rebuild_pipeline`
+        let num_extra_outputs = self
+            .current_extra_color_views
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(0, |i| i + 1);
+        let color_attachments = std::iter::once((
+            self.current_texture_view.as_ref(),
+            self.current_texture_resolve_view.as_ref(),
+        ))
+        .chain(
+            self.current_extra_color_views[..num_extra_outputs]
+                .iter()
+                .map(|view| (view.as_ref(), None)),
+        )
+        .map(|(view, resolve_target)| {
+            Some(wgpu::RenderPassColorAttachment {
+                view: view?,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: color_load,
                     store: wgpu::StoreOp::Store,
                 },
-            })],
+            })
+        })
+        .collect::<Vec<_>>();
+
+        let mut pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Context3D render pass"),
+            color_attachments: &color_attachments,
             depth_stencil_attachment,
             ..Default::default()
         });
@@ -258,6 +333,7 @@ impl WgpuContext3D {
                 .as_ref()
                 .expect("Missing compiled pipeline"),
         );
+        pass.set_stencil_reference(self.stencil_reference);
         if let Some(rect) = &self.scissor_rectangle {
             let current_size = self.current_texture_size.unwrap();
             if rect.x_min.to_pixels() < 0.0
@@ -389,12 +465,36 @@ impl Context3D for WgpuContext3D {
         })
     }
 
-    fn disposed_index_buffer_handle(&self) -> Rc<dyn IndexBuffer> {
-        todo!()
+    fn driver_info(&self) -> String {
+        let info = self.descriptors.adapter.get_info();
+        // Content such as Starling checks for "Software" here to detect that
+        // hardware acceleration is unavailable, so report CPU adapters the same
+        // way that Flash Player reports its software renderer.
+        let driver = if info.device_type == wgpu::DeviceType::Cpu {
+            "Software"
+        } else {
+            match info.backend {
+                wgpu::Backend::Vulkan => "Vulkan",
+                wgpu::Backend::Metal => "Metal",
+                wgpu::Backend::Dx12 => "DirectX12",
+                wgpu::Backend::Gl => "OpenGL",
+                wgpu::Backend::BrowserWebGpu => "WebGPU",
+                wgpu::Backend::Noop => "Software",
+            }
+        };
+        format!("{driver} (Ruffle wgpu, {})", info.name)
     }
 
-    fn disposed_vertex_buffer_handle(&self) -> Rc<dyn VertexBuffer> {
-        todo!()
+    fn max_back_buffer_size(&self) -> u32 {
+        // These are the defaults used by Flash Player for each profile.
+        let profile_max = match self.profile {
+            Context3DProfile::Baseline | Context3DProfile::BaselineConstrained => 2048,
+            Context3DProfile::BaselineExtended
+            | Context3DProfile::Standard
+            | Context3DProfile::StandardConstrained
+            | Context3DProfile::StandardExtended => 4096,
+        };
+        profile_max.min(self.descriptors.limits.max_texture_dimension_2d)
     }
 
     fn create_texture(
@@ -582,6 +682,7 @@ impl Context3D for WgpuContext3D {
 
                 self.current_texture_view =
                     Some(back_buffer_texture.create_view(&Default::default()));
+                self.clear_extra_color_outputs();
 
                 if depth_and_stencil {
                     self.back_buffer_depth_texture_view =
@@ -730,7 +831,13 @@ impl Context3D for WgpuContext3D {
                 enable_depth_and_stencil,
                 anti_alias,
                 surface_selector,
+                color_output_index,
             } => {
+                if color_output_index != 0 {
+                    self.set_extra_color_output(texture, surface_selector, color_output_index);
+                    return;
+                }
+
                 let mut sample_count = anti_alias;
                 if sample_count == 0 {
                     sample_count = 1;
@@ -1086,17 +1193,8 @@ impl Context3D for WgpuContext3D {
                 depth_mask,
                 pass_compare_mode,
             } => {
-                let function = match pass_compare_mode {
-                    Context3DCompareMode::Always => wgpu::CompareFunction::Always,
-                    Context3DCompareMode::Equal => wgpu::CompareFunction::Equal,
-                    Context3DCompareMode::Greater => wgpu::CompareFunction::Greater,
-                    Context3DCompareMode::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
-                    Context3DCompareMode::Less => wgpu::CompareFunction::Less,
-                    Context3DCompareMode::LessEqual => wgpu::CompareFunction::LessEqual,
-                    Context3DCompareMode::Never => wgpu::CompareFunction::Never,
-                    Context3DCompareMode::NotEqual => wgpu::CompareFunction::NotEqual,
-                };
-                self.current_pipeline.update_depth(depth_mask, function);
+                self.current_pipeline
+                    .update_depth(depth_mask, convert_compare_mode(pass_compare_mode));
             }
             Context3DCommand::SetBlendFactors {
                 source_factor,
@@ -1172,6 +1270,57 @@ impl Context3D for WgpuContext3D {
             Context3DCommand::SetScissorRectangle { rect } => {
                 self.scissor_rectangle = rect;
             }
+            Context3DCommand::SetStencilActions {
+                face,
+                compare_mode,
+                action_on_both_pass,
+                action_on_depth_fail,
+                action_on_depth_pass_stencil_fail,
+            } => {
+                self.current_pipeline.update_stencil_actions(
+                    face,
+                    wgpu::StencilFaceState {
+                        compare: convert_compare_mode(compare_mode),
+                        fail_op: convert_stencil_action(action_on_depth_pass_stencil_fail),
+                        depth_fail_op: convert_stencil_action(action_on_depth_fail),
+                        pass_op: convert_stencil_action(action_on_both_pass),
+                    },
+                );
+            }
+            Context3DCommand::SetStencilReferenceValue {
+                reference_value,
+                read_mask,
+                write_mask,
+            } => {
+                self.stencil_reference = reference_value;
+                self.current_pipeline
+                    .update_stencil_masks(read_mask, write_mask);
+            }
+            Context3DCommand::DisposeIndexBuffer { buffer } => {
+                // Nothing else refers to an index buffer after a draw call,
+                // so dropping it is enough. wgpu keeps the buffer alive until
+                // any submitted work using it has finished.
+                drop(buffer);
+            }
+            Context3DCommand::DisposeVertexBuffer { buffer } => {
+                let buffer = Rc::<dyn Any>::downcast::<VertexBufferWrapper>(buffer).unwrap();
+                for (index, attr) in self.vertex_attributes.iter_mut().enumerate() {
+                    if attr
+                        .as_ref()
+                        .is_some_and(|attr| Rc::ptr_eq(&attr.buffer, &buffer))
+                    {
+                        *attr = None;
+                        self.current_pipeline.update_vertex_buffer_at(index);
+                    }
+                }
+            }
+            Context3DCommand::DisposeTexture { texture } => {
+                self.current_pipeline.remove_texture(&texture);
+            }
+            Context3DCommand::DisposeShaders { module } => {
+                let shaders = Rc::<dyn Any>::downcast::<ShaderPairAgal>(module).unwrap();
+                self.current_pipeline.remove_shaders(&shaders);
+            }
         }
     }
 
@@ -1208,6 +1357,32 @@ pub struct ClearColor {
     mask: u32,
 }
 
+fn convert_compare_mode(mode: Context3DCompareMode) -> wgpu::CompareFunction {
+    match mode {
+        Context3DCompareMode::Always => wgpu::CompareFunction::Always,
+        Context3DCompareMode::Equal => wgpu::CompareFunction::Equal,
+        Context3DCompareMode::Greater => wgpu::CompareFunction::Greater,
+        Context3DCompareMode::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+        Context3DCompareMode::Less => wgpu::CompareFunction::Less,
+        Context3DCompareMode::LessEqual => wgpu::CompareFunction::LessEqual,
+        Context3DCompareMode::Never => wgpu::CompareFunction::Never,
+        Context3DCompareMode::NotEqual => wgpu::CompareFunction::NotEqual,
+    }
+}
+
+fn convert_stencil_action(action: Context3DStencilAction) -> wgpu::StencilOperation {
+    match action {
+        Context3DStencilAction::DecrementSaturate => wgpu::StencilOperation::DecrementClamp,
+        Context3DStencilAction::DecrementWrap => wgpu::StencilOperation::DecrementWrap,
+        Context3DStencilAction::IncrementSaturate => wgpu::StencilOperation::IncrementClamp,
+        Context3DStencilAction::IncrementWrap => wgpu::StencilOperation::IncrementWrap,
+        Context3DStencilAction::Invert => wgpu::StencilOperation::Invert,
+        Context3DStencilAction::Keep => wgpu::StencilOperation::Keep,
+        Context3DStencilAction::Set => wgpu::StencilOperation::Replace,
+        Context3DStencilAction::Zero => wgpu::StencilOperation::Zero,
+    }
+}
+
 fn convert_texture_format(input: Context3DTextureFormat) -> wgpu::TextureFormat {
     match input {
         // Some of these formats are unsupported by wgpu to various degrees:
@@ -1,22 +1,26 @@
 mod bevel;
 mod blur;
 mod color_matrix;
+mod convolution;
 mod displacement_map;
 mod drop_shadow;
 mod glow;
+mod gradient_bevel;
+mod gradient_glow;
+mod gradient_lookup;
 mod shader;
 
-use std::collections::HashSet;
-use std::sync::{LazyLock, Mutex};
-
 use crate::buffer_pool::TexturePool;
 use crate::descriptors::Descriptors;
 use crate::filters::bevel::BevelFilter;
 use crate::filters::blur::BlurFilter;
 use crate::filters::color_matrix::ColorMatrixFilter;
+use crate::filters::convolution::ConvolutionFilter;
 use crate::filters::displacement_map::DisplacementMapFilter;
 use crate::filters::drop_shadow::DropShadowFilter;
 use crate::filters::glow::GlowFilter;
+use crate::filters::gradient_bevel::GradientBevelFilter;
+use crate::filters::gradient_glow::GradientGlowFilter;
 use crate::filters::shader::ShaderFilter;
 use crate::surface::target::CommandTarget;
 use bytemuck::{Pod, Zeroable};
@@ -192,6 +196,9 @@ pub struct Filters {
     pub glow: GlowFilter,
     pub bevel: BevelFilter,
     pub displacement_map: DisplacementMapFilter,
+    pub convolution: ConvolutionFilter,
+    pub gradient_glow: GradientGlowFilter,
+    pub gradient_bevel: GradientBevelFilter,
 }
 
 impl Filters {
@@ -203,6 +210,9 @@ impl Filters {
             glow: GlowFilter::new(device),
             bevel: BevelFilter::new(device),
             displacement_map: DisplacementMapFilter::new(device),
+            convolution: ConvolutionFilter::new(device),
+            gradient_glow: GradientGlowFilter::new(device),
+            gradient_bevel: GradientBevelFilter::new(device),
         }
     }
 
@@ -276,28 +286,32 @@ impl Filters {
                 &source,
                 &filter,
             ),
-            filter => {
-                static WARNED_FILTERS: LazyLock<Mutex<HashSet<&'static str>>> =
-                    LazyLock::new(Default::default);
-
-                let name = match filter {
-                    Filter::GradientGlowFilter(_) => "GradientGlowFilter",
-                    Filter::GradientBevelFilter(_) => "GradientBevelFilter",
-                    Filter::ConvolutionFilter(_) => "ConvolutionFilter",
-                    Filter::ColorMatrixFilter(_)
-                    | Filter::BlurFilter(_)
-                    | Filter::GlowFilter(_)
-                    | Filter::DropShadowFilter(_)
-                    | Filter::BevelFilter(_)
-                    | Filter::DisplacementMapFilter(_)
-                    | Filter::ShaderFilter(_) => unreachable!(),
-                };
-                // Only warn once per filter type
-                if WARNED_FILTERS.lock().unwrap().insert(name) {
-                    tracing::warn!("Unsupported filter {filter:?}");
-                }
-                None
-            }
+            Filter::ConvolutionFilter(filter) => descriptors.filters.convolution.apply(
+                descriptors,
+                texture_pool,
+                draw_encoder,
+                staging_belt,
+                &source,
+                &filter,
+            ),
+            Filter::GradientGlowFilter(filter) => Some(descriptors.filters.gradient_glow.apply(
+                descriptors,
+                texture_pool,
+                draw_encoder,
+                staging_belt,
+                &source,
+                &filter,
+                &self.blur,
+            )),
+            Filter::GradientBevelFilter(filter) => Some(descriptors.filters.gradient_bevel.apply(
+                descriptors,
+                texture_pool,
+                draw_encoder,
+                staging_belt,
+                &source,
+                &filter,
+                &self.blur,
+            )),
         };
 
         let target = target.unwrap_or_else(|| {
@@ -0,0 +1,288 @@
+use crate::backend::RenderTargetMode;
+use crate::buffer_pool::TexturePool;
+use crate::descriptors::Descriptors;
+use crate::filters::{FilterSource, FilterVertex, VERTEX_BUFFERS_DESCRIPTION_FILTERS};
+use crate::surface::target::CommandTarget;
+use crate::utils::SampleCountMap;
+use bytemuck::{Pod, Zeroable};
+use std::sync::OnceLock;
+use swf::ConvolutionFilter as ConvolutionFilterArgs;
+use wgpu::util::{DeviceExt, StagingBelt};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable, PartialEq)]
+struct ConvolutionUniform {
+    default_color: [f32; 4],
+    matrix_cols: u32,
+    matrix_rows: u32,
+    divisor: f32,
+    bias: f32,
+    source_x: u32,
+    source_y: u32,
+    source_width: u32,
+    source_height: u32,
+    clamp: u32,          // a wasteful bool, but we need to be aligned anyway
+    preserve_alpha: u32, // a wasteful bool, but we need to be aligned anyway
+    _padding: [u32; 2],
+}
+
+pub struct ConvolutionFilter {
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    vertex_buffer: wgpu::Buffer,
+    uniform_buffer: wgpu::Buffer,
+    vertices_size: wgpu::BufferSize,
+    uniform_size: wgpu::BufferSize,
+    pipelines: SampleCountMap<OnceLock<wgpu::RenderPipeline>>,
+}
+
+impl ConvolutionFilter {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let uniform_size = std::mem::size_of::<ConvolutionUniform>() as u64;
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(uniform_size),
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+            label: create_debug_label!("Convolution filter binds").as_deref(),
+        });
+
+        let vertices_size = std::mem::size_of::<[FilterVertex; 4]>() as u64;
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: vertices_size,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: uniform_size,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        Self {
+            pipelines: Default::default(),
+            pipeline_layout,
+            vertex_buffer,
+            uniform_buffer,
+            bind_group_layout,
+            uniform_size: wgpu::BufferSize::new(uniform_size).expect("Definitely not zero."),
+            vertices_size: wgpu::BufferSize::new(vertices_size).expect("Definitely not zero."),
+        }
+    }
+
+    fn pipeline(&self, descriptors: &Descriptors, msaa_sample_count: u32) -> &wgpu::RenderPipeline {
+        self.pipelines.get_or_init(msaa_sample_count, || {
+            let label = create_debug_label!("Convolution Filter ({} msaa)", msaa_sample_count);
+            descriptors
+                .device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: label.as_deref(),
+                    layout: Some(&self.pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &descriptors.shaders.convolution_filter,
+                        entry_point: Some("main_vertex"),
+                        buffers: &VERTEX_BUFFERS_DESCRIPTION_FILTERS,
+                        compilation_options: Default::default(),
+                    },
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        cull_mode: None,
+                        polygon_mode: wgpu::PolygonMode::default(),
+                        unclipped_depth: false,
+                        conservative: false,
+                    },
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState {
+                        count: msaa_sample_count,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &descriptors.shaders.convolution_filter,
+                        entry_point: Some("main_fragment"),
+                        targets: &[Some(wgpu::TextureFormat::Rgba8Unorm.into())],
+                        compilation_options: Default::default(),
+                    }),
+                    multiview: None,
+                    cache: None,
+                })
+        })
+    }
+
+    /// Applies the filter, or returns `None` if its matrix is empty.
+    pub fn apply(
+        &self,
+        descriptors: &Descriptors,
+        texture_pool: &mut TexturePool,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        staging_belt: &mut StagingBelt,
+        source: &FilterSource,
+        filter: &ConvolutionFilterArgs,
+    ) -> Option<CommandTarget> {
+        let matrix_cols = u32::from(filter.num_matrix_cols);
+        let matrix_rows = u32::from(filter.num_matrix_rows);
+        if matrix_cols == 0
+            || matrix_rows == 0
+            || filter.matrix.len() < (matrix_cols * matrix_rows) as usize
+        {
+            return None;
+        }
+
+        let sample_count = source.texture.sample_count();
+        let format = source.texture.format();
+        let pipeline = self.pipeline(descriptors, sample_count);
+
+        let target = CommandTarget::new(
+            descriptors,
+            texture_pool,
+            wgpu::Extent3d {
+                width: source.size.0,
+                height: source.size.1,
+                depth_or_array_layers: 1,
+            },
+            format,
+            sample_count,
+            RenderTargetMode::FreshWithColor(wgpu::Color::TRANSPARENT),
+            draw_encoder,
+        );
+        let source_view = source.texture.create_view(&Default::default());
+
+        // The matrix can be as large as 255x255, which is too much for a uniform buffer,
+        // so it's stored as a single channel texture instead.
+        let matrix_texture = descriptors.device.create_texture_with_data(
+            &descriptors.queue,
+            &wgpu::TextureDescriptor {
+                label: create_debug_label!("Convolution filter matrix").as_deref(),
+                size: wgpu::Extent3d {
+                    width: matrix_cols,
+                    height: matrix_rows,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R32Float,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            bytemuck::cast_slice(&filter.matrix[..(matrix_cols * matrix_rows) as usize]),
+        );
+        let matrix_view = matrix_texture.create_view(&Default::default());
+
+        let default_alpha = f32::from(filter.default_color.a) / 255.0;
+        staging_belt
+            .write_buffer(
+                draw_encoder,
+                &self.uniform_buffer,
+                0,
+                self.uniform_size,
+                &descriptors.device,
+            )
+            .copy_from_slice(bytemuck::cast_slice(&[ConvolutionUniform {
+                default_color: [
+                    f32::from(filter.default_color.r) / 255.0 * default_alpha,
+                    f32::from(filter.default_color.g) / 255.0 * default_alpha,
+                    f32::from(filter.default_color.b) / 255.0 * default_alpha,
+                    default_alpha,
+                ],
+                matrix_cols,
+                matrix_rows,
+                divisor: filter.divisor,
+                bias: filter.bias,
+                source_x: source.point.0,
+                source_y: source.point.1,
+                source_width: source.size.0,
+                source_height: source.size.1,
+                clamp: if filter.is_clamped() { 1 } else { 0 },
+                preserve_alpha: if filter.is_preserve_alpha() { 1 } else { 0 },
+                _padding: [0; 2],
+            }]));
+        staging_belt
+            .write_buffer(
+                draw_encoder,
+                &self.vertex_buffer,
+                0,
+                self.vertices_size,
+                &descriptors.device,
+            )
+            .copy_from_slice(bytemuck::cast_slice(&[source.vertices()]));
+        let filter_group = descriptors
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: create_debug_label!("Filter group").as_deref(),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&source_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: self.uniform_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(&matrix_view),
+                    },
+                ],
+            });
+        let mut render_pass = draw_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: create_debug_label!("Convolution filter").as_deref(),
+            color_attachments: &[target.color_attachments()],
+            ..Default::default()
+        });
+        render_pass.set_pipeline(pipeline);
+
+        render_pass.set_bind_group(0, &filter_group, &[]);
+
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(
+            descriptors.quad.indices.slice(..),
+            wgpu::IndexFormat::Uint32,
+        );
+        render_pass.draw_indexed(0..6, 0, 0..1);
+        drop(render_pass);
+        Some(target)
+    }
+}
@@ -0,0 +1,118 @@
+use swf::GradientRecord;
+use wgpu::util::StagingBelt;
+
+const GRADIENT_SIZE: usize = 256;
+
+/// A 256x1 texture of premultiplied colors, which gradient filters map their strength onto.
+///
+/// The colors are uploaded through the staging belt, so that filters applied one after
+/// another within the same encoder each see their own gradient.
+pub struct GradientLookup {
+    buffer: wgpu::Buffer,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl GradientLookup {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: create_debug_label!("Gradient filter lookup buffer").as_deref(),
+            size: (GRADIENT_SIZE * 4) as u64,
+            usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: create_debug_label!("Gradient filter lookup texture").as_deref(),
+            size: wgpu::Extent3d {
+                width: GRADIENT_SIZE as u32,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+        Self {
+            buffer,
+            texture,
+            view,
+        }
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn upload(
+        &self,
+        device: &wgpu::Device,
+        draw_encoder: &mut wgpu::CommandEncoder,
+        staging_belt: &mut StagingBelt,
+        records: &[GradientRecord],
+    ) {
+        staging_belt
+            .write_buffer(
+                draw_encoder,
+                &self.buffer,
+                0,
+                wgpu::BufferSize::new((GRADIENT_SIZE * 4) as u64).expect("Definitely not zero."),
+                device,
+            )
+            .copy_from_slice(&gradient_colors(records));
+        draw_encoder.copy_buffer_to_texture(
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some((GRADIENT_SIZE * 4) as u32),
+                    rows_per_image: None,
+                },
+            },
+            self.texture.as_image_copy(),
+            wgpu::Extent3d {
+                width: GRADIENT_SIZE as u32,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+/// Interpolates the gradient records into premultiplied RGBA colors.
+/// Positions before the first record or after the last one take the color of that record.
+fn gradient_colors(records: &[GradientRecord]) -> [u8; GRADIENT_SIZE * 4] {
+    let mut colors = [0; GRADIENT_SIZE * 4];
+    if records.is_empty() {
+        return colors;
+    }
+
+    for (t, color) in colors.chunks_exact_mut(4).enumerate() {
+        let next = records
+            .iter()
+            .position(|record| record.ratio as usize >= t)
+            .unwrap_or(records.len() - 1);
+        let last = next.saturating_sub(1);
+        let last_record = &records[last];
+        let next_record = &records[next];
+
+        let a = if next_record.ratio > last_record.ratio {
+            ((t as f32 - last_record.ratio as f32)
+                / (next_record.ratio as f32 - last_record.ratio as f32))
+                .clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let lerp = |start: u8, end: u8| start as f32 + (end as f32 - start as f32) * a;
+        let alpha = lerp(last_record.color.a, next_record.color.a) / 255.0;
+        color[0] = (lerp(last_record.color.r, next_record.color.r) * alpha).round() as u8;
+        color[1] = (lerp(last_record.color.g, next_record.color.g) * alpha).round() as u8;
+        color[2] = (lerp(last_record.color.b, next_record.color.b) * alpha).round() as u8;
+        color[3] = (alpha * 255.0).round() as u8;
+    }
+
+    colors
+}
@@ -9,6 +9,7 @@ pub struct BindLayouts {
     pub gradient: wgpu::BindGroupLayout,
     pub blend: wgpu::BindGroupLayout,
     pub alpha_mask: wgpu::BindGroupLayout,
+    pub shader_fill: wgpu::BindGroupLayout,
 }
 
 impl BindLayouts {
@@ -195,6 +196,23 @@ impl BindLayouts {
             label: alpha_mask_bind_layout_label.as_deref(),
         });
 
+        let shader_fill_bind_layout_label = create_debug_label!("Shader fill bind group layout");
+        let shader_fill = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: wgpu::BufferSize::new(
+                        std::mem::size_of::<TextureTransforms>() as u64,
+                    ),
+                },
+                count: None,
+            }],
+            label: shader_fill_bind_layout_label.as_deref(),
+        });
+
         Self {
             globals,
             transforms,
@@ -202,6 +220,7 @@ impl BindLayouts {
             gradient,
             blend,
             alpha_mask,
+            shader_fill,
         }
     }
 }
@@ -8,9 +8,13 @@ use std::ops::Range;
 use wgpu::util::DeviceExt;
 
 use crate::buffer_builder::BufferBuilder;
+use crate::pixel_bender::as_cache_holder;
 use ruffle_render::backend::{RenderBackend, ShapeHandle, ShapeHandleImpl};
 use ruffle_render::bitmap::BitmapSource;
-use ruffle_render::tessellator::{Bitmap, Draw as LyonDraw, DrawType as TessDrawType, Gradient};
+use ruffle_render::pixel_bender::PixelBenderShaderHandle;
+use ruffle_render::tessellator::{
+    Bitmap, Draw as LyonDraw, DrawType as TessDrawType, Gradient, Shader,
+};
 use swf::{CharacterId, GradientInterpolation};
 
 /// How big to make gradient textures. Larger will keep more detail, but be slower and use more memory.
@@ -78,6 +82,16 @@ impl PendingDraw {
         vertex_buffer: &mut BufferBuilder,
         index_buffer: &mut BufferBuilder,
     ) -> Option<Self> {
+        let mut draw = draw;
+        if let TessDrawType::Shader(shader) = &draw.draw_type {
+            if let Err(e) = as_cache_holder(&shader.fill.shader).compile_fill(backend.descriptors())
+            {
+                // The vertices of a shader fill are white, so this draws a solid white fill.
+                tracing::error!("Failed to compile shader fill, drawing a solid fill instead: {e}");
+                draw.draw_type = TessDrawType::Color;
+            }
+        }
+
         let vertices = if matches!(draw.draw_type, TessDrawType::Color) {
             let vertices: Vec<_> = draw
                 .vertices
@@ -107,6 +121,9 @@ impl PendingDraw {
             TessDrawType::Bitmap(bitmap) => {
                 PendingDrawType::bitmap(bitmap, shape_id, draw_id, source, backend, uniform_buffer)?
             }
+            TessDrawType::Shader(shader) => {
+                PendingDrawType::shader(shader, shape_id, draw_id, uniform_buffer)
+            }
         };
         Some(PendingDraw {
             draw_type,
@@ -133,6 +150,11 @@ pub enum PendingDrawType {
         is_smoothed: bool,
         bind_group_label: Option<String>,
     },
+    Shader {
+        texture_transforms_index: wgpu::BufferAddress,
+        shader: Shader,
+        bind_group_label: Option<String>,
+    },
 }
 
 /// Converts an RGBA color from sRGB space to linear color space.
@@ -195,6 +217,23 @@ impl PendingDrawType {
         })
     }
 
+    pub fn shader(
+        shader: Shader,
+        shape_id: CharacterId,
+        draw_id: usize,
+        uniform_buffers: &mut BufferBuilder,
+    ) -> Self {
+        let texture_transforms_index = create_texture_transforms(&shader.matrix, uniform_buffers);
+        let bind_group_label =
+            create_debug_label!("Shape {} (shader) draw {} bindgroup", shape_id, draw_id);
+
+        PendingDrawType::Shader {
+            texture_transforms_index,
+            shader,
+            bind_group_label,
+        }
+    }
+
     pub fn finish(
         self,
         descriptors: &Descriptors,
@@ -270,6 +309,39 @@ impl PendingDrawType {
 
                 DrawType::Bitmap { binds }
             }
+            PendingDrawType::Shader {
+                texture_transforms_index,
+                shader,
+                bind_group_label,
+            } => {
+                let bind_group = descriptors
+                    .device
+                    .create_bind_group(&wgpu::BindGroupDescriptor {
+                        layout: &descriptors.bind_layouts.shader_fill,
+                        entries: &[wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                                buffer: uniform_buffer,
+                                offset: texture_transforms_index,
+                                size: wgpu::BufferSize::new(
+                                    std::mem::size_of::<TextureTransforms>() as u64,
+                                ),
+                            }),
+                        }],
+                        label: bind_group_label.as_deref(),
+                    });
+                let shader_bind_group = as_cache_holder(&shader.fill.shader)
+                    .create_fill_bind_group(
+                        descriptors,
+                        &shader.fill.arguments,
+                        bind_group_label.as_deref(),
+                    );
+                DrawType::Shader {
+                    shader: shader.fill.shader,
+                    bind_group,
+                    shader_bind_group,
+                }
+            }
         }
     }
 }
@@ -277,8 +349,17 @@ impl PendingDrawType {
 #[derive(Debug)]
 pub enum DrawType {
     Color,
-    Gradient { bind_group: wgpu::BindGroup },
-    Bitmap { binds: BitmapBinds },
+    Gradient {
+        bind_group: wgpu::BindGroup,
+    },
+    Bitmap {
+        binds: BitmapBinds,
+    },
+    Shader {
+        shader: PixelBenderShaderHandle,
+        bind_group: wgpu::BindGroup,
+        shader_bind_group: wgpu::BindGroup,
+    },
 }
 
 #[derive(Debug)]
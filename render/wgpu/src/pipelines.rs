@@ -24,6 +24,31 @@ pub const VERTEX_BUFFERS_DESCRIPTION_COLOR: [wgpu::VertexBufferLayout; 1] =
         ],
     }];
 
+/// The vertex or fragment stage of a pipeline.
+#[derive(Clone, Copy)]
+pub struct PipelineStage<'a> {
+    pub module: &'a wgpu::ShaderModule,
+    pub entry_point: &'a str,
+}
+
+impl<'a> PipelineStage<'a> {
+    /// The vertex stage of one of our own WGSL shaders.
+    pub fn vertex(module: &'a wgpu::ShaderModule) -> Self {
+        Self {
+            module,
+            entry_point: "main_vertex",
+        }
+    }
+
+    /// The fragment stage of one of our own WGSL shaders.
+    pub fn fragment(module: &'a wgpu::ShaderModule) -> Self {
+        Self {
+            module,
+            entry_point: "main_fragment",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ShapePipeline {
     pub pipelines: EnumMap<MaskState, wgpu::RenderPipeline>,
@@ -47,6 +72,9 @@ pub struct Pipelines {
     pub gradients: ShapePipeline,
     pub complex_blends: EnumMap<ComplexBlend, ShapePipeline>,
     pub alpha_mask: ShapePipeline,
+    /// The format of the render target these pipelines draw to.
+    pub format: wgpu::TextureFormat,
+    pub msaa_sample_count: u32,
 }
 
 impl ShapePipeline {
@@ -188,8 +216,8 @@ impl Pipelines {
 
         let bitmap_opaque = device.create_render_pipeline(&create_pipeline_descriptor(
             create_debug_label!("Bitmap opaque copy").as_deref(),
-            &shaders.bitmap_shader,
-            &shaders.bitmap_shader,
+            PipelineStage::vertex(&shaders.bitmap_shader),
+            PipelineStage::fragment(&shaders.bitmap_shader),
             &bitmap_opaque_pipeline_layout,
             None,
             &[Some(wgpu::ColorTargetState {
@@ -205,8 +233,8 @@ impl Pipelines {
 
         let bitmap_opaque_dummy_depth = device.create_render_pipeline(&create_pipeline_descriptor(
             create_debug_label!("Bitmap opaque copy").as_deref(),
-            &shaders.bitmap_shader,
-            &shaders.bitmap_shader,
+            PipelineStage::vertex(&shaders.bitmap_shader),
+            PipelineStage::fragment(&shaders.bitmap_shader),
             &bitmap_opaque_pipeline_layout,
             Some(wgpu::DepthStencilState {
                 format: wgpu::TextureFormat::Stencil8,
@@ -259,6 +287,8 @@ impl Pipelines {
             gradients: gradient_pipeline,
             complex_blends: complex_blend_pipelines,
             alpha_mask: alpha_mask_pipeline,
+            format,
+            msaa_sample_count,
         }
     }
 }
@@ -266,8 +296,8 @@ impl Pipelines {
 #[expect(clippy::too_many_arguments)]
 fn create_pipeline_descriptor<'a>(
     label: Option<&'a str>,
-    vertex_shader: &'a wgpu::ShaderModule,
-    fragment_shader: &'a wgpu::ShaderModule,
+    vertex: PipelineStage<'a>,
+    fragment: PipelineStage<'a>,
     pipeline_layout: &'a wgpu::PipelineLayout,
     depth_stencil_state: Option<wgpu::DepthStencilState>,
     color_target_state: &'a [Option<wgpu::ColorTargetState>],
@@ -280,14 +310,14 @@ fn create_pipeline_descriptor<'a>(
         label,
         layout: Some(pipeline_layout),
         vertex: wgpu::VertexState {
-            module: vertex_shader,
-            entry_point: Some("main_vertex"),
+            module: vertex.module,
+            entry_point: Some(vertex.entry_point),
             buffers: vertex_buffer_layout,
             compilation_options: Default::default(),
         },
         fragment: Some(wgpu::FragmentState {
-            module: fragment_shader,
-            entry_point: Some("main_fragment"),
+            module: fragment.module,
+            entry_point: Some(fragment.entry_point),
             targets: color_target_state,
             compilation_options: wgpu::PipelineCompilationOptions {
                 constants: fragment_constants,
@@ -326,6 +356,37 @@ fn create_shape_pipeline(
     blend: BlendState,
     push_constant_ranges: &[wgpu::PushConstantRange],
     primitive_topology: PrimitiveTopology,
+) -> ShapePipeline {
+    create_shape_pipeline_for_stages(
+        name,
+        device,
+        format,
+        PipelineStage::vertex(shader),
+        PipelineStage::fragment(shader),
+        msaa_sample_count,
+        vertex_buffers_layout,
+        bind_group_layouts,
+        blend,
+        push_constant_ranges,
+        primitive_topology,
+    )
+}
+
+/// Like `create_shape_pipeline`, but allows the vertex and fragment stages to
+/// come from different shader modules.
+#[expect(clippy::too_many_arguments)]
+pub fn create_shape_pipeline_for_stages(
+    name: &str,
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    vertex: PipelineStage<'_>,
+    fragment: PipelineStage<'_>,
+    msaa_sample_count: u32,
+    vertex_buffers_layout: &[wgpu::VertexBufferLayout<'_>],
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    blend: BlendState,
+    push_constant_ranges: &[wgpu::PushConstantRange],
+    primitive_topology: PrimitiveTopology,
 ) -> ShapePipeline {
     let pipeline_layout_label = create_debug_label!("{} shape pipeline layout", name);
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -337,8 +398,8 @@ fn create_shape_pipeline(
     let mask_render_state = |mask_name, stencil_state, write_mask| {
         device.create_render_pipeline(&create_pipeline_descriptor(
             create_debug_label!("{} pipeline {}", name, mask_name).as_deref(),
-            shader,
-            shader,
+            vertex,
+            fragment,
             &pipeline_layout,
             Some(wgpu::DepthStencilState {
                 format: wgpu::TextureFormat::Stencil8,
@@ -367,8 +428,8 @@ fn create_shape_pipeline(
     ShapePipeline::build(
         device.create_render_pipeline(&create_pipeline_descriptor(
             create_debug_label!("{} stencilless pipeline", name).as_deref(),
-            shader,
-            shader,
+            vertex,
+            fragment,
             &pipeline_layout,
             None,
             &[Some(wgpu::ColorTargetState {
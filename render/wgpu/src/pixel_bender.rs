@@ -1,10 +1,11 @@
 use std::any::Any;
-use std::cell::RefCell;
+use std::cell::{OnceCell, RefCell};
 use std::collections::HashMap;
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::{borrow::Cow, cell::Cell, sync::Arc};
 
 use indexmap::IndexMap;
+use lru::LruCache;
 use ruffle_render::bitmap::BitmapHandle;
 use ruffle_render::error::Error as BitmapError;
 use ruffle_render::pixel_bender::{
@@ -22,6 +23,9 @@ use wgpu::{
 };
 
 use crate::filters::{FilterSource, VERTEX_BUFFERS_DESCRIPTION_FILTERS};
+use crate::pipelines::{
+    create_shape_pipeline_for_stages, PipelineStage, ShapePipeline, VERTEX_BUFFERS_DESCRIPTION_POS,
+};
 use crate::raw_texture_as_texture;
 use crate::{
     as_texture, backend::WgpuRenderBackend, descriptors::Descriptors, target::RenderTarget, Texture,
@@ -41,6 +45,32 @@ pub struct PixelBenderWgpuShader {
     int_parameters_buffer_size: u64,
     zeroed_out_of_range_mode: wgpu::Buffer,
     staging_belt: RefCell<StagingBelt>,
+    /// The pipelines used to draw this shader as a shape fill, or the reason why
+    /// the shader couldn't be compiled for that.
+    /// These are only created once the shader is used by a shape.
+    fill: OnceCell<Result<ShaderFillPipelines, String>>,
+}
+
+/// The number of different sets of parameters for which a shape fill keeps its
+/// uniform buffers around, so that shapes using the same arguments can share them.
+const FILL_PARAMETERS_CACHE_SIZE: usize = 16;
+
+#[derive(Debug)]
+struct ShaderFillPipelines {
+    fragment_shader: wgpu::ShaderModule,
+    pipelines: RefCell<HashMap<(u32, wgpu::TextureFormat), Arc<ShapePipeline>>>,
+    /// The float and int parameter buffers, keyed by their contents.
+    parameters: RefCell<LruCache<FillParametersKey, Arc<ShaderFillParameters>>>,
+    zeroed_out_of_range_mode: wgpu::Buffer,
+}
+
+/// The bits of the float parameters, and the int parameters of a shape fill.
+type FillParametersKey = (Vec<u32>, Vec<i32>);
+
+#[derive(Debug)]
+struct ShaderFillParameters {
+    float_parameters_buffer: wgpu::Buffer,
+    int_parameters_buffer: wgpu::Buffer,
 }
 
 impl PixelBenderWgpuShader {
@@ -95,6 +125,218 @@ impl PixelBenderWgpuShader {
     }
 }
 
+impl PixelBenderWgpuShader {
+    /// Compiles this shader for use as a shape fill, the first time that a shape uses it.
+    ///
+    /// Shapes must fall back to another fill if this fails, as the bytecode
+    /// comes from the movie and may be something that we can't compile.
+    pub fn compile_fill(&self, descriptors: &Descriptors) -> Result<(), &str> {
+        self.fill(descriptors).map(|_| ())
+    }
+
+    fn fill(&self, descriptors: &Descriptors) -> Result<&ShaderFillPipelines, &str> {
+        let fill = self.fill.get_or_init(|| {
+            let fragment = naga_pixelbender::ShaderBuilder::build_fill(&self.shader)
+                .map_err(|e| e.to_string())?;
+            Ok(ShaderFillPipelines {
+                fragment_shader: descriptors
+                    .device
+                    .create_shader_module(ShaderModuleDescriptor {
+                        label: create_debug_label!(
+                            "PixelBender shader fill {:?}",
+                            self.shader.name
+                        )
+                        .as_deref(),
+                        source: wgpu::ShaderSource::Naga(Cow::Owned(fragment)),
+                    }),
+                pipelines: Default::default(),
+                parameters: RefCell::new(LruCache::new(
+                    NonZeroUsize::new(FILL_PARAMETERS_CACHE_SIZE).unwrap(),
+                )),
+                // FIXME - we don't know how Flash Player treats out-of-range texture sample
+                // coordinates in shader fills. Clamp them, like `ShaderJob` does.
+                zeroed_out_of_range_mode: descriptors.device.create_buffer_init(
+                    &wgpu::util::BufferInitDescriptor {
+                        label: create_debug_label!(
+                            "PixelBender shader fill zeroed_out_of_range_mode parameter"
+                        )
+                        .as_deref(),
+                        contents: bytemuck::cast_slice(&[0.0f32; 4]),
+                        usage: BufferUsages::UNIFORM,
+                    },
+                ),
+            })
+        });
+        fill.as_ref().map_err(String::as_str)
+    }
+
+    /// Gets the `ShapePipeline` used to draw this shader as a shape fill.
+    /// This shader must have been compiled with `compile_fill`.
+    pub fn fill_pipeline(
+        &self,
+        descriptors: &Descriptors,
+        samples: u32,
+        format: TextureFormat,
+    ) -> Arc<ShapePipeline> {
+        let fill = self
+            .fill(descriptors)
+            .expect("Shape fills should only use shaders that compiled");
+
+        fill.pipelines
+            .borrow_mut()
+            .entry((samples, format))
+            .or_insert_with(|| {
+                let bind_layouts = &descriptors.bind_layouts;
+                Arc::new(create_shape_pipeline_for_stages(
+                    &format!("PixelBender shader fill {:?}", self.shader.name),
+                    &descriptors.device,
+                    format,
+                    PipelineStage::vertex(&descriptors.shaders.shader_fill_shader),
+                    PipelineStage {
+                        module: &fill.fragment_shader,
+                        entry_point: naga_pixelbender::FRAGMENT_SHADER_ENTRYPOINT,
+                    },
+                    samples,
+                    &VERTEX_BUFFERS_DESCRIPTION_POS,
+                    &[
+                        &bind_layouts.globals,
+                        &bind_layouts.transforms,
+                        &bind_layouts.shader_fill,
+                        &self.bind_group_layout,
+                    ],
+                    wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+                    &[],
+                    wgpu::PrimitiveTopology::TriangleList,
+                ))
+            })
+            .clone()
+    }
+
+    /// Creates the bind group holding the parameters and inputs of a shape fill
+    /// using this shader. This is bound at `naga_pixelbender::SHAPE_FILL_BIND_GROUP`.
+    /// This shader must have been compiled with `compile_fill`.
+    pub fn create_fill_bind_group(
+        &self,
+        descriptors: &Descriptors,
+        arguments: &[PixelBenderShaderArgument],
+        label: Option<&str>,
+    ) -> wgpu::BindGroup {
+        let fill = self
+            .fill(descriptors)
+            .expect("Shape fills should only use shaders that compiled");
+        let mut float_parameters: Vec<f32> = Vec::new();
+        let mut int_parameters: Vec<i32> = Vec::new();
+        let mut texture_views = Vec::new();
+
+        for argument in arguments {
+            match argument {
+                PixelBenderShaderArgument::ImageInput { index, texture, .. } => {
+                    let texture = texture
+                        .as_ref()
+                        .expect("Shader fill inputs should have been checked by the tessellator");
+                    let texture = image_input_as_texture(descriptors, texture);
+                    texture_views.push((
+                        *index,
+                        texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                    ));
+                }
+                PixelBenderShaderArgument::ValueInput { index, value } => {
+                    if let PixelBenderParam::Normal { name, .. } =
+                        &self.shader.params[*index as usize]
+                    {
+                        if name == OUT_COORD_NAME {
+                            continue;
+                        }
+                    }
+                    match FloatOrInt::from_value(value) {
+                        FloatOrInt::Float(v) => float_parameters.extend_from_slice(&v),
+                        FloatOrInt::Int(v) => int_parameters.extend_from_slice(&v),
+                    }
+                }
+            }
+        }
+
+        float_parameters.resize(self.float_parameters_buffer_size as usize / 4, 0.0);
+        int_parameters.resize(self.int_parameters_buffer_size as usize / 4, 0);
+
+        let key = (
+            float_parameters.iter().map(|v| v.to_bits()).collect(),
+            int_parameters.clone(),
+        );
+        let parameters = fill
+            .parameters
+            .borrow_mut()
+            .get_or_insert(key, || {
+                Arc::new(ShaderFillParameters {
+                    float_parameters_buffer: descriptors.device.create_buffer_init(
+                        &wgpu::util::BufferInitDescriptor {
+                            label: create_debug_label!(
+                                "PixelBender shader fill float parameters for {:?}",
+                                self.shader.name
+                            )
+                            .as_deref(),
+                            contents: bytemuck::cast_slice(&float_parameters),
+                            usage: BufferUsages::UNIFORM,
+                        },
+                    ),
+                    int_parameters_buffer: descriptors.device.create_buffer_init(
+                        &wgpu::util::BufferInitDescriptor {
+                            label: create_debug_label!(
+                                "PixelBender shader fill int parameters for {:?}",
+                                self.shader.name
+                            )
+                            .as_deref(),
+                            contents: bytemuck::cast_slice(&int_parameters),
+                            usage: BufferUsages::UNIFORM,
+                        },
+                    ),
+                })
+            })
+            .clone();
+
+        let mut entries = vec![
+            BindGroupEntry {
+                binding: naga_pixelbender::SAMPLER_CLAMP_NEAREST,
+                resource: BindingResource::Sampler(&descriptors.bitmap_samplers.clamp_nearest),
+            },
+            BindGroupEntry {
+                binding: naga_pixelbender::SAMPLER_CLAMP_LINEAR,
+                resource: BindingResource::Sampler(&descriptors.bitmap_samplers.clamp_linear),
+            },
+            BindGroupEntry {
+                binding: naga_pixelbender::SAMPLER_CLAMP_BILINEAR,
+                resource: BindingResource::Sampler(&descriptors.bitmap_samplers.clamp_bilinear),
+            },
+            BindGroupEntry {
+                binding: naga_pixelbender::SHADER_FLOAT_PARAMETERS_INDEX,
+                resource: parameters.float_parameters_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: naga_pixelbender::SHADER_INT_PARAMETERS_INDEX,
+                resource: parameters.int_parameters_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: naga_pixelbender::ZEROED_OUT_OF_RANGE_MODE_INDEX,
+                resource: fill.zeroed_out_of_range_mode.as_entire_binding(),
+            },
+        ];
+        for (index, view) in &texture_views {
+            entries.push(BindGroupEntry {
+                binding: naga_pixelbender::TEXTURE_START_BIND_INDEX + *index as u32,
+                resource: BindingResource::TextureView(view),
+            });
+        }
+
+        descriptors
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label,
+                layout: &self.bind_group_layout,
+                entries: &entries,
+            })
+    }
+}
+
 impl PixelBenderShaderImpl for PixelBenderWgpuShader {
     fn parsed_shader(&self) -> &PixelBenderShader {
         &self.shader
@@ -257,6 +499,68 @@ impl PixelBenderWgpuShader {
             zeroed_out_of_range_mode,
             // FIXME - come up with a good chunk size
             staging_belt: RefCell::new(StagingBelt::new(8)),
+            fill: OnceCell::new(),
+        }
+    }
+}
+
+/// A parameter value, laid out as some number of concatenated vec4s.
+#[derive(Debug)]
+enum FloatOrInt {
+    Float(SmallVec<[f32; 4]>),
+    Int(SmallVec<[i32; 4]>),
+}
+
+impl FloatOrInt {
+    fn from_value(value: &PixelBenderType) -> Self {
+        match value {
+            PixelBenderType::TFloat(f1) => FloatOrInt::Float(smallvec_inline![*f1, 0.0, 0.0, 0.0]),
+            PixelBenderType::TFloat2(f1, f2) => {
+                FloatOrInt::Float(smallvec_inline![*f1, *f2, 0.0, 0.0])
+            }
+            PixelBenderType::TFloat3(f1, f2, f3) => {
+                FloatOrInt::Float(smallvec_inline![*f1, *f2, *f3, 0.0])
+            }
+            PixelBenderType::TFloat4(f1, f2, f3, f4) => {
+                FloatOrInt::Float(smallvec_inline![*f1, *f2, *f3, *f4])
+            }
+            PixelBenderType::TInt(i1) | PixelBenderType::TBool(i1) => {
+                FloatOrInt::Int(smallvec_inline![*i1 as i32, 0, 0, 0])
+            }
+            PixelBenderType::TInt2(i1, i2) | PixelBenderType::TBool2(i1, i2) => {
+                FloatOrInt::Int(smallvec_inline![*i1 as i32, *i2 as i32, 0, 0])
+            }
+            PixelBenderType::TInt3(i1, i2, i3) | PixelBenderType::TBool3(i1, i2, i3) => {
+                FloatOrInt::Int(smallvec_inline![*i1 as i32, *i2 as i32, *i3 as i32, 0])
+            }
+            PixelBenderType::TInt4(i1, i2, i3, i4) | PixelBenderType::TBool4(i1, i2, i3, i4) => {
+                FloatOrInt::Int(smallvec_inline![
+                    *i1 as i32, *i2 as i32, *i3 as i32, *i4 as i32
+                ])
+            }
+            // We treat the input as being in column-major order. Despite what the Flash docs claim,
+            // this seems to be what Flash Player does.
+            PixelBenderType::TFloat2x2(arr) => FloatOrInt::Float(SmallVec::from(*arr)),
+            PixelBenderType::TFloat3x3(arr) => {
+                // Add a zero after every 3 values to created zero-padded vec4s
+                let mut vec4_arr = SmallVec::with_capacity(16);
+                for (i, val) in arr.iter().enumerate() {
+                    vec4_arr.push(*val);
+                    if i % 3 == 2 {
+                        vec4_arr.push(0.0);
+                    }
+                }
+                FloatOrInt::Float(vec4_arr)
+            }
+            PixelBenderType::TFloat4x4(arr) => FloatOrInt::Float(SmallVec::from_slice(arr)),
+            _ => unreachable!("Unimplemented value {value:?}"),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            FloatOrInt::Float(v) => v.len(),
+            FloatOrInt::Int(v) => v.len(),
         }
     }
 }
@@ -406,8 +710,7 @@ pub(super) fn run_pixelbender_shader_impl(
         },
         BindGroupEntry {
             binding: naga_pixelbender::SAMPLER_CLAMP_BILINEAR,
-            // FIXME - create bilinear sampler
-            resource: BindingResource::Sampler(&descriptors.bitmap_samplers.clamp_linear),
+            resource: BindingResource::Sampler(&descriptors.bitmap_samplers.clamp_bilinear),
         },
         BindGroupEntry {
             binding: naga_pixelbender::SHADER_FLOAT_PARAMETERS_INDEX,
@@ -538,64 +841,7 @@ pub(super) fn run_pixelbender_shader_impl(
                     continue;
                 }
 
-                #[derive(Debug)]
-                enum FloatOrInt {
-                    Float(SmallVec<[f32; 4]>),
-                    Int(SmallVec<[i32; 4]>),
-                }
-
-                impl FloatOrInt {
-                    fn len(&self) -> usize {
-                        match self {
-                            FloatOrInt::Float(v) => v.len(),
-                            FloatOrInt::Int(v) => v.len(),
-                        }
-                    }
-                }
-
-                let value_vec = match value {
-                    PixelBenderType::TFloat(f1) => {
-                        FloatOrInt::Float(smallvec_inline![*f1, 0.0, 0.0, 0.0])
-                    }
-                    PixelBenderType::TFloat2(f1, f2) => {
-                        FloatOrInt::Float(smallvec_inline![*f1, *f2, 0.0, 0.0])
-                    }
-                    PixelBenderType::TFloat3(f1, f2, f3) => {
-                        FloatOrInt::Float(smallvec_inline![*f1, *f2, *f3, 0.0])
-                    }
-                    PixelBenderType::TFloat4(f1, f2, f3, f4) => {
-                        FloatOrInt::Float(smallvec_inline![*f1, *f2, *f3, *f4])
-                    }
-                    PixelBenderType::TInt(i1) | PixelBenderType::TBool(i1) => {
-                        FloatOrInt::Int(smallvec_inline![*i1 as i32, 0, 0, 0])
-                    }
-                    PixelBenderType::TInt2(i1, i2) | PixelBenderType::TBool2(i1, i2) => {
-                        FloatOrInt::Int(smallvec_inline![*i1 as i32, *i2 as i32, 0, 0])
-                    }
-                    PixelBenderType::TInt3(i1, i2, i3) | PixelBenderType::TBool3(i1, i2, i3) => {
-                        FloatOrInt::Int(smallvec_inline![*i1 as i32, *i2 as i32, *i3 as i32, 0])
-                    }
-                    PixelBenderType::TInt4(i1, i2, i3, i4)
-                    | PixelBenderType::TBool4(i1, i2, i3, i4) => FloatOrInt::Int(smallvec_inline![
-                        *i1 as i32, *i2 as i32, *i3 as i32, *i4 as i32
-                    ]),
-                    // We treat the input as being in column-major order. Despite what the Flash docs claim,
-                    // this seems to be what Flash Player does.
-                    PixelBenderType::TFloat2x2(arr) => FloatOrInt::Float(SmallVec::from(*arr)),
-                    PixelBenderType::TFloat3x3(arr) => {
-                        // Add a zero after every 3 values to created zero-padded vec4s
-                        let mut vec4_arr = SmallVec::with_capacity(16);
-                        for (i, val) in arr.iter().enumerate() {
-                            vec4_arr.push(*val);
-                            if i % 3 == 2 {
-                                vec4_arr.push(0.0);
-                            }
-                        }
-                        FloatOrInt::Float(vec4_arr)
-                    }
-                    PixelBenderType::TFloat4x4(arr) => FloatOrInt::Float(SmallVec::from_slice(arr)),
-                    _ => unreachable!("Unimplemented value {value:?}"),
-                };
+                let value_vec = FloatOrInt::from_value(value);
 
                 assert_eq!(
                     value_vec.len() % 4,
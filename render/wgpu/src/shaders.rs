@@ -13,6 +13,9 @@ pub struct Shaders {
     /// dividing by the alpha value would produce an out-of-range value).
     pub bitmap_shader: wgpu::ShaderModule,
     pub gradient_shader: wgpu::ShaderModule,
+    /// This only has a vertex stage - the fragment stage is generated
+    /// from the Pixel Bender shader used by each shader fill.
+    pub shader_fill_shader: wgpu::ShaderModule,
     pub copy_srgb_shader: wgpu::ShaderModule,
     pub copy_shader: wgpu::ShaderModule,
     pub alpha_mask_shader: wgpu::ShaderModule,
@@ -22,6 +25,9 @@ pub struct Shaders {
     pub glow_filter: wgpu::ShaderModule,
     pub bevel_filter: wgpu::ShaderModule,
     pub displacement_map_filter: wgpu::ShaderModule,
+    pub convolution_filter: wgpu::ShaderModule,
+    pub gradient_glow_filter: wgpu::ShaderModule,
+    pub gradient_bevel_filter: wgpu::ShaderModule,
 }
 
 impl Shaders {
@@ -63,11 +69,31 @@ impl Shaders {
             "filter/displacement_map.wgsl",
             include_str!("../shaders/filter/displacement_map.wgsl"),
         );
+        let convolution_filter = make_filter_shader(
+            device,
+            "filter/convolution.wgsl",
+            include_str!("../shaders/filter/convolution.wgsl"),
+        );
+        let gradient_glow_filter = make_filter_shader(
+            device,
+            "filter/gradient_glow.wgsl",
+            include_str!("../shaders/filter/gradient_glow.wgsl"),
+        );
+        let gradient_bevel_filter = make_filter_shader(
+            device,
+            "filter/gradient_bevel.wgsl",
+            include_str!("../shaders/filter/gradient_bevel.wgsl"),
+        );
         let gradient_shader = make_shader(
             device,
             "gradient.wgsl",
             include_str!("../shaders/gradient.wgsl"),
         );
+        let shader_fill_shader = make_shader(
+            device,
+            "shader_fill.wgsl",
+            include_str!("../shaders/shader_fill.wgsl"),
+        );
         let alpha_mask_shader = make_shader(
             device,
             "alpha_mask.wgsl",
@@ -90,6 +116,7 @@ impl Shaders {
             color_shader,
             bitmap_shader,
             gradient_shader,
+            shader_fill_shader,
             copy_srgb_shader,
             copy_shader,
             alpha_mask_shader,
@@ -99,6 +126,9 @@ impl Shaders {
             glow_filter,
             bevel_filter,
             displacement_map_filter,
+            convolution_filter,
+            gradient_glow_filter,
+            gradient_bevel_filter,
         }
     }
 }
@@ -5,6 +5,7 @@ use crate::buffer_builder::BufferBuilder;
 use crate::buffer_pool::TexturePool;
 use crate::dynamic_transforms::DynamicTransforms;
 use crate::mesh::{as_mesh, DrawType, Mesh};
+use crate::pixel_bender::as_cache_holder;
 use crate::surface::target::CommandTarget;
 use crate::surface::Surface;
 use crate::{as_texture, Descriptors, MaskState, Pipelines, Transforms};
@@ -172,6 +173,33 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
         self.render_pass.set_bind_group(2, bind_group, &[]);
     }
 
+    pub fn prep_shader_fill(
+        &mut self,
+        shader: &PixelBenderShaderHandle,
+        bind_group: &'pass wgpu::BindGroup,
+        shader_bind_group: &'pass wgpu::BindGroup,
+    ) {
+        let pipeline = as_cache_holder(shader).fill_pipeline(
+            self.descriptors,
+            self.pipelines.msaa_sample_count,
+            self.pipelines.format,
+        );
+        if self.needs_stencil {
+            self.render_pass
+                .set_pipeline(pipeline.pipeline_for(self.mask_state));
+        } else {
+            self.render_pass
+                .set_pipeline(pipeline.stencilless_pipeline());
+        }
+
+        self.render_pass.set_bind_group(2, bind_group, &[]);
+        self.render_pass.set_bind_group(
+            naga_pixelbender::SHAPE_FILL_BIND_GROUP,
+            shader_bind_group,
+            &[],
+        );
+    }
+
     pub fn prep_alpha_mask(&mut self, bind_group: &'pass wgpu::BindGroup) {
         if self.needs_stencil {
             self.render_pass
@@ -297,6 +325,13 @@ impl<'pass, 'frame: 'pass, 'global: 'frame> CommandRenderer<'pass, 'frame, 'glob
                 DrawType::Bitmap { binds, .. } => {
                     self.prep_bitmap(&binds.bind_group, TrivialBlend::Normal, false);
                 }
+                DrawType::Shader {
+                    shader,
+                    bind_group,
+                    shader_bind_group,
+                } => {
+                    self.prep_shader_fill(shader, bind_group, shader_bind_group);
+                }
             }
             self.render_pass.set_bind_group(
                 1,
@@ -373,6 +373,7 @@ pub enum Filter {
 }
 
 #[derive(Default, Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, Enum)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlendMode {
     #[default]
     Normal = 0,
@@ -747,6 +748,7 @@ pub struct StyleChangeData {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FillStyle {
     Color(Color),
     LinearGradient(Gradient),
@@ -764,6 +766,7 @@ pub enum FillStyle {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gradient {
     pub matrix: Matrix,
     pub spread: GradientSpread,
@@ -772,6 +775,7 @@ pub struct Gradient {
 }
 
 #[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, Enum, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GradientSpread {
     Pad = 0,
     Reflect = 1,
@@ -790,6 +794,7 @@ impl GradientSpread {
 }
 
 #[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GradientInterpolation {
     Rgb = 0,
     LinearRgb = 1,
@@ -807,12 +812,14 @@ impl GradientInterpolation {
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GradientRecord {
     pub ratio: u8,
     pub color: Color,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineStyle {
     pub(crate) width: Twips,
     pub(crate) fill_style: FillStyle,
@@ -967,6 +974,7 @@ impl Default for LineStyle {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LineStyleFlag: u16 {
         // First byte.
         const PIXEL_HINTING = 1 << 0;
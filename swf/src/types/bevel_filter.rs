@@ -2,6 +2,7 @@ use crate::{BlurFilter, BlurFilterFlags, Color, Fixed16, Fixed8, Rectangle, Twip
 use bitflags::bitflags;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BevelFilter {
     pub shadow_color: Color,
     pub highlight_color: Color,
@@ -49,6 +50,10 @@ impl BevelFilter {
     }
 
     pub fn calculate_dest_rect(&self, source_rect: Rectangle<Twips>) -> Rectangle<Twips> {
+        // An inner bevel is only ever drawn over the source.
+        if self.is_inner() && !self.is_on_top() {
+            return source_rect;
+        }
         let mut result = self.inner_blur_filter().calculate_dest_rect(source_rect);
         let distance = self.distance.to_f64();
         let angle = self.angle.to_f64();
@@ -74,6 +79,7 @@ impl BevelFilter {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct BevelFilterFlags: u8 {
         const INNER_SHADOW     = 1 << 7;
         const KNOCKOUT         = 1 << 6;
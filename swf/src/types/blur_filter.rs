@@ -10,6 +10,7 @@ const PASS_SCALES: [f64; 15] = [
 ];
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlurFilter {
     pub blur_x: Fixed16,
     pub blur_y: Fixed16,
@@ -32,9 +33,21 @@ impl BlurFilter {
     }
 
     pub fn calculate_dest_rect(&self, source_rect: Rectangle<Twips>) -> Rectangle<Twips> {
-        let scale = PASS_SCALES[self.num_passes().clamp(1, 15) as usize - 1];
-        let x = Twips::from_pixels((scale * self.blur_x.to_f64()).max(0.0));
-        let y = Twips::from_pixels((scale * self.blur_y.to_f64()).max(0.0));
+        if self.num_passes() == 0 {
+            return source_rect;
+        }
+        // A blur of 1 pixel or less along an axis doesn't spread the image along it.
+        let scale = PASS_SCALES[self.num_passes().min(15) as usize - 1];
+        let x = if self.blur_x <= Fixed16::ONE {
+            Twips::ZERO
+        } else {
+            Twips::from_pixels(scale * self.blur_x.to_f64())
+        };
+        let y = if self.blur_y <= Fixed16::ONE {
+            Twips::ZERO
+        } else {
+            Twips::from_pixels(scale * self.blur_y.to_f64())
+        };
         Rectangle {
             x_min: source_rect.x_min - x,
             x_max: source_rect.x_max + x,
@@ -51,6 +64,7 @@ impl BlurFilter {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct BlurFilterFlags: u8 {
         const PASSES = 0b11111 << 3;
     }
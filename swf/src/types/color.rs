@@ -4,6 +4,7 @@ use std::fmt::{Debug, Formatter};
 ///
 /// All components are stored as [`u8`] and have a color range of 0-255.
 #[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     /// The red component value.
     pub r: u8,
@@ -2,6 +2,7 @@ use crate::Color;
 use bitflags::bitflags;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConvolutionFilter {
     pub num_matrix_rows: u8,
     pub num_matrix_cols: u8,
@@ -26,6 +27,7 @@ impl ConvolutionFilter {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ConvolutionFilterFlags: u8 {
         const CLAMP          = 1 << 1;
         const PRESERVE_ALPHA = 1 << 0;
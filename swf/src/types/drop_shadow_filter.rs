@@ -5,6 +5,7 @@ use crate::{
 use bitflags::bitflags;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DropShadowFilter {
     pub color: Color,
     pub blur_x: Fixed16,
@@ -65,6 +66,10 @@ impl DropShadowFilter {
     }
 
     pub fn calculate_dest_rect(&self, source_rect: Rectangle<Twips>) -> Rectangle<Twips> {
+        // An inner shadow is only ever drawn over the source.
+        if self.is_inner() {
+            return source_rect;
+        }
         let mut result = self.inner_glow_filter().calculate_dest_rect(source_rect);
         let distance = self.distance.to_f64();
         let angle = self.angle.to_f64();
@@ -86,6 +91,7 @@ impl DropShadowFilter {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DropShadowFilterFlags: u8 {
         const INNER_SHADOW     = 1 << 7;
         const KNOCKOUT         = 1 << 6;
@@ -20,6 +20,7 @@ macro_rules! define_fixed {
     ) => {
         /// A signed fixed-point value with $frac_bits bits.
         #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $type_name($underlying_type);
 
         /// A signed fixed-point type.
@@ -2,6 +2,7 @@ use crate::{BlurFilter, BlurFilterFlags, Color, Fixed16, Fixed8, Rectangle, Twip
 use bitflags::bitflags;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GlowFilter {
     pub color: Color,
     pub blur_x: Fixed16,
@@ -37,7 +38,10 @@ impl GlowFilter {
     }
 
     pub fn calculate_dest_rect(&self, source_rect: Rectangle<Twips>) -> Rectangle<Twips> {
-        // TODO: Inner might not need this. Docs suggest it doesn't care about source rect, but rather source *size*?
+        // An inner glow is only ever drawn over the source.
+        if self.is_inner() {
+            return source_rect;
+        }
         self.inner_blur_filter().calculate_dest_rect(source_rect)
     }
 
@@ -52,6 +56,7 @@ impl GlowFilter {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct GlowFilterFlags: u8 {
         const INNER_GLOW       = 1 << 7;
         const KNOCKOUT         = 1 << 6;
@@ -1,7 +1,8 @@
-use crate::{BlurFilter, BlurFilterFlags, Fixed16, Fixed8, GradientRecord};
+use crate::{BlurFilter, BlurFilterFlags, Fixed16, Fixed8, GradientRecord, Rectangle, Twips};
 use bitflags::bitflags;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GradientFilter {
     pub colors: Vec<GradientRecord>,
     pub blur_x: Fixed16,
@@ -23,6 +24,11 @@ impl GradientFilter {
         self.flags.contains(GradientFilterFlags::KNOCKOUT)
     }
 
+    #[inline]
+    pub fn composite_source(&self) -> bool {
+        self.flags.contains(GradientFilterFlags::COMPOSITE_SOURCE)
+    }
+
     #[inline]
     pub fn is_on_top(&self) -> bool {
         self.flags.contains(GradientFilterFlags::ON_TOP)
@@ -46,10 +52,38 @@ impl GradientFilter {
             flags: BlurFilterFlags::from_passes(self.num_passes()),
         }
     }
+
+    pub fn calculate_dest_rect(&self, source_rect: Rectangle<Twips>) -> Rectangle<Twips> {
+        // An inner glow or bevel is only ever drawn over the source.
+        if self.is_inner() && !self.is_on_top() {
+            return source_rect;
+        }
+        let mut result = self.inner_blur_filter().calculate_dest_rect(source_rect);
+        let distance = self.distance.to_f64();
+        let angle = self.angle.to_f64();
+        let x = Twips::from_pixels(angle.cos() * distance);
+        let y = Twips::from_pixels(angle.sin() * distance);
+        if x < Twips::ZERO {
+            result.x_min += x;
+            result.x_max -= x;
+        } else {
+            result.x_max += x;
+            result.x_min -= x;
+        }
+        if y < Twips::ZERO {
+            result.y_min += y;
+            result.y_max -= y;
+        } else {
+            result.y_max += y;
+            result.y_min -= y;
+        }
+        result
+    }
 }
 
 bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct GradientFilterFlags: u8 {
         const INNER_SHADOW     = 1 << 7;
         const KNOCKOUT         = 1 << 6;
@@ -28,6 +28,7 @@ impl Coordinate for Twips {
 
 /// A 2D position defined by x and y coordinates.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point<T: Coordinate> {
     pub x: T,
     pub y: T,
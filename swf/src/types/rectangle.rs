@@ -11,6 +11,7 @@ impl Coordinate for Twips {
 
 /// A rectangular region defined by minimum and maximum x- and y-coordinate positions.
 #[derive(Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle<T> {
     /// The minimum x-position of the rectangle.
     pub x_min: T,
@@ -16,6 +16,7 @@
 /// multiplying them as part of calculations yields `16*10^8`, which is
 /// relatively close to the upper limit of `i32` at about `2*10^9`.
 #[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Twips(i32);
 
 impl Twips {
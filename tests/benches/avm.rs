@@ -0,0 +1,62 @@
+//! Measures how long AVM1 and AVM2 scripts take to run.
+//!
+//! Run with `cargo bench -p tests --bench avm`, optionally followed by `--` and
+//! part of a movie's path to only measure the matching movies. Each movie is
+//! played for a number of frames, and the average time taken by a frame is
+//! reported.
+
+use ruffle_core::limits::ExecutionLimit;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::PlayerBuilder;
+use std::time::{Duration, Instant};
+
+/// Movies that run a script on every frame.
+const MOVIES: &[&str] = &[
+    "tests/swfs/avm1/action_cache/test.swf",
+    // A Box2D-style integration loop, using the type-specialised arithmetic.
+    "tests/swfs/avm2/optimize_numeric_frames/test.swf",
+    // Alchemy-style code running on domain memory.
+    "tests/swfs/avm2/domain_memory_alchemy_frames/test.swf",
+];
+
+/// Frames played before measuring, so that the first run of a script isn't counted.
+const WARMUP_FRAMES: u32 = 10;
+
+const FRAMES: u32 = 200;
+
+fn main() {
+    // Cargo passes `--bench` along, which isn't a filter.
+    let filters: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+    let movies: Vec<&str> = MOVIES
+        .iter()
+        .copied()
+        .filter(|path| filters.is_empty() || filters.iter().any(|filter| path.contains(filter)))
+        .collect();
+    run(&movies);
+}
+
+/// Play each movie and print the average time taken by a frame.
+fn run(movies: &[&str]) {
+    for path in movies {
+        let movie = SwfMovie::from_path(path, None).expect("Movie should load");
+        let player = PlayerBuilder::new()
+            .with_movie(movie)
+            .with_autoplay(true)
+            .with_max_execution_duration(Duration::from_secs(300))
+            .build();
+        let mut player = player.lock().unwrap();
+        while !player.preload(&mut ExecutionLimit::exhausted()) {}
+
+        for _ in 0..WARMUP_FRAMES {
+            player.run_frame();
+        }
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            player.run_frame();
+        }
+        println!("{path}: {:?} per frame", start.elapsed() / FRAMES);
+    }
+}
@@ -121,6 +121,8 @@ impl UiBackend for TestUiBackend {
         Ok(())
     }
 
+    fn set_mouse_lock(&mut self, _locked: bool) {}
+
     fn display_root_movie_download_failed_message(&self, _invalid_swf: bool, _fetch_error: String) {
     }
 
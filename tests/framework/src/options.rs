@@ -160,6 +160,8 @@ pub struct PlayerOptions {
     runtime: PlayerRuntime,
     mode: Option<PlayerMode>,
     with_default_font: bool,
+    fdb_port: Option<u16>,
+    fdb_password: Option<String>,
 }
 
 impl PlayerOptions {
@@ -186,7 +188,9 @@ impl PlayerOptions {
             .with_player_runtime(self.runtime)
             // Assume flashplayerdebugger is used in tests
             .with_player_mode(self.mode.unwrap_or(PlayerMode::Debug))
-            .with_default_font(self.with_default_font);
+            .with_default_font(self.with_default_font)
+            .with_debugger_address(self.fdb_port.map(|port| ("localhost".to_string(), port)))
+            .with_debugger_password(self.fdb_password.clone());
 
         if self.with_video {
             #[cfg(feature = "ruffle_video_external")]
@@ -340,7 +344,7 @@ impl ImageComparison {
             let filter_passed = check
                 .filter
                 .as_ref()
-                .map(|f| f.evaluate())
+                .map(|f| f.evaluate(&environment_name))
                 .unwrap_or(Ok(true))?;
             if !filter_passed {
                 println!("{check_name} skipped: Filtered out.");
@@ -481,6 +485,10 @@ struct ImageComparisonCheck {
 pub struct RenderOptions {
     optional: bool,
     pub sample_count: u32,
+
+    /// Whether this test also runs with the software renderer, which only
+    /// supports some of what the GPU renderers do.
+    pub software: bool,
 }
 
 impl Default for RenderOptions {
@@ -488,6 +496,7 @@ impl Default for RenderOptions {
         Self {
             optional: false,
             sample_count: 1,
+            software: false,
         }
     }
 }
@@ -527,7 +536,8 @@ pub struct FontSortOptions {
 /// Currently the following variables are supported:
 /// * `os` --- refers to [`std::env::consts::OS`],
 /// * `arch` --- refers to [`std::env::consts::ARCH`],
-/// * `family` --- refers to [`std::env::consts::FAMILY`].
+/// * `family` --- refers to [`std::env::consts::FAMILY`],
+/// * `environment` --- refers to the name of the render environment, such as `software`.
 ///
 /// Example expression:
 ///
@@ -538,7 +548,7 @@ pub struct FontSortOptions {
 struct TestExpression(String);
 
 impl TestExpression {
-    fn evaluate(&self) -> Result<bool> {
+    fn evaluate(&self, environment: &str) -> Result<bool> {
         let cfg_parsed = cfg_expr::Expression::parse(&self.0)
             .map_err(|err| anyhow!("Cannot parse expression:\n{err}"))?;
         let mut unknown_pred = None;
@@ -552,6 +562,9 @@ impl TestExpression {
             cfg_expr::Predicate::KeyValue { key, val } if *key == "family" => {
                 *val == std::env::consts::FAMILY
             }
+            cfg_expr::Predicate::KeyValue { key, val } if *key == "environment" => {
+                *val == environment
+            }
             _ => {
                 unknown_pred = Some(format!("{pred:?}"));
                 false
@@ -114,6 +114,10 @@ pub enum AutomatedEvent {
     /// Move the mouse to a new cursor position.
     MouseMove { pos: MousePosition },
 
+    /// Move the mouse by a relative amount (in screen pixels), as reported
+    /// while the mouse is locked with `Stage.mouseLock`.
+    MouseMoveRelative { dx: f64, dy: f64 },
+
     /// Click a mouse button.
     MouseDown {
         pos: MousePosition,
@@ -1,5 +1,9 @@
 mod format;
 mod injector;
+#[cfg(feature = "ruffle_core")]
+mod player;
 
 pub use format::{AutomatedEvent, AutomatedKey, MouseButton, TextControlCode};
 pub use injector::{InputInjector, MouseButtons};
+#[cfg(feature = "ruffle_core")]
+pub use player::{automated_event_to_player_event, InvalidEventError};
@@ -0,0 +1,198 @@
+//! Conversion of automated events into player events
+
+use crate::format::{
+    AutomatedEvent, AutomatedKey, MouseButton as InputMouseButton, TextControlCode,
+};
+use ruffle_core::events::{
+    ImeEvent, KeyDescriptor, KeyLocation, LogicalKey, NamedKey, PhysicalKey,
+    TextControlCode as RuffleTextControlCode,
+};
+use ruffle_core::events::{MouseButton as RuffleMouseButton, MouseWheelDelta};
+use ruffle_core::PlayerEvent;
+use std::error::Error;
+use std::fmt;
+
+/// An automated event that can't be turned into a player event, such as a
+/// mouse wheel event with both `lines` and `pixels` set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidEventError(&'static str);
+
+impl fmt::Display for InvalidEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Error for InvalidEventError {}
+
+/// Convert an automated event into the event the player should handle.
+///
+/// Returns `None` for events that don't directly translate into a player
+/// event, such as `Wait` and `SetClipboardText`; those have to be handled
+/// by the caller. Malformed events, which input files can contain, are
+/// reported as an error.
+///
+/// Mouse button indices default to 0, so that playback is deterministic.
+pub fn automated_event_to_player_event(
+    event: &AutomatedEvent,
+) -> Result<Option<PlayerEvent>, InvalidEventError> {
+    Ok(Some(match event {
+        AutomatedEvent::MouseDown {
+            pos, btn, index, ..
+        } => PlayerEvent::MouseDown {
+            x: pos.0,
+            y: pos.1,
+            button: automated_button_to_mouse_button(*btn),
+            // None here means that the core will compute index automatically,
+            // however we do not want that in automated playback.
+            index: Some(index.unwrap_or_default()),
+        },
+        AutomatedEvent::MouseMove { pos } => PlayerEvent::MouseMove { x: pos.0, y: pos.1 },
+        AutomatedEvent::MouseMoveRelative { dx, dy } => {
+            PlayerEvent::MouseMoveRelative { dx: *dx, dy: *dy }
+        }
+        AutomatedEvent::MouseUp { pos, btn } => PlayerEvent::MouseUp {
+            x: pos.0,
+            y: pos.1,
+            button: automated_button_to_mouse_button(*btn),
+        },
+        AutomatedEvent::MouseWheel { lines, pixels } => PlayerEvent::MouseWheel {
+            delta: match (lines, pixels) {
+                (Some(lines), None) => MouseWheelDelta::Lines(*lines),
+                (None, Some(pixels)) => MouseWheelDelta::Pixels(*pixels),
+                _ => {
+                    return Err(InvalidEventError(
+                        "MouseWheel: expected only one of 'lines' or 'pixels'",
+                    ))
+                }
+            },
+        },
+        AutomatedEvent::KeyDown { key } => PlayerEvent::KeyDown {
+            key: automated_key_to_descriptor(*key),
+        },
+        AutomatedEvent::KeyUp { key } => PlayerEvent::KeyUp {
+            key: automated_key_to_descriptor(*key),
+        },
+        AutomatedEvent::TextInput { codepoint } => PlayerEvent::TextInput {
+            codepoint: *codepoint,
+        },
+        AutomatedEvent::TextControl { code } => PlayerEvent::TextControl {
+            code: match code {
+                TextControlCode::MoveLeft => RuffleTextControlCode::MoveLeft,
+                TextControlCode::MoveLeftWord => RuffleTextControlCode::MoveLeftWord,
+                TextControlCode::MoveLeftLine => RuffleTextControlCode::MoveLeftLine,
+                TextControlCode::MoveLeftDocument => RuffleTextControlCode::MoveLeftDocument,
+                TextControlCode::MoveRight => RuffleTextControlCode::MoveRight,
+                TextControlCode::MoveRightWord => RuffleTextControlCode::MoveRightWord,
+                TextControlCode::MoveRightLine => RuffleTextControlCode::MoveRightLine,
+                TextControlCode::MoveRightDocument => RuffleTextControlCode::MoveRightDocument,
+                TextControlCode::SelectLeft => RuffleTextControlCode::SelectLeft,
+                TextControlCode::SelectLeftWord => RuffleTextControlCode::SelectLeftWord,
+                TextControlCode::SelectLeftLine => RuffleTextControlCode::SelectLeftLine,
+                TextControlCode::SelectLeftDocument => RuffleTextControlCode::SelectLeftDocument,
+                TextControlCode::SelectRight => RuffleTextControlCode::SelectRight,
+                TextControlCode::SelectRightWord => RuffleTextControlCode::SelectRightWord,
+                TextControlCode::SelectRightLine => RuffleTextControlCode::SelectRightLine,
+                TextControlCode::SelectRightDocument => RuffleTextControlCode::SelectRightDocument,
+                TextControlCode::SelectAll => RuffleTextControlCode::SelectAll,
+                TextControlCode::Copy => RuffleTextControlCode::Copy,
+                TextControlCode::Paste => RuffleTextControlCode::Paste,
+                TextControlCode::Cut => RuffleTextControlCode::Cut,
+                TextControlCode::Backspace => RuffleTextControlCode::Backspace,
+                TextControlCode::Enter => RuffleTextControlCode::Enter,
+                TextControlCode::Delete => RuffleTextControlCode::Delete,
+            },
+        },
+        AutomatedEvent::FocusGained => PlayerEvent::FocusGained,
+        AutomatedEvent::FocusLost => PlayerEvent::FocusLost,
+        AutomatedEvent::ImePreedit { text, cursor } => {
+            PlayerEvent::Ime(ImeEvent::Preedit(text.clone(), *cursor))
+        }
+        AutomatedEvent::ImeCommit { text } => PlayerEvent::Ime(ImeEvent::Commit(text.clone())),
+        AutomatedEvent::Wait | AutomatedEvent::SetClipboardText { .. } => return Ok(None),
+    }))
+}
+
+fn automated_button_to_mouse_button(btn: InputMouseButton) -> RuffleMouseButton {
+    match btn {
+        InputMouseButton::Left => RuffleMouseButton::Left,
+        InputMouseButton::Middle => RuffleMouseButton::Middle,
+        InputMouseButton::Right => RuffleMouseButton::Right,
+    }
+}
+
+fn automated_key_to_descriptor(automated_key: AutomatedKey) -> KeyDescriptor {
+    let logical_key = match automated_key {
+        AutomatedKey::Char(ch) | AutomatedKey::Numpad(ch) => LogicalKey::Character(ch),
+        AutomatedKey::ArrowDown => LogicalKey::Named(NamedKey::ArrowDown),
+        AutomatedKey::ArrowLeft => LogicalKey::Named(NamedKey::ArrowLeft),
+        AutomatedKey::ArrowRight => LogicalKey::Named(NamedKey::ArrowRight),
+        AutomatedKey::ArrowUp => LogicalKey::Named(NamedKey::ArrowUp),
+        AutomatedKey::Backspace => LogicalKey::Named(NamedKey::Backspace),
+        AutomatedKey::CapsLock => LogicalKey::Named(NamedKey::CapsLock),
+        AutomatedKey::Delete => LogicalKey::Named(NamedKey::Delete),
+        AutomatedKey::End => LogicalKey::Named(NamedKey::End),
+        AutomatedKey::Enter => LogicalKey::Named(NamedKey::Enter),
+        AutomatedKey::Escape => LogicalKey::Named(NamedKey::Escape),
+        AutomatedKey::F1 => LogicalKey::Named(NamedKey::F1),
+        AutomatedKey::F2 => LogicalKey::Named(NamedKey::F2),
+        AutomatedKey::F3 => LogicalKey::Named(NamedKey::F3),
+        AutomatedKey::F4 => LogicalKey::Named(NamedKey::F4),
+        AutomatedKey::F5 => LogicalKey::Named(NamedKey::F5),
+        AutomatedKey::F6 => LogicalKey::Named(NamedKey::F6),
+        AutomatedKey::F7 => LogicalKey::Named(NamedKey::F7),
+        AutomatedKey::F8 => LogicalKey::Named(NamedKey::F8),
+        AutomatedKey::F9 => LogicalKey::Named(NamedKey::F9),
+        AutomatedKey::Home => LogicalKey::Named(NamedKey::Home),
+        AutomatedKey::Insert => LogicalKey::Named(NamedKey::Insert),
+        AutomatedKey::LeftAlt => LogicalKey::Named(NamedKey::Alt),
+        AutomatedKey::LeftControl => LogicalKey::Named(NamedKey::Control),
+        AutomatedKey::LeftShift => LogicalKey::Named(NamedKey::Shift),
+        AutomatedKey::NumLock => LogicalKey::Named(NamedKey::NumLock),
+        AutomatedKey::NumpadDelete => LogicalKey::Named(NamedKey::Delete),
+        AutomatedKey::NumpadDown => LogicalKey::Named(NamedKey::ArrowDown),
+        AutomatedKey::NumpadEnd => LogicalKey::Named(NamedKey::End),
+        AutomatedKey::NumpadHome => LogicalKey::Named(NamedKey::Home),
+        AutomatedKey::NumpadInsert => LogicalKey::Named(NamedKey::Insert),
+        AutomatedKey::NumpadLeft => LogicalKey::Named(NamedKey::ArrowLeft),
+        AutomatedKey::NumpadPageDown => LogicalKey::Named(NamedKey::PageDown),
+        AutomatedKey::NumpadPageUp => LogicalKey::Named(NamedKey::PageUp),
+        AutomatedKey::NumpadRight => LogicalKey::Named(NamedKey::ArrowRight),
+        AutomatedKey::NumpadUp => LogicalKey::Named(NamedKey::ArrowUp),
+        AutomatedKey::PageDown => LogicalKey::Named(NamedKey::PageDown),
+        AutomatedKey::PageUp => LogicalKey::Named(NamedKey::PageUp),
+        AutomatedKey::Pause => LogicalKey::Named(NamedKey::Pause),
+        AutomatedKey::RightControl => LogicalKey::Named(NamedKey::Control),
+        AutomatedKey::RightShift => LogicalKey::Named(NamedKey::Shift),
+        AutomatedKey::ScrollLock => LogicalKey::Named(NamedKey::ScrollLock),
+        AutomatedKey::Space => LogicalKey::Character(' '),
+        AutomatedKey::Tab => LogicalKey::Named(NamedKey::Tab),
+        AutomatedKey::Unknown => LogicalKey::Unknown,
+    };
+    let key_location = match automated_key {
+        AutomatedKey::Numpad(_) => KeyLocation::Numpad,
+        AutomatedKey::LeftAlt => KeyLocation::Left,
+        AutomatedKey::LeftControl => KeyLocation::Left,
+        AutomatedKey::LeftShift => KeyLocation::Left,
+        AutomatedKey::NumLock => KeyLocation::Numpad,
+        AutomatedKey::NumpadDelete => KeyLocation::Numpad,
+        AutomatedKey::NumpadDown => KeyLocation::Numpad,
+        AutomatedKey::NumpadEnd => KeyLocation::Numpad,
+        AutomatedKey::NumpadHome => KeyLocation::Numpad,
+        AutomatedKey::NumpadInsert => KeyLocation::Numpad,
+        AutomatedKey::NumpadLeft => KeyLocation::Numpad,
+        AutomatedKey::NumpadPageDown => KeyLocation::Numpad,
+        AutomatedKey::NumpadPageUp => KeyLocation::Numpad,
+        AutomatedKey::NumpadRight => KeyLocation::Numpad,
+        AutomatedKey::NumpadUp => KeyLocation::Numpad,
+        AutomatedKey::RightControl => KeyLocation::Right,
+        AutomatedKey::RightShift => KeyLocation::Right,
+        _ => KeyLocation::Standard,
+    };
+    KeyDescriptor {
+        // We don't use physical keys in tests
+        physical_key: PhysicalKey::Unknown,
+        logical_key,
+        key_location,
+    }
+}
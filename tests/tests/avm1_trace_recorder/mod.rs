@@ -0,0 +1,155 @@
+use ruffle_core::avm1_trace_recorder::{BreakReason, Breakpoint};
+use ruffle_core::context::UpdateContext;
+use ruffle_core::Player;
+use ruffle_test_framework::environment::Environment;
+use ruffle_test_framework::options::TestOptions;
+use ruffle_test_framework::runner::TestStatus;
+use ruffle_test_framework::test::Test;
+use ruffle_test_framework::vfs::{PhysicalFS, VfsPath};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+
+/// Where the trace recorder is currently looking: the offset of the action, how
+/// deep its activation is nested, and the stack right before it.
+fn current(context: &mut UpdateContext<'_>) -> (usize, u16, String) {
+    let record = context
+        .avm1
+        .trace_recorder()
+        .current()
+        .expect("Trace recorder should be paused");
+    (record.offset, record.depth, format!("{:?}", record.stack))
+}
+
+fn debug<R>(player: &Arc<Mutex<Player>>, f: impl FnOnce(&mut UpdateContext<'_>) -> R) -> R {
+    player.lock().unwrap().mutate_with_update_context(f)
+}
+
+pub fn avm1_trace_recorder(environment: &impl Environment) -> Result<(), libtest_mimic::Failed> {
+    // Break on the first frame, then step through what was recorded since,
+    // while the movie still produces its regular output.
+    let test = &Test::from_options(
+        TestOptions {
+            num_frames: Some(1),
+            ..Default::default()
+        },
+        VfsPath::new(PhysicalFS::new("tests/swfs/avm1/add/")),
+        "avm1_trace_recorder".to_string(),
+    )?;
+    let mut runner = test.create_test_runner(environment)?;
+    let player = runner.player().clone();
+
+    debug(&player, |context| {
+        context
+            .avm1
+            .trace_recorder_mut()
+            .add_breakpoint(Breakpoint::Frame {
+                path: "_level0".to_string(),
+                frame: 1,
+            })
+    });
+
+    let mut paused = false;
+    loop {
+        runner.tick();
+        match runner.test()? {
+            TestStatus::Continue => {}
+            TestStatus::Sleep(duration) => sleep(duration),
+            TestStatus::Finished => break,
+        }
+
+        if !debug(&player, |context| context.avm1.trace_recorder().is_paused()) {
+            continue;
+        }
+        assert!(!paused, "Trace recorder should only break once");
+        paused = true;
+
+        // The frame breakpoint stops at the first action of the `DoAction`.
+        let (start, depth, stack) = debug(&player, |context| {
+            let record = context.avm1.trace_recorder().current().unwrap();
+            assert_eq!(record.reason, Some(BreakReason::Frame));
+            assert_eq!(record.activation, "[Actions Parent] / [Frame]");
+            current(context)
+        });
+        assert_eq!(stack, "[]");
+
+        // Breakpoints set while paused are found in the recording.
+        // `Push "a", "ab", "cd"` is followed by `Add` at 0x97.
+        let url = debug(&player, |context| {
+            let url = context.root_swf.url().to_string();
+            context
+                .avm1
+                .trace_recorder_mut()
+                .add_breakpoint(Breakpoint::Action {
+                    url: url.clone(),
+                    offset: start + 0x97,
+                });
+            context.avm1.trace_recorder_mut().resume();
+            url
+        });
+        assert_eq!(
+            debug(&player, current),
+            (
+                start + 0x97,
+                depth,
+                r#"[String("a"), String("ab"), String("cd")]"#.to_string()
+            )
+        );
+
+        debug(&player, |context| {
+            context.avm1.trace_recorder_mut().step_in()
+        });
+        assert_eq!(
+            debug(&player, current),
+            (
+                start + 0x98,
+                depth,
+                r#"[String("a"), Number(NaN)]"#.to_string()
+            )
+        );
+
+        // Adding `obj_1` and `obj_2` calls their `valueOf` functions.
+        debug(&player, |context| {
+            context
+                .avm1
+                .trace_recorder_mut()
+                .add_breakpoint(Breakpoint::Action {
+                    url,
+                    offset: start + 0x274,
+                });
+            context.avm1.trace_recorder_mut().resume();
+        });
+        assert_eq!(debug(&player, current).0, start + 0x274);
+
+        debug(&player, |context| {
+            context.avm1.trace_recorder_mut().step_in()
+        });
+        let (offset, function_depth, _) = debug(&player, current);
+        assert_eq!(offset, start + 0x20);
+        assert!(function_depth > depth);
+
+        // Stepping out skips the other `valueOf` too, as it returns to the same depth.
+        debug(&player, |context| {
+            context.avm1.trace_recorder_mut().step_out()
+        });
+        assert_eq!(
+            debug(&player, current),
+            (
+                start + 0x275,
+                depth,
+                r#"[String("a"), Number(5.0)]"#.to_string()
+            )
+        );
+
+        // There are no breakpoints left to hit, so the player runs again.
+        debug(&player, |context| {
+            context.avm1.trace_recorder_mut().resume();
+            assert!(!context.avm1.trace_recorder().is_paused());
+        });
+    }
+    assert!(
+        paused,
+        "Trace recorder should have hit the frame breakpoint"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use ruffle_core::avm2_debugger::recorder::{Recorder, Snapshot};
+use ruffle_core::avm2_debugger::StopReason;
+use ruffle_test_framework::environment::Environment;
+use ruffle_test_framework::options::TestOptions;
+use ruffle_test_framework::runner::TestStatus;
+use ruffle_test_framework::test::Test;
+use ruffle_test_framework::vfs::{PhysicalFS, VfsPath};
+use std::thread::sleep;
+
+fn locals(snapshot: &Snapshot) -> Vec<(String, String)> {
+    snapshot
+        .locals
+        .iter()
+        .map(|variable| (variable.name.clone(), variable.value.clone()))
+        .collect()
+}
+
+/// Step through what the movie recorded after hitting the first breakpoint,
+/// the way an editor would.
+fn debug_session(recorder: &Recorder) {
+    // Line breakpoint inside `testfunc("arg1", "arg2")`
+    let snapshot = recorder.current().expect("Movie should be paused");
+    assert_eq!(snapshot.reason, StopReason::Breakpoint);
+    let frame = &snapshot.frames[0];
+    assert_eq!(frame.line, 7);
+    assert!(frame
+        .file
+        .as_deref()
+        .unwrap_or_default()
+        .ends_with("Test.as"));
+    assert!(frame.name.contains("testfunc"));
+    assert_eq!(
+        &locals(&snapshot)[1..],
+        &[
+            ("v1".to_string(), "\"arg1\"".to_string()),
+            ("v2".to_string(), "\"arg2\"".to_string()),
+            ("v3".to_string(), "\"Default string\"".to_string()),
+        ]
+    );
+
+    assert_eq!(recorder.step_over(), Some(StopReason::Step));
+    assert_eq!(recorder.current().unwrap().frames[0].line, 8);
+
+    // Back out into the script initializer, at the call to `testfunc2`
+    assert_eq!(recorder.step_out(), Some(StopReason::Step));
+    assert_eq!(recorder.current().unwrap().frames[0].line, 19);
+
+    assert_eq!(recorder.resume(), Some(StopReason::FunctionBreakpoint));
+    let snapshot = recorder.current().unwrap();
+    assert!(snapshot.frames[0].name.contains("testfunc2"));
+    assert_eq!(
+        locals(&snapshot)[1],
+        ("v1".to_string(), "\"arg_again_1\"".to_string())
+    );
+
+    assert_eq!(recorder.resume(), None);
+    assert!(!recorder.is_paused());
+}
+
+pub fn avm2_debugger(environment: &impl Environment) -> Result<(), libtest_mimic::Failed> {
+    // Drive a debugging session through breakpoints and steps, while the
+    // movie still produces its regular output.
+    let test = &Test::from_options(
+        TestOptions {
+            num_ticks: Some(1),
+            ..Default::default()
+        },
+        VfsPath::new(PhysicalFS::new("tests/swfs/avm2/function_call_default/")),
+        "avm2_debugger".to_string(),
+    )?;
+    let mut runner = test.create_test_runner(environment)?;
+
+    let recorder = Recorder::waiting_for_configuration();
+    runner
+        .player()
+        .lock()
+        .unwrap()
+        .attach_avm2_debugger(Box::new(recorder.clone()));
+    recorder.set_line_breakpoints(
+        "/home/user/function_call_default/Test.as".to_string(),
+        vec![7],
+    );
+    recorder.set_function_breakpoints(vec!["testfunc2".to_string()]);
+    recorder.configuration_done();
+
+    let mut debugged = false;
+    loop {
+        runner.tick();
+        if recorder.is_paused() {
+            debug_session(&recorder);
+            debugged = true;
+        }
+        match runner.test()? {
+            TestStatus::Continue => {}
+            TestStatus::Sleep(duration) => sleep(duration),
+            TestStatus::Finished => break,
+        }
+    }
+    assert!(debugged, "Movie should have stopped at a breakpoint");
+
+    Ok(())
+}
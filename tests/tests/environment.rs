@@ -22,6 +22,62 @@ impl Environment for NativeEnvironment {
     )> {
         renderer::NativeRenderInterface::create_pair(width, height)
     }
+
+    #[cfg(all(feature = "imgtests_software", not(feature = "imgtests")))]
+    fn is_render_supported(
+        &self,
+        requirements: &ruffle_test_framework::options::RenderOptions,
+    ) -> bool {
+        requirements.software
+    }
+
+    #[cfg(all(feature = "imgtests_software", not(feature = "imgtests")))]
+    fn create_renderer(
+        &self,
+        width: u32,
+        height: u32,
+    ) -> Option<(
+        Box<dyn ruffle_test_framework::environment::RenderInterface>,
+        Box<dyn ruffle_test_framework::environment::RenderBackend>,
+    )> {
+        Some(software_renderer::SoftwareRenderInterface::create_pair(
+            width, height,
+        ))
+    }
+}
+
+#[cfg(all(feature = "imgtests_software", not(feature = "imgtests")))]
+mod software_renderer {
+    use image::RgbaImage;
+    use ruffle_render_software::SoftwareRenderBackend;
+    use ruffle_test_framework::environment::{RenderBackend, RenderInterface};
+    use std::any::Any;
+
+    pub struct SoftwareRenderInterface;
+
+    impl SoftwareRenderInterface {
+        pub fn create_pair(
+            width: u32,
+            height: u32,
+        ) -> (Box<dyn RenderInterface>, Box<dyn RenderBackend>) {
+            (
+                Box::new(Self),
+                Box::new(SoftwareRenderBackend::new(width, height)),
+            )
+        }
+    }
+
+    impl RenderInterface for SoftwareRenderInterface {
+        fn name(&self) -> String {
+            "software".to_string()
+        }
+
+        fn capture(&self, backend: &mut dyn RenderBackend) -> RgbaImage {
+            let renderer = <dyn Any>::downcast_mut::<SoftwareRenderBackend>(backend).unwrap();
+
+            renderer.capture_frame().expect("Failed to capture image")
+        }
+    }
 }
 
 #[cfg(feature = "imgtests")]
@@ -2,6 +2,8 @@
 //!
 //! Trace output can be compared with correct output from the official Flash Player.
 
+use crate::avm1_trace_recorder::avm1_trace_recorder;
+use crate::avm2_debugger::avm2_debugger;
 use crate::environment::NativeEnvironment;
 use crate::external_interface::tests::{external_interface_avm1, external_interface_avm2};
 use crate::shared_object::{shared_object_avm1, shared_object_avm2, shared_object_self_ref_avm1};
@@ -19,6 +21,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::thread::sleep;
 
+mod avm1_trace_recorder;
+mod avm2_debugger;
 mod environment;
 mod external_interface;
 mod shared_object;
@@ -84,6 +88,12 @@ fn main() {
     runner.with_additional_test(Trial::test("external_interface_avm2", || {
         external_interface_avm2(&NativeEnvironment)
     }));
+    runner.with_additional_test(Trial::test("avm1_trace_recorder", || {
+        avm1_trace_recorder(&NativeEnvironment)
+    }));
+    runner.with_additional_test(Trial::test("avm2_debugger", || {
+        avm2_debugger(&NativeEnvironment)
+    }));
 
     runner.run()
 }
@@ -0,0 +1,89 @@
+// SWF hand-assembled, as jumping into the middle of an action can't be written in ActionScript.
+// The movie has two frames, so that the script runs again every loop when used as a benchmark.
+
+ConstantPool "first" "second"
+Push constant0
+Trace
+ConstantPool "second" "first"
+Push constant0
+Trace
+
+// function sum(n) { var total = 0; while (0 < n) { total += n; n--; } return total; }
+DefineFunction2 "sum", 1, 3, false, false, true, false, true, false, true, false, false, 1, "n" {
+  Push 0
+  StoreRegister 2
+  Pop
+loop:
+  Push 0, register1
+  Less2
+  Not
+  If end
+  Push register2, register1
+  Add2
+  StoreRegister 2
+  Pop
+  Push register1
+  Decrement
+  StoreRegister 1
+  Pop
+  Jump loop
+end:
+  Push register2
+  Return
+}
+
+// Jumps past the end of the function, which returns from it.
+DefineFunction2 "skip", 0, 1, false, false, true, false, true, false, true, false, false {
+  Push "skip"
+  Trace
+  Jump 100
+  Push "unreachable"
+  Trace
+}
+
+// var i = 0; var total = 0; while (i < 1000) { total += i * 2; i++; } trace(total);
+Push "i", 0
+SetVariable
+Push "total", 0
+SetVariable
+loop:
+Push "i"
+GetVariable
+Push 1000
+Less2
+Not
+If end
+Push "total", "total"
+GetVariable
+Push "i"
+GetVariable
+Push 2
+Multiply
+Add2
+SetVariable
+Push "i", "i"
+GetVariable
+Increment
+SetVariable
+Jump loop
+end:
+Push "total"
+GetVariable
+Trace
+
+// trace(sum(1000));
+Push 1000, 1, "sum"
+CallFunction
+Trace
+
+// trace(skip());
+Push 0, "skip"
+CallFunction
+Trace
+
+// The operand of the Push below reads as `PushDuplicate; Pop; PushDuplicate; Pop`.
+Push "middle"
+Jump middle
+Push 0x174C174C (middle is at the second byte of the operand)
+Trace
+End
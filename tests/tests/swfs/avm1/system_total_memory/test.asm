@@ -0,0 +1,19 @@
+// AVM1's `System` has no memory properties, unlike AVM2's.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+do_action {
+  Push "System"
+  GetVariable
+  Push "totalMemory"
+  GetMember
+  TypeOf
+  Trace
+  Push "totalMemory", 1, "System"
+  GetVariable
+  Push "hasOwnProperty"
+  CallMethod
+  Trace
+}
+show
@@ -0,0 +1,213 @@
+// `BitmapData.applyFilter` with a ConvolutionFilter on a small opaque image,
+// with filters chosen so that every result is exact: shifting the image by a
+// pixel, adding a bias, or dividing by a divisor that leaves no remainder.
+//
+// The image is black, except for its middle pixel, 0xFF902D09.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+size 64, 64
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    slot "check"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "check"
+      NewFunction check
+      InitProperty "check"
+
+      // var source = new BitmapData(3, 3, true, 0xFF000000);
+      // source.setPixel32(1, 1, 0xFF902D09);
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 3
+      PushByte 3
+      PushTrue
+      PushUint 0xFF000000
+      ConstructProp "flash.display::BitmapData", 4
+      SetLocal 1
+      GetLocal 1
+      PushByte 1
+      PushByte 1
+      PushUint 0xFF902D09
+      CallPropVoid "setPixel32", 3
+
+      // Each pixel takes the color of its top-left neighbour, repeating the edge pixels.
+      FindPropStrict "check"
+      GetLocal 1
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      NewArray 9
+      PushByte 1
+      PushByte 0
+      PushTrue
+      PushTrue
+      PushByte 0
+      PushByte 0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "check", 2
+
+      // Pixels outside of the image are opaque green instead.
+      FindPropStrict "check"
+      GetLocal 1
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      NewArray 9
+      PushByte 1
+      PushByte 0
+      PushTrue
+      PushFalse
+      PushInt 0x00FF00
+      PushByte 1
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "check", 2
+
+      // Pixels outside of the image are transparent, and alpha is filtered too.
+      FindPropStrict "check"
+      GetLocal 1
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      NewArray 9
+      PushByte 1
+      PushByte 0
+      PushFalse
+      PushFalse
+      PushByte 0
+      PushByte 0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "check", 2
+
+      // The bias is added to every color channel.
+      FindPropStrict "check"
+      GetLocal 1
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      NewArray 9
+      PushByte 1
+      PushByte 16
+      PushTrue
+      PushTrue
+      PushByte 0
+      PushByte 0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "check", 2
+
+      // A box blur, divided by the number of pixels in the box.
+      FindPropStrict "check"
+      GetLocal 1
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      NewArray 9
+      PushByte 9
+      PushByte 0
+      PushTrue
+      PushTrue
+      PushByte 0
+      PushByte 0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "check", 2
+      ReturnVoid
+    }
+  }
+
+  // Applies `filter` to `source`, and traces the top-left, middle and
+  // bottom-right pixels of the result.
+  method check {
+    param "flash.display::BitmapData", name="source"
+    param "flash.filters::BitmapFilter", name="filter"
+    code {
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 3
+      PushByte 3
+      PushTrue
+      PushByte 0
+      ConstructProp "flash.display::BitmapData", 4
+      SetLocal 3
+      GetLocal 3
+      GetLocal 1
+      GetLocal 1
+      GetProperty "rect"
+      FindPropStrict "flash.geom::Point"
+      ConstructProp "flash.geom::Point", 0
+      GetLocal 2
+      CallPropVoid "applyFilter", 4
+      FindPropStrict "trace"
+      GetLocal 3
+      PushByte 0
+      PushByte 0
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      GetLocal 3
+      PushByte 1
+      PushByte 1
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      GetLocal 3
+      PushByte 2
+      PushByte 2
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      CallPropVoid "trace", 3
+      ReturnVoid
+    }
+  }
+}
+show
@@ -0,0 +1,283 @@
+// `BitmapData.encode` with PNG and JPEG options. The encoded bytes differ
+// between encoders, so only their headers are checked, and the images are
+// decoded again with `Loader.loadBytes` to check their pixels.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 11
+size 64, 64
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "png"
+    slot "jpeg"
+    slot "loader"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+
+      // var bitmap = new BitmapData(4, 2, true, 0x80FF0000);
+      // bitmap.setPixel32(1, 0, 0xFF00FF00);
+      // bitmap.setPixel32(2, 1, 0xFF0000FF);
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 4
+      PushByte 2
+      PushTrue
+      PushUint 0x80FF0000
+      ConstructProp "flash.display::BitmapData", 4
+      SetLocal 1
+      GetLocal 1
+      PushByte 1
+      PushByte 0
+      PushUint 0xFF00FF00
+      CallPropVoid "setPixel32", 3
+      GetLocal 1
+      PushByte 2
+      PushByte 1
+      PushUint 0xFF0000FF
+      CallPropVoid "setPixel32", 3
+
+      // png = bitmap.encode(bitmap.rect, new PNGEncoderOptions());
+      // trace(png[0], png[1], png[2], png[3]);
+      FindProperty "png"
+      GetLocal 1
+      GetLocal 1
+      GetProperty "rect"
+      FindPropStrict "flash.display::PNGEncoderOptions"
+      ConstructProp "flash.display::PNGEncoderOptions", 0
+      CallProperty "encode", 2
+      SetProperty "png"
+      FindPropStrict "trace"
+      GetLex "png"
+      PushByte 0
+      GetProperty "[]::<runtime>"
+      GetLex "png"
+      PushByte 1
+      GetProperty "[]::<runtime>"
+      GetLex "png"
+      PushByte 2
+      GetProperty "[]::<runtime>"
+      GetLex "png"
+      PushByte 3
+      GetProperty "[]::<runtime>"
+      CallPropVoid "trace", 4
+
+      // The image is written after what the given ByteArray already holds.
+      // var bytes = new ByteArray();
+      // bytes.writeUTFBytes("abc");
+      // trace(bitmap.encode(bitmap.rect, new PNGEncoderOptions(true), bytes) === bytes);
+      // trace(bytes[2], bytes[3], bytes[4]);
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 2
+      GetLocal 2
+      PushString "abc"
+      CallPropVoid "writeUTFBytes", 1
+      FindPropStrict "trace"
+      GetLocal 1
+      GetLocal 1
+      GetProperty "rect"
+      FindPropStrict "flash.display::PNGEncoderOptions"
+      PushTrue
+      ConstructProp "flash.display::PNGEncoderOptions", 1
+      GetLocal 2
+      CallProperty "encode", 3
+      GetLocal 2
+      StrictEquals
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 2
+      PushByte 2
+      GetProperty "[]::<runtime>"
+      GetLocal 2
+      PushByte 3
+      GetProperty "[]::<runtime>"
+      GetLocal 2
+      PushByte 4
+      GetProperty "[]::<runtime>"
+      CallPropVoid "trace", 3
+
+      // jpeg = new BitmapData(8, 8, false, 0xFF0000).encode(new Rectangle(0, 0, 8, 8), new JPEGEncoderOptions(100));
+      // trace(jpeg[0], jpeg[1], jpeg[2]);
+      FindProperty "jpeg"
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 8
+      PushByte 8
+      PushFalse
+      PushInt 0xFF0000
+      ConstructProp "flash.display::BitmapData", 4
+      FindPropStrict "flash.geom::Rectangle"
+      PushByte 0
+      PushByte 0
+      PushByte 8
+      PushByte 8
+      ConstructProp "flash.geom::Rectangle", 4
+      FindPropStrict "flash.display::JPEGEncoderOptions"
+      PushByte 100
+      ConstructProp "flash.display::JPEGEncoderOptions", 1
+      CallProperty "encode", 2
+      SetProperty "jpeg"
+      FindPropStrict "trace"
+      GetLex "jpeg"
+      PushByte 0
+      GetProperty "[]::<runtime>"
+      GetLex "jpeg"
+      PushByte 1
+      GetProperty "[]::<runtime>"
+      GetLex "jpeg"
+      PushByte 2
+      GetProperty "[]::<runtime>"
+      CallPropVoid "trace", 3
+
+      // loader = new Loader();
+      // loader.contentLoaderInfo.addEventListener("complete", on_png);
+      // loader.loadBytes(png);
+      FindProperty "loader"
+      FindPropStrict "flash.display::Loader"
+      ConstructProp "flash.display::Loader", 0
+      SetProperty "loader"
+      GetLex "loader"
+      GetProperty "contentLoaderInfo"
+      PushString "complete"
+      NewFunction on_png
+      CallPropVoid "addEventListener", 2
+      GetLex "loader"
+      GetLex "png"
+      CallPropVoid "loadBytes", 1
+      ReturnVoid
+    }
+  }
+
+  // The decoded PNG has the same pixels, including their alpha.
+  method on_png {
+    param "flash.events::Event"
+    code {
+      GetLex "loader"
+      GetProperty "content"
+      GetProperty "bitmapData"
+      SetLocal 2
+      FindPropStrict "trace"
+      GetLocal 2
+      GetProperty "width"
+      GetLocal 2
+      GetProperty "height"
+      GetLocal 2
+      GetProperty "transparent"
+      CallPropVoid "trace", 3
+      FindPropStrict "trace"
+      GetLocal 2
+      PushByte 0
+      PushByte 0
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      GetLocal 2
+      PushByte 1
+      PushByte 0
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      GetLocal 2
+      PushByte 2
+      PushByte 1
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      CallPropVoid "trace", 3
+
+      // loader = new Loader();
+      // loader.contentLoaderInfo.addEventListener("complete", on_jpeg);
+      // loader.loadBytes(jpeg);
+      FindProperty "loader"
+      FindPropStrict "flash.display::Loader"
+      ConstructProp "flash.display::Loader", 0
+      SetProperty "loader"
+      GetLex "loader"
+      GetProperty "contentLoaderInfo"
+      PushString "complete"
+      NewFunction on_jpeg
+      CallPropVoid "addEventListener", 2
+      GetLex "loader"
+      GetLex "jpeg"
+      CallPropVoid "loadBytes", 1
+      ReturnVoid
+    }
+  }
+
+  // The decoded JPEG is opaque, and close to the original color.
+  method on_jpeg {
+    param "flash.events::Event"
+    code {
+      GetLex "loader"
+      GetProperty "content"
+      GetProperty "bitmapData"
+      SetLocal 2
+      GetLocal 2
+      PushByte 4
+      PushByte 4
+      CallProperty "getPixel32", 2
+      SetLocal 3
+      FindPropStrict "trace"
+      GetLocal 2
+      GetProperty "width"
+      GetLocal 2
+      GetProperty "height"
+      GetLocal 2
+      GetProperty "transparent"
+      CallPropVoid "trace", 3
+      FindPropStrict "trace"
+      GetLocal 3
+      PushByte 24
+      URShift
+      GetLocal 3
+      PushByte 16
+      URShift
+      PushShort 255
+      BitAnd
+      PushShort 240
+      GreaterThan
+      GetLocal 3
+      PushByte 8
+      URShift
+      PushShort 255
+      BitAnd
+      PushByte 16
+      LessThan
+      GetLocal 3
+      PushShort 255
+      BitAnd
+      PushByte 16
+      LessThan
+      CallPropVoid "trace", 4
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
@@ -0,0 +1,198 @@
+// A `Dictionary` with weak keys, whose entries are removed once their keys
+// are collected, compared to one with strong keys. Garbage is made on every
+// frame until the collector has run.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    slot "weak"
+    slot "strong"
+    slot "kept"
+    slot "sprite"
+    slot "count"
+    slot "frames", "int"
+    slot "done", "Boolean"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "count"
+      NewFunction count
+      InitProperty "count"
+      FindProperty "weak"
+      FindPropStrict "flash.utils::Dictionary"
+      PushTrue
+      ConstructProp "flash.utils::Dictionary", 1
+      InitProperty "weak"
+      FindProperty "strong"
+      FindPropStrict "flash.utils::Dictionary"
+      ConstructProp "flash.utils::Dictionary", 0
+      InitProperty "strong"
+      FindProperty "kept"
+      NewObject 0
+      InitProperty "kept"
+
+      // A key which stays reachable, and a string key, in both dictionaries.
+      GetLex "weak"
+      GetLex "kept"
+      PushString "kept"
+      SetProperty "[]::<runtime>"
+      GetLex "weak"
+      PushString "text"
+      SetProperty "name"
+      GetLex "strong"
+      GetLex "kept"
+      PushString "kept"
+      SetProperty "[]::<runtime>"
+      GetLex "strong"
+      PushString "text"
+      SetProperty "name"
+
+      // Keys which become unreachable once this script returns.
+      PushByte 0
+      SetLocal 1
+      Jump check
+    loop:
+      Label
+      GetLex "weak"
+      NewObject 0
+      GetLocal 1
+      SetProperty "[]::<runtime>"
+      GetLex "strong"
+      NewObject 0
+      GetLocal 1
+      SetProperty "[]::<runtime>"
+      IncLocalI 1
+    check:
+      GetLocal 1
+      PushByte 10
+      IfLt loop
+
+      FindProperty "sprite"
+      FindPropStrict "flash.display::Sprite"
+      ConstructProp "flash.display::Sprite", 0
+      InitProperty "sprite"
+      GetLex "sprite"
+      PushString "enterFrame"
+      NewFunction on_frame
+      CallPropVoid "addEventListener", 2
+      ReturnVoid
+    }
+  }
+
+  // function count(o) { var n = 0; for (var key in o) n++; return n; }
+  method count, returns="int" {
+    param "*"
+    code {
+      PushByte 0
+      SetLocal 2
+      GetLocal 1
+      SetLocal 3
+      PushByte 0
+      SetLocal 4
+      Jump check
+    loop:
+      Label
+      GetLocal 3
+      GetLocal 4
+      NextName
+      Pop
+      IncLocalI 2
+    check:
+      HasNext2 3, 4
+      IfTrue loop
+      GetLocal 2
+      ReturnValue
+    }
+  }
+
+  // Makes garbage on every frame, and checks what's left once the weak keys
+  // have been collected.
+  method on_frame {
+    param "*"
+    code {
+      GetLex "done"
+      IfTrue end
+      FindProperty "frames"
+      GetLex "frames"
+      IncrementI
+      SetProperty "frames"
+
+      GetLex "frames"
+      PushByte 1
+      IfNe collect
+      FindPropStrict "trace"
+      PushString "weak:"
+      FindPropStrict "count"
+      GetLex "weak"
+      CallProperty "count", 1
+      CallPropVoid "trace", 2
+      FindPropStrict "trace"
+      PushString "strong:"
+      FindPropStrict "count"
+      GetLex "strong"
+      CallProperty "count", 1
+      CallPropVoid "trace", 2
+      ReturnVoid
+
+    collect:
+      FindPropStrict "count"
+      GetLex "weak"
+      CallProperty "count", 1
+      PushByte 12
+      IfLt collected
+      PushShort 20000
+      SetLocal 2
+      Jump check
+    loop:
+      Label
+      NewObject 0
+      Pop
+      DecLocalI 2
+    check:
+      GetLocal 2
+      PushByte 0
+      IfGt loop
+      ReturnVoid
+
+    collected:
+      FindProperty "done"
+      PushTrue
+      SetProperty "done"
+      FindPropStrict "trace"
+      PushString "weak:"
+      FindPropStrict "count"
+      GetLex "weak"
+      CallProperty "count", 1
+      CallPropVoid "trace", 2
+      FindPropStrict "trace"
+      PushString "strong:"
+      FindPropStrict "count"
+      GetLex "strong"
+      CallProperty "count", 1
+      CallPropVoid "trace", 2
+      FindPropStrict "trace"
+      GetLex "weak"
+      GetLex "kept"
+      GetProperty "[]::<runtime>"
+      GetLex "weak"
+      GetProperty "name"
+      CallPropVoid "trace", 2
+      FindPropStrict "trace"
+      GetLex "strong"
+      GetLex "kept"
+      GetProperty "[]::<runtime>"
+      GetLex "strong"
+      GetProperty "name"
+      CallPropVoid "trace", 2
+    end:
+      ReturnVoid
+    }
+  }
+}
+show
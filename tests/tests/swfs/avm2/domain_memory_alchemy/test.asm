@@ -0,0 +1,305 @@
+// The code that Alchemy generates for the following C program, which runs on
+// domain memory. The sieve and the structs are placed in memory by hand, at
+// the addresses Alchemy would have given them.
+//
+//     #define N 10000
+//
+//     struct particle { int id; float mass; double x; };
+//
+//     static unsigned char sieve[N];       // at 1024
+//     static struct particle particles[8]; // at 16384
+//
+//     int count_primes(void) {
+//         int count = 0;
+//         for (int i = 2; i < N; i++) {
+//             if (!sieve[i]) {
+//                 count++;
+//                 for (int j = i + i; j < N; j += i) {
+//                     sieve[j] = 1;
+//                 }
+//             }
+//         }
+//         return count;
+//     }
+//
+//     double weigh_particles(void) {
+//         for (int i = 0; i < 8; i++) {
+//             struct particle *p = &particles[i];
+//             p->id = i;
+//             p->mass = i * 0.5;
+//             p->x = i * 1.25;
+//         }
+//         double total = 0;
+//         for (int i = 0; i < 8; i++) {
+//             struct particle *p = &particles[i];
+//             total += p->mass * p->x + p->id;
+//         }
+//         return total;
+//     }
+//
+// These functions are benchmarked by `domain_memory_alchemy_frames`.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+
+      GetLex "flash.system::ApplicationDomain"
+      GetProperty "currentDomain"
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      Dup
+      PushInt 65536
+      SetProperty "length"
+      SetProperty "domainMemory"
+
+      FindPropStrict "trace"
+      NewFunction count_primes
+      PushNull
+      Call 0
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      NewFunction weigh_particles
+      PushNull
+      Call 0
+      CallPropVoid "trace", 1
+
+      // Loads zero-extend the value, and stores truncate it.
+      PushByte -2
+      PushByte 0
+      Si16
+      FindPropStrict "trace"
+      PushByte 0
+      Li16
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushByte 0
+      Li16
+      Sxi16
+      CallPropVoid "trace", 1
+      PushShort 300
+      PushByte 8
+      Si8
+      FindPropStrict "trace"
+      PushByte 8
+      Li8
+      CallPropVoid "trace", 1
+
+      // A folded offset wraps around like `add_i` does.
+      PushByte 7
+      PushByte 4
+      Si8
+      FindPropStrict "trace"
+      PushInt -1
+      PushByte 5
+      AddI
+      Li8
+      CallPropVoid "trace", 1
+
+      // Accesses that are partly or entirely out of range.
+      FindPropStrict "trace"
+      NewFunction attempt
+      PushNull
+      NewFunction peek
+      PushInt 65532
+      Call 2
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      NewFunction attempt
+      PushNull
+      NewFunction peek
+      PushInt 65530
+      Call 2
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      NewFunction attempt
+      PushNull
+      NewFunction poke
+      PushByte 2
+      Call 2
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      NewFunction attempt
+      PushNull
+      NewFunction poke
+      PushByte 4
+      Call 2
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushByte 0
+      Li32
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  method count_primes, returns="int" {
+    code {
+      PushByte 0
+      SetLocal 3
+      PushByte 2
+      SetLocal 1
+      Jump outer_cond
+    outer:
+      Label
+      GetLocal 1
+      PushShort 1024
+      AddI
+      Li8
+      IfTrue next
+      IncLocalI 3
+      GetLocal 1
+      GetLocal 1
+      AddI
+      SetLocal 2
+      Jump inner_cond
+    inner:
+      Label
+      PushByte 1
+      GetLocal 2
+      PushShort 1024
+      AddI
+      Si8
+      GetLocal 2
+      GetLocal 1
+      AddI
+      SetLocal 2
+    inner_cond:
+      GetLocal 2
+      PushShort 10000
+      IfLt inner
+    next:
+      IncLocalI 1
+    outer_cond:
+      GetLocal 1
+      PushShort 10000
+      IfLt outer
+      GetLocal 3
+      ReturnValue
+    }
+  }
+
+  method weigh_particles, returns="Number" {
+    code {
+      PushByte 0
+      SetLocal 1
+      Jump init_cond
+    init:
+      Label
+      GetLocal 1
+      PushByte 16
+      MultiplyI
+      PushShort 16384
+      AddI
+      SetLocal 2
+      GetLocal 1
+      GetLocal 2
+      Si32
+      GetLocal 1
+      PushDouble 0.5
+      Multiply
+      GetLocal 2
+      PushByte 4
+      AddI
+      Sf32
+      GetLocal 1
+      PushDouble 1.25
+      Multiply
+      GetLocal 2
+      PushByte 8
+      AddI
+      Sf64
+      IncLocalI 1
+    init_cond:
+      GetLocal 1
+      PushByte 8
+      IfLt init
+
+      PushDouble 0
+      SetLocal 3
+      PushByte 0
+      SetLocal 1
+      Jump sum_cond
+    sum:
+      Label
+      GetLocal 1
+      PushByte 16
+      MultiplyI
+      PushShort 16384
+      AddI
+      SetLocal 2
+      GetLocal 3
+      GetLocal 2
+      PushByte 4
+      AddI
+      Lf32
+      GetLocal 2
+      PushByte 8
+      AddI
+      Lf64
+      Multiply
+      GetLocal 2
+      Li32
+      Add
+      Add
+      SetLocal 3
+      IncLocalI 1
+    sum_cond:
+      GetLocal 1
+      PushByte 8
+      IfLt sum
+      GetLocal 3
+      ReturnValue
+    }
+  }
+
+  method peek, returns="int" {
+    param "int", name="address"
+    code {
+      GetLocal 1
+      PushByte 2
+      AddI
+      Li32
+      ReturnValue
+    }
+  }
+
+  method poke {
+    param "int", name="address"
+    code {
+      PushByte 1
+      GetLocal 1
+      PushByte -4
+      AddI
+      Si32
+      ReturnVoid
+    }
+  }
+
+  method attempt {
+    param "*", name="f"
+    param "*", name="arg"
+    catch start, end, handler, type="Error"
+    code {
+      GetLocal 0
+      PushScope
+    start:
+      GetLocal 1
+      PushNull
+      GetLocal 2
+      Call 1
+    end:
+      ReturnValue
+    handler:
+      ReturnValue
+    }
+  }
+}
+show
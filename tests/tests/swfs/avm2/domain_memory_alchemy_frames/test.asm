@@ -0,0 +1,236 @@
+// The `count_primes` and `weigh_particles` functions of `domain_memory_alchemy`,
+// run on every frame. This is also used by the `avm` benchmark, which is why it
+// keeps running.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+size 64, 64
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "frames", "int"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      GetLex "flash.system::ApplicationDomain"
+      GetProperty "currentDomain"
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      Dup
+      PushInt 65536
+      SetProperty "length"
+      SetProperty "domainMemory"
+      GetLocal 0
+      PushString "enterFrame"
+      NewFunction on_frame
+      CallPropVoid "addEventListener", 2
+      ReturnVoid
+    }
+  }
+
+  method on_frame {
+    param "flash.events::Event"
+    code {
+      NewFunction clear_sieve
+      PushNull
+      Call 0
+      Pop
+      NewFunction count_primes
+      PushNull
+      Call 0
+      SetLocal 2
+      NewFunction weigh_particles
+      PushNull
+      Call 0
+      SetLocal 3
+      FindProperty "frames"
+      GetLex "frames"
+      IncrementI
+      SetProperty "frames"
+      // Only the first results are traced, the following frames are the same.
+      GetLex "frames"
+      PushByte 1
+      IfNe done
+      FindPropStrict "trace"
+      GetLocal 2
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 3
+      CallPropVoid "trace", 1
+    done:
+      ReturnVoid
+    }
+  }
+
+  // memset(sieve, 0, N);
+  method clear_sieve {
+    code {
+      PushByte 0
+      SetLocal 1
+      Jump cond
+    clear:
+      Label
+      PushByte 0
+      GetLocal 1
+      PushShort 1024
+      AddI
+      Si8
+      IncLocalI 1
+    cond:
+      GetLocal 1
+      PushShort 10000
+      IfLt clear
+      ReturnVoid
+    }
+  }
+
+  method count_primes, returns="int" {
+    code {
+      PushByte 0
+      SetLocal 3
+      PushByte 2
+      SetLocal 1
+      Jump outer_cond
+    outer:
+      Label
+      GetLocal 1
+      PushShort 1024
+      AddI
+      Li8
+      IfTrue next
+      IncLocalI 3
+      GetLocal 1
+      GetLocal 1
+      AddI
+      SetLocal 2
+      Jump inner_cond
+    inner:
+      Label
+      PushByte 1
+      GetLocal 2
+      PushShort 1024
+      AddI
+      Si8
+      GetLocal 2
+      GetLocal 1
+      AddI
+      SetLocal 2
+    inner_cond:
+      GetLocal 2
+      PushShort 10000
+      IfLt inner
+    next:
+      IncLocalI 1
+    outer_cond:
+      GetLocal 1
+      PushShort 10000
+      IfLt outer
+      GetLocal 3
+      ReturnValue
+    }
+  }
+
+  method weigh_particles, returns="Number" {
+    code {
+      PushByte 0
+      SetLocal 1
+      Jump init_cond
+    init:
+      Label
+      GetLocal 1
+      PushByte 16
+      MultiplyI
+      PushShort 16384
+      AddI
+      SetLocal 2
+      GetLocal 1
+      GetLocal 2
+      Si32
+      GetLocal 1
+      PushDouble 0.5
+      Multiply
+      GetLocal 2
+      PushByte 4
+      AddI
+      Sf32
+      GetLocal 1
+      PushDouble 1.25
+      Multiply
+      GetLocal 2
+      PushByte 8
+      AddI
+      Sf64
+      IncLocalI 1
+    init_cond:
+      GetLocal 1
+      PushByte 8
+      IfLt init
+
+      PushDouble 0
+      SetLocal 3
+      PushByte 0
+      SetLocal 1
+      Jump sum_cond
+    sum:
+      Label
+      GetLocal 1
+      PushByte 16
+      MultiplyI
+      PushShort 16384
+      AddI
+      SetLocal 2
+      GetLocal 3
+      GetLocal 2
+      PushByte 4
+      AddI
+      Lf32
+      GetLocal 2
+      PushByte 8
+      AddI
+      Lf64
+      Multiply
+      GetLocal 2
+      Li32
+      Add
+      Add
+      SetLocal 3
+      IncLocalI 1
+    sum_cond:
+      GetLocal 1
+      PushByte 8
+      IfLt sum
+      GetLocal 3
+      ReturnValue
+    }
+  }
+
+}
+symbol 0, "Main"
+show
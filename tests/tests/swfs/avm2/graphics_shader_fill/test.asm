@@ -0,0 +1,267 @@
+// `Graphics.beginShaderFill` with a Pixel Bender shader that draws red and green ramps
+// repeating every 20 pixels, and the blue and alpha channels of its `color` parameter.
+// The left square uses no matrix, and the right one a scaled and translated matrix.
+//
+// The shader is assembled from `shader.pbasm` with the `pixel_bender` crate's assembler,
+// and its bytes are written into a `ByteArray` here.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+size 200, 100
+file_attributes IS_ACTION_SCRIPT_3
+background 0xFFFFFF
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "shaderBytes"
+    slot "addSquare"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "shaderBytes"
+      NewFunction shader_bytes
+      InitProperty "shaderBytes"
+      FindProperty "addSquare"
+      NewFunction add_square
+      InitProperty "addSquare"
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  // The bytecode of `shader.pbasm`.
+  method shader_bytes {
+    code {
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 1
+      PushShort 165
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushShort 164
+      PushByte 5
+      PushByte 0
+      PushByte 82
+      PushByte 97
+      PushByte 109
+      PushByte 112
+      PushByte 115
+      PushShort 161
+      PushByte 1
+      PushByte 2
+      PushByte 0
+      PushByte 0
+      PushByte 12
+      PushByte 95
+      PushByte 79
+      PushByte 117
+      PushByte 116
+      PushByte 67
+      PushByte 111
+      PushByte 111
+      PushByte 114
+      PushByte 100
+      PushByte 0
+      PushShort 161
+      PushByte 1
+      PushByte 4
+      PushByte 1
+      PushByte 0
+      PushByte 15
+      PushByte 99
+      PushByte 111
+      PushByte 108
+      PushByte 111
+      PushByte 114
+      PushByte 0
+      PushShort 161
+      PushByte 2
+      PushByte 4
+      PushByte 2
+      PushByte 0
+      PushByte 15
+      PushByte 100
+      PushByte 115
+      PushByte 116
+      PushByte 0
+      PushByte 29
+      PushByte 3
+      PushByte 0
+      PushShort 193
+      PushByte 0
+      PushByte 0
+      PushByte 16
+      PushByte 0
+      PushByte 50
+      PushByte 4
+      PushByte 0
+      PushShort 128
+      PushByte 65
+      PushShort 160
+      PushByte 0
+      PushByte 0
+      PushByte 50
+      PushByte 4
+      PushByte 0
+      PushByte 64
+      PushByte 65
+      PushShort 160
+      PushByte 0
+      PushByte 0
+      PushByte 5
+      PushByte 3
+      PushByte 0
+      PushShort 193
+      PushByte 4
+      PushByte 0
+      PushByte 16
+      PushByte 0
+      PushByte 28
+      PushByte 2
+      PushByte 0
+      PushShort 193
+      PushByte 3
+      PushByte 0
+      PushByte 16
+      PushByte 0
+      PushByte 29
+      PushByte 2
+      PushByte 0
+      PushByte 49
+      PushByte 1
+      PushByte 0
+      PushShort 176
+      PushByte 0
+      NewArray 99
+      SetLocal 2
+      PushByte 0
+      SetLocal 3
+      Jump check
+    loop:
+      Label
+      GetLocal 1
+      GetLocal 2
+      GetLocal 3
+      GetProperty "[]::<runtime>"
+      CallPropVoid "writeByte", 1
+      IncLocalI 3
+    check:
+      GetLocal 3
+      GetLocal 2
+      GetProperty "length"
+      IfLt loop
+      GetLocal 1
+      ReturnValue
+    }
+  }
+
+  // An 80x80 square at the given position, filled with a new shader whose `color`
+  // parameter has the given blue and alpha channels, drawn with the given matrix.
+  method add_square {
+    param "flash.display::DisplayObjectContainer"
+    param "Number"
+    param "Number"
+    param "Number"
+    param "Number"
+    param "flash.geom::Matrix"
+    code {
+      FindPropStrict "flash.display::Shader"
+      FindPropStrict "shaderBytes"
+      CallProperty "shaderBytes", 0
+      ConstructProp "flash.display::Shader", 1
+      SetLocal 7
+      GetLocal 7
+      GetProperty "data"
+      GetProperty "color"
+      PushByte 0
+      PushByte 0
+      GetLocal 4
+      GetLocal 5
+      NewArray 4
+      SetProperty "value"
+      FindPropStrict "flash.display::Shape"
+      ConstructProp "flash.display::Shape", 0
+      SetLocal 8
+      GetLocal 8
+      GetProperty "graphics"
+      SetLocal 9
+      GetLocal 9
+      GetLocal 7
+      GetLocal 6
+      CallPropVoid "beginShaderFill", 2
+      GetLocal 9
+      PushByte 0
+      PushByte 0
+      PushByte 80
+      PushByte 80
+      CallPropVoid "drawRect", 4
+      GetLocal 9
+      CallPropVoid "endFill", 0
+      GetLocal 8
+      GetLocal 2
+      SetProperty "x"
+      GetLocal 8
+      GetLocal 3
+      SetProperty "y"
+      GetLocal 1
+      GetLocal 8
+      CallPropVoid "addChild", 1
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      // Opaque, with the shader's own coordinates.
+      FindPropStrict "addSquare"
+      GetLocal 0
+      PushByte 10
+      PushByte 10
+      PushDouble 0.5
+      PushByte 1
+      PushNull
+      CallPropVoid "addSquare", 6
+
+      // Translucent, with the ramps scaled up and moved by half of their size.
+      FindPropStrict "addSquare"
+      GetLocal 0
+      PushByte 110
+      PushByte 10
+      PushByte 1
+      PushDouble 0.5
+      FindPropStrict "flash.geom::Matrix"
+      PushByte 2
+      PushByte 0
+      PushByte 0
+      PushByte 2
+      PushByte 20
+      PushByte 20
+      ConstructProp "flash.geom::Matrix", 6
+      CallPropVoid "addSquare", 6
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
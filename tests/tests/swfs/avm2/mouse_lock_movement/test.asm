@@ -0,0 +1,117 @@
+// `Stage.mouseLock` can only be set in full screen, and while the mouse is locked,
+// relative mouse movement is reported through `MouseEvent.movementX/movementY`.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 11
+size 64, 64
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    catch lock_start, lock_end, lock_handler, type="Error"
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      GetLocal 0
+      GetProperty "stage"
+      SetLocal 1
+      GetLocal 1
+      PushString "mouseMove"
+      NewFunction on_move
+      CallPropVoid "addEventListener", 2
+
+      // Locking the mouse outside of full screen throws.
+    lock_start:
+      GetLocal 1
+      PushTrue
+      SetProperty "mouseLock"
+    lock_end:
+      FindPropStrict "trace"
+      PushString "no error"
+      CallPropVoid "trace", 1
+      Jump fullscreen
+    lock_handler:
+      SetLocal 2
+      FindPropStrict "trace"
+      PushString "mouseLock outside of full screen throws IllegalOperationError: "
+      GetLocal 2
+      GetLex "flash.errors::IllegalOperationError"
+      IsTypeLate
+      Add
+      CallPropVoid "trace", 1
+    fullscreen:
+      GetLocal 0
+      GetProperty "stage"
+      SetLocal 1
+      GetLocal 1
+      PushString "fullScreen"
+      SetProperty "displayState"
+      GetLocal 1
+      PushTrue
+      SetProperty "mouseLock"
+      FindPropStrict "trace"
+      PushString "mouseLock: "
+      GetLocal 1
+      GetProperty "mouseLock"
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  method on_move {
+    param "flash.events::MouseEvent"
+    code {
+      FindPropStrict "trace"
+      PushString "mouseMove movement: "
+      GetLocal 1
+      GetProperty "movementX"
+      Add
+      PushString ", "
+      Add
+      GetLocal 1
+      GetProperty "movementY"
+      Add
+      PushString " stage: "
+      Add
+      GetLocal 1
+      GetProperty "stageX"
+      Add
+      PushString ", "
+      Add
+      GetLocal 1
+      GetProperty "stageY"
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
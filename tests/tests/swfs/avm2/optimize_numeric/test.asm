@@ -0,0 +1,161 @@
+// A Box2D-style integration loop, along with the edge cases of the
+// type-specialised arithmetic the optimizer emits for it.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+
+      // The falling body bounces on the ground a number of times.
+      NewFunction simulate
+      PushNull
+      PushInt 20000
+      PushDouble 0.0005
+      Call 2
+      SetLocal 1
+      FindPropStrict "trace"
+      GetLocal 1
+      PushByte 100
+      Multiply
+      ConvertI
+      CallPropVoid "trace", 1
+
+      // The sum of two ints may not fit in an int.
+      FindPropStrict "trace"
+      PushInt 2147483647
+      PushByte 1
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushInt -2147483648
+      PushByte 1
+      Subtract
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushInt 100000000
+      PushInt 200000000
+      Add
+      CallPropVoid "trace", 1
+
+      // An int and a Number.
+      FindPropStrict "trace"
+      PushByte 3
+      PushDouble 0.5
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushByte 7
+      PushDouble 2
+      Divide
+      CallPropVoid "trace", 1
+
+      // Comparisons involving NaN are always false.
+      FindPropStrict "trace"
+      PushNaN
+      PushByte 0
+      LessThan
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushNaN
+      PushByte 0
+      GreaterEquals
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushDouble -0.0
+      PushByte 0
+      LessEquals
+      CallPropVoid "trace", 1
+
+      // Coercions that can't be removed are merged into the local store.
+      PushString "2.5"
+      ConvertD
+      SetLocal 2
+      FindPropStrict "trace"
+      GetLocal 2
+      PushByte 1
+      Add
+      CallPropVoid "trace", 1
+      PushString "7.9"
+      ConvertI
+      SetLocal 3
+      FindPropStrict "trace"
+      GetLocal 3
+      CallPropVoid "trace", 1
+
+      ReturnVoid
+    }
+  }
+
+  method simulate, returns="Number" {
+    param "int", name="steps"
+    param "Number", name="dt"
+    code {
+      // y = 100, vy = 0, i = 0, bounces = 0
+      PushDouble 100
+      SetLocal 3
+      PushByte 0
+      ConvertD
+      SetLocal 4
+      PushByte 0
+      SetLocal 5
+      PushByte 0
+      SetLocal 6
+      Jump check
+
+    step:
+      Label
+      // vy = vy - 9.8 * dt
+      GetLocal 4
+      PushDouble 9.8
+      GetLocal 2
+      Multiply
+      Subtract
+      ConvertD
+      SetLocal 4
+      // y = y + vy * dt
+      GetLocal 3
+      GetLocal 4
+      GetLocal 2
+      Multiply
+      Add
+      ConvertD
+      SetLocal 3
+      // if (y < 0) { y = -y; vy = -vy * 0.5; bounces++; }
+      GetLocal 3
+      PushByte 0
+      IfNlt next
+      GetLocal 3
+      Negate
+      ConvertD
+      SetLocal 3
+      GetLocal 4
+      Negate
+      PushDouble 0.5
+      Multiply
+      ConvertD
+      SetLocal 4
+      IncLocalI 6
+
+    next:
+      IncLocalI 5
+    check:
+      GetLocal 5
+      GetLocal 1
+      IfLt step
+
+      FindPropStrict "trace"
+      GetLocal 6
+      CallPropVoid "trace", 1
+      GetLocal 3
+      ReturnValue
+    }
+  }
+}
+show
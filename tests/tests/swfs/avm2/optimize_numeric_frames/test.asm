@@ -0,0 +1,140 @@
+// The Box2D-style integration loop of `optimize_numeric`, run on every frame.
+// This is also used by the `avm` benchmark, which is why it keeps running.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+size 64, 64
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "frames", "int"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      GetLocal 0
+      PushString "enterFrame"
+      NewFunction on_frame
+      CallPropVoid "addEventListener", 2
+      ReturnVoid
+    }
+  }
+
+  method on_frame {
+    param "flash.events::Event"
+    code {
+      NewFunction simulate
+      PushNull
+      PushInt 20000
+      PushDouble 0.0005
+      Call 2
+      SetLocal 2
+      FindProperty "frames"
+      GetLex "frames"
+      IncrementI
+      SetProperty "frames"
+      // Only the first result is traced, the following frames are the same.
+      GetLex "frames"
+      PushByte 1
+      IfNe done
+      FindPropStrict "trace"
+      GetLocal 2
+      PushByte 100
+      Multiply
+      ConvertI
+      CallPropVoid "trace", 1
+    done:
+      ReturnVoid
+    }
+  }
+
+  method simulate, returns="Number" {
+    param "int", name="steps"
+    param "Number", name="dt"
+    code {
+      // y = 100, vy = 0, i = 0, bounces = 0
+      PushDouble 100
+      SetLocal 3
+      PushByte 0
+      ConvertD
+      SetLocal 4
+      PushByte 0
+      SetLocal 5
+      PushByte 0
+      SetLocal 6
+      Jump check
+
+    step:
+      Label
+      // vy = vy - 9.8 * dt
+      GetLocal 4
+      PushDouble 9.8
+      GetLocal 2
+      Multiply
+      Subtract
+      ConvertD
+      SetLocal 4
+      // y = y + vy * dt
+      GetLocal 3
+      GetLocal 4
+      GetLocal 2
+      Multiply
+      Add
+      ConvertD
+      SetLocal 3
+      // if (y < 0) { y = -y; vy = -vy * 0.5; bounces++; }
+      GetLocal 3
+      PushByte 0
+      IfNlt next
+      GetLocal 3
+      Negate
+      ConvertD
+      SetLocal 3
+      GetLocal 4
+      Negate
+      PushDouble 0.5
+      Multiply
+      ConvertD
+      SetLocal 4
+      IncLocalI 6
+
+    next:
+      IncLocalI 5
+    check:
+      GetLocal 5
+      GetLocal 1
+      IfLt step
+
+      GetLocal 3
+      ReturnValue
+    }
+  }
+}
+symbol 0, "Main"
+show
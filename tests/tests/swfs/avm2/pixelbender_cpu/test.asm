@@ -0,0 +1,250 @@
+// Shaders run by Ruffle's CPU Pixel Bender interpreter, which is used when there is no
+// renderer that can compile them: a `ShaderFilter` applied with
+// `BitmapData.applyFilter`, and a `ShaderJob` writing to a `BitmapData`.
+//
+// The shader is assembled from `shader.pbasm` with the `pixel_bender` crate's assembler,
+// and swaps the red and blue channels of its input.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "shaderBytes"
+    slot "tracePixels"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "shaderBytes"
+      NewFunction shader_bytes
+      InitProperty "shaderBytes"
+      FindProperty "tracePixels"
+      NewFunction trace_pixels
+      InitProperty "tracePixels"
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  // The bytecode of `shader.pbasm`.
+  method shader_bytes {
+    code {
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 1
+      PushShort 165
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushShort 164
+      PushByte 4
+      PushByte 0
+      PushByte 83
+      PushByte 119
+      PushByte 97
+      PushByte 112
+      PushShort 161
+      PushByte 1
+      PushByte 2
+      PushByte 0
+      PushByte 0
+      PushByte 12
+      PushByte 95
+      PushByte 79
+      PushByte 117
+      PushByte 116
+      PushByte 67
+      PushByte 111
+      PushByte 111
+      PushByte 114
+      PushByte 100
+      PushByte 0
+      PushShort 163
+      PushByte 0
+      PushByte 4
+      PushByte 115
+      PushByte 114
+      PushByte 99
+      PushByte 0
+      PushShort 161
+      PushByte 2
+      PushByte 4
+      PushByte 1
+      PushByte 0
+      PushByte 15
+      PushByte 100
+      PushByte 115
+      PushByte 116
+      PushByte 0
+      PushByte 48
+      PushByte 2
+      PushByte 0
+      PushShort 240
+      PushByte 0
+      PushByte 0
+      PushByte 16
+      PushByte 0
+      PushByte 29
+      PushByte 1
+      PushByte 0
+      PushShort 243
+      PushByte 2
+      PushByte 0
+      PushShort 147
+      PushByte 0
+      NewArray 61
+      SetLocal 2
+      PushByte 0
+      SetLocal 3
+      Jump check
+    loop:
+      Label
+      GetLocal 1
+      GetLocal 2
+      GetLocal 3
+      GetProperty "[]::<runtime>"
+      CallPropVoid "writeByte", 1
+      IncLocalI 3
+    check:
+      GetLocal 3
+      GetLocal 2
+      GetProperty "length"
+      IfLt loop
+      GetLocal 1
+      ReturnValue
+    }
+  }
+
+  // Traces both pixels of a 2x1 `BitmapData` in hexadecimal.
+  method trace_pixels {
+    param "String"
+    param "flash.display::BitmapData"
+    code {
+      FindPropStrict "trace"
+      GetLocal 1
+      PushString ": "
+      Add
+      GetLocal 2
+      PushByte 0
+      PushByte 0
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      Add
+      PushString " "
+      Add
+      GetLocal 2
+      PushByte 1
+      PushByte 0
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+
+      // The source, with a dark grey-blue pixel and a blue pixel.
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 2
+      PushByte 1
+      PushTrue
+      PushByte 0
+      ConstructProp "flash.display::BitmapData", 4
+      SetLocal 1
+      GetLocal 1
+      PushByte 0
+      PushByte 0
+      PushUint 0xFF112233
+      CallPropVoid "setPixel32", 3
+      GetLocal 1
+      PushByte 1
+      PushByte 0
+      PushUint 0xFF0000FF
+      CallPropVoid "setPixel32", 3
+
+      // A `ShaderFilter`, applied to the source.
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 2
+      PushByte 1
+      PushTrue
+      PushByte 0
+      ConstructProp "flash.display::BitmapData", 4
+      SetLocal 2
+      GetLocal 2
+      GetLocal 1
+      GetLocal 1
+      GetProperty "rect"
+      FindPropStrict "flash.geom::Point"
+      ConstructProp "flash.geom::Point", 0
+      FindPropStrict "flash.filters::ShaderFilter"
+      FindPropStrict "flash.display::Shader"
+      FindPropStrict "shaderBytes"
+      CallProperty "shaderBytes", 0
+      ConstructProp "flash.display::Shader", 1
+      ConstructProp "flash.filters::ShaderFilter", 1
+      CallPropVoid "applyFilter", 4
+      FindPropStrict "tracePixels"
+      PushString "ShaderFilter"
+      GetLocal 2
+      CallPropVoid "tracePixels", 2
+
+      // A `ShaderJob` with the source as its input and a `BitmapData` as its target.
+      FindPropStrict "flash.display::Shader"
+      FindPropStrict "shaderBytes"
+      CallProperty "shaderBytes", 0
+      ConstructProp "flash.display::Shader", 1
+      SetLocal 3
+      GetLocal 3
+      GetProperty "data"
+      GetProperty "src"
+      GetLocal 1
+      SetProperty "input"
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 2
+      PushByte 1
+      PushTrue
+      PushByte 0
+      ConstructProp "flash.display::BitmapData", 4
+      SetLocal 4
+      FindPropStrict "flash.display::ShaderJob"
+      GetLocal 3
+      GetLocal 4
+      ConstructProp "flash.display::ShaderJob", 2
+      PushTrue
+      CallPropVoid "start", 1
+      FindPropStrict "tracePixels"
+      PushString "ShaderJob"
+      GetLocal 4
+      CallPropVoid "tracePixels", 2
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
@@ -0,0 +1,290 @@
+// A blend shader, drawn into a `BitmapData`. The blend shader takes the red channel of
+// the background and the green channel of the foreground.
+//
+// Drawing needs a renderer, so unlike `pixelbender_cpu` this runs on a real one. The
+// software renderer (used with the `imgtests_software` feature) runs blend shaders
+// on Ruffle's CPU Pixel Bender interpreter.
+//
+// The shader is assembled from `shader.pbasm` with the `pixel_bender` crate's assembler.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "shaderBytes"
+    slot "addRect"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "shaderBytes"
+      NewFunction shader_bytes
+      InitProperty "shaderBytes"
+      FindProperty "addRect"
+      NewFunction add_rect
+      InitProperty "addRect"
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  // The bytecode of `shader.pbasm`.
+  method shader_bytes {
+    code {
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 1
+      PushShort 165
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushShort 164
+      PushByte 3
+      PushByte 0
+      PushByte 77
+      PushByte 105
+      PushByte 120
+      PushShort 161
+      PushByte 1
+      PushByte 2
+      PushByte 0
+      PushByte 0
+      PushByte 12
+      PushByte 95
+      PushByte 79
+      PushByte 117
+      PushByte 116
+      PushByte 67
+      PushByte 111
+      PushByte 111
+      PushByte 114
+      PushByte 100
+      PushByte 0
+      PushShort 163
+      PushByte 0
+      PushByte 4
+      PushByte 98
+      PushByte 97
+      PushByte 99
+      PushByte 107
+      PushByte 103
+      PushByte 114
+      PushByte 111
+      PushByte 117
+      PushByte 110
+      PushByte 100
+      PushByte 0
+      PushShort 163
+      PushByte 1
+      PushByte 4
+      PushByte 102
+      PushByte 111
+      PushByte 114
+      PushByte 101
+      PushByte 103
+      PushByte 114
+      PushByte 111
+      PushByte 117
+      PushByte 110
+      PushByte 100
+      PushByte 0
+      PushShort 161
+      PushByte 2
+      PushByte 4
+      PushByte 1
+      PushByte 0
+      PushByte 15
+      PushByte 100
+      PushByte 115
+      PushByte 116
+      PushByte 0
+      PushByte 48
+      PushByte 2
+      PushByte 0
+      PushShort 240
+      PushByte 0
+      PushByte 0
+      PushByte 16
+      PushByte 0
+      PushByte 48
+      PushByte 3
+      PushByte 0
+      PushShort 240
+      PushByte 0
+      PushByte 0
+      PushByte 16
+      PushByte 1
+      PushByte 29
+      PushByte 1
+      PushByte 0
+      PushShort 128
+      PushByte 2
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 29
+      PushByte 1
+      PushByte 0
+      PushByte 64
+      PushByte 3
+      PushByte 0
+      PushByte 64
+      PushByte 0
+      PushByte 50
+      PushByte 1
+      PushByte 0
+      PushByte 32
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushByte 50
+      PushByte 1
+      PushByte 0
+      PushByte 16
+      PushByte 63
+      PushShort 128
+      PushByte 0
+      PushByte 0
+      NewArray 113
+      SetLocal 2
+      PushByte 0
+      SetLocal 3
+      Jump check
+    loop:
+      Label
+      GetLocal 1
+      GetLocal 2
+      GetLocal 3
+      GetProperty "[]::<runtime>"
+      CallPropVoid "writeByte", 1
+      IncLocalI 3
+    check:
+      GetLocal 3
+      GetLocal 2
+      GetProperty "length"
+      IfLt loop
+      GetLocal 1
+      ReturnValue
+    }
+  }
+
+  // Adds a shape with a rectangle of the given color and width to the given parent,
+  // returning the shape.
+  method add_rect {
+    param "flash.display::DisplayObjectContainer"
+    param "Number"
+    param "uint"
+    param "Number"
+    code {
+      FindPropStrict "flash.display::Shape"
+      ConstructProp "flash.display::Shape", 0
+      SetLocal 5
+      GetLocal 5
+      GetProperty "graphics"
+      SetLocal 6
+      GetLocal 6
+      GetLocal 3
+      CallPropVoid "beginFill", 1
+      GetLocal 6
+      GetLocal 2
+      PushByte 0
+      GetLocal 4
+      PushByte 1
+      CallPropVoid "drawRect", 4
+      GetLocal 6
+      CallPropVoid "endFill", 0
+      GetLocal 1
+      GetLocal 5
+      CallPropVoid "addChild", 1
+      GetLocal 5
+      ReturnValue
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      FindPropStrict "flash.display::Sprite"
+      ConstructProp "flash.display::Sprite", 0
+      SetLocal 1
+
+      // A red pixel and a blue pixel in the background, and a green foreground.
+      FindPropStrict "addRect"
+      GetLocal 1
+      PushByte 0
+      PushUint 0xFF0000
+      PushByte 1
+      CallPropVoid "addRect", 4
+      FindPropStrict "addRect"
+      GetLocal 1
+      PushByte 1
+      PushUint 0x0000FF
+      PushByte 1
+      CallPropVoid "addRect", 4
+      FindPropStrict "addRect"
+      GetLocal 1
+      PushByte 0
+      PushUint 0x00FF00
+      PushByte 2
+      CallProperty "addRect", 4
+      FindPropStrict "flash.display::Shader"
+      FindPropStrict "shaderBytes"
+      CallProperty "shaderBytes", 0
+      ConstructProp "flash.display::Shader", 1
+      SetProperty "blendShader"
+
+      FindPropStrict "flash.display::BitmapData"
+      PushByte 2
+      PushByte 1
+      PushTrue
+      PushByte 0
+      ConstructProp "flash.display::BitmapData", 4
+      SetLocal 2
+      GetLocal 2
+      GetLocal 1
+      CallPropVoid "draw", 1
+
+      FindPropStrict "trace"
+      GetLocal 2
+      PushByte 0
+      PushByte 0
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      PushString " "
+      Add
+      GetLocal 2
+      PushByte 1
+      PushByte 0
+      CallProperty "getPixel32", 2
+      PushByte 16
+      CallProperty "toString", 1
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
@@ -0,0 +1,236 @@
+// Property accesses on untyped receivers, which go through inline caches.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Prox", prox
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      GetScopeObject 0
+      GetLex "flash.utils::Proxy"
+      NewClass prox
+      InitProperty "Prox"
+      NewFunction read
+      SetLocal 1
+      NewFunction write
+      SetLocal 2
+      NewFunction call
+      SetLocal 3
+      PushString "x"
+      PushByte 1
+      NewObject 1
+      SetLocal 4
+      FindPropStrict "flash.geom::Point"
+      PushByte 3
+      PushByte 4
+      ConstructProp "flash.geom::Point", 2
+      SetLocal 5
+      FindPropStrict "Prox"
+      ConstructProp "Prox", 0
+      SetLocal 6
+
+      // A dynamic property, which is then changed and deleted.
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 4
+      Call 1
+      CallPropVoid "trace", 1
+      GetLocal 4
+      PushByte 2
+      SetProperty "x"
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 4
+      Call 1
+      CallPropVoid "trace", 1
+      GetLocal 4
+      DeleteProperty "x"
+      Pop
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 4
+      Call 1
+      CallPropVoid "trace", 1
+
+      // A getter, and a Proxy.
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 5
+      Call 1
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 6
+      Call 1
+      CallPropVoid "trace", 1
+
+      // More receivers than the cache has room for.
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      NewArray 0
+      Dup
+      PushByte 5
+      SetProperty "x"
+      Call 4
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      FindPropStrict "flash.geom::Rectangle"
+      PushByte 6
+      PushByte 0
+      PushByte 1
+      PushByte 1
+      ConstructProp "flash.geom::Rectangle", 4
+      Call 6
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 5
+      Call 1
+      CallPropVoid "trace", 1
+      GetLocal 4
+      PushByte 7
+      SetProperty "x"
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 4
+      Call 1
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 6
+      Call 1
+      CallPropVoid "trace", 1
+
+      // Setting through a setter and a dynamic property.
+      GetLocal 2
+      PushNull
+      GetLocal 5
+      PushByte 8
+      Call 2
+      Pop
+      GetLocal 2
+      PushNull
+      GetLocal 4
+      PushByte 9
+      Call 2
+      Pop
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 5
+      Call 1
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 1
+      PushNull
+      GetLocal 4
+      Call 1
+      CallPropVoid "trace", 1
+
+      // Calling a method, and a function found on the prototype.
+      FindPropStrict "trace"
+      GetLocal 3
+      PushNull
+      GetLocal 5
+      Call 1
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 3
+      PushNull
+      PushByte 1
+      PushByte 2
+      NewArray 2
+      Call 3
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 3
+      PushNull
+      GetLocal 4
+      Call 1
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 3
+      PushNull
+      GetLocal 5
+      Call 1
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  // function read(o) { return o.x; }
+  method read {
+    param "*"
+    code {
+      GetLocal 1
+      GetProperty "x"
+      ReturnValue
+    }
+  }
+
+  // function write(o, v) { o.x = v; }
+  method write {
+    param "*"
+    param "*"
+    code {
+      GetLocal 1
+      GetLocal 2
+      SetProperty "x"
+      ReturnVoid
+    }
+  }
+
+  // function call(o) { return o.toString(); }
+  method call {
+    param "*"
+    code {
+      GetLocal 1
+      CallProperty "toString", 0
+      ReturnValue
+    }
+  }
+
+  class prox, "Prox", extends="flash.utils::Proxy", init=prox_init, class_init=prox_cinit {
+    method "namespace http://www.adobe.com/2006/actionscript/flash/proxy::getProperty", prox_get, override
+  }
+  method prox_init {
+    code {
+      GetLocal 0
+      ConstructSuper 0
+      ReturnVoid
+    }
+  }
+  method prox_cinit {
+    code {
+      ReturnVoid
+    }
+  }
+  method prox_get {
+    param "*"
+    code {
+      PushString "proxied "
+      GetLocal 1
+      Add
+      ReturnValue
+    }
+  }
+}
+show
@@ -0,0 +1,418 @@
+// `ShaderJob.start(false)` completes on a later frame, dispatching `complete` with its
+// target, and `progress` grows in between. Ruffle runs jobs on its CPU interpreter
+// 65536 pixels per frame, so the 256x512 job here takes two frames. A cancelled job
+// leaves its target untouched and never completes, and `start(true)` completes before
+// returning.
+//
+// The shader is assembled from `shader.pbasm` with the `pixel_bender` crate's assembler.
+// It outputs the coordinates of each pixel along with the blue and alpha channels of its
+// `color` parameter.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "shaderBytes"
+    slot "makeShader"
+    slot "readPixel"
+    slot "job1"
+    slot "job2"
+    slot "target1"
+    slot "target2"
+    slot "lastProgress"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "shaderBytes"
+      NewFunction shader_bytes
+      InitProperty "shaderBytes"
+      FindProperty "makeShader"
+      NewFunction make_shader
+      InitProperty "makeShader"
+      FindProperty "readPixel"
+      NewFunction read_pixel
+      InitProperty "readPixel"
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  // The bytecode of `shader.pbasm`.
+  method shader_bytes {
+    code {
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 1
+      PushShort 165
+      PushByte 1
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      PushShort 164
+      PushByte 6
+      PushByte 0
+      PushByte 67
+      PushByte 111
+      PushByte 111
+      PushByte 114
+      PushByte 100
+      PushByte 115
+      PushShort 161
+      PushByte 1
+      PushByte 2
+      PushByte 0
+      PushByte 0
+      PushByte 12
+      PushByte 95
+      PushByte 79
+      PushByte 117
+      PushByte 116
+      PushByte 67
+      PushByte 111
+      PushByte 111
+      PushByte 114
+      PushByte 100
+      PushByte 0
+      PushShort 161
+      PushByte 1
+      PushByte 4
+      PushByte 1
+      PushByte 0
+      PushByte 15
+      PushByte 99
+      PushByte 111
+      PushByte 108
+      PushByte 111
+      PushByte 114
+      PushByte 0
+      PushShort 161
+      PushByte 2
+      PushByte 4
+      PushByte 2
+      PushByte 0
+      PushByte 15
+      PushByte 100
+      PushByte 115
+      PushByte 116
+      PushByte 0
+      PushByte 29
+      PushByte 2
+      PushByte 0
+      PushShort 193
+      PushByte 0
+      PushByte 0
+      PushByte 16
+      PushByte 0
+      PushByte 29
+      PushByte 2
+      PushByte 0
+      PushByte 49
+      PushByte 1
+      PushByte 0
+      PushShort 176
+      PushByte 0
+      NewArray 68
+      SetLocal 2
+      PushByte 0
+      SetLocal 3
+      Jump check
+    loop:
+      Label
+      GetLocal 1
+      GetLocal 2
+      GetLocal 3
+      GetProperty "[]::<runtime>"
+      CallPropVoid "writeByte", 1
+      IncLocalI 3
+    check:
+      GetLocal 3
+      GetLocal 2
+      GetProperty "length"
+      IfLt loop
+      GetLocal 1
+      ReturnValue
+    }
+  }
+
+  method make_shader {
+    code {
+      FindPropStrict "flash.display::Shader"
+      FindPropStrict "shaderBytes"
+      CallProperty "shaderBytes", 0
+      ConstructProp "flash.display::Shader", 1
+      SetLocal 1
+      GetLocal 1
+      GetProperty "data"
+      GetProperty "color"
+      PushByte 0
+      PushByte 0
+      PushDouble 0.25
+      PushByte 1
+      NewArray 4
+      SetProperty "value"
+      GetLocal 1
+      ReturnValue
+    }
+  }
+
+  // The four channels of the pixel at the given position of a shader's output.
+  method read_pixel {
+    param "flash.utils::ByteArray"
+    param "int"
+    code {
+      GetLocal 1
+      PushString "littleEndian"
+      SetProperty "endian"
+      GetLocal 1
+      GetLocal 2
+      SetProperty "position"
+      GetLocal 1
+      CallProperty "readFloat", 0
+      PushString " "
+      Add
+      GetLocal 1
+      CallProperty "readFloat", 0
+      Add
+      PushString " "
+      Add
+      GetLocal 1
+      CallProperty "readFloat", 0
+      Add
+      PushString " "
+      Add
+      GetLocal 1
+      CallProperty "readFloat", 0
+      Add
+      ReturnValue
+    }
+  }
+
+  // Traces the progress of the first job whenever it changes before completing.
+  method on_frame {
+    param "flash.events::Event"
+    code {
+      GetLex "job1"
+      GetProperty "progress"
+      SetLocal 2
+      GetLocal 2
+      GetLex "lastProgress"
+      IfEq done
+      GetLocal 2
+      PushByte 1
+      IfGe done
+      FindPropStrict "trace"
+      PushString "job1 progress: "
+      GetLocal 2
+      Add
+      CallPropVoid "trace", 1
+      FindProperty "lastProgress"
+      GetLocal 2
+      SetProperty "lastProgress"
+    done:
+      ReturnVoid
+    }
+  }
+
+  method on_complete {
+    param "flash.events::Event"
+    code {
+      FindPropStrict "trace"
+      PushString "job1 complete, progress: "
+      GetLex "job1"
+      GetProperty "progress"
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "event target is job1: "
+      GetLocal 1
+      GetProperty "target"
+      GetLex "job1"
+      StrictEquals
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "event byteArray is target1: "
+      GetLocal 1
+      GetProperty "byteArray"
+      GetLex "target1"
+      StrictEquals
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "job1 target length: "
+      GetLex "target1"
+      GetProperty "length"
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "job1 first pixel: "
+      FindPropStrict "readPixel"
+      GetLex "target1"
+      PushByte 0
+      CallProperty "readPixel", 2
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "job1 last pixel: "
+      FindPropStrict "readPixel"
+      GetLex "target1"
+      GetLex "target1"
+      GetProperty "length"
+      PushByte 16
+      Subtract
+      CallProperty "readPixel", 2
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "job2 target length: "
+      GetLex "target2"
+      GetProperty "length"
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  method on_cancelled_complete {
+    param "flash.events::Event"
+    code {
+      FindPropStrict "trace"
+      PushString "job2 complete"
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      FindProperty "lastProgress"
+      PushByte 0
+      SetProperty "lastProgress"
+
+      // An asynchronous job.
+      FindProperty "target1"
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetProperty "target1"
+      FindProperty "job1"
+      FindPropStrict "flash.display::ShaderJob"
+      FindPropStrict "makeShader"
+      CallProperty "makeShader", 0
+      GetLex "target1"
+      PushShort 256
+      PushShort 512
+      ConstructProp "flash.display::ShaderJob", 4
+      SetProperty "job1"
+      GetLex "job1"
+      PushString "complete"
+      NewFunction on_complete
+      CallPropVoid "addEventListener", 2
+      GetLex "job1"
+      PushFalse
+      CallPropVoid "start", 1
+      FindPropStrict "trace"
+      PushString "job1 progress after start: "
+      GetLex "job1"
+      GetProperty "progress"
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "job1 target length after start: "
+      GetLex "target1"
+      GetProperty "length"
+      Add
+      CallPropVoid "trace", 1
+
+      // A cancelled job.
+      FindProperty "target2"
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetProperty "target2"
+      FindProperty "job2"
+      FindPropStrict "flash.display::ShaderJob"
+      FindPropStrict "makeShader"
+      CallProperty "makeShader", 0
+      GetLex "target2"
+      PushByte 4
+      PushByte 4
+      ConstructProp "flash.display::ShaderJob", 4
+      SetProperty "job2"
+      GetLex "job2"
+      PushString "complete"
+      NewFunction on_cancelled_complete
+      CallPropVoid "addEventListener", 2
+      GetLex "job2"
+      PushFalse
+      CallPropVoid "start", 1
+      GetLex "job2"
+      CallPropVoid "cancel", 0
+
+      // A synchronous job.
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 1
+      FindPropStrict "flash.display::ShaderJob"
+      FindPropStrict "makeShader"
+      CallProperty "makeShader", 0
+      GetLocal 1
+      PushByte 2
+      PushByte 2
+      ConstructProp "flash.display::ShaderJob", 4
+      SetLocal 2
+      GetLocal 2
+      PushTrue
+      CallPropVoid "start", 1
+      FindPropStrict "trace"
+      PushString "job3 progress: "
+      GetLocal 2
+      GetProperty "progress"
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "job3 first pixel: "
+      FindPropStrict "readPixel"
+      GetLocal 1
+      PushByte 0
+      CallProperty "readPixel", 2
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "job3 last pixel: "
+      FindPropStrict "readPixel"
+      GetLocal 1
+      PushByte 48
+      CallProperty "readPixel", 2
+      Add
+      CallPropVoid "trace", 1
+
+      GetLocal 0
+      PushString "enterFrame"
+      NewFunction on_frame
+      CallPropVoid "addEventListener", 2
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
@@ -0,0 +1,203 @@
+// Using resources and contexts after `dispose()` throws Error #3694, and
+// `Context3D.dispose(true)` dispatches `context3DCreate` again later, while
+// `dispose(false)` doesn't.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 11
+size 64, 64
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "stage3d"
+    slot "first"
+    slot "count", "int"
+    slot "frames", "int"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      FindProperty "stage3d"
+      GetLocal 0
+      GetProperty "stage"
+      GetProperty "stage3Ds"
+      PushByte 0
+      GetProperty "[]::<runtime>"
+      SetProperty "stage3d"
+      GetLex "stage3d"
+      PushString "context3DCreate"
+      NewFunction on_create
+      CallPropVoid "addEventListener", 2
+      GetLocal 0
+      PushString "enterFrame"
+      NewFunction on_frame
+      CallPropVoid "addEventListener", 2
+      GetLex "stage3d"
+      CallPropVoid "requestContext3D", 0
+      ReturnVoid
+    }
+  }
+
+  method on_create {
+    param "flash.events::Event"
+    catch upload_start, upload_end, upload_handler, type="Error"
+    catch clear_start, clear_end, clear_handler, type="Error"
+    code {
+      FindProperty "count"
+      GetLex "count"
+      IncrementI
+      SetProperty "count"
+      FindPropStrict "trace"
+      PushString "context3DCreate "
+      GetLex "count"
+      Add
+      CallPropVoid "trace", 1
+      GetLex "stage3d"
+      GetProperty "context3D"
+      SetLocal 2
+      GetLex "count"
+      PushByte 1
+      IfNe second
+
+      // A disposed buffer can't be used anymore.
+      FindProperty "first"
+      GetLocal 2
+      SetProperty "first"
+      GetLocal 2
+      PushByte 3
+      PushByte 1
+      CallProperty "createVertexBuffer", 2
+      SetLocal 3
+      GetLocal 3
+      CallPropVoid "dispose", 0
+      FindPropStrict "trace"
+      PushString "buffer disposed"
+      CallPropVoid "trace", 1
+    upload_start:
+      GetLocal 3
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      PushByte 0
+      PushByte 0
+      PushByte 1
+      CallPropVoid "uploadFromByteArray", 4
+    upload_end:
+      FindPropStrict "trace"
+      PushString "no error"
+      CallPropVoid "trace", 1
+      Jump recreate
+    upload_handler:
+      SetLocal 4
+      FindPropStrict "trace"
+      GetLocal 4
+      GetProperty "errorID"
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 4
+      GetProperty "message"
+      CallPropVoid "trace", 1
+    recreate:
+      GetLocal 2
+      PushTrue
+      CallPropVoid "dispose", 1
+      // The new context is only created after this handler has returned.
+      FindPropStrict "trace"
+      PushString "dispose(true) returned"
+      CallPropVoid "trace", 1
+      ReturnVoid
+
+      // The context given after `dispose(true)` is a new one, and the old one can't be used.
+    second:
+      FindPropStrict "trace"
+      PushString "new context: "
+      GetLocal 2
+      GetLex "first"
+      StrictEquals
+      Not
+      Add
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      PushString "old driverInfo: "
+      GetLex "first"
+      GetProperty "driverInfo"
+      Add
+      CallPropVoid "trace", 1
+    clear_start:
+      GetLex "first"
+      PushByte 0
+      PushByte 0
+      PushByte 0
+      CallPropVoid "clear", 3
+    clear_end:
+      FindPropStrict "trace"
+      PushString "no error"
+      CallPropVoid "trace", 1
+      Jump keep_disposed
+    clear_handler:
+      SetLocal 4
+      FindPropStrict "trace"
+      GetLocal 4
+      GetProperty "errorID"
+      CallPropVoid "trace", 1
+    keep_disposed:
+      GetLocal 2
+      PushFalse
+      CallPropVoid "dispose", 1
+      FindPropStrict "trace"
+      PushString "context3D after dispose(false): "
+      GetLex "stage3d"
+      GetProperty "context3D"
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  // No more contexts are created after `dispose(false)`.
+  method on_frame {
+    param "flash.events::Event"
+    code {
+      FindProperty "frames"
+      GetLex "frames"
+      IncrementI
+      SetProperty "frames"
+      GetLex "frames"
+      PushByte 3
+      IfNe done
+      FindPropStrict "trace"
+      PushString "context3DCreate events: "
+      GetLex "count"
+      Add
+      CallPropVoid "trace", 1
+    done:
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
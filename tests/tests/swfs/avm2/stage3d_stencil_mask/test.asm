@@ -0,0 +1,451 @@
+// The stencil buffer masks out what is drawn with `setStencilActions`:
+// a red quad marks the middle of the stencil buffer, and a blue quad drawn
+// with a `notEqual` comparison should only cover the area around it.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 11
+size 64, 64
+file_attributes IS_ACTION_SCRIPT_3
+background 0xFFFFFF
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "stage3d"
+    slot "agalHeader"
+    slot "agalMov"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "agalHeader"
+      NewFunction agal_header
+      SetProperty "agalHeader"
+      FindProperty "agalMov"
+      NewFunction agal_mov
+      SetProperty "agalMov"
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      FindProperty "stage3d"
+      GetLocal 0
+      GetProperty "stage"
+      GetProperty "stage3Ds"
+      PushByte 0
+      GetProperty "[]::<runtime>"
+      SetProperty "stage3d"
+      GetLex "stage3d"
+      PushString "context3DCreate"
+      NewFunction on_create
+      CallPropVoid "addEventListener", 2
+      GetLex "stage3d"
+      CallPropVoid "requestContext3D", 0
+      ReturnVoid
+    }
+  }
+
+  // Starts an AGAL program of the given type (0 for vertex, 1 for fragment).
+  method agal_header, returns="flash.utils::ByteArray" {
+    param "int"
+    code {
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 2
+      GetLocal 2
+      PushString "littleEndian"
+      SetProperty "endian"
+      GetLocal 2
+      PushShort 160
+      CallPropVoid "writeByte", 1
+      GetLocal 2
+      PushByte 1
+      CallPropVoid "writeInt", 1
+      GetLocal 2
+      PushShort 161
+      CallPropVoid "writeByte", 1
+      GetLocal 2
+      GetLocal 1
+      CallPropVoid "writeByte", 1
+      GetLocal 2
+      ReturnValue
+    }
+  }
+
+  // Writes `mov dest, source` with every component, given the number and type of both registers.
+  method agal_mov {
+    param "flash.utils::ByteArray"
+    param "int"
+    param "int"
+    param "int"
+    param "int"
+    code {
+      GetLocal 1
+      PushByte 0
+      CallPropVoid "writeInt", 1
+      GetLocal 1
+      GetLocal 2
+      CallPropVoid "writeShort", 1
+      GetLocal 1
+      PushByte 15
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      GetLocal 3
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      GetLocal 4
+      CallPropVoid "writeShort", 1
+      GetLocal 1
+      PushByte 0
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      PushShort 228
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      GetLocal 5
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      PushByte 0
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      PushByte 0
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      PushByte 0
+      CallPropVoid "writeByte", 1
+      GetLocal 1
+      PushByte 0
+      CallPropVoid "writeInt", 1
+      GetLocal 1
+      PushByte 0
+      CallPropVoid "writeInt", 1
+      ReturnVoid
+    }
+  }
+
+  method on_create {
+    param "flash.events::Event"
+    code {
+      GetLex "stage3d"
+      GetProperty "context3D"
+      SetLocal 2
+      GetLocal 2
+      PushByte 64
+      PushByte 64
+      PushByte 0
+      PushTrue
+      CallPropVoid "configureBackBuffer", 4
+
+      // Each vertex is a position followed by a color.
+      GetLocal 2
+      PushByte 8
+      PushByte 5
+      CallProperty "createVertexBuffer", 2
+      SetLocal 3
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 4
+      GetLocal 4
+      PushString "littleEndian"
+      SetProperty "endian"
+      GetLocal 4
+      PushDouble -1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble -1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble -1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble -1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble -0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble -0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble -0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble -0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.5
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 1.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 4
+      PushDouble 0.0
+      CallPropVoid "writeFloat", 1
+      GetLocal 3
+      GetLocal 4
+      PushByte 0
+      PushByte 0
+      PushByte 8
+      CallPropVoid "uploadFromByteArray", 4
+
+      GetLocal 2
+      PushByte 12
+      CallProperty "createIndexBuffer", 1
+      SetLocal 5
+      FindPropStrict "flash.utils::ByteArray"
+      ConstructProp "flash.utils::ByteArray", 0
+      SetLocal 4
+      GetLocal 4
+      PushString "littleEndian"
+      SetProperty "endian"
+      GetLocal 4
+      PushByte 0
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 1
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 2
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 0
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 2
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 3
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 4
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 5
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 6
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 4
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 6
+      CallPropVoid "writeShort", 1
+      GetLocal 4
+      PushByte 7
+      CallPropVoid "writeShort", 1
+      GetLocal 5
+      GetLocal 4
+      PushByte 0
+      PushByte 0
+      PushByte 12
+      CallPropVoid "uploadFromByteArray", 4
+
+      // mov op, va0; mov v0, va1
+      FindPropStrict "agalHeader"
+      PushByte 0
+      CallProperty "agalHeader", 1
+      SetLocal 6
+      FindPropStrict "agalMov"
+      GetLocal 6
+      PushByte 0
+      PushByte 3
+      PushByte 0
+      PushByte 0
+      CallPropVoid "agalMov", 5
+      FindPropStrict "agalMov"
+      GetLocal 6
+      PushByte 0
+      PushByte 4
+      PushByte 1
+      PushByte 0
+      CallPropVoid "agalMov", 5
+      // mov oc, v0
+      FindPropStrict "agalHeader"
+      PushByte 1
+      CallProperty "agalHeader", 1
+      SetLocal 7
+      FindPropStrict "agalMov"
+      GetLocal 7
+      PushByte 0
+      PushByte 3
+      PushByte 0
+      PushByte 4
+      CallPropVoid "agalMov", 5
+      GetLocal 2
+      CallProperty "createProgram", 0
+      SetLocal 8
+      GetLocal 8
+      GetLocal 6
+      GetLocal 7
+      CallPropVoid "upload", 2
+
+      GetLocal 2
+      GetLocal 8
+      CallPropVoid "setProgram", 1
+      GetLocal 2
+      PushByte 0
+      GetLocal 3
+      PushByte 0
+      PushString "float2"
+      CallPropVoid "setVertexBufferAt", 4
+      GetLocal 2
+      PushByte 1
+      GetLocal 3
+      PushByte 2
+      PushString "float3"
+      CallPropVoid "setVertexBufferAt", 4
+      GetLocal 2
+      PushFalse
+      PushString "always"
+      CallPropVoid "setDepthTest", 2
+      GetLocal 2
+      PushDouble 0.2
+      PushDouble 0.2
+      PushDouble 0.2
+      PushByte 1
+      PushByte 1
+      PushByte 0
+      CallPropVoid "clear", 6
+
+      // The red quad sets the stencil value of the pixels it covers to 1...
+      GetLocal 2
+      PushByte 1
+      CallPropVoid "setStencilReferenceValue", 1
+      GetLocal 2
+      PushString "frontAndBack"
+      PushString "always"
+      PushString "set"
+      CallPropVoid "setStencilActions", 3
+      GetLocal 2
+      GetLocal 5
+      PushByte 6
+      PushByte 2
+      CallPropVoid "drawTriangles", 3
+
+      // ...so that the blue quad is only drawn everywhere else.
+      GetLocal 2
+      PushString "frontAndBack"
+      PushString "notEqual"
+      PushString "keep"
+      CallPropVoid "setStencilActions", 3
+      GetLocal 2
+      GetLocal 5
+      PushByte 0
+      PushByte 2
+      CallPropVoid "drawTriangles", 3
+      GetLocal 2
+      CallPropVoid "present", 0
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
@@ -0,0 +1,165 @@
+// `System.gc()` collects everything unreachable before the next frame, which
+// removes the entries of a `Dictionary` with weak keys whose keys were
+// collected, but not those of one with strong keys.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main {
+    slot "weak"
+    slot "strong"
+    slot "kept"
+    slot "sprite"
+    slot "count"
+    slot "frames", "int"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "count"
+      NewFunction count
+      InitProperty "count"
+      FindProperty "weak"
+      FindPropStrict "flash.utils::Dictionary"
+      PushTrue
+      ConstructProp "flash.utils::Dictionary", 1
+      InitProperty "weak"
+      FindProperty "strong"
+      FindPropStrict "flash.utils::Dictionary"
+      ConstructProp "flash.utils::Dictionary", 0
+      InitProperty "strong"
+      FindProperty "kept"
+      NewObject 0
+      InitProperty "kept"
+
+      // A key which stays reachable, and a string key, in both dictionaries.
+      GetLex "weak"
+      GetLex "kept"
+      PushString "kept"
+      SetProperty "[]::<runtime>"
+      GetLex "weak"
+      PushString "text"
+      SetProperty "name"
+      GetLex "strong"
+      GetLex "kept"
+      PushString "kept"
+      SetProperty "[]::<runtime>"
+      GetLex "strong"
+      PushString "text"
+      SetProperty "name"
+
+      // Keys which become unreachable once this script returns.
+      PushByte 0
+      SetLocal 1
+      Jump check
+    loop:
+      Label
+      GetLex "weak"
+      NewObject 0
+      GetLocal 1
+      SetProperty "[]::<runtime>"
+      GetLex "strong"
+      NewObject 0
+      GetLocal 1
+      SetProperty "[]::<runtime>"
+      IncLocalI 1
+    check:
+      GetLocal 1
+      PushByte 10
+      IfLt loop
+
+      FindProperty "sprite"
+      FindPropStrict "flash.display::Sprite"
+      ConstructProp "flash.display::Sprite", 0
+      InitProperty "sprite"
+      GetLex "sprite"
+      PushString "enterFrame"
+      NewFunction on_frame
+      CallPropVoid "addEventListener", 2
+      ReturnVoid
+    }
+  }
+
+  // function count(o) { var n = 0; for (var key in o) n++; return n; }
+  method count, returns="int" {
+    param "*"
+    code {
+      PushByte 0
+      SetLocal 2
+      GetLocal 1
+      SetLocal 3
+      PushByte 0
+      SetLocal 4
+      Jump check
+    loop:
+      Label
+      GetLocal 3
+      GetLocal 4
+      NextName
+      Pop
+      IncLocalI 2
+    check:
+      HasNext2 3, 4
+      IfTrue loop
+      GetLocal 2
+      ReturnValue
+    }
+  }
+
+  // Collects on the first frame, and checks what's left on the next one.
+  method on_frame {
+    param "*"
+    code {
+      FindProperty "frames"
+      GetLex "frames"
+      IncrementI
+      SetProperty "frames"
+      GetLex "frames"
+      PushByte 2
+      IfGt end
+
+      FindPropStrict "trace"
+      PushString "weak:"
+      FindPropStrict "count"
+      GetLex "weak"
+      CallProperty "count", 1
+      CallPropVoid "trace", 2
+      FindPropStrict "trace"
+      PushString "strong:"
+      FindPropStrict "count"
+      GetLex "strong"
+      CallProperty "count", 1
+      CallPropVoid "trace", 2
+      GetLex "frames"
+      PushByte 2
+      IfEq after_gc
+
+      GetLex "flash.system::System"
+      CallPropVoid "gc", 0
+      ReturnVoid
+
+    after_gc:
+      FindPropStrict "trace"
+      GetLex "weak"
+      GetLex "kept"
+      GetProperty "[]::<runtime>"
+      GetLex "weak"
+      GetProperty "name"
+      CallPropVoid "trace", 2
+      FindPropStrict "trace"
+      GetLex "strong"
+      GetLex "kept"
+      GetProperty "[]::<runtime>"
+      GetLex "strong"
+      GetProperty "name"
+      CallPropVoid "trace", 2
+    end:
+      ReturnVoid
+    }
+  }
+}
+show
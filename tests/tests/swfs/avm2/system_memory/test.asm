@@ -0,0 +1,153 @@
+// `System.totalMemory` and similar APIs, which count the memory of bitmaps,
+// and `System.disposeXML`.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+
+      // var before = System.totalMemoryNumber;
+      GetLex "flash.system::System"
+      GetProperty "totalMemoryNumber"
+      SetLocal 1
+
+      // var bitmap = new BitmapData(1000, 1000);
+      // trace(System.totalMemoryNumber - before >= 4000000);
+      FindPropStrict "flash.display::BitmapData"
+      PushShort 1000
+      PushShort 1000
+      ConstructProp "flash.display::BitmapData", 2
+      SetLocal 2
+      FindPropStrict "trace"
+      GetLex "flash.system::System"
+      GetProperty "totalMemoryNumber"
+      GetLocal 1
+      Subtract
+      PushInt 4000000
+      GreaterEquals
+      CallPropVoid "trace", 1
+
+      // bitmap.dispose();
+      // trace(System.totalMemoryNumber - before < 4000000);
+      GetLocal 2
+      CallPropVoid "dispose", 0
+      FindPropStrict "trace"
+      GetLex "flash.system::System"
+      GetProperty "totalMemoryNumber"
+      GetLocal 1
+      Subtract
+      PushInt 4000000
+      LessThan
+      CallPropVoid "trace", 1
+
+      // trace(System.totalMemory > 0);
+      FindPropStrict "trace"
+      GetLex "flash.system::System"
+      GetProperty "totalMemory"
+      PushByte 0
+      GreaterThan
+      CallPropVoid "trace", 1
+
+      // trace(System.privateMemory >= System.totalMemoryNumber);
+      FindPropStrict "trace"
+      GetLex "flash.system::System"
+      GetProperty "privateMemory"
+      GetLex "flash.system::System"
+      GetProperty "totalMemoryNumber"
+      GreaterEquals
+      CallPropVoid "trace", 1
+
+      // trace(System.freeMemory <= System.totalMemoryNumber);
+      FindPropStrict "trace"
+      GetLex "flash.system::System"
+      GetProperty "freeMemory"
+      GetLex "flash.system::System"
+      GetProperty "totalMemoryNumber"
+      LessEquals
+      CallPropVoid "trace", 1
+
+      // var xml = new XML("<a><b><c/></b></a>");
+      // var b = xml.b;
+      // trace(xml.children().length());
+      FindPropStrict "XML"
+      PushString "<a><b><c/></b></a>"
+      ConstructProp "XML", 1
+      SetLocal 3
+      GetLocal 3
+      GetProperty "b"
+      SetLocal 4
+      FindPropStrict "trace"
+      GetLocal 3
+      CallProperty "children", 0
+      CallProperty "length", 0
+      CallPropVoid "trace", 1
+
+      // System.disposeXML(xml);
+      // trace(xml.children().length());
+      // trace(b.children().length());
+      // trace(b.parent());
+      GetLex "flash.system::System"
+      GetLocal 3
+      CallPropVoid "disposeXML", 1
+      FindPropStrict "trace"
+      GetLocal 3
+      CallProperty "children", 0
+      CallProperty "length", 0
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 4
+      CallProperty "children", 0
+      CallProperty "length", 0
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 4
+      CallProperty "parent", 0
+      CallPropVoid "trace", 1
+
+      // var y = new XML("<a><b/><c/></a>");
+      // var c = y.c[0];
+      // System.disposeXML(c);
+      // trace(y.children().length());
+      // trace(c.parent());
+      FindPropStrict "XML"
+      PushString "<a><b/><c/></a>"
+      ConstructProp "XML", 1
+      SetLocal 5
+      GetLocal 5
+      GetProperty "c"
+      PushByte 0
+      GetProperty "[]::<runtime>"
+      SetLocal 6
+      GetLex "flash.system::System"
+      GetLocal 6
+      CallPropVoid "disposeXML", 1
+      FindPropStrict "trace"
+      GetLocal 5
+      CallProperty "children", 0
+      CallProperty "length", 0
+      CallPropVoid "trace", 1
+      FindPropStrict "trace"
+      GetLocal 6
+      CallProperty "parent", 0
+      CallPropVoid "trace", 1
+
+      // System.pauseForGCIfCollectionImminent(0);
+      // trace("done");
+      GetLex "flash.system::System"
+      PushByte 0
+      CallPropVoid "pauseForGCIfCollectionImminent", 1
+      FindPropStrict "trace"
+      PushString "done"
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+}
+show
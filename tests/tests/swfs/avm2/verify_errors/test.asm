@@ -0,0 +1,398 @@
+// Methods that fail verification, one for each error the verifier reports,
+// along with some invalid-looking code that verifies fine.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+file_attributes IS_ACTION_SCRIPT_3
+abc {
+  script init=main
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      NewFunction check
+      SetLocal 1
+      GetLocal 1
+      PushNull
+      PushString "fall off end"
+      NewFunction fall_off_end
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "empty code"
+      NewFunction empty_code
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "backward branch"
+      NewFunction backward_branch
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "invalid register"
+      NewFunction invalid_register
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "kill register"
+      NewFunction kill_register
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "stack overflow"
+      NewFunction stack_overflow
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "stack underflow"
+      NewFunction stack_underflow
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "scope overflow"
+      NewFunction scope_overflow
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "scope underflow"
+      NewFunction scope_underflow
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "scope out of bounds"
+      NewFunction scope_out_of_bounds
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "stack unbalanced"
+      NewFunction stack_unbalanced
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "loop unbalanced"
+      NewFunction loop_unbalanced
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "scope unbalanced"
+      NewFunction scope_unbalanced
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "scope kinds"
+      NewFunction scope_kinds
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "hasnext2 registers"
+      NewFunction hasnext2_registers
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "newactivation"
+      NewFunction new_activation
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "callmethod 0"
+      NewFunction call_method_0
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "callmethod"
+      NewFunction call_method
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "newcatch"
+      NewFunction new_catch
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "handler before range"
+      NewFunction handler_before_range
+      Call 2
+      Pop
+      GetLocal 1
+      PushNull
+      PushString "handler on last op"
+      NewFunction handler_on_last_op
+      Call 2
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // Traces whether calling `f` throws.
+  method check {
+    param "*", name="name"
+    param "*", name="f"
+    catch start, end, handler
+    code {
+      GetLocal 0
+      PushScope
+    start:
+      GetLocal 2
+      PushNull
+      Call 0
+      Pop
+    end:
+      FindPropStrict "trace"
+      GetLocal 1
+      PushString " did not throw"
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    handler:
+      SetLocal 3
+      FindPropStrict "trace"
+      GetLocal 1
+      PushString ": "
+      Add
+      GetLocal 3
+      Add
+      CallPropVoid "trace", 1
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1020
+  method fall_off_end {
+    code {
+      PushByte 1
+      Pop
+    }
+  }
+
+  // VerifyError #1043
+  method empty_code {
+    code {
+    }
+  }
+
+  // A backward branch to code that hasn't been reached yet must target a
+  // `label` op. VerifyError #1021
+  method backward_branch {
+    code {
+      Jump forward
+    back:
+      ReturnVoid
+    forward:
+      Jump back
+    }
+  }
+
+  // VerifyError #1025
+  method invalid_register, locals=1 {
+    code {
+      GetLocal 3
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1025
+  method kill_register, locals=1 {
+    code {
+      Kill 3
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1023
+  method stack_overflow, max_stack=1 {
+    code {
+      PushByte 1
+      PushByte 2
+      Pop
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1024
+  method stack_underflow {
+    code {
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1017
+  method scope_overflow, max_scope=0 {
+    code {
+      GetLocal 0
+      PushScope
+      PopScope
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1018
+  method scope_underflow {
+    code {
+      PopScope
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1019
+  method scope_out_of_bounds {
+    code {
+      GetLocal 0
+      PushScope
+      GetScopeObject 1
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1030
+  method stack_unbalanced {
+    code {
+      PushTrue
+      IfTrue end
+      PushByte 1
+    end:
+      ReturnVoid
+    }
+  }
+
+  // The loop body leaves a value on the stack, which is only noticed when the
+  // back edge is merged into the loop header. VerifyError #1030
+  method loop_unbalanced {
+    code {
+      PushTrue
+      Pop
+    loop:
+      Label
+      PushByte 1
+      PushTrue
+      IfTrue loop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1031
+  method scope_unbalanced {
+    code {
+      PushTrue
+      IfTrue end
+      GetLocal 0
+      PushScope
+    end:
+      ReturnVoid
+    }
+  }
+
+  // A `pushwith` on one edge and a `pushscope` on the other.
+  // VerifyError #1068
+  method scope_kinds {
+    code {
+      PushTrue
+      IfTrue object
+      NewArray 0
+      PushWith
+      Jump end
+    object:
+      NewObject 0
+      PushScope
+    end:
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1124
+  method hasnext2_registers, locals=2 {
+    code {
+      HasNext2 1, 1
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1113
+  method new_activation {
+    code {
+      NewActivation
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1072
+  method call_method_0 {
+    code {
+      GetLocal 0
+      CallMethod 0, 0
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1051
+  method call_method {
+    code {
+      GetLocal 0
+      CallMethod 1, 0
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // A `newcatch` without a matching exception handler. VerifyError #1107
+  method new_catch {
+    code {
+      NewCatch 0
+      Pop
+      ReturnVoid
+    }
+  }
+
+  // VerifyError #1054
+  method handler_before_range {
+    catch start, end, start
+    code {
+    start:
+      PushByte 1
+      Throw
+    end:
+      ReturnVoid
+    }
+  }
+
+  // An exception handler may start right where its range ends, even when
+  // that is the last op of the method. This rethrows the caught value.
+  method handler_on_last_op {
+    catch start, end, end
+    code {
+    start:
+      PushByte 1
+      Throw
+    end:
+      Throw
+    }
+  }
+}
+show
@@ -0,0 +1,262 @@
+// ConvolutionFilter with every combination of clamp, preserveAlpha, divisor and bias.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+size 380, 200
+file_attributes IS_ACTION_SCRIPT_3
+background 0xFFFFFF
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "addBox"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "addBox"
+      NewFunction add_box
+      InitProperty "addBox"
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  // A red square with a translucent blue square in its middle, at the given position
+  // with the given filter.
+  method add_box {
+    param "flash.display::DisplayObjectContainer"
+    param "Number"
+    param "Number"
+    param "flash.filters::BitmapFilter"
+    code {
+      FindPropStrict "flash.display::Shape"
+      ConstructProp "flash.display::Shape", 0
+      SetLocal 5
+      GetLocal 5
+      GetProperty "graphics"
+      SetLocal 6
+      GetLocal 6
+      PushUint 0xFF0000
+      CallPropVoid "beginFill", 1
+      GetLocal 6
+      PushByte 0
+      PushByte 0
+      PushByte 60
+      PushByte 60
+      CallPropVoid "drawRect", 4
+      GetLocal 6
+      PushUint 0x0000FF
+      PushDouble 0.5
+      CallPropVoid "beginFill", 2
+      GetLocal 6
+      PushByte 15
+      PushByte 15
+      PushByte 30
+      PushByte 30
+      CallPropVoid "drawRect", 4
+      GetLocal 6
+      CallPropVoid "endFill", 0
+      GetLocal 5
+      GetLocal 2
+      SetProperty "x"
+      GetLocal 5
+      GetLocal 3
+      SetProperty "y"
+      GetLocal 5
+      GetLocal 4
+      NewArray 1
+      SetProperty "filters"
+      GetLocal 1
+      GetLocal 5
+      CallPropVoid "addChild", 1
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      // Edge detection, clamping the edges and keeping the alpha.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushByte 20
+      PushByte 20
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 0
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      PushByte -4
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      PushByte 0
+      NewArray 9
+      PushByte 1
+      PushByte 0
+      PushTrue
+      PushTrue
+      PushByte 0
+      PushDouble 0.0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "addBox", 4
+
+      // Edge detection, with opaque green outside of the image.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 140
+      PushByte 20
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 0
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      PushByte -4
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      PushByte 0
+      NewArray 9
+      PushByte 1
+      PushByte 0
+      PushTrue
+      PushFalse
+      PushUint 0x00FF00
+      PushDouble 1.0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "addBox", 4
+
+      // A box blur, which also blurs the alpha.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 260
+      PushByte 20
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      NewArray 9
+      PushByte 9
+      PushByte 0
+      PushFalse
+      PushTrue
+      PushByte 0
+      PushDouble 0.0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "addBox", 4
+
+      // A box blur, with translucent magenta outside of the image.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushByte 20
+      PushByte 120
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      NewArray 9
+      PushByte 9
+      PushByte 0
+      PushFalse
+      PushFalse
+      PushUint 0xFF00FF
+      PushDouble 0.5
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "addBox", 4
+
+      // Sharpening, with a divisor and a bias.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 140
+      PushByte 120
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 0
+      PushByte -1
+      PushByte 0
+      PushByte -1
+      PushByte 5
+      PushByte -1
+      PushByte 0
+      PushByte -1
+      PushByte 0
+      NewArray 9
+      PushByte 2
+      PushByte 64
+      PushTrue
+      PushTrue
+      PushByte 0
+      PushDouble 0.0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "addBox", 4
+
+      // A brightening blur, with a negative bias.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 260
+      PushByte 120
+      FindPropStrict "flash.filters::ConvolutionFilter"
+      PushByte 3
+      PushByte 3
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      PushByte 1
+      NewArray 9
+      PushByte 4
+      PushByte -32
+      PushFalse
+      PushTrue
+      PushByte 0
+      PushDouble 0.0
+      ConstructProp "flash.filters::ConvolutionFilter", 9
+      CallPropVoid "addBox", 4
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
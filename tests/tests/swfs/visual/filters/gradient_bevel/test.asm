@@ -0,0 +1,335 @@
+// GradientBevelFilter with different gradients: the highlight and shadow halves,
+// the position of the transparent middle stop and the light angle, along with every
+// type and knockout.
+//
+// Assemble with `cargo run -p ruffle_assembler -- test.asm`.
+
+version 10
+size 380, 200
+file_attributes IS_ACTION_SCRIPT_3
+background 0xFFFFFF
+abc {
+  script init=main {
+    class "Main", main_class
+    slot "addBox"
+  }
+
+  method main {
+    code {
+      GetLocal 0
+      PushScope
+      FindProperty "addBox"
+      NewFunction add_box
+      InitProperty "addBox"
+      GetScopeObject 0
+      GetLex "flash.display::MovieClip"
+      NewClass main_class
+      InitProperty "Main"
+      ReturnVoid
+    }
+  }
+
+  class main_class, "Main", extends="flash.display::MovieClip", init=main_init, class_init=main_class_init
+
+  method main_class_init {
+    code {
+      ReturnVoid
+    }
+  }
+
+  // A red square with a round hole in its middle, at the given position with the
+  // given filter.
+  method add_box {
+    param "flash.display::DisplayObjectContainer"
+    param "Number"
+    param "Number"
+    param "flash.filters::BitmapFilter"
+    code {
+      FindPropStrict "flash.display::Shape"
+      ConstructProp "flash.display::Shape", 0
+      SetLocal 5
+      GetLocal 5
+      GetProperty "graphics"
+      SetLocal 6
+      GetLocal 6
+      PushUint 0xFF0000
+      CallPropVoid "beginFill", 1
+      GetLocal 6
+      PushByte 0
+      PushByte 0
+      PushByte 60
+      PushByte 60
+      CallPropVoid "drawRect", 4
+      GetLocal 6
+      PushByte 30
+      PushByte 30
+      PushByte 15
+      CallPropVoid "drawCircle", 3
+      GetLocal 6
+      CallPropVoid "endFill", 0
+      GetLocal 5
+      GetLocal 2
+      SetProperty "x"
+      GetLocal 5
+      GetLocal 3
+      SetProperty "y"
+      GetLocal 5
+      GetLocal 4
+      NewArray 1
+      SetProperty "filters"
+      GetLocal 1
+      GetLocal 5
+      CallPropVoid "addChild", 1
+      ReturnVoid
+    }
+  }
+
+  method main_init {
+    code {
+      GetLocal 0
+      PushScope
+      GetLocal 0
+      ConstructSuper 0
+      // A highlight and a shadow, from a three-stop gradient with a transparent middle.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushByte 20
+      PushByte 20
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 4
+      PushByte 45
+      PushUint 0xFFFFFF
+      PushUint 0xFF0000
+      PushUint 0x000000
+      NewArray 3
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      NewArray 3
+      PushByte 0
+      PushShort 128
+      PushShort 255
+      NewArray 3
+      PushByte 4
+      PushByte 4
+      PushByte 1
+      PushByte 1
+      PushString "inner"
+      PushFalse
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+
+      // More stops on the shadow side than on the highlight side.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushByte 110
+      PushByte 20
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 4
+      PushByte 45
+      PushUint 0xFFFF00
+      PushUint 0xFF0000
+      PushUint 0x00FFFF
+      PushUint 0x0000FF
+      PushUint 0x000080
+      NewArray 5
+      PushByte 1
+      PushByte 0
+      PushDouble 0.5
+      PushByte 1
+      PushByte 1
+      NewArray 5
+      PushByte 0
+      PushShort 128
+      PushShort 160
+      PushShort 200
+      PushShort 255
+      NewArray 5
+      PushByte 6
+      PushByte 6
+      PushByte 1
+      PushByte 1
+      PushString "inner"
+      PushFalse
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+
+      // The transparent stop moved off-centre, so the highlight covers more of the gradient.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 200
+      PushByte 20
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 4
+      PushByte 45
+      PushUint 0x00FF00
+      PushUint 0xFF0000
+      PushUint 0xFF00FF
+      NewArray 3
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      NewArray 3
+      PushByte 0
+      PushShort 200
+      PushShort 255
+      NewArray 3
+      PushByte 4
+      PushByte 4
+      PushByte 1
+      PushByte 1
+      PushString "inner"
+      PushFalse
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+
+      // Lit from the opposite direction.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 290
+      PushByte 20
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 4
+      PushShort 225
+      PushUint 0xFFFFFF
+      PushUint 0xFF0000
+      PushUint 0x000000
+      NewArray 3
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      NewArray 3
+      PushByte 0
+      PushShort 128
+      PushShort 255
+      NewArray 3
+      PushByte 4
+      PushByte 4
+      PushByte 1
+      PushByte 1
+      PushString "inner"
+      PushFalse
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+
+      // A long distance with a high strength.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushByte 20
+      PushByte 120
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 10
+      PushByte 90
+      PushUint 0xFFFF00
+      PushUint 0xFF0000
+      PushUint 0x0000FF
+      NewArray 3
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      NewArray 3
+      PushByte 0
+      PushShort 128
+      PushShort 255
+      NewArray 3
+      PushByte 6
+      PushByte 6
+      PushByte 3
+      PushByte 2
+      PushString "inner"
+      PushFalse
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+
+      // An outer bevel.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushByte 110
+      PushByte 120
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 4
+      PushByte 45
+      PushUint 0xFFFFFF
+      PushUint 0xFF0000
+      PushUint 0x000000
+      NewArray 3
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      NewArray 3
+      PushByte 0
+      PushShort 128
+      PushShort 255
+      NewArray 3
+      PushByte 4
+      PushByte 4
+      PushByte 1
+      PushByte 1
+      PushString "outer"
+      PushFalse
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+
+      // A bevel on both sides of the edge.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 200
+      PushByte 120
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 4
+      PushByte 45
+      PushUint 0xFFFFFF
+      PushUint 0xFF0000
+      PushUint 0x000000
+      NewArray 3
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      NewArray 3
+      PushByte 0
+      PushShort 128
+      PushShort 255
+      NewArray 3
+      PushByte 4
+      PushByte 4
+      PushByte 1
+      PushByte 1
+      PushString "full"
+      PushFalse
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+
+      // An inner bevel, knocking out the source.
+      FindPropStrict "addBox"
+      GetLocal 0
+      PushShort 290
+      PushByte 120
+      FindPropStrict "flash.filters::GradientBevelFilter"
+      PushByte 4
+      PushByte 45
+      PushUint 0xFFFFFF
+      PushUint 0xFF0000
+      PushUint 0x000000
+      NewArray 3
+      PushByte 1
+      PushByte 0
+      PushByte 1
+      NewArray 3
+      PushByte 0
+      PushShort 128
+      PushShort 255
+      NewArray 3
+      PushByte 4
+      PushByte 4
+      PushByte 1
+      PushByte 1
+      PushString "inner"
+      PushTrue
+      ConstructProp "flash.filters::GradientBevelFilter", 11
+      CallPropVoid "addBox", 4
+      ReturnVoid
+    }
+  }
+}
+symbol 0, "Main"
+show
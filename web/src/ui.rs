@@ -272,6 +272,10 @@ impl UiBackend for WebUiBackend {
         }
     }
 
+    fn set_mouse_lock(&mut self, _locked: bool) {
+        // TODO: Use the Pointer Lock API and report `movementX`/`movementY` from mouse events.
+    }
+
     fn display_root_movie_download_failed_message(&self, invalid_swf: bool, fetch_error: String) {
         self.js_player
             .display_root_movie_download_failed_message(invalid_swf, fetch_error)